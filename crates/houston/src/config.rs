@@ -92,6 +92,40 @@ impl Config {
     fn get_elv2_toml_path(&self) -> Utf8PathBuf {
         self.home.join("elv2_license.toml")
     }
+
+    /// Loads the HTTP client settings that apply to every profile from
+    /// self.home.join("client.toml"), or the defaults if they were never saved.
+    pub fn get_client_settings(&self) -> Result<ClientSettings, HoustonProblem> {
+        let toml_path = self.get_client_toml_path();
+        if !toml_path.exists() {
+            return Ok(ClientSettings::default());
+        }
+        Ok(toml::from_str(&Fs::read_file(toml_path)?)?)
+    }
+
+    /// Saves the HTTP client settings that apply to every profile to self.home.join("client.toml")
+    pub fn save_client_settings(&self, settings: &ClientSettings) -> Result<(), HoustonProblem> {
+        let contents = toml::to_string(settings)?;
+        Fs::write_file(self.get_client_toml_path(), contents)?;
+        Ok(())
+    }
+
+    fn get_client_toml_path(&self) -> Utf8PathBuf {
+        self.home.join("client.toml")
+    }
+}
+
+/// Settings for Rover's HTTP requests, which the `--client-timeout` and `--client-retries`
+/// flags override.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientSettings {
+    /// How long to wait for a response, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// How many times to retry a failed request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -101,7 +135,7 @@ struct Elv2Toml {
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{ClientSettings, Config};
     use assert_fs::TempDir;
     use camino::Utf8PathBuf;
     use std::convert::TryFrom;
@@ -114,4 +148,21 @@ mod tests {
         config.clear().unwrap();
         assert!(!config.home.exists());
     }
+
+    #[test]
+    fn it_can_save_client_settings() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_path = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let config = Config::new(Some(&tmp_path), None).unwrap();
+        assert_eq!(
+            config.get_client_settings().unwrap(),
+            ClientSettings::default()
+        );
+        let settings = ClientSettings {
+            timeout: Some(90),
+            retries: Some(5),
+        };
+        config.save_client_settings(&settings).unwrap();
+        assert_eq!(config.get_client_settings().unwrap(), settings);
+    }
 }
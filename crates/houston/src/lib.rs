@@ -6,9 +6,9 @@ mod config;
 mod error;
 mod profile;
 
-pub use config::Config;
+pub use config::{ClientSettings, Config};
 pub use error::HoustonProblem;
 
 pub use profile::mask_key;
 /// Utilites for saving, loading, and deleting configuration profiles.
-pub use profile::{Credential, CredentialOrigin, LoadOpts, Profile};
+pub use profile::{Credential, CredentialOrigin, LoadOpts, Profile, ProfileSettings};
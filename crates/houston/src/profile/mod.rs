@@ -1,8 +1,10 @@
 mod sensitive;
+mod settings;
 
 use crate::{Config, HoustonProblem};
 use sensitive::Sensitive;
 use serde::{Deserialize, Serialize};
+pub use settings::ProfileSettings;
 
 use camino::Utf8PathBuf as PathBuf;
 use rover_std::Fs;
@@ -99,6 +101,24 @@ impl Profile {
         Ok(())
     }
 
+    /// Returns the non-sensitive settings of a profile, which are the defaults if none were saved.
+    pub fn get_settings(name: &str, config: &Config) -> Result<ProfileSettings, HoustonProblem> {
+        ProfileSettings::load(name, config)
+    }
+
+    /// Saves the non-sensitive settings of an existing profile
+    /// (`$APOLLO_CONFIG_HOME/profiles/<profile_name>/settings.toml`).
+    pub fn save_settings(
+        name: &str,
+        config: &Config,
+        settings: &ProfileSettings,
+    ) -> Result<(), HoustonProblem> {
+        if !Profile::dir(name, config).exists() {
+            return Err(HoustonProblem::ProfileNotFound(name.to_string()));
+        }
+        settings.save(name, config)
+    }
+
     /// Loads and deserializes configuration from the file system for a
     /// specific profile.
     fn load(
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use crate::{profile::Profile, Config, HoustonProblem};
+use rover_std::Fs;
+
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Holds the settings of a profile that aren't sensitive.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileSettings {
+    /// Accept invalid TLS certificates from introspection and local development endpoints.
+    /// Requests to the Apollo registry are still verified.
+    #[serde(default)]
+    pub insecure_accept_invalid_certs: bool,
+
+    /// An identifier for the organization that sends requests with this profile, which is added
+    /// to the `User-Agent` of requests to the Apollo registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+
+    /// Metadata like a CI build ID or repository that's sent as headers with requests to the
+    /// Apollo registry. Values that start with `$` name the environment variable to read them from.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub request_metadata: BTreeMap<String, String>,
+
+    /// The graph ref that commands use when one isn't passed or set in `APOLLO_GRAPH_REF`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_ref: Option<String>,
+
+    /// The output format, `plain` or `json`, that commands use when `--format` isn't passed or
+    /// set in `APOLLO_ROVER_FORMAT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+
+    /// The federation version to compose with when a supergraph config doesn't set one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub federation_version: Option<String>,
+
+    /// The Apollo registry endpoint to send requests to, unless `APOLLO_REGISTRY_URL` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry_url: Option<String>,
+
+    /// Comma-separated patterns of graph refs or variants, like `*@prod`, that publishes ask
+    /// for confirmation before changing, along with any in `APOLLO_PROTECTED_VARIANTS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protected_variants: Option<String>,
+}
+
+impl ProfileSettings {
+    fn path(profile_name: &str, config: &Config) -> Utf8PathBuf {
+        Profile::dir(profile_name, config).join("settings.toml")
+    }
+
+    /// Serializes to toml and saves to file system at `$APOLLO_CONFIG_HOME/<profile_name>/settings.toml`.
+    pub fn save(&self, profile_name: &str, config: &Config) -> Result<(), HoustonProblem> {
+        let path = ProfileSettings::path(profile_name, config);
+        let data = toml::to_string(self)?;
+
+        if let Some(dirs) = &path.parent() {
+            Fs::create_dir_all(dirs)?;
+        }
+
+        Fs::write_file(&path, &data)?;
+        tracing::debug!(path = ?path, data = ?data);
+        Ok(())
+    }
+
+    /// Opens and deserializes `$APOLLO_CONFIG_HOME/<profile_name>/settings.toml`, or returns the
+    /// default settings if the profile has never saved any.
+    pub fn load(profile_name: &str, config: &Config) -> Result<ProfileSettings, HoustonProblem> {
+        let path = ProfileSettings::path(profile_name, config);
+        if !path.exists() {
+            return Ok(ProfileSettings::default());
+        }
+        let data = Fs::read_file(&path)?;
+        tracing::debug!(path = ?path, data = ?data);
+        Ok(toml::from_str(&data)?)
+    }
+}
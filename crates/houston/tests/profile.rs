@@ -27,6 +27,41 @@ fn it_lists_many_profiles() {
     config.clear().expect("clearing configuration failed");
 }
 
+#[test]
+fn it_saves_profile_settings() {
+    let config = get_config(None);
+    let profile_name = "local";
+    config::Profile::set_api_key(profile_name, &config, "localpassword")
+        .expect("setting api key failed");
+
+    assert_eq!(
+        config::Profile::get_settings(profile_name, &config).expect("loading settings failed"),
+        config::ProfileSettings::default()
+    );
+
+    let settings = config::ProfileSettings {
+        insecure_accept_invalid_certs: true,
+        organization: Some("acme".to_string()),
+        request_metadata: [("build-id".to_string(), "$BUILD_ID".to_string())]
+            .into_iter()
+            .collect(),
+        graph_ref: Some("my-graph@prod".to_string()),
+        format: Some("json".to_string()),
+        federation_version: Some("=2.7.1".to_string()),
+        registry_url: None,
+        protected_variants: Some("*@prod".to_string()),
+    };
+    config::Profile::save_settings(profile_name, &config, &settings)
+        .expect("saving settings failed");
+    assert_eq!(
+        config::Profile::get_settings(profile_name, &config).expect("loading settings failed"),
+        settings
+    );
+    assert!(config::Profile::save_settings("missing", &config, &settings).is_err());
+
+    config.clear().expect("clearing configuration failed");
+}
+
 fn get_config(override_api_key: Option<String>) -> Config {
     let tmp_home = TempDir::new().unwrap();
     let tmp_home_path = Utf8Path::from_path(tmp_home.path()).unwrap().to_owned();
@@ -3,21 +3,63 @@ use crate::error::{EndpointKind, RoverClientError};
 use graphql_client::{Error as GraphQLError, GraphQLQuery, Response as GraphQLResponse};
 use reqwest::{
     blocking::{Client as ReqwestClient, Response},
-    header::{HeaderMap, HeaderValue},
+    header::{HeaderMap, HeaderValue, RETRY_AFTER},
     StatusCode,
 };
+use rover_std::perf::{self, Phase};
 
 pub(crate) const JSON_CONTENT_TYPE: &str = "application/json";
 
+/// `extensions.code`s of GraphQL errors that are worth retrying.
+const TRANSIENT_GRAPHQL_ERROR_CODES: &[&str] = &[
+    "INTERNAL_SERVER_ERROR",
+    "SERVICE_UNAVAILABLE",
+    "RATE_LIMITED",
+    "TOO_MANY_REQUESTS",
+];
+
 const MAX_ELAPSED_TIME: Option<Duration> =
     Some(Duration::from_secs(if cfg!(test) { 2 } else { 10 }));
 
-use std::time::Duration;
+/// The longest `Retry-After` that's honored, so a misbehaving server can't stall a command
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+use std::{
+    cell::Cell,
+    io::ErrorKind,
+    time::{Duration, Instant},
+};
+
+use super::{give_up_after_max_retries, max_elapsed_time, RateLimiter};
+
+/// Which failed requests are sent again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryPolicy {
+    Never,
+    /// Only requests the server didn't act on, for publishes that aren't safe to apply twice
+    Unsent,
+    /// Anything that's likely to succeed on another try, for queries and checks
+    Transient,
+}
+
+impl RetryPolicy {
+    /// Whether a request that failed in a way that's worth retrying can be sent again, given
+    /// whether the server might have acted on it before it failed.
+    fn allows(self, maybe_acted_on: bool) -> bool {
+        match self {
+            RetryPolicy::Never => false,
+            RetryPolicy::Unsent => !maybe_acted_on,
+            RetryPolicy::Transient => true,
+        }
+    }
+}
 
 /// Represents a generic GraphQL client for making http requests.
 pub struct GraphQLClient {
     graphql_endpoint: String,
     client: ReqwestClient,
+    rate_limiter: Option<RateLimiter>,
+    max_retries: Option<u32>,
 }
 
 impl GraphQLClient {
@@ -27,9 +69,33 @@ impl GraphQLClient {
         GraphQLClient {
             graphql_endpoint: graphql_endpoint.to_string(),
             client,
+            rate_limiter: None,
+            max_retries: None,
         }
     }
 
+    /// Waits for `rate_limiter` before every request, including retries.
+    pub fn with_rate_limiter(self, rate_limiter: RateLimiter) -> GraphQLClient {
+        GraphQLClient {
+            rate_limiter: Some(rate_limiter),
+            ..self
+        }
+    }
+
+    /// Retries failed requests `max_retries` times, with exponential backoff between them, rather
+    /// than for a fixed amount of time. `0` turns retries off.
+    pub fn with_max_retries(self, max_retries: u32) -> GraphQLClient {
+        GraphQLClient {
+            max_retries: Some(max_retries),
+            ..self
+        }
+    }
+
+    /// The number of times failed requests are retried, if it was set with [`Self::with_max_retries`].
+    pub fn max_retries(&self) -> Option<u32> {
+        self.max_retries
+    }
+
     /// Client method for making a GraphQL request.
     ///
     /// Takes one argument, `variables`. Returns an optional response.
@@ -43,10 +109,29 @@ impl GraphQLClient {
     where
         Q: GraphQLQuery,
     {
-        let request_body = self.get_request_body::<Q>(variables)?;
-        header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
-        let response = self.execute(request_body, header_map, true, endpoint_kind);
-        GraphQLClient::handle_response::<Q>(response?, endpoint_kind)
+        self.post_with_retry_policy::<Q>(
+            variables,
+            header_map,
+            RetryPolicy::Transient,
+            endpoint_kind,
+        )
+    }
+
+    /// Client method for making a GraphQL request that isn't safe to apply twice, like a publish.
+    ///
+    /// Takes one argument, `variables`. Returns an optional response.
+    /// Only retries requests that never reached the server, so a publish that failed partway
+    /// through isn't applied twice.
+    pub fn post_non_idempotent<Q>(
+        &self,
+        variables: Q::Variables,
+        header_map: &mut HeaderMap,
+        endpoint_kind: EndpointKind,
+    ) -> Result<Q::ResponseData, RoverClientError>
+    where
+        Q: GraphQLQuery,
+    {
+        self.post_with_retry_policy::<Q>(variables, header_map, RetryPolicy::Unsent, endpoint_kind)
     }
 
     /// Client method for making a GraphQL request.
@@ -59,13 +144,31 @@ impl GraphQLClient {
         header_map: &mut HeaderMap,
         endpoint_kind: EndpointKind,
     ) -> Result<Q::ResponseData, RoverClientError>
+    where
+        Q: GraphQLQuery,
+    {
+        self.post_with_retry_policy::<Q>(variables, header_map, RetryPolicy::Never, endpoint_kind)
+    }
+
+    fn post_with_retry_policy<Q>(
+        &self,
+        variables: Q::Variables,
+        header_map: &mut HeaderMap,
+        retry_policy: RetryPolicy,
+        endpoint_kind: EndpointKind,
+    ) -> Result<Q::ResponseData, RoverClientError>
     where
         Q: GraphQLQuery,
     {
         let request_body = self.get_request_body::<Q>(variables)?;
         header_map.append("Content-Type", HeaderValue::from_str(JSON_CONTENT_TYPE)?);
-        let response = self.execute(request_body, header_map, false, endpoint_kind);
-        GraphQLClient::handle_response::<Q>(response?, endpoint_kind)
+        self.execute(
+            request_body,
+            header_map,
+            retry_policy,
+            endpoint_kind,
+            |response| GraphQLClient::handle_response::<Q>(response, endpoint_kind),
+        )
     }
 
     fn get_request_body<Q: GraphQLQuery>(
@@ -76,105 +179,136 @@ impl GraphQLClient {
         Ok(serde_json::to_string(&body)?)
     }
 
-    fn execute(
+    /// Sends a request, passing the response to `handle_response`. Errors that are worth retrying,
+    /// including transient GraphQL errors in the response, are retried as `retry_policy` allows.
+    fn execute<T>(
         &self,
         request_body: String,
         header_map: &HeaderMap,
-        should_retry: bool,
+        retry_policy: RetryPolicy,
         endpoint_kind: EndpointKind,
-    ) -> Result<Response, RoverClientError> {
-        use backoff::{retry, Error as BackoffError, ExponentialBackoff};
+        handle_response: impl Fn(Response) -> Result<T, RoverClientError>,
+    ) -> Result<T, RoverClientError> {
+        use backoff::{retry_notify, Error as BackoffError, ExponentialBackoff};
 
         tracing::trace!(request_headers = ?header_map);
         tracing::debug!("Request Body: {}", request_body);
+        let attempts = Cell::new(0);
+        let started = Instant::now();
+        let max_elapsed_time = max_elapsed_time(MAX_ELAPSED_TIME, self.max_retries);
+        // backoff always honors a `Retry-After`, so stop once waiting would go past the time
+        // requests are retried for, rather than retrying for as long as the server asks
+        let can_wait = |wait: Duration| {
+            max_elapsed_time.map_or(true, |max_elapsed| started.elapsed() + wait <= max_elapsed)
+        };
+        let send_request_error = |source| RoverClientError::SendRequest {
+            source,
+            endpoint_kind,
+        };
         let graphql_operation = || {
-            let response = self
-                .client
-                .post(&self.graphql_endpoint)
-                .headers(header_map.clone())
-                .body(request_body.clone())
-                .send();
+            attempts.set(attempts.get() + 1);
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.wait();
+            }
+            let response = perf::time(Phase::Network, || {
+                self.client
+                    .post(&self.graphql_endpoint)
+                    .headers(header_map.clone())
+                    .body(request_body.clone())
+                    .send()
+            });
 
             match response {
                 Err(client_error) => {
-                    if client_error.is_timeout() || client_error.is_connect() {
-                        Err(BackoffError::transient(client_error))
-                    } else if client_error.is_body()
-                        || client_error.is_decode()
-                        || client_error.is_builder()
-                        || client_error.is_redirect()
+                    // a request that couldn't connect never reached the server
+                    let maybe_acted_on = !client_error.is_connect();
+                    if (client_error.is_timeout()
+                        || client_error.is_connect()
+                        || is_connection_reset(&client_error))
+                        && retry_policy.allows(maybe_acted_on)
                     {
-                        Err(BackoffError::Permanent(client_error))
-                    } else if client_error.is_request() {
-                        if let Some(hyper_error) =
-                            get_source_error_type::<hyper::Error>(&client_error)
-                        {
-                            if hyper_error.is_incomplete_message() {
-                                Err(BackoffError::transient(client_error))
-                            } else {
-                                Err(BackoffError::Permanent(client_error))
-                            }
-                        } else {
-                            Err(BackoffError::Permanent(client_error))
-                        }
+                        Err(BackoffError::transient(send_request_error(client_error)))
                     } else {
-                        Err(BackoffError::Permanent(client_error))
+                        Err(BackoffError::Permanent(send_request_error(client_error)))
                     }
                 }
                 Ok(success) => {
                     if let Err(status_error) = success.error_for_status_ref() {
-                        if let Some(response_status) = status_error.status() {
-                            if response_status.is_server_error()
-                                || response_status.is_client_error()
-                                || response_status.is_redirection()
+                        match status_error.status() {
+                            // the server turns rate limited requests away without acting on them
+                            Some(response_status)
+                                if is_retryable_status(response_status)
+                                    && retry_policy.allows(
+                                        response_status != StatusCode::TOO_MANY_REQUESTS,
+                                    ) =>
                             {
-                                if matches!(response_status, StatusCode::BAD_REQUEST) {
-                                    if let Ok(text) = success.text() {
-                                        tracing::debug!("{}", text);
+                                let error = send_request_error(status_error);
+                                match retry_after(&success) {
+                                    Some(wait) if can_wait(wait) => {
+                                        Err(BackoffError::retry_after(error, wait))
                                     }
-                                    Err(BackoffError::Permanent(status_error))
-                                } else {
-                                    Err(BackoffError::transient(status_error))
+                                    Some(_) => Err(BackoffError::Permanent(error)),
+                                    None => Err(BackoffError::transient(error)),
+                                }
+                            }
+                            Some(StatusCode::BAD_REQUEST) => {
+                                if let Ok(text) = success.text() {
+                                    tracing::debug!("{}", text);
                                 }
-                            } else {
-                                Err(BackoffError::Permanent(status_error))
+                                Err(BackoffError::Permanent(send_request_error(status_error)))
                             }
-                        } else {
-                            Err(BackoffError::Permanent(status_error))
+                            _ => Err(BackoffError::Permanent(send_request_error(status_error))),
                         }
                     } else {
-                        Ok(success)
+                        match handle_response(success) {
+                            // the server answered, so it may have acted on the request
+                            Err(error @ RoverClientError::TransientGraphQl { .. })
+                                if retry_policy.allows(true) =>
+                            {
+                                Err(BackoffError::transient(error))
+                            }
+                            Err(error) => Err(BackoffError::Permanent(error)),
+                            Ok(data) => Ok(data),
+                        }
                     }
                 }
             }
         };
 
-        if should_retry {
+        if retry_policy != RetryPolicy::Never {
+            // the default randomization factor jitters each interval by up to 50%
             let backoff_strategy = ExponentialBackoff {
-                max_elapsed_time: MAX_ELAPSED_TIME,
+                max_elapsed_time,
                 ..Default::default()
             };
+            let retried_operation = || {
+                graphql_operation()
+                    .map_err(|e| give_up_after_max_retries(e, attempts.get(), self.max_retries))
+            };
 
-            retry(backoff_strategy, graphql_operation).map_err(|e| match e {
-                BackoffError::Permanent(reqwest_error)
-                | BackoffError::Transient {
-                    err: reqwest_error,
-                    retry_after: _,
-                } => RoverClientError::SendRequest {
-                    source: reqwest_error,
-                    endpoint_kind,
-                },
+            let notify = |e: RoverClientError, wait: Duration| {
+                tracing::info!(
+                    "attempt {} to reach {} failed: {}. Retrying in {}ms.",
+                    attempts.get(),
+                    &self.graphql_endpoint,
+                    e,
+                    wait.as_millis()
+                );
+            };
+            let result = retry_notify(backoff_strategy, retried_operation, notify);
+            if attempts.get() > 1 {
+                tracing::info!(
+                    "request to {} finished after {} attempts",
+                    &self.graphql_endpoint,
+                    attempts.get()
+                );
+            }
+            result.map_err(|e| match e {
+                BackoffError::Permanent(err) | BackoffError::Transient { err, .. } => err,
             })
         } else {
             graphql_operation().map_err(|e| match e {
-                BackoffError::Permanent(reqwest_error)
-                | BackoffError::Transient {
-                    err: reqwest_error,
-                    retry_after: _,
-                } => RoverClientError::SendRequest {
-                    source: reqwest_error,
-                    endpoint_kind,
-                },
+                BackoffError::Permanent(err) | BackoffError::Transient { err, .. } => err,
             })
         }
     }
@@ -234,6 +368,14 @@ fn handle_graphql_body_errors(errors: Vec<GraphQLError>) -> Result<(), RoverClie
         tracing::debug!("GraphQL response errors: {:?}", errors);
         if errors[0].message == "406: Not Acceptable" {
             Err(RoverClientError::MalformedKey)
+        } else if errors.iter().any(is_transient_graphql_error) {
+            Err(RoverClientError::TransientGraphQl {
+                msg: errors
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            })
         } else {
             Err(RoverClientError::GraphQl {
                 msg: errors
@@ -246,6 +388,56 @@ fn handle_graphql_body_errors(errors: Vec<GraphQLError>) -> Result<(), RoverClie
     }
 }
 
+/// Whether a GraphQL error from the registry is likely to go away on its own, based on its `extensions.code`.
+fn is_transient_graphql_error(error: &GraphQLError) -> bool {
+    error
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("code"))
+        .and_then(|code| code.as_str())
+        .map(|code| TRANSIENT_GRAPHQL_ERROR_CODES.contains(&code))
+        .unwrap_or(false)
+}
+
+/// Whether a response with this status is worth retrying: rate limits, timeouts, and server errors.
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
+
+/// How long a rate limited or unavailable server asked to wait before retrying, from the
+/// `Retry-After` header. Only the number of seconds form is read; for an HTTP date, requests fall
+/// back to exponential backoff.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Whether a request failed because the connection was reset or closed partway through.
+pub(crate) fn is_connection_reset(err: &reqwest::Error) -> bool {
+    if let Some(hyper_error) = get_source_error_type::<hyper::Error>(err) {
+        if hyper_error.is_incomplete_message() {
+            return true;
+        }
+    }
+    get_source_error_type::<std::io::Error>(err)
+        .map(|io_error| {
+            matches!(
+                io_error.kind(),
+                ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe
+            )
+        })
+        .unwrap_or(false)
+}
+
 /// Downcasts the given err source into T.
 fn get_source_error_type<T: std::error::Error + 'static>(
     err: &dyn std::error::Error,
@@ -325,8 +517,9 @@ mod tests {
         let response = graphql_client.execute(
             "{}".to_string(),
             &HeaderMap::new(),
-            true,
+            RetryPolicy::Transient,
             EndpointKind::ApolloStudio,
+            Ok,
         );
 
         let mock_hits = success_mock.hits();
@@ -350,8 +543,9 @@ mod tests {
         let response = graphql_client.execute(
             "{}".to_string(),
             &HeaderMap::new(),
-            true,
+            RetryPolicy::Transient,
             EndpointKind::ApolloStudio,
+            Ok,
         );
 
         let mock_hits = internal_server_error_mock.hits();
@@ -375,18 +569,208 @@ mod tests {
         let response = graphql_client.execute(
             "{}".to_string(),
             &HeaderMap::new(),
-            true,
+            RetryPolicy::Transient,
             EndpointKind::ApolloStudio,
+            Ok,
         );
 
         let mock_hits = not_found_mock.hits();
 
-        assert!(mock_hits > 1);
+        assert_eq!(mock_hits, 1);
 
         let error = response.expect_err("Response didn't error");
         assert!(error.to_string().contains("Not Found"));
     }
 
+    #[test]
+    fn test_rate_limited_error() {
+        let server = MockServer::start();
+        let rate_limited_path = "/slow-down";
+        let rate_limited_mock = server.mock(|when, then| {
+            when.method(POST).path(rate_limited_path);
+            then.status(429).body("too many requests");
+        });
+
+        let client = ReqwestClient::new();
+        let graphql_client = GraphQLClient::new(&server.url(rate_limited_path), client);
+
+        let response = graphql_client.execute(
+            "{}".to_string(),
+            &HeaderMap::new(),
+            RetryPolicy::Transient,
+            EndpointKind::ApolloStudio,
+            Ok,
+        );
+
+        let mock_hits = rate_limited_mock.hits();
+
+        assert!(mock_hits > 1);
+        let error = response.expect_err("Response didn't error");
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn it_only_retries_publishes_that_were_not_acted_on() {
+        let server = MockServer::start();
+        let unavailable_path = "/unavailable";
+        let unavailable_mock = server.mock(|when, then| {
+            when.method(POST).path(unavailable_path);
+            then.status(503).body("service unavailable");
+        });
+        let rate_limited_path = "/rate-limited";
+        let rate_limited_mock = server.mock(|when, then| {
+            when.method(POST).path(rate_limited_path);
+            then.status(429).body("too many requests");
+        });
+
+        // the server may have published before it failed, so publishing again could apply it twice
+        let response = GraphQLClient::new(&server.url(unavailable_path), ReqwestClient::new())
+            .execute(
+                "{}".to_string(),
+                &HeaderMap::new(),
+                RetryPolicy::Unsent,
+                EndpointKind::ApolloStudio,
+                Ok,
+            );
+        assert!(response.is_err());
+        assert_eq!(unavailable_mock.hits(), 1);
+
+        let response = GraphQLClient::new(&server.url(rate_limited_path), ReqwestClient::new())
+            .with_max_retries(2)
+            .execute(
+                "{}".to_string(),
+                &HeaderMap::new(),
+                RetryPolicy::Unsent,
+                EndpointKind::ApolloStudio,
+                Ok,
+            );
+        assert!(response.is_err());
+        assert_eq!(rate_limited_mock.hits(), 3);
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let server = MockServer::start();
+        let unavailable_path = "/down-for-maintenance";
+        let unavailable_mock = server.mock(|when, then| {
+            when.method(POST).path(unavailable_path);
+            then.status(503).body("service unavailable");
+        });
+
+        let client = ReqwestClient::new();
+        let graphql_client =
+            GraphQLClient::new(&server.url(unavailable_path), client).with_max_retries(2);
+
+        let response = graphql_client.execute(
+            "{}".to_string(),
+            &HeaderMap::new(),
+            RetryPolicy::Transient,
+            EndpointKind::ApolloStudio,
+            Ok,
+        );
+
+        assert_eq!(unavailable_mock.hits(), 3);
+        assert!(response.is_err());
+    }
+
+    #[test]
+    fn test_rate_limited_error_with_retry_after() {
+        let server = MockServer::start();
+        let rate_limited_path = "/come-back-later";
+        let rate_limited_mock = server.mock(|when, then| {
+            when.method(POST).path(rate_limited_path);
+            then.status(429)
+                .header("Retry-After", "1")
+                .body("too many requests");
+        });
+
+        let client = ReqwestClient::new();
+        let graphql_client = GraphQLClient::new(&server.url(rate_limited_path), client);
+
+        let started = Instant::now();
+        let response = graphql_client.execute(
+            "{}".to_string(),
+            &HeaderMap::new(),
+            RetryPolicy::Transient,
+            EndpointKind::ApolloStudio,
+            Ok,
+        );
+
+        // every retry waits the full second the server asked for
+        let mock_hits = rate_limited_mock.hits();
+        assert!(mock_hits > 1);
+        assert!(started.elapsed() >= Duration::from_secs(mock_hits as u64 - 1));
+        assert!(response.is_err());
+    }
+
+    #[test]
+    fn it_returns_transient_graphql_errors() {
+        let errors = vec![GraphQLError {
+            message: "Internal server error".to_string(),
+            locations: None,
+            extensions: Some(
+                [(
+                    "code".to_string(),
+                    serde_json::Value::String("INTERNAL_SERVER_ERROR".to_string()),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            path: None,
+        }];
+        let error = handle_graphql_body_errors(errors).unwrap_err();
+        assert!(matches!(error, RoverClientError::TransientGraphQl { .. }));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn it_retries_transient_graphql_errors_unless_publishing() {
+        let server = MockServer::start();
+        let transient_path = "/internal-server-error";
+        let transient_mock = server.mock(|when, then| {
+            when.method(POST).path(transient_path);
+            then.status(200).json_body(serde_json::json!({
+                "data": null,
+                "errors": [{
+                    "message": "Internal server error",
+                    "extensions": { "code": "INTERNAL_SERVER_ERROR" }
+                }]
+            }));
+        });
+        let handle_response = |response: Response| {
+            let body = response
+                .json::<GraphQLResponse<serde_json::Value>>()
+                .unwrap();
+            handle_graphql_body_errors(body.errors.unwrap_or_default())
+        };
+        let graphql_client = GraphQLClient::new(&server.url(transient_path), ReqwestClient::new())
+            .with_max_retries(2);
+
+        let response = graphql_client.execute(
+            "{}".to_string(),
+            &HeaderMap::new(),
+            RetryPolicy::Transient,
+            EndpointKind::ApolloStudio,
+            handle_response,
+        );
+        assert!(matches!(
+            response,
+            Err(RoverClientError::TransientGraphQl { .. })
+        ));
+        assert_eq!(transient_mock.hits(), 3);
+
+        // the server answered, so it may have published before it failed
+        let response = graphql_client.execute(
+            "{}".to_string(),
+            &HeaderMap::new(),
+            RetryPolicy::Unsent,
+            EndpointKind::ApolloStudio,
+            handle_response,
+        );
+        assert!(response.is_err());
+        assert_eq!(transient_mock.hits(), 4);
+    }
+
     #[test]
     fn test_timeout_error() {
         let server = MockServer::start();
@@ -407,8 +791,9 @@ mod tests {
         let response = graphql_client.execute(
             "{}".to_string(),
             &HeaderMap::new(),
-            true,
+            RetryPolicy::Transient,
             EndpointKind::ApolloStudio,
+            Ok,
         );
 
         let mock_hits = timeout_mock.hits();
@@ -1,7 +1,12 @@
 mod client;
+mod rate_limit;
+mod retry;
 mod studio_client;
 
 pub use client::GraphQLClient;
+pub(crate) use client::{is_connection_reset, is_retryable_status};
+pub use rate_limit::RateLimiter;
+pub(crate) use retry::{give_up_after_max_retries, max_elapsed_time};
 pub use studio_client::StudioClient;
 
 pub(crate) const CLIENT_NAME: &str = "rover-client";
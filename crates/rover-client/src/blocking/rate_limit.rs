@@ -0,0 +1,64 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Spaces out requests so no more than a fixed number start each second, across every client
+/// that shares the limiter. Used to keep commands that make many registry requests, like
+/// fetching every subgraph of a graph, under the registry's rate limits.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_request: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: u32) -> RateLimiter {
+        RateLimiter {
+            interval: Duration::from_secs(1) / requests_per_second.max(1),
+            next_request: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Blocks until the next request is allowed to start.
+    pub fn wait(&self) {
+        let now = Instant::now();
+        // reserve a slot while holding the lock, but sleep without it so other threads can
+        // reserve the slots after this one
+        let slot = match self.next_request.lock() {
+            Ok(mut next_request) => {
+                let slot = next_request.map_or(now, |next| next.max(now));
+                *next_request = Some(slot + self.interval);
+                slot
+            }
+            Err(_) => return,
+        };
+        if slot > now {
+            tracing::debug!(
+                "waiting {}ms to stay under the request rate limit",
+                (slot - now).as_millis()
+            );
+            std::thread::sleep(slot - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    #[test]
+    fn it_spaces_out_requests() {
+        let rate_limiter = RateLimiter::new(20);
+        let started = Instant::now();
+        rate_limiter.wait();
+        assert!(started.elapsed() < Duration::from_millis(50));
+
+        let shared = rate_limiter.clone();
+        shared.wait();
+        rate_limiter.wait();
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,31 @@
+use backoff::Error as BackoffError;
+
+use std::time::Duration;
+
+/// How long to keep retrying for: `default` unless there's a maximum number of retries, which
+/// bounds retries on its own.
+pub(crate) fn max_elapsed_time(
+    default: Option<Duration>,
+    max_retries: Option<u32>,
+) -> Option<Duration> {
+    match max_retries {
+        Some(_) => None,
+        None => default,
+    }
+}
+
+/// Makes a transient error permanent once the request has been retried `max_retries` times.
+pub(crate) fn give_up_after_max_retries<E>(
+    error: BackoffError<E>,
+    attempts: u32,
+    max_retries: Option<u32>,
+) -> BackoffError<E> {
+    match error {
+        BackoffError::Transient { err, .. }
+            if max_retries.map_or(false, |max_retries| attempts > max_retries) =>
+        {
+            BackoffError::Permanent(err)
+        }
+        error => error,
+    }
+}
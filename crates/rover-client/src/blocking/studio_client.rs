@@ -1,5 +1,5 @@
 use crate::{
-    blocking::{GraphQLClient, CLIENT_NAME},
+    blocking::{GraphQLClient, RateLimiter, CLIENT_NAME},
     error::EndpointKind,
     RoverClientError,
 };
@@ -16,6 +16,7 @@ pub struct StudioClient {
     client: GraphQLClient,
     version: String,
     is_sudo: bool,
+    request_headers: HeaderMap,
 }
 
 impl StudioClient {
@@ -33,6 +34,38 @@ impl StudioClient {
             client: GraphQLClient::new(graphql_endpoint, client),
             version: version.to_string(),
             is_sudo,
+            request_headers: HeaderMap::new(),
+        }
+    }
+
+    /// Spaces out requests with `rate_limiter`, which can be shared with other clients.
+    pub fn with_rate_limiter(self, rate_limiter: RateLimiter) -> StudioClient {
+        StudioClient {
+            client: self.client.with_rate_limiter(rate_limiter),
+            ..self
+        }
+    }
+
+    /// Retries failed requests `max_retries` times rather than for a fixed amount of time, see
+    /// [`GraphQLClient::with_max_retries`].
+    pub fn with_max_retries(self, max_retries: u32) -> StudioClient {
+        StudioClient {
+            client: self.client.with_max_retries(max_retries),
+            ..self
+        }
+    }
+
+    /// The number of times failed requests are retried, if it was set.
+    pub fn max_retries(&self) -> Option<u32> {
+        self.client.max_retries()
+    }
+
+    /// Sends `request_headers` with every request, like metadata that identifies the CI system
+    /// making them. They can't replace the headers that identify Rover or carry the API key.
+    pub fn with_request_headers(self, request_headers: HeaderMap) -> StudioClient {
+        StudioClient {
+            request_headers,
+            ..self
         }
     }
 
@@ -49,6 +82,20 @@ impl StudioClient {
             .post::<Q>(variables, &mut header_map, EndpointKind::ApolloStudio)
     }
 
+    /// Client method for making a GraphQL request to Apollo Studio that isn't safe to apply twice,
+    /// like a publish.
+    ///
+    /// Takes one argument, `variables`. Returns a Response or a RoverClientError.
+    /// Only retries requests that never reached Apollo Studio.
+    pub fn post_non_idempotent<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Q::ResponseData, RoverClientError> {
+        let mut header_map = self.build_studio_headers()?;
+        self.client
+            .post_non_idempotent::<Q>(variables, &mut header_map, EndpointKind::ApolloStudio)
+    }
+
     /// Client method for making a GraphQL request to Apollo Studio.
     ///
     /// Takes one argument, `variables`. Returns a Response or a RoverClientError.
@@ -68,7 +115,7 @@ impl StudioClient {
     ///
     /// Takes an `api_key` and a `client_version`, and returns a [HeaderMap].
     pub fn build_studio_headers(&self) -> Result<HeaderMap, RoverClientError> {
-        let mut headers = HeaderMap::new();
+        let mut headers = self.request_headers.clone();
 
         // The headers "apollographql-client-name" and "apollographql-client-version"
         // are used for client identification in Apollo Studio.
@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+use crate::blocking::{is_connection_reset, is_retryable_status};
 use crate::shared::{CheckTaskStatus, CheckWorkflowResponse, GraphRef, LintResponse};
 
 use apollo_federation_types::build::BuildErrors;
@@ -14,6 +15,14 @@ pub enum RoverClientError {
         msg: String,
     },
 
+    /// The registry returned a GraphQL error that is likely to go away on its own,
+    /// such as a rate limit or an internal server error.
+    #[error("{msg}")]
+    TransientGraphQl {
+        /// The encountered GraphQL error.
+        msg: String,
+    },
+
     /// Failed to parse Introspection Response coming from server.
     #[error("{msg}")]
     IntrospectionError {
@@ -207,6 +216,16 @@ pub enum RoverClientError {
     #[error("The check workflow took too long to run.")]
     ChecksTimeoutError { url: Option<String> },
 
+    #[error("The launch for {graph_ref} failed, so routers were not updated.{}", launch_errors_msg(.errors))]
+    LaunchFailed {
+        graph_ref: GraphRef,
+        launch_url: Option<String>,
+        errors: Vec<String>,
+    },
+
+    #[error("The launch took too long to finish.")]
+    LaunchTimeoutError { url: Option<String> },
+
     #[error(
         "A check workflow status was reported but it was not specified as a pass or a failure."
     )]
@@ -238,6 +257,31 @@ pub enum RoverClientError {
     RateLimitExceeded,
 }
 
+impl RoverClientError {
+    /// Whether this error is likely to go away on its own, like a rate limit, a server error,
+    /// or a dropped connection, as opposed to a terminal error like invalid input or missing permissions.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::TransientGraphQl { .. } | Self::RateLimitExceeded => true,
+            Self::SendRequest { source, .. } => {
+                source.is_timeout()
+                    || source.is_connect()
+                    || is_connection_reset(source)
+                    || source.status().map(is_retryable_status).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn launch_errors_msg(errors: &[String]) -> String {
+    errors.iter().fold(String::new(), |mut msg, error| {
+        msg.push('\n');
+        msg.push_str(error);
+        msg
+    })
+}
+
 fn contract_publish_errors_msg(msgs: &Vec<String>, no_launch: &bool) -> String {
     let plural = match msgs.len() {
         1 => "",
@@ -2,4 +2,4 @@ mod runner;
 mod types;
 
 pub use runner::run;
-pub use types::{Actor, ConfigWhoAmIInput, RegistryIdentity};
+pub use types::{Actor, ConfigWhoAmIInput, GraphMembership, RegistryIdentity};
@@ -1,7 +1,7 @@
 use crate::blocking::StudioClient;
 use crate::operations::config::who_am_i::{
-    types::{QueryActorType, QueryResponseData, RegistryIdentity},
-    Actor, ConfigWhoAmIInput,
+    types::{QueryActorType, QueryResponseData, QueryUserPermission, RegistryIdentity},
+    Actor, ConfigWhoAmIInput, GraphMembership,
 };
 use crate::RoverClientError;
 
@@ -49,9 +49,42 @@ fn get_identity_from_response_data(
             _ => Actor::OTHER,
         };
 
-        let graph_title = match me.on {
-            config_who_am_i_query::ConfigWhoAmIQueryMeOn::Graph(s) => Some(s.title),
-            _ => None,
+        let (graph_title, graph_memberships) = match me.on {
+            config_who_am_i_query::ConfigWhoAmIQueryMeOn::Graph(graph) => {
+                let membership = GraphMembership {
+                    organization: graph
+                        .account
+                        .map(|account| account.name)
+                        .unwrap_or_default(),
+                    graph_id: me.id.clone(),
+                    graph_title: graph.title.clone(),
+                    role: graph.my_role.as_ref().map(get_role_name),
+                };
+                (Some(graph.title), vec![membership])
+            }
+            config_who_am_i_query::ConfigWhoAmIQueryMeOn::User(user) => {
+                let memberships = user
+                    .memberships
+                    .into_iter()
+                    .flat_map(|membership| {
+                        let organization = membership.account.name;
+                        let permission = membership.permission;
+                        membership.account.graphs.into_iter().map(move |graph| {
+                            GraphMembership {
+                                organization: organization.clone(),
+                                graph_id: graph.id,
+                                graph_title: graph.title,
+                                // fall back to the user's role in the organization
+                                role: Some(get_role_name(
+                                    graph.my_role.as_ref().unwrap_or(&permission),
+                                )),
+                            }
+                        })
+                    })
+                    .collect();
+                (None, memberships)
+            }
+            _ => (None, Vec::new()),
         };
 
         Ok(RegistryIdentity {
@@ -59,12 +92,20 @@ fn get_identity_from_response_data(
             graph_title,
             key_actor_type,
             credential_origin,
+            graph_memberships,
         })
     } else {
         Err(RoverClientError::InvalidKey)
     }
 }
 
+fn get_role_name(role: &QueryUserPermission) -> String {
+    serde_json::to_value(role)
+        .ok()
+        .and_then(|role| role.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", role))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +120,16 @@ mod tests {
               "asActor": {
                 "type": "USER"
               },
+              "memberships": [{
+                "permission": "CONTRIBUTOR",
+                "account": {
+                  "name": "Tuna Org",
+                  "graphs": [
+                    { "id": "tuna", "title": "Tuna", "myRole": "GRAPH_ADMIN" },
+                    { "id": "salmon", "title": "Salmon", "myRole": null }
+                  ]
+                }
+              }]
             }
         });
         let data: config_who_am_i_query::ResponseData =
@@ -90,6 +141,20 @@ mod tests {
             graph_title: None,
             key_actor_type: Actor::USER,
             credential_origin: CredentialOrigin::EnvVar,
+            graph_memberships: vec![
+                GraphMembership {
+                    organization: "Tuna Org".to_string(),
+                    graph_id: "tuna".to_string(),
+                    graph_title: "Tuna".to_string(),
+                    role: Some("GRAPH_ADMIN".to_string()),
+                },
+                GraphMembership {
+                    organization: "Tuna Org".to_string(),
+                    graph_id: "salmon".to_string(),
+                    graph_title: "Salmon".to_string(),
+                    role: Some("CONTRIBUTOR".to_string()),
+                },
+            ],
         };
         assert!(output.is_ok());
         assert_eq!(output.unwrap(), expected_identity);
@@ -105,6 +170,10 @@ mod tests {
               "asActor": {
                 "type": "GRAPH"
               },
+              "myRole": "CONTRIBUTOR",
+              "account": {
+                "name": "Big Org"
+              },
             }
         });
         let data: config_who_am_i_query::ResponseData =
@@ -116,6 +185,12 @@ mod tests {
             graph_title: Some("GraphKeyService".to_string()),
             key_actor_type: Actor::GRAPH,
             credential_origin: CredentialOrigin::EnvVar,
+            graph_memberships: vec![GraphMembership {
+                organization: "Big Org".to_string(),
+                graph_id: "big-ol-graph-key-lolol".to_string(),
+                graph_title: "GraphKeyService".to_string(),
+                role: Some("CONTRIBUTOR".to_string()),
+            }],
         };
         assert!(output.is_ok());
         assert_eq!(output.unwrap(), expected_identity);
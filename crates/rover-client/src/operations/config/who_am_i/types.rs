@@ -3,10 +3,12 @@ use std::fmt::{Display, Formatter, Result};
 use super::runner::config_who_am_i_query;
 
 use houston::CredentialOrigin;
+use serde::Serialize;
 
 pub(crate) type QueryResponseData = config_who_am_i_query::ResponseData;
 pub(crate) type QueryVariables = config_who_am_i_query::Variables;
 pub(crate) type QueryActorType = config_who_am_i_query::ActorType;
+pub(crate) type QueryUserPermission = config_who_am_i_query::UserPermission;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct RegistryIdentity {
@@ -14,6 +16,16 @@ pub struct RegistryIdentity {
     pub graph_title: Option<String>,
     pub key_actor_type: Actor,
     pub credential_origin: CredentialOrigin,
+    pub graph_memberships: Vec<GraphMembership>,
+}
+
+/// A graph that a credential can access, and the role it has on that graph.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct GraphMembership {
+    pub organization: String,
+    pub graph_id: String,
+    pub graph_title: String,
+    pub role: Option<String>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -44,6 +44,7 @@ pub fn run(
     let graph_ref = input.graph_ref.clone();
     let mut url: Option<String> = None;
     let now = Instant::now();
+    let mut failed_attempts = 0;
     loop {
         let result = client.post::<GraphCheckWorkflowQuery>(input.clone().into());
         match result {
@@ -58,9 +59,12 @@ pub fn run(
                 }
                 url = get_target_url_from_data(data);
             }
-            Err(e) => {
+            Err(e) if e.is_retryable() => {
+                failed_attempts += 1;
+                tracing::info!("check status request failed {} time(s)", failed_attempts);
                 eprintln!("error while checking status of check: {e}\nthis error may be transient... retrying");
             }
+            Err(e) => return Err(e),
         }
         if now.elapsed() > Duration::from_secs(input.checks_timeout_seconds) {
             return Err(RoverClientError::ChecksTimeoutError { url });
@@ -0,0 +1,177 @@
+use crate::blocking::StudioClient;
+use crate::operations::graph::create::types::NewKeyMutationVariables;
+use crate::operations::graph::create::{GraphCreateInput, GraphCreateResponse};
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "src/operations/graph/create/create_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. graph_create_mutation
+pub(crate) struct GraphCreateMutation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "src/operations/graph/create/new_key_mutation.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. graph_new_key_mutation
+pub(crate) struct GraphNewKeyMutation;
+
+/// The name given to the API key that's created alongside a new graph.
+const KEY_NAME: &str = "rover-graph-create";
+
+/// The main function to be used from this module.
+/// This function creates a graph with an initial variant in an organization,
+/// then creates a graph API key for it.
+pub fn run(
+    input: GraphCreateInput,
+    client: &StudioClient,
+) -> Result<GraphCreateResponse, RoverClientError> {
+    let organization_id = input.organization_id.clone();
+    let graph_ref = input.graph_ref.clone();
+    let response_data = client.post::<GraphCreateMutation>(input.into())?;
+    let graph_id = get_graph_id_from_response_data(response_data, &organization_id)?;
+
+    // the graph exists now, so a failure from here on has to say so
+    let api_key = client
+        .post::<GraphNewKeyMutation>(NewKeyMutationVariables {
+            graph_id: graph_id.clone(),
+            key_name: Some(KEY_NAME.to_string()),
+        })
+        .and_then(|response_data| get_api_key_from_response_data(response_data, &graph_id))
+        .map_err(|e| RoverClientError::AdhocError {
+            msg: format!(
+                "Created {}, but could not create an API key for it: {}. Create a graph API key for it in Apollo Studio instead.",
+                graph_ref, e
+            ),
+        })?;
+
+    Ok(GraphCreateResponse { graph_ref, api_key })
+}
+
+fn get_graph_id_from_response_data(
+    data: graph_create_mutation::ResponseData,
+    organization_id: &str,
+) -> Result<String, RoverClientError> {
+    let organization = data
+        .organization
+        .ok_or_else(|| RoverClientError::AdhocError {
+            msg: format!(
+                "Could not find organization \"{}\", or your API key can't create graphs in it.",
+                organization_id
+            ),
+        })?;
+    match organization.create_graph {
+        graph_create_mutation::GraphCreateMutationOrganizationCreateGraph::Graph(graph) => {
+            Ok(graph.id)
+        }
+        graph_create_mutation::GraphCreateMutationOrganizationCreateGraph::GraphCreationError(
+            error,
+        ) => Err(RoverClientError::AdhocError { msg: error.message }),
+    }
+}
+
+fn get_api_key_from_response_data(
+    data: graph_new_key_mutation::ResponseData,
+    graph_id: &str,
+) -> Result<String, RoverClientError> {
+    let graph = data
+        .graph
+        .ok_or_else(|| RoverClientError::GraphIdNotFound {
+            graph_id: graph_id.to_string(),
+        })?;
+    Ok(graph.new_key.token)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::shared::GraphRef;
+    use serde_json::json;
+
+    #[test]
+    fn get_graph_id_from_response_data_works() {
+        let json_response = json!({
+            "organization": {
+                "createGraph": {
+                    "__typename": "Graph",
+                    "id": "my-graph"
+                }
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = get_graph_id_from_response_data(data, "my-org");
+        assert_eq!(output.unwrap(), "my-graph");
+    }
+
+    #[test]
+    fn get_graph_id_from_response_data_errs_with_creation_error() {
+        let json_response = json!({
+            "organization": {
+                "createGraph": {
+                    "__typename": "GraphCreationError",
+                    "message": "A graph with this ID already exists."
+                }
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = get_graph_id_from_response_data(data, "my-org");
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "A graph with this ID already exists."
+        );
+    }
+
+    #[test]
+    fn get_graph_id_from_response_data_errs_with_no_organization() {
+        let json_response = json!({ "organization": null });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = get_graph_id_from_response_data(data, "my-org");
+        assert!(output.unwrap_err().to_string().contains("\"my-org\""));
+    }
+
+    #[test]
+    fn it_creates_variants_on_the_federation_version_track() {
+        let build_pipeline_track = |federation_version: &str| {
+            let variables = graph_create_mutation::Variables::from(GraphCreateInput {
+                organization_id: "my-org".to_string(),
+                graph_ref: GraphRef::from_str("my-graph@prod").unwrap(),
+                title: "My Graph".to_string(),
+                hidden: false,
+                federation_version: federation_version.parse().unwrap(),
+            });
+            serde_json::to_value(variables).unwrap()["build_pipeline_track"].clone()
+        };
+        assert_eq!(build_pipeline_track("=2.4.7"), json!("FED_2_4"));
+        assert_eq!(build_pipeline_track("2"), json!("FED_2_9"));
+        assert_eq!(build_pipeline_track("1"), json!("FED_1_1"));
+    }
+
+    #[test]
+    fn get_api_key_from_response_data_works() {
+        let json_response = json!({
+            "graph": {
+                "newKey": {
+                    "token": "service:my-graph:abc123"
+                }
+            }
+        });
+        let data = serde_json::from_value(json_response).unwrap();
+        let output = get_api_key_from_response_data(data, "my-graph");
+        assert_eq!(output.unwrap(), "service:my-graph:abc123");
+    }
+}
@@ -0,0 +1,52 @@
+use apollo_federation_types::config::FederationVersion;
+
+use crate::operations::graph::create::runner::{graph_create_mutation, graph_new_key_mutation};
+use crate::shared::GraphRef;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphCreateInput {
+    pub organization_id: String,
+    pub graph_ref: GraphRef,
+    pub title: String,
+    pub hidden: bool,
+    /// The federation version the graph's initial variant composes with
+    pub federation_version: FederationVersion,
+}
+
+type CreateMutationVariables = graph_create_mutation::Variables;
+type BuildPipelineTrack = graph_create_mutation::BuildPipelineTrack;
+
+impl From<GraphCreateInput> for CreateMutationVariables {
+    fn from(input: GraphCreateInput) -> Self {
+        Self {
+            organization_id: input.organization_id,
+            graph_id: input.graph_ref.name,
+            title: input.title,
+            hidden: input.hidden,
+            variant: input.graph_ref.variant,
+            build_pipeline_track: build_pipeline_track(&input.federation_version),
+        }
+    }
+}
+
+/// The build pipeline track for `federation_version`, named after its major and minor version.
+/// Floating versions use the newest track of their major version.
+fn build_pipeline_track(federation_version: &FederationVersion) -> BuildPipelineTrack {
+    match federation_version {
+        FederationVersion::LatestFedOne | FederationVersion::ExactFedOne(_) => {
+            BuildPipelineTrack::FED_1_1
+        }
+        FederationVersion::LatestFedTwo => BuildPipelineTrack::FED_2_9,
+        FederationVersion::ExactFedTwo(version) => {
+            BuildPipelineTrack::Other(format!("FED_2_{}", version.minor))
+        }
+    }
+}
+
+pub(crate) type NewKeyMutationVariables = graph_new_key_mutation::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphCreateResponse {
+    pub graph_ref: GraphRef,
+    pub api_key: String,
+}
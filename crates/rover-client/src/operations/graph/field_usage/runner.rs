@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use graphql_client::*;
+
+use crate::blocking::StudioClient;
+use crate::operations::graph::field_usage::types::*;
+use crate::shared::GraphRef;
+use crate::RoverClientError;
+
+type Timestamp = String;
+type Long = i64;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "src/operations/graph/field_usage/field_usage_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. field_usage_query
+pub(crate) struct FieldUsageQuery;
+
+/// Fetches how many times each client executed a field of a graph variant
+pub fn run(
+    input: FieldUsageInput,
+    client: &StudioClient,
+) -> Result<FieldUsageResponse, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let response_data = client.post::<FieldUsageQuery>(input.into())?;
+    let records = get_records_from_response_data(response_data, graph_ref)?;
+    Ok(FieldUsageResponse {
+        clients: usage_by_client(records),
+    })
+}
+
+fn get_records_from_response_data(
+    response_data: QueryResponseData,
+    graph_ref: GraphRef,
+) -> Result<Vec<QueryFieldUsageRecord>, RoverClientError> {
+    let graph = response_data
+        .graph
+        .ok_or(RoverClientError::GraphNotFound { graph_ref })?;
+    Ok(graph
+        .stats_window
+        .map(|stats_window| stats_window.field_usage)
+        .unwrap_or_default())
+}
+
+/// Adds up the records of each client and version, since each only covers part of the window.
+fn usage_by_client(records: Vec<QueryFieldUsageRecord>) -> Vec<ClientFieldUsage> {
+    let mut executions = BTreeMap::new();
+    for record in records {
+        *executions
+            .entry((record.group_by.client_name, record.group_by.client_version))
+            .or_default() += record.metrics.estimated_execution_count;
+    }
+    let mut clients: Vec<ClientFieldUsage> = executions
+        .into_iter()
+        .map(
+            |((client_name, client_version), estimated_execution_count)| ClientFieldUsage {
+                client_name,
+                client_version,
+                estimated_execution_count,
+            },
+        )
+        .collect();
+    // the sort is stable, so clients with as many executions stay sorted by name
+    clients.sort_by(|a, b| {
+        b.estimated_execution_count
+            .cmp(&a.estimated_execution_count)
+    });
+    clients
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_adds_up_usage_by_client() {
+        let json_response = json!({
+            "graph": {
+                "statsWindow": {
+                    "fieldUsage": [
+                        {
+                            "groupBy": { "clientName": "web", "clientVersion": "1.2.0" },
+                            "metrics": { "estimatedExecutionCount": 40 }
+                        },
+                        {
+                            "groupBy": { "clientName": "ios", "clientVersion": "3.0.1" },
+                            "metrics": { "estimatedExecutionCount": 75 }
+                        },
+                        {
+                            "groupBy": { "clientName": "web", "clientVersion": "1.2.0" },
+                            "metrics": { "estimatedExecutionCount": 60 }
+                        },
+                        {
+                            "groupBy": { "clientName": null, "clientVersion": null },
+                            "metrics": { "estimatedExecutionCount": 5 }
+                        }
+                    ]
+                }
+            }
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let records = get_records_from_response_data(data, mock_graph_ref()).unwrap();
+        let clients = usage_by_client(records);
+        let summary = clients
+            .iter()
+            .map(|client| {
+                (
+                    client.client_name.as_deref(),
+                    client.estimated_execution_count,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(Some("web"), 100), (Some("ios"), 75), (None, 5)]
+        );
+    }
+
+    #[test]
+    fn it_errs_when_the_graph_is_missing() {
+        let data: QueryResponseData = serde_json::from_value(json!({ "graph": null })).unwrap();
+        assert!(get_records_from_response_data(data, mock_graph_ref()).is_err());
+    }
+
+    fn mock_graph_ref() -> GraphRef {
+        GraphRef {
+            name: "mygraph".to_string(),
+            variant: "current".to_string(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{operations::graph::field_usage::runner::field_usage_query, shared::GraphRef};
+
+pub(crate) type QueryFieldUsageRecord =
+    field_usage_query::FieldUsageQueryGraphStatsWindowFieldUsage;
+pub(crate) type QueryResponseData = field_usage_query::ResponseData;
+
+type QueryVariables = field_usage_query::Variables;
+type QueryFieldUsageFilter = field_usage_query::FieldUsageFilter;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FieldUsageInput {
+    pub graph_ref: GraphRef,
+    pub type_name: String,
+    pub field_name: String,
+    /// The start of the window to report usage for, which ends now
+    pub from: DateTime<Utc>,
+}
+
+impl From<FieldUsageInput> for QueryVariables {
+    fn from(input: FieldUsageInput) -> Self {
+        Self {
+            graph_id: input.graph_ref.name,
+            from: input.from.to_rfc3339_opts(SecondsFormat::Secs, true),
+            filter: QueryFieldUsageFilter {
+                client_name: None,
+                client_version: None,
+                field_name: Some(input.field_name),
+                parent_type: Some(input.type_name),
+                schema_tag: Some(input.graph_ref.variant),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct FieldUsageResponse {
+    /// The clients that executed the field, the most executions first
+    pub clients: Vec<ClientFieldUsage>,
+}
+
+#[derive(Clone, Serialize, Eq, PartialEq, Debug)]
+pub struct ClientFieldUsage {
+    pub client_name: Option<String>,
+    pub client_version: Option<String>,
+    /// How many times the client executed the field, accounting for sampling
+    pub estimated_execution_count: i64,
+}
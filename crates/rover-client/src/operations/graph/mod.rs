@@ -13,11 +13,20 @@ pub mod check;
 /// "graph introspect" command execution
 pub mod introspect;
 
+/// "graph create" command execution
+pub mod create;
+
 /// "graph delete" command execution
 pub mod delete;
 
 /// "graph lint" command execution
 pub mod lint;
 
+/// "graph explain" command execution
+pub mod field_usage;
+
+/// checking what an API key is allowed to do with a graph before running a command
+pub mod permissions;
+
 /// internal module for getting info about variants
 pub(crate) mod variant;
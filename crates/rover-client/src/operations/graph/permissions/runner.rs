@@ -0,0 +1,99 @@
+use crate::blocking::StudioClient;
+use crate::operations::graph::permissions::{GraphPermissions, GraphPermissionsInput};
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "src/operations/graph/permissions/permissions_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. graph_permissions_query
+pub(crate) struct GraphPermissionsQuery;
+
+/// Fetches the permissions the authenticated API key has for a graph variant.
+pub fn run(
+    input: GraphPermissionsInput,
+    client: &StudioClient,
+) -> Result<GraphPermissions, RoverClientError> {
+    let graph_ref = input.graph_ref.clone();
+    let response_data = client.post::<GraphPermissionsQuery>(input.into())?;
+    let graph = response_data
+        .graph
+        .ok_or(RoverClientError::GraphNotFound { graph_ref })?;
+    Ok(get_permissions_from_graph(graph))
+}
+
+fn get_permissions_from_graph(
+    graph: graph_permissions_query::GraphPermissionsQueryGraph,
+) -> GraphPermissions {
+    let roles = graph.roles;
+    let variant_permissions = graph.variant.map(|variant| variant.permissions);
+    GraphPermissions {
+        can_check_schemas: roles.as_ref().map(|roles| roles.can_check_schemas),
+        can_manage_variants: roles.as_ref().map(|roles| roles.can_manage_variants),
+        can_register_operations: roles.as_ref().map(|roles| roles.can_register_operations),
+        can_push_schemas: variant_permissions
+            .as_ref()
+            .map(|permissions| permissions.can_push_schemas),
+        can_update_variant_readme: variant_permissions
+            .as_ref()
+            .map(|permissions| permissions.can_update_variant_readme),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_permissions_from_graph_works() {
+        let json_response = json!({
+            "roles": {
+                "canCheckSchemas": true,
+                "canManageVariants": false,
+                "canRegisterOperations": false
+            },
+            "variant": {
+                "permissions": {
+                    "canPushSchemas": false,
+                    "canUpdateVariantReadme": true
+                }
+            }
+        });
+        let graph = serde_json::from_value(json_response).unwrap();
+        assert_eq!(
+            get_permissions_from_graph(graph),
+            GraphPermissions {
+                can_check_schemas: Some(true),
+                can_manage_variants: Some(false),
+                can_register_operations: Some(false),
+                can_push_schemas: Some(false),
+                can_update_variant_readme: Some(true),
+            }
+        );
+    }
+
+    #[test]
+    fn get_permissions_from_graph_leaves_unknown_variant_permissions_empty() {
+        let json_response = json!({
+            "roles": {
+                "canCheckSchemas": true,
+                "canManageVariants": true,
+                "canRegisterOperations": true
+            },
+            "variant": null
+        });
+        let graph = serde_json::from_value(json_response).unwrap();
+        let permissions = get_permissions_from_graph(graph);
+        assert_eq!(permissions.can_check_schemas, Some(true));
+        assert_eq!(permissions.can_push_schemas, None);
+        assert_eq!(permissions.can_update_variant_readme, None);
+    }
+}
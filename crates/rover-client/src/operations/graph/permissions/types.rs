@@ -0,0 +1,30 @@
+use crate::operations::graph::permissions::runner::graph_permissions_query;
+use crate::shared::GraphRef;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphPermissionsInput {
+    pub graph_ref: GraphRef,
+}
+
+type QueryVariables = graph_permissions_query::Variables;
+impl From<GraphPermissionsInput> for QueryVariables {
+    fn from(input: GraphPermissionsInput) -> Self {
+        Self {
+            graph_id: input.graph_ref.name,
+            variant: input.graph_ref.variant,
+        }
+    }
+}
+
+/// What the authenticated API key is allowed to do with a graph and one of its variants.
+///
+/// A permission is `None` when the registry didn't report it, e.g. because the
+/// variant doesn't exist yet.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GraphPermissions {
+    pub can_check_schemas: Option<bool>,
+    pub can_manage_variants: Option<bool>,
+    pub can_register_operations: Option<bool>,
+    pub can_push_schemas: Option<bool>,
+    pub can_update_variant_readme: Option<bool>,
+}
@@ -27,7 +27,7 @@ pub fn run(
     client: &StudioClient,
 ) -> Result<GraphPublishResponse, RoverClientError> {
     let graph_ref = input.graph_ref.clone();
-    let data = client.post::<GraphPublishMutation>(input.into())?;
+    let data = client.post_non_idempotent::<GraphPublishMutation>(input.into())?;
     let publish_response = get_publish_response_from_data(data, graph_ref)?;
     build_response(publish_response)
 }
@@ -21,3 +21,6 @@ pub mod config;
 
 /// all rover-client functionality for the "license" commands in rover
 pub mod license;
+
+/// all rover-client functionality for the "org" commands in rover
+pub mod org;
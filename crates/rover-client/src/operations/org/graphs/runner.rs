@@ -0,0 +1,198 @@
+use crate::blocking::StudioClient;
+use crate::operations::org::graphs::types::{
+    OrgGraph, OrgGraphsInput, OrgGraphsResponse, OrgVariant, QueryResponseData,
+};
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/org/graphs/graphs_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. org_graphs_query
+pub(crate) struct OrgGraphsQuery;
+
+/// Lists every graph, and each of its variants, that the authenticated
+/// API key can access, filtered by the graph ID and title in `input`.
+pub fn run(
+    input: OrgGraphsInput,
+    client: &StudioClient,
+) -> Result<OrgGraphsResponse, RoverClientError> {
+    let response_data = client.post::<OrgGraphsQuery>(input.clone().into())?;
+    get_graphs_from_response_data(response_data, &input)
+}
+
+fn get_graphs_from_response_data(
+    response_data: QueryResponseData,
+    input: &OrgGraphsInput,
+) -> Result<OrgGraphsResponse, RoverClientError> {
+    let me = response_data.me.ok_or(RoverClientError::InvalidKey)?;
+    let mut graphs = match me {
+        org_graphs_query::OrgGraphsQueryMe::Graph(graph) => {
+            let variants = graph
+                .variants
+                .into_iter()
+                .map(|variant| OrgVariant::new(&graph.id, variant.name))
+                .collect();
+            vec![OrgGraph {
+                organization: graph
+                    .account
+                    .map(|account| account.name)
+                    .unwrap_or_default(),
+                id: graph.id,
+                title: graph.title,
+                variants,
+            }]
+        }
+        org_graphs_query::OrgGraphsQueryMe::User(user) => user
+            .memberships
+            .into_iter()
+            .flat_map(|membership| {
+                let organization = membership.account.name;
+                membership
+                    .account
+                    .graphs
+                    .into_iter()
+                    .map(move |graph| OrgGraph {
+                        organization: organization.clone(),
+                        variants: graph
+                            .variants
+                            .into_iter()
+                            .map(|variant| OrgVariant::new(&graph.id, variant.name))
+                            .collect(),
+                        id: graph.id,
+                        title: graph.title,
+                    })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    graphs.retain(|graph| input.matches(graph));
+    graphs.sort_by(|a, b| (&a.organization, &a.id).cmp(&(&b.organization, &b.id)));
+    Ok(OrgGraphsResponse { graphs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_user_response() -> QueryResponseData {
+        let json_response = json!({
+            "me": {
+              "__typename": "User",
+              "memberships": [
+                {
+                  "account": {
+                    "name": "Tuna Org",
+                    "graphs": [
+                      {
+                        "id": "tuna",
+                        "title": "Tuna",
+                        "variants": [{ "name": "current" }, { "name": "staging" }]
+                      },
+                      { "id": "salmon", "title": "Salmon Supergraph", "variants": [] }
+                    ]
+                  }
+                },
+                {
+                  "account": {
+                    "name": "Another Org",
+                    "graphs": [
+                      { "id": "cod", "title": "Cod", "variants": [{ "name": "prod" }] }
+                    ]
+                  }
+                }
+              ]
+            }
+        });
+        serde_json::from_value(json_response).unwrap()
+    }
+
+    #[test]
+    fn get_graphs_from_response_data_works_for_users() {
+        let output =
+            get_graphs_from_response_data(get_user_response(), &OrgGraphsInput::default()).unwrap();
+        let ids: Vec<&str> = output
+            .graphs
+            .iter()
+            .map(|graph| graph.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["cod", "salmon", "tuna"]);
+        assert_eq!(
+            output.graphs[2].variants,
+            vec![
+                OrgVariant {
+                    name: "current".to_string(),
+                    graph_ref: "tuna@current".to_string(),
+                },
+                OrgVariant {
+                    name: "staging".to_string(),
+                    graph_ref: "tuna@staging".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn get_graphs_from_response_data_works_for_graph_keys() {
+        let json_response = json!({
+            "me": {
+              "__typename": "Graph",
+              "id": "big-ol-graph",
+              "title": "Big Ol Graph",
+              "account": { "name": "Big Org" },
+              "variants": [{ "name": "current" }]
+            }
+        });
+        let data: QueryResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_graphs_from_response_data(data, &OrgGraphsInput::default()).unwrap();
+        assert_eq!(
+            output.graphs,
+            vec![OrgGraph {
+                organization: "Big Org".to_string(),
+                id: "big-ol-graph".to_string(),
+                title: "Big Ol Graph".to_string(),
+                variants: vec![OrgVariant {
+                    name: "current".to_string(),
+                    graph_ref: "big-ol-graph@current".to_string(),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn get_graphs_from_response_data_filters_by_id_and_title() {
+        let filter = |graph_id: Option<&str>, title: Option<&str>| {
+            let input = OrgGraphsInput {
+                graph_id: graph_id.map(str::to_string),
+                title: title.map(str::to_string),
+            };
+            get_graphs_from_response_data(get_user_response(), &input)
+                .unwrap()
+                .graphs
+                .into_iter()
+                .map(|graph| graph.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(filter(Some("TUN"), None), vec!["tuna"]);
+        assert_eq!(filter(None, Some("supergraph")), vec!["salmon"]);
+        assert_eq!(filter(Some("o"), Some("c")), vec!["cod"]);
+        assert!(filter(Some("tuna"), Some("salmon")).is_empty());
+    }
+
+    #[test]
+    fn get_graphs_from_response_data_errs_with_no_identity() {
+        let data: QueryResponseData = serde_json::from_value(json!({ "me": null })).unwrap();
+        let output = get_graphs_from_response_data(data, &OrgGraphsInput::default());
+        assert!(matches!(output, Err(RoverClientError::InvalidKey)));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use crate::operations::org::graphs::runner::org_graphs_query;
+
+pub(crate) type QueryResponseData = org_graphs_query::ResponseData;
+type QueryVariables = org_graphs_query::Variables;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OrgGraphsInput {
+    /// Only include graphs whose ID contains this text (case-insensitive)
+    pub graph_id: Option<String>,
+
+    /// Only include graphs whose title contains this text (case-insensitive)
+    pub title: Option<String>,
+}
+
+impl From<OrgGraphsInput> for QueryVariables {
+    fn from(_input: OrgGraphsInput) -> Self {
+        Self {}
+    }
+}
+
+impl OrgGraphsInput {
+    pub(crate) fn matches(&self, graph: &OrgGraph) -> bool {
+        let contains = |haystack: &str, needle: &Option<String>| {
+            needle
+                .as_ref()
+                .map(|needle| haystack.to_lowercase().contains(&needle.to_lowercase()))
+                .unwrap_or(true)
+        };
+        contains(&graph.id, &self.graph_id) && contains(&graph.title, &self.title)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OrgGraphsResponse {
+    pub graphs: Vec<OrgGraph>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OrgGraph {
+    pub organization: String,
+    pub id: String,
+    pub title: String,
+    pub variants: Vec<OrgVariant>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct OrgVariant {
+    pub name: String,
+    pub graph_ref: String,
+}
+
+impl OrgVariant {
+    pub(crate) fn new(graph_id: &str, name: String) -> Self {
+        Self {
+            graph_ref: format!("{}@{}", graph_id, name),
+            name,
+        }
+    }
+}
@@ -10,7 +10,7 @@ use crate::shared::{
 };
 use crate::RoverClientError;
 
-use apollo_federation_types::build::BuildError;
+use apollo_federation_types::build::{BuildError, BuildErrorNode};
 
 use self::subgraph_check_workflow_query::SubgraphCheckWorkflowQueryGraphCheckWorkflowTasksOn::{
     CompositionCheckTask, DownstreamCheckTask, LintCheckTask, OperationsCheckTask,
@@ -51,6 +51,7 @@ pub fn run(
     let graph_ref = input.graph_ref.clone();
     let mut url: Option<String> = None;
     let now = Instant::now();
+    let mut failed_attempts = 0;
     loop {
         let result = client.post::<SubgraphCheckWorkflowQuery>(input.clone().into());
         match result {
@@ -65,9 +66,12 @@ pub fn run(
                 }
                 url = get_target_url_from_data(data);
             }
-            Err(e) => {
+            Err(e) if e.is_retryable() => {
+                failed_attempts += 1;
+                tracing::info!("check status request failed {} time(s)", failed_attempts);
                 eprintln!("error while checking status of check: {e}\nthis error may be transient... retrying");
             }
+            Err(e) => return Err(e),
         }
         if now.elapsed() > Duration::from_secs(input.checks_timeout_seconds) {
             return Err(RoverClientError::ChecksTimeoutError { url });
@@ -173,10 +177,25 @@ fn get_check_response_from_data(
 
         let mut build_errors = Vec::with_capacity(num_failures);
         for query_composition_error in composition_errors {
+            // GraphOS only reports the line and column of each location, not which subgraph it's in
+            let nodes = query_composition_error
+                .locations
+                .into_iter()
+                .flatten()
+                .filter_map(|location| {
+                    serde_json::from_value::<BuildErrorNode>(serde_json::json!({
+                        "subgraph": null,
+                        "source": null,
+                        "start": { "line": location.line, "column": location.column },
+                        "end": null
+                    }))
+                    .ok()
+                })
+                .collect::<Vec<_>>();
             build_errors.push(BuildError::composition_error(
                 query_composition_error.code,
                 Some(query_composition_error.message),
-                None,
+                Some(nodes).filter(|nodes| !nodes.is_empty()),
                 None,
             ));
         }
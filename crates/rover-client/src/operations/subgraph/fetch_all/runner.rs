@@ -0,0 +1,165 @@
+use std::str::FromStr;
+
+use apollo_federation_types::config::FederationVersion;
+
+use super::types::*;
+use crate::blocking::StudioClient;
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/subgraph/fetch_all/fetch_all_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. subgraph_fetch_all_query
+pub(crate) struct SubgraphFetchAllQuery;
+
+/// Fetches the schema and routing URL of every subgraph published to a variant in one
+/// request, along with the federation version the variant was last composed with.
+pub fn run(
+    input: SubgraphFetchAllInput,
+    client: &StudioClient,
+) -> Result<SubgraphFetchAllResponse, RoverClientError> {
+    let response_data = client.post::<SubgraphFetchAllQuery>(input.clone().into())?;
+    get_subgraphs_from_response_data(input, response_data)
+}
+
+fn get_subgraphs_from_response_data(
+    input: SubgraphFetchAllInput,
+    response_data: SubgraphFetchAllResponseData,
+) -> Result<SubgraphFetchAllResponse, RoverClientError> {
+    let variant = match response_data.variant {
+        Some(SubgraphFetchAllGraphVariant::GraphVariant(variant)) => variant,
+        Some(_) => return Err(RoverClientError::InvalidGraphRef),
+        None => {
+            return Err(RoverClientError::GraphNotFound {
+                graph_ref: input.graph_ref,
+            })
+        }
+    };
+    let subgraphs = variant
+        .subgraphs
+        .ok_or_else(|| RoverClientError::ExpectedFederatedGraph {
+            graph_ref: input.graph_ref.clone(),
+            can_operation_convert: true,
+        })?
+        .into_iter()
+        .map(|subgraph| PublishedSubgraph {
+            name: subgraph.name,
+            routing_url: subgraph.url,
+            sdl: subgraph.active_partial_schema.sdl,
+        })
+        .collect();
+    // the build input records the exact version, like `2.9.0`
+    let federation_version = variant
+        .latest_launch
+        .and_then(|launch| match launch.build_input {
+            SubgraphFetchAllBuildInput::CompositionBuildInput(build_input) => build_input.version,
+            _ => None,
+        })
+        .and_then(|version| FederationVersion::from_str(&format!("={}", version)).ok());
+    Ok(SubgraphFetchAllResponse {
+        subgraphs,
+        federation_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::GraphRef;
+    use serde_json::json;
+
+    fn mock_input() -> SubgraphFetchAllInput {
+        SubgraphFetchAllInput {
+            graph_ref: GraphRef {
+                name: "mygraph".to_string(),
+                variant: "current".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn get_subgraphs_from_response_data_works() {
+        let json_response = json!({
+            "variant": {
+                "__typename": "GraphVariant",
+                "subgraphs": [
+                    {
+                        "name": "accounts",
+                        "url": "http://accounts.svc",
+                        "activePartialSchema": { "sdl": "type Query { me: User }" }
+                    },
+                    {
+                        "name": "products",
+                        "url": null,
+                        "activePartialSchema": { "sdl": "type Query { top: [Product] }" }
+                    }
+                ],
+                "latestLaunch": {
+                    "buildInput": {
+                        "__typename": "CompositionBuildInput",
+                        "version": "2.9.0"
+                    }
+                }
+            }
+        });
+        let data: SubgraphFetchAllResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_subgraphs_from_response_data(mock_input(), data).unwrap();
+        assert_eq!(
+            output.subgraphs,
+            vec![
+                PublishedSubgraph {
+                    name: "accounts".to_string(),
+                    routing_url: Some("http://accounts.svc".to_string()),
+                    sdl: "type Query { me: User }".to_string(),
+                },
+                PublishedSubgraph {
+                    name: "products".to_string(),
+                    routing_url: None,
+                    sdl: "type Query { top: [Product] }".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            output.federation_version,
+            Some(FederationVersion::from_str("=2.9.0").unwrap())
+        );
+    }
+
+    #[test]
+    fn get_subgraphs_from_response_data_errs_for_non_federated_graphs() {
+        let json_response = json!({
+            "variant": {
+                "__typename": "GraphVariant",
+                "subgraphs": null,
+                "latestLaunch": null
+            }
+        });
+        let data: SubgraphFetchAllResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_subgraphs_from_response_data(mock_input(), data);
+        assert!(matches!(
+            output,
+            Err(RoverClientError::ExpectedFederatedGraph { .. })
+        ));
+    }
+
+    #[test]
+    fn get_subgraphs_from_response_data_errs_with_no_variant() {
+        let json_response = json!({ "variant": null });
+        let data: SubgraphFetchAllResponseData = serde_json::from_value(json_response).unwrap();
+        let output = get_subgraphs_from_response_data(mock_input(), data);
+        assert!(matches!(
+            output,
+            Err(RoverClientError::GraphNotFound { .. })
+        ));
+    }
+}
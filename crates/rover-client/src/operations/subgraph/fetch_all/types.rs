@@ -0,0 +1,40 @@
+use apollo_federation_types::config::FederationVersion;
+
+use crate::shared::GraphRef;
+
+use super::runner::subgraph_fetch_all_query;
+
+pub(crate) type SubgraphFetchAllResponseData = subgraph_fetch_all_query::ResponseData;
+pub(crate) type SubgraphFetchAllGraphVariant =
+    subgraph_fetch_all_query::SubgraphFetchAllQueryVariant;
+pub(crate) type SubgraphFetchAllBuildInput =
+    subgraph_fetch_all_query::SubgraphFetchAllQueryVariantOnGraphVariantLatestLaunchBuildInput;
+pub(crate) type QueryVariables = subgraph_fetch_all_query::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphFetchAllInput {
+    pub graph_ref: GraphRef,
+}
+
+impl From<SubgraphFetchAllInput> for QueryVariables {
+    fn from(input: SubgraphFetchAllInput) -> Self {
+        Self {
+            graph_ref: input.graph_ref.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphFetchAllResponse {
+    /// The subgraphs published to the variant, in the order the registry lists them
+    pub subgraphs: Vec<PublishedSubgraph>,
+    /// The federation version the variant was last composed with, if it has been
+    pub federation_version: Option<FederationVersion>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PublishedSubgraph {
+    pub name: String,
+    pub routing_url: Option<String>,
+    pub sdl: String,
+}
@@ -0,0 +1,205 @@
+use std::time::{Duration, Instant};
+
+use super::types::*;
+use crate::blocking::StudioClient;
+use crate::RoverClientError;
+
+use graphql_client::*;
+
+use self::subgraph_launch_query::LaunchStatus;
+
+#[derive(GraphQLQuery)]
+// The paths are relative to the directory where your `Cargo.toml` is located.
+// Both json and the GraphQL schema language are supported as sources for the schema
+#[graphql(
+    query_path = "src/operations/subgraph/launch/launch_query.graphql",
+    schema_path = ".schema/schema.graphql",
+    response_derives = "Eq, PartialEq, Debug, Serialize, Deserialize",
+    deprecated = "warn"
+)]
+/// This struct is used to generate the module containing `Variables` and
+/// `ResponseData` structs.
+/// Snake case of this name is the mod name. i.e. subgraph_launch_query
+pub(crate) struct SubgraphLaunchQuery;
+
+/// Polls a launch until its supergraph has been delivered to Uplink, following any launches
+/// that supersede it. Fails if the launch fails, or doesn't finish within the timeout.
+pub fn run(
+    input: SubgraphLaunchInput,
+    client: &StudioClient,
+) -> Result<SubgraphLaunchResponse, RoverClientError> {
+    let mut input = input;
+    let now = Instant::now();
+    let mut failed_attempts = 0;
+    loop {
+        match client.post::<SubgraphLaunchQuery>(input.clone().into()) {
+            Ok(data) => match get_launch_state_from_response_data(&input, data)? {
+                LaunchState::Published => {
+                    return Ok(SubgraphLaunchResponse {
+                        launch_id: input.launch_id,
+                    })
+                }
+                LaunchState::Failed { errors } => {
+                    return Err(RoverClientError::LaunchFailed {
+                        graph_ref: input.graph_ref,
+                        launch_url: input.launch_url,
+                        errors,
+                    })
+                }
+                LaunchState::SupersededBy { launch_id } => {
+                    tracing::info!(
+                        "launch {} was superseded by launch {}",
+                        input.launch_id,
+                        launch_id
+                    );
+                    input.launch_id = launch_id;
+                    continue;
+                }
+                LaunchState::InProgress => {}
+            },
+            Err(e) if e.is_retryable() => {
+                failed_attempts += 1;
+                tracing::info!("launch status request failed {} time(s)", failed_attempts);
+                eprintln!("error while checking status of launch: {e}\nthis error may be transient... retrying");
+            }
+            Err(e) => return Err(e),
+        }
+        if now.elapsed() > Duration::from_secs(input.timeout_seconds) {
+            return Err(RoverClientError::LaunchTimeoutError {
+                url: input.launch_url,
+            });
+        }
+        std::thread::sleep(Duration::from_secs(5));
+    }
+}
+
+fn get_launch_state_from_response_data(
+    input: &SubgraphLaunchInput,
+    data: SubgraphLaunchResponseData,
+) -> Result<LaunchState, RoverClientError> {
+    let variant = match data.variant {
+        Some(SubgraphLaunchGraphVariant::GraphVariant(variant)) => variant,
+        Some(_) => return Err(RoverClientError::InvalidGraphRef),
+        None => {
+            return Err(RoverClientError::GraphNotFound {
+                graph_ref: input.graph_ref.clone(),
+            })
+        }
+    };
+    let launch = variant
+        .launch
+        .ok_or_else(|| RoverClientError::MalformedResponse {
+            null_field: "variant.launch".to_string(),
+        })?;
+
+    if let Some(superseding_launch) = launch.superseded_by {
+        return Ok(LaunchState::SupersededBy {
+            launch_id: superseding_launch.id,
+        });
+    }
+    Ok(match launch.status {
+        LaunchStatus::LAUNCH_COMPLETED if launch.is_published == Some(true) => {
+            LaunchState::Published
+        }
+        // a launch that completed without publishing had nothing to deliver, like a failed build
+        LaunchStatus::LAUNCH_COMPLETED | LaunchStatus::LAUNCH_FAILED => {
+            let errors = match launch.build.and_then(|build| build.result) {
+                Some(SubgraphLaunchBuildResult::BuildFailure(failure)) => failure
+                    .error_messages
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect(),
+                _ => Vec::new(),
+            };
+            LaunchState::Failed { errors }
+        }
+        _ => LaunchState::InProgress,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::GraphRef;
+    use serde_json::json;
+
+    #[test]
+    fn get_launch_state_from_response_data_works() {
+        let state = |launch| {
+            let data: SubgraphLaunchResponseData = serde_json::from_value(json!({
+                "variant": { "__typename": "GraphVariant", "launch": launch }
+            }))
+            .unwrap();
+            get_launch_state_from_response_data(&mock_input(), data).unwrap()
+        };
+
+        assert_eq!(
+            state(json!({
+                "id": "launch-1",
+                "status": "LAUNCH_INITIATED",
+                "isPublished": false,
+                "supersededBy": null,
+                "build": null
+            })),
+            LaunchState::InProgress
+        );
+        assert_eq!(
+            state(json!({
+                "id": "launch-1",
+                "status": "LAUNCH_COMPLETED",
+                "isPublished": true,
+                "supersededBy": null,
+                "build": { "result": { "__typename": "BuildSuccess" } }
+            })),
+            LaunchState::Published
+        );
+        assert_eq!(
+            state(json!({
+                "id": "launch-1",
+                "status": "LAUNCH_INITIATED",
+                "isPublished": false,
+                "supersededBy": { "id": "launch-2" },
+                "build": null
+            })),
+            LaunchState::SupersededBy {
+                launch_id: "launch-2".to_string()
+            }
+        );
+        assert_eq!(
+            state(json!({
+                "id": "launch-1",
+                "status": "LAUNCH_FAILED",
+                "isPublished": false,
+                "supersededBy": null,
+                "build": {
+                    "result": {
+                        "__typename": "BuildFailure",
+                        "errorMessages": [{ "message": "Field \"Query.products\" can only be defined once." }]
+                    }
+                }
+            })),
+            LaunchState::Failed {
+                errors: vec!["Field \"Query.products\" can only be defined once.".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn get_launch_state_from_response_data_errs_with_no_variant() {
+        let data: SubgraphLaunchResponseData =
+            serde_json::from_value(json!({ "variant": null })).unwrap();
+        assert!(get_launch_state_from_response_data(&mock_input(), data).is_err());
+    }
+
+    fn mock_input() -> SubgraphLaunchInput {
+        SubgraphLaunchInput {
+            graph_ref: GraphRef {
+                name: "mygraph".to_string(),
+                variant: "current".to_string(),
+            },
+            launch_id: "launch-1".to_string(),
+            launch_url: None,
+            timeout_seconds: 300,
+        }
+    }
+}
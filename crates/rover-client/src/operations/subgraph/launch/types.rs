@@ -0,0 +1,45 @@
+use crate::shared::GraphRef;
+
+use super::runner::subgraph_launch_query;
+
+use serde::Serialize;
+
+pub(crate) type SubgraphLaunchResponseData = subgraph_launch_query::ResponseData;
+pub(crate) type SubgraphLaunchGraphVariant = subgraph_launch_query::SubgraphLaunchQueryVariant;
+pub(crate) type SubgraphLaunchBuildResult =
+    subgraph_launch_query::SubgraphLaunchQueryVariantOnGraphVariantLaunchBuildResult;
+pub(crate) type QueryVariables = subgraph_launch_query::Variables;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubgraphLaunchInput {
+    pub graph_ref: GraphRef,
+    pub launch_id: String,
+    pub launch_url: Option<String>,
+    pub timeout_seconds: u64,
+}
+
+/// A launch that finished and delivered its supergraph to Uplink
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct SubgraphLaunchResponse {
+    /// The launch that delivered the supergraph. This is a later launch than the one the
+    /// publish started if that one was superseded.
+    pub launch_id: String,
+}
+
+/// Where a launch is at, as of the last time it was polled
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum LaunchState {
+    InProgress,
+    Published,
+    Failed { errors: Vec<String> },
+    SupersededBy { launch_id: String },
+}
+
+impl From<SubgraphLaunchInput> for QueryVariables {
+    fn from(input: SubgraphLaunchInput) -> Self {
+        Self {
+            graph_ref: input.graph_ref.to_string(),
+            launch_id: input.launch_id,
+        }
+    }
+}
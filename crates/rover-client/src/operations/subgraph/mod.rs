@@ -10,9 +10,15 @@ pub mod check;
 /// "subgraph fetch" command execution
 pub mod fetch;
 
+/// query for every subgraph's schema in a variant at once
+pub mod fetch_all;
+
 /// "subgraph publish" command execution
 pub mod publish;
 
+/// "subgraph publish --wait-for-launch" command execution
+pub mod launch;
+
 /// query for a single subgraph's routing URL
 pub mod routing_url;
 
@@ -31,7 +31,6 @@ pub fn run(
     client: &StudioClient,
 ) -> Result<SubgraphPublishResponse, RoverClientError> {
     let graph_ref = input.graph_ref.clone();
-    let variables: MutationVariables = input.clone().into();
     // We don't want to implicitly convert non-federated graph to supergraphs.
     // Error here if no --convert flag is passed _and_ the current context
     // is non-federated. Add a suggestion to require a --convert flag.
@@ -70,7 +69,8 @@ pub fn run(
             );
         }
     }
-    let data = client.post::<SubgraphPublishMutation>(variables)?;
+    let variables: MutationVariables = input.into();
+    let data = client.post_non_idempotent::<SubgraphPublishMutation>(variables)?;
     let publish_response = get_publish_response_from_data(data, graph_ref)?;
     Ok(build_response(publish_response))
 }
@@ -112,6 +112,7 @@ fn build_response(publish_response: UpdateResponse) -> SubgraphPublishResponse {
         build_errors,
         launch_cli_copy: publish_response.launch_cli_copy,
         launch_url: publish_response.launch_url,
+        launch_id: publish_response.launch.map(|launch| launch.id),
     }
 }
 
@@ -165,6 +166,7 @@ mod tests {
                 subgraph_was_updated: true,
                 launch_url: None,
                 launch_cli_copy: None,
+                launch_id: None,
             }
         );
     }
@@ -191,6 +193,7 @@ mod tests {
                 subgraph_was_updated: true,
                 launch_url: None,
                 launch_cli_copy: None,
+                launch_id: None,
             }
         );
     }
@@ -228,6 +231,7 @@ mod tests {
                 subgraph_was_updated: true,
                 launch_url: None,
                 launch_cli_copy: None,
+                launch_id: None,
             }
         );
     }
@@ -240,6 +244,7 @@ mod tests {
             "didUpdateGateway": true,
             "serviceWasCreated": true,
             "serviceWasUpdated": true,
+            "launch": { "id": "launch-1" },
             "launchUrl": "test.com/launchurl",
             "launchCliCopy": "You can monitor this launch in Apollo Studio: test.com/launchurl",
         });
@@ -258,6 +263,7 @@ mod tests {
                 launch_cli_copy: Some(
                     "You can monitor this launch in Apollo Studio: test.com/launchurl".to_string()
                 ),
+                launch_id: Some("launch-1".to_string()),
             }
         );
     }
@@ -284,6 +290,7 @@ mod tests {
                 subgraph_was_updated: false,
                 launch_url: None,
                 launch_cli_copy: None,
+                launch_id: None,
             }
         );
     }
@@ -40,6 +40,10 @@ pub struct SubgraphPublishResponse {
     pub launch_url: Option<String>,
 
     pub launch_cli_copy: Option<String>,
+
+    /// The launch the publish started, if the supergraph changed
+    #[serde(skip_serializing)]
+    pub launch_id: Option<String>,
 }
 
 impl From<SubgraphPublishInput> for MutationVariables {
@@ -61,15 +61,41 @@ impl Fs {
             File::create(path)
                 .with_context(|| format!("{} does not exist and it could not be created", &path))?;
         }
-        if !path.exists() {
-            File::create(path)
-                .with_context(|| format!("{} does not exist and it could not be created", &path))?;
-        }
         tracing::info!("writing {} to disk", &path);
         fs::write(path, contents).with_context(|| format!("could not write {}", &path))?;
         Ok(())
     }
 
+    /// writes a file to disk by writing a temporary file alongside it and renaming it into place,
+    /// so readers never see a partially written file. missing parent directories are created.
+    pub fn write_file_atomic<P, C>(path: P, contents: C) -> Result<(), RoverStdError>
+    where
+        P: AsRef<Utf8Path>,
+        C: AsRef<[u8]>,
+    {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("'{}' is not a file path", &path))?;
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_str().is_empty() => parent,
+            _ => Utf8Path::new("."),
+        };
+        if !parent.exists() {
+            Self::create_dir_all(parent)?;
+        }
+        let tmp_path = parent.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+        Self::write_file(&tmp_path, contents)?;
+        tracing::info!("moving {} to {}", &tmp_path, &path);
+        if let Err(e) = fs::rename(&tmp_path, path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(anyhow!(e)
+                .context(format!("could not move {} to {}", &tmp_path, &path))
+                .into());
+        }
+        Ok(())
+    }
+
     /// creates a directory
     pub fn create_dir_all<P>(path: P) -> Result<(), RoverStdError>
     where
@@ -201,6 +227,15 @@ impl Fs {
     ///   });
     /// });
     pub fn watch_file<P>(path: P, tx: Sender<()>)
+    where
+        P: AsRef<Utf8Path>,
+    {
+        Fs::watch_file_with_delay(path, tx, Duration::from_secs(1))
+    }
+
+    /// spawns a file watcher like [`Fs::watch_file`], but only sends an event once
+    /// the file has gone `delay` without changing, so a burst of writes is reported once
+    pub fn watch_file_with_delay<P>(path: P, tx: Sender<()>, delay: Duration)
     where
         P: AsRef<Utf8Path>,
     {
@@ -209,7 +244,7 @@ impl Fs {
             eprintln!("{}watching {} for changes", Emoji::Watch, &path);
 
             let (fs_tx, fs_rx) = channel();
-            let mut watcher = watcher(fs_tx, Duration::from_secs(1))
+            let mut watcher = watcher(fs_tx, delay)
                 .unwrap_or_else(|_| panic!("could not watch {} for changes", &path));
             watcher
                 .watch(&path, RecursiveMode::NonRecursive)
@@ -239,3 +274,29 @@ impl Fs {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+
+    use super::Fs;
+
+    #[test]
+    fn it_writes_files_atomically_into_new_directories() {
+        let dir = Utf8PathBuf::try_from(std::env::temp_dir())
+            .unwrap()
+            .join(format!("rover-std-fs-{}", std::process::id()));
+        let path = dir.join("nested").join("schema.graphql");
+
+        Fs::write_file_atomic(&path, "type Query { a: Int }").unwrap();
+        Fs::write_file_atomic(&path, "type Query { b: Int }").unwrap();
+        assert_eq!(Fs::read_file(&path).unwrap(), "type Query { b: Int }");
+        // only the file itself is left behind
+        assert_eq!(
+            Fs::get_dir_entries(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ mod fs;
 mod style;
 mod url;
 
+pub mod perf;
 pub mod prompt;
 pub use emoji::Emoji;
 pub use error::RoverStdError;
@@ -0,0 +1,169 @@
+//! Times the phases of a command for `--profile-perf`, so slowness can be attributed to Rover,
+//! the network, or the composition plugin. Timing is a no-op until [`enable`] is called.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A phase of a command that's timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading the API key from a profile or the environment
+    Credentials,
+    /// Finding, and possibly downloading, a versioned plugin
+    Plugins,
+    /// Requests to GraphOS, subgraphs, and introspection endpoints
+    Network,
+    /// Running the composition plugin
+    Composition,
+    /// Formatting and writing the command's output
+    Rendering,
+}
+
+impl Phase {
+    const ALL: [Phase; 5] = [
+        Phase::Credentials,
+        Phase::Plugins,
+        Phase::Network,
+        Phase::Composition,
+        Phase::Rendering,
+    ];
+
+    fn description(&self) -> &'static str {
+        match self {
+            Phase::Credentials => "credential resolution",
+            Phase::Plugins => "plugin resolution",
+            Phase::Network => "network calls",
+            Phase::Composition => "composition",
+            Phase::Rendering => "rendering",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PhaseTiming {
+    duration: Duration,
+    count: u32,
+}
+
+const UNTIMED: PhaseTiming = PhaseTiming {
+    duration: Duration::ZERO,
+    count: 0,
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+static TIMINGS: Mutex<[PhaseTiming; 5]> = Mutex::new([UNTIMED; 5]);
+
+/// Starts recording timings, measuring the total from now.
+pub fn enable() {
+    if let Ok(mut started) = STARTED.lock() {
+        started.get_or_insert_with(Instant::now);
+    }
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--profile-perf` was passed
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, adding the time it takes to `phase`.
+pub fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    let elapsed = started.elapsed();
+    if let Ok(mut timings) = TIMINGS.lock() {
+        let timing = &mut timings[phase as usize];
+        timing.duration += elapsed;
+        timing.count += 1;
+    }
+    result
+}
+
+/// The timings recorded so far, if profiling is enabled.
+pub fn report() -> Option<PerfReport> {
+    if !is_enabled() {
+        return None;
+    }
+    let total = STARTED.lock().ok()?.map(|started| started.elapsed())?;
+    let timings = *TIMINGS.lock().ok()?;
+    Some(PerfReport { total, timings })
+}
+
+/// A breakdown of where a command spent its time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfReport {
+    total: Duration,
+    timings: [PhaseTiming; 5],
+}
+
+impl PerfReport {
+    /// The time not spent in any phase, which is Rover's own work
+    fn other(&self) -> Duration {
+        let timed: Duration = self.timings.iter().map(|timing| timing.duration).sum();
+        self.total.saturating_sub(timed)
+    }
+}
+
+impl fmt::Display for PerfReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter<'_>, name: &str, duration: Duration, detail: String| {
+            let percent = if self.total.is_zero() {
+                0.0
+            } else {
+                duration.as_secs_f64() / self.total.as_secs_f64() * 100.0
+            };
+            writeln!(
+                f,
+                "  {:<22} {:>10.1?} {:>5.1}%{}",
+                name, duration, percent, detail
+            )
+        };
+        for (phase, timing) in Phase::ALL.iter().zip(self.timings.iter()) {
+            let detail = match timing.count {
+                0 => String::new(),
+                1 => "  (1 call)".to_string(),
+                count => format!("  ({} calls)", count),
+            };
+            row(f, phase.description(), timing.duration, detail)?;
+        }
+        row(f, "other (rover)", self.other(), String::new())?;
+        write!(f, "  {:<22} {:>10.1?}", "total", self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{PerfReport, PhaseTiming, UNTIMED};
+
+    #[test]
+    fn it_attributes_untimed_work_to_rover() {
+        let mut timings = [UNTIMED; 5];
+        timings[2] = PhaseTiming {
+            duration: Duration::from_millis(600),
+            count: 3,
+        };
+        timings[3] = PhaseTiming {
+            duration: Duration::from_millis(300),
+            count: 1,
+        };
+        let report = PerfReport {
+            total: Duration::from_secs(1),
+            timings,
+        };
+        assert_eq!(report.other(), Duration::from_millis(100));
+
+        let printed = report.to_string();
+        assert!(printed.contains("network calls"));
+        assert!(printed.contains("60.0%  (3 calls)"));
+        assert!(printed.contains("30.0%  (1 call)"));
+        assert!(printed.lines().last().unwrap().contains("total"));
+    }
+}
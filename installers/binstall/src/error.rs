@@ -34,6 +34,10 @@ pub enum InstallerError {
     #[error(transparent)]
     PathNotUtf8(#[from] camino::FromPathBufError),
 
+    /// A download ended before all of its bytes were received
+    #[error("The download was incomplete: expected {expected} bytes but received {actual}")]
+    IncompleteDownload { expected: u64, actual: u64 },
+
     #[error("This binary has already been placed in the installation destination.")]
     AlreadyInstalled,
 
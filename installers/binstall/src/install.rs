@@ -2,11 +2,17 @@ use crate::InstallerError;
 
 use rover_std::Fs;
 use std::env;
-use std::io::{self, IsTerminal, Write};
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::header::{ACCEPT, CONTENT_RANGE, RANGE, USER_AGENT};
+use reqwest::StatusCode;
 use url::Url;
 
+/// How many times to try (and resume) a plugin download before giving up
+const DOWNLOAD_ATTEMPTS: usize = 3;
+
 pub struct Installer {
     pub binary_name: String,
     pub force_install: bool,
@@ -48,7 +54,7 @@ impl Installer {
         client: &reqwest::blocking::Client,
         is_latest: bool,
     ) -> Result<Option<Utf8PathBuf>, InstallerError> {
-        let version = self.get_plugin_version(plugin_tarball_url, is_latest)?;
+        let version = self.get_plugin_version(plugin_tarball_url, is_latest, client)?;
 
         let bin_dir_path = self.get_bin_dir_path()?;
         if !bin_dir_path.exists() {
@@ -64,7 +70,7 @@ impl Installer {
         }
 
         let plugin_bin_path =
-            self.extract_plugin_tarball(plugin_name, plugin_tarball_url, client)?;
+            self.extract_plugin_tarball(plugin_name, &version, plugin_tarball_url, client)?;
         self.write_plugin_bin_to_fs(plugin_name, &plugin_bin_path, &version)?;
 
         eprintln!(
@@ -75,19 +81,54 @@ impl Installer {
         Ok(Some(plugin_bin_destination))
     }
 
+    /// Installs a plugin from a tarball on the local filesystem without making any network requests.
+    ///
+    /// `plugin_version` must be the exact version of the plugin in the tarball, prefixed with a `v`.
+    pub fn install_plugin_from_tarball(
+        &self,
+        plugin_name: &str,
+        plugin_version: &str,
+        plugin_tarball_path: &Utf8Path,
+    ) -> Result<Option<Utf8PathBuf>, InstallerError> {
+        let bin_dir_path = self.get_bin_dir_path()?;
+        if !bin_dir_path.exists() {
+            Fs::create_dir_all(bin_dir_path)?;
+        }
+
+        let plugin_bin_destination = self.get_plugin_bin_path(plugin_name, plugin_version)?;
+        if !self.force_install
+            && plugin_bin_destination.exists()
+            && !self.should_overwrite(&plugin_bin_destination, plugin_name)?
+        {
+            return Ok(None);
+        }
+
+        let unpack_dir = tempdir::TempDir::new(plugin_name)?;
+        let unpack_dir_path = Utf8PathBuf::try_from(unpack_dir.into_path())?;
+        let plugin_bin_path =
+            self.unpack_plugin_tarball(plugin_name, plugin_tarball_path, &unpack_dir_path)?;
+        self.write_plugin_bin_to_fs(plugin_name, &plugin_bin_path, plugin_version)?;
+
+        eprintln!(
+            "the '{}' plugin was successfully installed to {}",
+            &plugin_name, &plugin_bin_destination
+        );
+
+        Ok(Some(plugin_bin_destination))
+    }
+
+    /// Gets the version of the plugin at `plugin_tarball_url`.
+    ///
+    /// When `is_latest` is true, the version is read from the `X-Version` header of the
+    /// download host's response, so `client` must not follow redirects.
     pub fn get_plugin_version(
         &self,
         plugin_tarball_url: &str,
         is_latest: bool,
+        client: &reqwest::blocking::Client,
     ) -> Result<String, InstallerError> {
         if is_latest {
-            let no_redirect_client = reqwest::blocking::Client::builder()
-                .redirect(reqwest::redirect::Policy::none())
-                .build()?;
-            let response = no_redirect_client
-                .head(plugin_tarball_url)
-                .send()?
-                .error_for_status()?;
+            let response = client.head(plugin_tarball_url).send()?.error_for_status()?;
 
             if let Some(version) = response.headers().get("x-version") {
                 Ok(version
@@ -237,27 +278,61 @@ impl Installer {
     fn extract_plugin_tarball(
         &self,
         plugin_name: &str,
+        plugin_version: &str,
         plugin_tarball_url: &str,
         client: &reqwest::blocking::Client,
     ) -> Result<Utf8PathBuf, InstallerError> {
+        let tarball_path =
+            self.download_plugin_tarball(plugin_name, plugin_version, plugin_tarball_url, client)?;
         let download_dir = tempdir::TempDir::new(plugin_name)?;
         let download_dir_path = Utf8PathBuf::try_from(download_dir.into_path())?;
-        let tarball_path = download_dir_path.join(format!("{}.tar.gz", plugin_name));
-        let mut f = std::fs::File::create(&tarball_path)?;
-        let response_bytes = client
-            .get(plugin_tarball_url)
-            .header(reqwest::header::USER_AGENT, "rover-client")
-            .header(reqwest::header::ACCEPT, "application/octet-stream")
-            .send()?
-            .error_for_status()?
-            .bytes()?;
-        f.write_all(&response_bytes[..])?;
-        f.sync_all()?;
-        let f = std::fs::File::open(&tarball_path)?;
+        let plugin_bin_path =
+            self.unpack_plugin_tarball(plugin_name, &tarball_path, &download_dir_path)?;
+        let _ = std::fs::remove_file(&tarball_path);
+        Ok(plugin_bin_path)
+    }
+
+    /// Downloads a plugin tarball to `~/.rover/downloads`, resuming any earlier download
+    /// of the same plugin version that was interrupted.
+    fn download_plugin_tarball(
+        &self,
+        plugin_name: &str,
+        plugin_version: &str,
+        plugin_tarball_url: &str,
+        client: &reqwest::blocking::Client,
+    ) -> Result<Utf8PathBuf, InstallerError> {
+        let downloads_dir = self.get_base_dir_path()?.join("downloads");
+        Fs::create_dir_all(&downloads_dir)?;
+        let tarball_path =
+            downloads_dir.join(format!("{}-{}.tar.gz.part", plugin_name, plugin_version));
+
+        let mut attempt = 1;
+        loop {
+            match resume_download(plugin_tarball_url, &tarball_path, client) {
+                Ok(()) => return Ok(tarball_path),
+                Err(e) if attempt < DOWNLOAD_ATTEMPTS && is_resumable(&e) => {
+                    eprintln!(
+                        "WARN: the download of the '{}' plugin was interrupted, resuming: {}",
+                        plugin_name, e
+                    );
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn unpack_plugin_tarball(
+        &self,
+        plugin_name: &str,
+        plugin_tarball_path: &Utf8Path,
+        unpack_dir_path: &Utf8Path,
+    ) -> Result<Utf8PathBuf, InstallerError> {
+        let f = std::fs::File::open(plugin_tarball_path)?;
         let tar = flate2::read::GzDecoder::new(f);
         let mut archive = tar::Archive::new(tar);
-        archive.unpack(&download_dir_path)?;
-        let path = download_dir_path.join("dist").join(format!(
+        archive.unpack(unpack_dir_path)?;
+        let path = unpack_dir_path.join("dist").join(format!(
             "{}{}",
             plugin_name,
             std::env::consts::EXE_SUFFIX
@@ -278,3 +353,120 @@ impl Installer {
         crate::unix::add_binary_to_path(self)
     }
 }
+
+/// Downloads `url` to `path`, asking the server for only the remaining bytes
+/// if `path` already holds part of the download.
+fn resume_download(
+    url: &str,
+    path: &Utf8Path,
+    client: &reqwest::blocking::Client,
+) -> Result<(), InstallerError> {
+    let downloaded = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut request = client
+        .get(url)
+        .header(USER_AGENT, "rover-client")
+        .header(ACCEPT, "application/octet-stream");
+    if downloaded > 0 {
+        tracing::debug!("resuming download of {} from byte {}", url, downloaded);
+        request = request.header(RANGE, format!("bytes={}-", downloaded));
+    }
+    let response = request.send()?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // the partial download doesn't match what the server has, so start over
+        std::fs::remove_file(path)?;
+        return resume_download(url, path, client);
+    }
+    let mut response = response.error_for_status()?;
+
+    let content_range = response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("bytes "))
+        .and_then(|v| v.split_once('/'))
+        .map(|(range, total)| (range.to_string(), total.parse::<u64>().ok()));
+    let (mut file, expected_len) = match content_range {
+        Some((range, total))
+            if response.status() == StatusCode::PARTIAL_CONTENT
+                && range.starts_with(&format!("{}-", downloaded)) =>
+        {
+            (OpenOptions::new().append(true).open(path)?, total)
+        }
+        _ => (File::create(path)?, response.content_length()),
+    };
+
+    response.copy_to(&mut file)?;
+    file.sync_all()?;
+
+    if let Some(expected) = expected_len {
+        let actual = file.metadata()?.len();
+        if actual != expected {
+            return Err(InstallerError::IncompleteDownload { expected, actual });
+        }
+    }
+    Ok(())
+}
+
+/// Whether a failed download can be picked up where it left off
+fn is_resumable(error: &InstallerError) -> bool {
+    match error {
+        InstallerError::IncompleteDownload { .. } => true,
+        InstallerError::ReqwestError(e) => e.status().is_none(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use httpmock::{Method::GET, MockServer};
+
+    use super::resume_download;
+
+    #[test]
+    fn it_resumes_partial_downloads() {
+        let tmp_home = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(tmp_home.path().join("plugin.tar.gz.part")).unwrap();
+        std::fs::write(&path, "01234").unwrap();
+
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/tar").header("range", "bytes=5-");
+            then.status(206)
+                .header("content-range", "bytes 5-9/10")
+                .body("56789");
+        });
+
+        resume_download(
+            &server.url("/tar"),
+            &path,
+            &reqwest::blocking::Client::new(),
+        )
+        .unwrap();
+        mock.assert();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "0123456789");
+    }
+
+    #[test]
+    fn it_restarts_downloads_when_ranges_are_unsupported() {
+        let tmp_home = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(tmp_home.path().join("plugin.tar.gz.part")).unwrap();
+        std::fs::write(&path, "stale").unwrap();
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/tar");
+            then.status(200).body("0123456789");
+        });
+
+        resume_download(
+            &server.url("/tar"),
+            &path,
+            &reqwest::blocking::Client::new(),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "0123456789");
+    }
+}
@@ -1,14 +1,16 @@
 use camino::Utf8PathBuf;
-use clap::{Parser, ValueEnum};
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
 use lazycell::{AtomicLazyCell, LazyCell};
 use reqwest::blocking::Client;
 use serde::Serialize;
 
 use crate::command::{self, RoverOutput};
-use crate::options::OutputOpts;
+use crate::options::{EventOpts, OutputOpts};
 use crate::utils::{
-    client::{ClientBuilder, ClientTimeout, StudioClientConfig},
+    client::{self, ClientBuilder, ClientTimeout, InvalidCertScope, StudioClientConfig},
+    completion_cache::CompletionCache,
     env::{RoverEnv, RoverEnvKey},
+    profile_defaults,
     stringify::option_from_display,
     version,
 };
@@ -17,6 +19,8 @@ use crate::RoverResult;
 use config::Config;
 use houston as config;
 use rover_client::shared::GitContext;
+use rover_std::perf::{self, Phase};
+use rover_std::{Emoji, Style};
 use sputnik::Session;
 use timber::Level;
 
@@ -64,6 +68,9 @@ pub struct Rover {
     #[clap(flatten)]
     output_opts: OutputOpts,
 
+    #[clap(flatten)]
+    event_opts: EventOpts,
+
     /// Accept invalid certificates when performing HTTPS requests.
     ///
     /// You should think very carefully before using this flag.
@@ -71,8 +78,30 @@ pub struct Rover {
     /// If invalid certificates are trusted, any certificate for any site will be trusted for use.
     /// This includes expired certificates.
     /// This introduces significant vulnerabilities, and should only be used as a last resort.
-    #[arg(long = "insecure-accept-invalid-certs", global = true)]
-    accept_invalid_certs: bool,
+    ///
+    /// Pass `--insecure-accept-invalid-certs=endpoints` to only accept invalid certificates from
+    /// introspection and local development endpoints, and keep verifying requests to the Apollo registry.
+    ///
+    /// To trust the certificates of a TLS-intercepting proxy instead, pass `--ca-file`.
+    #[arg(
+        long = "insecure-accept-invalid-certs",
+        visible_alias = "insecure-skip-tls-verify",
+        global = true,
+        value_name = "SCOPE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "all"
+    )]
+    accept_invalid_certs: Option<InvalidCertScope>,
+
+    /// Trust the certificate authorities in this PEM file, along with the system's, for every
+    /// HTTPS request.
+    ///
+    /// Use this behind a proxy that intercepts TLS with its own certificate authority.
+    /// Defaults to the `APOLLO_CA_FILE` environment variable.
+    #[arg(long = "ca-file", global = true, value_name = "PATH")]
+    #[serde(skip_serializing)]
+    ca_file: Option<Utf8PathBuf>,
 
     /// Accept invalid hostnames when performing HTTPS requests.
     ///
@@ -84,17 +113,41 @@ pub struct Rover {
     accept_invalid_hostnames: bool,
 
     /// Configure the timeout length (in seconds) when performing HTTP(S) requests.
+    ///
+    /// Defaults to the timeout saved with `rover config client`, or 30 seconds.
+    #[arg(long = "client-timeout", global = true)]
+    client_timeout: Option<ClientTimeout>,
+
+    /// Retry failed HTTP(S) requests this many times, with exponential backoff between them.
+    ///
+    /// Defaults to the retries saved with `rover config client`. Without either, requests to the
+    /// Apollo registry are retried for a few seconds. Pass `0` to turn retries off.
+    #[arg(long = "client-retries", global = true, value_name = "RETRIES")]
+    client_retries: Option<u32>,
+
+    /// Start no more than this many requests to the Apollo registry each second.
+    ///
+    /// Use this to stay under the registry's rate limits with commands that make many requests,
+    /// like fetching every subgraph of a graph. Rate limited requests are retried either way.
     #[arg(
-        long = "client-timeout",
+        long = "max-requests-per-second",
         global = true,
-        default_value_t = ClientTimeout::default()
+        value_name = "REQUESTS",
+        value_parser = clap::value_parser!(u32).range(1..)
     )]
-    client_timeout: ClientTimeout,
+    max_requests_per_second: Option<u32>,
 
     /// Skip checking for newer versions of rover.
     #[arg(long = "skip-update-check", global = true)]
     skip_update_check: bool,
 
+    /// Time each phase of the command and print a breakdown when it exits.
+    ///
+    /// This shows whether time was spent resolving credentials or plugins, waiting on the network,
+    /// composing, or in Rover itself.
+    #[arg(long = "profile-perf", global = true)]
+    profile_perf: bool,
+
     #[arg(skip)]
     #[serde(skip_serializing)]
     env_store: LazyCell<RoverEnv>,
@@ -110,13 +163,41 @@ pub struct Rover {
 
 impl Rover {
     pub fn run_from_args() -> RoverResult<()> {
-        Rover::parse().run()
+        Rover::parse_with_profile_defaults().run()
+    }
+
+    /// Parses the command line like [`Parser::parse`], with the defaults of the profile that it
+    /// runs the command with.
+    fn parse_with_profile_defaults() -> Rover {
+        let args = std::env::args_os().collect::<Vec<_>>();
+        let mut command = profile_defaults::apply(Rover::command(), &profile_defaults::load(&args));
+        command
+            .try_get_matches_from_mut(&args)
+            .and_then(|matches| {
+                Rover::from_arg_matches(&matches).map_err(|e| e.format(&mut command))
+            })
+            .unwrap_or_else(|e| e.exit())
     }
 
     pub fn run(&self) -> RoverResult<()> {
         timber::init(self.log_level);
         tracing::trace!(command_structure = ?self);
         self.output_opts.validate_options();
+        if self.profile_perf {
+            perf::enable();
+        }
+        self.event_opts.enable()?;
+        if let Some(scope) = self.accept_invalid_certs {
+            let requests = match scope {
+                InvalidCertScope::All => "every request, including requests to the Apollo registry",
+                InvalidCertScope::Endpoints => "introspection and local development endpoints",
+            };
+            eprintln!(
+                "{} TLS certificates are not verified for {}. Requests can be intercepted.",
+                Style::WarningPrefix.paint("WARN:"),
+                requests
+            );
+        }
 
         // attempt to create a new `Session` to capture anonymous usage data
         let rover_output = match Session::new(self) {
@@ -152,14 +233,20 @@ impl Rover {
 
         match rover_output {
             Ok(output) => {
-                self.output_opts.handle_output(output)?;
+                if let Ok(config) = self.get_rover_config() {
+                    CompletionCache::record_output(&config, &output);
+                }
+                perf::time(Phase::Rendering, || self.output_opts.handle_output(output))?;
+                print_perf_report();
 
                 process::exit(0);
             }
             Err(error) => {
-                self.output_opts.handle_output(error)?;
+                let exit_code = error.exit_code();
+                perf::time(Phase::Rendering, || self.output_opts.handle_output(error))?;
+                print_perf_report();
 
-                process::exit(1);
+                process::exit(exit_code);
             }
         }
     }
@@ -185,9 +272,12 @@ impl Rover {
                 command.run(self.get_install_override_path()?, self.get_client_config()?)
             }
             Command::Fed2(command) => command.run(self.get_client_config()?),
-            Command::Supergraph(command) => {
-                command.run(self.get_install_override_path()?, self.get_client_config()?)
-            }
+            Command::Fleet(command) => command.run(),
+            Command::Supergraph(command) => command.run(
+                self.get_install_override_path()?,
+                self.get_client_config()?,
+                &self.output_opts,
+            ),
             Command::Docs(command) => command.run(),
             Command::Graph(command) => command.run(
                 self.get_client_config()?,
@@ -195,8 +285,11 @@ impl Rover {
                 self.get_checks_timeout_seconds()?,
                 &self.output_opts,
             ),
-            Command::Template(command) => command.run(self.get_client_config()?),
+            Command::Template(command) => {
+                command.run(self.get_install_override_path()?, self.get_client_config()?)
+            }
             Command::Readme(command) => command.run(self.get_client_config()?),
+            Command::Schema(command) => command.run(self.get_client_config()?),
             Command::Subgraph(command) => command.run(
                 self.get_client_config()?,
                 self.get_git_context()?,
@@ -213,6 +306,9 @@ impl Rover {
             Command::Explain(command) => command.run(),
             Command::PersistedQueries(command) => command.run(self.get_client_config()?),
             Command::License(command) => command.run(self.get_client_config()?),
+            Command::Org(command) => command.run(self.get_client_config()?),
+            Command::Ops(command) => command.run(),
+            Command::Completions(command) => command.run(self.get_rover_config()?),
         }
     }
 
@@ -238,7 +334,9 @@ impl Rover {
             config,
             is_sudo,
             self.get_reqwest_client_builder()?,
-        ))
+        )
+        .accept_invalid_endpoint_certs(self.accept_invalid_certs.is_some())
+        .max_requests_per_second(self.max_requests_per_second))
     }
 
     pub(crate) fn get_install_override_path(&self) -> RoverResult<Option<Utf8PathBuf>> {
@@ -278,13 +376,36 @@ impl Rover {
             Ok(*client_builder)
         } else {
             // if a request hasn't been made yet, this cell won't be populated yet
+            let ca_file = match &self.ca_file {
+                Some(ca_file) => Some(ca_file.clone()),
+                None => self
+                    .get_env_var(RoverEnvKey::CaFile)?
+                    .map(Utf8PathBuf::from),
+            };
+            if let Some(ca_file) = ca_file {
+                client::trust_ca_file(&ca_file)?;
+            }
+            let settings = self
+                .get_rover_config()?
+                .get_client_settings()
+                .unwrap_or_else(|e| {
+                    tracing::debug!("could not read the client settings: {}", e);
+                    config::ClientSettings::default()
+                });
+            let mut client_builder = ClientBuilder::new()
+                .accept_invalid_certs(self.accept_invalid_certs == Some(InvalidCertScope::All))
+                .accept_invalid_hostnames(self.accept_invalid_hostnames);
+            let timeout = self
+                .client_timeout
+                .or_else(|| settings.timeout.map(ClientTimeout::new));
+            if let Some(timeout) = timeout {
+                client_builder = client_builder.with_timeout(timeout.get_duration());
+            }
+            if let Some(retries) = self.client_retries.or(settings.retries) {
+                client_builder = client_builder.with_retries(retries);
+            }
             self.client_builder
-                .fill(
-                    ClientBuilder::new()
-                        .accept_invalid_certs(self.accept_invalid_certs)
-                        .accept_invalid_hostnames(self.accept_invalid_hostnames)
-                        .with_timeout(self.client_timeout.get_duration()),
-                )
+                .fill(client_builder)
                 .expect("Could not overwrite existing request client builder");
             self.get_reqwest_client_builder()
         }
@@ -327,6 +448,17 @@ impl Rover {
     }
 }
 
+/// Prints where the command spent its time, if `--profile-perf` was passed.
+fn print_perf_report() {
+    if let Some(report) = perf::report() {
+        eprintln!(
+            "{}",
+            Style::Heading.paint(format!("{}Performance profile:", Emoji::Hourglass))
+        );
+        eprintln!("{}", report);
+    }
+}
+
 #[derive(Debug, Serialize, Parser)]
 pub enum Command {
     /// Configuration profile commands
@@ -366,12 +498,15 @@ pub enum Command {
     /// or third subgraph) will decompose that subgraph from your supergraph.
     ///
     /// Think plug-n-play USB devices but with your GraphQL APIs!
-    Dev(command::Dev),
+    Dev(Box<command::Dev>),
 
     /// (deprecated) Federation 2 Alpha commands
     #[command(hide = true)]
     Fed2(command::Fed2),
 
+    /// Commands for running Rover across many graphs at once
+    Fleet(command::Fleet),
+
     /// Supergraph schema commands
     Supergraph(command::Supergraph),
 
@@ -384,6 +519,9 @@ pub enum Command {
     /// Readme commands
     Readme(command::Readme),
 
+    /// Commands for working with local schema files: normalize, diff, filter, and store them in OCI registries
+    Schema(command::Schema),
+
     /// Subgraph schema commands
     Subgraph(command::Subgraph),
 
@@ -410,6 +548,15 @@ pub enum Command {
 
     /// Commands for fetching offline licenses
     License(command::License),
+
+    /// Commands for exploring the graphs in your organizations
+    Org(command::Org),
+
+    /// Commands for analyzing sets of operations
+    Ops(command::Ops),
+
+    /// Generate shell completions for Rover
+    Completions(command::Completions),
 }
 
 #[derive(Default, ValueEnum, Debug, Serialize, Clone, Eq, PartialEq)]
@@ -0,0 +1,126 @@
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap_complete::Shell;
+use houston::Config;
+use serde::Serialize;
+
+use crate::cli::Rover;
+use crate::utils::completion_cache::CompletionCache;
+use crate::{RoverOutput, RoverResult};
+
+/// Suggests the graph refs and subgraph names that recent commands used. The generated scripts
+/// call `rover completions --cached` for these, so they only read Rover's config home.
+const BASH_CACHED: &str = r#"
+# suggests graph refs and subgraph names that recent rover commands used
+_rover_cached() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" cached
+    if [[ "$prev" == "--name" ]]; then
+        cached="$(rover completions --cached subgraph-names --graph-ref "${COMP_WORDS[3]}" 2>/dev/null)"
+    elif [[ $COMP_CWORD -eq 3 && "${COMP_WORDS[1]}" =~ ^(graph|subgraph|readme|contract)$ && "$cur" != -* ]]; then
+        cached="$(rover completions --cached graph-refs 2>/dev/null)"
+    fi
+    if [[ -n "$cached" ]]; then
+        COMPREPLY=( $(compgen -W "$cached" -- "$cur") )
+        return 0
+    fi
+    _rover "$@"
+}
+complete -F _rover_cached -o bashdefault -o default rover
+"#;
+
+const ZSH_CACHED: &str = r#"
+# suggests graph refs and subgraph names that recent rover commands used
+_rover_cached() {
+    local -a cached
+    if [[ "${words[CURRENT-1]}" == "--name" ]]; then
+        cached=(${(f)"$(rover completions --cached subgraph-names --graph-ref "${words[4]}" 2>/dev/null)"})
+    elif [[ $CURRENT -eq 4 && "${words[2]}" == (graph|subgraph|readme|contract) && "${words[CURRENT]}" != -* ]]; then
+        cached=(${(f)"$(rover completions --cached graph-refs 2>/dev/null)"})
+    fi
+    if (( ${#cached} )); then
+        compadd -a cached
+        return
+    fi
+    _rover "$@"
+}
+compdef _rover_cached rover
+"#;
+
+const FISH_CACHED: &str = r#"
+# suggests graph refs and subgraph names that recent rover commands used
+complete -c rover -n "__fish_seen_subcommand_from graph subgraph readme contract; and test (count (commandline -opc)) -eq 3" -f -a "(rover completions --cached graph-refs 2>/dev/null)"
+complete -c rover -l name -f -a "(rover completions --cached subgraph-names 2>/dev/null)"
+"#;
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Completions {
+    /// The shell to generate completions for
+    #[arg(value_enum, required_unless_present = "cached")]
+    #[serde(skip_serializing)]
+    shell: Option<Shell>,
+
+    /// Print the cached values that the completion scripts suggest, one per line
+    #[arg(long = "cached", value_enum, hide = true, conflicts_with = "shell")]
+    #[serde(skip_serializing)]
+    cached: Option<CachedValues>,
+
+    /// The graph ref to suggest subgraph names for
+    #[arg(long = "graph-ref", hide = true, requires = "cached")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CachedValues {
+    GraphRefs,
+    SubgraphNames,
+}
+
+impl Completions {
+    pub fn run(&self, config: Config) -> RoverResult<RoverOutput> {
+        if let Some(cached) = self.cached {
+            let cache = CompletionCache::load(&config);
+            let values = match cached {
+                CachedValues::GraphRefs => cache.graph_refs().to_vec(),
+                CachedValues::SubgraphNames => cache.subgraph_names(self.graph_ref.as_deref()),
+            };
+            return Ok(RoverOutput::Completions(values.join("\n")));
+        }
+
+        let shell = self
+            .shell
+            .expect("clap requires a shell unless --cached is passed");
+        Ok(RoverOutput::Completions(script(shell)))
+    }
+}
+
+/// The completion script for `shell`. Bash, zsh, and fish also suggest cached graph refs and
+/// subgraph names.
+fn script(shell: Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Rover::command(), "rover", &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+    script.push_str(match shell {
+        Shell::Bash => BASH_CACHED,
+        Shell::Zsh => ZSH_CACHED,
+        Shell::Fish => FISH_CACHED,
+        _ => "",
+    });
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+
+    use super::script;
+
+    #[test]
+    fn it_generates_completions_with_cached_values() {
+        let bash = script(Shell::Bash);
+        assert!(bash.contains("_rover()"));
+        assert!(bash.contains("complete -F _rover_cached"));
+        assert!(script(Shell::Fish).contains("--cached subgraph-names"));
+        // clap lists the hidden `--cached` flag itself, but no script that suggests cached values
+        assert!(!script(Shell::PowerShell).contains("--cached graph-refs"));
+    }
+}
@@ -0,0 +1,70 @@
+use clap::Parser;
+use rover_std::Style;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::utils::client::ClientTimeout;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Set the timeout and retries for every HTTP request Rover makes
+///
+/// These settings apply to every profile, and to requests to the Apollo registry, introspection,
+/// and `rover dev` subgraphs. `--client-timeout` and `--client-retries` override them for a
+/// single command. Run without options to print the current settings.
+pub struct Client {
+    /// How long to wait for a response, in seconds
+    #[arg(
+        long = "timeout",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    timeout: Option<u64>,
+
+    /// How many times to retry a failed request, with exponential backoff between them. `0`
+    /// turns retries off.
+    #[arg(long = "retries")]
+    retries: Option<u32>,
+
+    /// Go back to Rover's default timeout and retries
+    #[arg(long = "reset", conflicts_with_all = ["timeout", "retries"])]
+    reset: bool,
+}
+
+impl Client {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let mut settings = config.get_client_settings()?;
+        if self.reset {
+            settings = config::ClientSettings::default();
+        }
+        if let Some(timeout) = self.timeout {
+            settings.timeout = Some(timeout);
+        }
+        if let Some(retries) = self.retries {
+            settings.retries = Some(retries);
+        }
+        if self.reset || self.timeout.is_some() || self.retries.is_some() {
+            config.save_client_settings(&settings)?;
+        }
+
+        let timeout = settings
+            .timeout
+            .unwrap_or_else(|| ClientTimeout::default().get_duration().as_secs());
+        eprintln!(
+            "Requests time out after {} seconds.",
+            Style::Command.paint(timeout.to_string())
+        );
+        match settings.retries {
+            Some(0) => eprintln!("Failed requests aren't retried."),
+            Some(retries) => eprintln!(
+                "Failed requests are retried {} times.",
+                Style::Command.paint(retries.to_string())
+            ),
+            None => {
+                eprintln!("Failed requests to the Apollo registry are retried for a few seconds.")
+            }
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
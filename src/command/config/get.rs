@@ -0,0 +1,55 @@
+use clap::{Parser, ValueEnum};
+use rover_std::Style;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::command::config::settings::SettingKey;
+use crate::{options::ProfileOpt, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Print a default of a profile
+///
+/// Prints the value of KEY, or every default the profile has if KEY isn't passed.
+pub struct Get {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The default to print
+    #[arg(value_enum)]
+    #[serde(skip_serializing)]
+    key: Option<SettingKey>,
+}
+
+impl Get {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let profile_name = &self.profile.profile_name;
+        let settings = config::Profile::get_settings(profile_name, &config)?;
+        if let Some(key) = self.key {
+            return match key.get(&settings) {
+                Some(value) => Ok(RoverOutput::ProfileSetting {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                }),
+                None => {
+                    eprintln!("Profile \"{}\" has no default {}.", profile_name, key);
+                    Ok(RoverOutput::EmptySuccess)
+                }
+            };
+        }
+
+        let defaults = SettingKey::value_variants()
+            .iter()
+            .filter_map(|key| key.get(&settings).map(|value| (key, value)))
+            .collect::<Vec<_>>();
+        if defaults.is_empty() {
+            eprintln!("Profile \"{}\" doesn't have any defaults.", profile_name);
+            return Ok(RoverOutput::EmptySuccess);
+        }
+        eprintln!("Profile \"{}\" has these defaults:", profile_name);
+        for (key, value) in defaults {
+            eprintln!("  {}: {}", Style::Heading.paint(key.to_string()), value);
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
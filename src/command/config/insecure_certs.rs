@@ -0,0 +1,47 @@
+use clap::Parser;
+use rover_std::Style;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::{options::ProfileOpt, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Accept invalid TLS certificates from introspection and local development endpoints
+///
+/// Commands run with this profile stop verifying certificates for introspection and
+/// `rover dev` subgraphs, for example ones with self-signed certificates.
+/// Requests to the Apollo registry are still verified.
+///
+/// You should think very carefully before using this setting.
+/// Requests to those endpoints can be intercepted.
+pub struct InsecureCerts {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// Verify certificates from every endpoint again
+    #[arg(long = "disable")]
+    disable: bool,
+}
+
+impl InsecureCerts {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let mut settings = config::Profile::get_settings(&self.profile.profile_name, &config)?;
+        settings.insecure_accept_invalid_certs = !self.disable;
+        config::Profile::save_settings(&self.profile.profile_name, &config, &settings)?;
+        if self.disable {
+            eprintln!(
+                "Profile \"{}\" verifies TLS certificates from every endpoint.",
+                &self.profile.profile_name
+            );
+        } else {
+            eprintln!(
+                "{} Profile \"{}\" now accepts invalid TLS certificates from introspection and local development endpoints. Run this command with {} to undo this.",
+                Style::WarningPrefix.paint("WARN:"),
+                &self.profile.profile_name,
+                Style::Command.paint("--disable")
+            );
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
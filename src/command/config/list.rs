@@ -3,15 +3,124 @@ use serde::Serialize;
 
 use houston as config;
 
+use crate::utils::client::StudioClientConfig;
+use crate::utils::env::RoverEnvKey;
+use crate::utils::scope::KeyType;
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Serialize, Debug, Parser)]
 /// List all configuration profiles
-pub struct List {}
+pub struct List {
+    /// Show each profile's masked API key, key type, registry endpoint,
+    /// and whether an environment variable overrides it
+    #[arg(long, short = 'v')]
+    verbose: bool,
+}
+
+/// The details of a configuration profile shown by `rover config list --verbose`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ProfileDetails {
+    pub name: String,
+    pub api_key: Option<String>,
+    pub key_type: Option<String>,
+    pub registry_url: String,
+    pub registry_selected_by: String,
+    pub overridden_by: Option<String>,
+}
 
 impl List {
-    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
-        let profiles = config::Profile::list(&config)?;
-        Ok(RoverOutput::Profiles(profiles))
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let profiles = config::Profile::list(&client_config.config)?;
+        if !self.verbose {
+            return Ok(RoverOutput::Profiles(profiles));
+        }
+
+        // read each profile's own key, even when `APOLLO_KEY` overrides it
+        let profile_config = config::Config {
+            override_api_key: None,
+            ..client_config.config.clone()
+        };
+        let overridden_by = client_config
+            .config
+            .override_api_key
+            .as_ref()
+            .map(|_| RoverEnvKey::Key.to_string());
+
+        let details = profiles
+            .into_iter()
+            .map(|name| {
+                let api_key = config::Profile::get_credential(&name, &profile_config)
+                    .ok()
+                    .map(|credential| credential.api_key);
+                let (registry_url, registry_selected_by) =
+                    client_config.get_registry_endpoint(&name);
+                ProfileDetails {
+                    api_key: api_key.as_deref().map(config::mask_key),
+                    key_type: api_key
+                        .as_deref()
+                        .map(|api_key| KeyType::from_api_key(api_key).to_string()),
+                    registry_url,
+                    registry_selected_by,
+                    overridden_by: overridden_by.clone(),
+                    name,
+                }
+            })
+            .collect();
+        Ok(RoverOutput::ProfileDetails(details))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::{Config, Profile, ProfileSettings};
+
+    use super::List;
+    use crate::utils::client::{ClientBuilder, StudioClientConfig};
+    use crate::RoverOutput;
+
+    #[test]
+    fn it_lists_masked_keys_and_overrides() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_path = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let config = Config::new(
+            Some(&tmp_path),
+            Some("service:env-graph:from-the-environment".to_string()),
+        )
+        .unwrap();
+        Profile::set_api_key(
+            "default",
+            &config,
+            "service:my-graph:djru4788dhsg3657fhLOLO",
+        )
+        .unwrap();
+        let settings = ProfileSettings {
+            registry_url: Some("https://registry.example.com/graphql".to_string()),
+            ..Default::default()
+        };
+        Profile::save_settings("default", &config, &settings).unwrap();
+        Profile::set_api_key("work", &config, "user:gh.me:djru4788dhsg3657fhLOLO").unwrap();
+        let client_config = StudioClientConfig::new(None, config, false, ClientBuilder::default());
+
+        let output = List { verbose: true }.run(client_config).unwrap();
+        let details = match output {
+            RoverOutput::ProfileDetails(details) => details,
+            _ => panic!("expected profile details"),
+        };
+        assert_eq!(details.len(), 2);
+        let default = details.iter().find(|d| d.name == "default").unwrap();
+        assert_eq!(
+            default.api_key.as_deref(),
+            Some("serv*******************************LOLO")
+        );
+        assert_eq!(default.key_type.as_deref(), Some("graph"));
+        assert_eq!(default.overridden_by.as_deref(), Some("APOLLO_KEY"));
+        assert_eq!(default.registry_url, "https://registry.example.com/graphql");
+
+        // a profile without its own registry endpoint uses the global one
+        let work = details.iter().find(|d| d.name == "work").unwrap();
+        assert_eq!(work.key_type.as_deref(), Some("personal"));
+        assert_eq!(work.registry_url, "https://api.apollographql.com/graphql");
     }
 }
@@ -1,9 +1,18 @@
 mod auth;
 mod clear;
+mod client;
 mod delete;
+mod get;
+mod insecure_certs;
 mod list;
+mod request_metadata;
+mod set;
+mod settings;
+mod unset;
 mod whoami;
 
+pub(crate) use list::ProfileDetails;
+
 use clap::Parser;
 use serde::Serialize;
 
@@ -24,12 +33,30 @@ pub enum Command {
     /// Clear ALL configuration profiles
     Clear(clear::Clear),
 
+    /// Set the timeout and retries for every HTTP request Rover makes
+    Client(client::Client),
+
     /// Delete a configuration profile
     Delete(delete::Delete),
 
+    /// Print a default of a profile
+    Get(get::Get),
+
+    /// Accept invalid TLS certificates from introspection and local development endpoints for a profile
+    InsecureAcceptInvalidCerts(insecure_certs::InsecureCerts),
+
     /// List all configuration profiles
     List(list::List),
 
+    /// Attribute requests to the Apollo registry to an organization and CI system for a profile
+    RequestMetadata(request_metadata::RequestMetadata),
+
+    /// Set a default for the commands run with a profile
+    Set(set::Set),
+
+    /// Remove a default from a profile
+    Unset(unset::Unset),
+
     /// View the identity of a user/api key
     Whoami(whoami::WhoAmI),
 }
@@ -38,9 +65,15 @@ impl Config {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         match &self.command {
             Command::Auth(command) => command.run(client_config.config),
-            Command::List(command) => command.run(client_config.config),
+            Command::InsecureAcceptInvalidCerts(command) => command.run(client_config.config),
+            Command::List(command) => command.run(client_config),
+            Command::RequestMetadata(command) => command.run(client_config.config),
             Command::Delete(command) => command.run(client_config.config),
             Command::Clear(command) => command.run(client_config.config),
+            Command::Client(command) => command.run(client_config.config),
+            Command::Get(command) => command.run(client_config.config),
+            Command::Set(command) => command.run(client_config.config),
+            Command::Unset(command) => command.run(client_config.config),
             Command::Whoami(command) => command.run(client_config),
         }
     }
@@ -0,0 +1,129 @@
+use anyhow::anyhow;
+use clap::Parser;
+use rover_std::Style;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::utils::client::REQUEST_METADATA_HEADER_PREFIX;
+use crate::utils::parsers::parse_header;
+use crate::{options::ProfileOpt, RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Attribute requests to the Apollo registry to an organization and CI system
+///
+/// Commands run with this profile add the organization to Rover's `User-Agent`, and send each
+/// metadata entry as an `apollo-rover-metadata-<KEY>` header, so gateways and audit logs can tell
+/// which pipeline made a request. Run without options to print the profile's current metadata.
+pub struct RequestMetadata {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// An identifier for your organization to add to Rover's `User-Agent`
+    #[arg(long = "organization")]
+    #[serde(skip_serializing)]
+    organization: Option<String>,
+
+    /// Metadata to send with every request, as `KEY:VALUE`. Values that start with `$` are read
+    /// from that environment variable each time Rover runs, like `build-id:$GITHUB_RUN_ID`.
+    #[arg(long = "set", value_name = "KEY:VALUE", value_parser = parse_header)]
+    #[serde(skip_serializing)]
+    set: Vec<(String, String)>,
+
+    /// Stop sending the metadata with this key
+    #[arg(long = "unset", value_name = "KEY")]
+    #[serde(skip_serializing)]
+    unset: Vec<String>,
+
+    /// Remove the organization and all metadata from the profile
+    #[arg(long = "clear", conflicts_with_all = ["organization", "set", "unset"])]
+    clear: bool,
+}
+
+impl RequestMetadata {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let profile_name = &self.profile.profile_name;
+        let mut settings = config::Profile::get_settings(profile_name, &config)?;
+        let changed = self.clear
+            || self.organization.is_some()
+            || !self.set.is_empty()
+            || !self.unset.is_empty();
+        if self.clear {
+            settings.organization = None;
+            settings.request_metadata.clear();
+        }
+        if let Some(organization) = &self.organization {
+            settings.organization = Some(organization.trim().to_string());
+        }
+        for (key, value) in &self.set {
+            let key = metadata_key(key)?;
+            settings
+                .request_metadata
+                .insert(key, value.trim().to_string());
+        }
+        for key in &self.unset {
+            settings.request_metadata.remove(&key.trim().to_lowercase());
+        }
+        if changed {
+            config::Profile::save_settings(profile_name, &config, &settings)?;
+        }
+
+        if settings.organization.is_none() && settings.request_metadata.is_empty() {
+            eprintln!(
+                "Profile \"{}\" doesn't send any request metadata.",
+                profile_name
+            );
+            return Ok(RoverOutput::EmptySuccess);
+        }
+        eprintln!(
+            "Profile \"{}\" sends this metadata with requests to the Apollo registry:",
+            profile_name
+        );
+        if let Some(organization) = &settings.organization {
+            eprintln!(
+                "  {}: {}",
+                Style::Heading.paint("organization"),
+                organization
+            );
+        }
+        for (key, value) in &settings.request_metadata {
+            eprintln!(
+                "  {}: {}",
+                Style::Heading.paint(format!("{}{}", REQUEST_METADATA_HEADER_PREFIX, key)),
+                value
+            );
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
+
+/// Metadata keys become part of a header name, so they're limited to lowercase letters, digits,
+/// and dashes.
+fn metadata_key(key: &str) -> RoverResult<String> {
+    let key = key.trim().to_lowercase();
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Ok(key);
+    }
+    let mut err = RoverError::new(anyhow!("'{}' is not a valid metadata key", key));
+    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+        "Use only letters, digits, and dashes, like `build-id`.".to_string(),
+    ));
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::metadata_key;
+
+    #[test]
+    fn it_validates_metadata_keys() {
+        assert_eq!(metadata_key(" Build-ID ").unwrap(), "build-id");
+        assert!(metadata_key("build id").is_err());
+        assert!(metadata_key("build_id").is_err());
+        assert!(metadata_key("").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use clap::Parser;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::command::config::settings::SettingKey;
+use crate::{options::ProfileOpt, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Set a default for the commands run with a profile
+///
+/// Commands use a profile's defaults when a value isn't passed on the command line or set in an
+/// environment variable: `graph-ref` fills in a missing `GRAPH_REF` after `APOLLO_GRAPH_REF`,
+/// `format` replaces `--format` after `APOLLO_ROVER_FORMAT`, `federation-version` applies when a
+/// supergraph config doesn't set one, and `registry-url` applies unless `APOLLO_REGISTRY_URL`
+/// is set.
+pub struct Set {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The default to set
+    #[arg(value_enum)]
+    #[serde(skip_serializing)]
+    key: SettingKey,
+
+    /// The value to set it to
+    #[serde(skip_serializing)]
+    value: String,
+}
+
+impl Set {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let profile_name = &self.profile.profile_name;
+        let mut settings = config::Profile::get_settings(profile_name, &config)?;
+        self.key.set(&mut settings, &self.value)?;
+        config::Profile::save_settings(profile_name, &config, &settings)?;
+        eprintln!(
+            "Set {} to \"{}\" for profile \"{}\".",
+            self.key,
+            self.key.get(&settings).unwrap_or_default(),
+            profile_name
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use rover_client::shared::GraphRef;
+use semver::Version;
+use url::Url;
+
+use houston::ProfileSettings;
+
+use crate::cli::RoverOutputFormatKind;
+use crate::command::install::PluginSpec;
+use crate::utils::protected_variant::ProtectedVariants;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// A default that a profile stores for the commands run with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SettingKey {
+    /// The graph ref to use when a command's `GRAPH_REF` isn't passed
+    GraphRef,
+
+    /// The output format to use when `--format` isn't passed
+    Format,
+
+    /// The federation version to compose with when a supergraph config doesn't set one
+    FederationVersion,
+
+    /// The Apollo registry endpoint to send requests to
+    RegistryUrl,
+
+    /// The graph refs or variants that publishes ask for confirmation before changing
+    ProtectedVariants,
+}
+
+impl SettingKey {
+    fn value_mut(self, settings: &mut ProfileSettings) -> &mut Option<String> {
+        match self {
+            SettingKey::GraphRef => &mut settings.graph_ref,
+            SettingKey::Format => &mut settings.format,
+            SettingKey::FederationVersion => &mut settings.federation_version,
+            SettingKey::RegistryUrl => &mut settings.registry_url,
+            SettingKey::ProtectedVariants => &mut settings.protected_variants,
+        }
+    }
+
+    pub(crate) fn get(self, settings: &ProfileSettings) -> Option<&str> {
+        match self {
+            SettingKey::GraphRef => settings.graph_ref.as_deref(),
+            SettingKey::Format => settings.format.as_deref(),
+            SettingKey::FederationVersion => settings.federation_version.as_deref(),
+            SettingKey::RegistryUrl => settings.registry_url.as_deref(),
+            SettingKey::ProtectedVariants => settings.protected_variants.as_deref(),
+        }
+    }
+
+    /// Validates `value` and saves it in `settings`, in the form commands read it.
+    pub(crate) fn set(self, settings: &mut ProfileSettings, value: &str) -> RoverResult<()> {
+        *self.value_mut(settings) = Some(self.parse(value)?);
+        Ok(())
+    }
+
+    pub(crate) fn unset(self, settings: &mut ProfileSettings) {
+        *self.value_mut(settings) = None;
+    }
+
+    fn parse(self, value: &str) -> RoverResult<String> {
+        let value = value.trim();
+        let (valid, example) = match self {
+            SettingKey::GraphRef => (
+                GraphRef::from_str(value)
+                    .map(|graph_ref| graph_ref.to_string())
+                    .map_err(|e| anyhow!(e)),
+                "my-graph@prod",
+            ),
+            SettingKey::Format => (
+                RoverOutputFormatKind::from_str(value, true)
+                    .ok()
+                    .and_then(|format| format.to_possible_value())
+                    .map(|format| format.get_name().to_string())
+                    .ok_or_else(|| anyhow!("expected `plain` or `json`")),
+                "json",
+            ),
+            SettingKey::FederationVersion => {
+                let valid = Version::parse(value.strip_prefix('v').unwrap_or(value))
+                    .map(|_| ())
+                    .or_else(|_| {
+                        format!("supergraph@{}", value)
+                            .parse::<PluginSpec>()
+                            .map(|_| ())
+                    });
+                (valid.map(|_| value.to_string()), "=2.7.1")
+            }
+            SettingKey::RegistryUrl => (
+                Url::parse(value)
+                    .map(|_| value.to_string())
+                    .map_err(|e| anyhow!(e)),
+                "https://api.apollographql.com/graphql",
+            ),
+            SettingKey::ProtectedVariants => {
+                let patterns = ProtectedVariants::parse_patterns(value);
+                let valid = if patterns.is_empty() {
+                    Err(anyhow!("expected at least one pattern"))
+                } else {
+                    Ok(patterns.join(","))
+                };
+                (valid, "*@prod,my-graph@staging")
+            }
+        };
+        valid.map_err(|e| {
+            let mut err = RoverError::new(anyhow!("'{}' is not a valid {}: {}", value, self, e));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Set a value like `{}`.",
+                example
+            )));
+            err
+        })
+    }
+}
+
+impl fmt::Display for SettingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self
+            .to_possible_value()
+            .expect("setting keys are never skipped");
+        write!(f, "{}", name.get_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use houston::ProfileSettings;
+
+    use super::SettingKey;
+
+    #[test]
+    fn it_validates_settings() {
+        let mut settings = ProfileSettings::default();
+        SettingKey::GraphRef.set(&mut settings, "my-graph").unwrap();
+        SettingKey::Format.set(&mut settings, "JSON").unwrap();
+        SettingKey::FederationVersion
+            .set(&mut settings, "=2.7.1")
+            .unwrap();
+        assert_eq!(settings.graph_ref.as_deref(), Some("my-graph@current"));
+        assert_eq!(settings.format.as_deref(), Some("json"));
+        assert_eq!(SettingKey::FederationVersion.get(&settings), Some("=2.7.1"));
+
+        assert!(SettingKey::GraphRef.set(&mut settings, "my graph").is_err());
+        assert!(SettingKey::Format.set(&mut settings, "yaml").is_err());
+        assert!(SettingKey::RegistryUrl
+            .set(&mut settings, "not a url")
+            .is_err());
+        assert_eq!(SettingKey::RegistryUrl.get(&settings), None);
+
+        SettingKey::ProtectedVariants
+            .set(&mut settings, " *@prod, release-* ,")
+            .unwrap();
+        assert_eq!(
+            settings.protected_variants.as_deref(),
+            Some("*@prod,release-*")
+        );
+        assert!(SettingKey::ProtectedVariants
+            .set(&mut settings, " , ")
+            .is_err());
+
+        SettingKey::GraphRef.unset(&mut settings);
+        assert_eq!(settings.graph_ref, None);
+        assert_eq!(
+            SettingKey::FederationVersion.to_string(),
+            "federation-version"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use clap::Parser;
+use serde::Serialize;
+
+use houston as config;
+
+use crate::command::config::settings::SettingKey;
+use crate::{options::ProfileOpt, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Remove a default from a profile
+pub struct Unset {
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The default to remove
+    #[arg(value_enum)]
+    #[serde(skip_serializing)]
+    key: SettingKey,
+}
+
+impl Unset {
+    pub fn run(&self, config: config::Config) -> RoverResult<RoverOutput> {
+        let profile_name = &self.profile.profile_name;
+        let mut settings = config::Profile::get_settings(profile_name, &config)?;
+        self.key.unset(&mut settings);
+        config::Profile::save_settings(profile_name, &config, &settings)?;
+        eprintln!(
+            "Profile \"{}\" no longer has a default {}.",
+            profile_name, self.key
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
@@ -52,6 +52,7 @@ impl WhoAmI {
             key_type: identity.key_actor_type.to_string(),
             origin: self.get_origin(&client),
             user_id: self.get_user_id(&identity),
+            graph_memberships: identity.graph_memberships,
         })
     }
 
@@ -115,6 +116,7 @@ mod tests {
             key_actor_type: actor_type,
             graph_title: Some("graph_title".to_string()),
             credential_origin: CredentialOrigin::EnvVar,
+            graph_memberships: vec![],
         }
     }
 
@@ -3,6 +3,7 @@ use serde::Serialize;
 
 use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 use rover_client::operations::contract::publish::{self, ContractPublishInput};
@@ -71,6 +72,11 @@ pub struct Publish {
 impl Publish {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::ManageVariants,
+        )?;
         eprintln!(
             "Publishing configuration to {} using credentials from the {} profile.\n",
             Style::Link.paint(self.graph.graph_ref.to_string()),
@@ -0,0 +1,425 @@
+//! Records every operation that passes through a `rover dev` session to a JSON Lines file, for
+//! analyzing query plans and performance locally.
+//!
+//! The main `rover dev` process serves a proxy on the router's address and runs the router on
+//! another port behind it. The proxy tags each operation with an ID that the router propagates
+//! to subgraphs, and a proxy in front of each subgraph records the requests made with that ID.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::Context;
+use camino::Utf8Path;
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::command::dev::proxy::{self, ProxiedRequest, ProxiedResponse};
+use crate::RoverResult;
+
+/// The header that ties the requests the router makes to subgraphs to the operation they're for.
+pub(crate) const OPERATION_ID_HEADER: &str = "x-rover-dev-operation-id";
+
+/// One line of the access log.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OperationLogEntry {
+    pub(crate) timestamp: String,
+    pub(crate) operation_name: Option<String>,
+    /// The SHA-256 of the operation's document
+    pub(crate) operation_hash: Option<String>,
+    /// The operation's variables, with each value replaced by the name of its JSON type
+    pub(crate) variables: Value,
+    pub(crate) subgraph_requests: Vec<SubgraphRequest>,
+    pub(crate) duration_ms: f64,
+    pub(crate) status: u16,
+    pub(crate) errors: Vec<String>,
+}
+
+/// A request the router made to a subgraph while executing an operation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct SubgraphRequest {
+    pub(crate) subgraph: String,
+    pub(crate) duration_ms: f64,
+    pub(crate) status: u16,
+}
+
+/// The access log of a `rover dev` session, shared between its proxies.
+#[derive(Debug, Clone)]
+pub(crate) struct AccessLog {
+    inner: Arc<AccessLogInner>,
+}
+
+#[derive(Debug)]
+struct AccessLogInner {
+    file: Mutex<File>,
+    client: Client,
+    next_operation_id: AtomicU64,
+    /// The subgraph requests made so far for each operation that's in flight
+    subgraph_requests: Mutex<HashMap<String, Vec<SubgraphRequest>>>,
+}
+
+impl AccessLog {
+    /// Opens `path` for appending, creating it if it doesn't exist.
+    pub(crate) fn create(path: &Utf8Path) -> RoverResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("could not open the access log at '{}'", path))?;
+        Ok(Self {
+            inner: Arc::new(AccessLogInner {
+                file: Mutex::new(file),
+                client: Client::new(),
+                next_operation_id: AtomicU64::new(0),
+                subgraph_requests: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// Serves the proxy in front of the router on `public_addr`, forwarding to the router at `router_addr`.
+    pub(crate) fn serve(
+        &self,
+        public_addr: SocketAddr,
+        router_addr: SocketAddr,
+    ) -> RoverResult<()> {
+        let listener = TcpListener::bind(public_addr).with_context(|| {
+            format!("could not start the access log proxy on '{}'", public_addr)
+        })?;
+        let router_url = Url::parse(&format!("http://{}", router_addr))?;
+        tracing::info!(
+            "logging operations sent to {} before forwarding them to the router at {}",
+            public_addr,
+            router_addr
+        );
+        let access_log = self.clone();
+        proxy::serve(listener, "to the router".to_string(), move |stream| {
+            access_log.handle_operation(stream, &router_url)
+        });
+        Ok(())
+    }
+
+    /// Starts a proxy in front of a subgraph that records requests made for logged operations,
+    /// returning the URL the router should send the subgraph's requests to instead.
+    pub(crate) fn start_subgraph_proxy(
+        &self,
+        subgraph_name: &str,
+        target: &Url,
+    ) -> RoverResult<Url> {
+        let listener = TcpListener::bind("127.0.0.1:0").with_context(|| {
+            format!(
+                "could not start the access log proxy for '{}'",
+                subgraph_name
+            )
+        })?;
+        let mut proxy_url = Url::parse(&format!("http://{}", listener.local_addr()?))?;
+        proxy_url.set_path(target.path());
+        let access_log = self.clone();
+        let subgraph_name = subgraph_name.to_string();
+        let target = target.clone();
+        proxy::serve(
+            listener,
+            format!("to subgraph '{}'", subgraph_name),
+            move |stream| access_log.handle_subgraph_request(stream, &subgraph_name, &target),
+        );
+        Ok(proxy_url)
+    }
+
+    fn handle_operation(&self, stream: &TcpStream, router_url: &Url) -> anyhow::Result<()> {
+        let request = ProxiedRequest::read(stream)?;
+        let operation_id = self
+            .inner
+            .next_operation_id
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string();
+        let started = Instant::now();
+        let response = request
+            .forward(
+                &self.inner.client,
+                router_url,
+                &[(OPERATION_ID_HEADER, &operation_id)],
+            )
+            .unwrap_or_else(|e| {
+                ProxiedResponse::graphql_error(
+                    "502 Bad Gateway",
+                    format!("could not reach the router: {}", e),
+                )
+            });
+        let duration = started.elapsed();
+        response.write(stream)?;
+
+        let subgraph_requests = self
+            .lock_subgraph_requests()
+            .remove(&operation_id)
+            .unwrap_or_default();
+        let entry = OperationLogEntry::new(
+            &request,
+            &response,
+            subgraph_requests,
+            duration.as_secs_f64() * 1000.0,
+        );
+        // requests without an operation, like CORS preflights, aren't worth logging
+        if entry.operation_hash.is_some() {
+            self.write(&entry)?;
+        }
+        Ok(())
+    }
+
+    fn handle_subgraph_request(
+        &self,
+        stream: &TcpStream,
+        subgraph_name: &str,
+        target: &Url,
+    ) -> anyhow::Result<()> {
+        let request = ProxiedRequest::read(stream)?;
+        let started = Instant::now();
+        let response = request.forward(&self.inner.client, target, &[]);
+        let duration = started.elapsed();
+        if let Some(operation_id) = request.header(OPERATION_ID_HEADER) {
+            self.lock_subgraph_requests()
+                .entry(operation_id.to_string())
+                .or_default()
+                .push(SubgraphRequest {
+                    subgraph: subgraph_name.to_string(),
+                    duration_ms: duration.as_secs_f64() * 1000.0,
+                    status: response
+                        .as_ref()
+                        .map_or(502, |response| response.status_code),
+                });
+        }
+        response
+            .unwrap_or_else(|e| {
+                ProxiedResponse::graphql_error(
+                    "502 Bad Gateway",
+                    format!("could not reach subgraph '{}': {}", subgraph_name, e),
+                )
+            })
+            .write(stream)
+    }
+
+    fn write(&self, entry: &OperationLogEntry) -> anyhow::Result<()> {
+        let line = serde_json::to_string(entry)?;
+        let mut file = self
+            .inner
+            .file
+            .lock()
+            .expect("could not acquire lock on the access log");
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    fn lock_subgraph_requests(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, Vec<SubgraphRequest>>> {
+        self.inner
+            .subgraph_requests
+            .lock()
+            .expect("could not acquire lock on the access log's subgraph requests")
+    }
+}
+
+impl OperationLogEntry {
+    fn new(
+        request: &ProxiedRequest,
+        response: &ProxiedResponse,
+        subgraph_requests: Vec<SubgraphRequest>,
+        duration_ms: f64,
+    ) -> Self {
+        let operation = parse_operation(request);
+        let errors = serde_json::from_slice::<Value>(&response.body)
+            .ok()
+            .and_then(|body| body["errors"].as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .map(|error| {
+                error["message"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string())
+            })
+            .collect();
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            operation_name: operation["operationName"].as_str().map(str::to_string),
+            operation_hash: operation["query"]
+                .as_str()
+                .map(|query| format!("{:x}", Sha256::digest(query.as_bytes()))),
+            variables: variables_shape(&operation["variables"]),
+            subgraph_requests,
+            duration_ms,
+            status: response.status_code,
+            errors,
+        }
+    }
+}
+
+/// Reads the operation from a POST body or a GET query string.
+fn parse_operation(request: &ProxiedRequest) -> Value {
+    if request.method.eq_ignore_ascii_case("GET") {
+        let query = request.path.split_once('?').map_or("", |(_, query)| query);
+        let mut operation = Map::new();
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            let value = match key.as_ref() {
+                "variables" => serde_json::from_str(&value).unwrap_or(Value::Null),
+                _ => Value::String(value.to_string()),
+            };
+            operation.insert(key.to_string(), value);
+        }
+        Value::Object(operation)
+    } else {
+        serde_json::from_slice(&request.body).unwrap_or(Value::Null)
+    }
+}
+
+/// Replaces each value in `variables` with the name of its JSON type, so the log shows the shape
+/// of the variables without their (possibly sensitive) values.
+fn variables_shape(variables: &Value) -> Value {
+    match variables {
+        Value::Null => Value::Null,
+        Value::Bool(_) => Value::String("boolean".to_string()),
+        Value::Number(_) => Value::String("number".to_string()),
+        Value::String(_) => Value::String("string".to_string()),
+        Value::Array(values) => Value::Array(values.iter().map(variables_shape).collect()),
+        Value::Object(values) => Value::Object(
+            values
+                .iter()
+                .map(|(key, value)| (key.clone(), variables_shape(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use super::{variables_shape, AccessLog, OPERATION_ID_HEADER};
+
+    #[test]
+    fn it_keeps_the_shape_of_variables() {
+        assert_eq!(
+            variables_shape(&json!({
+                "id": "1",
+                "first": 10,
+                "filter": { "inStock": true, "tags": ["a", "b"], "after": null }
+            })),
+            json!({
+                "id": "string",
+                "first": "number",
+                "filter": { "inStock": "boolean", "tags": ["string", "string"], "after": null }
+            })
+        );
+    }
+
+    /// Serves a single request, checking that it carries an operation ID if `expect_operation_id` is set.
+    fn fake_server(expect_operation_id: bool, body: &'static str) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_lowercase();
+            assert_eq!(request.contains(OPERATION_ID_HEADER), expect_operation_id);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn it_logs_operations_and_their_subgraph_requests() {
+        let dir = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(dir.path().join("ops.jsonl")).unwrap();
+        let access_log = AccessLog::create(&path).unwrap();
+
+        let subgraph_url = fake_server(true, r#"{"data":{"products":[]}}"#);
+        let subgraph_proxy = access_log
+            .start_subgraph_proxy("products", &subgraph_url)
+            .unwrap();
+        // stands in for the router, calling the subgraph with the propagated operation ID
+        let router = TcpListener::bind("127.0.0.1:0").unwrap();
+        let router_addr = router.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = router.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).to_string();
+            let operation_id = request
+                .lines()
+                .find_map(|line| {
+                    line.to_lowercase()
+                        .strip_prefix(&format!("{}: ", OPERATION_ID_HEADER))
+                        .map(str::to_string)
+                })
+                .unwrap();
+            reqwest::blocking::Client::new()
+                .post(subgraph_proxy)
+                .header(OPERATION_ID_HEADER, operation_id)
+                .body("{}")
+                .send()
+                .unwrap();
+            let body = r#"{"data":null,"errors":[{"message":"boom"}]}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        let public = TcpListener::bind("127.0.0.1:0").unwrap();
+        let public_addr = public.local_addr().unwrap();
+        drop(public);
+        access_log.serve(public_addr, router_addr).unwrap();
+
+        let body = r#"{"query":"query Products { products { id } }","operationName":"Products","variables":{"first":1}}"#;
+        let mut stream = TcpStream::connect(public_addr).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nhost: localhost\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.ends_with(r#"{"data":null,"errors":[{"message":"boom"}]}"#));
+
+        // the entry is written after the response is sent
+        let mut entry = None;
+        for _ in 0..50 {
+            if let Some(line) = std::fs::read_to_string(&path).unwrap().lines().next() {
+                entry = Some(serde_json::from_str::<Value>(line).unwrap());
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let entry = entry.expect("no operation was logged");
+        assert_eq!(entry["operation_name"], json!("Products"));
+        assert_eq!(entry["variables"], json!({ "first": "number" }));
+        assert_eq!(entry["status"], json!(200));
+        assert_eq!(entry["errors"], json!(["boom"]));
+        assert_eq!(entry["subgraph_requests"][0]["subgraph"], json!("products"));
+        assert_eq!(entry["subgraph_requests"][0]["status"], json!(200));
+        assert_eq!(entry["operation_hash"].as_str().unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,263 @@
+//! Injects latency and errors between the router and a subgraph, so timeouts and partial data
+//! can be tried out locally. Subgraphs opt in with a `chaos` section in the supergraph config:
+//!
+//! ```yaml
+//! subgraphs:
+//!   products:
+//!     routing_url: http://localhost:4001
+//!     schema:
+//!       file: ./products.graphql
+//!     chaos:
+//!       latency: 200ms
+//!       error_rate: 0.1
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Deserializer};
+use url::Url;
+
+use crate::command::dev::proxy::{self, ProxiedRequest, ProxiedResponse};
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// The faults to inject into requests to a subgraph.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ChaosConfig {
+    /// How long to delay each request, like `200ms` or `1s`
+    #[serde(default, deserialize_with = "deserialize_latency")]
+    pub(crate) latency: Option<Duration>,
+    /// The fraction of requests, from 0 to 1, to fail with a `503 Service Unavailable`
+    #[serde(default)]
+    pub(crate) error_rate: f64,
+}
+
+fn deserialize_latency<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|latency| humantime::parse_duration(&latency).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Debug, Deserialize)]
+struct ChaosSupergraphConfig {
+    #[serde(default)]
+    subgraphs: BTreeMap<String, ChaosSubgraphConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChaosSubgraphConfig {
+    #[serde(default)]
+    chaos: Option<ChaosConfig>,
+}
+
+impl ChaosConfig {
+    /// Reads the `chaos` section of each subgraph in a supergraph config.
+    pub(crate) fn from_supergraph_yaml(yaml: &str) -> RoverResult<BTreeMap<String, Self>> {
+        let config: ChaosSupergraphConfig = serde_yaml::from_str(yaml)
+            .context("could not read the `chaos` sections of the supergraph config")?;
+        config
+            .subgraphs
+            .into_iter()
+            .filter_map(|(name, subgraph)| subgraph.chaos.map(|chaos| (name, chaos)))
+            .map(|(name, chaos)| {
+                if !(0.0..=1.0).contains(&chaos.error_rate) {
+                    let mut err = RoverError::new(anyhow!(
+                        "the `error_rate` for subgraph '{}' must be between 0 and 1, but it is {}",
+                        name,
+                        chaos.error_rate
+                    ));
+                    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                        "Set `error_rate: 0.1` to fail one in ten requests.".to_string(),
+                    ));
+                    return Err(err);
+                }
+                Ok((name, chaos))
+            })
+            .collect()
+    }
+
+    /// Starts a proxy in the background that forwards requests to `target` with these faults,
+    /// returning the URL the router should send the subgraph's requests to instead.
+    pub(crate) fn start_proxy(&self, subgraph_name: &str, target: &Url) -> RoverResult<Url> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .with_context(|| format!("could not start the chaos proxy for '{}'", subgraph_name))?;
+        let mut proxy_url = Url::parse(&format!("http://{}", listener.local_addr()?))?;
+        proxy_url.set_path(target.path());
+        tracing::info!(
+            "proxying requests for subgraph '{}' from {} to {}",
+            subgraph_name,
+            proxy_url,
+            target
+        );
+
+        let proxy = ChaosProxy {
+            subgraph_name: subgraph_name.to_string(),
+            target: target.clone(),
+            config: self.clone(),
+            client: Client::new(),
+            request_count: AtomicU64::new(0),
+            random_state: RandomState::new(),
+        };
+        proxy::serve(
+            listener,
+            format!("to subgraph '{}'", subgraph_name),
+            move |stream| proxy.handle(stream),
+        );
+        Ok(proxy_url)
+    }
+}
+
+struct ChaosProxy {
+    subgraph_name: String,
+    target: Url,
+    config: ChaosConfig,
+    client: Client,
+    request_count: AtomicU64,
+    random_state: RandomState,
+}
+
+impl ChaosProxy {
+    fn handle(&self, stream: &TcpStream) -> anyhow::Result<()> {
+        let request = ProxiedRequest::read(stream)?;
+        if let Some(latency) = self.config.latency {
+            std::thread::sleep(latency);
+        }
+        let response = if self.should_fail() {
+            tracing::debug!("failing a request to subgraph '{}'", self.subgraph_name);
+            ProxiedResponse::graphql_error(
+                "503 Service Unavailable",
+                format!(
+                    "`rover dev` injected an error into subgraph '{}'",
+                    self.subgraph_name
+                ),
+            )
+        } else {
+            request.forward(&self.client, &self.target, &[])?
+        };
+        response.write(stream)
+    }
+
+    /// Decides whether to fail the next request, failing `error_rate` of them on average.
+    fn should_fail(&self) -> bool {
+        if self.config.error_rate <= 0.0 {
+            return false;
+        }
+        let mut hasher = self.random_state.build_hasher();
+        hasher.write_u64(self.request_count.fetch_add(1, Ordering::Relaxed));
+        (hasher.finish() as f64 / u64::MAX as f64) < self.config.error_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    use url::Url;
+
+    use super::ChaosConfig;
+
+    #[test]
+    fn it_reads_chaos_config_from_supergraph_yaml() {
+        let chaos = ChaosConfig::from_supergraph_yaml(
+            r#"
+subgraphs:
+  products:
+    routing_url: http://localhost:4001
+    schema:
+      file: ./products.graphql
+    chaos:
+      latency: 200ms
+      error_rate: 0.1
+  reviews:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./reviews.graphql
+"#,
+        )
+        .unwrap();
+        assert_eq!(chaos.len(), 1);
+        assert_eq!(
+            chaos["products"],
+            ChaosConfig {
+                latency: Some(Duration::from_millis(200)),
+                error_rate: 0.1,
+            }
+        );
+
+        assert!(ChaosConfig::from_supergraph_yaml(
+            "subgraphs:\n  products:\n    chaos:\n      error_rate: 2\n"
+        )
+        .is_err());
+    }
+
+    fn post(url: &Url) -> String {
+        let mut stream = TcpStream::connect(url.socket_addrs(|| None).unwrap()[0]).unwrap();
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nhost: localhost\r\ncontent-length: 2\r\n\r\n{{}}",
+            url.path()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn it_delays_and_forwards_requests() {
+        let subgraph = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = Url::parse(&format!(
+            "http://{}/graphql",
+            subgraph.local_addr().unwrap()
+        ))
+        .unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = subgraph.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: 11\r\nconnection: close\r\n\r\n{{\"data\":{{}}}}"
+            )
+            .unwrap();
+        });
+
+        let chaos = ChaosConfig {
+            latency: Some(Duration::from_millis(100)),
+            error_rate: 0.0,
+        };
+        let proxy_url = chaos.start_proxy("products", &target).unwrap();
+        assert_eq!(proxy_url.path(), "/graphql");
+
+        let started = Instant::now();
+        let response = post(&proxy_url);
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("{\"data\":{}}"));
+    }
+
+    #[test]
+    fn it_injects_errors() {
+        let chaos = ChaosConfig {
+            latency: None,
+            error_rate: 1.0,
+        };
+        let target = Url::parse("http://localhost:1/graphql").unwrap();
+        let proxy_url = chaos.start_proxy("products", &target).unwrap();
+        let response = post(&proxy_url);
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.contains("injected an error into subgraph 'products'"));
+    }
+}
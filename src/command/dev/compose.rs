@@ -1,15 +1,17 @@
 use std::fs;
 use std::io::prelude::*;
+use std::net::SocketAddr;
 
 use anyhow::{Context, Error};
 use apollo_federation_types::config::{FederationVersion, SupergraphConfig};
 use camino::Utf8PathBuf;
-use rover_std::{Emoji, Fs};
+use rover_std::{Emoji, Fs, Style};
 
 use crate::command::dev::do_dev::log_err_and_continue;
-use crate::command::supergraph::compose::{Compose, CompositionOutput};
+use crate::command::supergraph::compose::{format_hints, Compose, CompositionOutput};
 use crate::options::PluginOpts;
 use crate::utils::client::StudioClientConfig;
+use crate::utils::source_map::SubgraphSourceMap;
 use crate::{RoverError, RoverResult};
 
 #[derive(Debug)]
@@ -18,8 +20,11 @@ pub struct ComposeRunner {
     override_install_path: Option<Utf8PathBuf>,
     client_config: StudioClientConfig,
     write_path: Utf8PathBuf,
+    router_address: SocketAddr,
     composition_state: Option<RoverResult<CompositionOutput>>,
     plugin_exe: Option<Utf8PathBuf>,
+    last_known_supergraph: Option<String>,
+    source_map: SubgraphSourceMap,
 }
 
 impl ComposeRunner {
@@ -28,17 +33,53 @@ impl ComposeRunner {
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         write_path: Utf8PathBuf,
+        router_address: SocketAddr,
     ) -> Self {
         Self {
             compose: Compose::new(compose_opts),
             override_install_path,
             client_config,
             write_path,
+            router_address,
             composition_state: None,
             plugin_exe: None,
+            last_known_supergraph: None,
+            source_map: SubgraphSourceMap::default(),
         }
     }
 
+    /// Loads the last supergraph schema that was successfully composed on this machine for a
+    /// router on the same address so the router can start when composition is unavailable (e.g. while offline).
+    /// Returns `false` if there is no supergraph schema to fall back to.
+    pub fn use_last_known_supergraph(&mut self) -> bool {
+        match Fs::read_file(self.last_known_supergraph_path()) {
+            Ok(sdl) => {
+                eprintln!(
+                    "{} Composition is unavailable, so the router will use the last supergraph schema composed on this machine. It will not reflect changes to your subgraphs until composition is available again.",
+                    Style::WarningPrefix.paint("WARN:")
+                );
+                self.last_known_supergraph = Some(sdl);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Each session is identified by its router's address, like its interprocess socket, so
+    /// sessions for different supergraphs don't fall back to each other's schemas.
+    fn last_known_supergraph_path(&self) -> Utf8PathBuf {
+        self.client_config
+            .config
+            .home
+            .join("dev")
+            .join(last_known_supergraph_file_name(self.router_address))
+    }
+
+    /// Point composition errors and hints at the files that subgraph schemas are read from.
+    pub fn set_source_map(&mut self, source_map: SubgraphSourceMap) {
+        self.source_map = source_map;
+    }
+
     pub fn maybe_install_supergraph(
         &mut self,
         federation_version: FederationVersion,
@@ -61,14 +102,10 @@ impl ComposeRunner {
         supergraph_config: &mut SupergraphConfig,
     ) -> std::result::Result<Option<CompositionOutput>, String> {
         let prev_state = self.composition_state();
-        self.composition_state = Some(self.compose.exec(
-            self.override_install_path.clone(),
-            self.client_config.clone(),
-            supergraph_config,
-        ));
+        self.composition_state = Some(self.compose(supergraph_config));
         let new_state = self.composition_state();
 
-        match (prev_state, new_state) {
+        let result = match (prev_state, new_state) {
             // wasn't composed, now composed
             (None, Some(Ok(new_success))) | (Some(Err(_)), Some(Ok(new_success))) => {
                 let _ = self
@@ -102,7 +139,29 @@ impl ComposeRunner {
                 let _ = self.remove_supergraph_schema();
                 Err(new_err)
             }
+        };
+        if let Ok(Some(composition_output)) = &result {
+            eprint!("{}", format_hints(&composition_output.hints));
         }
+        result
+    }
+
+    fn compose(&self, supergraph_config: &mut SupergraphConfig) -> RoverResult<CompositionOutput> {
+        if self.plugin_exe.is_none() {
+            if let Some(supergraph_sdl) = &self.last_known_supergraph {
+                return Ok(CompositionOutput {
+                    supergraph_sdl: supergraph_sdl.to_string(),
+                    hints: Vec::new(),
+                    federation_version: None,
+                });
+            }
+        }
+        self.compose.exec(
+            self.override_install_path.clone(),
+            self.client_config.clone(),
+            supergraph_config,
+            &self.source_map,
+        )
     }
 
     fn remove_supergraph_schema(&self) -> RoverResult<()> {
@@ -134,6 +193,10 @@ impl ComposeRunner {
                     ))
                 } else {
                     tracing::info!("wrote updated supergraph schema to {}", &self.write_path);
+                    let last_known_supergraph_path = self.last_known_supergraph_path();
+                    let _ = Fs::create_dir_all(last_known_supergraph_path.parent().unwrap())
+                        .and_then(|_| Fs::write_file(&last_known_supergraph_path, sdl))
+                        .map_err(|e| log_err_and_continue(e.into()));
                     Ok(())
                 }
             }
@@ -147,3 +210,37 @@ impl ComposeRunner {
             .map(|s| s.as_ref().map(|o| o.clone()).map_err(|e| e.to_string()))
     }
 }
+
+/// Addresses contain characters like `:` that aren't allowed in file names on every platform.
+fn last_known_supergraph_file_name(router_address: SocketAddr) -> String {
+    let address: String = router_address
+        .to_string()
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']'))
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("supergraph-{}.graphql", address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::last_known_supergraph_file_name;
+
+    #[test]
+    fn it_names_last_known_supergraphs_by_router_address() {
+        assert_eq!(
+            last_known_supergraph_file_name("127.0.0.1:4000".parse().unwrap()),
+            "supergraph-127.0.0.1-4000.graphql"
+        );
+        assert_eq!(
+            last_known_supergraph_file_name("[::1]:4001".parse().unwrap()),
+            "supergraph---1-4001.graphql"
+        );
+    }
+}
@@ -1,10 +1,17 @@
 use anyhow::{anyhow, Context};
 use camino::Utf8PathBuf;
-use rover_std::Emoji;
+use rover_std::{Emoji, Style};
+use url::Url;
 
-use super::protocol::{FollowerChannel, FollowerMessenger, LeaderChannel, LeaderSession};
+use super::access_log::AccessLog;
+use super::health::HEALTH_PATH;
+use super::ide::serve_ide;
+use super::protocol::{
+    force_shutdown, FollowerChannel, FollowerMessenger, LeaderChannel, LeaderSession,
+};
+use super::reload::{reload_on_sighup, SubgraphWatcherSet};
 use super::router::RouterConfigHandler;
-use super::Dev;
+use super::{Command, Dev};
 
 use crate::command::dev::protocol::FollowerMessage;
 use crate::utils::client::StudioClientConfig;
@@ -12,6 +19,9 @@ use crate::{RoverError, RoverOutput, RoverResult};
 
 use crossbeam_channel::bounded as sync_channel;
 
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+
 pub fn log_err_and_continue(err: RoverError) -> RoverError {
     let _ = err.print();
     err
@@ -23,12 +33,22 @@ impl Dev {
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
     ) -> RoverResult<RoverOutput> {
+        if let Some(command) = &self.command {
+            return match command {
+                Command::Doctor(command) => command.run(override_install_path),
+                Command::List(command) => command.run(client_config),
+                Command::Stop(command) => command.run(client_config),
+                Command::Attach(command) => command.run(override_install_path, client_config),
+            };
+        }
         self.opts
             .plugin_opts
             .prompt_for_license_accept(&client_config)?;
 
         let router_config_handler = RouterConfigHandler::try_from(&self.opts.supergraph_opts)?;
         let router_address = router_config_handler.get_router_address();
+        let router_internal_address = router_config_handler.get_router_internal_address();
+        let router_listen_path = router_config_handler.get_router_listen_path();
         let ipc_socket_addr = router_config_handler.get_ipc_address()?;
         let leader_channel = LeaderChannel::new();
         let follower_channel = FollowerChannel::new();
@@ -39,19 +59,83 @@ impl Dev {
             leader_channel.clone(),
             follower_channel.clone(),
             self.opts.plugin_opts.clone(),
+            self.opts.supergraph_opts.force,
             router_config_handler,
+            self.opts.supergraph_opts.router_version()?,
         )? {
             eprintln!("{0}Do not run this command in production! {0}It is intended for local development.", Emoji::Warn);
+            if !self.opts.supergraph_opts.serialize_compositions {
+                leader_session.batch_changes_within(self.opts.supergraph_opts.watch_debounce());
+            }
+            leader_session
+                .set_shutdown_grace_period(self.opts.supergraph_opts.shutdown_grace_period());
+            leader_session.set_router_environment(self.opts.supergraph_opts.router_environment()?);
+            let mut source_map = self.opts.supergraph_opts.get_source_map();
+            if let Some((subgraph_name, schema_file)) = self.opts.subgraph_opts.schema_file() {
+                source_map.insert(subgraph_name, schema_file);
+            }
+            leader_session.set_source_map(source_map);
+            if let Some(access_log_path) = &self.opts.supergraph_opts.access_log {
+                let access_log = AccessLog::create(access_log_path)?;
+                access_log.serve(router_address, router_internal_address)?;
+                leader_session.set_access_log(access_log);
+                eprintln!(
+                    "{}recording operations sent to the router in {}",
+                    Emoji::Memo,
+                    access_log_path
+                );
+            }
+            if let Some(health_port) = self.opts.supergraph_opts.health_port {
+                let health_addr = leader_session
+                    .serve_health_endpoint(SocketAddr::new(router_address.ip(), health_port))?;
+                eprintln!(
+                    "{}the `rover dev` session's health endpoint is available at http://{}{}",
+                    Emoji::Listen,
+                    health_addr,
+                    HEALTH_PATH
+                );
+            }
+            if let Some(ide_port) = self.opts.supergraph_opts.ide_port {
+                // the router can listen on every interface, but the IDE needs one it can reach
+                let router_ip = match router_address.ip() {
+                    ip if !ip.is_unspecified() => ip,
+                    IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                };
+                let router_url = Url::parse(&format!(
+                    "http://{}{}",
+                    SocketAddr::new(router_ip, router_address.port()),
+                    router_listen_path
+                ))?;
+                let ide_addr =
+                    serve_ide(SocketAddr::new(router_address.ip(), ide_port), router_url)?;
+                eprintln!(
+                    "{}an offline GraphQL IDE for your supergraph is available at http://{}",
+                    Emoji::Web,
+                    ide_addr
+                );
+            }
             let (ready_sender, ready_receiver) = sync_channel(1);
             let follower_messenger = FollowerMessenger::from_main_session(
                 follower_channel.clone().sender,
                 leader_channel.receiver,
             );
 
+            let force_shutdown_addr = ipc_socket_addr.clone();
             rayon::spawn(move || {
+                let shutting_down = AtomicBool::new(false);
                 ctrlc::set_handler(move || {
+                    // a second ctrl-c means the graceful shutdown is stuck, so stop without it
+                    if shutting_down.swap(true, Ordering::SeqCst) {
+                        eprintln!(
+                            "\n{}forcing the `rover dev` session to stop...",
+                            Emoji::Stop
+                        );
+                        force_shutdown(&force_shutdown_addr);
+                        std::process::exit(1);
+                    }
                     eprintln!(
-                        "\n{}shutting down the `rover dev` session and all attached processes...",
+                        "\n{}shutting down the `rover dev` session and all attached processes... (press CTRL+C again to force it)",
                         Emoji::Stop
                     );
                     let _ = follower_channel
@@ -75,7 +159,7 @@ impl Dev {
 
             ready_receiver.recv().unwrap();
 
-            let subgraph_watchers = self
+            let mut subgraph_watchers = self
                 .opts
                 .supergraph_opts
                 .get_subgraph_watchers(
@@ -92,28 +176,62 @@ impl Dev {
                             router_address,
                             &client_config,
                             follower_messenger.clone(),
+                            &self.opts.plugin_opts.profile,
                         )
                         .map(|watcher| vec![watcher])
                 })?;
+            self.opts.supergraph_opts.add_remote_subgraph_watchers(
+                &client_config,
+                follower_messenger.clone(),
+                &self.opts.plugin_opts.profile,
+                &mut subgraph_watchers,
+            )?;
 
-            subgraph_watchers.into_iter().for_each(|mut watcher| {
-                std::thread::spawn(move || {
-                    let _ = watcher
-                        .watch_subgraph_for_changes()
+            let mut watcher_set = SubgraphWatcherSet::new(
+                follower_messenger.clone(),
+                self.opts.supergraph_opts.watch_debounce(),
+                self.opts.supergraph_opts.startup_timeout(),
+            );
+            watcher_set.start(subgraph_watchers);
+
+            // SIGHUP asks the session to pick up changes to the supergraph config
+            if self.opts.supergraph_opts.supergraph_config_path.is_some() {
+                let supergraph_opts = self.opts.supergraph_opts.clone();
+                let polling_interval = self.opts.subgraph_opts.subgraph_polling_interval;
+                let profile = self.opts.plugin_opts.profile.clone();
+                let client_config = client_config.clone();
+                reload_on_sighup(move || {
+                    eprintln!("{}reloading the supergraph config...", Emoji::Reload);
+                    let _ = supergraph_opts
+                        .reload_subgraph_watchers(
+                            &client_config,
+                            polling_interval,
+                            &profile,
+                            &mut watcher_set,
+                        )
                         .map_err(log_err_and_continue);
                 });
-            });
+            }
 
             subgraph_watcher_handle
                 .join()
                 .expect("could not wait for subgraph watcher thread");
         } else {
+            if !self.opts.supergraph_opts.remote_subgraphs.is_empty() {
+                eprintln!(
+                    "{} this process is attaching to a running `rover dev` session, so `--remote-subgraph` is ignored",
+                    Style::WarningPrefix.paint("WARN:")
+                );
+            }
             let follower_messenger = FollowerMessenger::from_attached_session(&ipc_socket_addr);
             let mut subgraph_refresher = self.opts.subgraph_opts.get_subgraph_watcher(
                 router_address,
                 &client_config,
                 follower_messenger.clone(),
+                &self.opts.plugin_opts.profile,
             )?;
+            subgraph_refresher.set_debounce(self.opts.supergraph_opts.watch_debounce());
+            subgraph_refresher.set_startup_timeout(self.opts.supergraph_opts.startup_timeout());
             tracing::info!(
                 "connecting to existing `rover dev` process by communicating via the interprocess socket located at {ipc_socket_addr}"
             );
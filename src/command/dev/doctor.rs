@@ -0,0 +1,494 @@
+use std::env;
+use std::net::{SocketAddr, TcpListener};
+
+use anyhow::anyhow;
+use apollo_federation_types::config::RouterVersion;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::{Emoji, Style};
+use serde::Serialize;
+
+use super::protocol::{session_state, SessionState};
+use super::router::RouterConfigHandler;
+use super::{DevOpts, OVERRIDE_DEV_COMPOSITION_VERSION, OVERRIDE_DEV_ROUTER_VERSION};
+use crate::command::install::{find_installed_plugins, Plugin, PluginSpec};
+use crate::command::Install;
+use crate::options::PluginChannel;
+use crate::utils::env::RoverEnvKey;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+/// Check this machine for common problems that stop `rover dev` from starting
+///
+/// Pass the same options you pass to `rover dev`, so the checks use the same ports,
+/// router config, and plugins.
+pub struct Doctor {
+    #[clap(flatten)]
+    opts: DevOpts,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// The result of one check, with a fix for anything that didn't pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn print(&self) {
+        let emoji = match self.status {
+            Status::Pass => Emoji::Success,
+            Status::Warn => Emoji::Warn,
+            Status::Fail => Emoji::Stop,
+        };
+        eprintln!(
+            "{}{}: {}",
+            emoji,
+            Style::Heading.paint(self.name),
+            self.message
+        );
+        if let Some(fix) = &self.fix {
+            eprintln!("    {}", fix);
+        }
+    }
+}
+
+impl Doctor {
+    pub fn run(&self, override_install_path: Option<Utf8PathBuf>) -> RoverResult<RoverOutput> {
+        let mut checks = Vec::new();
+        match RouterConfigHandler::try_from(&self.opts.supergraph_opts) {
+            Ok(router_config_handler) => {
+                checks.push(Check::pass("router config", "the router config is valid"));
+                let session = router_config_handler
+                    .get_ipc_address()
+                    .map(|ipc_socket_addr| (session_state(&ipc_socket_addr), ipc_socket_addr));
+                let session_running = matches!(session, Ok((SessionState::Running(_), _)));
+                checks.push(check_session(session));
+                checks.extend(self.check_ports(
+                    router_config_handler.get_router_address(),
+                    session_running,
+                ));
+            }
+            Err(e) => checks.push(Check::fail(
+                "router config",
+                format!("the router config can't be read: {}", e.message()),
+                "Fix the file passed to `--router-config`, or leave it out to use the default config.",
+            )),
+        }
+        match self.install_command().get_plugin_dir(override_install_path) {
+            Ok(plugin_dir) => {
+                checks.push(self.check_router_plugin(&plugin_dir));
+                checks.push(self.check_supergraph_plugin(&plugin_dir));
+            }
+            Err(e) => checks.push(Check::fail(
+                "plugins",
+                format!("the plugin directory can't be found: {}", e.message()),
+                format!(
+                    "Set `{}` to a directory you own to install plugins there.",
+                    RoverEnvKey::Home
+                ),
+            )),
+        }
+        checks.push(check_proxy(
+            proxy_from_env().as_deref(),
+            env_var(&["NO_PROXY", "no_proxy"]).as_deref(),
+        ));
+
+        for check in &checks {
+            check.print();
+        }
+        let failures = checks
+            .iter()
+            .filter(|check| check.status == Status::Fail)
+            .count();
+        if failures > 0 {
+            let mut err = RoverError::new(anyhow!(
+                "`rover dev doctor` found {} problem{} that will stop `rover dev` from starting",
+                failures,
+                if failures == 1 { "" } else { "s" }
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Fix the problems above, then run `rover dev doctor` again.".to_string(),
+            ));
+            return Err(err);
+        }
+        eprintln!(
+            "{}no problems found, `rover dev` is ready to start",
+            Emoji::Success
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+
+    fn install_command(&self) -> Install {
+        Install {
+            force: false,
+            plugin: None,
+            from: None,
+            list: false,
+            locked: self.opts.plugin_opts.locked,
+            elv2_license_accepter: self.opts.plugin_opts.elv2_license_accepter,
+        }
+    }
+
+    /// Checks that the ports `rover dev` listens on are free, or belong to a session it can attach to.
+    fn check_ports(&self, router_address: SocketAddr, session_running: bool) -> Vec<Check> {
+        let supergraph_opts = &self.opts.supergraph_opts;
+        let mut checks = Vec::new();
+        if TcpListener::bind(router_address).is_ok() {
+            checks.push(Check::pass(
+                "router port",
+                format!("{} is free", router_address),
+            ));
+        } else if session_running {
+            checks.push(Check::pass(
+                "router port",
+                format!("{} is in use by the running session", router_address),
+            ));
+        } else {
+            checks.push(Check::fail(
+                "router port",
+                format!("{} is in use by another process", router_address),
+                "Stop the process listening on it, or pass a different `--supergraph-port`.",
+            ));
+        }
+        let other_ports = [
+            ("health port", "--health-port", supergraph_opts.health_port),
+            ("IDE port", "--ide-port", supergraph_opts.ide_port),
+        ];
+        for (name, flag, port) in other_ports {
+            if let Some(port) = port {
+                let addr = SocketAddr::new(router_address.ip(), port);
+                if TcpListener::bind(addr).is_ok() {
+                    checks.push(Check::pass(name, format!("{} is free", addr)));
+                } else {
+                    checks.push(Check::fail(
+                        name,
+                        format!("{} is in use by another process", addr),
+                        format!(
+                            "Stop the process listening on it, or pass a different `{}`.",
+                            flag
+                        ),
+                    ));
+                }
+            }
+        }
+        checks
+    }
+
+    fn check_router_plugin(&self, plugin_dir: &Utf8PathBuf) -> Check {
+        let name = "router plugin";
+        if self.opts.plugin_opts.channel == PluginChannel::Next {
+            return Check::pass(
+                name,
+                "the `next` router is resolved when `rover dev` starts",
+            );
+        }
+        let (source, requested) = match (
+            &self.opts.supergraph_opts.router_version,
+            OVERRIDE_DEV_ROUTER_VERSION.as_deref(),
+        ) {
+            (Some(version), _) => ("`--router-version`", Some(version.as_str())),
+            (None, version) => ("`APOLLO_ROVER_DEV_ROUTER_VERSION`", version),
+        };
+        let invalid_version_fix = format!(
+            "Set {} to a v1 router, like `1.33.0`, or leave it out.",
+            source
+        );
+        let version = match requested {
+            None => None,
+            Some(version) => match PluginSpec::router(version) {
+                Ok(PluginSpec::Plugin(Plugin::Router(RouterVersion::Exact(version)))) => {
+                    Some(version)
+                }
+                Ok(_) => {
+                    return Check::pass(
+                        name,
+                        format!("router@{} is resolved when `rover dev` starts", version),
+                    )
+                }
+                Err(e) => {
+                    return Check::fail(
+                        name,
+                        format!(
+                            "{} is set to '{}', which isn't a router version: {}",
+                            source,
+                            version,
+                            e.message()
+                        ),
+                        &invalid_version_fix,
+                    )
+                }
+            },
+        };
+        match version {
+            Some(version) if version.major != 1 => Check::fail(
+                name,
+                format!(
+                    "{} is set to v{}, but `rover dev` only runs v1 routers",
+                    source, version
+                ),
+                &invalid_version_fix,
+            ),
+            Some(version) => {
+                let found = find_installed_plugins(plugin_dir, "router", 1)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|plugin| plugin_version(plugin) == Some(version.to_string()));
+                self.check_plugin(name, plugin_dir, found, &format!("router@v{}", version))
+            }
+            None => {
+                let found = find_installed_plugins(plugin_dir, "router", 1)
+                    .unwrap_or_default()
+                    .pop();
+                self.check_plugin(name, plugin_dir, found, "router@latest")
+            }
+        }
+    }
+
+    fn check_supergraph_plugin(&self, plugin_dir: &Utf8PathBuf) -> Check {
+        let name = "supergraph plugin";
+        if self.opts.plugin_opts.channel == PluginChannel::Next
+            || OVERRIDE_DEV_COMPOSITION_VERSION.is_some()
+        {
+            return Check::pass(
+                name,
+                "the composition version is resolved when `rover dev` starts",
+            );
+        }
+        let found = find_installed_plugins(plugin_dir, "supergraph", 2)
+            .unwrap_or_default()
+            .pop();
+        self.check_plugin(name, plugin_dir, found, "supergraph@latest-2")
+    }
+
+    /// Reports whether the plugin `spec` names is installed. Missing plugins are downloaded when
+    /// `rover dev` starts, unless `--skip-update` is passed.
+    fn check_plugin(
+        &self,
+        name: &'static str,
+        plugin_dir: &Utf8PathBuf,
+        found: Option<Utf8PathBuf>,
+        spec: &str,
+    ) -> Check {
+        match found {
+            Some(plugin) => Check::pass(
+                name,
+                format!(
+                    "{} is installed",
+                    Style::Path.paint(plugin_dir.join(plugin))
+                ),
+            ),
+            None if self.opts.plugin_opts.skip_update => Check::fail(
+                name,
+                format!(
+                    "{} isn't installed, and `--skip-update` won't download it",
+                    spec
+                ),
+                format!("Run `rover install --plugin {}` to install it.", spec),
+            ),
+            None => Check::warn(
+                name,
+                format!(
+                    "{} isn't installed, so `rover dev` will download it when it starts",
+                    spec
+                ),
+                format!(
+                    "Run `rover install --plugin {}` to install it ahead of time.",
+                    spec
+                ),
+            ),
+        }
+    }
+}
+
+/// Looks for a session left behind by a crashed `rover dev` process, which stops new processes
+/// from starting a session or attaching to it.
+fn check_session(session: RoverResult<(SessionState, String)>) -> Check {
+    let name = "session";
+    let (state, ipc_socket_addr) = match session {
+        Ok(session) => session,
+        Err(e) => {
+            return Check::fail(
+                name,
+                format!("the session's socket can't be created: {}", e.message()),
+                format!(
+                    "Set `{}` to a directory you own.",
+                    RoverEnvKey::DevSocketDir
+                ),
+            )
+        }
+    };
+    match state {
+        SessionState::Free => Check::pass(name, "no session is running on this address"),
+        SessionState::Running(pid) => Check::pass(
+            name,
+            format!(
+                "a session is running as PID {}, and `rover dev` will attach to it",
+                pid
+            ),
+        ),
+        SessionState::Orphaned { pid, router_pid } => {
+            let crashed = match pid {
+                Some(pid) => format!("a `rover dev` process (PID {}) that exited", pid),
+                None => "a `rover dev` process that exited".to_string(),
+            };
+            let socket = Style::Path.paint(ipc_socket_addr);
+            let left_behind = match router_pid {
+                Some(router_pid) => format!(
+                    "its socket at {} and its router (PID {})",
+                    socket, router_pid
+                ),
+                None => format!("its socket at {}", socket),
+            };
+            Check::fail(
+                name,
+                format!("{} left behind {}", crashed, left_behind),
+                "Pass `--force` to `rover dev` to clean up and start a new session.",
+            )
+        }
+    }
+}
+
+/// The version in an installed plugin's file name, like `1.33.0` in `router-v1.33.0`.
+fn plugin_version(plugin: &Utf8PathBuf) -> Option<String> {
+    let file_name = plugin.file_name()?;
+    let file_name = file_name
+        .strip_suffix(env::consts::EXE_SUFFIX)
+        .unwrap_or(file_name);
+    file_name
+        .split_once("-v")
+        .map(|(_, version)| version.to_string())
+}
+
+fn env_var(keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| env::var(key).ok())
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// The proxy that requests to local subgraphs and the router would be sent through, if any.
+fn proxy_from_env() -> Option<String> {
+    env_var(&[
+        "HTTP_PROXY",
+        "http_proxy",
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+    ])
+}
+
+/// Checks that a proxy isn't used for the loopback addresses that subgraphs and the router
+/// usually listen on, since most proxies can't reach them.
+fn check_proxy(proxy: Option<&str>, no_proxy: Option<&str>) -> Check {
+    let name = "proxy";
+    let proxy = match proxy {
+        Some(proxy) => proxy,
+        None => return Check::pass(name, "no proxy is configured"),
+    };
+    let bypassed: Vec<&str> = no_proxy
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let bypasses = |hosts: &[&str]| {
+        bypassed
+            .iter()
+            .any(|host| *host == "*" || hosts.contains(host))
+    };
+    let missing: Vec<&str> = [
+        ("localhost", bypasses(&["localhost", ".localhost"])),
+        ("127.0.0.1", bypasses(&["127.0.0.1", "127.0.0.0/8"])),
+    ]
+    .into_iter()
+    .filter_map(|(host, bypassed)| (!bypassed).then_some(host))
+    .collect();
+    if missing.is_empty() {
+        Check::pass(
+            name,
+            format!("requests to local servers bypass the proxy at {}", proxy),
+        )
+    } else {
+        Check::fail(
+            name,
+            format!(
+                "requests to {} are sent through the proxy at {}, which usually can't reach local subgraphs or the router",
+                missing.join(" and "),
+                proxy
+            ),
+            format!("Add `{}` to `NO_PROXY`.", missing.join(",")),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_proxy, plugin_version, Status};
+
+    #[test]
+    fn it_checks_that_local_requests_bypass_the_proxy() {
+        assert_eq!(check_proxy(None, None).status, Status::Pass);
+
+        let check = check_proxy(Some("http://proxy:3128"), None);
+        assert_eq!(check.status, Status::Fail);
+        assert_eq!(
+            check.fix.as_deref(),
+            Some("Add `localhost,127.0.0.1` to `NO_PROXY`.")
+        );
+
+        let check = check_proxy(Some("http://proxy:3128"), Some("example.com, localhost"));
+        assert_eq!(check.fix.as_deref(), Some("Add `127.0.0.1` to `NO_PROXY`."));
+
+        let check = check_proxy(Some("http://proxy:3128"), Some("localhost,127.0.0.0/8"));
+        assert_eq!(check.status, Status::Pass);
+        assert_eq!(
+            check_proxy(Some("http://proxy:3128"), Some("*")).status,
+            Status::Pass
+        );
+    }
+
+    #[test]
+    fn it_reads_plugin_versions_from_file_names() {
+        assert_eq!(
+            plugin_version(&format!("router-v1.33.0{}", std::env::consts::EXE_SUFFIX).into()),
+            Some("1.33.0".to_string())
+        );
+        assert_eq!(plugin_version(&"router".into()), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! A small HTTP API served by the main `rover dev` process, so that editor extensions, scripts,
+//! and test harnesses can check whether a session is up, and read its supergraph schema,
+//! composition errors, and subgraphs, without talking to the router or scraping its output.
+
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+use serde_json::Value;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+};
+
+use super::protocol::{CompositionResult, SubgraphKey};
+use crate::command::supergraph::compose::hints_json;
+use crate::RoverResult;
+
+/// The path that the health endpoint responds on.
+pub(crate) const HEALTH_PATH: &str = "/health";
+
+/// The path that reports the result of the last composition.
+pub(crate) const COMPOSITION_PATH: &str = "/composition";
+
+/// The path that lists the session's subgraphs.
+pub(crate) const SUBGRAPHS_PATH: &str = "/subgraphs";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SessionStatus {
+    /// No subgraphs have been composed yet
+    Starting,
+    /// The last composition succeeded
+    Running,
+    /// The last composition failed, so the router is serving an older supergraph or not running
+    CompositionFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LastComposition {
+    pub(crate) success: bool,
+    pub(crate) error: Option<String>,
+    pub(crate) finished_at: String,
+}
+
+/// What the health endpoint reports about the session.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SessionHealth {
+    pub(crate) status: SessionStatus,
+    pub(crate) pid: u32,
+    pub(crate) router_address: SocketAddr,
+    pub(crate) subgraph_count: usize,
+    pub(crate) last_composition: Option<LastComposition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CompositionState {
+    /// No subgraphs have been composed yet
+    Pending,
+    /// The last composition succeeded, and the router is serving its supergraph
+    Composed,
+    /// The last composition failed, so the router isn't running
+    Failed,
+}
+
+/// What `GET /composition` reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct CompositionStatus {
+    pub(crate) state: CompositionState,
+    /// The supergraph schema the router is serving
+    pub(crate) supergraph_sdl: Option<String>,
+    /// The hints from the last successful composition, each with its `level`
+    pub(crate) hints: Vec<Value>,
+    pub(crate) error: Option<String>,
+    pub(crate) finished_at: Option<String>,
+}
+
+/// A subgraph in the session, as listed by `GET /subgraphs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SubgraphStatus {
+    pub(crate) name: String,
+    pub(crate) routing_url: String,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    health: SessionHealth,
+    composition: CompositionStatus,
+    subgraphs: Vec<SubgraphStatus>,
+}
+
+/// The health and composition results of a session, shared between the main `rover dev` process
+/// and its health endpoint.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionHealthHandle {
+    state: Arc<Mutex<SessionState>>,
+}
+
+impl SessionHealthHandle {
+    pub(crate) fn new(router_address: SocketAddr) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SessionState {
+                health: SessionHealth {
+                    status: SessionStatus::Starting,
+                    pid: std::process::id(),
+                    router_address,
+                    subgraph_count: 0,
+                    last_composition: None,
+                },
+                composition: CompositionStatus {
+                    state: CompositionState::Pending,
+                    supergraph_sdl: None,
+                    hints: Vec::new(),
+                    error: None,
+                    finished_at: None,
+                },
+                subgraphs: Vec::new(),
+            })),
+        }
+    }
+
+    /// Records the outcome of composing `subgraphs`.
+    pub(crate) fn record_composition<'a>(
+        &self,
+        subgraphs: impl Iterator<Item = &'a SubgraphKey>,
+        composition_result: &CompositionResult,
+    ) {
+        let mut subgraphs: Vec<SubgraphStatus> = subgraphs
+            .map(|(name, url)| SubgraphStatus {
+                name: name.clone(),
+                routing_url: url.to_string(),
+            })
+            .collect();
+        subgraphs.sort_by(|a, b| a.name.cmp(&b.name));
+        let error = composition_result.as_ref().err().cloned();
+        let finished_at = chrono::Utc::now().to_rfc3339();
+
+        let mut state = self.lock();
+        let health = &mut state.health;
+        health.status = if error.is_some() {
+            SessionStatus::CompositionFailed
+        } else {
+            SessionStatus::Running
+        };
+        health.subgraph_count = subgraphs.len();
+        health.last_composition = Some(LastComposition {
+            success: error.is_none(),
+            error: error.clone(),
+            finished_at: finished_at.clone(),
+        });
+
+        let composition = &mut state.composition;
+        match composition_result {
+            Ok(Some(composition_output)) => {
+                composition.state = CompositionState::Composed;
+                composition.supergraph_sdl = Some(composition_output.supergraph_sdl.clone());
+                composition.hints = hints_json(&composition_output.hints);
+                composition.error = None;
+            }
+            // the supergraph didn't change, so the router is still serving the last one
+            Ok(None) => {}
+            Err(_) => {
+                composition.state = CompositionState::Failed;
+                composition.supergraph_sdl = None;
+                composition.hints = Vec::new();
+                composition.error = error;
+            }
+        }
+        composition.finished_at = Some(finished_at);
+        state.subgraphs = subgraphs;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.state
+            .lock()
+            .expect("could not acquire lock on the session health")
+    }
+
+    /// Serves the health endpoint on `addr` in the background, returning the address it's bound to.
+    pub(crate) fn serve(&self, addr: SocketAddr) -> RoverResult<SocketAddr> {
+        let handle = self.clone();
+        serve_json("health endpoint", addr, move |method, path| {
+            match (method, path) {
+                ("GET", HEALTH_PATH) => {
+                    Ok(("200 OK", serde_json::to_string(&handle.lock().health)?))
+                }
+                ("GET", COMPOSITION_PATH) => {
+                    Ok(("200 OK", serde_json::to_string(&handle.lock().composition)?))
+                }
+                ("GET", SUBGRAPHS_PATH) => Ok((
+                    "200 OK",
+                    serde_json::json!({ "subgraphs": handle.lock().subgraphs }).to_string(),
+                )),
+                _ => Ok(not_found(&[HEALTH_PATH, COMPOSITION_PATH, SUBGRAPHS_PATH])),
+            }
+        })
+    }
+}
+
+/// Serves a small JSON API on `addr` in the background, answering each request with the status
+/// and body that `route` returns for its method and path. Returns the address it's bound to.
+fn serve_json<F>(
+    name: &'static str,
+    addr: SocketAddr,
+    route: F,
+) -> RoverResult<SocketAddr>
+where
+    F: Fn(&str, &str) -> anyhow::Result<(&'static str, String)> + Send + 'static,
+{
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("could not start the `rover dev` {} on '{}'", name, addr))?;
+    let local_addr = listener.local_addr()?;
+    tracing::info!("serving the `rover dev` {} on {}", name, local_addr);
+    // the server runs for the rest of the session, so it gets its own thread rather than holding
+    // one of rayon's for good
+    std::thread::spawn(move || {
+        listener.incoming().flatten().for_each(|stream| {
+            if let Err(e) = respond(stream, &route) {
+                tracing::debug!("could not respond to a request to the {}: {}", name, e);
+            }
+        })
+    });
+    Ok(local_addr)
+}
+
+/// The response to a request for a path that isn't one of `paths`.
+fn not_found(paths: &[&str]) -> (&'static str, String) {
+    (
+        "404 Not Found",
+        serde_json::json!({ "error": format!("only GET {} is supported", paths.join(" and GET ")) })
+            .to_string(),
+    )
+}
+
+fn respond<F>(mut stream: TcpStream, route: &F) -> anyhow::Result<()>
+where
+    F: Fn(&str, &str) -> anyhow::Result<(&'static str, String)>,
+{
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => route(method, path)?,
+        _ => return Err(anyhow!("malformed request line '{}'", request_line.trim())),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+
+    use super::SessionHealthHandle;
+    use crate::command::supergraph::compose::CompositionOutput;
+
+    fn get(handle: &SessionHealthHandle, path: &str) -> String {
+        let addr = handle.serve("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, addr).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn it_reports_session_health() {
+        let handle = SessionHealthHandle::new("127.0.0.1:4000".parse().unwrap());
+        let subgraphs = [
+            (
+                "reviews".to_string(),
+                "http://localhost:4002/".parse().unwrap(),
+            ),
+            (
+                "products".to_string(),
+                "http://localhost:4001/".parse().unwrap(),
+            ),
+        ];
+        handle.record_composition(subgraphs.iter(), &Err("could not compose".to_string()));
+
+        let response = get(&handle, "/health");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let body: Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["status"], json!("composition_failed"));
+        assert_eq!(body["router_address"], json!("127.0.0.1:4000"));
+        assert_eq!(body["subgraph_count"], json!(2));
+        assert_eq!(body["last_composition"]["success"], json!(false));
+        assert_eq!(
+            body["last_composition"]["error"],
+            json!("could not compose")
+        );
+    }
+
+    fn get_json(handle: &SessionHealthHandle, path: &str) -> Value {
+        let response = get(handle, path);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_reports_compositions_and_subgraphs() {
+        let handle = SessionHealthHandle::new("127.0.0.1:4000".parse().unwrap());
+        let subgraphs = [
+            (
+                "reviews".to_string(),
+                "http://localhost:4002/".parse().unwrap(),
+            ),
+            (
+                "products".to_string(),
+                "http://localhost:4001/".parse().unwrap(),
+            ),
+        ];
+        handle.record_composition(
+            subgraphs.iter(),
+            &Ok(Some(CompositionOutput {
+                supergraph_sdl: "schema { query: Query }".to_string(),
+                hints: serde_json::from_value(json!([
+                    { "message": "descriptions differ", "code": "INCONSISTENT_DESCRIPTION" }
+                ]))
+                .unwrap(),
+                federation_version: None,
+            })),
+        );
+
+        let body = get_json(&handle, "/composition");
+        assert_eq!(body["state"], json!("composed"));
+        assert_eq!(body["supergraph_sdl"], json!("schema { query: Query }"));
+        assert_eq!(body["hints"][0]["level"], json!("warn"));
+        assert_eq!(body["error"], json!(null));
+
+        let body = get_json(&handle, "/subgraphs");
+        assert_eq!(
+            body,
+            json!({ "subgraphs": [
+                { "name": "products", "routing_url": "http://localhost:4001/" },
+                { "name": "reviews", "routing_url": "http://localhost:4002/" }
+            ]})
+        );
+
+        handle.record_composition(subgraphs.iter(), &Err("could not compose".to_string()));
+        let body = get_json(&handle, "/composition");
+        assert_eq!(body["state"], json!("failed"));
+        assert_eq!(body["supergraph_sdl"], json!(null));
+        assert_eq!(body["hints"], json!([]));
+        assert_eq!(body["error"], json!("could not compose"));
+    }
+
+    #[test]
+    fn it_only_serves_its_paths() {
+        let handle = SessionHealthHandle::new("127.0.0.1:4000".parse().unwrap());
+        assert!(get(&handle, "/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -0,0 +1,143 @@
+//! A GraphQL IDE bundled into Rover, for querying the `rover dev` router where the
+//! CDN-hosted Apollo Sandbox can't load, like air-gapped networks or strict CSP environments.
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+use anyhow::Context;
+use reqwest::blocking::Client;
+use url::Url;
+
+use crate::command::dev::proxy::{self, ProxiedRequest, ProxiedResponse};
+use crate::RoverResult;
+
+/// The IDE's page, which has no external dependencies.
+const IDE_HTML: &str = include_str!("./ide.html");
+
+/// The path the IDE sends operations to, which are forwarded to the router.
+pub(crate) const IDE_GRAPHQL_PATH: &str = "/graphql";
+
+/// Serves the IDE on `addr` in the background, forwarding its operations to the router at
+/// `router_url`, and returns the address it's bound to.
+pub(crate) fn serve_ide(addr: SocketAddr, router_url: Url) -> RoverResult<SocketAddr> {
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("could not start the `rover dev` IDE on '{}'", addr))?;
+    let local_addr = listener.local_addr()?;
+    tracing::info!(
+        "serving the `rover dev` IDE on {} for the router at {}",
+        local_addr,
+        router_url
+    );
+    let client = Client::new();
+    proxy::serve(listener, "from the IDE".to_string(), move |stream| {
+        respond(stream, &client, &router_url)
+    });
+    Ok(local_addr)
+}
+
+fn respond(stream: &TcpStream, client: &Client, router_url: &Url) -> anyhow::Result<()> {
+    let request = ProxiedRequest::read(stream)?;
+    let method = request.method.clone();
+    let path = request
+        .path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let response = match (method.as_str(), path.as_str()) {
+        ("GET", "/") => ProxiedResponse {
+            status: "200 OK".to_string(),
+            status_code: 200,
+            headers: vec![(
+                "content-type".to_string(),
+                "text/html; charset=utf-8".to_string(),
+            )],
+            body: IDE_HTML.as_bytes().to_vec(),
+        },
+        (_, IDE_GRAPHQL_PATH) => {
+            // the router may be listening on a path other than the IDE's
+            let forwarded = ProxiedRequest {
+                path: router_url.path().to_string(),
+                ..request
+            };
+            forwarded
+                .forward(client, router_url, &[])
+                .unwrap_or_else(|e| {
+                    ProxiedResponse::graphql_error(
+                        "502 Bad Gateway",
+                        format!("could not reach the router: {}", e),
+                    )
+                })
+        }
+        _ => ProxiedResponse::graphql_error(
+            "404 Not Found",
+            format!(
+                "the IDE is served at / and sends operations to {}",
+                IDE_GRAPHQL_PATH
+            ),
+        ),
+    };
+    response.write(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    use url::Url;
+
+    use super::serve_ide;
+
+    fn request(addr: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn it_serves_the_ide_and_forwards_operations() {
+        let router = TcpListener::bind("127.0.0.1:0").unwrap();
+        let router_url = Url::parse(&format!(
+            "http://{}/custom-path",
+            router.local_addr().unwrap()
+        ))
+        .unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = router.accept().unwrap();
+            let mut buffer = [0; 4096];
+            let read = stream.read(&mut buffer).unwrap();
+            assert!(String::from_utf8_lossy(&buffer[..read]).starts_with("POST /custom-path "));
+            let body = r#"{"data":{"__typename":"Query"}}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        let addr = serve_ide("127.0.0.1:0".parse().unwrap(), router_url).unwrap();
+
+        let page = request(addr, "GET / HTTP/1.1\r\nhost: localhost\r\n\r\n");
+        assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(page.contains("<title>rover dev</title>"));
+        // the page must work offline, so it can't load anything from elsewhere
+        assert!(!page.contains("<script src="));
+        assert!(!page.contains("<link"));
+
+        let body = r#"{"query":"{ __typename }"}"#;
+        let response = request(
+            addr,
+            &format!(
+                "POST /graphql HTTP/1.1\r\nhost: localhost\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+        );
+        assert!(response.ends_with(r#"{"data":{"__typename":"Query"}}"#));
+
+        assert!(request(addr, "GET /missing HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404"));
+    }
+}
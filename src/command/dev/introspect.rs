@@ -13,18 +13,23 @@ pub struct UnknownIntrospectRunner {
     endpoint: SubgraphUrl,
     client: Client,
     headers: Option<Vec<(String, String)>>,
+    retries: u32,
 }
 
 impl UnknownIntrospectRunner {
+    /// A runner that retries failed introspection requests `retries` times before waiting for
+    /// the next poll.
     pub fn new(
         endpoint: SubgraphUrl,
         client: Client,
         headers: Option<Vec<(String, String)>>,
+        retries: u32,
     ) -> Self {
         Self {
             endpoint,
             client,
             headers,
+            retries,
         }
     }
 
@@ -33,12 +38,14 @@ impl UnknownIntrospectRunner {
             endpoint: self.endpoint.clone(),
             client: self.client.clone(),
             headers: self.headers.clone(),
+            retries: self.retries,
         };
 
         let graph_runner = GraphIntrospectRunner {
             endpoint: self.endpoint.clone(),
             client: self.client.clone(),
             headers: self.headers.clone(),
+            retries: self.retries,
         };
 
         // we _could_ run these in parallel
@@ -94,6 +101,19 @@ impl IntrospectRunnerKind {
             Self::Graph(g) => g.endpoint.clone(),
         }
     }
+
+    /// A runner for `endpoint` with the same client and headers, which works out again whether
+    /// the endpoint supports federated introspection.
+    pub fn with_endpoint(&self, endpoint: SubgraphUrl) -> Self {
+        let (client, headers, retries) = match &self {
+            Self::Unknown(u) => (u.client.clone(), u.headers.clone(), u.retries),
+            Self::Subgraph(s) => (s.client.clone(), s.headers.clone(), s.retries),
+            Self::Graph(g) => (g.client.clone(), g.headers.clone(), g.retries),
+        };
+        Self::Unknown(UnknownIntrospectRunner::new(
+            endpoint, client, headers, retries,
+        ))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -101,6 +121,7 @@ pub struct SubgraphIntrospectRunner {
     endpoint: SubgraphUrl,
     client: Client,
     headers: Option<Vec<(String, String)>>,
+    retries: u32,
 }
 
 impl SubgraphIntrospectRunner {
@@ -116,7 +137,7 @@ impl SubgraphIntrospectRunner {
                 watch: false,
             },
         }
-        .exec(&self.client, false)
+        .exec(&self.client, Some(self.retries))
     }
 }
 
@@ -125,6 +146,7 @@ pub struct GraphIntrospectRunner {
     endpoint: SubgraphUrl,
     client: Client,
     headers: Option<Vec<(String, String)>>,
+    retries: u32,
 }
 
 impl GraphIntrospectRunner {
@@ -139,7 +161,8 @@ impl GraphIntrospectRunner {
                 headers: self.headers.clone(),
                 watch: false,
             },
+            prefer_federation_sdl: false,
         }
-        .exec(&self.client, false)
+        .exec(&self.client, Some(self.retries))
     }
 }
@@ -1,18 +1,44 @@
+#[cfg(feature = "composition-js")]
+mod access_log;
+
+#[cfg(feature = "composition-js")]
+mod chaos;
+
 #[cfg(feature = "composition-js")]
 mod compose;
 
+#[cfg(feature = "composition-js")]
+mod health;
+
+#[cfg(feature = "composition-js")]
+mod ide;
+
 #[cfg(feature = "composition-js")]
 mod introspect;
 
 #[cfg(feature = "composition-js")]
-mod router;
+mod reload;
+
+#[cfg(feature = "composition-js")]
+pub(crate) mod router;
 
 #[cfg(feature = "composition-js")]
 mod schema;
 
+#[cfg(feature = "composition-js")]
+mod port_file;
+
+#[cfg(feature = "composition-js")]
+mod startup;
+
+#[cfg(feature = "composition-js")]
+
 #[cfg(feature = "composition-js")]
 mod protocol;
 
+#[cfg(feature = "composition-js")]
+mod proxy;
+
 #[cfg(feature = "composition-js")]
 mod netstat;
 
@@ -22,23 +48,64 @@ mod watcher;
 #[cfg(feature = "composition-js")]
 mod do_dev;
 
+#[cfg(feature = "composition-js")]
+mod doctor;
+
+#[cfg(feature = "composition-js")]
+pub(crate) mod sessions;
+
 #[cfg(not(feature = "composition-js"))]
 mod no_dev;
 
 use crate::options::{OptionalSubgraphOpts, PluginOpts};
-use std::net::IpAddr;
+use crate::utils::parsers::parse_env_var;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
 
 use camino::Utf8PathBuf;
 use clap::Parser;
+use rover_client::shared::GraphRef;
 use serde::Serialize;
 
 #[derive(Debug, Serialize, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Dev {
+    #[cfg(feature = "composition-js")]
+    #[clap(subcommand)]
+    pub(crate) command: Option<Command>,
+
     #[clap(flatten)]
     pub(crate) opts: DevOpts,
 }
 
+#[cfg(feature = "composition-js")]
 #[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Check this machine for common problems that stop `rover dev` from starting, like occupied ports,
+    /// sessions left behind by a crash, missing plugins, and proxy settings
+    Doctor(doctor::Doctor),
+
+    /// List the `rover dev` sessions running on this machine, with their subgraphs
+    List(sessions::List),
+
+    /// Stop a running `rover dev` session by its router's port
+    Stop(sessions::Stop),
+
+    /// Add a subgraph to a running `rover dev` session by its router's port
+    Attach(sessions::Attach),
+}
+
+/// A `rover dev` session running on this machine, as listed by `rover dev list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DevSession {
+    pub router_address: SocketAddr,
+    /// The main `rover dev` process
+    pub pid: u32,
+    /// The session's subgraphs and their routing URLs, or `None` if the session didn't respond
+    pub subgraphs: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Parser, Clone)]
 pub struct DevOpts {
     #[clap(flatten)]
     pub plugin_opts: PluginOpts,
@@ -75,6 +142,12 @@ pub struct SupergraphOpts {
     #[serde(skip_serializing)]
     router_config_path: Option<Utf8PathBuf>,
 
+    /// The version of the router to run, like `1.33.0`, so the session matches the router you run in production.
+    ///
+    /// Defaults to `APOLLO_ROVER_DEV_ROUTER_VERSION`, or the latest v1 router. Only the main `rover dev` process runs the router.
+    #[arg(long = "router-version", value_name = "VERSION")]
+    router_version: Option<String>,
+
     /// The path to a supergraph configuration file. If provided, subgraphs will be loaded from this
     /// file.
     ///
@@ -86,6 +159,123 @@ pub struct SupergraphOpts {
         conflicts_with_all = ["subgraph_name", "subgraph_url", "subgraph_schema_path"]
     )]
     supergraph_config_path: Option<Utf8PathBuf>,
+
+    /// Replace a session left behind by a `rover dev` process that crashed, instead of attaching to it.
+    ///
+    /// This fails if the session's main process is still running. Otherwise, Rover stops the router
+    /// that the crashed process left behind, removes its socket, and starts a new session.
+    #[arg(long)]
+    force: bool,
+
+    /// Serve a health endpoint for this session on the given port, at `/health` on the same address as the router.
+    ///
+    /// The endpoint reports whether the session is up, how many subgraphs it's composing, and the result of the last composition.
+    /// The same port also serves `GET /composition`, with the supergraph schema the router is serving and the error from the last
+    /// composition, if it failed, and `GET /subgraphs`, with the session's subgraphs and their routing URLs.
+    /// Only the main `rover dev` process serves this endpoint.
+    #[arg(long = "health-port")]
+    health_port: Option<u16>,
+
+    /// Serve a GraphQL IDE for the router on the given port, on the same address as the router.
+    ///
+    /// Unlike the Apollo Sandbox that the router serves, this IDE is bundled into Rover, so it works offline
+    /// and in environments whose content security policy blocks Apollo's CDN. Only the main `rover dev` process serves the IDE.
+    #[arg(long = "ide-port")]
+    ide_port: Option<u16>,
+
+    /// How long a watched schema file has to go without changing before it's reloaded, in milliseconds.
+    ///
+    /// The main `rover dev` process also waits this long after a subgraph changes for other subgraphs to change,
+    /// so that saving several schema files at once triggers a single recomposition.
+    #[arg(long = "watch-debounce", default_value_t = 500)]
+    watch_debounce: u64,
+
+    /// How long to wait for a subgraph to be ready before adding it to the session anyway, in seconds.
+    ///
+    /// A subgraph is ready once its health check passes, or, without one, once it responds to introspection.
+    /// Subgraphs also wait this long for each subgraph and service they depend on.
+    #[arg(long = "startup-timeout", value_name = "SECONDS", default_value_t = 60)]
+    startup_timeout: u64,
+
+    /// How long the router has to shut down after it's asked to stop, in seconds, before it's killed.
+    ///
+    /// When the session ends or the router restarts, Rover sends it `SIGTERM` (`CTRL_BREAK` on Windows) so it can
+    /// flush telemetry and close its connections, then kills the router and any processes it started if it's still running.
+    #[arg(
+        long = "shutdown-grace-period",
+        value_name = "SECONDS",
+        default_value_t = 5
+    )]
+    shutdown_grace_period: u64,
+
+    /// Set an environment variable for the router process, like `--router-env RUST_LOG=debug`. Can be passed more than once.
+    ///
+    /// Variables set this way take precedence over Rover's environment, so they can also be used with
+    /// variable expansion in the router config.
+    #[arg(
+        long = "router-env",
+        value_name = "KEY=VALUE",
+        value_parser = parse_env_var
+    )]
+    router_envs: Vec<(String, String)>,
+
+    /// The directory to run the router process in, instead of the current directory.
+    ///
+    /// Relative paths in the router config, like Rhai scripts, are resolved against this directory.
+    #[arg(long = "router-dir", value_name = "DIR")]
+    #[serde(skip_serializing)]
+    router_dir: Option<Utf8PathBuf>,
+
+    /// Recompose once for every subgraph change, in the order they arrive, instead of batching changes together.
+    ///
+    /// This makes it easier to tell which change broke composition, at the cost of slower reloads.
+    #[arg(long = "serialize-compositions")]
+    serialize_compositions: bool,
+
+    /// The graph ref to fetch `--remote-subgraph`s from, like `my-graph@current`.
+    #[arg(
+        long = "graph-ref",
+        value_name = "GRAPH_REF",
+        requires = "remote_subgraphs"
+    )]
+    graph_ref: Option<GraphRef>,
+
+    /// Compose a subgraph published to `--graph-ref` alongside the subgraphs running locally. Can be passed more than once.
+    ///
+    /// The subgraph's schema is fetched from GraphOS, and the router sends its requests to the routing URL it was published with.
+    /// Only the main `rover dev` process composes remote subgraphs.
+    #[arg(
+        long = "remote-subgraph",
+        value_name = "SUBGRAPH_NAME",
+        requires = "graph_ref"
+    )]
+    remote_subgraphs: Vec<String>,
+
+    /// How often to fetch the schemas of `--remote-subgraph`s again, in seconds.
+    #[arg(
+        long = "remote-refresh-interval",
+        value_name = "SECONDS",
+        default_value_t = 30
+    )]
+    remote_refresh_interval: u64,
+
+    /// Record every operation sent to the router in a JSON Lines file, with its name, hash, the shape of its variables,
+    /// the requests made to subgraphs for it, how long it took, and any errors.
+    ///
+    /// To record operations, the router listens on another port behind a proxy on the router address.
+    /// Only the main `rover dev` process records operations.
+    #[arg(long = "access-log", value_name = "PATH")]
+    #[serde(skip_serializing)]
+    access_log: Option<Utf8PathBuf>,
+
+    /// Only allow the operations in a persisted query manifest, like the router does with safelisting enforced in GraphOS.
+    ///
+    /// The router rejects every other operation, so client teams can check that their apps work before enforcement
+    /// is turned on in production. The manifest must be in Apollo's format, like the ones `rover persisted-queries publish` takes.
+    /// This overrides `persisted_queries` and turns off `apq` in the router config.
+    #[arg(long = "persisted-queries-manifest", value_name = "PATH")]
+    #[serde(skip_serializing)]
+    persisted_queries_manifest: Option<Utf8PathBuf>,
 }
 
 lazy_static::lazy_static! {
@@ -0,0 +1,154 @@
+//! Follows subgraphs that listen on a different port every time they start, like dev servers that
+//! pick a random free port. The subgraph writes its port (or its whole URL) to a file, and
+//! `rover dev` routes to wherever the file points instead of leaving a dead route behind:
+//!
+//! ```yaml
+//! subgraphs:
+//!   accounts:
+//!     routing_url: http://localhost:4001
+//!     schema:
+//!       subgraph_url: http://localhost:4001
+//!     port_file: ./accounts/.port
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
+use crossbeam_channel::Sender;
+use rover_std::Fs;
+use serde::Deserialize;
+use url::Url;
+
+use crate::RoverResult;
+
+/// How often to check a port file for changes, for subgraphs that aren't polled anyway
+const PORT_FILE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Deserialize)]
+struct PortFileSupergraphConfig {
+    #[serde(default)]
+    subgraphs: BTreeMap<String, PortFileSubgraphConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PortFileSubgraphConfig {
+    #[serde(default)]
+    port_file: Option<Utf8PathBuf>,
+}
+
+/// A file that a subgraph writes the port or URL it's listening on to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PortFile {
+    path: Utf8PathBuf,
+}
+
+impl PortFile {
+    /// Reads the `port_file` of each subgraph in a supergraph config. Paths are relative to
+    /// `config_dir`, the directory the supergraph config is in.
+    pub(crate) fn from_supergraph_yaml(
+        yaml: &str,
+        config_dir: Option<&Utf8Path>,
+    ) -> RoverResult<BTreeMap<String, Self>> {
+        let config: PortFileSupergraphConfig = serde_yaml::from_str(yaml)
+            .context("could not read the `port_file` entries of the supergraph config")?;
+        Ok(config
+            .subgraphs
+            .into_iter()
+            .filter_map(|(name, subgraph)| subgraph.port_file.map(|path| (name, path)))
+            .map(|(name, path)| {
+                let path = match config_dir {
+                    Some(config_dir) if path.is_relative() => config_dir.join(path),
+                    _ => path,
+                };
+                (name, Self { path })
+            })
+            .collect())
+    }
+
+    /// The URL the subgraph listens on now, if the file moved it away from `url`. A missing or
+    /// unreadable file leaves the subgraph where it is, since it's usually being rewritten.
+    pub(crate) fn moved_url(&self, url: &Url) -> Option<Url> {
+        let contents = Fs::read_file(&self.path).ok()?;
+        endpoint_from_contents(contents.trim(), url).filter(|new_url| new_url != url)
+    }
+
+    /// Sends to `tx` whenever the file's contents change, until the receiver is dropped.
+    pub(crate) fn poll(&self, tx: Sender<()>) {
+        let path = self.path.clone();
+        rayon::spawn(move || {
+            let mut last_contents = Fs::read_file(&path).ok();
+            loop {
+                std::thread::sleep(PORT_FILE_INTERVAL);
+                let contents = Fs::read_file(&path).ok();
+                if contents != last_contents {
+                    last_contents = contents;
+                    if tx.send(()).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// A port file holds either a port, which replaces the port of `url`, or a whole URL.
+fn endpoint_from_contents(contents: &str, url: &Url) -> Option<Url> {
+    match contents.parse::<u16>() {
+        Ok(port) => {
+            let mut url = url.clone();
+            url.set_port(Some(port)).ok()?;
+            Some(url)
+        }
+        Err(_) => Url::parse(contents).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use url::Url;
+
+    use super::{endpoint_from_contents, PortFile};
+
+    #[test]
+    fn it_reads_port_files_from_the_supergraph_config() {
+        let yaml = r#"
+subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      subgraph_url: http://localhost:4001
+    port_file: ./accounts/.port
+  products:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./products.graphql
+"#;
+        let port_files =
+            PortFile::from_supergraph_yaml(yaml, Some(Utf8Path::new("/graphs/local"))).unwrap();
+        assert_eq!(port_files.len(), 1);
+        assert_eq!(
+            port_files["accounts"].path,
+            "/graphs/local/./accounts/.port"
+        );
+    }
+
+    #[test]
+    fn it_reads_a_port_or_a_url() {
+        let url = Url::parse("http://localhost:4001/graphql").unwrap();
+        assert_eq!(
+            endpoint_from_contents("53127", &url).unwrap().as_str(),
+            "http://localhost:53127/graphql"
+        );
+        assert_eq!(
+            endpoint_from_contents("http://127.0.0.1:53127/", &url)
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:53127/"
+        );
+        assert!(endpoint_from_contents("", &url).is_none());
+        assert!(endpoint_from_contents("starting...", &url).is_none());
+    }
+}
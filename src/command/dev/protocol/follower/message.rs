@@ -197,6 +197,14 @@ impl FollowerMessageKind {
             subgraph_name: subgraph_name.to_string(),
         }
     }
+
+    /// Whether this message adds, updates, or removes a subgraph, which requires recomposing
+    pub fn is_subgraph_change(&self) -> bool {
+        matches!(
+            self,
+            Self::AddSubgraph { .. } | Self::UpdateSubgraph { .. } | Self::RemoveSubgraph { .. }
+        )
+    }
 }
 
 #[cfg(test)]
@@ -1,13 +1,13 @@
 use anyhow::anyhow;
 use apollo_federation_types::build::SubgraphDefinition;
 use crossbeam_channel::{Receiver, Sender};
-use interprocess::local_socket::LocalSocketStream;
 use std::{fmt::Debug, io::BufReader, time::Duration};
 
 use crate::{RoverError, RoverErrorSuggestion, RoverResult, PKG_VERSION};
 
 use crate::command::dev::protocol::{
-    socket_read, socket_write, FollowerMessage, LeaderMessageKind, SubgraphKeys, SubgraphName,
+    ipc_connect, socket_read, socket_write, FollowerMessage, LeaderMessageKind, SubgraphKeys,
+    SubgraphName,
 };
 
 #[derive(Clone, Debug)]
@@ -88,6 +88,12 @@ impl FollowerMessenger {
         Ok(())
     }
 
+    /// Ask the main session to shut down. The main process exits without replying, so the
+    /// caller has to check whether it stopped.
+    pub fn shutdown_session(&self) {
+        let _ = self.message_leader(FollowerMessage::shutdown(self.is_from_main_session()));
+    }
+
     /// Send a message to the leader
     fn message_leader(
         &self,
@@ -150,9 +156,10 @@ impl FollowerMessengerKind {
                 leader_message
             }
             FromAttachedSession { ipc_socket_addr } => {
-                let stream = LocalSocketStream::connect(&**ipc_socket_addr).map_err(|_| {
+                let stream = ipc_connect(ipc_socket_addr).map_err(|e| {
                     let mut err = RoverError::new(anyhow!(
-                        "there is not a main `rover dev` process to report updates to"
+                        "there is not a main `rover dev` process to report updates to: {}",
+                        e
                     ));
                     err.set_suggestion(RoverErrorSuggestion::SubmitIssue);
                     err
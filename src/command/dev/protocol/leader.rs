@@ -1,12 +1,19 @@
 use crate::{
     command::dev::{
+        access_log::AccessLog,
         compose::ComposeRunner,
         do_dev::log_err_and_continue,
-        router::{RouterConfigHandler, RouterRunner},
+        health::SessionHealthHandle,
+        router::{RouterConfigHandler, RouterRunner, TaskEnvironment},
         OVERRIDE_DEV_COMPOSITION_VERSION,
     },
-    options::PluginOpts,
-    utils::client::StudioClientConfig,
+    command::install::resolve_federation_version,
+    options::{PluginChannel, PluginOpts},
+    utils::{
+        client::StudioClientConfig,
+        events::{self, Event},
+        source_map::SubgraphSourceMap,
+    },
     RoverError, RoverErrorSuggestion, RoverResult, PKG_VERSION,
 };
 use anyhow::{anyhow, Context};
@@ -16,21 +23,34 @@ use apollo_federation_types::{
 };
 use camino::Utf8PathBuf;
 use crossbeam_channel::{bounded, Receiver, Sender};
-use interprocess::local_socket::{LocalSocketListener, LocalSocketStream};
-use rover_std::Emoji;
-use semver::Version;
+use rover_std::{Emoji, Style};
 use serde::{Deserialize, Serialize};
-
-use std::{collections::HashMap, fmt::Debug, io::BufReader, net::TcpListener};
+use url::Url;
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io::BufReader,
+    net::{SocketAddr, TcpListener},
+    time::{Duration, Instant},
+};
 
 use super::{
-    socket::{handle_socket_error, socket_read, socket_write},
+    registry::{RegisteredSession, SessionRegistry},
+    session_lock::{reclaim_orphaned_session, SessionLock, SessionLockStatus},
+    socket::{
+        handle_socket_error, ipc_bind, ipc_connect, remove_socket, remove_stale_socket,
+        socket_read, socket_write, IpcStream, IpcTransport,
+    },
     types::{
         CompositionResult, SubgraphEntry, SubgraphKey, SubgraphKeys, SubgraphName, SubgraphSdl,
     },
     FollowerChannel, FollowerMessage, FollowerMessageKind,
 };
 
+/// How long to wait for another `rover dev` process that's starting a session before giving up on attaching to it.
+const SESSION_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub struct LeaderSession {
     subgraphs: HashMap<SubgraphKey, SubgraphSdl>,
@@ -40,6 +60,14 @@ pub struct LeaderSession {
     follower_channel: FollowerChannel,
     leader_channel: LeaderChannel,
     federation_version: FederationVersion,
+    session_lock: SessionLock,
+    registry: SessionRegistry,
+    router_socket_addr: SocketAddr,
+    health: SessionHealthHandle,
+    batch_window: Option<Duration>,
+    access_log: Option<AccessLog>,
+    /// The access log proxies in front of each subgraph, by the URL they forward to
+    subgraph_proxies: HashMap<SubgraphKey, Url>,
 }
 
 impl LeaderSession {
@@ -50,32 +78,49 @@ impl LeaderSession {
     /// Ok(Some(Self)) when successfully initiated
     /// Ok(None) when a LeaderSession already exists for that address
     /// Err(RoverError) when something went wrong.
+    ///
+    /// With `force`, a session whose main process has exited is cleaned up and replaced
+    /// instead of being attached to.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         override_install_path: Option<Utf8PathBuf>,
         client_config: &StudioClientConfig,
         leader_channel: LeaderChannel,
         follower_channel: FollowerChannel,
         plugin_opts: PluginOpts,
+        force: bool,
         router_config_handler: RouterConfigHandler,
+        router_version: Option<String>,
     ) -> RoverResult<Option<Self>> {
         let ipc_socket_addr = router_config_handler.get_ipc_address()?;
+        // fail on an invalid `APOLLO_DEV_IPC_TRANSPORT` before it looks like there's no session
+        IpcTransport::from_env()?;
         let router_socket_addr = router_config_handler.get_router_address();
-        if let Ok(stream) = LocalSocketStream::connect(&*ipc_socket_addr) {
-            // write to the socket so we don't make the other session deadlock waiting on a message
-            let mut stream = BufReader::new(stream);
-            socket_write(&FollowerMessage::health_check(false)?, &mut stream)?;
-            let _ = LeaderSession::socket_read(&mut stream);
+        let router_internal_addr = router_config_handler.get_router_internal_address();
+        if force {
+            reclaim_orphaned_session(&ipc_socket_addr)?;
+        } else if Self::attach_to_running_session(&ipc_socket_addr)? {
             // return early so an attached session can be created instead
             return Ok(None);
         }
 
+        // only one process can own the lock, so two `rover dev` processes started at the
+        // same time don't both try to become the main session
+        let session_lock = match SessionLock::acquire(&ipc_socket_addr)? {
+            SessionLockStatus::Acquired(session_lock) => session_lock,
+            SessionLockStatus::OwnedBy(pid) => {
+                return Self::wait_for_starting_session(&ipc_socket_addr, router_socket_addr, pid)
+                    .map(|_| None)
+            }
+        };
+
         tracing::info!("initializing main `rover dev process`");
         // if we can't connect to the socket, we should start it and listen for incoming
         // subgraph events
         //
         // remove the socket file before starting in case it was here from last time
         // if we can't connect to it, it's safe to remove
-        let _ = std::fs::remove_file(&ipc_socket_addr);
+        remove_stale_socket(&ipc_socket_addr)?;
 
         if TcpListener::bind(router_socket_addr).is_err() {
             let mut err =
@@ -92,8 +137,26 @@ impl LeaderSession {
             override_install_path.clone(),
             client_config.clone(),
             router_config_handler.get_supergraph_schema_path(),
+            router_socket_addr,
         );
 
+        // resolve the composition version before `plugin_opts` is handed off to the router.
+        // the profile's `federation-version` default only applies to the stable channel
+        let default_composition_version = match plugin_opts.channel {
+            PluginChannel::Stable => client_config
+                .get_profile_settings(&plugin_opts.profile.profile_name)
+                .federation_version
+                .unwrap_or_else(|| "latest-2".to_string()),
+            PluginChannel::Next => "next".to_string(),
+        };
+        let federation_version = resolve_federation_version(
+            OVERRIDE_DEV_COMPOSITION_VERSION
+                .as_deref()
+                .unwrap_or(&default_composition_version),
+            client_config,
+            plugin_opts.locked,
+        )?;
+
         // create a [`RouterRunner`] that we will use to spawn the router when we have a successful composition
         let mut router_runner = RouterRunner::new(
             router_config_handler.get_supergraph_schema_path(),
@@ -104,21 +167,37 @@ impl LeaderSession {
             override_install_path,
             client_config.clone(),
         );
+        if router_internal_addr != router_socket_addr {
+            router_runner.set_internal_address(router_internal_addr);
+        }
+        if let Some(router_version) = router_version {
+            router_runner.set_router_version(router_version);
+        }
 
         // install plugins before proceeding
-        let federation_version = match &*OVERRIDE_DEV_COMPOSITION_VERSION {
-            Some(version) => FederationVersion::ExactFedTwo(
-                Version::parse(version)
-                    .with_context(|| format!("could not parse composition version: {version}"))?,
-            ),
-            None => FederationVersion::LatestFedTwo,
-        };
-
         router_runner.maybe_install_router()?;
-        compose_runner.maybe_install_supergraph(federation_version.clone())?;
+        if let Err(e) = compose_runner.maybe_install_supergraph(federation_version.clone()) {
+            // without the plugin we can still start the router with the last supergraph we composed
+            if !compose_runner.use_last_known_supergraph() {
+                return Err(e);
+            }
+            eprintln!(
+                "{} Could not install the `supergraph` plugin: {}",
+                Style::WarningPrefix.paint("WARN:"),
+                e.message()
+            );
+        }
 
         router_config_handler.start()?;
 
+        // record the session so `rover dev list`, `stop`, and `attach` can find it by its port
+        let registry = SessionRegistry::new(&client_config.config);
+        registry.register(&RegisteredSession {
+            router_address: router_socket_addr,
+            ipc_socket_addr: ipc_socket_addr.clone(),
+            pid: std::process::id(),
+        })?;
+
         Ok(Some(Self {
             subgraphs: HashMap::new(),
             ipc_socket_addr,
@@ -127,9 +206,103 @@ impl LeaderSession {
             follower_channel,
             leader_channel,
             federation_version,
+            session_lock,
+            registry,
+            router_socket_addr,
+            health: SessionHealthHandle::new(router_socket_addr),
+            batch_window: None,
+            access_log: None,
+            subgraph_proxies: HashMap::new(),
         }))
     }
 
+    /// Checks whether a main session is already listening on `ipc_socket_addr`.
+    /// Returns true if this process should attach to it.
+    fn attach_to_running_session(ipc_socket_addr: &str) -> RoverResult<bool> {
+        let Ok(stream) = ipc_connect(ipc_socket_addr) else {
+            return Ok(false);
+        };
+        // write to the socket so we don't make the other session deadlock waiting on a message
+        let mut stream = BufReader::new(stream);
+        socket_write(&FollowerMessage::health_check(false)?, &mut stream)?;
+        if let Err(e) = LeaderSession::socket_read(&mut stream) {
+            let mut err = RoverError::new(anyhow!(
+                "The `rover dev` session at '{}' is not responding: {}",
+                ipc_socket_addr,
+                e
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "If a previous `rover dev` process crashed, rerun this command with `--force` to replace its session.".to_string(),
+            ));
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    /// Waits for the main `rover dev` process `pid`, which owns the session but is still
+    /// starting up, to listen on `ipc_socket_addr` so this process can attach to it.
+    fn wait_for_starting_session(
+        ipc_socket_addr: &str,
+        router_socket_addr: SocketAddr,
+        pid: u32,
+    ) -> RoverResult<()> {
+        eprintln!(
+            "another `rover dev` process (PID {}) is starting a session for '{}', waiting to attach to it...",
+            pid, router_socket_addr
+        );
+        let started = Instant::now();
+        while started.elapsed() < SESSION_STARTUP_TIMEOUT {
+            if Self::attach_to_running_session(ipc_socket_addr)? {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        let mut err = RoverError::new(anyhow!(
+            "The `rover dev` session for '{}' belongs to another process (PID {}), but it isn't accepting connections.",
+            router_socket_addr,
+            pid
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "Wait for PID {} to finish starting, then rerun this command to attach to its session. Otherwise, stop PID {} or use a different `--supergraph-port`.",
+            pid, pid
+        )));
+        Err(err)
+    }
+
+    /// Composes subgraph changes that arrive within `batch_window` of each other together,
+    /// instead of recomposing for each change in the order they arrive.
+    pub fn batch_changes_within(&mut self, batch_window: Duration) {
+        self.batch_window = Some(batch_window).filter(|window| !window.is_zero());
+    }
+
+    /// Sets how long the router has to shut down on its own before it's killed.
+    pub fn set_shutdown_grace_period(&mut self, shutdown_grace_period: Duration) {
+        self.router_runner
+            .set_shutdown_grace_period(shutdown_grace_period);
+    }
+
+    /// Sets the directory the router runs in, and the environment variables it gets.
+    pub fn set_router_environment(&mut self, router_environment: TaskEnvironment) {
+        self.router_runner
+            .set_router_environment(router_environment);
+    }
+
+    /// Points composition errors and hints at the files that subgraph schemas are read from.
+    pub fn set_source_map(&mut self, source_map: SubgraphSourceMap) {
+        self.compose_runner.set_source_map(source_map);
+    }
+
+    /// Records the operations the router executes, and the subgraph requests it makes for them.
+    /// The access log's proxy in front of the router must already be serving.
+    pub fn set_access_log(&mut self, access_log: AccessLog) {
+        self.access_log = Some(access_log);
+    }
+
+    /// Serves the session's health endpoint on `addr`, returning the address it's bound to.
+    pub fn serve_health_endpoint(&self, addr: SocketAddr) -> RoverResult<SocketAddr> {
+        self.health.serve(addr)
+    }
+
     /// Start the session by watching for incoming subgraph updates and re-composing when needed
     pub fn listen_for_all_subgraph_updates(&mut self, ready_sender: Sender<()>) -> RoverResult<()> {
         self.receive_messages_from_attached_sessions()?;
@@ -141,26 +314,47 @@ impl LeaderSession {
         ready_sender.send(()).unwrap();
         loop {
             tracing::trace!("main session waiting for follower message");
-            let follower_message = self.follower_channel.receiver.recv().unwrap();
-            let leader_message = self.handle_follower_message_kind(follower_message.kind());
+            let follower_messages = self.receive_follower_messages();
+            let leader_messages = self.handle_follower_messages(&follower_messages);
 
-            if !follower_message.is_from_main_session() {
-                leader_message.print();
+            for (follower_message, leader_message) in follower_messages.iter().zip(leader_messages)
+            {
+                if !follower_message.is_from_main_session() {
+                    leader_message.print();
+                }
+                let debug_message = format!("could not send message {:?}", &leader_message);
+                tracing::trace!("main session sending leader message");
+
+                self.leader_channel
+                    .sender
+                    .send(leader_message)
+                    .expect(&debug_message);
+                tracing::trace!("main session sent leader message");
             }
-            let debug_message = format!("could not send message {:?}", &leader_message);
-            tracing::trace!("main session sending leader message");
-
-            self.leader_channel
-                .sender
-                .send(leader_message)
-                .expect(&debug_message);
-            tracing::trace!("main session sent leader message");
         }
     }
 
+    /// Waits for the next follower message. When it changes a subgraph, this keeps collecting
+    /// subgraph changes until none arrive for the batching window, so they're composed together.
+    fn receive_follower_messages(&self) -> Vec<FollowerMessage> {
+        let mut follower_messages = vec![self.follower_channel.receiver.recv().unwrap()];
+        if let Some(batch_window) = self.batch_window {
+            while follower_messages
+                .last()
+                .map_or(false, |message| message.kind().is_subgraph_change())
+            {
+                match self.follower_channel.receiver.recv_timeout(batch_window) {
+                    Ok(follower_message) => follower_messages.push(follower_message),
+                    Err(_) => break,
+                }
+            }
+        }
+        follower_messages
+    }
+
     /// Listen on the socket for incoming [`FollowerMessageKind`] messages.
     fn receive_messages_from_attached_sessions(&self) -> RoverResult<()> {
-        let listener = LocalSocketListener::bind(&*self.ipc_socket_addr).with_context(|| {
+        let listener = ipc_bind(&self.ipc_socket_addr).with_context(|| {
             format!(
                 "could not start local socket server at {}",
                 &self.ipc_socket_addr
@@ -204,7 +398,7 @@ impl LeaderSession {
     }
 
     /// Adds a subgraph to the internal supergraph representation.
-    fn add_subgraph(&mut self, subgraph_entry: &SubgraphEntry) -> LeaderMessageKind {
+    fn add_subgraph(&mut self, subgraph_entry: &SubgraphEntry) -> SubgraphChange {
         let is_first_subgraph = self.subgraphs.is_empty();
         let ((name, url), sdl) = subgraph_entry;
         if self
@@ -212,44 +406,56 @@ impl LeaderSession {
             .get(&(name.to_string(), url.clone()))
             .is_some()
         {
-            LeaderMessageKind::error(
+            SubgraphChange::Unchanged(LeaderMessageKind::error(
                 RoverError::new(anyhow!(
                     "subgraph with name '{}' and url '{}' already exists",
                     &name,
                     &url
                 ))
                 .to_string(),
-            )
+            ))
         } else {
             self.subgraphs
                 .insert((name.to_string(), url.clone()), sdl.to_string());
-            let composition_result = self.compose();
-            if let Err(composition_err) = composition_result {
-                LeaderMessageKind::error(composition_err)
-            } else if composition_result.transpose().is_some() && !is_first_subgraph {
-                LeaderMessageKind::add_subgraph_composition_success(name)
+            events::emit(Event::SubgraphAdded {
+                subgraph: name.to_string(),
+                url: url.to_string(),
+            });
+            SubgraphChange::Changed(if is_first_subgraph {
+                LeaderMessageKind::message_received()
             } else {
-                LeaderMessageKind::MessageReceived
-            }
+                LeaderMessageKind::add_subgraph_composition_success(name)
+            })
         }
     }
 
     /// Updates a subgraph in the internal supergraph representation.
-    fn update_subgraph(&mut self, subgraph_entry: &SubgraphEntry) -> LeaderMessageKind {
+    fn update_subgraph(&mut self, subgraph_entry: &SubgraphEntry) -> SubgraphChange {
         let ((name, url), sdl) = &subgraph_entry;
+        // a subgraph that moved to a new URL replaces the entry at its old one
+        let moved_from = self
+            .subgraphs
+            .keys()
+            .find(|(existing_name, existing_url)| existing_name == name && existing_url != url)
+            .cloned();
+        if let Some(moved_from) = moved_from {
+            self.subgraphs.remove(&moved_from);
+            self.subgraphs
+                .insert((name.to_string(), url.clone()), sdl.to_string());
+            emit_subgraph_updated(name, url);
+            return SubgraphChange::Changed(
+                LeaderMessageKind::update_subgraph_composition_success(name),
+            );
+        }
         if let Some(prev_sdl) = self.subgraphs.get_mut(&(name.to_string(), url.clone())) {
             if prev_sdl != sdl {
                 *prev_sdl = sdl.to_string();
-                let composition_result = self.compose();
-                if let Err(composition_err) = composition_result {
-                    LeaderMessageKind::error(composition_err)
-                } else if composition_result.transpose().is_some() {
-                    LeaderMessageKind::update_subgraph_composition_success(name)
-                } else {
-                    LeaderMessageKind::message_received()
-                }
+                emit_subgraph_updated(name, url);
+                SubgraphChange::Changed(LeaderMessageKind::update_subgraph_composition_success(
+                    name,
+                ))
             } else {
-                LeaderMessageKind::message_received()
+                SubgraphChange::Unchanged(LeaderMessageKind::message_received())
             }
         } else {
             self.add_subgraph(subgraph_entry)
@@ -257,7 +463,7 @@ impl LeaderSession {
     }
 
     /// Removes a subgraph from the internal subgraph representation.
-    fn remove_subgraph(&mut self, subgraph_name: &SubgraphName) -> LeaderMessageKind {
+    fn remove_subgraph(&mut self, subgraph_name: &SubgraphName) -> SubgraphChange {
         let found = self
             .subgraphs
             .keys()
@@ -266,23 +472,59 @@ impl LeaderSession {
 
         if let Some((name, url)) = found {
             self.subgraphs.remove(&(name.to_string(), url));
-            let composition_result = self.compose();
-            if let Err(composition_err) = composition_result {
-                LeaderMessageKind::error(composition_err)
-            } else if composition_result.transpose().is_some() {
-                LeaderMessageKind::remove_subgraph_composition_success(&name)
-            } else {
-                LeaderMessageKind::message_received()
-            }
+            events::emit(Event::SubgraphRemoved {
+                subgraph: name.to_string(),
+            });
+            SubgraphChange::Changed(LeaderMessageKind::remove_subgraph_composition_success(
+                &name,
+            ))
         } else {
-            LeaderMessageKind::message_received()
+            SubgraphChange::Unchanged(LeaderMessageKind::message_received())
+        }
+    }
+
+    /// Recomposes once for all of `changes`, and returns the message to send in reply to each change.
+    ///
+    /// If composition fails, only the first reply reports the error, so it's only printed once.
+    fn compose_changes(&mut self, changes: Vec<SubgraphChange>) -> Vec<LeaderMessageKind> {
+        if !changes
+            .iter()
+            .any(|change| matches!(change, SubgraphChange::Changed(_)))
+        {
+            return changes
+                .into_iter()
+                .map(SubgraphChange::into_reply)
+                .collect();
         }
+        if changes.len() > 1 {
+            tracing::info!("recomposing once for {} subgraph changes", changes.len());
+        }
+        let composition_result = self.compose();
+        let mut reported_error = false;
+        changes
+            .into_iter()
+            .map(|change| match (change, &composition_result) {
+                (SubgraphChange::Unchanged(reply), _) => reply,
+                (SubgraphChange::Changed(_), Err(composition_err)) => {
+                    if reported_error {
+                        LeaderMessageKind::message_received()
+                    } else {
+                        reported_error = true;
+                        LeaderMessageKind::error(composition_err.clone())
+                    }
+                }
+                (SubgraphChange::Changed(success), Ok(Some(_))) => success,
+                (SubgraphChange::Changed(_), Ok(None)) => LeaderMessageKind::message_received(),
+            })
+            .collect()
     }
 
     /// Reruns composition, which triggers the router to reload.
     fn compose(&mut self) -> CompositionResult {
-        self.compose_runner
-            .run(&mut self.supergraph_config())
+        let mut supergraph_config = self.supergraph_config();
+        let composition_result = self
+            .compose_runner
+            .run(&mut supergraph_config)
             .and_then(|maybe_new_schema| {
                 if maybe_new_schema.is_some() {
                     if let Err(err) = self.router_runner.spawn() {
@@ -294,11 +536,16 @@ impl LeaderSession {
             .map_err(|e| {
                 let _ = self.router_runner.kill().map_err(log_err_and_continue);
                 e
-            })
+            });
+        self.session_lock
+            .record_router(self.router_runner.router_pid());
+        self.health
+            .record_composition(self.subgraphs.keys(), &composition_result);
+        composition_result
     }
 
     /// Reads a [`FollowerMessage`] from an open socket connection.
-    fn socket_read(stream: &mut BufReader<LocalSocketStream>) -> RoverResult<FollowerMessage> {
+    fn socket_read(stream: &mut BufReader<IpcStream>) -> RoverResult<FollowerMessage> {
         socket_read(stream)
             .map(|message| {
                 tracing::debug!("leader received message {:?}", &message);
@@ -313,7 +560,7 @@ impl LeaderSession {
     /// Writes a [`LeaderMessageKind`] to an open socket connection.
     fn socket_write(
         message: LeaderMessageKind,
-        stream: &mut BufReader<LocalSocketStream>,
+        stream: &mut BufReader<IpcStream>,
     ) -> RoverResult<()> {
         tracing::debug!("leader sending message {:?}", message);
         socket_write(&message, stream)
@@ -321,17 +568,44 @@ impl LeaderSession {
 
     /// Gets the supergraph configuration from the internal state.
     /// Calling `.to_string()` on a [`SupergraphConfig`] writes
-    fn supergraph_config(&self) -> SupergraphConfig {
-        let mut supergraph_config: SupergraphConfig = self
-            .subgraphs
-            .iter()
-            .map(|((name, url), sdl)| SubgraphDefinition::new(name, url.to_string(), sdl))
-            .collect::<Vec<SubgraphDefinition>>()
-            .into();
+    fn supergraph_config(&mut self) -> SupergraphConfig {
+        let mut subgraph_definitions = Vec::new();
+        for (subgraph_key, sdl) in self.subgraphs.clone() {
+            let routing_url = self.routing_url(&subgraph_key);
+            subgraph_definitions.push(SubgraphDefinition::new(
+                subgraph_key.0,
+                routing_url.to_string(),
+                sdl,
+            ));
+        }
+        let mut supergraph_config: SupergraphConfig = subgraph_definitions.into();
         supergraph_config.set_federation_version(self.federation_version.clone());
         supergraph_config
     }
 
+    /// The URL the router should send a subgraph's requests to, which is a proxy in front of it
+    /// when operations are logged.
+    fn routing_url(&mut self, subgraph_key: &SubgraphKey) -> Url {
+        let (name, url) = subgraph_key;
+        let Some(access_log) = &self.access_log else {
+            return url.clone();
+        };
+        if let Some(proxy_url) = self.subgraph_proxies.get(subgraph_key) {
+            return proxy_url.clone();
+        }
+        match access_log.start_subgraph_proxy(name, url) {
+            Ok(proxy_url) => {
+                self.subgraph_proxies
+                    .insert(subgraph_key.clone(), proxy_url.clone());
+                proxy_url
+            }
+            Err(e) => {
+                let _ = log_err_and_continue(e);
+                url.clone()
+            }
+        }
+    }
+
     /// Gets the list of subgraphs running in this session
     fn get_subgraphs(&self) -> SubgraphKeys {
         tracing::debug!("notifying new `rover dev` process about existing subgraphs");
@@ -341,10 +615,51 @@ impl LeaderSession {
     /// Shuts the router down, removes the socket file, and exits the process.
     pub fn shutdown(&mut self) {
         let _ = self.router_runner.kill().map_err(log_err_and_continue);
-        let _ = std::fs::remove_file(&self.ipc_socket_addr);
+        remove_socket(&self.ipc_socket_addr);
+        self.registry.unregister(self.router_socket_addr);
+        self.session_lock.release();
+        events::emit(Event::SessionEnded);
         std::process::exit(1)
     }
 
+    /// Handles a batch of follower messages, composing once for all of the subgraph changes in it,
+    /// and returns a [`LeaderMessageKind`] in reply to each message
+    fn handle_follower_messages(
+        &mut self,
+        follower_messages: &[FollowerMessage],
+    ) -> Vec<LeaderMessageKind> {
+        let mut leader_messages = Vec::with_capacity(follower_messages.len());
+        let mut changes = Vec::new();
+        for follower_message in follower_messages {
+            match self.apply_subgraph_change(follower_message.kind()) {
+                Some(change) => changes.push(change),
+                None => {
+                    // compose any earlier changes before handling a message that isn't one
+                    leader_messages.extend(self.compose_changes(std::mem::take(&mut changes)));
+                    leader_messages
+                        .push(self.handle_follower_message_kind(follower_message.kind()));
+                }
+            }
+        }
+        leader_messages.extend(self.compose_changes(changes));
+        leader_messages
+    }
+
+    /// Updates the internal subgraph representation if `follower_message` changes a subgraph.
+    /// Returns `None` for messages that don't change subgraphs.
+    fn apply_subgraph_change(
+        &mut self,
+        follower_message: &FollowerMessageKind,
+    ) -> Option<SubgraphChange> {
+        use FollowerMessageKind::*;
+        match follower_message {
+            AddSubgraph { subgraph_entry } => Some(self.add_subgraph(subgraph_entry)),
+            UpdateSubgraph { subgraph_entry } => Some(self.update_subgraph(subgraph_entry)),
+            RemoveSubgraph { subgraph_name } => Some(self.remove_subgraph(subgraph_name)),
+            _ => None,
+        }
+    }
+
     /// Handles a follower message by updating the internal subgraph representation if needed,
     /// and returns a [`LeaderMessageKind`] that can be sent over a socket or printed by the main session
     fn handle_follower_message_kind(
@@ -353,11 +668,12 @@ impl LeaderSession {
     ) -> LeaderMessageKind {
         use FollowerMessageKind::*;
         match follower_message {
-            AddSubgraph { subgraph_entry } => self.add_subgraph(subgraph_entry),
-
-            UpdateSubgraph { subgraph_entry } => self.update_subgraph(subgraph_entry),
-
-            RemoveSubgraph { subgraph_name } => self.remove_subgraph(subgraph_name),
+            AddSubgraph { .. } | UpdateSubgraph { .. } | RemoveSubgraph { .. } => {
+                let change = self.apply_subgraph_change(follower_message);
+                self.compose_changes(change.into_iter().collect())
+                    .pop()
+                    .unwrap_or_else(LeaderMessageKind::message_received)
+            }
 
             GetSubgraphs => LeaderMessageKind::current_subgraphs(self.get_subgraphs()),
 
@@ -373,6 +689,30 @@ impl LeaderSession {
     }
 }
 
+fn emit_subgraph_updated(name: &str, url: &Url) {
+    events::emit(Event::SubgraphUpdated {
+        subgraph: name.to_string(),
+        url: url.to_string(),
+    });
+}
+
+/// A change to the session's subgraphs, before the supergraph is recomposed
+#[derive(Debug)]
+enum SubgraphChange {
+    /// The subgraphs changed, and this is the reply to send if composition succeeds
+    Changed(LeaderMessageKind),
+    /// The subgraphs didn't change, and this is the reply to send right away
+    Unchanged(LeaderMessageKind),
+}
+
+impl SubgraphChange {
+    fn into_reply(self) -> LeaderMessageKind {
+        match self {
+            Self::Changed(reply) | Self::Unchanged(reply) => reply,
+        }
+    }
+}
+
 impl Drop for LeaderSession {
     fn drop(&mut self) {
         self.shutdown();
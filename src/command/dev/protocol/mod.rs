@@ -1,9 +1,16 @@
 mod follower;
 mod leader;
+#[cfg(windows)]
+mod named_pipe;
+mod registry;
+mod session_lock;
 mod socket;
+mod tcp;
 mod types;
 
 pub use follower::*;
 pub use leader::*;
+pub(crate) use registry::{RegisteredSession, SessionRegistry};
+pub(crate) use session_lock::{force_shutdown, session_state, SessionState};
 pub(crate) use socket::*;
 pub use types::*;
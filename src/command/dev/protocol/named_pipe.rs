@@ -0,0 +1,289 @@
+//! Named pipes for `rover dev` sessions on Windows, restricted to the current user.
+//!
+//! By default, a named pipe can be opened by other accounts on the machine, which would
+//! let them attach subgraphs to (or read subgraphs from) someone else's `rover dev` session.
+//! Pipes created here only grant access to the user that created them, and both ends of
+//! a connection check that the process on the other end belongs to the same user.
+
+use std::ffi::c_void;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::windows::io::{AsRawHandle, FromRawHandle};
+use std::ptr;
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+use windows_sys::Win32::Foundation::{
+    CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_PIPE_CONNECTED, HANDLE,
+    INVALID_HANDLE_VALUE,
+};
+use windows_sys::Win32::Security::Authorization::{
+    ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows_sys::Win32::Security::{
+    GetTokenInformation, TokenUser, PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES, TOKEN_QUERY,
+    TOKEN_USER,
+};
+use windows_sys::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+use windows_sys::Win32::System::Memory::LocalFree;
+use windows_sys::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, GetNamedPipeClientProcessId,
+    GetNamedPipeServerProcessId, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE,
+    PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows_sys::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+const BUFFER_SIZE: u32 = 64 * 1024;
+
+/// Listens for connections on a named pipe that only the current user can access.
+#[derive(Debug)]
+pub(crate) struct OwnerOnlyPipeListener {
+    name: Vec<u16>,
+    security_descriptor: PSECURITY_DESCRIPTOR,
+    user_sid: String,
+    /// The pipe instance waiting for the next connection
+    pending: AtomicIsize,
+}
+
+// the security descriptor is only read after it's created, and freed on drop
+unsafe impl Send for OwnerOnlyPipeListener {}
+unsafe impl Sync for OwnerOnlyPipeListener {}
+
+impl OwnerOnlyPipeListener {
+    /// Creates the pipe for `ipc_socket_addr`, failing if a pipe with that name already exists,
+    /// since another process (possibly belonging to another user) would be listening on it.
+    pub(crate) fn bind(ipc_socket_addr: &str) -> io::Result<Self> {
+        let user_sid = current_user_sid()?;
+        // D:P => a protected DACL that doesn't inherit any other entries
+        // (A;;GA;;;<sid>) => allow all access to the current user, and no one else
+        let sddl = to_wide(&format!("D:P(A;;GA;;;{})", user_sid));
+        let mut security_descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+        if unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1,
+                &mut security_descriptor,
+                ptr::null_mut(),
+            )
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        let listener = Self {
+            name: to_wide(&pipe_path(ipc_socket_addr)),
+            security_descriptor,
+            user_sid,
+            pending: AtomicIsize::new(INVALID_HANDLE_VALUE),
+        };
+        let first_instance = listener.create_instance(true).map_err(|e| {
+            if e.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+                io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!(
+                        "the named pipe {} already exists and is owned by another process",
+                        pipe_path(ipc_socket_addr)
+                    ),
+                )
+            } else {
+                e
+            }
+        })?;
+        listener.pending.store(first_instance, Ordering::SeqCst);
+        Ok(listener)
+    }
+
+    /// Waits for a connection from a process that belongs to the current user.
+    pub(crate) fn accept(&self) -> io::Result<File> {
+        let instance = self.pending.swap(INVALID_HANDLE_VALUE, Ordering::SeqCst);
+        let instance = if instance == INVALID_HANDLE_VALUE {
+            self.create_instance(false)?
+        } else {
+            instance
+        };
+        if unsafe { ConnectNamedPipe(instance, ptr::null_mut()) } == 0
+            && unsafe { GetLastError() } != ERROR_PIPE_CONNECTED
+        {
+            let error = io::Error::last_os_error();
+            unsafe { CloseHandle(instance) };
+            return Err(error);
+        }
+        // have the next instance ready before handling this connection
+        if let Ok(next_instance) = self.create_instance(false) {
+            self.pending.store(next_instance, Ordering::SeqCst);
+        }
+
+        let mut client_process_id = 0;
+        let client_sid =
+            if unsafe { GetNamedPipeClientProcessId(instance, &mut client_process_id) } == 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                process_user_sid(client_process_id)
+            };
+        match client_sid {
+            Ok(client_sid) if client_sid == self.user_sid => {
+                Ok(unsafe { File::from_raw_handle(instance as _) })
+            }
+            client_sid => {
+                unsafe {
+                    DisconnectNamedPipe(instance);
+                    CloseHandle(instance);
+                }
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "refused a connection from a process that doesn't belong to the current user ({})",
+                        client_sid.unwrap_or_else(|e| e.to_string())
+                    ),
+                ))
+            }
+        }
+    }
+
+    fn create_instance(&self, first: bool) -> io::Result<HANDLE> {
+        let security_attributes = SECURITY_ATTRIBUTES {
+            nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+            lpSecurityDescriptor: self.security_descriptor,
+            bInheritHandle: 0,
+        };
+        let open_mode = if first {
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE
+        } else {
+            PIPE_ACCESS_DUPLEX
+        };
+        let instance = unsafe {
+            CreateNamedPipeW(
+                self.name.as_ptr(),
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                &security_attributes,
+            )
+        };
+        if instance == INVALID_HANDLE_VALUE {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(instance)
+        }
+    }
+}
+
+impl Drop for OwnerOnlyPipeListener {
+    fn drop(&mut self) {
+        let pending = self.pending.swap(INVALID_HANDLE_VALUE, Ordering::SeqCst);
+        unsafe {
+            if pending != INVALID_HANDLE_VALUE {
+                CloseHandle(pending);
+            }
+            LocalFree(self.security_descriptor as _);
+        }
+    }
+}
+
+/// Connects to the `rover dev` session listening on `ipc_socket_addr`, refusing to
+/// talk to it if the session belongs to another user.
+pub(crate) fn connect(ipc_socket_addr: &str) -> io::Result<File> {
+    let pipe = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(pipe_path(ipc_socket_addr))?;
+    let mut server_process_id = 0;
+    if unsafe { GetNamedPipeServerProcessId(pipe.as_raw_handle() as _, &mut server_process_id) }
+        == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    if process_user_sid(server_process_id)? != current_user_sid()? {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "the `rover dev` session at {} belongs to another user",
+                pipe_path(ipc_socket_addr)
+            ),
+        ));
+    }
+    Ok(pipe)
+}
+
+/// Maps an address from `RouterConfigHandler::get_ipc_address` to a pipe path,
+/// the same way `interprocess` maps namespaced local socket names.
+fn pipe_path(ipc_socket_addr: &str) -> String {
+    format!(r"\\.\pipe\{}", ipc_socket_addr.trim_start_matches('@'))
+}
+
+fn current_user_sid() -> io::Result<String> {
+    token_user_sid(unsafe { GetCurrentProcess() })
+}
+
+fn process_user_sid(process_id: u32) -> io::Result<String> {
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id) };
+    if process == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let sid = token_user_sid(process);
+    unsafe { CloseHandle(process) };
+    sid
+}
+
+/// Returns the SID of the user that `process` runs as, e.g. `S-1-5-21-...`.
+fn token_user_sid(process: HANDLE) -> io::Result<String> {
+    let mut token: HANDLE = 0;
+    if unsafe { OpenProcessToken(process, TOKEN_QUERY, &mut token) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let sid = (|| {
+        let mut length = 0;
+        unsafe { GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut length) };
+        // use u64s so the buffer is aligned for TOKEN_USER
+        let mut buffer = vec![0u64; (length as usize + 7) / 8];
+        if unsafe {
+            GetTokenInformation(
+                token,
+                TokenUser,
+                buffer.as_mut_ptr() as *mut c_void,
+                length,
+                &mut length,
+            )
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        let token_user = unsafe { &*(buffer.as_ptr() as *const TOKEN_USER) };
+        let mut sid_string = ptr::null_mut();
+        if unsafe { ConvertSidToStringSidW(token_user.User.Sid, &mut sid_string) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let sid = unsafe { from_wide(sid_string) };
+        unsafe { LocalFree(sid_string as _) };
+        Ok(sid)
+    })();
+    unsafe { CloseHandle(token) };
+    sid
+}
+
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Reads a null-terminated UTF-16 string.
+unsafe fn from_wide(value: *const u16) -> String {
+    let length = (0..).take_while(|&i| *value.add(i) != 0).count();
+    String::from_utf16_lossy(std::slice::from_raw_parts(value, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pipe_path;
+
+    #[test]
+    fn it_maps_namespaced_addresses_to_pipe_paths() {
+        assert_eq!(
+            pipe_path("@supergraph-127.0.0.1:4000.sock"),
+            r"\\.\pipe\supergraph-127.0.0.1:4000.sock"
+        );
+    }
+}
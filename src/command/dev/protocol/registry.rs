@@ -0,0 +1,175 @@
+//! Records the `rover dev` sessions running on this machine, so that `rover dev list`, `stop`,
+//! and `attach` can find a session by its router's port without knowing where its socket is.
+
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use houston::Config;
+use rover_std::Fs;
+use serde::{Deserialize, Serialize};
+
+use std::net::SocketAddr;
+
+use super::session_lock::{session_state, SessionState};
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// A session's entry in the registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RegisteredSession {
+    /// The address the session's router listens on
+    pub(crate) router_address: SocketAddr,
+    /// The socket that attached `rover dev` processes talk to the session over
+    pub(crate) ipc_socket_addr: String,
+    /// The main `rover dev` process
+    pub(crate) pid: u32,
+}
+
+/// A directory in Rover's config home with a file for each running session.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionRegistry {
+    dir: Utf8PathBuf,
+}
+
+impl SessionRegistry {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            dir: config.home.join("dev-sessions"),
+        }
+    }
+
+    fn path(&self, router_address: SocketAddr) -> Utf8PathBuf {
+        // Windows doesn't allow colons in file names
+        self.dir
+            .join(format!("{}.json", router_address).replace(':', "_"))
+    }
+
+    pub(crate) fn register(&self, session: &RegisteredSession) -> RoverResult<()> {
+        Fs::create_dir_all(&self.dir)?;
+        Fs::write_file(
+            self.path(session.router_address),
+            serde_json::to_string(session)?,
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn unregister(&self, router_address: SocketAddr) {
+        let _ = std::fs::remove_file(self.path(router_address));
+    }
+
+    /// The sessions whose main process is still running, sorted by their router's address.
+    /// Entries left behind by sessions that crashed are removed.
+    pub(crate) fn list(&self) -> Vec<RegisteredSession> {
+        let Ok(entries) = Fs::get_dir_entries(&self.dir) else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<RegisteredSession> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension() == Some("json"))
+            .filter_map(|entry| {
+                let session = Fs::read_file(entry.path())
+                    .ok()
+                    .and_then(|contents| serde_json::from_str::<RegisteredSession>(&contents).ok())
+                    .filter(|session| {
+                        session_state(&session.ipc_socket_addr)
+                            == SessionState::Running(session.pid)
+                    });
+                if session.is_none() {
+                    tracing::info!("removing the stale session entry at {}", entry.path());
+                    let _ = std::fs::remove_file(entry.path());
+                }
+                session
+            })
+            .collect();
+        sessions.sort_by_key(|session| session.router_address);
+        sessions
+    }
+
+    /// Finds a running session by its router's port, or by its router's full address for when
+    /// sessions on different addresses share a port.
+    pub(crate) fn find(&self, session: &str) -> RoverResult<RegisteredSession> {
+        let matches: Vec<RegisteredSession> = self
+            .list()
+            .into_iter()
+            .filter(|registered| {
+                registered.router_address.to_string() == session
+                    || registered.router_address.port().to_string() == session
+            })
+            .collect();
+        match matches.as_slice() {
+            [registered] => Ok(registered.clone()),
+            [] => {
+                let mut err = RoverError::new(anyhow!(
+                    "There isn't a `rover dev` session running on '{}'.",
+                    session
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Run `rover dev list` to see the sessions running on this machine.".to_string(),
+                ));
+                Err(err)
+            }
+            [first, ..] => {
+                let mut err = RoverError::new(anyhow!(
+                    "More than one `rover dev` session is running on port {}.",
+                    session
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Pass the session's full address instead, like `{}`.",
+                    first.router_address
+                )));
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::Config;
+
+    use super::{RegisteredSession, SessionRegistry};
+
+    #[test]
+    fn it_lists_and_finds_running_sessions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let home = Utf8PathBuf::try_from(tmp_dir.path().to_path_buf()).unwrap();
+        let registry = SessionRegistry::new(&Config {
+            home: home.clone(),
+            override_api_key: None,
+        });
+
+        let running = RegisteredSession {
+            router_address: "127.0.0.1:4000".parse().unwrap(),
+            ipc_socket_addr: home.join("running.sock").to_string(),
+            pid: std::process::id(),
+        };
+        std::fs::write(
+            format!("{}.lock", running.ipc_socket_addr),
+            format!(r#"{{"pid":{},"router_pid":null}}"#, running.pid),
+        )
+        .unwrap();
+        registry.register(&running).unwrap();
+
+        // PIDs are at most 2^22 on Linux, and smaller elsewhere, so this one can't be running
+        let crashed = RegisteredSession {
+            router_address: "127.0.0.1:4001".parse().unwrap(),
+            ipc_socket_addr: home.join("crashed.sock").to_string(),
+            pid: 4194305,
+        };
+        std::fs::write(
+            format!("{}.lock", crashed.ipc_socket_addr),
+            r#"{"pid":4194305,"router_pid":null}"#,
+        )
+        .unwrap();
+        registry.register(&crashed).unwrap();
+
+        assert_eq!(registry.list(), vec![running.clone()]);
+        assert!(!registry.path(crashed.router_address).exists());
+        assert_eq!(registry.find("4000").unwrap(), running);
+        assert_eq!(registry.find("127.0.0.1:4000").unwrap(), running);
+        assert!(registry.find("4001").is_err());
+
+        registry.unregister(running.router_address);
+        assert!(registry.list().is_empty());
+    }
+}
@@ -0,0 +1,517 @@
+//! Records which processes own a `rover dev` session, so that `rover dev --force` can tell
+//! whether a session was orphaned by a crash and clean up after it.
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_std::Fs;
+use serde::{Deserialize, Serialize};
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use super::socket::remove_stale_socket;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How long to wait for a leftover router to exit after it's been killed.
+const ROUTER_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The processes that belong to a main `rover dev` session.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SessionOwners {
+    /// The main `rover dev` process
+    pid: u32,
+    /// The router spawned by the main `rover dev` process, if it's running
+    router_pid: Option<u32>,
+}
+
+/// A lock file next to the session's socket, owned by the main `rover dev` process.
+/// Since the socket's name includes the router's address, so does the lock's.
+#[derive(Debug)]
+pub(crate) struct SessionLock {
+    path: Utf8PathBuf,
+    owners: SessionOwners,
+}
+
+/// The result of trying to become the main `rover dev` process for a session.
+#[derive(Debug)]
+pub(crate) enum SessionLockStatus {
+    /// The current process owns the session
+    Acquired(SessionLock),
+    /// The session is owned by another `rover dev` process that's still running
+    OwnedBy(u32),
+}
+
+impl SessionLock {
+    /// Tries to record the current process as the owner of the session listening on
+    /// `ipc_socket_addr`. A lock left behind by a main process that has exited is replaced.
+    pub(crate) fn acquire(ipc_socket_addr: &str) -> RoverResult<SessionLockStatus> {
+        let path = lock_path(ipc_socket_addr);
+        let owners = SessionOwners {
+            pid: std::process::id(),
+            router_pid: None,
+        };
+        // write the lock somewhere else first, so other processes never read a partial lock
+        let pending_path = Utf8PathBuf::from(format!("{}.{}", path, owners.pid));
+        write_owners(&pending_path, &owners)?;
+        let owned_by = link_lock(&pending_path, &path);
+        let _ = std::fs::remove_file(&pending_path);
+        // only a lock we acquired is released on drop, so a lock that belongs to another
+        // session is never removed here
+        Ok(match owned_by? {
+            None => SessionLockStatus::Acquired(Self { path, owners }),
+            Some(pid) => SessionLockStatus::OwnedBy(pid),
+        })
+    }
+
+    /// Records the router that the session is currently running, if any.
+    pub(crate) fn record_router(&mut self, router_pid: Option<u32>) {
+        if self.owners.router_pid != router_pid {
+            self.owners.router_pid = router_pid;
+            if let Err(e) = write_owners(&self.path, &self.owners) {
+                tracing::warn!("could not update the session lock at {}: {}", self.path, e);
+            }
+        }
+    }
+
+    /// Removes the lock file when the session shuts down.
+    pub(crate) fn release(&self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Links the lock written to `pending_path` into place at `path`. Returns the PID of the
+/// `rover dev` process that owns the lock if it isn't the current one.
+fn link_lock(pending_path: &Utf8Path, path: &Utf8Path) -> RoverResult<Option<u32>> {
+    // a lock left behind by a crashed session is removed, then we try again once
+    for _ in 0..2 {
+        match std::fs::hard_link(pending_path, path) {
+            Ok(()) => return Ok(None),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => match read_owners(path) {
+                Some(owners) if process::is_running(owners.pid) => return Ok(Some(owners.pid)),
+                Some(SessionOwners {
+                    pid,
+                    router_pid: Some(router_pid),
+                }) if process::is_router(router_pid) => {
+                    let mut err = RoverError::new(anyhow!(
+                        "A previous `rover dev` process (PID {}) exited without stopping its router (PID {}).",
+                        pid,
+                        router_pid
+                    ));
+                    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                        "Rerun this command with `--force` to stop the router and start a new session.".to_string(),
+                    ));
+                    return Err(err);
+                }
+                _ => {
+                    tracing::info!("removing the stale session lock at {}", path);
+                    let _ = std::fs::remove_file(path);
+                }
+            },
+            Err(e) => {
+                return Err(RoverError::new(anyhow!(
+                    "Could not create the session lock at '{}': {}",
+                    path,
+                    e
+                )))
+            }
+        }
+    }
+    Err(RoverError::new(anyhow!(
+        "Could not create the session lock at '{}' because other `rover dev` processes keep replacing it.",
+        path
+    )))
+}
+
+fn write_owners(path: &Utf8Path, owners: &SessionOwners) -> RoverResult<()> {
+    Fs::write_file(path, serde_json::to_string(owners)?)?;
+    Ok(())
+}
+
+/// Cleans up after a main `rover dev` session that exited without shutting down, so that a new
+/// session can start on `ipc_socket_addr`. This kills the router the session left behind, and
+/// removes its socket and lock file.
+///
+/// Fails if the process that owns the session is still running.
+pub(crate) fn reclaim_orphaned_session(ipc_socket_addr: &str) -> RoverResult<()> {
+    let path = lock_path(ipc_socket_addr);
+    if let Some(owners) = read_owners(&path) {
+        if owners.pid != std::process::id() && process::is_running(owners.pid) {
+            let mut err = RoverError::new(anyhow!(
+                "The `rover dev` session at '{}' is still running as PID {}.",
+                ipc_socket_addr,
+                owners.pid
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "`--force` only replaces sessions whose main process has exited. Stop PID {} or use a different `--supergraph-port`.",
+                owners.pid
+            )));
+            return Err(err);
+        }
+        if let Some(router_pid) = owners.router_pid {
+            if process::is_router(router_pid) {
+                eprintln!(
+                    "killing the router (PID {}) left behind by a previous `rover dev` session",
+                    router_pid
+                );
+                process::kill(router_pid);
+                wait_for_exit(router_pid)?;
+            }
+        }
+    }
+    remove_stale_socket(ipc_socket_addr)?;
+    if path.exists() {
+        tracing::info!("removing the session lock at {}", path);
+        std::fs::remove_file(&path).map_err(|e| {
+            RoverError::new(anyhow!(
+                "Could not remove the session lock at '{}': {}",
+                path,
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// Whether a `rover dev` session is using `ipc_socket_addr`, according to its lock file and socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionState {
+    /// There's no lock or socket for a session on the address
+    Free,
+    /// The session's main `rover dev` process is still running
+    Running(u32),
+    /// The session was left behind by a main process that exited, along with its router if that's
+    /// still running. `pid` is `None` when only the socket was left behind.
+    Orphaned {
+        pid: Option<u32>,
+        router_pid: Option<u32>,
+    },
+}
+
+/// Checks whether a `rover dev` session is using `ipc_socket_addr`, without changing anything.
+pub(crate) fn session_state(ipc_socket_addr: &str) -> SessionState {
+    match read_owners(&lock_path(ipc_socket_addr)) {
+        Some(owners) if process::is_running(owners.pid) => SessionState::Running(owners.pid),
+        Some(owners) => SessionState::Orphaned {
+            pid: Some(owners.pid),
+            router_pid: owners.router_pid.filter(|pid| process::is_router(*pid)),
+        },
+        // namespaced sockets don't live on the filesystem, so only socket files can be left behind
+        None if Utf8Path::new(ipc_socket_addr).exists() => SessionState::Orphaned {
+            pid: None,
+            router_pid: None,
+        },
+        None => SessionState::Free,
+    }
+}
+
+/// Stops the current process's session on `ipc_socket_addr` without waiting on anything, for when
+/// a graceful shutdown hangs. This kills the router recorded in the lock file, and removes the
+/// session's socket and lock file.
+pub(crate) fn force_shutdown(ipc_socket_addr: &str) {
+    let path = lock_path(ipc_socket_addr);
+    // leave the lock alone if it belongs to another session
+    if let Some(owners) = read_owners(&path).filter(|owners| owners.pid == std::process::id()) {
+        if let Some(router_pid) = owners.router_pid {
+            if process::is_router(router_pid) {
+                tracing::info!("killing the router (PID {})", router_pid);
+                process::kill(router_pid);
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+    let _ = std::fs::remove_file(ipc_socket_addr);
+}
+
+fn wait_for_exit(pid: u32) -> RoverResult<()> {
+    let started = Instant::now();
+    while process::is_running(pid) {
+        if started.elapsed() > ROUTER_EXIT_TIMEOUT {
+            return Err(RoverError::new(anyhow!(
+                "The router left behind by a previous `rover dev` session (PID {}) did not exit after {}s.",
+                pid,
+                ROUTER_EXIT_TIMEOUT.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+fn read_owners(path: &Utf8Path) -> Option<SessionOwners> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents)
+        .map_err(|e| tracing::warn!("ignoring the unreadable session lock at {}: {}", path, e))
+        .ok()
+}
+
+/// The lock file for the session listening on `ipc_socket_addr`. Namespaced sockets don't live
+/// on the filesystem, so their lock files go in the temp directory instead.
+fn lock_path(ipc_socket_addr: &str) -> Utf8PathBuf {
+    match ipc_socket_addr.strip_prefix('@') {
+        Some(socket_name) => {
+            let temp_dir = Utf8PathBuf::try_from(std::env::temp_dir())
+                .unwrap_or_else(|_| Utf8PathBuf::from("."));
+            temp_dir.join(format!("{}.lock", socket_name))
+        }
+        None => Utf8PathBuf::from(format!("{}.lock", ipc_socket_addr)),
+    }
+}
+
+#[cfg(unix)]
+mod process {
+    pub(super) fn is_running(pid: u32) -> bool {
+        // 0 and negative PIDs refer to groups of processes rather than a single process
+        let pid = match libc::pid_t::try_from(pid) {
+            Ok(pid) if pid > 0 => pid,
+            _ => return false,
+        };
+        // signal 0 only checks whether the process exists, and EPERM means it belongs to someone else
+        let signalled = unsafe { libc::kill(pid, 0) == 0 };
+        signalled || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+
+    /// Checks that `pid` is still a router, and not an unrelated process that reused its PID.
+    #[cfg(target_os = "linux")]
+    pub(super) fn is_router(pid: u32) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .map(|name| name.trim().starts_with("router"))
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn is_router(pid: u32) -> bool {
+        let pid = match libc::c_int::try_from(pid) {
+            Ok(pid) if pid > 0 => pid,
+            _ => return false,
+        };
+        let mut name = [0u8; 256];
+        let len = unsafe { libc::proc_name(pid, name.as_mut_ptr().cast(), name.len() as u32) };
+        len > 0 && name[..len as usize].starts_with(b"router")
+    }
+
+    /// There's no portable way to look up a process's name elsewhere, so a recorded router is
+    /// never treated as running rather than risking killing a process that reused its PID.
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub(super) fn is_router(_pid: u32) -> bool {
+        false
+    }
+
+    pub(super) fn kill(pid: u32) {
+        if let Ok(pid) = libc::pid_t::try_from(pid) {
+            unsafe { libc::kill(pid, libc::SIGKILL) };
+        }
+    }
+}
+
+#[cfg(windows)]
+mod process {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, QueryFullProcessImageNameW, TerminateProcess,
+        PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    };
+
+    /// The exit code reported for processes that haven't exited yet.
+    const STILL_ACTIVE: u32 = 259;
+
+    fn with_process<T>(pid: u32, access: u32, f: impl FnOnce(HANDLE) -> T) -> Option<T> {
+        let process = unsafe { OpenProcess(access, 0, pid) };
+        if process == 0 {
+            return None;
+        }
+        let result = f(process);
+        unsafe { CloseHandle(process) };
+        Some(result)
+    }
+
+    pub(super) fn is_running(pid: u32) -> bool {
+        with_process(pid, PROCESS_QUERY_LIMITED_INFORMATION, |process| {
+            let mut exit_code = 0;
+            let succeeded = unsafe { GetExitCodeProcess(process, &mut exit_code) } != 0;
+            succeeded && exit_code == STILL_ACTIVE
+        })
+        .unwrap_or(false)
+    }
+
+    /// Checks that `pid` is still a running router, and not an unrelated process that reused
+    /// its PID.
+    pub(super) fn is_router(pid: u32) -> bool {
+        is_running(pid)
+            && with_process(pid, PROCESS_QUERY_LIMITED_INFORMATION, |process| {
+                let mut path = [0u16; 1024];
+                let mut len = path.len() as u32;
+                let succeeded = unsafe {
+                    QueryFullProcessImageNameW(
+                        process,
+                        PROCESS_NAME_WIN32,
+                        path.as_mut_ptr(),
+                        &mut len,
+                    )
+                } != 0;
+                succeeded
+                    && std::path::Path::new(&String::from_utf16_lossy(&path[..len as usize]))
+                        .file_stem()
+                        .and_then(|name| name.to_str())
+                        .map(|name| name.starts_with("router"))
+                        .unwrap_or(false)
+            })
+            .unwrap_or(false)
+    }
+
+    pub(super) fn kill(pid: u32) {
+        with_process(pid, PROCESS_TERMINATE, |process| unsafe {
+            TerminateProcess(process, 1)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+
+    use super::{
+        force_shutdown, lock_path, reclaim_orphaned_session, session_state, SessionLock,
+        SessionLockStatus, SessionState,
+    };
+
+    #[test]
+    fn it_puts_lock_files_next_to_socket_files() {
+        assert_eq!(
+            lock_path("/tmp/supergraph-127.0.0.1:4000.sock"),
+            Utf8PathBuf::from("/tmp/supergraph-127.0.0.1:4000.sock.lock")
+        );
+        assert_eq!(
+            lock_path("@supergraph-127.0.0.1:4000.sock").file_name(),
+            Some("supergraph-127.0.0.1:4000.sock.lock")
+        );
+    }
+
+    #[test]
+    fn it_reclaims_sessions_whose_owner_has_exited() {
+        let tmp_dir = TempDir::new().unwrap();
+        let socket_path = Utf8PathBuf::try_from(tmp_dir.path().join("supergraph.sock")).unwrap();
+        std::fs::write(&socket_path, "").unwrap();
+        // PIDs are at most 2^22 on Linux, and smaller elsewhere, so this one can't be running
+        std::fs::write(
+            lock_path(socket_path.as_str()),
+            r#"{"pid":4194305,"router_pid":null}"#,
+        )
+        .unwrap();
+
+        reclaim_orphaned_session(socket_path.as_str()).unwrap();
+        assert!(!socket_path.exists());
+        assert!(!lock_path(socket_path.as_str()).exists());
+    }
+
+    #[test]
+    fn it_reports_the_state_of_sessions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let socket_path = Utf8PathBuf::try_from(tmp_dir.path().join("supergraph.sock")).unwrap();
+        assert_eq!(session_state(socket_path.as_str()), SessionState::Free);
+
+        std::fs::write(&socket_path, "").unwrap();
+        assert_eq!(
+            session_state(socket_path.as_str()),
+            SessionState::Orphaned {
+                pid: None,
+                router_pid: None
+            }
+        );
+
+        std::fs::write(
+            lock_path(socket_path.as_str()),
+            r#"{"pid":4194305,"router_pid":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            session_state(socket_path.as_str()),
+            SessionState::Orphaned {
+                pid: Some(4194305),
+                router_pid: None
+            }
+        );
+
+        std::fs::write(
+            lock_path(socket_path.as_str()),
+            format!(r#"{{"pid":{},"router_pid":null}}"#, std::process::id()),
+        )
+        .unwrap();
+        assert_eq!(
+            session_state(socket_path.as_str()),
+            SessionState::Running(std::process::id())
+        );
+    }
+
+    #[test]
+    fn it_force_shuts_down_its_own_session() {
+        let tmp_dir = TempDir::new().unwrap();
+        let socket_path = Utf8PathBuf::try_from(tmp_dir.path().join("supergraph.sock")).unwrap();
+        std::fs::write(&socket_path, "").unwrap();
+        std::fs::write(
+            lock_path(socket_path.as_str()),
+            format!(r#"{{"pid":{},"router_pid":null}}"#, std::process::id()),
+        )
+        .unwrap();
+
+        force_shutdown(socket_path.as_str());
+        assert!(!socket_path.exists());
+        assert!(!lock_path(socket_path.as_str()).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_refuses_to_reclaim_running_sessions() {
+        let tmp_dir = TempDir::new().unwrap();
+        let socket_path = Utf8PathBuf::try_from(tmp_dir.path().join("supergraph.sock")).unwrap();
+        // pretend the session belongs to another process that's still running, i.e. our parent
+        let parent_pid = std::os::unix::process::parent_id();
+        std::fs::write(
+            lock_path(socket_path.as_str()),
+            format!(r#"{{"pid":{},"router_pid":null}}"#, parent_pid),
+        )
+        .unwrap();
+
+        let err = reclaim_orphaned_session(socket_path.as_str()).unwrap_err();
+        assert!(err.message().contains(&parent_pid.to_string()));
+        assert!(lock_path(socket_path.as_str()).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_only_acquires_locks_that_are_free_or_stale() {
+        let tmp_dir = TempDir::new().unwrap();
+        let socket_path = Utf8PathBuf::try_from(tmp_dir.path().join("supergraph.sock")).unwrap();
+        let lock = match SessionLock::acquire(socket_path.as_str()).unwrap() {
+            SessionLockStatus::Acquired(lock) => lock,
+            status => panic!("expected to acquire the lock, got {:?}", status),
+        };
+
+        let parent_pid = std::os::unix::process::parent_id();
+        std::fs::write(
+            &lock.path,
+            format!(r#"{{"pid":{},"router_pid":null}}"#, parent_pid),
+        )
+        .unwrap();
+        assert!(matches!(
+            SessionLock::acquire(socket_path.as_str()).unwrap(),
+            SessionLockStatus::OwnedBy(pid) if pid == parent_pid
+        ));
+        // the lock still belongs to the running session
+        assert!(lock.path.exists());
+
+        std::fs::write(&lock.path, r#"{"pid":4194305,"router_pid":null}"#).unwrap();
+        assert!(matches!(
+            SessionLock::acquire(socket_path.as_str()).unwrap(),
+            SessionLockStatus::Acquired(_)
+        ));
+        // the lock is removed once the session that owns it is dropped
+        assert!(!lock.path.exists());
+    }
+}
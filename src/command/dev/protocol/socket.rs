@@ -1,16 +1,171 @@
 use anyhow::{anyhow, Context, Error};
-use interprocess::local_socket::LocalSocketStream;
+use camino::Utf8Path;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     fmt::Debug,
-    io::{self, BufRead, BufReader, Write},
+    io::{self, BufRead, BufReader, Read, Write},
+    str::FromStr,
 };
 
-use crate::RoverResult;
+use super::tcp;
+use crate::utils::env::RoverEnvKey;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
 
-pub(crate) fn handle_socket_error(
-    conn: io::Result<LocalSocketStream>,
-) -> Option<LocalSocketStream> {
+/// A connection between two `rover dev` sessions.
+pub(crate) type IpcStream = Box<dyn IpcConnection>;
+
+/// A stream that `rover dev` sessions can send messages over.
+pub(crate) trait IpcConnection: Read + Write + Send {}
+
+impl<T: Read + Write + Send> IpcConnection for T {}
+
+/// Accepts connections from attached `rover dev` sessions over one kind of transport.
+pub(crate) trait IpcAccept: Send {
+    fn accept_connection(&self) -> io::Result<IpcStream>;
+}
+
+/// Listens for connections from attached `rover dev` sessions.
+pub(crate) struct IpcListener(Box<dyn IpcAccept>);
+
+impl IpcListener {
+    /// Returns an iterator over connections from attached `rover dev` sessions.
+    pub(crate) fn incoming(&self) -> impl Iterator<Item = io::Result<IpcStream>> + '_ {
+        std::iter::from_fn(move || Some(self.0.accept_connection()))
+    }
+}
+
+#[cfg(not(windows))]
+impl IpcAccept for interprocess::local_socket::LocalSocketListener {
+    fn accept_connection(&self) -> io::Result<IpcStream> {
+        Ok(Box::new(self.accept()?))
+    }
+}
+
+#[cfg(windows)]
+impl IpcAccept for super::named_pipe::OwnerOnlyPipeListener {
+    fn accept_connection(&self) -> io::Result<IpcStream> {
+        Ok(Box::new(self.accept()?))
+    }
+}
+
+/// How `rover dev` sessions talk to each other, chosen at runtime with
+/// `APOLLO_DEV_IPC_TRANSPORT`. Every process in a session has to use the same transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IpcTransport {
+    /// A Unix domain socket, or a named pipe on Windows
+    Local,
+    /// A loopback TCP port, for environments where local sockets and named pipes don't work.
+    /// Connections have to start with a token that's only readable by the current user.
+    Tcp,
+}
+
+impl IpcTransport {
+    pub(crate) fn from_env() -> RoverResult<Self> {
+        match std::env::var(RoverEnvKey::DevIpcTransport.to_string()) {
+            Err(_) => Ok(Self::Local),
+            Ok(transport) => transport.parse(),
+        }
+    }
+}
+
+impl FromStr for IpcTransport {
+    type Err = RoverError;
+
+    fn from_str(transport: &str) -> RoverResult<Self> {
+        match transport.trim().to_lowercase().as_str() {
+            "" | "local" => Ok(Self::Local),
+            "tcp" => Ok(Self::Tcp),
+            _ => {
+                let mut err = RoverError::new(anyhow!(
+                    "'{}' is not a valid value for `{}`.",
+                    transport,
+                    RoverEnvKey::DevIpcTransport
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Set it to `local` or `tcp`, or unset it to use a local socket.".to_string(),
+                ));
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Connects to the main `rover dev` session listening on `ipc_socket_addr`.
+/// On Windows, the session has to belong to the current user.
+pub(crate) fn ipc_connect(ipc_socket_addr: &str) -> io::Result<IpcStream> {
+    match IpcTransport::from_env().map_err(invalid_transport)? {
+        IpcTransport::Local => local_connect(ipc_socket_addr),
+        IpcTransport::Tcp => Ok(Box::new(tcp::connect(ipc_socket_addr)?)),
+    }
+}
+
+#[cfg(not(windows))]
+fn local_connect(ipc_socket_addr: &str) -> io::Result<IpcStream> {
+    Ok(Box::new(
+        interprocess::local_socket::LocalSocketStream::connect(ipc_socket_addr)?,
+    ))
+}
+
+#[cfg(windows)]
+fn local_connect(ipc_socket_addr: &str) -> io::Result<IpcStream> {
+    Ok(Box::new(super::named_pipe::connect(ipc_socket_addr)?))
+}
+
+/// Starts listening on `ipc_socket_addr` for attached `rover dev` sessions.
+pub(crate) fn ipc_bind(ipc_socket_addr: &str) -> io::Result<IpcListener> {
+    let listener: Box<dyn IpcAccept> = match IpcTransport::from_env().map_err(invalid_transport)? {
+        #[cfg(not(windows))]
+        IpcTransport::Local => Box::new(interprocess::local_socket::LocalSocketListener::bind(
+            ipc_socket_addr,
+        )?),
+        #[cfg(windows)]
+        IpcTransport::Local => Box::new(super::named_pipe::OwnerOnlyPipeListener::bind(
+            ipc_socket_addr,
+        )?),
+        IpcTransport::Tcp => Box::new(tcp::TokenListener::bind(ipc_socket_addr)?),
+    };
+    Ok(IpcListener(listener))
+}
+
+fn invalid_transport(err: RoverError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err.message())
+}
+
+/// Removes the socket file and TCP endpoint file of a session that has stopped.
+pub(crate) fn remove_socket(ipc_socket_addr: &str) {
+    let _ = std::fs::remove_file(ipc_socket_addr);
+    let _ = std::fs::remove_file(tcp::endpoint_path(ipc_socket_addr));
+}
+
+/// Removes a socket file left behind by a `rover dev` session that crashed.
+/// Only call this after failing to connect to the socket, which means no session is listening on it.
+pub(crate) fn remove_stale_socket(ipc_socket_addr: &str) -> RoverResult<()> {
+    let _ = std::fs::remove_file(tcp::endpoint_path(ipc_socket_addr));
+    let socket_path = Utf8Path::new(ipc_socket_addr);
+    // namespaced sockets don't leave files behind
+    if !socket_path.exists() {
+        return Ok(());
+    }
+    tracing::info!(
+        "removing the stale socket at {} left behind by a previous `rover dev` session",
+        socket_path
+    );
+    std::fs::remove_file(socket_path).map_err(|e| {
+        let mut err = RoverError::new(anyhow!(
+            "Could not remove the stale socket at '{}' left behind by a previous `rover dev` session: {}",
+            socket_path,
+            e
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "Delete '{}' yourself, or set `{}` to a directory you own.",
+            socket_path,
+            RoverEnvKey::DevSocketDir
+        )));
+        err
+    })
+}
+
+pub(crate) fn handle_socket_error(conn: io::Result<IpcStream>) -> Option<IpcStream> {
     match conn {
         Ok(val) => Some(val),
         Err(error) => {
@@ -20,9 +175,7 @@ pub(crate) fn handle_socket_error(
     }
 }
 
-pub(crate) fn socket_read<B>(
-    stream: &mut BufReader<LocalSocketStream>,
-) -> std::result::Result<B, Error>
+pub(crate) fn socket_read<B>(stream: &mut BufReader<IpcStream>) -> std::result::Result<B, Error>
 where
     B: Serialize + DeserializeOwned + Debug,
 {
@@ -47,10 +200,7 @@ where
     }
 }
 
-pub(crate) fn socket_write<A>(
-    message: &A,
-    stream: &mut BufReader<LocalSocketStream>,
-) -> RoverResult<()>
+pub(crate) fn socket_write<A>(message: &A, stream: &mut BufReader<IpcStream>) -> RoverResult<()>
 where
     A: Serialize + DeserializeOwned + Debug,
 {
@@ -0,0 +1,189 @@
+//! Loopback TCP connections between `rover dev` sessions, for environments where Unix domain
+//! sockets and named pipes don't work, like some containers and network filesystems.
+//!
+//! Any process on the machine can connect to a loopback port, so the main process writes a
+//! random token next to where its socket would be, readable only by the current user. Attached
+//! sessions send the token before their first message, and connections that don't are closed.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use super::socket::{IpcAccept, IpcStream};
+
+/// How long an incoming connection has to send its token.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tokens are UUIDs, so anything longer isn't one.
+const MAX_TOKEN_LENGTH: usize = 64;
+
+/// Where the main `rover dev` process is listening, and the token it expects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TcpEndpoint {
+    port: u16,
+    token: String,
+}
+
+impl TcpEndpoint {
+    fn read(path: &Utf8Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the endpoint somewhere else first, so other processes never read a partial one.
+    fn write(&self, path: &Utf8Path) -> io::Result<()> {
+        let pending_path = Utf8PathBuf::from(format!("{}.{}", path, std::process::id()));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&pending_path)?;
+        file.write_all(serde_json::to_string(self)?.as_bytes())?;
+        drop(file);
+        std::fs::rename(&pending_path, path)
+    }
+}
+
+/// The file a session's TCP endpoint is written to. Namespaced socket addresses don't have
+/// a directory, so their endpoints go in the temp directory, which is per-user on Windows.
+pub(crate) fn endpoint_path(ipc_socket_addr: &str) -> Utf8PathBuf {
+    match ipc_socket_addr.strip_prefix('@') {
+        Some(socket_name) => {
+            let temp_dir = Utf8PathBuf::try_from(std::env::temp_dir())
+                .unwrap_or_else(|_| Utf8PathBuf::from("."));
+            // the router's address is part of the name, and `:` isn't allowed in Windows paths
+            temp_dir.join(format!("{}.tcp.json", socket_name.replace(':', "-")))
+        }
+        None => Utf8PathBuf::from(format!("{}.tcp.json", ipc_socket_addr)),
+    }
+}
+
+/// Listens on a loopback port for attached `rover dev` sessions that know the session's token.
+#[derive(Debug)]
+pub(crate) struct TokenListener {
+    listener: TcpListener,
+    token: String,
+}
+
+impl TokenListener {
+    /// Listens on a random loopback port, and writes it to the endpoint file for
+    /// `ipc_socket_addr` with a new token.
+    pub(crate) fn bind(ipc_socket_addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let endpoint = TcpEndpoint {
+            port: listener.local_addr()?.port(),
+            token: Uuid::new_v4().simple().to_string(),
+        };
+        endpoint.write(&endpoint_path(ipc_socket_addr))?;
+        Ok(Self {
+            listener,
+            token: endpoint.token,
+        })
+    }
+}
+
+impl IpcAccept for TokenListener {
+    fn accept_connection(&self) -> io::Result<IpcStream> {
+        let (mut stream, _) = self.listener.accept()?;
+        // a connection that never sends anything can't hold up everyone else's
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let token = read_token(&mut stream)?;
+        stream.set_read_timeout(None)?;
+        if !tokens_match(&token, &self.token) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "refused a connection that didn't send the session's token",
+            ));
+        }
+        Ok(Box::new(stream))
+    }
+}
+
+/// Connects to the main `rover dev` session for `ipc_socket_addr` and sends it the token
+/// from its endpoint file.
+pub(crate) fn connect(ipc_socket_addr: &str) -> io::Result<TcpStream> {
+    let endpoint = TcpEndpoint::read(&endpoint_path(ipc_socket_addr))?;
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, endpoint.port))?;
+    stream.write_all(format!("{}\n", endpoint.token).as_bytes())?;
+    Ok(stream)
+}
+
+/// Reads the first line of a connection one byte at a time, so none of the message after it
+/// is consumed.
+fn read_token(stream: &mut TcpStream) -> io::Result<String> {
+    let mut token = Vec::new();
+    let mut byte = [0];
+    while token.len() <= MAX_TOKEN_LENGTH {
+        stream.read_exact(&mut byte)?;
+        if byte[0] == b'\n' {
+            return Ok(String::from_utf8_lossy(&token).to_string());
+        }
+        token.push(byte[0]);
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "the connection didn't start with a token",
+    ))
+}
+
+/// Compares every byte, so how long the comparison takes doesn't reveal how much of a
+/// guessed token was right.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{Ipv4Addr, TcpStream};
+
+    use super::{connect, endpoint_path, IpcAccept, TcpEndpoint, TokenListener};
+
+    fn socket_addr(tmp_dir: &TempDir) -> String {
+        tmp_dir
+            .path()
+            .join("supergraph.sock")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn it_accepts_connections_with_the_token() {
+        let tmp_dir = TempDir::new().unwrap();
+        let ipc_socket_addr = socket_addr(&tmp_dir);
+        let listener = TokenListener::bind(&ipc_socket_addr).unwrap();
+
+        let mut stream = connect(&ipc_socket_addr).unwrap();
+        stream.write_all(b"{\"kind\":\"health_check\"}\n").unwrap();
+        let mut accepted = BufReader::new(listener.accept_connection().unwrap());
+        let mut message = String::new();
+        accepted.read_line(&mut message).unwrap();
+        assert_eq!(message, "{\"kind\":\"health_check\"}\n");
+    }
+
+    #[test]
+    fn it_refuses_connections_without_the_token() {
+        let tmp_dir = TempDir::new().unwrap();
+        let ipc_socket_addr = socket_addr(&tmp_dir);
+        let listener = TokenListener::bind(&ipc_socket_addr).unwrap();
+
+        let endpoint = TcpEndpoint::read(&endpoint_path(&ipc_socket_addr)).unwrap();
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, endpoint.port)).unwrap();
+        stream.write_all(b"not-the-token\n").unwrap();
+        let err = listener.accept_connection().err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,204 @@
+//! Just enough HTTP/1.1 to sit between the router and its clients or subgraphs. Each connection
+//! carries a single request, which is read in full, forwarded, and answered with `connection: close`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use reqwest::blocking::Client;
+use reqwest::Method;
+use url::Url;
+
+/// An HTTP request read from a proxy's client.
+#[derive(Debug, Clone)]
+pub(crate) struct ProxiedRequest {
+    pub(crate) method: String,
+    /// The path and query string
+    pub(crate) path: String,
+    /// Header names are lowercase
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl ProxiedRequest {
+    pub(crate) fn read(stream: &TcpStream) -> anyhow::Result<Self> {
+        Self::read_from(&mut BufReader::new(stream))
+    }
+
+    fn read_from(reader: &mut impl BufRead) -> anyhow::Result<Self> {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(anyhow!("malformed request line '{}'", request_line.trim())),
+        };
+
+        let mut request = Self {
+            method,
+            path,
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                request
+                    .headers
+                    .push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        if request
+            .header("transfer-encoding")
+            .map_or(false, |encoding| encoding.contains("chunked"))
+        {
+            loop {
+                let mut size = String::new();
+                reader.read_line(&mut size)?;
+                let size = usize::from_str_radix(size.trim().split(';').next().unwrap_or(""), 16)?;
+                // each chunk is followed by a CRLF
+                let mut chunk = vec![0; size + 2];
+                reader.read_exact(&mut chunk)?;
+                if size == 0 {
+                    break;
+                }
+                request.body.extend_from_slice(&chunk[..size]);
+            }
+        } else if let Some(length) = request.header("content-length") {
+            request.body.resize(length.parse()?, 0);
+            reader.read_exact(&mut request.body)?;
+        }
+        Ok(request)
+    }
+
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sends this request to the same path on `target`'s origin, with `extra_headers` added.
+    pub(crate) fn forward(
+        &self,
+        client: &Client,
+        target: &Url,
+        extra_headers: &[(&str, &str)],
+    ) -> anyhow::Result<ProxiedResponse> {
+        let mut url = target.clone();
+        let (path, query) = match self.path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (self.path.as_str(), None),
+        };
+        url.set_path(path);
+        url.set_query(query);
+        let mut forwarded = client
+            .request(Method::from_bytes(self.method.as_bytes())?, url)
+            .body(self.body.clone());
+        for (name, value) in &self.headers {
+            if !is_hop_by_hop(name) {
+                forwarded = forwarded.header(name, value);
+            }
+        }
+        for (name, value) in extra_headers {
+            forwarded = forwarded.header(*name, *value);
+        }
+
+        let response = forwarded.send()?;
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !is_hop_by_hop(name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        Ok(ProxiedResponse {
+            status: status.to_string(),
+            status_code: status.as_u16(),
+            headers,
+            body: response.bytes()?.to_vec(),
+        })
+    }
+}
+
+/// An HTTP response to send back to a proxy's client.
+#[derive(Debug, Clone)]
+pub(crate) struct ProxiedResponse {
+    /// The status line's code and reason, like `200 OK`
+    pub(crate) status: String,
+    pub(crate) status_code: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl ProxiedResponse {
+    /// A GraphQL error response with a single error `message`.
+    pub(crate) fn graphql_error(status: &str, message: String) -> Self {
+        Self {
+            status: status.to_string(),
+            status_code: status
+                .split_whitespace()
+                .next()
+                .and_then(|code| code.parse().ok())
+                .unwrap_or(500),
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: serde_json::json!({ "errors": [{ "message": message }] })
+                .to_string()
+                .into_bytes(),
+        }
+    }
+
+    pub(crate) fn write(&self, mut stream: &TcpStream) -> anyhow::Result<()> {
+        write!(stream, "HTTP/1.1 {}\r\n", self.status)?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(
+            stream,
+            "content-length: {}\r\nconnection: close\r\n\r\n",
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)?;
+        Ok(())
+    }
+}
+
+/// Handles each connection to `listener` on its own thread, in the background.
+pub(crate) fn serve<F>(listener: TcpListener, description: String, handle: F)
+where
+    F: Fn(&TcpStream) -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    let handle = Arc::new(handle);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let handle = handle.clone();
+            let description = description.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handle(&stream) {
+                    tracing::debug!("could not proxy a request {}: {}", description, e);
+                }
+            });
+        }
+    });
+}
+
+/// Headers that describe a single connection, which the proxy handles itself.
+fn is_hop_by_hop(name: &str) -> bool {
+    [
+        "connection",
+        "content-length",
+        "host",
+        "keep-alive",
+        "transfer-encoding",
+        "upgrade",
+    ]
+    .iter()
+    .any(|header| name.eq_ignore_ascii_case(header))
+}
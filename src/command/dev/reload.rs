@@ -0,0 +1,136 @@
+//! Lets external tools ask the main `rover dev` process to reload its supergraph config by
+//! sending it SIGHUP, without restarting the session.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{protocol::FollowerMessenger, watcher::SubgraphSchemaWatcher};
+use crate::RoverResult;
+
+/// The subgraph watchers the main `rover dev` process is running, keyed by subgraph name
+#[derive(Debug)]
+pub struct SubgraphWatcherSet {
+    follower_messenger: FollowerMessenger,
+    debounce: Duration,
+    startup_timeout: Duration,
+    running: HashMap<String, RunningWatcher>,
+}
+
+#[derive(Debug)]
+struct RunningWatcher {
+    source: String,
+    stopped: Arc<AtomicBool>,
+}
+
+impl SubgraphWatcherSet {
+    pub fn new(
+        follower_messenger: FollowerMessenger,
+        debounce: Duration,
+        startup_timeout: Duration,
+    ) -> Self {
+        Self {
+            follower_messenger,
+            debounce,
+            startup_timeout,
+            running: HashMap::new(),
+        }
+    }
+
+    pub fn follower_messenger(&self) -> FollowerMessenger {
+        self.follower_messenger.clone()
+    }
+
+    /// Starts each watcher in its own thread.
+    pub fn start(&mut self, watchers: Vec<SubgraphSchemaWatcher>) {
+        for mut watcher in watchers {
+            watcher.set_debounce(self.debounce);
+            watcher.set_startup_timeout(self.startup_timeout);
+            self.running.insert(
+                watcher.get_name(),
+                RunningWatcher {
+                    source: watcher.source(),
+                    stopped: watcher.stop_handle(),
+                },
+            );
+            std::thread::spawn(move || {
+                let _ = watcher
+                    .watch_subgraph_for_changes()
+                    .map_err(super::do_dev::log_err_and_continue);
+            });
+        }
+    }
+
+    /// Compares `watchers`, built from a reloaded supergraph config, with the running watchers.
+    /// Subgraphs that were removed from the config, or whose schema source changed, are stopped
+    /// and removed from the session. Returns the watchers that need to be started.
+    pub fn reconcile(
+        &mut self,
+        watchers: Vec<SubgraphSchemaWatcher>,
+    ) -> RoverResult<Vec<SubgraphSchemaWatcher>> {
+        let mut removed = std::mem::take(&mut self.running);
+        let mut new_watchers = Vec::new();
+        for watcher in watchers {
+            match removed.remove(&watcher.get_name()) {
+                Some(running) if running.source == watcher.source() => {
+                    self.running.insert(watcher.get_name(), running);
+                }
+                Some(running) => {
+                    tracing::info!("restarting the '{}' subgraph", watcher.get_name());
+                    self.stop(&watcher.get_name(), running)?;
+                    new_watchers.push(watcher);
+                }
+                None => {
+                    tracing::info!("adding the '{}' subgraph", watcher.get_name());
+                    new_watchers.push(watcher);
+                }
+            }
+        }
+        for (name, running) in removed {
+            tracing::info!("removing the '{}' subgraph", name);
+            self.stop(&name, running)?;
+        }
+        Ok(new_watchers)
+    }
+
+    fn stop(&self, name: &str, running: RunningWatcher) -> RoverResult<()> {
+        running.stopped.store(true, Ordering::SeqCst);
+        self.follower_messenger.remove_subgraph(&name.to_string())
+    }
+}
+
+/// Whether a SIGHUP has arrived since the reload thread last checked
+#[cfg(unix)]
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn request_reload(_signal: libc::c_int) {
+    // only async-signal-safe work is allowed here, so the reload itself happens on another thread
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Calls `reload` in a background thread every time the process receives SIGHUP.
+#[cfg(unix)]
+pub fn reload_on_sighup(mut reload: impl FnMut() + Send + 'static) {
+    let handler = request_reload as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    if unsafe { libc::signal(libc::SIGHUP, handler) } == libc::SIG_ERR {
+        tracing::warn!(
+            "could not handle SIGHUP: {}",
+            std::io::Error::last_os_error()
+        );
+        return;
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_millis(200));
+        if RELOAD_REQUESTED.swap(false, Ordering::SeqCst) {
+            reload();
+        }
+    });
+}
+
+/// Windows doesn't have SIGHUP, so the supergraph config is only read when the session starts.
+#[cfg(not(unix))]
+pub fn reload_on_sighup(_reload: impl FnMut() + Send + 'static) {
+    tracing::debug!("reloading the supergraph config on SIGHUP is only supported on unix");
+}
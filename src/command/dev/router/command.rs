@@ -2,9 +2,11 @@ use std::env::var;
 use std::{
     io::{BufRead, BufReader},
     process::{Child, Command, Stdio},
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Context};
+use camino::Utf8PathBuf;
 use crossbeam_channel::Sender;
 use rover_client::operations::config::who_am_i::{self, Actor, ConfigWhoAmIInput};
 use rover_std::Emoji;
@@ -13,10 +15,26 @@ use crate::options::ProfileOpt;
 use crate::utils::client::StudioClientConfig;
 use crate::{command::dev::do_dev::log_err_and_continue, RoverError, RoverResult};
 
+/// How long to wait for a child process to exit after asking it to stop, unless
+/// [`BackgroundTask::set_shutdown_grace_period`] overrides it.
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 #[derive(Debug)]
 pub struct BackgroundTask {
     child: Child,
+    /// Everything the child started, which is killed along with it
+    job: Option<process::Job>,
     descriptor: String,
+    shutdown_grace_period: Duration,
+}
+
+/// Where a [`BackgroundTask`] runs, and the environment variables it gets on top of Rover's own.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TaskEnvironment {
+    /// Variables to set for the task, which take precedence over the ones Rover sets
+    pub envs: Vec<(String, String)>,
+    /// The directory to run the task in, instead of Rover's working directory
+    pub current_dir: Option<Utf8PathBuf>,
 }
 
 pub enum BackgroundTaskLog {
@@ -25,19 +43,21 @@ pub enum BackgroundTaskLog {
 }
 
 impl BackgroundTask {
+    /// Starts `command`, which is split into arguments the way a POSIX shell would, so
+    /// arguments with spaces in them have to be quoted.
     pub fn new(
         command: String,
         log_sender: Sender<BackgroundTaskLog>,
         client_config: &StudioClientConfig,
         profile_opt: &ProfileOpt,
+        environment: &TaskEnvironment,
     ) -> RoverResult<Self> {
         let descriptor = command.clone();
-        let args: Vec<&str> = command.split(' ').collect();
-        let (bin, args) = match args.len() {
-            0 => Err(anyhow!("the command you passed is empty")),
-            1 => Ok((args[0], Vec::new())),
-            _ => Ok((args[0], Vec::from_iter(args[1..].iter()))),
-        }?;
+        let args = shell_words::split(&command)
+            .with_context(|| format!("could not parse the command `{}`", &command))?;
+        let (bin, args) = args
+            .split_first()
+            .ok_or_else(|| anyhow!("the command you passed is empty"))?;
         tracing::info!("starting `{}`", &command);
 
         if which::which(bin).is_err() {
@@ -46,8 +66,13 @@ impl BackgroundTask {
 
         let mut command = Command::new(bin);
         command.args(args).env("APOLLO_ROVER", "true");
+        if let Some(current_dir) = &environment.current_dir {
+            command.current_dir(current_dir);
+        }
 
         command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // the child leads its own process group, so stopping it also stops anything it spawned
+        process::new_process_group(&mut command);
 
         if let Ok(apollo_graph_ref) = var("APOLLO_GRAPH_REF") {
             command.env("APOLLO_GRAPH_REF", apollo_graph_ref);
@@ -76,10 +101,14 @@ impl BackgroundTask {
                 })
             }) { command.env("APOLLO_KEY", api_key); }
         }
+        for (key, value) in &environment.envs {
+            command.env(key, value);
+        }
 
         let mut child = command
             .spawn()
             .with_context(|| "could not spawn child process")?;
+        let job = process::Job::new(&child);
 
         if let Some(stdout) = child.stdout.take() {
             let log_sender = log_sender.clone();
@@ -108,18 +137,55 @@ impl BackgroundTask {
             });
         }
 
-        Ok(Self { child, descriptor })
+        Ok(Self {
+            child,
+            job,
+            descriptor,
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+        })
+    }
+
+    /// Sets how long [`BackgroundTask::kill`] waits for the child to exit on its own before
+    /// killing it.
+    pub fn set_shutdown_grace_period(&mut self, shutdown_grace_period: Duration) {
+        self.shutdown_grace_period = shutdown_grace_period;
     }
 
+    /// Asks the child to stop, so it can flush telemetry and close its connections, and kills
+    /// its process group if it's still running after the shutdown grace period.
     pub fn kill(&mut self) {
+        if !matches!(self.child.try_wait(), Ok(None)) {
+            return;
+        }
         let pid = self.id();
+        tracing::info!("stopping child with pid {}", &pid);
+        if process::terminate(pid) {
+            let started = Instant::now();
+            while started.elapsed() < self.shutdown_grace_period {
+                if !matches!(self.child.try_wait(), Ok(None)) {
+                    tracing::info!("child with pid {} stopped", &pid);
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            tracing::info!(
+                "child with pid {} didn't stop within {} seconds",
+                &pid,
+                self.shutdown_grace_period.as_secs()
+            );
+        }
+
         tracing::info!("killing child with pid {}", &pid);
+        if let Some(job) = &self.job {
+            job.kill();
+        }
         let _ = self.child.kill().map_err(|_| {
             log_err_and_continue(RoverError::new(anyhow!(
                 "could not kill child with pid {}",
                 &pid
             )));
         });
+        let _ = self.child.wait();
     }
 
     pub fn id(&self) -> u32 {
@@ -136,3 +202,128 @@ impl Drop for BackgroundTask {
         self.kill()
     }
 }
+
+#[cfg(unix)]
+mod process {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Child, Command};
+
+    pub(super) fn new_process_group(command: &mut Command) {
+        command.process_group(0);
+    }
+
+    /// Sends `SIGTERM` to the process group led by `pid`, returning whether it was delivered.
+    pub(super) fn terminate(pid: u32) -> bool {
+        signal_group(pid, libc::SIGTERM)
+    }
+
+    /// The process group a child leads, which everything it starts joins
+    #[derive(Debug)]
+    pub(super) struct Job {
+        pid: u32,
+    }
+
+    impl Job {
+        pub(super) fn new(child: &Child) -> Option<Self> {
+            Some(Self { pid: child.id() })
+        }
+
+        pub(super) fn kill(&self) {
+            signal_group(self.pid, libc::SIGKILL);
+        }
+    }
+
+    fn signal_group(pid: u32, signal: libc::c_int) -> bool {
+        match libc::pid_t::try_from(pid) {
+            // negating the PID signals every process in its group
+            Ok(pid) if pid > 0 => unsafe { libc::kill(-pid, signal) == 0 },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(windows)]
+mod process {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child, Command};
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+
+    pub(super) fn new_process_group(command: &mut Command) {
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+
+    /// Sends `CTRL_BREAK` to the process group led by `pid`, returning whether it was delivered.
+    pub(super) fn terminate(pid: u32) -> bool {
+        unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0 }
+    }
+
+    /// A Job Object that a child is assigned to, which everything it starts joins. The job kills
+    /// its processes when its last handle is closed, so they stop with Rover even if Rover
+    /// crashes or is killed before it can stop them.
+    #[derive(Debug)]
+    pub(super) struct Job {
+        handle: HANDLE,
+    }
+
+    impl Job {
+        /// Assigns `child` to a new job, or returns `None` if it can't be, in which case only the
+        /// child itself is killed.
+        pub(super) fn new(child: &Child) -> Option<Self> {
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle == 0 {
+                tracing::debug!(
+                    "could not create a job object for child with pid {}",
+                    child.id()
+                );
+                return None;
+            }
+            // closes the handle if the child can't be assigned to it
+            let job = Self { handle };
+
+            let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let assigned = unsafe {
+                SetInformationJobObject(
+                    job.handle,
+                    JobObjectExtendedLimitInformation,
+                    &limits as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) != 0
+                    && AssignProcessToJobObject(job.handle, child.as_raw_handle() as HANDLE) != 0
+            };
+            if assigned {
+                Some(job)
+            } else {
+                tracing::debug!(
+                    "could not assign child with pid {} to a job object",
+                    child.id()
+                );
+                None
+            }
+        }
+
+        pub(super) fn kill(&self) {
+            unsafe {
+                TerminateJobObject(self.handle, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.handle);
+            }
+        }
+    }
+}
@@ -1,20 +1,22 @@
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
     sync::{Arc, Mutex},
 };
 
 use anyhow::{anyhow, Context};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use crossbeam_channel::{unbounded, Receiver};
 use serde_json::json;
 use tempdir::TempDir;
 
+use rover_client::operations::persisted_queries::publish::ApolloPersistedQueryManifest;
 use rover_std::{Emoji, Fs};
 
+use crate::utils::env::RoverEnvKey;
 use crate::utils::expansion::expand;
 use crate::{
-    command::dev::{do_dev::log_err_and_continue, SupergraphOpts},
-    RoverError, RoverResult,
+    command::dev::{access_log::OPERATION_ID_HEADER, do_dev::log_err_and_continue, SupergraphOpts},
+    RoverError, RoverErrorSuggestion, RoverResult,
 };
 
 const DEFAULT_ROUTER_SOCKET_ADDR: SocketAddr =
@@ -44,16 +46,24 @@ impl TryFrom<&SupergraphOpts> for RouterConfigHandler {
             value.router_config_path.clone(),
             value.supergraph_address,
             value.supergraph_port,
+            value.access_log.is_some(),
+            value.persisted_queries_manifest.as_deref(),
         )
     }
 }
 
 impl RouterConfigHandler {
     /// Create a [`RouterConfigHandler`]
+    ///
+    /// With `behind_proxy`, the router listens on a free loopback port instead of the router
+    /// address, so that a proxy can listen there and forward operations to it. With
+    /// `persisted_queries_manifest`, the router only allows the operations in that manifest.
     pub fn new(
         input_config_path: Option<Utf8PathBuf>,
         ip_override: Option<IpAddr>,
         port_override: Option<u16>,
+        behind_proxy: bool,
+        persisted_queries_manifest: Option<&Utf8Path>,
     ) -> RoverResult<Self> {
         let tmp_dir = TempDir::new("supergraph")?;
         let tmp_config_dir_path = Utf8PathBuf::try_from(tmp_dir.into_path())?;
@@ -61,7 +71,18 @@ impl RouterConfigHandler {
         let tmp_router_config_path = tmp_config_dir_path.join("router.yaml");
         let tmp_supergraph_schema_path = tmp_config_dir_path.join("supergraph.graphql");
 
-        let config_reader = RouterConfigReader::new(input_config_path, ip_override, port_override);
+        let mut config_reader =
+            RouterConfigReader::new(input_config_path, ip_override, port_override);
+        if behind_proxy {
+            let internal_addr = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+                .and_then(|listener| listener.local_addr())
+                .context("could not find a free port for the router to listen on")?;
+            config_reader.internal_socket_addr = Some(internal_addr);
+        }
+        if let Some(manifest) = persisted_queries_manifest {
+            config_reader.persisted_queries_manifest =
+                Some(load_persisted_queries_manifest(manifest)?);
+        }
 
         let config_state = config_reader.read()?;
 
@@ -106,6 +127,14 @@ impl RouterConfigHandler {
             .unwrap_or(DEFAULT_ROUTER_SOCKET_ADDR)
     }
 
+    /// The address the router process itself listens on, which is only different from
+    /// [`Self::get_router_address`] when the router is behind a proxy.
+    pub fn get_router_internal_address(&self) -> SocketAddr {
+        self.config_reader
+            .internal_socket_addr
+            .unwrap_or_else(|| self.get_router_address())
+    }
+
     /// The path the router should listen on
     pub fn get_router_listen_path(&self) -> String {
         self.config_state
@@ -120,11 +149,14 @@ impl RouterConfigHandler {
         let socket_name = format!("supergraph-{}.sock", self.get_router_address());
         {
             use interprocess::local_socket::NameTypeSupport::{self, *};
-            let socket_prefix = match NameTypeSupport::query() {
-                OnlyPaths | Both => "/tmp/",
-                OnlyNamespaced => "@",
-            };
-            Ok(format!("{}{}", socket_prefix, socket_name))
+            match NameTypeSupport::query() {
+                OnlyPaths | Both => get_socket_path(
+                    std::env::var(RoverEnvKey::DevSocketDir.to_string()).ok(),
+                    std::env::var("XDG_RUNTIME_DIR").ok(),
+                    &socket_name,
+                ),
+                OnlyNamespaced => Ok(format!("@{}", socket_name)),
+            }
         }
     }
 
@@ -139,6 +171,115 @@ impl RouterConfigHandler {
     }
 }
 
+/// Adds a rule to `headers.all.request` in the router config that propagates `header` to every subgraph.
+fn propagate_header(yaml: &mut serde_yaml::Mapping, header: &str) -> RoverResult<()> {
+    let rules = yaml
+        .entry("headers".into())
+        .or_insert_with(|| serde_yaml::Mapping::new().into())
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow!("`headers` key in router YAML must be a mapping"))?
+        .entry("all".into())
+        .or_insert_with(|| serde_yaml::Mapping::new().into())
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow!("`headers.all` key in router YAML must be a mapping"))?
+        .entry("request".into())
+        .or_insert_with(|| serde_yaml::Sequence::new().into())
+        .as_sequence_mut()
+        .ok_or_else(|| anyhow!("`headers.all.request` key in router YAML must be a list"))?;
+    rules.push(serde_yaml::to_value(
+        json!({ "propagate": { "named": header } }),
+    )?);
+    Ok(())
+}
+
+/// Checks that `manifest` is a persisted query manifest in Apollo's format, and returns its
+/// absolute path, since the router might not run in the current directory.
+fn load_persisted_queries_manifest(manifest: &Utf8Path) -> RoverResult<Utf8PathBuf> {
+    let operations =
+        serde_json::from_str::<ApolloPersistedQueryManifest>(&Fs::read_file(manifest)?)
+            .with_context(|| {
+                format!(
+                    "{} is not a persisted query manifest in Apollo's format",
+                    manifest
+                )
+            })?
+            .operations
+            .len();
+    let manifest = manifest
+        .canonicalize_utf8()
+        .with_context(|| format!("could not find the absolute path of {}", manifest))?;
+    eprintln!(
+        "{}the router will only allow the {} operations in {}",
+        Emoji::Note,
+        operations,
+        manifest
+    );
+    Ok(manifest)
+}
+
+/// Turns on persisted queries with a local manifest and safelisting, so the router rejects
+/// operations that aren't in the manifest, like it would with enforcement on in GraphOS.
+fn enforce_safelist(yaml: &mut serde_yaml::Mapping, manifest: &Utf8Path) -> RoverResult<()> {
+    let persisted_queries = yaml
+        .entry("persisted_queries".into())
+        .or_insert_with(|| serde_yaml::Mapping::new().into())
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow!("`persisted_queries` key in router YAML must be a mapping"))?;
+    persisted_queries.insert("enabled".into(), true.into());
+    persisted_queries.insert(
+        "local_manifests".into(),
+        serde_yaml::to_value(vec![manifest])?,
+    );
+    // operations can still be sent by their full text, as long as it's in the manifest
+    persisted_queries.insert(
+        "safelist".into(),
+        serde_yaml::to_value(json!({ "enabled": true, "require_id": false }))?,
+    );
+    // the router won't start with both safelisting and automatic persisted queries
+    yaml.insert(
+        "apq".into(),
+        serde_yaml::to_value(json!({ "enabled": false }))?,
+    );
+    Ok(())
+}
+
+/// The longest socket path that every supported platform accepts (`sun_path` is 104 bytes on macOS).
+const MAX_SOCKET_PATH_LEN: usize = 103;
+
+/// Chooses where the socket that `rover dev` sessions talk over lives: a user-specified
+/// directory, then the per-user runtime directory, then `/tmp`.
+fn get_socket_path(
+    override_dir: Option<String>,
+    runtime_dir: Option<String>,
+    socket_name: &str,
+) -> RoverResult<String> {
+    let is_override = override_dir.is_some();
+    let socket_dir = match (override_dir, runtime_dir) {
+        (Some(override_dir), _) => Utf8PathBuf::from(override_dir),
+        (None, Some(runtime_dir)) if Utf8Path::new(&runtime_dir).is_dir() => {
+            Utf8PathBuf::from(runtime_dir)
+        }
+        _ => Utf8PathBuf::from("/tmp"),
+    };
+    let socket_path = socket_dir.join(socket_name).to_string();
+    if socket_path.len() > MAX_SOCKET_PATH_LEN {
+        let mut err = RoverError::new(anyhow!(
+            "The socket path '{}' is too long. Socket paths can be at most {} bytes.",
+            socket_path,
+            MAX_SOCKET_PATH_LEN
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "Set `{}` to a shorter directory, e.g. `/tmp/rover`.",
+            RoverEnvKey::DevSocketDir
+        )));
+        return Err(err);
+    }
+    if is_override {
+        Fs::create_dir_all(&socket_dir)?;
+    }
+    Ok(socket_path)
+}
+
 #[derive(Debug, Clone)]
 pub struct RouterConfigState {
     /// Where the router should listen
@@ -156,6 +297,10 @@ struct RouterConfigReader {
     input_config_path: Option<Utf8PathBuf>,
     ip_override: Option<IpAddr>,
     port_override: Option<u16>,
+    /// Where the router listens when it's behind a proxy
+    internal_socket_addr: Option<SocketAddr>,
+    /// The absolute path to the persisted query manifest to safelist operations with
+    persisted_queries_manifest: Option<Utf8PathBuf>,
 }
 
 impl RouterConfigReader {
@@ -168,6 +313,8 @@ impl RouterConfigReader {
             input_config_path,
             ip_override,
             port_override,
+            internal_socket_addr: None,
+            persisted_queries_manifest: None,
         }
     }
 
@@ -218,13 +365,23 @@ impl RouterConfigReader {
             (None, None, None) => None,
         };
 
-        if let Some(socket_addr) = socket_addr {
+        if let Some(listen_addr) = self.internal_socket_addr.or(socket_addr) {
             // update YAML with the ip and port CLI options
             yaml.entry("supergraph".into())
                 .or_insert_with(|| serde_yaml::Mapping::new().into())
                 .as_mapping_mut()
                 .ok_or_else(|| anyhow!("`supergraph` key in router YAML must be a mapping"))?
-                .insert("listen".into(), serde_yaml::to_value(socket_addr)?);
+                .insert("listen".into(), serde_yaml::to_value(listen_addr)?);
+        }
+
+        // the proxy in front of the router tags each operation, and the proxies in front
+        // of subgraphs need the tag to tell which operation a request is for
+        if self.internal_socket_addr.is_some() {
+            propagate_header(&mut yaml, OPERATION_ID_HEADER)?;
+        }
+
+        if let Some(manifest) = &self.persisted_queries_manifest {
+            enforce_safelist(&mut yaml, manifest)?;
         }
 
         // disable the health check unless they have their own config
@@ -289,3 +446,87 @@ impl RouterConfigReader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+
+    use super::{enforce_safelist, get_socket_path, propagate_header};
+
+    #[test]
+    fn it_prefers_the_override_dir_then_the_runtime_dir() {
+        let tmp_dir = TempDir::new().unwrap();
+        let tmp_path = Utf8PathBuf::try_from(tmp_dir.path().to_path_buf()).unwrap();
+        let override_dir = tmp_path.join("rover");
+        let socket_name = "supergraph-127.0.0.1:4000.sock";
+
+        assert_eq!(
+            get_socket_path(
+                Some(override_dir.to_string()),
+                Some(tmp_path.to_string()),
+                socket_name
+            )
+            .unwrap(),
+            override_dir.join(socket_name).to_string()
+        );
+        assert!(override_dir.is_dir());
+        assert_eq!(
+            get_socket_path(None, Some(tmp_path.to_string()), socket_name).unwrap(),
+            tmp_path.join(socket_name).to_string()
+        );
+        assert_eq!(
+            get_socket_path(None, Some("/does/not/exist".to_string()), socket_name).unwrap(),
+            format!("/tmp/{}", socket_name)
+        );
+    }
+
+    #[test]
+    fn it_rejects_socket_paths_that_are_too_long() {
+        let long_dir = format!("/tmp/{}", "a".repeat(100));
+        assert!(get_socket_path(Some(long_dir), None, "supergraph-127.0.0.1:4000.sock").is_err());
+    }
+
+    #[test]
+    fn it_adds_header_propagation_to_existing_rules() {
+        let mut yaml: serde_yaml::Mapping = serde_yaml::from_str(
+            "headers:\n  all:\n    request:\n      - propagate:\n          named: authorization\n",
+        )
+        .unwrap();
+        propagate_header(&mut yaml, "x-rover-dev-operation-id").unwrap();
+        let rules = yaml["headers"]["all"]["request"].as_sequence().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[1]["propagate"]["named"].as_str(),
+            Some("x-rover-dev-operation-id")
+        );
+
+        let mut yaml = serde_yaml::Mapping::new();
+        propagate_header(&mut yaml, "x-rover-dev-operation-id").unwrap();
+        assert_eq!(
+            yaml["headers"]["all"]["request"][0]["propagate"]["named"].as_str(),
+            Some("x-rover-dev-operation-id")
+        );
+    }
+
+    #[test]
+    fn it_enforces_the_safelist_over_existing_persisted_query_config() {
+        let mut yaml: serde_yaml::Mapping = serde_yaml::from_str(
+            "persisted_queries:\n  enabled: false\n  log_unknown: true\napq:\n  enabled: true\n",
+        )
+        .unwrap();
+        enforce_safelist(&mut yaml, Utf8PathBuf::from("/app/manifest.json").as_path()).unwrap();
+        let persisted_queries = &yaml["persisted_queries"];
+        assert_eq!(persisted_queries["enabled"].as_bool(), Some(true));
+        assert_eq!(persisted_queries["log_unknown"].as_bool(), Some(true));
+        assert_eq!(
+            persisted_queries["local_manifests"][0].as_str(),
+            Some("/app/manifest.json")
+        );
+        assert_eq!(
+            persisted_queries["safelist"]["enabled"].as_bool(),
+            Some(true)
+        );
+        assert_eq!(yaml["apq"]["enabled"].as_bool(), Some(false));
+    }
+}
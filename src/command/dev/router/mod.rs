@@ -2,6 +2,8 @@ mod command;
 mod config;
 mod runner;
 
-pub use command::{BackgroundTask, BackgroundTaskLog};
+pub use command::{
+    BackgroundTask, BackgroundTaskLog, TaskEnvironment, DEFAULT_SHUTDOWN_GRACE_PERIOD,
+};
 pub use config::RouterConfigHandler;
 pub use runner::RouterRunner;
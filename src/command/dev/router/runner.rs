@@ -5,20 +5,20 @@ use crossbeam_channel::bounded;
 use reqwest::blocking::Client;
 use reqwest::Url;
 use rover_std::{Emoji, Style};
-use semver::Version;
 
 use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 
 use crate::command::dev::{
     do_dev::log_err_and_continue,
-    router::{BackgroundTask, BackgroundTaskLog},
+    router::{BackgroundTask, BackgroundTaskLog, TaskEnvironment, DEFAULT_SHUTDOWN_GRACE_PERIOD},
     OVERRIDE_DEV_ROUTER_VERSION,
 };
-use crate::command::install::Plugin;
+use crate::command::install::{Plugin, PluginSpec};
 use crate::command::Install;
-use crate::options::PluginOpts;
+use crate::options::{PluginChannel, PluginOpts};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::events::{self, Event};
 use crate::{RoverError, RoverResult};
 
 #[derive(Debug)]
@@ -27,11 +27,19 @@ pub struct RouterRunner {
     router_config_path: Utf8PathBuf,
     plugin_opts: PluginOpts,
     router_socket_addr: SocketAddr,
+    /// Where the router process listens, if it's behind a proxy on `router_socket_addr`
+    router_internal_addr: Option<SocketAddr>,
     router_listen_path: String,
     override_install_path: Option<Utf8PathBuf>,
     client_config: StudioClientConfig,
     plugin_exe: Option<Utf8PathBuf>,
     router_handle: Option<BackgroundTask>,
+    shutdown_grace_period: Duration,
+    router_environment: TaskEnvironment,
+    /// The router version to install, or the latest v1 router
+    router_version: Option<String>,
+    /// Whether to tell the user where to query the router once it's up
+    announce_startup: bool,
 }
 
 impl RouterRunner {
@@ -49,22 +57,65 @@ impl RouterRunner {
             router_config_path,
             plugin_opts,
             router_socket_addr,
+            router_internal_addr: None,
             router_listen_path,
             override_install_path,
             client_config,
             router_handle: None,
             plugin_exe: None,
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            router_environment: TaskEnvironment::default(),
+            router_version: OVERRIDE_DEV_ROUTER_VERSION.clone(),
+            announce_startup: true,
         }
     }
 
+    /// Sets the router version to install, like `1.33.0`, instead of the latest v1 router.
+    pub fn set_router_version(&mut self, router_version: String) {
+        self.router_version = Some(router_version);
+    }
+
+    /// Sets the directory the router runs in, and the environment variables it gets.
+    pub fn set_router_environment(&mut self, router_environment: TaskEnvironment) {
+        self.router_environment = router_environment;
+    }
+
+    /// Sets how long the router has to shut down on its own before it's killed.
+    pub fn set_shutdown_grace_period(&mut self, shutdown_grace_period: Duration) {
+        self.shutdown_grace_period = shutdown_grace_period;
+    }
+
+    /// Sets whether to print where to query the router, and emit a `router_ready` event, once it's
+    /// up. Commands that only run the router for their own requests turn this off.
+    pub fn set_announce_startup(&mut self, announce_startup: bool) {
+        self.announce_startup = announce_startup;
+    }
+
+    /// Checks on the router at `router_internal_addr` instead of the router address, for when
+    /// a proxy is listening on the router address.
+    pub fn set_internal_address(&mut self, router_internal_addr: SocketAddr) {
+        self.router_internal_addr = Some(router_internal_addr);
+    }
+
+    /// The address the router process listens on.
+    fn listen_address(&self) -> SocketAddr {
+        self.router_internal_addr.unwrap_or(self.router_socket_addr)
+    }
+
     fn install_command(&self) -> RoverResult<Install> {
-        let plugin = match &*OVERRIDE_DEV_ROUTER_VERSION {
-            Some(version) => Plugin::Router(RouterVersion::Exact(Version::parse(version)?)),
-            None => Plugin::Router(RouterVersion::Latest),
+        let plugin = match &self.router_version {
+            Some(version) => PluginSpec::router(version)?,
+            None if self.plugin_opts.channel == PluginChannel::Next => PluginSpec::Next {
+                name: "router".to_string(),
+            },
+            None => Plugin::Router(RouterVersion::Latest).into(),
         };
         Ok(Install {
             force: false,
             plugin: Some(plugin),
+            from: None,
+            list: false,
+            locked: self.plugin_opts.locked,
             elv2_license_accepter: self.plugin_opts.elv2_license_accepter,
         })
     }
@@ -87,9 +138,9 @@ impl RouterRunner {
     pub fn get_command_to_spawn(&mut self) -> RoverResult<String> {
         Ok(format!(
             "{plugin_exe} --supergraph {supergraph} --hot-reload --config {config} --log trace --dev",
-            plugin_exe = self.maybe_install_router()?,
-            supergraph = self.supergraph_schema_path.as_str(),
-            config = self.router_config_path.as_str(),
+            plugin_exe = shell_words::quote(self.maybe_install_router()?.as_str()),
+            supergraph = shell_words::quote(self.supergraph_schema_path.as_str()),
+            config = shell_words::quote(self.router_config_path.as_str()),
         ))
     }
 
@@ -99,7 +150,8 @@ impl RouterRunner {
         let seconds = 10;
         let base_url = format!(
             "http://{}{}",
-            &self.router_socket_addr, &self.router_listen_path
+            &self.listen_address(),
+            &self.router_listen_path
         );
         let mut endpoint =
             Url::parse(&base_url).with_context(|| format!("{base_url} is not a valid URL."))?;
@@ -117,18 +169,24 @@ impl RouterRunner {
         }
 
         if ready {
-            eprintln!(
-                "{}your supergraph is running! head to http://{}{} to query your supergraph",
-                Emoji::Rocket,
-                &self
-                    .router_socket_addr
-                    .to_string()
-                    .replace("127.0.0.1", "localhost")
-                    .replace("0.0.0.0", "localhost")
-                    .replace("[::]", "localhost")
-                    .replace("[::1]", "localhost"),
-                &self.router_listen_path
-            );
+            if self.announce_startup {
+                let url = format!(
+                    "http://{}{}",
+                    self.router_socket_addr
+                        .to_string()
+                        .replace("127.0.0.1", "localhost")
+                        .replace("0.0.0.0", "localhost")
+                        .replace("[::]", "localhost")
+                        .replace("[::1]", "localhost"),
+                    &self.router_listen_path
+                );
+                eprintln!(
+                    "{}your supergraph is running! head to {} to query your supergraph",
+                    Emoji::Rocket,
+                    &url
+                );
+                events::emit(Event::RouterReady { url });
+            }
             Ok(())
         } else {
             Err(RoverError::new(anyhow!(
@@ -145,7 +203,7 @@ impl RouterRunner {
             let _ = client
                 .get(format!(
                     "http://{}/?query={{__typename}}",
-                    &self.router_socket_addr
+                    &self.listen_address()
                 ))
                 .header("Content-Type", "application/json")
                 .send()
@@ -169,12 +227,14 @@ impl RouterRunner {
             let client = self.client_config.get_reqwest_client()?;
             self.maybe_install_router()?;
             let (router_log_sender, router_log_receiver) = bounded(0);
-            let router_handle = BackgroundTask::new(
+            let mut router_handle = BackgroundTask::new(
                 self.get_command_to_spawn()?,
                 router_log_sender,
                 &self.client_config,
                 &self.plugin_opts.profile,
+                &self.router_environment,
             )?;
+            router_handle.set_shutdown_grace_period(self.shutdown_grace_period);
             tracing::info!("spawning router with `{}`", router_handle.descriptor());
 
             let warn_prefix = Style::WarningPrefix.paint("WARN:");
@@ -229,9 +289,14 @@ impl RouterRunner {
         }
     }
 
+    /// The PID of the running router, if any
+    pub fn router_pid(&self) -> Option<u32> {
+        self.router_handle.as_ref().map(BackgroundTask::id)
+    }
+
     pub fn kill(&mut self) -> RoverResult<()> {
         if self.router_handle.is_some() {
-            tracing::info!("killing the router");
+            tracing::info!("stopping the router");
             self.router_handle = None;
             if let Ok(client) = self.client_config.get_reqwest_client() {
                 let _ = self.wait_for_stop(client).map_err(log_err_and_continue);
@@ -1,29 +1,52 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use anyhow::anyhow;
-use apollo_federation_types::config::SchemaSource;
+use apollo_federation_types::config::{RouterVersion, SchemaSource};
+use camino::Utf8Path;
 use reqwest::Url;
 use rover_client::blocking::StudioClient;
-use rover_std::Fs;
+use rover_std::{Emoji, Fs};
 
-use crate::command::supergraph::expand_supergraph_yaml;
+use crate::command::supergraph::expand_supergraph_yaml_with_timeouts;
 use crate::options::ProfileOpt;
 use crate::{
     command::dev::{
-        netstat::normalize_loopback_urls, protocol::FollowerMessenger,
-        watcher::SubgraphSchemaWatcher, SupergraphOpts,
+        chaos::ChaosConfig,
+        netstat::normalize_loopback_urls,
+        port_file::PortFile,
+        protocol::FollowerMessenger,
+        reload::SubgraphWatcherSet,
+        router::TaskEnvironment,
+        startup::{HealthCheck, StartupOrder},
+        watcher::SubgraphSchemaWatcher,
+        SupergraphOpts, OVERRIDE_DEV_ROUTER_VERSION,
     },
+    command::install::{Plugin, PluginSpec},
     options::OptionalSubgraphOpts,
-    utils::client::StudioClientConfig,
+    utils::{
+        client::{ClientBuilder, StudioClientConfig},
+        source_map::SubgraphSourceMap,
+    },
     RoverError, RoverErrorSuggestion, RoverResult,
 };
 
+/// How long to wait for a subgraph to respond to introspection, unless `--client-timeout` or
+/// `rover config client` set a timeout
+const INTROSPECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Subgraphs are introspected again on every poll, so failed introspection requests are only
+/// retried if `--client-retries` or `rover config client` ask for it.
+fn introspection_retries(client_builder: ClientBuilder) -> u32 {
+    client_builder.get_retries().unwrap_or(0)
+}
+
 impl OptionalSubgraphOpts {
     pub fn get_subgraph_watcher(
         &self,
         router_socket_addr: SocketAddr,
         client_config: &StudioClientConfig,
         follower_messenger: FollowerMessenger,
+        profile_opt: &ProfileOpt,
     ) -> RoverResult<SubgraphSchemaWatcher> {
         tracing::info!("checking version");
         follower_messenger.version_check()?;
@@ -74,25 +97,148 @@ impl OptionalSubgraphOpts {
             }
         }
 
-        if let Some(schema) = schema {
-            SubgraphSchemaWatcher::new_from_file_path((name, url), schema, follower_messenger)
+        let mut watcher = if let Some(schema) = schema {
+            SubgraphSchemaWatcher::new_from_file_path((name, url), schema, follower_messenger)?
         } else {
-            let client = client_config
-                .get_builder()
-                .with_timeout(Duration::from_secs(5))
-                .build()?;
+            let client_builder = client_config
+                .get_endpoint_builder(profile_opt)
+                .with_default_timeout(INTROSPECTION_TIMEOUT);
             SubgraphSchemaWatcher::new_from_url(
                 (name, url),
-                client,
+                client_builder.build()?,
                 follower_messenger,
                 self.subgraph_polling_interval,
-                None,
-            )
+                self.subgraph_introspection_headers
+                    .clone()
+                    .map(|headers| headers.into_iter().collect()),
+                introspection_retries(client_builder),
+            )?
+        };
+        if let Some(url) = &self.subgraph_health_check {
+            watcher.set_health_check(HealthCheck { url: url.clone() });
         }
+        Ok(watcher)
     }
 }
 
 impl SupergraphOpts {
+    /// How long to wait for changes to settle before acting on them
+    pub fn watch_debounce(&self) -> Duration {
+        Duration::from_millis(self.watch_debounce)
+    }
+
+    /// How long to wait for subgraphs, and what they depend on, to be ready
+    pub fn startup_timeout(&self) -> Duration {
+        Duration::from_secs(self.startup_timeout)
+    }
+
+    /// How long the router has to shut down on its own before it's killed
+    pub fn shutdown_grace_period(&self) -> Duration {
+        Duration::from_secs(self.shutdown_grace_period)
+    }
+
+    /// The directory the router runs in, and the environment variables it gets
+    pub fn router_environment(&self) -> RoverResult<TaskEnvironment> {
+        if let Some(router_dir) = &self.router_dir {
+            if !router_dir.is_dir() {
+                let mut err = RoverError::new(anyhow!(
+                    "The router directory '{}' does not exist.",
+                    router_dir
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass an existing directory to `--router-dir`.".to_string(),
+                ));
+                return Err(err);
+            }
+        }
+        Ok(TaskEnvironment {
+            envs: self.router_envs.clone(),
+            current_dir: self.router_dir.clone(),
+        })
+    }
+
+    /// The router version to run, from `--router-version` or `APOLLO_ROVER_DEV_ROUTER_VERSION`,
+    /// checked before anything is installed
+    pub fn router_version(&self) -> RoverResult<Option<String>> {
+        let Some(version) = self
+            .router_version
+            .clone()
+            .or_else(|| OVERRIDE_DEV_ROUTER_VERSION.clone())
+        else {
+            return Ok(None);
+        };
+        if let PluginSpec::Plugin(Plugin::Router(RouterVersion::Exact(exact))) =
+            PluginSpec::router(&version)?
+        {
+            if exact.major != 1 {
+                let mut err = RoverError::new(anyhow!(
+                    "`rover dev` only runs v1 routers, but v{} was requested.",
+                    exact
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass a v1 router to `--router-version`, like `1.33.0`.".to_string(),
+                ));
+                return Err(err);
+            }
+        }
+        Ok(Some(version))
+    }
+
+    /// Adds a watcher for each `--remote-subgraph` to `subgraph_watchers`, which fetches its schema
+    /// from `--graph-ref` and keeps it up to date.
+    pub fn add_remote_subgraph_watchers(
+        &self,
+        client_config: &StudioClientConfig,
+        follower_messenger: FollowerMessenger,
+        profile_opt: &ProfileOpt,
+        subgraph_watchers: &mut Vec<SubgraphSchemaWatcher>,
+    ) -> RoverResult<()> {
+        let graph_ref = match &self.graph_ref {
+            Some(graph_ref) if !self.remote_subgraphs.is_empty() => graph_ref,
+            _ => return Ok(()),
+        };
+        let client = Arc::new(client_config.get_authenticated_client(profile_opt)?);
+        for subgraph_name in &self.remote_subgraphs {
+            if subgraph_watchers
+                .iter()
+                .any(|watcher| &watcher.get_name() == subgraph_name)
+            {
+                let mut err = RoverError::new(anyhow!(
+                    "subgraph '{}' is running locally, so it can't also be fetched from {}",
+                    subgraph_name,
+                    graph_ref
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Remove `--remote-subgraph {}` to compose the local subgraph instead.",
+                    subgraph_name
+                )));
+                return Err(err);
+            }
+            subgraph_watchers.push(SubgraphSchemaWatcher::new_from_studio(
+                graph_ref.clone(),
+                subgraph_name.clone(),
+                client.clone(),
+                follower_messenger.clone(),
+                self.remote_refresh_interval,
+            )?);
+        }
+        Ok(())
+    }
+
+    /// The files that subgraph schemas in the supergraph config are watched at, so composition
+    /// errors can point at them. This doesn't follow `include`s or fetch remote sources.
+    pub fn get_source_map(&self) -> SubgraphSourceMap {
+        self.supergraph_config_path
+            .as_ref()
+            .and_then(|path| Fs::read_file(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .map(|supergraph_config| {
+                // watchers read schema files relative to the working directory, so do the same here
+                SubgraphSourceMap::from_supergraph_config(&supergraph_config, None)
+            })
+            .unwrap_or_default()
+    }
+
     pub fn get_subgraph_watchers(
         &self,
         client_config: &StudioClientConfig,
@@ -109,15 +255,78 @@ impl SupergraphOpts {
         tracing::info!("checking version");
         follower_messenger.version_check()?;
 
+        let (config_content, mut subgraph_watchers) = self.read_subgraph_watchers(
+            config_path,
+            client_config,
+            follower_messenger,
+            polling_interval,
+            profile_opt,
+        )?;
+        StartupOrder::from_supergraph_yaml(&config_content)?.apply(&mut subgraph_watchers)?;
+        follow_port_files(&config_content, config_path, &mut subgraph_watchers)?;
+        inject_faults(&config_content, &mut subgraph_watchers)?;
+        Ok(Some(subgraph_watchers))
+    }
+
+    /// Reads the supergraph config again after it's changed, and has `watcher_set` start, stop,
+    /// and restart subgraph watchers to match it.
+    pub fn reload_subgraph_watchers(
+        &self,
+        client_config: &StudioClientConfig,
+        polling_interval: u64,
+        profile_opt: &ProfileOpt,
+        watcher_set: &mut SubgraphWatcherSet,
+    ) -> RoverResult<()> {
+        let config_path = match &self.supergraph_config_path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let (config_content, mut subgraph_watchers) = self.read_subgraph_watchers(
+            config_path,
+            client_config,
+            watcher_set.follower_messenger(),
+            polling_interval,
+            profile_opt,
+        )?;
+        StartupOrder::from_supergraph_yaml(&config_content)?.apply(&mut subgraph_watchers)?;
+        self.add_remote_subgraph_watchers(
+            client_config,
+            watcher_set.follower_messenger(),
+            profile_opt,
+            &mut subgraph_watchers,
+        )?;
+        let mut new_watchers = watcher_set.reconcile(subgraph_watchers)?;
+        follow_port_files(&config_content, config_path, &mut new_watchers)?;
+        inject_faults(&config_content, &mut new_watchers)?;
+        watcher_set.start(new_watchers);
+        Ok(())
+    }
+
+    /// Builds a watcher for every subgraph in the supergraph config at `config_path`, and returns
+    /// them along with the config's contents.
+    fn read_subgraph_watchers(
+        &self,
+        config_path: &Utf8Path,
+        client_config: &StudioClientConfig,
+        follower_messenger: FollowerMessenger,
+        polling_interval: u64,
+        profile_opt: &ProfileOpt,
+    ) -> RoverResult<(String, Vec<SubgraphSchemaWatcher>)> {
         let config_content = Fs::read_file(config_path)?;
-        let supergraph_config = expand_supergraph_yaml(&config_content)?;
+        let (supergraph_config, introspection_timeouts) = expand_supergraph_yaml_with_timeouts(
+            &config_content,
+            config_path.parent(),
+            &[],
+            client_config,
+            false,
+        )?;
 
-        let client = client_config
-            .get_builder()
-            .with_timeout(Duration::from_secs(5))
-            .build()?;
+        let client_builder = client_config
+            .get_endpoint_builder(profile_opt)
+            .with_default_timeout(INTROSPECTION_TIMEOUT);
+        let client = client_builder.build()?;
         let mut studio_client: Option<StudioClient> = None;
-        supergraph_config
+        let subgraph_watchers = supergraph_config
             .into_iter()
             .map(|(yaml_subgraph_name, subgraph_config)| {
                 let routing_url = subgraph_config
@@ -138,13 +347,23 @@ impl SupergraphOpts {
                     SchemaSource::SubgraphIntrospection {
                         subgraph_url,
                         introspection_headers,
-                    } => SubgraphSchemaWatcher::new_from_url(
-                        (yaml_subgraph_name, subgraph_url),
-                        client.clone(),
-                        follower_messenger.clone(),
-                        polling_interval,
-                        introspection_headers,
-                    ),
+                    } => {
+                        let client = match introspection_timeouts.get(&yaml_subgraph_name) {
+                            Some(timeout) => client_config
+                                .get_endpoint_builder(profile_opt)
+                                .with_timeout(timeout)
+                                .build()?,
+                            None => client.clone(),
+                        };
+                        SubgraphSchemaWatcher::new_from_url(
+                            (yaml_subgraph_name, subgraph_url),
+                            client,
+                            follower_messenger.clone(),
+                            polling_interval,
+                            introspection_headers,
+                            introspection_retries(client_builder),
+                        )
+                    }
                     SchemaSource::Sdl { sdl } => {
                         let routing_url = routing_url.ok_or_else(|| {
                             anyhow!("`routing_url` must be set when providing SDL directly")
@@ -178,7 +397,44 @@ impl SupergraphOpts {
                     }
                 }
             })
-            .collect::<RoverResult<Vec<_>>>()
-            .map(Some)
+            .collect::<RoverResult<Vec<_>>>()?;
+        Ok((config_content, subgraph_watchers))
+    }
+}
+
+/// Has the subgraphs with a `port_file` in the supergraph config follow the port they write to it.
+fn follow_port_files(
+    config_content: &str,
+    config_path: &Utf8Path,
+    subgraph_watchers: &mut [SubgraphSchemaWatcher],
+) -> RoverResult<()> {
+    let port_files = PortFile::from_supergraph_yaml(config_content, config_path.parent())?;
+    for watcher in subgraph_watchers.iter_mut() {
+        if let Some(port_file) = port_files.get(&watcher.get_name()) {
+            watcher.set_port_file(port_file.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Routes requests to the subgraphs that the supergraph config injects faults into through a
+/// chaos proxy.
+fn inject_faults(
+    config_content: &str,
+    subgraph_watchers: &mut [SubgraphSchemaWatcher],
+) -> RoverResult<()> {
+    let chaos_configs = ChaosConfig::from_supergraph_yaml(config_content)?;
+    for watcher in subgraph_watchers.iter_mut() {
+        if let Some(chaos) = chaos_configs.get(&watcher.get_name()) {
+            let proxy_url = chaos.start_proxy(&watcher.get_name(), &watcher.get_url())?;
+            eprintln!(
+                "{}injecting faults into requests to subgraph '{}' through {}",
+                Emoji::Warn,
+                watcher.get_name(),
+                proxy_url
+            );
+            watcher.set_routing_url_override(proxy_url);
+        }
     }
+    Ok(())
 }
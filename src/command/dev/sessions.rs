@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::Emoji;
+use serde::Serialize;
+
+use super::protocol::{
+    session_state, FollowerMessenger, RegisteredSession, SessionRegistry, SessionState,
+};
+use super::{Dev, DevOpts, DevSession};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// How long to wait for a session to stop after asking it to, which covers the router's
+/// shutdown grace period.
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Serialize, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let sessions = SessionRegistry::new(&client_config.config)
+            .list()
+            .into_iter()
+            .map(|session| {
+                let subgraphs = FollowerMessenger::from_attached_session(&session.ipc_socket_addr)
+                    .session_subgraphs()
+                    .map_err(|e| {
+                        tracing::info!(
+                            "could not get the subgraphs of the session on {}: {}",
+                            session.router_address,
+                            e
+                        )
+                    })
+                    .ok()
+                    .flatten()
+                    .map(|subgraphs| {
+                        subgraphs
+                            .into_iter()
+                            .map(|(name, url)| (name, url.to_string()))
+                            .collect()
+                    });
+                DevSession {
+                    router_address: session.router_address,
+                    pid: session.pid,
+                    subgraphs,
+                }
+            })
+            .collect();
+        Ok(RoverOutput::DevSessionList(sessions))
+    }
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Stop {
+    /// The port of the session's router, or its full address, like `127.0.0.1:4000`
+    #[arg(value_name = "SESSION")]
+    session: String,
+}
+
+impl Stop {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let RegisteredSession {
+            router_address,
+            ipc_socket_addr,
+            pid,
+        } = SessionRegistry::new(&client_config.config).find(&self.session)?;
+        eprintln!(
+            "{}stopping the `rover dev` session on {} (PID {})...",
+            Emoji::Stop,
+            router_address,
+            pid
+        );
+        FollowerMessenger::from_attached_session(&ipc_socket_addr).shutdown_session();
+
+        let started = Instant::now();
+        while session_state(&ipc_socket_addr) == SessionState::Running(pid) {
+            if started.elapsed() > STOP_TIMEOUT {
+                let mut err = RoverError::new(anyhow!(
+                    "The `rover dev` session on {} did not stop after {}s.",
+                    router_address,
+                    STOP_TIMEOUT.as_secs()
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Press CTRL+C in the terminal running the session, or stop PID {}.",
+                    pid
+                )));
+                return Err(err);
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        eprintln!(
+            "{}stopped the `rover dev` session on {}",
+            Emoji::Success,
+            router_address
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Attach {
+    /// The port of the session's router, or its full address, like `127.0.0.1:4000`
+    #[arg(
+        value_name = "SESSION",
+        conflicts_with_all = ["supergraph_port", "supergraph_address"]
+    )]
+    session: String,
+
+    #[clap(flatten)]
+    opts: DevOpts,
+}
+
+impl Attach {
+    pub fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        let session = SessionRegistry::new(&client_config.config).find(&self.session)?;
+        let mut opts = self.opts.clone();
+        opts.supergraph_opts.supergraph_address = Some(session.router_address.ip());
+        opts.supergraph_opts.supergraph_port = Some(session.router_address.port());
+        Dev {
+            command: None,
+            opts,
+        }
+        .run(override_install_path, client_config)
+    }
+}
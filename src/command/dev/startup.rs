@@ -0,0 +1,448 @@
+//! Starts subgraphs after the subgraphs and services they depend on are up, so a subgraph that
+//! needs a database or an auth service isn't introspected before those accept connections.
+//! Subgraphs list their dependencies with `depends_on`, and services outside the supergraph are
+//! declared with the address to wait for:
+//!
+//! ```yaml
+//! services:
+//!   postgres:
+//!     address: localhost:5432
+//! subgraphs:
+//!   users:
+//!     routing_url: http://localhost:4002
+//!     schema:
+//!       subgraph_url: http://localhost:4002
+//!     depends_on: [postgres]
+//!   products:
+//!     routing_url: http://localhost:4001
+//!     schema:
+//!       file: ./products.graphql
+//!     depends_on: [users]
+//!     health_check: http://localhost:4001/health
+//! ```
+//!
+//! A subgraph with a `health_check` isn't added to the session until that endpoint responds
+//! successfully. Other subgraphs that are introspected aren't added until they respond to
+//! introspection, so a slow-starting server doesn't fail composition while it boots.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use rover_std::{Emoji, Style};
+use serde::Deserialize;
+use url::Url;
+
+use crate::command::dev::watcher::SubgraphSchemaWatcher;
+use crate::utils::client::ClientBuilder;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How long a subgraph waits for each of its dependencies, and to be ready itself, before
+/// starting anyway, unless `--startup-timeout` says otherwise
+pub(crate) const READINESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often to check whether a dependency or a subgraph is ready
+const READINESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a single request to a health check endpoint can take
+const HEALTH_CHECK_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A service outside the supergraph that subgraphs can depend on.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExternalService {
+    /// The `host:port` the service accepts connections on once it's ready
+    pub(crate) address: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StartupSupergraphConfig {
+    #[serde(default)]
+    services: BTreeMap<String, ExternalService>,
+    #[serde(default)]
+    subgraphs: BTreeMap<String, StartupSubgraphConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StartupSubgraphConfig {
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default)]
+    health_check: Option<Url>,
+}
+
+/// A subgraph or service that has to accept connections before a subgraph starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    /// The `host:port` to connect to
+    pub address: String,
+}
+
+impl Dependency {
+    /// Blocks until the dependency accepts a TCP connection. Gives up with a warning after
+    /// `timeout`, since the subgraph might not need the dependency to serve its schema.
+    pub fn wait_until_ready(&self, subgraph_name: &str, timeout: Duration) {
+        wait_until(
+            &format!("'{}' at {}", self.name, self.address),
+            subgraph_name,
+            timeout,
+            || self.is_ready(),
+        );
+    }
+
+    fn is_ready(&self) -> bool {
+        self.address
+            .to_socket_addrs()
+            .map(|mut addrs| {
+                addrs.any(|addr| TcpStream::connect_timeout(&addr, READINESS_INTERVAL).is_ok())
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// An HTTP endpoint that responds successfully once a subgraph is ready to serve its schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub url: Url,
+}
+
+impl HealthCheck {
+    /// Blocks until the endpoint responds with a success status. Gives up with a warning after
+    /// `timeout`, so a subgraph with a broken health check still gets introspected.
+    pub fn wait_until_healthy(&self, subgraph_name: &str, timeout: Duration) {
+        let client = ClientBuilder::new()
+            .with_timeout(HEALTH_CHECK_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        wait_until(
+            &format!("the health check at {}", self.url),
+            subgraph_name,
+            timeout,
+            || {
+                client
+                    .get(self.url.clone())
+                    .send()
+                    .map(|response| response.status().is_success())
+                    .unwrap_or(false)
+            },
+        );
+    }
+}
+
+/// Calls `is_ready` every `READINESS_INTERVAL` until it returns true, or until `timeout` has passed,
+/// and returns whether it became ready. `waiting_for` describes what `subgraph_name` is waiting
+/// for in the messages printed along the way.
+pub(crate) fn wait_until(
+    waiting_for: &str,
+    subgraph_name: &str,
+    timeout: Duration,
+    mut is_ready: impl FnMut() -> bool,
+) -> bool {
+    let started = Instant::now();
+    let mut announced = false;
+    loop {
+        if is_ready() {
+            return true;
+        }
+        if !announced {
+            eprintln!(
+                "{}waiting for {} before starting subgraph '{}'",
+                Emoji::Hourglass,
+                waiting_for,
+                subgraph_name
+            );
+            announced = true;
+        }
+        if started.elapsed() >= timeout {
+            eprintln!(
+                "{} gave up waiting for {} after {} seconds, starting subgraph '{}' anyway",
+                Style::WarningPrefix.paint("WARN:"),
+                waiting_for,
+                timeout.as_secs(),
+                subgraph_name
+            );
+            return false;
+        }
+        std::thread::sleep(READINESS_INTERVAL);
+    }
+}
+
+/// The `depends_on` lists and health checks of the subgraphs in a supergraph config, and the
+/// services they can name.
+#[derive(Debug, Default)]
+pub(crate) struct StartupOrder {
+    services: BTreeMap<String, ExternalService>,
+    depends_on: BTreeMap<String, Vec<String>>,
+    health_checks: BTreeMap<String, Url>,
+}
+
+impl StartupOrder {
+    /// Reads the `services` section, and the `depends_on` list and `health_check` of each
+    /// subgraph in a supergraph config.
+    pub(crate) fn from_supergraph_yaml(yaml: &str) -> RoverResult<Self> {
+        let config: StartupSupergraphConfig = serde_yaml::from_str(yaml).context(
+            "could not read the `services`, `depends_on`, and `health_check` sections of the supergraph config",
+        )?;
+        let mut depends_on = BTreeMap::new();
+        let mut health_checks = BTreeMap::new();
+        for (name, subgraph) in config.subgraphs {
+            if let Some(health_check) = subgraph.health_check {
+                health_checks.insert(name.clone(), health_check);
+            }
+            if !subgraph.depends_on.is_empty() {
+                depends_on.insert(name, subgraph.depends_on);
+            }
+        }
+        Ok(Self {
+            services: config.services,
+            depends_on,
+            health_checks,
+        })
+    }
+
+    /// Sorts `subgraph_watchers` so every subgraph comes after the subgraphs it depends on, and
+    /// tells each watcher which subgraphs and services to wait for before it starts, and which
+    /// health check to wait for before its subgraph is added to the session.
+    pub(crate) fn apply(
+        &self,
+        subgraph_watchers: &mut Vec<SubgraphSchemaWatcher>,
+    ) -> RoverResult<()> {
+        let subgraph_addresses: HashMap<String, String> = subgraph_watchers
+            .iter()
+            .map(|watcher| {
+                let url = watcher.get_url();
+                let address = format!(
+                    "{}:{}",
+                    url.host_str().unwrap_or("localhost"),
+                    url.port_or_known_default().unwrap_or(80)
+                );
+                (watcher.get_name(), address)
+            })
+            .collect();
+
+        let order = self.sort(subgraph_addresses.keys().cloned().collect())?;
+        let mut watchers_by_name: HashMap<String, SubgraphSchemaWatcher> = subgraph_watchers
+            .drain(..)
+            .map(|watcher| (watcher.get_name(), watcher))
+            .collect();
+        for name in order {
+            let mut watcher = watchers_by_name
+                .remove(&name)
+                .expect("every sorted subgraph has a watcher");
+            let dependencies = self
+                .depends_on
+                .get(&name)
+                .into_iter()
+                .flatten()
+                .map(|dependency| Dependency {
+                    name: dependency.clone(),
+                    address: match self.services.get(dependency) {
+                        Some(service) => service.address.clone(),
+                        None => subgraph_addresses[dependency].clone(),
+                    },
+                })
+                .collect();
+            watcher.set_dependencies(dependencies);
+            if let Some(url) = self.health_checks.get(&name) {
+                watcher.set_health_check(HealthCheck { url: url.clone() });
+            }
+            subgraph_watchers.push(watcher);
+        }
+        Ok(())
+    }
+
+    /// Orders `subgraph_names` so dependencies come first, breaking ties by name.
+    fn sort(&self, subgraph_names: BTreeSet<String>) -> RoverResult<Vec<String>> {
+        for (name, dependencies) in &self.depends_on {
+            if !subgraph_names.contains(name) {
+                continue;
+            }
+            if let Some(unknown) = dependencies.iter().find(|dependency| {
+                !subgraph_names.contains(*dependency) && !self.services.contains_key(*dependency)
+            }) {
+                let mut err = RoverError::new(anyhow!(
+                    "subgraph '{}' depends on '{}', which isn't a subgraph or a service in the supergraph config",
+                    name,
+                    unknown
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Declare services outside the supergraph in the top-level `services` section, like `{}: {{ address: localhost:5432 }}`.",
+                    unknown
+                )));
+                return Err(err);
+            }
+        }
+
+        let mut remaining = subgraph_names;
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let ready: Vec<String> = remaining
+                .iter()
+                .filter(|name| {
+                    self.depends_on
+                        .get(*name)
+                        .into_iter()
+                        .flatten()
+                        .all(|dependency| !remaining.contains(dependency))
+                })
+                .cloned()
+                .collect();
+            if ready.is_empty() {
+                let mut err = RoverError::new(anyhow!(
+                    "the `depends_on` lists of subgraphs {} form a cycle",
+                    remaining
+                        .iter()
+                        .map(|name| format!("'{}'", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Remove one of the dependencies so each subgraph can start after the ones it depends on.".to_string(),
+                ));
+                return Err(err);
+            }
+            for name in ready {
+                remaining.remove(&name);
+                order.push(name);
+            }
+        }
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    use super::{wait_until, Dependency, StartupOrder};
+
+    fn names(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn it_sorts_subgraphs_after_their_dependencies() {
+        let startup = StartupOrder::from_supergraph_yaml(
+            r#"
+services:
+  postgres:
+    address: localhost:5432
+subgraphs:
+  products:
+    routing_url: http://localhost:4001
+    schema:
+      file: ./products.graphql
+    depends_on: [users, postgres]
+  users:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./users.graphql
+    depends_on: [postgres]
+  accounts:
+    routing_url: http://localhost:4003
+    schema:
+      file: ./accounts.graphql
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            startup
+                .sort(names(&["accounts", "products", "users"]))
+                .unwrap(),
+            vec!["accounts", "users", "products"]
+        );
+    }
+
+    #[test]
+    fn it_rejects_cycles_and_unknown_dependencies() {
+        let startup = StartupOrder::from_supergraph_yaml(
+            "subgraphs:\n  products:\n    depends_on: [users]\n  users:\n    depends_on: [products]\n",
+        )
+        .unwrap();
+        assert!(startup.sort(names(&["products", "users"])).is_err());
+
+        let startup = StartupOrder::from_supergraph_yaml(
+            "subgraphs:\n  products:\n    depends_on: [postgres]\n",
+        )
+        .unwrap();
+        assert!(startup.sort(names(&["products"])).is_err());
+    }
+
+    #[test]
+    fn it_waits_for_dependencies_to_accept_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ready = Dependency {
+            name: "postgres".to_string(),
+            address: listener.local_addr().unwrap().to_string(),
+        };
+        let started = Instant::now();
+        ready.wait_until_ready("users", Duration::from_secs(5));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let down = Dependency {
+            name: "postgres".to_string(),
+            address,
+        };
+        let started = Instant::now();
+        down.wait_until_ready("users", Duration::from_secs(1));
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn it_reads_health_checks() {
+        let startup = StartupOrder::from_supergraph_yaml(
+            r#"
+subgraphs:
+  products:
+    routing_url: http://localhost:4001
+    schema:
+      subgraph_url: http://localhost:4001
+    health_check: http://localhost:4001/health
+  users:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./users.graphql
+"#,
+        )
+        .unwrap();
+        assert_eq!(startup.health_checks.len(), 1);
+        assert_eq!(
+            startup.health_checks["products"].as_str(),
+            "http://localhost:4001/health"
+        );
+        assert!(StartupOrder::from_supergraph_yaml(
+            "subgraphs:\n  products:\n    health_check: not a url\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_waits_until_ready_or_timed_out() {
+        let mut attempts = 0;
+        assert!(wait_until(
+            "products",
+            "users",
+            Duration::from_secs(5),
+            || {
+                attempts += 1;
+                attempts == 2
+            }
+        ));
+        assert_eq!(attempts, 2);
+
+        let started = Instant::now();
+        assert!(!wait_until(
+            "products",
+            "users",
+            Duration::from_secs(1),
+            || false
+        ));
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+}
@@ -1,13 +1,18 @@
 use crate::{
     command::dev::{
         introspect::{IntrospectRunnerKind, UnknownIntrospectRunner},
+        port_file::PortFile,
         protocol::{FollowerMessenger, SubgraphKey},
+        startup::{wait_until, Dependency, HealthCheck, READINESS_TIMEOUT},
     },
     RoverError, RoverErrorSuggestion, RoverResult,
 };
 use anyhow::{anyhow, Context};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use apollo_federation_types::build::SubgraphDefinition;
 use camino::{Utf8Path, Utf8PathBuf};
@@ -16,8 +21,8 @@ use reqwest::blocking::Client;
 use rover_client::blocking::StudioClient;
 use rover_client::operations::subgraph::fetch;
 use rover_client::operations::subgraph::fetch::SubgraphFetchInput;
-use rover_client::shared::GraphRef;
-use rover_std::{Emoji, Fs};
+use rover_client::shared::{FetchResponse, GraphRef, SdlType};
+use rover_std::{Emoji, Fs, Style};
 use url::Url;
 
 #[derive(Debug)]
@@ -25,6 +30,19 @@ pub struct SubgraphSchemaWatcher {
     schema_watcher_kind: SubgraphSchemaWatcherKind,
     subgraph_key: SubgraphKey,
     message_sender: FollowerMessenger,
+    debounce: Option<Duration>,
+    /// Where the router should send requests instead of the subgraph's own URL, like a chaos proxy
+    routing_url_override: Option<Url>,
+    /// Set when the subgraph is removed from the supergraph config, so the watcher stops
+    stopped: Arc<AtomicBool>,
+    /// The subgraphs and services that must accept connections before the watcher starts
+    dependencies: Vec<Dependency>,
+    /// A file the subgraph writes its port to, for subgraphs that start on a different port each time
+    port_file: Option<PortFile>,
+    /// An endpoint that must respond successfully before the subgraph is added to the session
+    health_check: Option<HealthCheck>,
+    /// How long to wait for dependencies, and for the subgraph to be ready, before starting anyway
+    startup_timeout: Duration,
 }
 
 impl SubgraphSchemaWatcher {
@@ -40,6 +58,13 @@ impl SubgraphSchemaWatcher {
             schema_watcher_kind: SubgraphSchemaWatcherKind::File(path.as_ref().to_path_buf()),
             subgraph_key,
             message_sender,
+            debounce: None,
+            routing_url_override: None,
+            stopped: Arc::new(AtomicBool::new(false)),
+            dependencies: Vec::new(),
+            port_file: None,
+            health_check: None,
+            startup_timeout: READINESS_TIMEOUT,
         })
     }
 
@@ -49,11 +74,13 @@ impl SubgraphSchemaWatcher {
         message_sender: FollowerMessenger,
         polling_interval: u64,
         headers: Option<HashMap<String, String>>,
+        retries: u32,
     ) -> RoverResult<Self> {
         let (_, url) = subgraph_key.clone();
         let headers = headers.map(|header_map| header_map.into_iter().collect());
-        let introspect_runner =
-            IntrospectRunnerKind::Unknown(UnknownIntrospectRunner::new(url, client, headers));
+        let introspect_runner = IntrospectRunnerKind::Unknown(UnknownIntrospectRunner::new(
+            url, client, headers, retries,
+        ));
         Self::new_from_introspect_runner(
             subgraph_key,
             introspect_runner,
@@ -71,6 +98,13 @@ impl SubgraphSchemaWatcher {
             schema_watcher_kind: SubgraphSchemaWatcherKind::Once(sdl),
             subgraph_key,
             message_sender,
+            debounce: None,
+            routing_url_override: None,
+            stopped: Arc::new(AtomicBool::new(false)),
+            dependencies: Vec::new(),
+            port_file: None,
+            health_check: None,
+            startup_timeout: READINESS_TIMEOUT,
         })
     }
 
@@ -92,29 +126,14 @@ impl SubgraphSchemaWatcher {
             client,
         )
         .map_err(RoverError::from)?;
-        let routing_url = match (routing_url, response.sdl.r#type) {
-            (Some(routing_url), _) => routing_url,
-            (
-                None,
-                rover_client::shared::SdlType::Subgraph {
-                    routing_url: Some(graph_registry_routing_url),
-                },
-            ) => graph_registry_routing_url.parse().context(format!(
-                "Could not parse graph registry routing url {}",
-                graph_registry_routing_url
-            ))?,
-            (None, _) => {
-                return Err(RoverError::new(anyhow!(
-                    "Could not find routing URL in GraphOS for subgraph {graphos_subgraph_name}"
-                ))
-                .with_suggestion(RoverErrorSuggestion::AddRoutingUrlToSupergraphYaml)
-                .with_suggestion(
-                    RoverErrorSuggestion::PublishSubgraphWithRoutingUrl {
-                        subgraph_name: yaml_subgraph_name,
-                        graph_ref: graph_ref.to_string(),
-                    },
-                ));
-            }
+        let routing_url = match routing_url {
+            Some(routing_url) => routing_url,
+            None => graphos_routing_url(
+                graph_ref,
+                &graphos_subgraph_name,
+                yaml_subgraph_name.clone(),
+                response.sdl.r#type,
+            )?,
         };
         Self::new_from_sdl(
             (yaml_subgraph_name, routing_url),
@@ -123,6 +142,44 @@ impl SubgraphSchemaWatcher {
         )
     }
 
+    /// Composes a subgraph published to GraphOS, fetching its schema again every
+    /// `refresh_interval` seconds so the session picks up new publishes.
+    pub fn new_from_studio(
+        graph_ref: GraphRef,
+        subgraph_name: String,
+        client: Arc<StudioClient>,
+        message_sender: FollowerMessenger,
+        refresh_interval: u64,
+    ) -> RoverResult<Self> {
+        let fetcher = StudioSubgraphFetcher {
+            client,
+            graph_ref,
+            subgraph_name,
+        };
+        let response = fetcher.fetch()?;
+        let routing_url = graphos_routing_url(
+            &fetcher.graph_ref.to_string(),
+            &fetcher.subgraph_name,
+            fetcher.subgraph_name.clone(),
+            response.sdl.r#type,
+        )?;
+        Ok(Self {
+            schema_watcher_kind: SubgraphSchemaWatcherKind::Studio(
+                fetcher.clone(),
+                refresh_interval,
+            ),
+            subgraph_key: (fetcher.subgraph_name, routing_url),
+            message_sender,
+            debounce: None,
+            routing_url_override: None,
+            stopped: Arc::new(AtomicBool::new(false)),
+            dependencies: Vec::new(),
+            port_file: None,
+            health_check: None,
+            startup_timeout: READINESS_TIMEOUT,
+        })
+    }
+
     pub fn new_from_introspect_runner(
         subgraph_key: SubgraphKey,
         introspect_runner: IntrospectRunnerKind,
@@ -136,6 +193,13 @@ impl SubgraphSchemaWatcher {
             ),
             subgraph_key,
             message_sender,
+            debounce: None,
+            routing_url_override: None,
+            stopped: Arc::new(AtomicBool::new(false)),
+            dependencies: Vec::new(),
+            port_file: None,
+            health_check: None,
+            startup_timeout: READINESS_TIMEOUT,
         })
     }
 
@@ -143,6 +207,7 @@ impl SubgraphSchemaWatcher {
         &self,
     ) -> RoverResult<(SubgraphDefinition, Option<SubgraphSchemaWatcherKind>)> {
         let (name, url) = self.subgraph_key.clone();
+        let url = self.routing_url_override.clone().unwrap_or(url);
         let (sdl, refresher) = match &self.schema_watcher_kind {
             SubgraphSchemaWatcherKind::Introspect(introspect_runner_kind, polling_interval) => {
                 match introspect_runner_kind {
@@ -171,6 +236,7 @@ impl SubgraphSchemaWatcher {
                 (sdl, None)
             }
             SubgraphSchemaWatcherKind::Once(sdl) => (sdl.clone(), None),
+            SubgraphSchemaWatcherKind::Studio(fetcher, _) => (fetcher.fetch()?.sdl.contents, None),
         };
 
         let subgraph_definition = SubgraphDefinition::new(name, url, sdl);
@@ -182,6 +248,7 @@ impl SubgraphSchemaWatcher {
         let print_error = |e: RoverError| {
             let _ = e.print();
         };
+        let moved = self.follow_port_file();
 
         let maybe_update_message = match self.get_subgraph_definition_and_maybe_new_runner() {
             Ok((subgraph_definition, maybe_new_refresher)) => {
@@ -190,7 +257,7 @@ impl SubgraphSchemaWatcher {
                 }
                 match last_message {
                     Some(last_message) => {
-                        if &subgraph_definition.sdl != last_message {
+                        if moved || &subgraph_definition.sdl != last_message {
                             self.message_sender.update_subgraph(&subgraph_definition)?;
                         }
                     }
@@ -221,11 +288,78 @@ impl SubgraphSchemaWatcher {
         Ok(maybe_update_message)
     }
 
+    /// Moves the subgraph to the URL in its port file, if the file points somewhere new, and
+    /// returns whether it moved.
+    fn follow_port_file(&mut self) -> bool {
+        let (name, url) = self.subgraph_key.clone();
+        let new_url = match self
+            .port_file
+            .as_ref()
+            .and_then(|port_file| port_file.moved_url(&url))
+        {
+            Some(new_url) => new_url,
+            None => return false,
+        };
+        eprintln!(
+            "{}subgraph '{}' moved from {} to {}",
+            Emoji::Reload,
+            name,
+            Style::Link.paint(url.as_str()),
+            Style::Link.paint(new_url.as_str())
+        );
+        if let SubgraphSchemaWatcherKind::Introspect(introspect_runner_kind, polling_interval) =
+            &self.schema_watcher_kind
+        {
+            // a subgraph introspected at its routing URL is introspected wherever it moves to
+            if introspect_runner_kind.endpoint() == url {
+                self.schema_watcher_kind = SubgraphSchemaWatcherKind::Introspect(
+                    introspect_runner_kind.with_endpoint(new_url.clone()),
+                    *polling_interval,
+                );
+            }
+        }
+        self.subgraph_key = (name, new_url);
+        true
+    }
+
+    /// Waits for the subgraph's health check to pass, or, without one, for an introspected
+    /// subgraph to respond to introspection, so it isn't added to the session while it boots.
+    fn wait_until_subgraph_ready(&self) {
+        let subgraph_name = &self.subgraph_key.0;
+        match (&self.health_check, &self.schema_watcher_kind) {
+            (Some(health_check), _) => {
+                health_check.wait_until_healthy(subgraph_name, self.startup_timeout)
+            }
+            (None, SubgraphSchemaWatcherKind::Introspect(introspect_runner_kind, _)) => {
+                wait_until(
+                    &format!(
+                        "{} to respond to introspection",
+                        introspect_runner_kind.endpoint()
+                    ),
+                    subgraph_name,
+                    self.startup_timeout,
+                    || {
+                        self.is_stopped()
+                            || self.get_subgraph_definition_and_maybe_new_runner().is_ok()
+                    },
+                );
+            }
+            (None, _) => {}
+        }
+    }
+
     /// Start checking for subgraph updates and sending them to the main process.
     ///
     /// This function will block forever for `SubgraphSchemaWatcherKind` that poll for changes—so it
     /// should be started in a separate thread.
     pub fn watch_subgraph_for_changes(&mut self) -> RoverResult<()> {
+        for dependency in &self.dependencies {
+            dependency.wait_until_ready(&self.subgraph_key.0, self.startup_timeout);
+        }
+        self.wait_until_subgraph_ready();
+        if self.is_stopped() {
+            return Ok(());
+        }
         let mut last_message = None;
         match self.schema_watcher_kind.clone() {
             SubgraphSchemaWatcherKind::Introspect(introspect_runner_kind, polling_interval) => {
@@ -243,6 +377,9 @@ impl SubgraphSchemaWatcher {
                 loop {
                     last_message = self.update_subgraph(last_message.as_ref())?;
                     std::thread::sleep(std::time::Duration::from_secs(polling_interval));
+                    if self.is_stopped() {
+                        break;
+                    }
                 }
             }
             SubgraphSchemaWatcherKind::File(path) => {
@@ -253,22 +390,142 @@ impl SubgraphSchemaWatcher {
 
                 let watch_path = path.clone();
 
-                Fs::watch_file(watch_path, tx);
+                if let Some(port_file) = &self.port_file {
+                    port_file.poll(tx.clone());
+                }
+                match self.debounce {
+                    Some(debounce) => Fs::watch_file_with_delay(watch_path, tx, debounce),
+                    None => Fs::watch_file(watch_path, tx),
+                }
 
                 loop {
                     rx.recv().unwrap_or_else(|_| {
                         panic!("an unexpected error occurred while watching {}", &path)
                     });
+                    if self.is_stopped() {
+                        break;
+                    }
                     last_message = self.update_subgraph(last_message.as_ref())?;
                 }
             }
             SubgraphSchemaWatcherKind::Once(_) => {
                 self.update_subgraph(None)?;
             }
+            SubgraphSchemaWatcherKind::Studio(fetcher, refresh_interval) => {
+                eprintln!(
+                    "{}refreshing subgraph '{}' from {} every {} {}",
+                    Emoji::Listen,
+                    fetcher.subgraph_name,
+                    Style::Link.paint(fetcher.graph_ref.to_string()),
+                    refresh_interval,
+                    match refresh_interval {
+                        1 => "second",
+                        _ => "seconds",
+                    }
+                );
+                last_message = self.update_subgraph(last_message.as_ref())?;
+                loop {
+                    std::thread::sleep(std::time::Duration::from_secs(refresh_interval));
+                    if self.is_stopped() {
+                        break;
+                    }
+                    // keep composing the last schema fetched while GraphOS can't be reached,
+                    // rather than dropping the subgraph from the session
+                    match self.get_subgraph_definition_and_maybe_new_runner() {
+                        Ok((subgraph_definition, _)) => {
+                            if last_message.as_ref() != Some(&subgraph_definition.sdl) {
+                                eprintln!(
+                                    "{}fetched a new schema for subgraph '{}' from {}",
+                                    Emoji::Reload,
+                                    fetcher.subgraph_name,
+                                    Style::Link.paint(fetcher.graph_ref.to_string())
+                                );
+                                self.message_sender.update_subgraph(&subgraph_definition)?;
+                                last_message = Some(subgraph_definition.sdl);
+                            }
+                        }
+                        Err(e) => eprintln!(
+                            "{} could not refresh subgraph '{}' from {}, so it keeps its last schema: {}",
+                            Style::WarningPrefix.paint("WARN:"),
+                            fetcher.subgraph_name,
+                            fetcher.graph_ref,
+                            e
+                        ),
+                    }
+                }
+            }
         }
         Ok(())
     }
 
+    /// A handle that stops the watcher the next time it wakes up, without sending further updates.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.stopped.clone()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Describes where the subgraph's schema comes from, so a reloaded supergraph config can
+    /// tell which subgraphs changed.
+    pub fn source(&self) -> String {
+        let source = match &self.schema_watcher_kind {
+            SubgraphSchemaWatcherKind::Introspect(introspect_runner_kind, polling_interval) => {
+                format!(
+                    "introspect {} every {}s",
+                    introspect_runner_kind.endpoint(),
+                    polling_interval
+                )
+            }
+            SubgraphSchemaWatcherKind::File(path) => format!("file {}", path),
+            SubgraphSchemaWatcherKind::Once(sdl) => format!("sdl {}", sdl),
+            SubgraphSchemaWatcherKind::Studio(fetcher, refresh_interval) => format!(
+                "fetch {} from {} every {}s",
+                fetcher.subgraph_name, fetcher.graph_ref, refresh_interval
+            ),
+        };
+        format!(
+            "{} at {}: {}",
+            self.subgraph_key.0, self.subgraph_key.1, source
+        )
+    }
+
+    /// Waits for a watched file to go `debounce` without changing before reading it again.
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = Some(debounce);
+    }
+
+    /// Routes the router's requests for this subgraph to `url` instead of the subgraph's own URL.
+    pub fn set_routing_url_override(&mut self, url: Url) {
+        self.routing_url_override = Some(url);
+    }
+
+    /// Waits for `dependencies` to accept connections before watching the subgraph.
+    pub fn set_dependencies(&mut self, dependencies: Vec<Dependency>) {
+        self.dependencies = dependencies;
+    }
+
+    /// Waits for `health_check` to respond successfully before adding the subgraph to the session.
+    pub fn set_health_check(&mut self, health_check: HealthCheck) {
+        self.health_check = Some(health_check);
+    }
+
+    /// How long to wait for dependencies, and for the subgraph to be ready, before starting anyway.
+    pub fn set_startup_timeout(&mut self, startup_timeout: Duration) {
+        self.startup_timeout = startup_timeout;
+    }
+
+    /// Follows the subgraph to the port or URL that it writes to `port_file`.
+    pub fn set_port_file(&mut self, port_file: PortFile) {
+        self.port_file = Some(port_file);
+    }
+
+    /// The URL the subgraph is served at.
+    pub fn get_url(&self) -> Url {
+        self.subgraph_key.1.clone()
+    }
+
     pub fn set_schema_refresher(&mut self, new_refresher: SubgraphSchemaWatcherKind) {
         self.schema_watcher_kind = new_refresher;
     }
@@ -286,4 +543,61 @@ pub enum SubgraphSchemaWatcherKind {
     File(Utf8PathBuf),
     /// Don't ever update, schema is only pulled once
     Once(String),
+    /// Fetch a subgraph published to GraphOS every so many seconds
+    Studio(StudioSubgraphFetcher, u64),
+}
+
+/// Fetches a subgraph's schema from GraphOS
+#[derive(Clone)]
+pub struct StudioSubgraphFetcher {
+    client: Arc<StudioClient>,
+    graph_ref: GraphRef,
+    subgraph_name: String,
+}
+
+impl StudioSubgraphFetcher {
+    fn fetch(&self) -> RoverResult<FetchResponse> {
+        Ok(fetch::run(
+            SubgraphFetchInput {
+                graph_ref: self.graph_ref.clone(),
+                subgraph_name: self.subgraph_name.clone(),
+            },
+            &self.client,
+        )?)
+    }
+}
+
+impl std::fmt::Debug for StudioSubgraphFetcher {
+    // `StudioClient` holds the API key, so it's left out
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StudioSubgraphFetcher")
+            .field("graph_ref", &self.graph_ref)
+            .field("subgraph_name", &self.subgraph_name)
+            .finish()
+    }
+}
+
+/// The routing URL a subgraph was published to GraphOS with.
+fn graphos_routing_url(
+    graph_ref: &str,
+    graphos_subgraph_name: &str,
+    yaml_subgraph_name: String,
+    sdl_type: SdlType,
+) -> RoverResult<Url> {
+    match sdl_type {
+        SdlType::Subgraph {
+            routing_url: Some(graph_registry_routing_url),
+        } => Ok(graph_registry_routing_url.parse().context(format!(
+            "Could not parse graph registry routing url {}",
+            graph_registry_routing_url
+        ))?),
+        _ => Err(RoverError::new(anyhow!(
+            "Could not find routing URL in GraphOS for subgraph {graphos_subgraph_name}"
+        ))
+        .with_suggestion(RoverErrorSuggestion::AddRoutingUrlToSupergraphYaml)
+        .with_suggestion(RoverErrorSuggestion::PublishSubgraphWithRoutingUrl {
+            subgraph_name: yaml_subgraph_name,
+            graph_ref: graph_ref.to_string(),
+        })),
+    }
 }
@@ -1,18 +1,51 @@
-use crate::{RoverErrorCode, RoverOutput, RoverResult};
+use crate::{RoverError, RoverErrorCode, RoverErrorSuggestion, RoverOutput, RoverResult};
 
+use anyhow::anyhow;
 use clap::Parser;
 use serde::Serialize;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Explain {
-    /// The full error code. For example, E020
-    #[arg(value_name = "CODE")]
-    code: RoverErrorCode,
+    /// The full error code, for example E020, or keywords to search the explanations for
+    #[arg(
+        value_name = "CODE",
+        required_unless_present = "search",
+        conflicts_with = "search"
+    )]
+    code: Option<String>,
+
+    /// Search the explanations of every error code for keywords, for example "compose"
+    #[arg(long = "search", value_name = "KEYWORDS")]
+    search: Option<String>,
 }
 
 impl Explain {
     pub fn run(&self) -> RoverResult<RoverOutput> {
-        let explanation = &self.code.explain();
-        Ok(RoverOutput::ErrorExplanation(explanation.clone()))
+        let query = match (&self.code, &self.search) {
+            (Some(code), _) => match RoverErrorCode::from_str(&code.trim().to_uppercase()) {
+                Ok(code) => return Ok(RoverOutput::ErrorExplanation(code.explain())),
+                Err(_) => code,
+            },
+            (None, Some(search)) => search,
+            (None, None) => unreachable!("clap requires a code or a search"),
+        };
+
+        let matches = RoverErrorCode::search(query);
+        match matches.len() {
+            0 => {
+                let mut err =
+                    RoverError::new(anyhow!("No error codes matched '{}'.", query.trim()));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Try fewer or more general keywords, or find the code in the error message you received, like E029.".to_string(),
+                ));
+                Err(err)
+            }
+            1 => Ok(RoverOutput::ErrorExplanation(matches[0].code.explain())),
+            _ => Ok(RoverOutput::ErrorCodeSearchResults {
+                query: query.trim().to_string(),
+                matches,
+            }),
+        }
     }
 }
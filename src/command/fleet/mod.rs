@@ -0,0 +1,27 @@
+pub(crate) mod run;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Fleet {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Run a Rover command against every graph ref in a manifest, a few at a time, and report
+    /// how it went for each of them
+    Run(run::Run),
+}
+
+impl Fleet {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Run(command) => command.run(),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rover_client::shared::GraphRef;
+use rover_std::{Emoji, Fs, Style};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::utils::table::{self, row};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Run {
+    /// A YAML file that lists the graph refs to run the command against, and the config profile
+    /// to use for each
+    #[arg(long = "manifest")]
+    #[serde(skip_serializing)]
+    manifest: Utf8PathBuf,
+
+    /// How many graph refs to run the command against at once
+    #[arg(
+        long = "concurrency",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    concurrency: u64,
+
+    /// The Rover command to run, after `--`, like `-- subgraph fetch {graph_ref} --name products`.
+    /// `{graph_ref}` and `{profile}` are replaced with each target's graph ref and profile.
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    #[serde(skip_serializing)]
+    command: Vec<String>,
+}
+
+impl Run {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let manifest = FleetManifest::parse(&Fs::read_file(&self.manifest)?).map_err(|e| {
+            RoverError::new(anyhow!(
+                "Could not read the fleet manifest at {}: {}",
+                self.manifest,
+                e
+            ))
+        })?;
+        let rover = std::env::current_exe()
+            .context("could not find the Rover executable to run the command with")?;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency as usize)
+            .build()
+            .context("could not start the threads to run the command on")?;
+        eprintln!(
+            "{}running {} against {} targets, {} at a time",
+            Emoji::Action,
+            Style::Command.paint(format!("rover {}", self.command.join(" "))),
+            manifest.targets.len(),
+            self.concurrency
+        );
+
+        let targets = pool.install(|| {
+            manifest
+                .targets
+                .par_iter()
+                .map(|target| {
+                    let report = target.run(&rover, &self.command);
+                    if report.success {
+                        eprintln!("{}{} succeeded", Emoji::Success, report.graph_ref);
+                    } else {
+                        eprintln!(
+                            "{} {} failed: {}",
+                            Style::ErrorPrefix.paint("ERROR:"),
+                            report.graph_ref,
+                            report.error_message()
+                        );
+                    }
+                    report
+                })
+                .collect::<Vec<_>>()
+        });
+        let report = FleetReport {
+            command: self.command.clone(),
+            targets,
+        };
+
+        if report.failures() == 0 {
+            Ok(RoverOutput::FleetReport(report))
+        } else {
+            let mut err = RoverError::new(FleetFailures { report });
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Run the command against one of the failed graph refs on its own to see its full output."
+                    .to_string(),
+            ));
+            Err(err)
+        }
+    }
+}
+
+/// The graph refs that `rover fleet run` runs a command against.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FleetManifest {
+    pub(crate) targets: Vec<FleetTarget>,
+}
+
+impl FleetManifest {
+    pub(crate) fn parse(yaml: &str) -> anyhow::Result<Self> {
+        let manifest: Self = serde_yaml::from_str(yaml)?;
+        if manifest.targets.is_empty() {
+            return Err(anyhow!("it doesn't list any targets"));
+        }
+        for target in &manifest.targets {
+            GraphRef::from_str(&target.graph_ref)
+                .map_err(|_| anyhow!("'{}' is not a valid graph ref", target.graph_ref))?;
+        }
+        Ok(manifest)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FleetTarget {
+    pub(crate) graph_ref: String,
+    /// The config profile to run the command with, or the `default` profile
+    #[serde(default)]
+    pub(crate) profile: Option<String>,
+}
+
+impl FleetTarget {
+    fn profile_name(&self) -> &str {
+        self.profile.as_deref().unwrap_or("default")
+    }
+
+    /// The arguments to run `command` with for this target.
+    pub(crate) fn args(&self, command: &[String]) -> Vec<String> {
+        command
+            .iter()
+            .map(|arg| {
+                arg.replace("{graph_ref}", &self.graph_ref)
+                    .replace("{profile}", self.profile_name())
+            })
+            .collect()
+    }
+
+    fn run(&self, rover: &Path, command: &[String]) -> FleetTargetReport {
+        let started = Instant::now();
+        let output = Command::new(rover)
+            .args(["--format", "json", "--skip-update-check"])
+            .args(self.args(command))
+            .output();
+        let duration_ms = started.elapsed().as_millis() as u64;
+        match output {
+            Ok(output) => FleetTargetReport::from_output(
+                self,
+                output.status.success(),
+                &output.stdout,
+                &output.stderr,
+                duration_ms,
+            ),
+            Err(e) => FleetTargetReport {
+                graph_ref: self.graph_ref.clone(),
+                profile: self.profile.clone(),
+                success: false,
+                data: Value::Null,
+                error: json!({ "message": format!("could not run Rover: {}", e) }),
+                duration_ms,
+            },
+        }
+    }
+}
+
+/// How a command went for each target of a fleet run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FleetReport {
+    /// The command, before its placeholders were replaced
+    pub(crate) command: Vec<String>,
+    pub(crate) targets: Vec<FleetTargetReport>,
+}
+
+impl FleetReport {
+    pub(crate) fn failures(&self) -> usize {
+        self.targets.iter().filter(|target| !target.success).count()
+    }
+
+    pub(crate) fn get_table(&self) -> String {
+        let mut table = table::get_table();
+
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Graph Ref", "Profile", "Result", "Duration"]);
+        for target in &self.targets {
+            let result = if target.success {
+                "succeeded".to_string()
+            } else {
+                format!("failed: {}", target.error_message())
+            };
+            table.add_row(row![
+                target.graph_ref,
+                target.profile_name(),
+                result,
+                format!("{:.1}s", target.duration_ms as f64 / 1000.0)
+            ]);
+        }
+        table.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct FleetTargetReport {
+    pub(crate) graph_ref: String,
+    pub(crate) profile: Option<String>,
+    pub(crate) success: bool,
+    /// The `data` of the command's JSON output
+    pub(crate) data: Value,
+    /// The `error` of the command's JSON output, or why the command couldn't run
+    pub(crate) error: Value,
+    pub(crate) duration_ms: u64,
+}
+
+impl FleetTargetReport {
+    /// Reads the output of `rover --format json`. Output that isn't JSON, like an error about
+    /// the command's arguments, is reported by the first line it printed to stderr.
+    pub(crate) fn from_output(
+        target: &FleetTarget,
+        success: bool,
+        stdout: &[u8],
+        stderr: &[u8],
+        duration_ms: u64,
+    ) -> Self {
+        let (data, error) = match serde_json::from_slice::<Value>(stdout) {
+            Ok(json) => (json["data"].clone(), json["error"].clone()),
+            Err(_) if success => (Value::Null, Value::Null),
+            Err(_) => {
+                let stderr = String::from_utf8_lossy(stderr);
+                let message = stderr
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .unwrap_or("Rover exited without any output");
+                (Value::Null, json!({ "message": message }))
+            }
+        };
+        Self {
+            graph_ref: target.graph_ref.clone(),
+            profile: target.profile.clone(),
+            success,
+            data,
+            error,
+            duration_ms,
+        }
+    }
+
+    fn profile_name(&self) -> &str {
+        self.profile.as_deref().unwrap_or("default")
+    }
+
+    pub(crate) fn error_message(&self) -> &str {
+        self.error["message"].as_str().unwrap_or("unknown error")
+    }
+}
+
+/// The error for a fleet run where the command failed for some of the targets. It carries the
+/// report, so it's printed like a successful run's.
+#[derive(Debug)]
+pub(crate) struct FleetFailures {
+    pub(crate) report: FleetReport,
+}
+
+impl Display for FleetFailures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the command failed for {} of {} targets",
+            self.report.failures(),
+            self.report.targets.len()
+        )
+    }
+}
+
+impl std::error::Error for FleetFailures {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{FleetManifest, FleetTarget, FleetTargetReport};
+
+    #[test]
+    fn it_parses_a_manifest() {
+        let manifest = FleetManifest::parse(
+            "targets:
+  - graph_ref: payments@prod
+    profile: payments
+  - graph_ref: search@staging
+",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.targets,
+            vec![
+                FleetTarget {
+                    graph_ref: "payments@prod".to_string(),
+                    profile: Some("payments".to_string()),
+                },
+                FleetTarget {
+                    graph_ref: "search@staging".to_string(),
+                    profile: None,
+                },
+            ]
+        );
+        assert!(FleetManifest::parse("targets: []").is_err());
+        assert!(FleetManifest::parse("targets:\n  - graph_ref: \"@prod\"\n").is_err());
+    }
+
+    #[test]
+    fn it_replaces_placeholders() {
+        let target = FleetTarget {
+            graph_ref: "search@staging".to_string(),
+            profile: None,
+        };
+        let command = [
+            "supergraph",
+            "fetch",
+            "{graph_ref}",
+            "--profile",
+            "{profile}",
+        ]
+        .map(String::from);
+        assert_eq!(
+            target.args(&command),
+            vec![
+                "supergraph",
+                "fetch",
+                "search@staging",
+                "--profile",
+                "default"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reads_the_output_of_each_target() {
+        let target = FleetTarget {
+            graph_ref: "search@staging".to_string(),
+            profile: None,
+        };
+        let report = FleetTargetReport::from_output(
+            &target,
+            false,
+            br#"{"json_version":"1","data":{"success":false},"error":{"message":"no such graph","code":"E009"}}"#,
+            b"",
+            1200,
+        );
+        assert_eq!(report.data, json!({ "success": false }));
+        assert_eq!(report.error_message(), "no such graph");
+
+        let report = FleetTargetReport::from_output(
+            &target,
+            false,
+            b"",
+            b"\nerror: unexpected argument '--nme' found\n\nUsage: rover subgraph fetch",
+            10,
+        );
+        assert_eq!(report.data, json!(null));
+        assert_eq!(
+            report.error_message(),
+            "error: unexpected argument '--nme' found"
+        );
+    }
+}
@@ -9,7 +9,12 @@ use rover_client::operations::graph::{
 use rover_client::shared::{CheckConfig, GitContext};
 
 use crate::options::{CheckConfigOpts, GraphRefOpt, ProfileOpt, SchemaOpt};
+use crate::utils::check_annotations;
+use crate::utils::check_history::CheckHistory;
+use crate::utils::check_report;
 use crate::utils::client::StudioClientConfig;
+use crate::utils::events::emit_check_completed;
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
@@ -36,6 +41,11 @@ impl Check {
         checks_timeout_seconds: u64,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::CheckSchemas,
+        )?;
         let proposed_schema = self
             .schema
             .read_file_descriptor("SDL", &mut std::io::stdin())?;
@@ -47,7 +57,7 @@ impl Check {
         let workflow_res = check::run(
             CheckSchemaAsyncInput {
                 graph_ref: self.graph.graph_ref.clone(),
-                proposed_schema,
+                proposed_schema: proposed_schema.clone(),
                 git_context,
                 config: CheckConfig {
                     validation_period: self.config.validation_period.clone(),
@@ -60,6 +70,13 @@ impl Check {
         if self.config.background {
             Ok(RoverOutput::AsyncCheckResponse(workflow_res))
         } else {
+            if self.config.watch_until_complete {
+                eprintln!(
+                    "Waiting for check {} to complete. Follow its progress at {}",
+                    workflow_res.workflow_id,
+                    Style::Link.paint(&workflow_res.target_url)
+                );
+            }
             let check_res = check_workflow::run(
                 CheckWorkflowInput {
                     graph_ref: self.graph.graph_ref.clone(),
@@ -67,8 +84,26 @@ impl Check {
                     checks_timeout_seconds,
                 },
                 &client,
-            )?;
-            Ok(RoverOutput::CheckWorkflowResponse(check_res))
+            );
+            CheckHistory::new(&client_config.config.home, &self.graph.graph_ref, None)
+                .record(&check_res);
+            emit_check_completed(&self.graph.graph_ref, None, &check_res);
+            if let Some(format) = self.config.annotations {
+                check_annotations::write(
+                    format,
+                    self.config.annotations_file.as_deref(),
+                    &check_res,
+                    &proposed_schema,
+                    self.schema.file_path().as_deref(),
+                );
+            }
+            if self.config.watch_until_complete {
+                check_report::print(&check_res);
+                return Ok(RoverOutput::CheckWorkflowResponse(check_report::finish(
+                    check_res,
+                )?));
+            }
+            Ok(RoverOutput::CheckWorkflowResponse(check_res?))
         }
     }
 }
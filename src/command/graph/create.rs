@@ -0,0 +1,84 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::graph::create::{self, GraphCreateInput};
+use rover_std::Style;
+
+use crate::command::install::resolve_federation_version;
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::scope::{check_key_scope, RequiredScope};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Create {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The ID of the organization to create the graph in
+    #[arg(long = "org", value_name = "ORG_ID")]
+    #[serde(skip_serializing)]
+    organization_id: String,
+
+    /// The title of the graph in Apollo Studio. Defaults to the graph's name.
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    title: Option<String>,
+
+    /// Hide the graph from organization members who haven't been invited to it
+    #[arg(long)]
+    hidden: bool,
+
+    /// The federation version the graph's initial variant composes with, like `2` or `=2.4.7`.
+    /// Defaults to the profile's `federation-version`, or the latest federation 2 version.
+    #[arg(long = "federation-version")]
+    #[serde(skip_serializing)]
+    federation_version: Option<String>,
+}
+
+impl Create {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::CreateGraphs,
+        )?;
+        let federation_version = resolve_federation_version(
+            &self.federation_version.clone().unwrap_or_else(|| {
+                client_config
+                    .get_profile_settings(&self.profile.profile_name)
+                    .federation_version
+                    .unwrap_or_else(|| "2".to_string())
+            }),
+            &client_config,
+            false,
+        )?;
+        let graph_ref = self.graph.graph_ref.to_string();
+        eprintln!(
+            "Creating {} in the {} organization using credentials from the {} profile.",
+            Style::Link.paint(graph_ref),
+            Style::Link.paint(&self.organization_id),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let create_response = create::run(
+            GraphCreateInput {
+                organization_id: self.organization_id.clone(),
+                graph_ref: self.graph.graph_ref.clone(),
+                title: self
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| self.graph.graph_ref.name.clone()),
+                hidden: self.hidden,
+                federation_version,
+            },
+            &client,
+        )?;
+
+        Ok(RoverOutput::GraphCreateResponse(create_response))
+    }
+}
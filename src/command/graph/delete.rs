@@ -6,6 +6,7 @@ use rover_std::{prompt, Style};
 
 use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
@@ -25,6 +26,11 @@ pub struct Delete {
 impl Delete {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::ManageVariants,
+        )?;
         let graph_ref = self.graph.graph_ref.to_string();
 
         eprintln!(
@@ -0,0 +1,65 @@
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::graph::fetch::{self, GraphFetchInput};
+use rover_std::Style;
+
+use crate::command::schema::diff::{FailOnOpt, SchemaDiff, SchemaSource, SeverityConfigOpt};
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Diff {
+    /// The schema to compare from: a graph ref like `my-graph@main`, a file, or `-` for stdin
+    #[arg(long = "old")]
+    #[serde(skip_serializing)]
+    old: SchemaSource,
+
+    /// The schema to compare to: a graph ref like `my-graph@staging`, a file, or `-` for stdin
+    #[arg(long = "new")]
+    #[serde(skip_serializing)]
+    new: SchemaSource,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    #[clap(flatten)]
+    severity_config: SeverityConfigOpt,
+
+    #[clap(flatten)]
+    fail_on: FailOnOpt,
+}
+
+impl Diff {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let overrides = self.severity_config.load()?;
+        let old = self.read(&self.old, "old SDL", &client_config)?;
+        let new = self.read(&self.new, "new SDL", &client_config)?;
+        let diff = SchemaDiff::new(&old, &new, &overrides)?;
+        Ok(RoverOutput::SchemaDiff(self.fail_on.check(diff)?))
+    }
+
+    fn read(
+        &self,
+        source: &SchemaSource,
+        file_description: &str,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<String> {
+        source.read(file_description, |graph_ref| {
+            let client = client_config.get_authenticated_client(&self.profile)?;
+            eprintln!(
+                "Fetching SDL from {} using credentials from the {} profile.",
+                Style::Link.paint(graph_ref.to_string()),
+                Style::Command.paint(&self.profile.profile_name)
+            );
+            let fetch_response = fetch::run(
+                GraphFetchInput {
+                    graph_ref: graph_ref.clone(),
+                },
+                &client,
+            )?;
+            Ok(fetch_response.sdl.contents)
+        })
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Context};
+use chrono::{Duration, Utc};
+use clap::Parser;
+use rover_client::operations::graph::field_usage::{self, ClientFieldUsage, FieldUsageInput};
+use rover_client::operations::subgraph::fetch_all::{self, SubgraphFetchAllInput};
+use rover_std::{Emoji, Style};
+use serde::Serialize;
+
+use crate::command::subgraph::local_lint::FederationUsage;
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::parse_field_coordinate;
+use crate::utils::table::{self, row};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Explain {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    /// The field to explain, as a schema coordinate like `Product.price`
+    #[arg(value_name = "COORDINATE", value_parser = parse_field_coordinate)]
+    #[serde(skip_serializing)]
+    coordinate: (String, String),
+
+    /// How many days of client usage to report
+    #[arg(
+        long = "days",
+        default_value_t = 7,
+        value_parser = clap::value_parser!(u64).range(1..=90)
+    )]
+    days: u64,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl Explain {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let graph_ref = &self.graph.graph_ref;
+        let (type_name, field_name) = &self.coordinate;
+        let coordinate = format!("{}.{}", type_name, field_name);
+        eprintln!(
+            "{}Explaining {} in {} using credentials from the {} profile.",
+            Emoji::Hourglass,
+            Style::Command.paint(&coordinate),
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let subgraphs = fetch_all::run(
+            SubgraphFetchAllInput {
+                graph_ref: graph_ref.clone(),
+            },
+            &client,
+        )?
+        .subgraphs
+        .into_iter()
+        .map(|subgraph| (subgraph.name, subgraph.sdl))
+        .collect::<Vec<_>>();
+
+        let mut explanation = FieldExplanation::analyze(&coordinate, &subgraphs)?;
+        if explanation.defined_in.is_empty() {
+            let mut err = RoverError::new(anyhow!(
+                "No subgraph in {} defines the field `{}`.",
+                graph_ref,
+                coordinate
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Check the spelling of the type and field names, which are case-sensitive."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+        explanation.graph_ref = graph_ref.to_string();
+        explanation.days = self.days;
+        explanation.clients = field_usage::run(
+            FieldUsageInput {
+                graph_ref: graph_ref.clone(),
+                type_name: type_name.clone(),
+                field_name: field_name.clone(),
+                from: Utc::now() - Duration::days(self.days as i64),
+            },
+            &client,
+        )?
+        .clients;
+        Ok(RoverOutput::GraphExplain(explanation))
+    }
+}
+
+/// Which subgraphs resolve a field, which `@requires` and `@provides` select it, and which
+/// clients recently executed it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FieldExplanation {
+    pub(crate) graph_ref: String,
+    /// The field, like `Product.price`
+    pub(crate) coordinate: String,
+    /// The subgraphs that resolve the field
+    pub(crate) defined_in: Vec<String>,
+    pub(crate) references: Vec<FieldReference>,
+    /// How many days of usage `clients` covers
+    pub(crate) days: u64,
+    /// The clients that executed the field, the most executions first
+    pub(crate) clients: Vec<ClientFieldUsage>,
+}
+
+/// A `@requires` or `@provides` in a subgraph that selects the explained field.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct FieldReference {
+    pub(crate) subgraph: String,
+    /// The field the directive is on, like `Product.shippingEstimate`
+    pub(crate) coordinate: String,
+    pub(crate) directive: String,
+}
+
+impl FieldExplanation {
+    /// Finds which of `subgraphs`, a list of names and schemas, resolve `coordinate`, and which
+    /// select it in a `@requires` or `@provides`.
+    pub(crate) fn analyze(coordinate: &str, subgraphs: &[(String, String)]) -> RoverResult<Self> {
+        let mut defined_in = BTreeSet::new();
+        let mut references = BTreeSet::new();
+        for (subgraph, sdl) in subgraphs {
+            let usage = FederationUsage::from_sdl(sdl).with_context(|| {
+                format!("could not parse the schema of the '{}' subgraph", subgraph)
+            })?;
+            if usage.fields.contains(coordinate) {
+                defined_in.insert(subgraph.clone());
+            }
+            for field_set in &usage.field_sets {
+                if matches!(field_set.directive, "requires" | "provides")
+                    && field_set.selected.iter().any(|field| field == coordinate)
+                {
+                    references.insert(FieldReference {
+                        subgraph: subgraph.clone(),
+                        coordinate: field_set.coordinate.clone(),
+                        directive: format!("@{}", field_set.directive),
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            coordinate: coordinate.to_string(),
+            defined_in: defined_in.into_iter().collect(),
+            references: references.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn get_output(&self) -> String {
+        let mut result = format!(
+            "{} in {} is defined in the {} {}.",
+            Style::Command.paint(&self.coordinate),
+            Style::Link.paint(&self.graph_ref),
+            self.defined_in.join(", "),
+            match self.defined_in.len() {
+                1 => "subgraph",
+                _ => "subgraphs",
+            }
+        );
+
+        result.push_str(&format!("\n\n{}\n", Style::Heading.paint("References:")));
+        if self.references.is_empty() {
+            result.push_str("No subgraphs select it in a @requires or @provides.");
+        } else {
+            let mut table = table::get_table();
+            // bc => sets top row to be bold and center
+            table.add_row(row![bc => "Subgraph", "Field", "Through"]);
+            for reference in &self.references {
+                table.add_row(row![
+                    reference.subgraph,
+                    reference.coordinate,
+                    reference.directive
+                ]);
+            }
+            result.push_str(&table.to_string());
+        }
+
+        let days = match self.days {
+            1 => "day".to_string(),
+            days => format!("{} days", days),
+        };
+        result.push_str(&format!(
+            "\n\n{}\n",
+            Style::Heading.paint(format!("Client usage in the last {}:", days))
+        ));
+        if self.clients.is_empty() {
+            result.push_str("No clients executed it.");
+        } else {
+            let mut table = table::get_table();
+            table.add_row(row![bc => "Client", "Version", "Executions"]);
+            for client in &self.clients {
+                table.add_row(row![
+                    client.client_name.as_deref().unwrap_or("unidentified"),
+                    client.client_version.as_deref().unwrap_or(""),
+                    client.estimated_execution_count
+                ]);
+            }
+            result.push_str(&table.to_string());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldExplanation, FieldReference};
+
+    const PRODUCTS: &str = r#"type Query {
+  product(id: ID!): Product
+}
+
+type Product @key(fields: "id") {
+  id: ID!
+  price: Float
+  name: String
+}
+"#;
+
+    const INVENTORY: &str = r#"type Product @key(fields: "id") {
+  id: ID!
+  price: Float @external
+  shippingEstimate: Float @requires(fields: "price")
+}
+"#;
+
+    const REVIEWS: &str = r#"type Review {
+  product: Product @provides(fields: "name")
+}
+
+type Product @key(fields: "id") {
+  id: ID!
+  name: String @external
+}
+"#;
+
+    #[test]
+    fn it_finds_where_a_field_is_defined_and_referenced() {
+        let subgraphs = [
+            ("inventory".to_string(), INVENTORY.to_string()),
+            ("products".to_string(), PRODUCTS.to_string()),
+            ("reviews".to_string(), REVIEWS.to_string()),
+        ];
+        let explanation = FieldExplanation::analyze("Product.price", &subgraphs).unwrap();
+        assert_eq!(explanation.defined_in, vec!["products"]);
+        assert_eq!(
+            explanation.references,
+            vec![FieldReference {
+                subgraph: "inventory".to_string(),
+                coordinate: "Product.shippingEstimate".to_string(),
+                directive: "@requires".to_string(),
+            }]
+        );
+
+        let explanation = FieldExplanation::analyze("Product.name", &subgraphs).unwrap();
+        assert_eq!(explanation.defined_in, vec!["products"]);
+        assert_eq!(explanation.references[0].coordinate, "Review.product");
+        assert_eq!(explanation.references[0].directive, "@provides");
+
+        let explanation = FieldExplanation::analyze("Product.weight", &subgraphs).unwrap();
+        assert!(explanation.defined_in.is_empty());
+    }
+}
@@ -1,7 +1,10 @@
+use apollo_parser::{ast, Parser as SdlParser};
 use clap::Parser;
 use reqwest::blocking::Client;
+use rover_std::{prompt, Style};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::io::{self, IsTerminal};
 
 use rover_client::{
     blocking::GraphQLClient,
@@ -9,6 +12,7 @@ use rover_client::{
 };
 
 use crate::{
+    command::subgraph::Introspect as SubgraphIntrospect,
     options::{IntrospectOpts, OutputOpts},
     RoverOutput, RoverResult,
 };
@@ -17,20 +21,58 @@ use crate::{
 pub struct Introspect {
     #[clap(flatten)]
     pub opts: IntrospectOpts,
+
+    /// If the endpoint is a federated subgraph, print its federation SDL without asking.
+    /// Unlike introspection, the federation SDL keeps directives like `@key`.
+    #[arg(long = "prefer-federation-sdl")]
+    pub prefer_federation_sdl: bool,
 }
 
 impl Introspect {
-    pub fn run(&self, client: Client, output_opts: &OutputOpts) -> RoverResult<RoverOutput> {
+    pub fn run(
+        &self,
+        client: Client,
+        retries: Option<u32>,
+        output_opts: &OutputOpts,
+    ) -> RoverResult<RoverOutput> {
         if self.opts.watch {
             self.exec_and_watch(&client, output_opts)
         } else {
-            let sdl = self.exec(&client, true)?;
-            Ok(RoverOutput::Introspection(sdl))
+            let sdl = self.exec(&client, retries)?;
+            if self.prefer_federation_sdl || !is_federated_subgraph(&sdl) {
+                return Ok(RoverOutput::Introspection(sdl));
+            }
+            let use_federation_sdl = if io::stderr().is_terminal() && io::stdin().is_terminal() {
+                eprintln!(
+                    "This endpoint is a federated subgraph. Introspection leaves out federation directives like `@key`, but its federation SDL keeps them."
+                );
+                prompt::prompt_confirm_default_yes("Would you like to use the federation SDL?")?
+            } else {
+                eprintln!(
+                    "{} This endpoint is a federated subgraph, so directives like `@key` are missing from its introspected schema. Pass {} to print its federation SDL instead.",
+                    Style::WarningPrefix.paint("WARN:"),
+                    Style::Command.paint("--prefer-federation-sdl")
+                );
+                false
+            };
+            if use_federation_sdl {
+                Ok(RoverOutput::Introspection(
+                    self.exec_federation_sdl(&client, retries)?,
+                ))
+            } else {
+                Ok(RoverOutput::Introspection(sdl))
+            }
         }
     }
 
-    pub fn exec(&self, client: &Client, should_retry: bool) -> RoverResult<String> {
-        let client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone());
+    /// Introspects the endpoint, retrying failed requests `retries` times, or `None` for Rover's
+    /// default retries.
+    pub fn exec(&self, client: &Client, retries: Option<u32>) -> RoverResult<String> {
+        let graphql_client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone());
+        let graphql_client = match retries {
+            Some(retries) => graphql_client.with_max_retries(retries),
+            None => graphql_client,
+        };
 
         // add the flag headers to a hashmap to pass along to rover-client
         let mut headers = HashMap::new();
@@ -40,11 +82,59 @@ impl Introspect {
             }
         };
 
-        Ok(introspect::run(GraphIntrospectInput { headers }, &client, should_retry)?.schema_sdl)
+        let sdl =
+            introspect::run(GraphIntrospectInput { headers }, &graphql_client, true)?.schema_sdl;
+        if self.prefer_federation_sdl && is_federated_subgraph(&sdl) {
+            tracing::debug!("the endpoint is a federated subgraph, using its federation SDL");
+            self.exec_federation_sdl(client, retries)
+        } else {
+            Ok(sdl)
+        }
+    }
+
+    fn exec_federation_sdl(&self, client: &Client, retries: Option<u32>) -> RoverResult<String> {
+        SubgraphIntrospect {
+            opts: IntrospectOpts {
+                endpoint: self.opts.endpoint.clone(),
+                headers: self.opts.headers.clone(),
+                watch: false,
+            },
+        }
+        .exec(client, retries)
     }
 
     pub fn exec_and_watch(&self, client: &Client, output_opts: &OutputOpts) -> ! {
         self.opts
-            .exec_and_watch(|| self.exec(client, false), output_opts)
+            .exec_and_watch(|| self.exec(client, Some(0)), output_opts)
+    }
+}
+
+/// Whether an introspected schema has the `_Service` type that federated subgraphs expose
+/// their federation SDL with.
+fn is_federated_subgraph(sdl: &str) -> bool {
+    SdlParser::new(sdl)
+        .parse()
+        .document()
+        .definitions()
+        .any(|definition| match definition {
+            ast::Definition::ObjectTypeDefinition(object) => object
+                .name()
+                .map_or(false, |name| name.text() == "_Service"),
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_federated_subgraph;
+
+    #[test]
+    fn it_detects_federated_subgraphs() {
+        assert!(is_federated_subgraph(
+            "type Query {\n  products: [Product]\n  _service: _Service!\n}\n\ntype _Service {\n  sdl: String\n}\n"
+        ));
+        assert!(!is_federated_subgraph(
+            "type Query {\n  products: [Product]\n}\n"
+        ));
     }
 }
@@ -1,15 +1,20 @@
 mod check;
+mod create;
 mod delete;
+mod diff;
+pub(crate) mod explain;
 mod fetch;
 mod introspect;
 mod lint;
 mod publish;
+mod restore;
+mod snapshot;
 
 use clap::Parser;
 pub use introspect::Introspect;
 use serde::Serialize;
 
-use crate::options::OutputOpts;
+use crate::options::{OutputOpts, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -27,9 +32,19 @@ pub enum Command {
     /// against a graph schema in the Apollo graph registry
     Check(check::Check),
 
+    /// Create a new graph, with an initial variant, in an organization
+    Create(create::Create),
+
     /// Delete a graph schema from the Apollo graph registry
     Delete(delete::Delete),
 
+    /// Compare two graph schemas, from graph refs or files, and list the changes by severity
+    Diff(diff::Diff),
+
+    /// Explain a field of a graph: which subgraphs define it, which reference it with
+    /// `@requires` or `@provides`, and which clients recently used it
+    Explain(explain::Explain),
+
     /// Fetch a graph schema from the Apollo graph registry
     Fetch(fetch::Fetch),
 
@@ -41,6 +56,12 @@ pub enum Command {
 
     /// Introspect current graph schema.
     Introspect(introspect::Introspect),
+
+    /// Save every subgraph schema of a graph, and its supergraph schema, to a snapshot archive
+    Snapshot(snapshot::Snapshot),
+
+    /// Republish every subgraph schema in a snapshot archive
+    Restore(restore::Restore),
 }
 
 impl Graph {
@@ -55,13 +76,24 @@ impl Graph {
             Command::Check(command) => {
                 command.run(client_config, git_context, checks_timeout_seconds)
             }
+            Command::Create(command) => command.run(client_config),
             Command::Delete(command) => command.run(client_config),
+            Command::Diff(command) => command.run(client_config),
+            Command::Explain(command) => command.run(client_config),
             Command::Fetch(command) => command.run(client_config),
             Command::Lint(command) => command.run(client_config),
             Command::Publish(command) => command.run(client_config, git_context),
             Command::Introspect(command) => {
-                command.run(client_config.get_reqwest_client()?, output_opts)
+                // introspection doesn't take a `--profile`, so it uses the default profile's settings
+                let client = client_config.get_endpoint_reqwest_client(&ProfileOpt::default())?;
+                command.run(
+                    client,
+                    client_config.get_builder().get_retries(),
+                    output_opts,
+                )
             }
+            Command::Snapshot(command) => command.run(client_config),
+            Command::Restore(command) => command.run(client_config, git_context),
         }
     }
 }
@@ -1,12 +1,19 @@
+use std::io::{self, IsTerminal};
+
 use clap::Parser;
 use serde::Serialize;
 
+use rover_client::operations::graph::fetch::{self, GraphFetchInput};
 use rover_client::operations::graph::publish::{self, GraphPublishInput};
 use rover_client::shared::GitContext;
+use rover_client::RoverClientError;
 use rover_std::Style;
 
+use crate::command::schema::diff::{SchemaDiff, SeverityOverrides};
 use crate::options::{GraphRefOpt, ProfileOpt, SchemaOpt};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::protected_variant::{confirm_protected_publish, ProtectedVariants};
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
@@ -20,6 +27,12 @@ pub struct Publish {
     #[clap(flatten)]
     #[serde(skip_serializing)]
     schema: SchemaOpt,
+
+    /// Skip the confirmation before publishing to a variant listed in `APOLLO_PROTECTED_VARIANTS`
+    /// or the profile's `protected-variants` default. Required to publish to one in a non-TTY
+    /// environment.
+    #[arg(long, short = 'y')]
+    yes: bool,
 }
 
 impl Publish {
@@ -29,6 +42,11 @@ impl Publish {
         git_context: GitContext,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::PushSchemas,
+        )?;
         let graph_ref = self.graph.graph_ref.to_string();
         eprintln!(
             "Publishing SDL to {} using credentials from the {} profile.",
@@ -42,6 +60,36 @@ impl Publish {
 
         tracing::debug!("Publishing \n{}", &proposed_schema);
 
+        if !self.yes {
+            let protected_variants =
+                ProtectedVariants::new(&client_config, &self.profile.profile_name);
+            if let Some(protected_by) = protected_variants.matching_pattern(&self.graph.graph_ref) {
+                let current_schema = match fetch::run(
+                    GraphFetchInput {
+                        graph_ref: self.graph.graph_ref.clone(),
+                    },
+                    &client,
+                ) {
+                    Ok(fetch_response) => fetch_response.sdl.contents,
+                    // a variant that hasn't been published yet has no current schema to compare to
+                    Err(RoverClientError::NoSchemaForVariant { .. }) => String::new(),
+                    Err(e) => return Err(e.into()),
+                };
+                confirm_protected_publish(
+                    &self.graph.graph_ref,
+                    protected_by,
+                    &SchemaDiff::new(
+                        &current_schema,
+                        &proposed_schema,
+                        &SeverityOverrides::default(),
+                    )?,
+                    &mut io::stdin(),
+                    &mut io::stderr(),
+                    io::stderr().is_terminal() && io::stdin().is_terminal(),
+                )?;
+            }
+        }
+
         let publish_response = publish::run(
             GraphPublishInput {
                 graph_ref: self.graph.graph_ref.clone(),
@@ -0,0 +1,120 @@
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_client::operations::subgraph::publish::{self, SubgraphPublishInput};
+use rover_client::shared::{GitContext, GraphRef};
+use rover_client::RoverClientError;
+use rover_std::{Emoji, Style};
+use serde::Serialize;
+
+use super::snapshot::SchemaSnapshot;
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::protected_variant::ProtectedVariants;
+use crate::utils::scope::{check_key_scope, RequiredScope};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Restore {
+    /// The snapshot archive to restore, created by `rover graph snapshot`
+    #[arg(value_name = "ARCHIVE")]
+    #[serde(skip_serializing)]
+    archive: Utf8PathBuf,
+
+    /// The graph ref to republish the subgraphs to, if not the one the snapshot was taken of
+    #[arg(long = "to", value_name = "GRAPH_REF")]
+    to: Option<GraphRef>,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// Acknowledge that the target variant is protected by `APOLLO_PROTECTED_VARIANTS` or the
+    /// profile's `protected-variants` default.
+    /// Required to restore to a protected variant.
+    #[arg(long, short = 'y')]
+    yes: bool,
+}
+
+impl Restore {
+    pub fn run(
+        &self,
+        client_config: StudioClientConfig,
+        git_context: GitContext,
+    ) -> RoverResult<RoverOutput> {
+        let snapshot = SchemaSnapshot::read_archive(&self.archive)?;
+        let graph_ref = match &self.to {
+            Some(graph_ref) => graph_ref.clone(),
+            None => snapshot.manifest.graph_ref.parse::<GraphRef>()?,
+        };
+        if !self.yes {
+            let protected_variants =
+                ProtectedVariants::new(&client_config, &self.profile.profile_name);
+            if let Some(protected_by) = protected_variants.matching_pattern(&graph_ref) {
+                // restoring republishes every subgraph at once, which is too much to review
+                // in a prompt, so it has to be acknowledged up front
+                let mut err = RoverError::new(anyhow!(
+                    "{} is a protected variant (it matches {}).",
+                    graph_ref,
+                    protected_by
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass `--yes` to restore the snapshot to it anyway.".to_string(),
+                ));
+                return Err(err);
+            }
+        }
+
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(&client, Some(&graph_ref), RequiredScope::PushSchemas)?;
+        eprintln!(
+            "Restoring the snapshot of {} taken at {} to {} using credentials from the {} profile.",
+            Style::Link.paint(&snapshot.manifest.graph_ref),
+            snapshot.manifest.created_at,
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let mut build_errors = None;
+        for subgraph in &snapshot.manifest.subgraphs {
+            eprintln!(
+                "{}Publishing {}...",
+                Emoji::Memo,
+                Style::Link.paint(&subgraph.name)
+            );
+            let publish_response = publish::run(
+                SubgraphPublishInput {
+                    graph_ref: graph_ref.clone(),
+                    subgraph: subgraph.name.clone(),
+                    url: subgraph.routing_url.clone(),
+                    schema: snapshot.subgraph_schemas[&subgraph.name].clone(),
+                    git_context: git_context.clone(),
+                    convert_to_federated_graph: false,
+                },
+                &client,
+            )?;
+            // subgraphs are published one at a time, so only the last publish composes every
+            // subgraph in the snapshot
+            build_errors = Some((subgraph.name.clone(), publish_response.build_errors));
+        }
+
+        match build_errors {
+            Some((subgraph, build_errors)) if !build_errors.is_empty() => {
+                Err(RoverClientError::SubgraphBuildErrors {
+                    subgraph,
+                    graph_ref,
+                    source: build_errors,
+                }
+                .into())
+            }
+            _ => {
+                eprintln!(
+                    "{}Restored {} subgraphs to {}",
+                    Emoji::Success,
+                    snapshot.manifest.subgraphs.len(),
+                    Style::Link.paint(graph_ref.to_string())
+                );
+                Ok(RoverOutput::EmptySuccess)
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Context};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rover_client::operations::subgraph::fetch_all::{self, SubgraphFetchAllInput};
+use rover_client::operations::supergraph::fetch::{self as supergraph_fetch, SupergraphFetchInput};
+use rover_std::{Fs, Style};
+use serde::{Deserialize, Serialize};
+
+use crate::options::{GraphRefOpt, ProfileOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult, PKG_VERSION};
+
+const MANIFEST_PATH: &str = "snapshot.json";
+const SUPERGRAPH_PATH: &str = "supergraph.graphql";
+const SUBGRAPHS_DIR: &str = "subgraphs";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Snapshot {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    /// The directory to write the snapshot archive to. It's created if it doesn't exist.
+    #[arg(long = "out", value_name = "DIR", default_value = ".")]
+    #[serde(skip_serializing)]
+    out: Utf8PathBuf,
+}
+
+impl Snapshot {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let graph_ref = &self.graph.graph_ref;
+        eprintln!(
+            "Taking a snapshot of {} using credentials from the {} profile.",
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let published = fetch_all::run(
+            SubgraphFetchAllInput {
+                graph_ref: graph_ref.clone(),
+            },
+            &client,
+        )?;
+        let mut snapshot = SchemaSnapshot::new(graph_ref.to_string());
+        for subgraph in published.subgraphs {
+            snapshot.add_subgraph(subgraph.name, subgraph.routing_url, subgraph.sdl);
+        }
+        // a graph whose subgraphs have never composed doesn't have a supergraph yet
+        match supergraph_fetch::run(
+            SupergraphFetchInput {
+                graph_ref: graph_ref.clone(),
+            },
+            &client,
+        ) {
+            Ok(fetch_response) => snapshot.supergraph_schema = Some(fetch_response.sdl.contents),
+            Err(e) => eprintln!(
+                "{} The snapshot won't include a supergraph schema: {}",
+                Style::WarningPrefix.paint("WARN:"),
+                e
+            ),
+        }
+
+        Fs::create_dir_all(&self.out)?;
+        let path = self.out.join(format!(
+            "{}-{}.tar.gz",
+            graph_ref,
+            chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+        ));
+        snapshot.write_archive(&path)?;
+
+        Ok(RoverOutput::GraphSnapshot {
+            path,
+            subgraphs: snapshot.manifest.subgraph_names(),
+        })
+    }
+}
+
+/// Describes what's in a snapshot archive, and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SnapshotManifest {
+    pub(crate) graph_ref: String,
+    pub(crate) created_at: String,
+    pub(crate) rover_version: String,
+    pub(crate) subgraphs: Vec<SnapshotSubgraph>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SnapshotSubgraph {
+    pub(crate) name: String,
+    pub(crate) routing_url: Option<String>,
+}
+
+impl SnapshotManifest {
+    pub(crate) fn subgraph_names(&self) -> Vec<String> {
+        self.subgraphs
+            .iter()
+            .map(|subgraph| subgraph.name.clone())
+            .collect()
+    }
+}
+
+/// Every subgraph schema of a graph ref, and its supergraph schema, saved to a `.tar.gz` archive
+/// with a `snapshot.json` manifest, a `supergraph.graphql`, and a `subgraphs/<NAME>.graphql` for
+/// each subgraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SchemaSnapshot {
+    pub(crate) manifest: SnapshotManifest,
+    pub(crate) subgraph_schemas: BTreeMap<String, String>,
+    pub(crate) supergraph_schema: Option<String>,
+}
+
+impl SchemaSnapshot {
+    fn new(graph_ref: String) -> Self {
+        Self {
+            manifest: SnapshotManifest {
+                graph_ref,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                rover_version: PKG_VERSION.to_string(),
+                subgraphs: Vec::new(),
+            },
+            subgraph_schemas: BTreeMap::new(),
+            supergraph_schema: None,
+        }
+    }
+
+    fn add_subgraph(&mut self, name: String, routing_url: Option<String>, schema: String) {
+        self.manifest.subgraphs.push(SnapshotSubgraph {
+            name: name.clone(),
+            routing_url,
+        });
+        self.subgraph_schemas.insert(name, schema);
+    }
+
+    fn write_archive(&self, path: &Utf8Path) -> RoverResult<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("could not create the snapshot archive at {}", path))?;
+        let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let mut append = |entry_path: &str, contents: &str| -> RoverResult<()> {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+            header.set_cksum();
+            archive
+                .append_data(&mut header, entry_path, contents.as_bytes())
+                .with_context(|| format!("could not write {} to {}", entry_path, path))?;
+            Ok(())
+        };
+        append(
+            MANIFEST_PATH,
+            &serde_json::to_string_pretty(&self.manifest)?,
+        )?;
+        if let Some(supergraph_schema) = &self.supergraph_schema {
+            append(SUPERGRAPH_PATH, supergraph_schema)?;
+        }
+        for (name, schema) in &self.subgraph_schemas {
+            append(&format!("{}/{}.graphql", SUBGRAPHS_DIR, name), schema)?;
+        }
+        archive
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .and_then(|mut file| file.flush())
+            .with_context(|| format!("could not finish writing {}", path))?;
+        Ok(())
+    }
+
+    pub(crate) fn read_archive(path: &Utf8Path) -> RoverResult<Self> {
+        let invalid_archive = |reason: String| {
+            let mut err = RoverError::new(anyhow!(
+                "{} is not a valid snapshot archive: {}",
+                path,
+                reason
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Pass an archive created by `rover graph snapshot`.".to_string(),
+            ));
+            err
+        };
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("could not open the snapshot archive at {}", path))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        let mut manifest = None;
+        let mut supergraph_schema = None;
+        let mut subgraph_schemas = BTreeMap::new();
+        let entries = archive
+            .entries()
+            .map_err(|e| invalid_archive(e.to_string()))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| invalid_archive(e.to_string()))?;
+            let entry_path = entry
+                .path()
+                .map_err(|e| invalid_archive(e.to_string()))?
+                .to_string_lossy()
+                .to_string();
+            let mut contents = String::new();
+            entry
+                .read_to_string(&mut contents)
+                .map_err(|e| invalid_archive(format!("could not read {}: {}", entry_path, e)))?;
+            if entry_path == MANIFEST_PATH {
+                manifest = Some(
+                    serde_json::from_str::<SnapshotManifest>(&contents)
+                        .map_err(|e| invalid_archive(format!("{}: {}", MANIFEST_PATH, e)))?,
+                );
+            } else if entry_path == SUPERGRAPH_PATH {
+                supergraph_schema = Some(contents);
+            } else if let Some(name) = entry_path
+                .strip_prefix(&format!("{}/", SUBGRAPHS_DIR))
+                .and_then(|file_name| file_name.strip_suffix(".graphql"))
+            {
+                subgraph_schemas.insert(name.to_string(), contents);
+            }
+        }
+        let manifest =
+            manifest.ok_or_else(|| invalid_archive(format!("it has no {}", MANIFEST_PATH)))?;
+        if let Some(missing) = manifest
+            .subgraphs
+            .iter()
+            .find(|subgraph| !subgraph_schemas.contains_key(&subgraph.name))
+        {
+            return Err(invalid_archive(format!(
+                "it has no schema for the '{}' subgraph",
+                missing.name
+            )));
+        }
+        Ok(Self {
+            manifest,
+            subgraph_schemas,
+            supergraph_schema,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+
+    use super::SchemaSnapshot;
+
+    #[test]
+    fn it_round_trips_snapshot_archives() {
+        let mut snapshot = SchemaSnapshot::new("my-graph@prod".to_string());
+        snapshot.add_subgraph(
+            "products".to_string(),
+            Some("https://products.example.com/graphql".to_string()),
+            "type Query { products: [String] }".to_string(),
+        );
+        snapshot.add_subgraph(
+            "reviews".to_string(),
+            None,
+            "type Query { reviews: [String] }".to_string(),
+        );
+        snapshot.supergraph_schema = Some("schema { query: Query }".to_string());
+
+        let tmp_dir = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(tmp_dir.path().join("snapshot.tar.gz")).unwrap();
+        snapshot.write_archive(&path).unwrap();
+        assert_eq!(SchemaSnapshot::read_archive(&path).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn it_rejects_archives_missing_subgraph_schemas() {
+        let mut snapshot = SchemaSnapshot::new("my-graph@prod".to_string());
+        snapshot.add_subgraph(
+            "products".to_string(),
+            None,
+            "type Query { products: [String] }".to_string(),
+        );
+        snapshot.subgraph_schemas.clear();
+
+        let tmp_dir = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(tmp_dir.path().join("snapshot.tar.gz")).unwrap();
+        snapshot.write_archive(&path).unwrap();
+        let err = SchemaSnapshot::read_archive(&path).unwrap_err();
+        assert!(err.message().contains("'products'"));
+    }
+}
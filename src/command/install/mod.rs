@@ -1,7 +1,9 @@
 use anyhow::anyhow;
 use camino::Utf8PathBuf;
 use clap::Parser;
-use rover_std::Style;
+#[cfg(feature = "composition-js")]
+use rover_std::perf::{self, Phase};
+use rover_std::{Fs, Style};
 use serde::Serialize;
 
 use binstall::{Installer, InstallerError};
@@ -15,7 +17,11 @@ use std::convert::TryFrom;
 use std::env;
 
 mod plugin;
-pub(crate) use plugin::{Plugin, PluginInstaller};
+mod release_index;
+#[cfg(feature = "composition-js")]
+pub(crate) use plugin::find_installed_plugins;
+pub(crate) use plugin::resolve_federation_version;
+pub(crate) use plugin::{AvailablePlugin, InstalledPlugin, Plugin, PluginInstaller, PluginSpec};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Install {
@@ -24,8 +30,29 @@ pub struct Install {
     pub(crate) force: bool,
 
     /// Download and install an officially supported plugin from GitHub releases.
+    ///
+    /// The version can be exact (`router@v1.33.0`), a release channel (`supergraph@latest-2`),
+    /// or a semver range (`router@~1.33`, `supergraph@=2`) that resolves to the newest matching release.
     #[arg(long)]
-    pub(crate) plugin: Option<Plugin>,
+    pub(crate) plugin: Option<PluginSpec>,
+
+    /// Refuse to install plugin versions that float, such as release channels and semver ranges.
+    #[arg(long = "locked")]
+    pub(crate) locked: bool,
+
+    /// Install plugins from a local tarball, or from every tarball in a local directory,
+    /// instead of downloading them.
+    ///
+    /// The plugin and version are read from each tarball's file name (e.g. `router-v1.33.0.tar.gz`)
+    /// unless `--plugin` is also passed.
+    #[arg(long = "from", value_name = "PATH")]
+    #[serde(skip_serializing)]
+    pub(crate) from: Option<Utf8PathBuf>,
+
+    /// List the plugin versions installed on this machine and the latest version available
+    /// for each release channel, instead of installing anything.
+    #[arg(long = "list", conflicts_with_all = ["force", "plugin", "from"])]
+    pub(crate) list: bool,
 
     #[clap(flatten)]
     pub(crate) elv2_license_accepter: LicenseAccepter,
@@ -40,14 +67,43 @@ impl Install {
         let binary_name = PKG_NAME.to_string();
         let rover_installer = self.get_installer(binary_name.to_string(), override_install_path)?;
 
-        if let Some(plugin) = &self.plugin {
+        if self.list {
+            let plugin_installer = PluginInstaller::new(client_config, rover_installer);
+            let (installed, available) = plugin_installer.list()?;
+            Ok(RoverOutput::PluginList {
+                installed,
+                available,
+            })
+        } else if let Some(from) = &self.from {
+            let plugin_installer = PluginInstaller::new(client_config.clone(), rover_installer);
+            for tarball in self.get_plugin_tarballs(from)? {
+                let plugin = match &self.plugin {
+                    Some(PluginSpec::Plugin(plugin)) => plugin.clone(),
+                    Some(PluginSpec::Range { .. } | PluginSpec::Next { .. }) => {
+                        return Err(anyhow!(
+                            "Plugins installed from a tarball must have an exact version."
+                        )
+                        .into())
+                    }
+                    None => Plugin::from_tarball_name(tarball.file_name().unwrap_or_default())?,
+                };
+                if plugin.requires_elv2_license() {
+                    self.elv2_license_accepter
+                        .require_elv2_license(&client_config)?;
+                }
+                plugin_installer.install_from_tarball(&plugin, &tarball)?;
+            }
+
+            Ok(RoverOutput::EmptySuccess)
+        } else if let Some(plugin) = &self.plugin {
+            let plugin = plugin.resolve(&client_config, self.locked)?;
             let requires_elv2_license = plugin.requires_elv2_license();
             if requires_elv2_license {
                 self.elv2_license_accepter
                     .require_elv2_license(&client_config)?;
             }
             let plugin_installer = PluginInstaller::new(client_config, rover_installer);
-            plugin_installer.install(plugin, false)?;
+            plugin_installer.install(&plugin, false)?;
 
             Ok(RoverOutput::EmptySuccess)
         } else {
@@ -106,11 +162,35 @@ impl Install {
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         skip_update: bool,
+    ) -> RoverResult<Utf8PathBuf> {
+        perf::time(Phase::Plugins, || {
+            self.install_versioned_plugin(override_install_path, client_config, skip_update)
+        })
+    }
+
+    /// The directory plugins are installed in, without installing anything.
+    #[cfg(feature = "composition-js")]
+    pub(crate) fn get_plugin_dir(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<Utf8PathBuf> {
+        Ok(self
+            .get_installer(PKG_NAME.to_string(), override_install_path)?
+            .get_bin_dir_path()?)
+    }
+
+    #[cfg(feature = "composition-js")]
+    fn install_versioned_plugin(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        skip_update: bool,
     ) -> RoverResult<Utf8PathBuf> {
         let rover_installer = self.get_installer(PKG_NAME.to_string(), override_install_path)?;
         if let Some(plugin) = &self.plugin {
+            let plugin = plugin.resolve(&client_config, self.locked)?;
             let plugin_installer = PluginInstaller::new(client_config, rover_installer);
-            plugin_installer.install(plugin, skip_update)
+            plugin_installer.install(&plugin, skip_update)
         } else {
             let mut err =
                 RoverError::new(anyhow!("Could not find a plugin to get a version from."));
@@ -119,6 +199,37 @@ impl Install {
         }
     }
 
+    fn get_plugin_tarballs(&self, from: &Utf8PathBuf) -> RoverResult<Vec<Utf8PathBuf>> {
+        if !from.is_dir() {
+            Fs::assert_path_exists(from)?;
+            return Ok(vec![from.clone()]);
+        }
+        if self.plugin.is_some() {
+            let mut err = RoverError::new(anyhow!(
+                "`--plugin` can't be used when installing from a directory."
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Pass the path to a single tarball in {} to `--from` instead.",
+                from
+            )));
+            return Err(err);
+        }
+
+        let mut tarballs: Vec<Utf8PathBuf> = Fs::get_dir_entries(from)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| path.as_str().ends_with(".tar.gz") || path.as_str().ends_with(".tgz"))
+            .collect();
+        if tarballs.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not find any plugin tarballs in {}.",
+                from
+            )));
+        }
+        tarballs.sort();
+        Ok(tarballs)
+    }
+
     fn get_installer(
         &self,
         binary_name: String,
@@ -3,13 +3,24 @@ use std::{env::consts, str::FromStr};
 use anyhow::{anyhow, Context};
 use apollo_federation_types::config::{FederationVersion, PluginVersion, RouterVersion};
 use binstall::Installer;
-use camino::Utf8PathBuf;
-use rover_std::{sanitize_url, Fs};
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_std::{sanitize_url, Fs, Style};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
+use super::release_index::{parse_version_range, resolve_next_version, resolve_version_range};
+use crate::utils::events::{self, Event};
 use crate::{utils::client::StudioClientConfig, RoverError, RoverErrorSuggestion, RoverResult};
 
+// The targets that plugin tarballs are built for
+const PLUGIN_TARGETS: [&str; 5] = [
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+];
+
 // These OSX versions of the router were compiled for aarch64 only
 const AARCH_OSX_ONLY_ROUTER_VERSIONS: [Version; 2] =
     [Version::new(1, 38, 0), Version::new(1, 39, 0)];
@@ -142,6 +153,56 @@ impl Plugin {
         std::env::var("APOLLO_ROVER_DOWNLOAD_HOST")
             .unwrap_or_else(|_| "https://rover.apollo.dev".to_string())
     }
+
+    /// Determines which plugin a tarball contains from its file name,
+    /// e.g. `router-v1.33.0-x86_64-unknown-linux-gnu.tar.gz` or `supergraph-2.5.0.tar.gz`.
+    pub fn from_tarball_name(file_name: &str) -> RoverResult<Self> {
+        let invalid_name = || {
+            let mut err = RoverError::new(anyhow!(
+                "Could not determine which plugin '{}' contains.",
+                file_name
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Name the tarball `{name}-v{version}.tar.gz` (e.g. `router-v1.33.0.tar.gz`), or pass `--plugin {name}@{version}`.".to_string(),
+            ));
+            err
+        };
+        let stem = file_name
+            .strip_suffix(".tar.gz")
+            .or_else(|| file_name.strip_suffix(".tgz"))
+            .ok_or_else(invalid_name)?;
+        let (name, version) = stem.split_once('-').ok_or_else(invalid_name)?;
+        let (version, target) = match PLUGIN_TARGETS
+            .iter()
+            .find_map(|target| Some((version.strip_suffix(&format!("-{target}"))?, *target)))
+        {
+            Some((version, target)) => (version, Some(target)),
+            None => (version, None),
+        };
+        let version = Version::parse(version.strip_prefix('v').unwrap_or(version))
+            .map_err(|_| invalid_name())?;
+        let plugin = match name {
+            "router" => Plugin::Router(RouterVersion::Exact(version)),
+            "supergraph" if version.major >= 2 => {
+                Plugin::Supergraph(FederationVersion::ExactFedTwo(version))
+            }
+            "supergraph" => Plugin::Supergraph(FederationVersion::ExactFedOne(version)),
+            _ => return Err(invalid_name()),
+        };
+
+        if let Some(target) = target {
+            let expected_target = plugin.get_target_arch()?;
+            if target != expected_target {
+                return Err(RoverError::new(anyhow!(
+                    "'{}' was built for {}, but this machine requires {}.",
+                    file_name,
+                    target,
+                    expected_target
+                )));
+            }
+        }
+        Ok(plugin)
+    }
 }
 
 impl FromStr for Plugin {
@@ -180,6 +241,164 @@ impl FromStr for Plugin {
     }
 }
 
+/// A plugin to install, whose version is either one the download host understands
+/// (an exact version or a `latest-*` channel), a semver range like `~2.3`, or the `next`
+/// pre-release channel. Ranges and `next` are resolved against the plugin's published releases.
+#[derive(Clone, Debug, Serialize)]
+pub enum PluginSpec {
+    Plugin(Plugin),
+    Range { name: String, range: String },
+    Next { name: String },
+}
+
+impl PluginSpec {
+    /// Creates a [`PluginSpec`] for the `router` plugin from an exact version or a semver range.
+    pub fn router(version: &str) -> RoverResult<Self> {
+        match Version::parse(version.strip_prefix('v').unwrap_or(version)) {
+            Ok(version) => Ok(Self::Plugin(Plugin::Router(RouterVersion::Exact(version)))),
+            Err(_) => Ok(format!("router@{version}").parse::<Self>()?),
+        }
+    }
+
+    /// Resolves this specifier to a [`Plugin`] that can be installed.
+    ///
+    /// When `locked` is true, only exact versions are accepted.
+    pub fn resolve(&self, client_config: &StudioClientConfig, locked: bool) -> RoverResult<Plugin> {
+        let floating_version = match self {
+            Self::Plugin(Plugin::Router(RouterVersion::Latest)) => {
+                Some("router@latest".to_string())
+            }
+            Self::Plugin(Plugin::Supergraph(FederationVersion::LatestFedOne)) => {
+                Some("supergraph@latest-0".to_string())
+            }
+            Self::Plugin(Plugin::Supergraph(FederationVersion::LatestFedTwo)) => {
+                Some("supergraph@latest-2".to_string())
+            }
+            Self::Range { name, range } => Some(format!("{name}@{range}")),
+            Self::Next { name } => Some(format!("{name}@next")),
+            Self::Plugin(_) => None,
+        };
+        if let Some(floating_version) = floating_version.filter(|_| locked) {
+            let mut err = RoverError::new(anyhow!(
+                "'{}' does not specify an exact version, which is required by `--locked`.",
+                floating_version
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Pin an exact plugin version, or remove the `--locked` flag.".to_string(),
+            ));
+            return Err(err);
+        }
+
+        let (name, version) = match self {
+            Self::Plugin(plugin) => return Ok(plugin.clone()),
+            Self::Range { name, range } => {
+                let range = parse_version_range(range)
+                    .ok_or_else(|| anyhow!("Invalid version range '{}'.", range))?;
+                let version = resolve_version_range(name, &range, client_config)?;
+                tracing::info!("resolved {}@{} to v{}", name, range, version);
+                (name, version)
+            }
+            Self::Next { name } => {
+                let version = resolve_next_version(name, client_config)?;
+                if !version.pre.is_empty() {
+                    eprintln!(
+                        "{} Using v{}, a pre-release build of the '{}' plugin. Pre-release builds are not supported in production.",
+                        Style::WarningPrefix.paint("WARN:"),
+                        version,
+                        name
+                    );
+                }
+                (name, version)
+            }
+        };
+        Ok(match name.as_str() {
+            "router" => Plugin::Router(RouterVersion::Exact(version)),
+            _ if version.major >= 2 => Plugin::Supergraph(FederationVersion::ExactFedTwo(version)),
+            _ => Plugin::Supergraph(FederationVersion::ExactFedOne(version)),
+        })
+    }
+}
+
+/// Resolves a federation version for the `supergraph` plugin, which may be exact (`=2.3.2` or `2.3.2`),
+/// a release channel (`2`, `latest-2`, or `next`), or a semver range (`=2` or `~2.3`).
+///
+/// When `locked` is true, only exact versions are accepted.
+pub fn resolve_federation_version(
+    spec: &str,
+    client_config: &StudioClientConfig,
+    locked: bool,
+) -> RoverResult<FederationVersion> {
+    let plugin_spec = match Version::parse(spec.strip_prefix('v').unwrap_or(spec)) {
+        Ok(version) if version.major >= 2 => {
+            PluginSpec::Plugin(Plugin::Supergraph(FederationVersion::ExactFedTwo(version)))
+        }
+        Ok(version) => {
+            PluginSpec::Plugin(Plugin::Supergraph(FederationVersion::ExactFedOne(version)))
+        }
+        Err(_) => format!("supergraph@{spec}").parse::<PluginSpec>()?,
+    };
+    match plugin_spec.resolve(client_config, locked)? {
+        Plugin::Supergraph(federation_version) => Ok(federation_version),
+        Plugin::Router(_) => {
+            unreachable!("supergraph versions always resolve to the supergraph plugin")
+        }
+    }
+}
+
+impl From<Plugin> for PluginSpec {
+    fn from(plugin: Plugin) -> Self {
+        Self::Plugin(plugin)
+    }
+}
+
+impl FromStr for PluginSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match Plugin::from_str(s) {
+            Ok(plugin) => Ok(Self::Plugin(plugin)),
+            Err(plugin_err) => match s.to_lowercase().split_once('@') {
+                Some((name, "next")) if name == "router" || name == "supergraph" => {
+                    Ok(Self::Next {
+                        name: name.to_string(),
+                    })
+                }
+                // exact versions that `Plugin` couldn't parse are invalid, not ranges
+                Some((name, range))
+                    if (name == "router" || name == "supergraph")
+                        && Version::parse(range.strip_prefix('v').unwrap_or(range)).is_err()
+                        && parse_version_range(range).is_some() =>
+                {
+                    Ok(Self::Range {
+                        name: name.to_string(),
+                        range: range.to_string(),
+                    })
+                }
+                _ => Err(plugin_err),
+            },
+        }
+    }
+}
+
+/// A plugin binary installed in Rover's `bin` directory.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct InstalledPlugin {
+    pub name: String,
+    pub version: String,
+    pub path: Utf8PathBuf,
+    pub size: u64,
+}
+
+/// The latest version of a plugin for one of its release channels (e.g. `supergraph@latest-2`).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AvailablePlugin {
+    pub name: String,
+    pub channel: String,
+    /// `None` if the latest version could not be determined, e.g. while offline.
+    pub latest_version: Option<String>,
+    pub installed: bool,
+}
+
 pub struct PluginInstaller {
     client_config: StudioClientConfig,
     rover_installer: Installer,
@@ -194,6 +413,27 @@ impl PluginInstaller {
     }
 
     pub fn install(&self, plugin: &Plugin, skip_update: bool) -> RoverResult<Utf8PathBuf> {
+        match self.find_or_install(plugin, skip_update) {
+            // if we can't reach the download host (e.g. while offline),
+            // fall back to a version of the plugin that's already installed
+            Err(install_err) if !skip_update => match self.find_or_install(plugin, true) {
+                Ok(exe) => {
+                    eprintln!(
+                        "{} Could not check for updates to the '{}' plugin, using the previously installed {} instead: {}",
+                        Style::WarningPrefix.paint("WARN:"),
+                        plugin.get_name(),
+                        Style::Path.paint(&exe),
+                        install_err.message()
+                    );
+                    Ok(exe)
+                }
+                Err(_) => Err(install_err),
+            },
+            result => result,
+        }
+    }
+
+    fn find_or_install(&self, plugin: &Plugin, skip_update: bool) -> RoverResult<Utf8PathBuf> {
         let skip_update_err = |plugin_name: &str, version: &str| {
             let mut err = RoverError::new(anyhow!(
                 "You do not have the '{}-v{}' plugin installed.",
@@ -306,6 +546,123 @@ impl PluginInstaller {
         Ok(install_location)
     }
 
+    /// Lists the plugins installed on this machine, along with the latest version
+    /// of each plugin release channel.
+    pub fn list(&self) -> RoverResult<(Vec<InstalledPlugin>, Vec<AvailablePlugin>)> {
+        let plugin_dir = self.rover_installer.get_bin_dir_path()?;
+        let mut installed: Vec<(Version, InstalledPlugin)> = Vec::new();
+        for entry in Fs::get_dir_entries(plugin_dir)?.flatten() {
+            let file_name = entry.file_name();
+            let file_name = file_name
+                .strip_suffix(std::env::consts::EXE_SUFFIX)
+                .unwrap_or(file_name);
+            match file_name.split_once("-v") {
+                Some((name, version)) if name == "router" || name == "supergraph" => {
+                    if let Ok(version) = Version::parse(version) {
+                        let plugin = InstalledPlugin {
+                            name: name.to_string(),
+                            version: format!("v{version}"),
+                            path: entry.path().to_path_buf(),
+                            size: entry.metadata().map(|m| m.len()).unwrap_or_default(),
+                        };
+                        installed.push((version, plugin));
+                    }
+                }
+                _ => {}
+            }
+        }
+        installed.sort_by(|(a_version, a), (b_version, b)| {
+            (&a.name, a_version).cmp(&(&b.name, b_version))
+        });
+        let installed: Vec<InstalledPlugin> = installed.into_iter().map(|(_, p)| p).collect();
+
+        let no_redirect_client = self
+            .client_config
+            .get_builder()
+            .follow_redirects(false)
+            .build()?;
+        let channels = [
+            ("latest", Plugin::Router(RouterVersion::Latest)),
+            (
+                "latest-2",
+                Plugin::Supergraph(FederationVersion::LatestFedTwo),
+            ),
+            (
+                "latest-0",
+                Plugin::Supergraph(FederationVersion::LatestFedOne),
+            ),
+        ];
+        let available = channels
+            .into_iter()
+            .map(|(channel, plugin)| {
+                let latest_version = plugin
+                    .get_tarball_url()
+                    .and_then(|url| {
+                        Ok(self.rover_installer.get_plugin_version(
+                            &url,
+                            true,
+                            &no_redirect_client,
+                        )?)
+                    })
+                    .map_err(|e| {
+                        tracing::debug!(
+                            "could not get the latest version of {}@{}: {}",
+                            plugin.get_name(),
+                            channel,
+                            e
+                        )
+                    })
+                    .ok();
+                let installed = latest_version.as_ref().map_or(false, |latest_version| {
+                    installed
+                        .iter()
+                        .any(|p| p.name == plugin.get_name() && &p.version == latest_version)
+                });
+                AvailablePlugin {
+                    name: plugin.get_name(),
+                    channel: channel.to_string(),
+                    latest_version,
+                    installed,
+                }
+            })
+            .collect();
+
+        Ok((installed, available))
+    }
+
+    /// Installs `plugin` from a tarball on the local filesystem instead of downloading it.
+    pub fn install_from_tarball(
+        &self,
+        plugin: &Plugin,
+        tarball_path: &Utf8Path,
+    ) -> RoverResult<Option<Utf8PathBuf>> {
+        let version = match plugin {
+            Plugin::Router(RouterVersion::Exact(version))
+            | Plugin::Supergraph(FederationVersion::ExactFedOne(version))
+            | Plugin::Supergraph(FederationVersion::ExactFedTwo(version)) => version,
+            _ => {
+                let mut err = RoverError::new(anyhow!(
+                    "Plugins installed from a tarball must have an exact version."
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass an exact version with `--plugin`, e.g. `--plugin router@v1.33.0`."
+                        .to_string(),
+                ));
+                return Err(err);
+            }
+        };
+        eprintln!(
+            "installing the '{}' plugin from {}",
+            plugin.get_name(),
+            tarball_path
+        );
+        Ok(self.rover_installer.install_plugin_from_tarball(
+            &plugin.get_name(),
+            &format!("v{version}"),
+            tarball_path,
+        )?)
+    }
+
     fn find_existing_latest_major(
         &self,
         plugin: &Plugin,
@@ -334,9 +691,17 @@ impl PluginInstaller {
     }
 
     fn install_latest_major(&self, plugin: &Plugin) -> RoverResult<Option<Utf8PathBuf>> {
-        let latest_version = self
-            .rover_installer
-            .get_plugin_version(&plugin.get_tarball_url()?, true)?;
+        // the latest version is read from the download host's response before it redirects
+        let no_redirect_client = self
+            .client_config
+            .get_builder()
+            .follow_redirects(false)
+            .build()?;
+        let latest_version = self.rover_installer.get_plugin_version(
+            &plugin.get_tarball_url()?,
+            true,
+            &no_redirect_client,
+        )?;
         if let Ok(Some(exe)) = self.find_existing_exact(plugin, &latest_version) {
             tracing::debug!("{} exists, skipping install", &exe);
             Ok(Some(exe))
@@ -374,16 +739,25 @@ impl PluginInstaller {
         } else {
             eprintln!("downloading the '{plugin_name}' plugin");
         }
-        Ok(self.rover_installer.install_plugin(
+        events::emit(Event::PluginDownloadStarted {
+            plugin: plugin_name.clone(),
+        });
+        let installed = self.rover_installer.install_plugin(
             &plugin_name,
             &plugin_tarball_url,
             &self.client_config.get_reqwest_client()?,
             is_latest,
-        )?)
+        )?;
+        events::emit(Event::PluginDownloadCompleted {
+            plugin: plugin_name,
+        });
+        Ok(installed)
     }
 }
 
-fn find_installed_plugins(
+/// The file names of the installed versions of `plugin_name` with the given major version,
+/// from oldest to newest.
+pub(crate) fn find_installed_plugins(
     plugin_dir: &Utf8PathBuf,
     plugin_name: &str,
     major_version: u64,
@@ -559,6 +933,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_plugin_from_tarball_name() {
+        assert!(matches!(
+            Plugin::from_tarball_name("router-1.33.0.tar.gz").unwrap(),
+            Plugin::Router(RouterVersion::Exact(v)) if v == Version::new(1, 33, 0)
+        ));
+        assert!(matches!(
+            Plugin::from_tarball_name("supergraph-v2.5.0.tgz").unwrap(),
+            Plugin::Supergraph(FederationVersion::ExactFedTwo(v)) if v == Version::new(2, 5, 0)
+        ));
+        assert!(matches!(
+            Plugin::from_tarball_name("supergraph-v0.37.1.tar.gz").unwrap(),
+            Plugin::Supergraph(FederationVersion::ExactFedOne(v)) if v == Version::new(0, 37, 1)
+        ));
+        Plugin::from_tarball_name("router.tar.gz").unwrap_err();
+        Plugin::from_tarball_name("gateway-v1.0.0.tar.gz").unwrap_err();
+        Plugin::from_tarball_name("router-v1.33.0.zip").unwrap_err();
+    }
+
+    #[test]
+    #[cfg(not(target_env = "musl"))]
+    fn test_plugin_from_tarball_name_checks_target() {
+        let plugin = Plugin::Router(RouterVersion::Exact(Version::new(1, 39, 1)));
+        let target = plugin.get_target_arch().unwrap();
+        Plugin::from_tarball_name(&format!("router-v1.39.1-{target}.tar.gz")).unwrap();
+
+        let other_target = PLUGIN_TARGETS.iter().find(|t| **t != target).unwrap();
+        Plugin::from_tarball_name(&format!("router-v1.39.1-{other_target}.tar.gz")).unwrap_err();
+    }
+
+    #[test]
+    fn test_plugin_spec_from_str() {
+        assert!(matches!(
+            "router@v1.33.0".parse::<PluginSpec>().unwrap(),
+            PluginSpec::Plugin(Plugin::Router(RouterVersion::Exact(_)))
+        ));
+        assert!(matches!(
+            "supergraph@latest-2".parse::<PluginSpec>().unwrap(),
+            PluginSpec::Plugin(Plugin::Supergraph(FederationVersion::LatestFedTwo))
+        ));
+        assert!(matches!(
+            "router@~1.33".parse::<PluginSpec>().unwrap(),
+            PluginSpec::Range { name, range } if name == "router" && range == "~1.33"
+        ));
+        assert!(matches!(
+            "supergraph@=2".parse::<PluginSpec>().unwrap(),
+            PluginSpec::Range { name, range } if name == "supergraph" && range == "=2"
+        ));
+        assert!(matches!(
+            "supergraph@next".parse::<PluginSpec>().unwrap(),
+            PluginSpec::Next { name } if name == "supergraph"
+        ));
+        "gateway@~1.33".parse::<PluginSpec>().unwrap_err();
+        "router@not-a-version".parse::<PluginSpec>().unwrap_err();
+    }
+
+    #[test]
+    fn test_plugin_spec_locked() {
+        let tmp_home = assert_fs::TempDir::new().unwrap();
+        let tmp_path = camino::Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let client_config = StudioClientConfig::new(
+            None,
+            houston::Config::new(Some(&tmp_path), None).unwrap(),
+            false,
+            crate::utils::client::ClientBuilder::default(),
+        );
+        for floating in [
+            "router@latest",
+            "supergraph@latest-2",
+            "router@~1.33",
+            "router@next",
+        ] {
+            floating
+                .parse::<PluginSpec>()
+                .unwrap()
+                .resolve(&client_config, true)
+                .unwrap_err();
+        }
+        "router@v1.33.0"
+            .parse::<PluginSpec>()
+            .unwrap()
+            .resolve(&client_config, true)
+            .unwrap();
+    }
+
     #[test]
     #[cfg(target_env = "musl")]
     fn test_plugin_version_should_fail() {
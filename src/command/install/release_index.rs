@@ -0,0 +1,197 @@
+use anyhow::anyhow;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const ROUTER_RELEASES_URL: &str =
+    "https://api.github.com/repos/apollographql/router/releases?per_page=100";
+const SUPERGRAPH_RELEASES_URL: &str =
+    "https://api.github.com/repos/apollographql/federation-rs/releases?per_page=100";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+/// Parses a semver range for a plugin version, such as `=2`, `~1.33`, or `>=2.4, <2.6`.
+pub(crate) fn parse_version_range(spec: &str) -> Option<VersionReq> {
+    VersionReq::parse(spec.trim()).ok()
+}
+
+/// Resolves `range` to the newest published release of the `plugin_name` plugin that satisfies it.
+pub(crate) fn resolve_version_range(
+    plugin_name: &str,
+    range: &VersionReq,
+    client_config: &StudioClientConfig,
+) -> RoverResult<Version> {
+    let versions = fetch_release_versions(plugin_name, &range.to_string(), false, client_config)?;
+    newest_matching(&versions, range).ok_or_else(|| {
+        let mut err = RoverError::new(anyhow!(
+            "No release of the '{}' plugin matches '{}'.",
+            plugin_name,
+            range
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "The newest release of the '{}' plugin is {}.",
+            plugin_name,
+            versions
+                .iter()
+                .max()
+                .map(|v| format!("v{v}"))
+                .unwrap_or_else(|| "unknown".to_string())
+        )));
+        err
+    })
+}
+
+/// Resolves the newest published release of the `plugin_name` plugin, including pre-releases.
+pub(crate) fn resolve_next_version(
+    plugin_name: &str,
+    client_config: &StudioClientConfig,
+) -> RoverResult<Version> {
+    fetch_release_versions(plugin_name, "next", true, client_config)?
+        .into_iter()
+        .max()
+        .ok_or_else(|| anyhow!("The '{}' plugin has no published releases.", plugin_name).into())
+}
+
+fn fetch_release_versions(
+    plugin_name: &str,
+    spec: &str,
+    include_prereleases: bool,
+    client_config: &StudioClientConfig,
+) -> RoverResult<Vec<Version>> {
+    let releases_url = match plugin_name {
+        "router" => ROUTER_RELEASES_URL,
+        "supergraph" => SUPERGRAPH_RELEASES_URL,
+        _ => return Err(anyhow!("Invalid plugin name {}.", plugin_name).into()),
+    };
+    tracing::info!("resolving {}@{} from {}", plugin_name, spec, releases_url);
+    let client = client_config.get_reqwest_client()?;
+    let fetch_err = |e: reqwest::Error| {
+        let mut err = RoverError::new(anyhow!(
+            "Could not fetch the releases of the '{}' plugin to resolve '{}': {}",
+            plugin_name,
+            spec,
+            e
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "Specify an exact plugin version instead.".to_string(),
+        ));
+        err
+    };
+
+    // GitHub lists at most 100 releases per page, and links each page to the next one
+    let mut releases = Vec::new();
+    let mut page_url = Some(releases_url.to_string());
+    while let Some(url) = page_url {
+        let response = client
+            .get(&url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_err)?;
+        page_url = response
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_page_url);
+        releases.extend(response.json::<Vec<Release>>().map_err(fetch_err)?);
+    }
+
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft && (include_prereleases || !release.prerelease))
+        .filter_map(|release| parse_release_tag(plugin_name, &release.tag_name))
+        .collect())
+}
+
+/// Finds the `rel="next"` URL in a `Link` header, e.g.
+/// `<https://api.github.com/...&page=2>; rel="next", <https://api.github.com/...&page=5>; rel="last"`.
+fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// Parses a release tag, e.g. `v1.33.0` for the router or `supergraph@v2.6.1` for composition.
+fn parse_release_tag(plugin_name: &str, tag: &str) -> Option<Version> {
+    let version = match plugin_name {
+        "router" => tag,
+        _ => tag.strip_prefix(plugin_name)?.strip_prefix('@')?,
+    };
+    Version::parse(version.strip_prefix('v').unwrap_or(version)).ok()
+}
+
+fn newest_matching(versions: &[Version], range: &VersionReq) -> Option<Version> {
+    versions.iter().filter(|v| range.matches(v)).max().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::{newest_matching, next_page_url, parse_release_tag, parse_version_range};
+
+    #[test]
+    fn it_follows_the_next_page_link() {
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/repositories/1/releases?per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/releases?per_page=100&page=5>; rel="last""#
+            ),
+            Some("https://api.github.com/repositories/1/releases?per_page=100&page=2".to_string())
+        );
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/repositories/1/releases?per_page=100&page=4>; rel="prev", <https://api.github.com/repositories/1/releases?per_page=100&page=1>; rel="first""#
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn it_parses_release_tags() {
+        assert_eq!(
+            parse_release_tag("router", "v1.33.0"),
+            Some(Version::new(1, 33, 0))
+        );
+        assert_eq!(
+            parse_release_tag("supergraph", "supergraph@v2.6.1"),
+            Some(Version::new(2, 6, 1))
+        );
+        assert_eq!(
+            parse_release_tag("supergraph", "router-bridge@v0.5.0"),
+            None
+        );
+    }
+
+    #[test]
+    fn it_resolves_the_newest_matching_version() {
+        let versions = vec![
+            Version::new(1, 32, 0),
+            Version::new(1, 33, 0),
+            Version::new(1, 33, 2),
+            Version::new(1, 34, 0),
+            Version::new(2, 0, 0),
+        ];
+        let resolve = |spec| newest_matching(&versions, &parse_version_range(spec).unwrap());
+        assert_eq!(resolve("~1.33"), Some(Version::new(1, 33, 2)));
+        assert_eq!(resolve("=1"), Some(Version::new(1, 34, 0)));
+        assert_eq!(resolve(">=1.32, <1.34"), Some(Version::new(1, 33, 2)));
+        assert_eq!(resolve("=3"), None);
+    }
+}
@@ -1,34 +1,44 @@
+mod completions;
 mod config;
 mod contract;
 mod dev;
 mod docs;
 mod explain;
 mod fed2;
+pub(crate) mod fleet;
 mod graph;
 mod info;
 pub(crate) mod install;
 mod license;
+mod ops;
+mod org;
 pub(crate) mod output;
 mod persisted_queries;
 mod readme;
+pub(crate) mod schema;
 pub(crate) mod subgraph;
 mod supergraph;
 pub(crate) mod template;
 mod update;
 
+pub use completions::Completions;
 pub use config::Config;
 pub use contract::Contract;
 pub use dev::Dev;
 pub use docs::Docs;
 pub use explain::Explain;
 pub use fed2::Fed2;
+pub use fleet::Fleet;
 pub use graph::Graph;
 pub use info::Info;
 pub use install::Install;
 pub use license::License;
+pub use ops::Ops;
+pub use org::Org;
 pub use output::RoverOutput;
 pub use persisted_queries::PersistedQueries;
 pub use readme::Readme;
+pub use schema::Schema;
 pub use subgraph::Subgraph;
 pub use supergraph::Supergraph;
 pub use template::Template;
@@ -0,0 +1,366 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::anyhow;
+use apollo_parser::ast;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::Fs;
+use serde::{Deserialize, Serialize};
+
+use super::coverage::{fragment_definitions, operation_files, parse_operations, SchemaFields};
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::name;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Cost {
+    /// The schema file the operations run against. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's')]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// An operation file, or a directory to read every `.graphql` and `.gql` file from.
+    /// Directories are searched recursively.
+    #[arg(long = "operation", alias = "operations")]
+    #[serde(skip_serializing)]
+    operation: Utf8PathBuf,
+
+    /// A YAML file with the weights to score fields with. Without one, every field costs 1
+    /// and every list is assumed to have 10 items.
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    config: Option<Utf8PathBuf>,
+
+    /// Fail if any operation's cost score is higher than this
+    #[arg(long = "max-cost")]
+    max_cost: Option<u64>,
+
+    /// Fail if any operation selects fields nested deeper than this
+    #[arg(long = "max-depth")]
+    max_depth: Option<usize>,
+}
+
+impl Cost {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let config = match &self.config {
+            Some(path) => CostConfig::from_yaml(&Fs::read_file(path)?).map_err(|e| {
+                RoverError::new(anyhow!("Could not read the cost config at {}: {}", path, e))
+            })?,
+            None => CostConfig::default(),
+        };
+        let operations = operation_files(&self.operation)?
+            .iter()
+            .map(|path| Ok((path.to_string(), Fs::read_file(path)?)))
+            .collect::<RoverResult<Vec<_>>>()?;
+        let costs = OperationCost::measure(&sdl, &operations, &config)?;
+
+        let over_limit = costs
+            .iter()
+            .filter(|cost| {
+                self.max_cost.map_or(false, |max_cost| cost.cost > max_cost)
+                    || self
+                        .max_depth
+                        .map_or(false, |max_depth| cost.depth > max_depth)
+            })
+            .map(|cost| {
+                format!(
+                    "{} ({}): cost {}, depth {}",
+                    cost.operation, cost.file, cost.cost, cost.depth
+                )
+            })
+            .collect::<Vec<_>>();
+        if !over_limit.is_empty() {
+            let mut err = RoverError::new(anyhow!(
+                "{} {} over the limit:\n{}",
+                over_limit.len(),
+                match over_limit.len() {
+                    1 => "operation is",
+                    _ => "operations are",
+                },
+                over_limit.join("\n")
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Select fewer fields or lists in these operations, or raise `--max-cost` or `--max-depth`."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+        Ok(RoverOutput::OperationCost(costs))
+    }
+}
+
+/// How fields are weighted when scoring operations.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct CostConfig {
+    /// The cost of a field without a weight
+    pub(crate) default_weight: u64,
+    /// How many items lists are assumed to have. A list field's cost, including the fields
+    /// selected from it, is multiplied by this.
+    pub(crate) list_size: u64,
+    /// Weights by field coordinate, like `Query.products`, or by type name, which weighs every
+    /// field that returns the type. Coordinates take precedence.
+    pub(crate) weights: BTreeMap<String, u64>,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            default_weight: 1,
+            list_size: 10,
+            weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl CostConfig {
+    fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    fn weight(&self, coordinate: &str, return_type: Option<&String>) -> u64 {
+        self.weights
+            .get(coordinate)
+            .or_else(|| return_type.and_then(|return_type| self.weights.get(return_type)))
+            .copied()
+            .unwrap_or(self.default_weight)
+    }
+}
+
+/// How expensive an operation is to execute.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct OperationCost {
+    /// The operation's name, or `<anonymous>`
+    pub(crate) operation: String,
+    pub(crate) file: String,
+    /// The sum of the weights of the fields it selects, with lists multiplied by their size
+    pub(crate) cost: u64,
+    /// How deeply its fields are nested
+    pub(crate) depth: usize,
+    /// The most fields it selects from a single object
+    pub(crate) breadth: usize,
+}
+
+impl OperationCost {
+    /// Scores each operation in `operations`, a list of file names and their contents, against
+    /// `sdl`. Fragments can be spread across files.
+    pub(crate) fn measure(
+        sdl: &str,
+        operations: &[(String, String)],
+        config: &CostConfig,
+    ) -> RoverResult<Vec<Self>> {
+        let schema = SchemaFields::from_sdl(sdl)?;
+        let documents = parse_operations(operations)?;
+        let fragments = fragment_definitions(documents.iter().map(|(_, document)| document));
+
+        let mut walker = CostWalker {
+            schema: &schema,
+            fragments: &fragments,
+            config,
+            spread: Vec::new(),
+        };
+        let mut costs = Vec::new();
+        for (file, document) in &documents {
+            for definition in document.definitions() {
+                let ast::Definition::OperationDefinition(operation) = definition else {
+                    continue;
+                };
+                let root_type = schema.root_type(operation.operation_type());
+                let measure = walker.selection_set(&root_type, operation.selection_set());
+                costs.push(OperationCost {
+                    operation: operation
+                        .name()
+                        .map(|name| name.text().to_string())
+                        .unwrap_or_else(|| "<anonymous>".to_string()),
+                    file: file.clone(),
+                    cost: measure.cost,
+                    depth: measure.depth,
+                    breadth: measure.breadth,
+                });
+            }
+        }
+        Ok(costs)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Measure {
+    cost: u64,
+    depth: usize,
+    breadth: usize,
+    /// The fields selected directly from this object, including through fragments
+    width: usize,
+}
+
+struct CostWalker<'a> {
+    schema: &'a SchemaFields,
+    fragments: &'a HashMap<String, ast::FragmentDefinition>,
+    config: &'a CostConfig,
+    /// The fragments being spread, so fragments that spread themselves don't loop forever
+    spread: Vec<String>,
+}
+
+impl CostWalker<'_> {
+    /// Fragments on different types of an interface or union are all counted, so the cost is
+    /// the most the selection set could cost.
+    fn selection_set(
+        &mut self,
+        type_name: &str,
+        selection_set: Option<ast::SelectionSet>,
+    ) -> Measure {
+        let mut measure = Measure::default();
+        for selection in selection_set
+            .iter()
+            .flat_map(|selection_set| selection_set.selections())
+        {
+            let fragment_measure = match selection {
+                ast::Selection::Field(field) => {
+                    let field_name = name(field.name());
+                    // introspection fields like `__typename` aren't part of the schema
+                    if field_name.starts_with("__") {
+                        continue;
+                    }
+                    let coordinate = format!("{}.{}", type_name, field_name);
+                    let return_type = self
+                        .schema
+                        .types
+                        .get(type_name)
+                        .and_then(|fields| fields.get(&field_name));
+                    let selected = match return_type {
+                        Some(return_type) => self.selection_set(return_type, field.selection_set()),
+                        None => Measure::default(),
+                    };
+                    let multiplier = if self.schema.is_list(&coordinate) {
+                        self.config.list_size
+                    } else {
+                        1
+                    };
+                    measure.width += 1;
+                    measure.cost +=
+                        (self.config.weight(&coordinate, return_type) + selected.cost) * multiplier;
+                    measure.depth = measure.depth.max(selected.depth + 1);
+                    measure.breadth = measure.breadth.max(selected.breadth);
+                    continue;
+                }
+                ast::Selection::InlineFragment(fragment) => {
+                    let fragment_type = fragment
+                        .type_condition()
+                        .and_then(|condition| condition.named_type())
+                        .map(|ty| name(ty.name()))
+                        .unwrap_or_else(|| type_name.to_string());
+                    self.selection_set(&fragment_type, fragment.selection_set())
+                }
+                ast::Selection::FragmentSpread(spread) => {
+                    let fragment_name = name(spread.fragment_name().and_then(|name| name.name()));
+                    if self.spread.contains(&fragment_name) {
+                        continue;
+                    }
+                    let Some(fragment) = self.fragments.get(&fragment_name) else {
+                        continue;
+                    };
+                    let fragment_type = name(
+                        fragment
+                            .type_condition()
+                            .and_then(|condition| condition.named_type())
+                            .and_then(|ty| ty.name()),
+                    );
+                    self.spread.push(fragment_name);
+                    let spread_measure =
+                        self.selection_set(&fragment_type, fragment.selection_set());
+                    self.spread.pop();
+                    spread_measure
+                }
+            };
+            // fragments select fields from the same object as the selection set they're in
+            measure.width += fragment_measure.width;
+            measure.cost += fragment_measure.cost;
+            measure.depth = measure.depth.max(fragment_measure.depth);
+            measure.breadth = measure.breadth.max(fragment_measure.breadth);
+        }
+        measure.breadth = measure.breadth.max(measure.width);
+        measure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CostConfig, OperationCost};
+
+    const SDL: &str = r#"
+type Query {
+  products: [Product!]!
+  product(id: ID!): Product
+}
+
+type Product {
+  id: ID!
+  name: String
+  reviews: [Review]
+}
+
+type Review {
+  body: String
+  author: String
+}
+"#;
+
+    #[test]
+    fn it_scores_operations() {
+        let operations = vec![(
+            "products.graphql".to_string(),
+            r#"
+query Products {
+  products {
+    ...ProductFields
+    reviews {
+      body
+      author
+    }
+  }
+}
+
+{
+  product(id: "1") {
+    name
+    __typename
+  }
+}
+
+fragment ProductFields on Product { id name }
+"#
+            .to_string(),
+        )];
+        let config = CostConfig::from_yaml(
+            r#"
+list_size: 5
+weights:
+  Product.reviews: 3
+  Product: 2
+"#,
+        )
+        .unwrap();
+        let costs = OperationCost::measure(SDL, &operations, &config).unwrap();
+        assert_eq!(costs.len(), 2);
+
+        let products = &costs[0];
+        assert_eq!(products.operation, "Products");
+        // products: (2 + id 1 + name 1 + reviews: (3 + body 1 + author 1) * 5) * 5
+        assert_eq!(products.cost, 145);
+        assert_eq!(products.depth, 3);
+        assert_eq!(products.breadth, 3);
+
+        let product = &costs[1];
+        assert_eq!(product.operation, "<anonymous>");
+        assert_eq!(product.cost, 3);
+        assert_eq!(product.depth, 2);
+        assert_eq!(product.breadth, 1);
+    }
+
+    #[test]
+    fn it_rejects_unknown_config_keys() {
+        assert!(CostConfig::from_yaml("list_sizes: 5").is_err());
+    }
+}
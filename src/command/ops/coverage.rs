@@ -0,0 +1,497 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::anyhow;
+use apollo_parser::{ast, Parser as GraphQLParser};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use rover_std::Fs;
+use serde::Serialize;
+
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::{name, named_type};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Coverage {
+    /// The schema file to measure coverage of. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's')]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// An operation file, or a directory to read every `.graphql` and `.gql` file from.
+    /// Directories are searched recursively.
+    #[arg(long = "operations")]
+    #[serde(skip_serializing)]
+    operations: Utf8PathBuf,
+}
+
+impl Coverage {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let files = operation_files(&self.operations)?;
+        let operations = files
+            .iter()
+            .map(|path| Ok((path.to_string(), Fs::read_file(path)?)))
+            .collect::<RoverResult<Vec<_>>>()?;
+        Ok(RoverOutput::OperationCoverage(SchemaCoverage::measure(
+            &sdl,
+            &operations,
+        )?))
+    }
+}
+
+/// How much of a schema's output fields a set of operations selects.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct SchemaCoverage {
+    pub(crate) operation_count: usize,
+    /// Fields of object and interface types
+    pub(crate) field_count: usize,
+    pub(crate) used_field_count: usize,
+    /// How many operations select each field, by coordinate like `Query.products`
+    pub(crate) usages: BTreeMap<String, usize>,
+    /// The coordinates of fields that no operation selects
+    pub(crate) unused_fields: Vec<String>,
+    /// The coordinates of fields that operations select but the schema doesn't have
+    pub(crate) unknown_fields: Vec<String>,
+}
+
+impl SchemaCoverage {
+    /// The percentage of fields that at least one operation selects.
+    pub(crate) fn percentage(&self) -> f64 {
+        if self.field_count == 0 {
+            100.0
+        } else {
+            self.used_field_count as f64 * 100.0 / self.field_count as f64
+        }
+    }
+
+    /// Measures how much of `sdl` the operations in `operations`, a list of file names and their
+    /// contents, select. Fragments can be spread across files.
+    pub(crate) fn measure(sdl: &str, operations: &[(String, String)]) -> RoverResult<Self> {
+        let schema = SchemaFields::from_sdl(sdl)?;
+        let documents = parse_operations(operations)?;
+        let fragments = fragment_definitions(documents.iter().map(|(_, document)| document));
+
+        let mut walker = SelectionWalker {
+            schema: &schema,
+            fragments: &fragments,
+            selected: BTreeSet::new(),
+            unknown: BTreeSet::new(),
+            spread: Vec::new(),
+        };
+        let mut coverage = SchemaCoverage::default();
+        for operation in documents
+            .iter()
+            .flat_map(|(_, document)| document.definitions())
+            .filter_map(|definition| match definition {
+                ast::Definition::OperationDefinition(operation) => Some(operation),
+                _ => None,
+            })
+        {
+            coverage.operation_count += 1;
+            let root_type = schema.root_type(operation.operation_type());
+            walker.selection_set(&root_type, operation.selection_set());
+            for coordinate in std::mem::take(&mut walker.selected) {
+                *coverage.usages.entry(coordinate).or_default() += 1;
+            }
+        }
+
+        coverage.field_count = schema.field_count();
+        coverage.used_field_count = coverage.usages.len();
+        coverage.unused_fields = schema
+            .coordinates()
+            .filter(|coordinate| !coverage.usages.contains_key(coordinate))
+            .collect();
+        coverage.unknown_fields = walker.unknown.into_iter().collect();
+        Ok(coverage)
+    }
+}
+
+/// The fields that each of `operations`, a list of names and documents, selects but `sdl`
+/// doesn't have, for the operations that select any. Unlike [`SchemaCoverage::measure`], each
+/// document has to define the fragments it spreads, like the operations in a persisted query
+/// manifest do.
+pub(crate) fn unknown_fields(
+    sdl: &str,
+    operations: &[(String, String)],
+) -> RoverResult<Vec<(String, Vec<String>)>> {
+    let schema = SchemaFields::from_sdl(sdl)?;
+    let mut invalid = Vec::new();
+    for (operation_name, document) in parse_operations(operations)? {
+        let fragments = fragment_definitions(std::iter::once(&document));
+        let mut walker = SelectionWalker {
+            schema: &schema,
+            fragments: &fragments,
+            selected: BTreeSet::new(),
+            unknown: BTreeSet::new(),
+            spread: Vec::new(),
+        };
+        for operation in document
+            .definitions()
+            .filter_map(|definition| match definition {
+                ast::Definition::OperationDefinition(operation) => Some(operation),
+                _ => None,
+            })
+        {
+            let root_type = schema.root_type(operation.operation_type());
+            walker.selection_set(&root_type, operation.selection_set());
+        }
+        if !walker.unknown.is_empty() {
+            invalid.push((operation_name, walker.unknown.into_iter().collect()));
+        }
+    }
+    Ok(invalid)
+}
+
+/// Parses each of `operations`, a list of file names and their contents.
+pub(super) fn parse_operations(
+    operations: &[(String, String)],
+) -> RoverResult<Vec<(String, ast::Document)>> {
+    let mut documents = Vec::new();
+    for (file_name, contents) in operations {
+        let tree = GraphQLParser::new(contents).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the operations in {}: {}",
+                file_name,
+                errors.join(", ")
+            )));
+        }
+        documents.push((file_name.clone(), tree.document()));
+    }
+    Ok(documents)
+}
+
+/// The fragments defined across `documents`, by name.
+pub(super) fn fragment_definitions<'a>(
+    documents: impl Iterator<Item = &'a ast::Document>,
+) -> HashMap<String, ast::FragmentDefinition> {
+    documents
+        .flat_map(|document| document.definitions())
+        .filter_map(|definition| match definition {
+            ast::Definition::FragmentDefinition(fragment) => Some((
+                name(fragment.fragment_name().and_then(|name| name.name())),
+                fragment,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The fields of each object and interface type in a schema, and the named types they return.
+#[derive(Debug, Default)]
+pub(super) struct SchemaFields {
+    pub(super) types: BTreeMap<String, BTreeMap<String, String>>,
+    root_types: HashMap<&'static str, String>,
+    /// The coordinates of fields that return lists
+    list_fields: BTreeSet<String>,
+}
+
+impl SchemaFields {
+    pub(super) fn from_sdl(sdl: &str) -> RoverResult<Self> {
+        let tree = GraphQLParser::new(sdl).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the schema: {}",
+                errors.join(", ")
+            )));
+        }
+
+        let mut schema = SchemaFields::default();
+        for definition in tree.document().definitions() {
+            use ast::Definition::*;
+            match definition {
+                SchemaDefinition(d) => schema.root_types(d.root_operation_type_definitions()),
+                SchemaExtension(d) => schema.root_types(d.root_operation_type_definitions()),
+                ObjectTypeDefinition(d) => schema.fields(name(d.name()), d.fields_definition()),
+                ObjectTypeExtension(d) => schema.fields(name(d.name()), d.fields_definition()),
+                InterfaceTypeDefinition(d) => schema.fields(name(d.name()), d.fields_definition()),
+                InterfaceTypeExtension(d) => schema.fields(name(d.name()), d.fields_definition()),
+                _ => {}
+            }
+        }
+        Ok(schema)
+    }
+
+    fn root_types(&mut self, definitions: impl Iterator<Item = ast::RootOperationTypeDefinition>) {
+        for definition in definitions {
+            let operation = match definition.operation_type() {
+                Some(operation_type) => operation_type_name(&operation_type),
+                None => continue,
+            };
+            let type_name = name(definition.named_type().and_then(|ty| ty.name()));
+            self.root_types.insert(operation, type_name);
+        }
+    }
+
+    fn fields(&mut self, type_name: String, fields: Option<ast::FieldsDefinition>) {
+        let type_fields = self.types.entry(type_name.clone()).or_default();
+        for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+            let field_name = name(field.name());
+            if field.ty().map(is_list).unwrap_or(false) {
+                self.list_fields
+                    .insert(format!("{}.{}", type_name, field_name));
+            }
+            let return_type = field.ty().and_then(named_type).unwrap_or_default();
+            type_fields.insert(field_name, return_type);
+        }
+    }
+
+    /// Whether the field at `coordinate`, like `Query.products`, returns a list.
+    pub(super) fn is_list(&self, coordinate: &str) -> bool {
+        self.list_fields.contains(coordinate)
+    }
+
+    /// The type that operations of `operation_type` start selecting from, which is `Query` for
+    /// shorthand queries and operations without an explicit schema definition.
+    pub(super) fn root_type(&self, operation_type: Option<ast::OperationType>) -> String {
+        let operation = operation_type
+            .as_ref()
+            .map(operation_type_name)
+            .unwrap_or("query");
+        self.root_types.get(operation).cloned().unwrap_or_else(|| {
+            let mut default = operation.to_string();
+            default[..1].make_ascii_uppercase();
+            default
+        })
+    }
+
+    fn field_count(&self) -> usize {
+        self.types.values().map(BTreeMap::len).sum()
+    }
+
+    fn coordinates(&self) -> impl Iterator<Item = String> + '_ {
+        self.types.iter().flat_map(|(type_name, fields)| {
+            fields
+                .keys()
+                .map(move |field_name| format!("{}.{}", type_name, field_name))
+        })
+    }
+}
+
+struct SelectionWalker<'a> {
+    schema: &'a SchemaFields,
+    fragments: &'a HashMap<String, ast::FragmentDefinition>,
+    /// The fields the current operation selects
+    selected: BTreeSet<String>,
+    unknown: BTreeSet<String>,
+    /// The fragments being spread, so fragments that spread themselves don't loop forever
+    spread: Vec<String>,
+}
+
+impl SelectionWalker<'_> {
+    fn selection_set(&mut self, type_name: &str, selection_set: Option<ast::SelectionSet>) {
+        for selection in selection_set
+            .iter()
+            .flat_map(|selection_set| selection_set.selections())
+        {
+            match selection {
+                ast::Selection::Field(field) => {
+                    let field_name = name(field.name());
+                    // introspection fields like `__typename` aren't part of the schema
+                    if field_name.starts_with("__") {
+                        continue;
+                    }
+                    let coordinate = format!("{}.{}", type_name, field_name);
+                    match self
+                        .schema
+                        .types
+                        .get(type_name)
+                        .and_then(|fields| fields.get(&field_name))
+                    {
+                        Some(return_type) => {
+                            self.selected.insert(coordinate);
+                            self.selection_set(return_type, field.selection_set());
+                        }
+                        None => {
+                            self.unknown.insert(coordinate);
+                        }
+                    }
+                }
+                ast::Selection::InlineFragment(fragment) => {
+                    let fragment_type = fragment
+                        .type_condition()
+                        .and_then(|condition| condition.named_type())
+                        .map(|ty| name(ty.name()))
+                        .unwrap_or_else(|| type_name.to_string());
+                    self.selection_set(&fragment_type, fragment.selection_set());
+                }
+                ast::Selection::FragmentSpread(spread) => {
+                    let fragment_name = name(spread.fragment_name().and_then(|name| name.name()));
+                    if self.spread.contains(&fragment_name) {
+                        continue;
+                    }
+                    if let Some(fragment) = self.fragments.get(&fragment_name) {
+                        let fragment_type = name(
+                            fragment
+                                .type_condition()
+                                .and_then(|condition| condition.named_type())
+                                .and_then(|ty| ty.name()),
+                        );
+                        self.spread.push(fragment_name);
+                        self.selection_set(&fragment_type, fragment.selection_set());
+                        self.spread.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Finds the operation files at `path`, which is either a file or a directory to search.
+pub(super) fn operation_files(path: &Utf8Path) -> RoverResult<Vec<Utf8PathBuf>> {
+    if !Fs::path_is_dir(path)? {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in Fs::get_dir_entries(&dir)? {
+            let entry_path = entry?.into_path();
+            if entry_path.is_dir() {
+                dirs.push(entry_path);
+            } else if matches!(entry_path.extension(), Some("graphql" | "gql")) {
+                files.push(entry_path);
+            }
+        }
+    }
+    if files.is_empty() {
+        let mut err = RoverError::new(anyhow!("There are no operation files in {}", path));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "Operation files must end in `.graphql` or `.gql`.".to_string(),
+        ));
+        return Err(err);
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn operation_type_name(operation_type: &ast::OperationType) -> &'static str {
+    if operation_type.mutation_token().is_some() {
+        "mutation"
+    } else if operation_type.subscription_token().is_some() {
+        "subscription"
+    } else {
+        "query"
+    }
+}
+
+/// Whether a field's type is a list, possibly wrapped in a non-null type.
+fn is_list(ty: ast::Type) -> bool {
+    match ty {
+        ast::Type::NamedType(_) => false,
+        ast::Type::ListType(_) => true,
+        ast::Type::NonNullType(non_null) => non_null.list_type().is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unknown_fields, SchemaCoverage};
+
+    const SDL: &str = r#"
+schema {
+  query: RootQuery
+}
+
+type RootQuery {
+  products: [Product!]!
+  product(id: ID!): Product
+  node(id: ID!): Node
+}
+
+interface Node {
+  id: ID!
+}
+
+type Product implements Node {
+  id: ID!
+  name: String
+  reviews: [Review]
+}
+
+extend type Product {
+  price: Int
+}
+
+type Review {
+  body: String
+  author: String
+}
+"#;
+
+    #[test]
+    fn it_measures_schema_coverage() {
+        let operations = vec![
+            (
+                "products.graphql".to_string(),
+                r#"
+query Products {
+  products {
+    ...ProductFields
+    __typename
+  }
+}
+
+query Product {
+  product(id: "1") {
+    ...ProductFields
+    reviews {
+      body
+    }
+  }
+  node(id: "1") {
+    id
+    ... on Product {
+      price
+    }
+  }
+}
+"#
+                .to_string(),
+            ),
+            (
+                "fragments.graphql".to_string(),
+                "fragment ProductFields on Product { id name sku }".to_string(),
+            ),
+        ];
+        let coverage = SchemaCoverage::measure(SDL, &operations).unwrap();
+        assert_eq!(coverage.operation_count, 2);
+        assert_eq!(coverage.field_count, 10);
+        assert_eq!(coverage.used_field_count, 9);
+        assert_eq!(coverage.usages["Product.id"], 2);
+        assert_eq!(coverage.usages["RootQuery.products"], 1);
+        assert_eq!(coverage.usages["Node.id"], 1);
+        assert_eq!(coverage.unused_fields, vec!["Review.author".to_string()]);
+        assert_eq!(coverage.unknown_fields, vec!["Product.sku".to_string()]);
+    }
+
+    #[test]
+    fn it_finds_unknown_fields_by_operation() {
+        let operations = vec![
+            (
+                "Products".to_string(),
+                "query Products { products { ...ProductFields } } fragment ProductFields on Product { id sku }"
+                    .to_string(),
+            ),
+            (
+                "Product".to_string(),
+                r#"query Product { product(id: "1") { name } }"#.to_string(),
+            ),
+        ];
+        assert_eq!(
+            unknown_fields(SDL, &operations).unwrap(),
+            vec![("Products".to_string(), vec!["Product.sku".to_string()])]
+        );
+    }
+}
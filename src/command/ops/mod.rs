@@ -0,0 +1,31 @@
+pub(crate) mod cost;
+pub(crate) mod coverage;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Ops {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Report which fields of a schema a set of operations uses, and which it never uses
+    Coverage(coverage::Coverage),
+
+    /// Score how expensive operations are to execute, by their depth, breadth, and weighted fields
+    Cost(cost::Cost),
+}
+
+impl Ops {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Coverage(command) => command.run(),
+            Command::Cost(command) => command.run(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+use clap::Parser;
+use rover_client::operations::org::graphs::{self, OrgGraphsInput};
+use rover_std::Style;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Graphs {
+    /// Only list graphs whose ID contains this text (case-insensitive)
+    #[arg(long)]
+    graph_id: Option<String>,
+
+    /// Only list graphs whose title contains this text (case-insensitive)
+    #[arg(long)]
+    title: Option<String>,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl Graphs {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        eprintln!(
+            "Listing graphs using credentials from the {} profile.",
+            Style::Command.paint(&self.profile.profile_name)
+        );
+        let response = graphs::run(
+            OrgGraphsInput {
+                graph_id: self.graph_id.clone(),
+                title: self.title.clone(),
+            },
+            &client,
+        )?;
+        Ok(RoverOutput::OrgGraphs(response))
+    }
+}
@@ -0,0 +1,28 @@
+mod graphs;
+
+pub use graphs::Graphs;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+use clap::Parser;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Org {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// List the graphs and variants that your API key can access
+    Graphs(Graphs),
+}
+
+impl Org {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Graphs(command) => command.run(client_config),
+        }
+    }
+}
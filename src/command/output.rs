@@ -1,10 +1,22 @@
 use std::{
     collections::BTreeMap,
-    fmt::Write,
     io::{self, IsTerminal},
 };
 
-use crate::command::supergraph::compose::CompositionOutput;
+use crate::command::config::ProfileDetails;
+use crate::command::dev::DevSession;
+use crate::command::fleet::run::FleetReport;
+use crate::command::graph::explain::FieldExplanation;
+use crate::command::install::{AvailablePlugin, InstalledPlugin};
+use crate::command::ops::cost::OperationCost;
+use crate::command::ops::coverage::SchemaCoverage;
+use crate::command::schema::describe_change::ChangeSummary;
+use crate::command::schema::diff::SchemaDiff;
+use crate::command::schema::stats::SchemaStats;
+use crate::command::subgraph::fetch::SubgraphFetchReport;
+use crate::command::subgraph::impact::SubgraphImpact;
+use crate::command::supergraph::compose::{format_hints, hints_json, CompositionOutput};
+use crate::error::ErrorCodeMatch;
 use crate::options::JsonVersion;
 use crate::utils::table::{self, row};
 use crate::RoverError;
@@ -13,9 +25,12 @@ use crate::command::template::queries::list_templates_for_language::ListTemplate
 use crate::options::ProjectLanguage;
 use calm_io::{stderr, stderrln};
 use camino::Utf8PathBuf;
+use rover_client::operations::config::who_am_i::GraphMembership;
 use rover_client::operations::contract::describe::ContractDescribeResponse;
 use rover_client::operations::contract::publish::ContractPublishResponse;
+use rover_client::operations::graph::create::GraphCreateResponse;
 use rover_client::operations::graph::publish::GraphPublishResponse;
+use rover_client::operations::org::graphs::OrgGraphsResponse;
 use rover_client::operations::persisted_queries::publish::PersistedQueriesPublishResponse;
 use rover_client::operations::subgraph::delete::SubgraphDeleteResponse;
 use rover_client::operations::subgraph::list::SubgraphListResponse;
@@ -46,6 +61,7 @@ pub enum RoverOutput {
         key_type: String,
         origin: String,
         user_id: Option<String>,
+        graph_memberships: Vec<GraphMembership>,
     },
     ContractDescribe(ContractDescribeResponse),
     ContractPublish(ContractPublishResponse),
@@ -54,9 +70,11 @@ pub enum RoverOutput {
     SupergraphSchema(String),
     CompositionResult(CompositionOutput),
     SubgraphList(SubgraphListResponse),
+    OrgGraphs(OrgGraphsResponse),
     CheckWorkflowResponse(CheckWorkflowResponse),
     AsyncCheckResponse(CheckRequestSuccessResult),
     LintResponse(LintResponse),
+    GraphCreateResponse(GraphCreateResponse),
     GraphPublishResponse {
         graph_ref: GraphRef,
         publish_response: GraphPublishResponse,
@@ -78,8 +96,18 @@ pub enum RoverOutput {
         path: Utf8PathBuf,
     },
     Profiles(Vec<String>),
+    ProfileDetails(Vec<ProfileDetails>),
+    /// A default stored in a profile, printed by `rover config get`
+    ProfileSetting {
+        key: String,
+        value: String,
+    },
     Introspection(String),
     ErrorExplanation(String),
+    ErrorCodeSearchResults {
+        query: String,
+        matches: Vec<ErrorCodeMatch>,
+    },
     ReadmeFetchResponse {
         graph_ref: GraphRef,
         content: String,
@@ -95,6 +123,49 @@ pub enum RoverOutput {
         graph_id: String,
         jwt: String,
     },
+    SchemaPushResponse {
+        reference: String,
+        digest: String,
+    },
+    SchemaNormalizeResponse {
+        sdl: Option<String>,
+        changed_files: Vec<String>,
+    },
+    SchemaFilterResponse {
+        sdl: String,
+        removed: Vec<String>,
+    },
+    SchemaStats(SchemaStats),
+    SchemaDiff(SchemaDiff),
+    SchemaChangeDescription {
+        summary: ChangeSummary,
+        /// The summary, written in the style that was asked for
+        description: String,
+    },
+    SubgraphImpact(SubgraphImpact),
+    GraphExplain(FieldExplanation),
+    SchemaGraph {
+        diagram: String,
+    },
+    OperationCoverage(SchemaCoverage),
+    OperationCost(Vec<OperationCost>),
+    FleetReport(FleetReport),
+    SubgraphFetchReport(SubgraphFetchReport),
+    QueryPlan {
+        text: String,
+        plan: Value,
+    },
+    PluginList {
+        installed: Vec<InstalledPlugin>,
+        available: Vec<AvailablePlugin>,
+    },
+    DevSessionList(Vec<DevSession>),
+    GraphSnapshot {
+        path: Utf8PathBuf,
+        subgraphs: Vec<String>,
+    },
+    /// A shell completion script, or the cached values that one suggests
+    Completions(String),
     EmptySuccess,
 }
 
@@ -108,6 +179,7 @@ impl RoverOutput {
                 key_type,
                 origin,
                 user_id,
+                graph_memberships,
             } => {
                 let mut table = table::get_table();
 
@@ -128,7 +200,25 @@ impl RoverOutput {
                 table.add_row(row![Style::WhoAmIKey.paint("Origin"), origin]);
                 table.add_row(row![Style::WhoAmIKey.paint("API Key"), api_key]);
 
-                Some(format!("{}", table))
+                if graph_memberships.is_empty() {
+                    return Ok(Some(format!("{}", table)));
+                }
+                let mut memberships_table = table::get_table();
+                memberships_table.add_row(row![bc => "Organization", "Graph ID", "Title", "Role"]);
+                for membership in graph_memberships {
+                    memberships_table.add_row(row![
+                        membership.organization,
+                        membership.graph_id,
+                        membership.graph_title,
+                        membership.role.as_deref().unwrap_or("unknown")
+                    ]);
+                }
+                Some(format!(
+                    "{}\n{}\n{}",
+                    table,
+                    Style::Heading.paint("Graphs this key can access:"),
+                    memberships_table
+                ))
             }
             RoverOutput::ContractDescribe(describe_response) => Some(format!(
                 "{description}\nView the variant's full configuration at {variant_config}",
@@ -167,6 +257,17 @@ impl RoverOutput {
             RoverOutput::FetchResponse(fetch_response) => {
                 Some((fetch_response.sdl.contents).to_string())
             }
+            RoverOutput::GraphCreateResponse(create_response) => {
+                stderrln!(
+                    "{} created successfully. Store its API key somewhere safe; it won't be shown again.",
+                    create_response.graph_ref
+                )?;
+                // formatted as a .env file, so bootstrap scripts can use it directly
+                Some(format!(
+                    "APOLLO_GRAPH_REF={}\nAPOLLO_KEY={}",
+                    create_response.graph_ref, create_response.api_key
+                ))
+            }
             RoverOutput::GraphPublishResponse {
                 graph_ref,
                 publish_response,
@@ -272,18 +373,18 @@ impl RoverOutput {
             }
             RoverOutput::SupergraphSchema(csdl) => Some((csdl).to_string()),
             RoverOutput::CompositionResult(composition_output) => {
-                let warn_prefix = Style::HintPrefix.paint("HINT:");
-
-                let hints_string =
-                    composition_output
-                        .hints
-                        .iter()
-                        .fold(String::new(), |mut output, hint| {
-                            let _ = writeln!(output, "{} {}", warn_prefix, hint.message);
-                            output
-                        });
+                stderrln!("{}", format_hints(&composition_output.hints))?;
 
-                stderrln!("{}", hints_string)?;
+                if composition_output.is_prerelease() {
+                    stderrln!(
+                        "{} This supergraph was composed with a pre-release build of Federation ({}).",
+                        Style::WarningPrefix.paint("WARN:"),
+                        composition_output
+                            .federation_version
+                            .as_deref()
+                            .unwrap_or_default()
+                    )?;
+                }
 
                 Some((composition_output.supergraph_sdl).to_string())
             }
@@ -317,6 +418,23 @@ impl RoverOutput {
                     table, details.root_url, details.graph_ref.name
                 ))
             }
+            RoverOutput::OrgGraphs(details) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "Organization", "Graph ID", "Title", "Variants"]);
+
+                for graph in &details.graphs {
+                    let variants = graph
+                        .variants
+                        .iter()
+                        .map(|variant| variant.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    table.add_row(row![graph.organization, graph.id, graph.title, variants]);
+                }
+                Some(format!("{}", table))
+            }
             RoverOutput::TemplateList(templates) => {
                 let mut table = table::get_table();
 
@@ -361,6 +479,29 @@ impl RoverOutput {
                 }
                 Some(profiles.join("\n"))
             }
+            RoverOutput::ProfileDetails(profiles) => {
+                if profiles.is_empty() {
+                    stderrln!("No profiles found.")?;
+                    return Ok(None);
+                }
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(
+                    row![bc => "Name", "API Key", "Key Type", "Registry", "Overridden By"],
+                );
+                for profile in profiles {
+                    table.add_row(row![
+                        profile.name,
+                        profile.api_key.as_deref().unwrap_or("N/A"),
+                        profile.key_type.as_deref().unwrap_or("N/A"),
+                        profile.registry_url,
+                        profile.overridden_by.as_deref().unwrap_or("")
+                    ]);
+                }
+                Some(format!("{}", table))
+            }
+            RoverOutput::ProfileSetting { value, .. } => Some(value.clone()),
             RoverOutput::Introspection(introspection_response) => {
                 Some((introspection_response).to_string())
             }
@@ -371,6 +512,28 @@ impl RoverOutput {
 
                 Some(format!("{}", skin.inline(explanation)))
             }
+            RoverOutput::ErrorCodeSearchResults { query, matches } => {
+                let mut output = format!(
+                    "{}\n",
+                    Style::Heading.paint(format!(
+                        "{} error codes matched '{}':",
+                        matches.len(),
+                        query
+                    ))
+                );
+                for ErrorCodeMatch { code, summary } in matches {
+                    output.push_str(&format!(
+                        "\n{}  {}",
+                        Style::Command.paint(code.to_string()),
+                        summary
+                    ));
+                }
+                output.push_str(&format!(
+                    "\n\nRun {} to see a code's full explanation.",
+                    Style::Command.paint("rover explain <CODE>")
+                ));
+                Some(output)
+            }
             RoverOutput::ReadmeFetchResponse {
                 graph_ref: _,
                 content,
@@ -441,6 +604,238 @@ impl RoverOutput {
                 stderrln!("Success!")?;
                 Some(jwt.to_string())
             }
+            RoverOutput::SchemaPushResponse { digest, .. } => Some(digest.to_string()),
+            RoverOutput::SchemaNormalizeResponse { sdl, .. } => {
+                sdl.as_ref().map(|sdl| sdl.trim_end().to_string())
+            }
+            RoverOutput::SchemaFilterResponse { sdl, removed } => {
+                stderrln!(
+                    "Removed {} {} from the schema.",
+                    removed.len(),
+                    match removed.len() {
+                        1 => "type, field, or value",
+                        _ => "types, fields, and values",
+                    }
+                )?;
+                Some(sdl.trim_end().to_string())
+            }
+            RoverOutput::OperationCoverage(coverage) => {
+                let mut result = format!(
+                    "{} of {} fields ({:.1}%) are used by {} {}.",
+                    coverage.used_field_count,
+                    coverage.field_count,
+                    coverage.percentage(),
+                    coverage.operation_count,
+                    match coverage.operation_count {
+                        1 => "operation",
+                        _ => "operations",
+                    }
+                );
+                if !coverage.unused_fields.is_empty() {
+                    result.push_str(&format!(
+                        "\n\n{}\n{}",
+                        Style::Heading.paint("Unused fields:"),
+                        coverage.unused_fields.join("\n")
+                    ));
+                }
+                if !coverage.unknown_fields.is_empty() {
+                    result.push_str(&format!(
+                        "\n\n{}\n{}",
+                        Style::Heading
+                            .paint("Fields selected by operations that aren't in the schema:"),
+                        coverage.unknown_fields.join("\n")
+                    ));
+                }
+                Some(result)
+            }
+            RoverOutput::OperationCost(costs) => {
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "Operation", "File", "Cost", "Depth", "Breadth"]);
+                for cost in costs {
+                    table.add_row(row![
+                        cost.operation,
+                        cost.file,
+                        cost.cost,
+                        cost.depth,
+                        cost.breadth
+                    ]);
+                }
+                Some(format!("{}", table))
+            }
+            RoverOutput::FleetReport(report) => Some(report.get_table()),
+            RoverOutput::SubgraphFetchReport(report) => Some(report.get_table()),
+            RoverOutput::QueryPlan { text, .. } => Some(text.to_string()),
+            RoverOutput::SchemaDiff(diff) => Some(diff.get_output()),
+            RoverOutput::SchemaChangeDescription { description, .. } => Some(description.clone()),
+            RoverOutput::SubgraphImpact(impact) => {
+                let changes = match impact.changes.len() {
+                    1 => "1 change".to_string(),
+                    count => format!("{} changes", count),
+                };
+                if impact.changes.is_empty() {
+                    Some(format!(
+                        "The proposed schema doesn't change the {} subgraph.",
+                        impact.subgraph
+                    ))
+                } else if impact.dependents.is_empty() {
+                    Some(format!(
+                        "No other subgraphs depend on the {} to the {} subgraph.",
+                        changes, impact.subgraph
+                    ))
+                } else {
+                    let dependent_subgraphs = impact.dependent_subgraphs();
+                    let mut table = table::get_table();
+
+                    // bc => sets top row to be bold and center
+                    table.add_row(row![bc => "Subgraph", "Type or Field", "Through", "Changed"]);
+                    for dependent in &impact.dependents {
+                        table.add_row(row![
+                            dependent.subgraph,
+                            dependent.coordinate,
+                            dependent.relationship,
+                            dependent.changed
+                        ]);
+                    }
+                    Some(format!(
+                        "{} in {} other {} depend on the {} to the {} subgraph:\n\n{}",
+                        match impact.dependents.len() {
+                            1 => "1 type or field".to_string(),
+                            count => format!("{} types and fields", count),
+                        },
+                        dependent_subgraphs.len(),
+                        match dependent_subgraphs.len() {
+                            1 => "subgraph",
+                            _ => "subgraphs",
+                        },
+                        changes,
+                        impact.subgraph,
+                        table
+                    ))
+                }
+            }
+            RoverOutput::GraphExplain(explanation) => Some(explanation.get_output()),
+            RoverOutput::SchemaStats(stats) => {
+                let mut types_table = table::get_table();
+                types_table.add_row(row![bc => "Kind", "Count"]);
+                types_table.add_row(row!["Object", stats.types.object]);
+                types_table.add_row(row!["Interface", stats.types.interface]);
+                types_table.add_row(row!["Union", stats.types.union]);
+                types_table.add_row(row!["Enum", stats.types.r#enum]);
+                types_table.add_row(row!["Input", stats.types.input]);
+                types_table.add_row(row!["Scalar", stats.types.scalar]);
+                types_table.add_row(row!["Total", stats.types.total]);
+
+                let mut members_table = table::get_table();
+                members_table.add_row(row![bc => "Member", "Count", "Deprecated"]);
+                members_table.add_row(row!["Fields", stats.fields, stats.deprecated.fields]);
+                members_table.add_row(row![
+                    "Arguments",
+                    stats.arguments,
+                    stats.deprecated.arguments
+                ]);
+                members_table.add_row(row![
+                    "Input fields",
+                    stats.input_fields,
+                    stats.deprecated.input_fields
+                ]);
+                members_table.add_row(row![
+                    "Enum values",
+                    stats.enum_values,
+                    stats.deprecated.enum_values
+                ]);
+
+                let mut directives_table = table::get_table();
+                directives_table.add_row(row![bc => "Directive", "Usages"]);
+                for (directive, usages) in &stats.directive_usages {
+                    directives_table.add_row(row![format!("@{}", directive), usages]);
+                }
+
+                let mut result = format!("Types:\n{}\nFields:\n{}\n", types_table, members_table);
+                if !stats.directive_usages.is_empty() {
+                    result.push_str(&format!("Directives:\n{}\n", directives_table));
+                }
+                result.push_str(&format!("Entities: {}", stats.entity_count));
+                if !stats.entities.is_empty() {
+                    result.push_str(&format!(" ({})", stats.entities.join(", ")));
+                }
+                Some(result)
+            }
+            RoverOutput::SchemaGraph { diagram } => Some(diagram.to_string()),
+            RoverOutput::PluginList {
+                installed,
+                available,
+            } => {
+                let mut installed_table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                installed_table.add_row(row![bc => "Plugin", "Version", "Size", "Path"]);
+                for plugin in installed {
+                    installed_table.add_row(row![
+                        plugin.name,
+                        plugin.version,
+                        format_file_size(plugin.size),
+                        plugin.path
+                    ]);
+                }
+
+                let mut available_table = table::get_table();
+                available_table
+                    .add_row(row![bc => "Plugin", "Channel", "Latest Version", "Installed"]);
+                for plugin in available {
+                    let (latest_version, installed) = match &plugin.latest_version {
+                        Some(latest_version) => (
+                            latest_version.as_str(),
+                            if plugin.installed { "yes" } else { "no" },
+                        ),
+                        None => ("unknown", "-"),
+                    };
+                    available_table.add_row(row![
+                        plugin.name,
+                        plugin.channel,
+                        latest_version,
+                        installed
+                    ]);
+                }
+
+                Some(format!(
+                    "Installed plugins:\n{}\nAvailable plugins:\n{}",
+                    installed_table, available_table
+                ))
+            }
+            RoverOutput::DevSessionList(sessions) => {
+                if sessions.is_empty() {
+                    stderrln!("No `rover dev` sessions are running on this machine.")?;
+                    return Ok(None);
+                }
+                let mut table = table::get_table();
+
+                // bc => sets top row to be bold and center
+                table.add_row(row![bc => "Router Address", "PID", "Subgraphs"]);
+                for session in sessions {
+                    let subgraphs = match &session.subgraphs {
+                        Some(subgraphs) if subgraphs.is_empty() => "none".to_string(),
+                        Some(subgraphs) => subgraphs
+                            .iter()
+                            .map(|(name, url)| format!("{} ({})", name, url))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        None => "not responding".to_string(),
+                    };
+                    table.add_row(row![session.router_address, session.pid, subgraphs]);
+                }
+                Some(format!("{}", table))
+            }
+            RoverOutput::GraphSnapshot { path, subgraphs } => {
+                stderrln!(
+                    "Saved {} subgraph schemas to {}",
+                    subgraphs.len(),
+                    Style::Path.paint(path.as_str())
+                )?;
+                Some(path.to_string())
+            }
+            RoverOutput::Completions(completions) => Some(completions.clone()),
             RoverOutput::EmptySuccess => None,
         })
     }
@@ -454,6 +849,7 @@ impl RoverOutput {
                 graph_title,
                 graph_id,
                 user_id,
+                graph_memberships,
             } => {
                 json!({
                   "key_type": key_type,
@@ -462,6 +858,7 @@ impl RoverOutput {
                   "user_id": user_id,
                   "origin": origin,
                   "api_key": api_key,
+                  "graph_memberships": graph_memberships,
                 })
             }
             RoverOutput::ContractDescribe(describe_response) => json!(describe_response),
@@ -481,16 +878,21 @@ impl RoverOutput {
                 if let Some(federation_version) = &composition_output.federation_version {
                     json!({
                       "core_schema": composition_output.supergraph_sdl,
-                      "hints": composition_output.hints,
-                      "federation_version": federation_version
+                      "hints": hints_json(&composition_output.hints),
+                      "federation_version": federation_version,
+                      "prerelease": composition_output.is_prerelease()
                     })
                 } else {
                     json!({
                         "core_schema": composition_output.supergraph_sdl,
-                        "hints": composition_output.hints
+                        "hints": hints_json(&composition_output.hints)
                     })
                 }
             }
+            RoverOutput::GraphCreateResponse(create_response) => json!({
+                "graph_ref": create_response.graph_ref.to_string(),
+                "api_key": create_response.api_key,
+            }),
             RoverOutput::GraphPublishResponse {
                 graph_ref: _,
                 publish_response,
@@ -509,6 +911,7 @@ impl RoverOutput {
                 json!(delete_response)
             }
             RoverOutput::SubgraphList(list_response) => json!(list_response),
+            RoverOutput::OrgGraphs(graphs_response) => json!(graphs_response),
             RoverOutput::TemplateList(templates) => json!({ "templates": templates }),
             RoverOutput::TemplateUseSuccess { template_id, path } => {
                 json!({ "template_id": template_id, "path": path })
@@ -517,12 +920,17 @@ impl RoverOutput {
             RoverOutput::AsyncCheckResponse(check_response) => check_response.get_json(),
             RoverOutput::LintResponse(lint_response) => lint_response.get_json(),
             RoverOutput::Profiles(profiles) => json!({ "profiles": profiles }),
+            RoverOutput::ProfileDetails(profiles) => json!({ "profiles": profiles }),
+            RoverOutput::ProfileSetting { key, value } => json!({ "key": key, "value": value }),
             RoverOutput::Introspection(introspection_response) => {
                 json!({ "introspection_response": introspection_response })
             }
             RoverOutput::ErrorExplanation(explanation_markdown) => {
                 json!({ "explanation_markdown": explanation_markdown })
             }
+            RoverOutput::ErrorCodeSearchResults { query, matches } => {
+                json!({ "query": query, "matches": matches })
+            }
             RoverOutput::ReadmeFetchResponse {
                 graph_ref: _,
                 content,
@@ -537,6 +945,7 @@ impl RoverOutput {
             } => {
                 json!({ "readme": new_content, "last_updated_time": last_updated_time })
             }
+            RoverOutput::Completions(completions) => json!({ "completions": completions }),
             RoverOutput::EmptySuccess => json!(null),
             RoverOutput::PersistedQueriesPublishResponse(response) => {
                 json!({
@@ -560,6 +969,47 @@ impl RoverOutput {
             RoverOutput::LicenseResponse { jwt, .. } => {
                 json!({"jwt": jwt })
             }
+            RoverOutput::SchemaPushResponse { reference, digest } => {
+                json!({ "reference": reference, "digest": digest })
+            }
+            RoverOutput::SchemaNormalizeResponse { sdl, changed_files } => {
+                json!({ "sdl": sdl, "changed_files": changed_files })
+            }
+            RoverOutput::SchemaFilterResponse { sdl, removed } => {
+                json!({ "sdl": sdl, "removed": removed })
+            }
+            RoverOutput::SchemaStats(stats) => json!(stats),
+            RoverOutput::SchemaDiff(diff) => json!(diff),
+            RoverOutput::SchemaChangeDescription {
+                summary,
+                description,
+            } => json!({
+                "breaking_risks": summary.breaking_risks,
+                "new_capabilities": summary.new_capabilities,
+                "deprecations": summary.deprecations,
+                "other_changes": summary.other_changes,
+                "description": description
+            }),
+            RoverOutput::SubgraphImpact(impact) => json!(impact),
+            RoverOutput::GraphExplain(explanation) => json!(explanation),
+            RoverOutput::SchemaGraph { diagram } => json!({ "diagram": diagram }),
+            RoverOutput::OperationCoverage(coverage) => json!(coverage),
+            RoverOutput::OperationCost(costs) => json!({ "operations": costs }),
+            RoverOutput::FleetReport(report) => json!(report),
+            RoverOutput::SubgraphFetchReport(report) => json!(report),
+            RoverOutput::QueryPlan { text, plan } => {
+                json!({ "query_plan": plan, "query_plan_text": text })
+            }
+            RoverOutput::PluginList {
+                installed,
+                available,
+            } => {
+                json!({ "installed": installed, "available": available })
+            }
+            RoverOutput::DevSessionList(sessions) => json!({ "sessions": sessions }),
+            RoverOutput::GraphSnapshot { path, subgraphs } => {
+                json!({ "path": path, "subgraphs": subgraphs })
+            }
         }
     }
 
@@ -637,15 +1087,34 @@ impl RoverOutput {
             }
             RoverOutput::TemplateUseSuccess { .. } => Some("Project generated"),
             RoverOutput::AsyncCheckResponse(_) => Some("Check Started"),
-            RoverOutput::Profiles(_) => Some("Profiles"),
+            RoverOutput::Profiles(_) | RoverOutput::ProfileDetails(_) => Some("Profiles"),
             RoverOutput::Introspection(_) => Some("Introspection Response"),
             RoverOutput::ReadmeFetchResponse { .. } => Some("Readme"),
             RoverOutput::GraphPublishResponse { .. } => Some("Schema Hash"),
+            RoverOutput::SchemaPushResponse { .. } => Some("Manifest Digest"),
+            RoverOutput::SchemaNormalizeResponse { sdl: Some(_), .. } => Some("Normalized Schema"),
+            RoverOutput::QueryPlan { .. } => Some("Query Plan"),
             _ => None,
         }
     }
 }
 
+/// Formats a number of bytes for humans, e.g. `45.3 MB`
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -654,7 +1123,11 @@ mod tests {
     use chrono::{DateTime, Local, Utc};
     use rover_client::{
         operations::{
-            graph::publish::{ChangeSummary, FieldChanges, TypeChanges},
+            graph::{
+                field_usage::ClientFieldUsage,
+                publish::{ChangeSummary, FieldChanges, TypeChanges},
+            },
+            org::graphs::{OrgGraph, OrgVariant},
             persisted_queries::publish::PersistedQueriesOperationCounts,
             subgraph::{
                 delete::SubgraphDeleteResponse,
@@ -672,7 +1145,13 @@ mod tests {
 
     use anyhow::anyhow;
 
+    use crate::command::fleet::run::FleetTargetReport;
+    use crate::command::graph::explain::FieldReference;
+    use crate::command::schema::diff::{ChangeCode, SchemaChange as SchemaDiffChange, Severity};
+    use crate::command::subgraph::fetch::{SubgraphFetchResult, SubgraphFetchStatus};
+    use crate::command::subgraph::impact::Dependent;
     use crate::options::JsonOutput;
+    use crate::RoverErrorCode;
 
     use super::*;
 
@@ -803,6 +1282,67 @@ mod tests {
         assert_json_eq!(expected_json, actual_json);
     }
 
+    #[test]
+    fn graph_create_json() {
+        let mock_create_response = GraphCreateResponse {
+            graph_ref: GraphRef {
+                name: "my-graph".to_string(),
+                variant: "current".to_string(),
+            },
+            api_key: "service:my-graph:abc123".to_string(),
+        };
+        let actual_json: JsonOutput = RoverOutput::GraphCreateResponse(mock_create_response).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "graph_ref": "my-graph@current",
+                "api_key": "service:my-graph:abc123",
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
+    #[test]
+    fn org_graphs_json() {
+        let mock_org_graphs_response = OrgGraphsResponse {
+            graphs: vec![OrgGraph {
+                organization: "Acme".to_string(),
+                id: "acme-supergraph".to_string(),
+                title: "Acme Supergraph".to_string(),
+                variants: vec![OrgVariant {
+                    name: "current".to_string(),
+                    graph_ref: "acme-supergraph@current".to_string(),
+                }],
+            }],
+        };
+        let actual_json: JsonOutput = RoverOutput::OrgGraphs(mock_org_graphs_response).into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "graphs": [
+                    {
+                        "organization": "Acme",
+                        "id": "acme-supergraph",
+                        "title": "Acme Supergraph",
+                        "variants": [
+                            {
+                                "name": "current",
+                                "graph_ref": "acme-supergraph@current"
+                            }
+                        ]
+                    }
+                ],
+                "success": true
+          },
+          "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
     #[test]
     fn subgraph_delete_success_json() {
         let mock_subgraph_delete = SubgraphDeleteResponse {
@@ -1263,6 +1803,7 @@ mod tests {
             launch_cli_copy: Some(
                 "You can monitor this launch in Apollo Studio: test.com/launchurl".to_string(),
             ),
+            launch_id: Some("launch-1".to_string()),
         };
         let actual_json: JsonOutput = RoverOutput::SubgraphPublishResponse {
             graph_ref: GraphRef {
@@ -1315,6 +1856,7 @@ mod tests {
             subgraph_was_updated: true,
             launch_url: None,
             launch_cli_copy: None,
+            launch_id: None,
         };
         let actual_json: JsonOutput = RoverOutput::SubgraphPublishResponse {
             graph_ref: GraphRef {
@@ -1373,6 +1915,7 @@ mod tests {
             subgraph_was_updated: false,
             launch_url: None,
             launch_cli_copy: None,
+            launch_id: None,
         };
         let actual_json: JsonOutput = RoverOutput::SubgraphPublishResponse {
             graph_ref: GraphRef {
@@ -1458,6 +2001,34 @@ mod tests {
         assert_json_eq!(expected_json, actual_json);
     }
 
+    #[test]
+    fn error_code_search_results_json() {
+        let actual_json: JsonOutput = RoverOutput::ErrorCodeSearchResults {
+            query: "compose".to_string(),
+            matches: vec![ErrorCodeMatch {
+                code: RoverErrorCode::E027,
+                summary: "This error occurs when working with a federated graph.".to_string(),
+            }],
+        }
+        .into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "query": "compose",
+                "matches": [
+                    {
+                        "code": "E027",
+                        "summary": "This error occurs when working with a federated graph."
+                    }
+                ],
+                "success": true
+            },
+            "error": null
+        });
+        assert_json_eq!(expected_json, actual_json);
+    }
+
     #[test]
     fn empty_success_json() {
         let actual_json: JsonOutput = RoverOutput::EmptySuccess.into();
@@ -1743,4 +2314,685 @@ mod tests {
 
         assert_json_eq!(actual_json, expected_json);
     }
+
+    #[test]
+    fn test_schema_push_response_json() {
+        let push_response = RoverOutput::SchemaPushResponse {
+            reference: "oci://ghcr.io/my-org/accounts-schema:1.2.3".to_string(),
+            digest: "sha256:abc".to_string(),
+        };
+
+        let actual_json: JsonOutput = push_response.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "reference": "oci://ghcr.io/my-org/accounts-schema:1.2.3",
+                "digest": "sha256:abc",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_schema_normalize_response_json() {
+        let normalize_response = RoverOutput::SchemaNormalizeResponse {
+            sdl: None,
+            changed_files: vec!["products.graphql".to_string()],
+        };
+
+        let actual_json: JsonOutput = normalize_response.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "sdl": null,
+                "changed_files": ["products.graphql"],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_schema_filter_response_json() {
+        let filter_response = RoverOutput::SchemaFilterResponse {
+            sdl: "type Query {\n  products: [String] @tag(name: \"public\")\n}\n".to_string(),
+            removed: vec!["Query.audit".to_string()],
+        };
+
+        let actual_json: JsonOutput = filter_response.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "sdl": "type Query {\n  products: [String] @tag(name: \"public\")\n}\n",
+                "removed": ["Query.audit"],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_query_plan_json() {
+        let query_plan = RoverOutput::QueryPlan {
+            text: "QueryPlan {\n  Fetch(service: \"products\") {\n    {\n      topProducts {\n        name\n      }\n    }\n  },\n}".to_string(),
+            plan: json!({ "kind": "Fetch", "serviceName": "products" }),
+        };
+
+        let actual_json: JsonOutput = query_plan.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "query_plan": { "kind": "Fetch", "serviceName": "products" },
+                "query_plan_text": "QueryPlan {\n  Fetch(service: \"products\") {\n    {\n      topProducts {\n        name\n      }\n    }\n  },\n}",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_schema_diff_json() {
+        let schema_diff = RoverOutput::SchemaDiff(SchemaDiff {
+            changes: vec![SchemaDiffChange {
+                code: ChangeCode::FieldRemoved,
+                severity: Severity::Breaking,
+                coordinate: "Product.price".to_string(),
+                description: "`Product.price` was removed".to_string(),
+            }],
+        });
+
+        let actual_json: JsonOutput = schema_diff.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "changes": [
+                    {
+                        "code": "FIELD_REMOVED",
+                        "severity": "breaking",
+                        "coordinate": "Product.price",
+                        "description": "`Product.price` was removed"
+                    }
+                ],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_schema_change_description_json() {
+        let deprecation = SchemaDiffChange {
+            code: ChangeCode::FieldDeprecated,
+            severity: Severity::Notable,
+            coordinate: "Product.name".to_string(),
+            description: "`Product.name` was deprecated".to_string(),
+        };
+        let description = RoverOutput::SchemaChangeDescription {
+            summary: super::ChangeSummary {
+                deprecations: vec![deprecation],
+                ..Default::default()
+            },
+            description: "This change deprecates 1 field.".to_string(),
+        };
+
+        let actual_json: JsonOutput = description.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "breaking_risks": [],
+                "new_capabilities": [],
+                "deprecations": [
+                    {
+                        "code": "FIELD_DEPRECATED",
+                        "severity": "notable",
+                        "coordinate": "Product.name",
+                        "description": "`Product.name` was deprecated"
+                    }
+                ],
+                "other_changes": [],
+                "description": "This change deprecates 1 field.",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_subgraph_impact_json() {
+        let subgraph_impact = RoverOutput::SubgraphImpact(SubgraphImpact {
+            subgraph: "products".to_string(),
+            changes: vec![SchemaDiffChange {
+                code: ChangeCode::FieldChangedType,
+                severity: Severity::Breaking,
+                coordinate: "Product.weight".to_string(),
+                description: "`Product.weight` changed type from `Float` to `Int`".to_string(),
+            }],
+            dependents: vec![Dependent {
+                subgraph: "inventory".to_string(),
+                coordinate: "Product.shippingEstimate".to_string(),
+                relationship: "@requires".to_string(),
+                changed: "Product.weight".to_string(),
+            }],
+        });
+
+        let actual_json: JsonOutput = subgraph_impact.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "subgraph": "products",
+                "changes": [
+                    {
+                        "code": "FIELD_CHANGED_TYPE",
+                        "severity": "breaking",
+                        "coordinate": "Product.weight",
+                        "description": "`Product.weight` changed type from `Float` to `Int`"
+                    }
+                ],
+                "dependents": [
+                    {
+                        "subgraph": "inventory",
+                        "coordinate": "Product.shippingEstimate",
+                        "relationship": "@requires",
+                        "changed": "Product.weight"
+                    }
+                ],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_schema_graph_json() {
+        let schema_graph = RoverOutput::SchemaGraph {
+            diagram: "classDiagram\n  class Product".to_string(),
+        };
+
+        let actual_json: JsonOutput = schema_graph.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "diagram": "classDiagram\n  class Product",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_plugin_list_json() {
+        let list_response = RoverOutput::PluginList {
+            installed: vec![InstalledPlugin {
+                name: "router".to_string(),
+                version: "v1.33.0".to_string(),
+                path: "/home/user/.rover/bin/router-v1.33.0".into(),
+                size: 45_300_000,
+            }],
+            available: vec![
+                AvailablePlugin {
+                    name: "router".to_string(),
+                    channel: "latest".to_string(),
+                    latest_version: Some("v1.33.0".to_string()),
+                    installed: true,
+                },
+                AvailablePlugin {
+                    name: "supergraph".to_string(),
+                    channel: "latest-2".to_string(),
+                    latest_version: None,
+                    installed: false,
+                },
+            ],
+        };
+
+        let actual_json: JsonOutput = list_response.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "installed": [{
+                    "name": "router",
+                    "version": "v1.33.0",
+                    "path": "/home/user/.rover/bin/router-v1.33.0",
+                    "size": 45300000
+                }],
+                "available": [{
+                    "name": "router",
+                    "channel": "latest",
+                    "latest_version": "v1.33.0",
+                    "installed": true
+                }, {
+                    "name": "supergraph",
+                    "channel": "latest-2",
+                    "latest_version": null,
+                    "installed": false
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_dev_session_list_json() {
+        let list_response = RoverOutput::DevSessionList(vec![
+            DevSession {
+                router_address: "127.0.0.1:4000".parse().unwrap(),
+                pid: 1234,
+                subgraphs: Some(
+                    [("products".to_string(), "http://localhost:4001/".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+            },
+            DevSession {
+                router_address: "127.0.0.1:4100".parse().unwrap(),
+                pid: 5678,
+                subgraphs: None,
+            },
+        ]);
+
+        let actual_json: JsonOutput = list_response.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "sessions": [{
+                    "router_address": "127.0.0.1:4000",
+                    "pid": 1234,
+                    "subgraphs": { "products": "http://localhost:4001/" }
+                }, {
+                    "router_address": "127.0.0.1:4100",
+                    "pid": 5678,
+                    "subgraphs": null
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_operation_cost_json() {
+        let costs = RoverOutput::OperationCost(vec![OperationCost {
+            operation: "Products".to_string(),
+            file: "operations/products.graphql".to_string(),
+            cost: 120,
+            depth: 3,
+            breadth: 4,
+        }]);
+
+        let actual_json: JsonOutput = costs.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "operations": [{
+                    "operation": "Products",
+                    "file": "operations/products.graphql",
+                    "cost": 120,
+                    "depth": 3,
+                    "breadth": 4
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_subgraph_fetch_report_json() {
+        let report = RoverOutput::SubgraphFetchReport(SubgraphFetchReport {
+            graph_ref: "my-graph@current".to_string(),
+            subgraphs: vec![
+                SubgraphFetchResult {
+                    name: "products".to_string(),
+                    status: SubgraphFetchStatus::Fetched,
+                    path: Some(Utf8PathBuf::from("subgraphs/products.graphql")),
+                    error: None,
+                },
+                SubgraphFetchResult {
+                    name: "reviews".to_string(),
+                    status: SubgraphFetchStatus::Skipped,
+                    path: Some(Utf8PathBuf::from("subgraphs/reviews.graphql")),
+                    error: None,
+                },
+            ],
+            supergraph_config: Some(Utf8PathBuf::from("subgraphs/supergraph.yaml")),
+        });
+
+        let actual_json: JsonOutput = report.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "graph_ref": "my-graph@current",
+                "subgraphs": [
+                    {
+                        "name": "products",
+                        "status": "fetched",
+                        "path": "subgraphs/products.graphql",
+                        "error": null
+                    },
+                    {
+                        "name": "reviews",
+                        "status": "skipped",
+                        "path": "subgraphs/reviews.graphql",
+                        "error": null
+                    }
+                ],
+                "supergraph_config": "subgraphs/supergraph.yaml",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_fleet_report_json() {
+        let report = RoverOutput::FleetReport(FleetReport {
+            command: vec![
+                "supergraph".to_string(),
+                "fetch".to_string(),
+                "{graph_ref}".to_string(),
+            ],
+            targets: vec![FleetTargetReport {
+                graph_ref: "payments@prod".to_string(),
+                profile: Some("payments".to_string()),
+                success: true,
+                data: json!({ "sdl": { "contents": "type Query { a: Int }" }, "success": true }),
+                error: Value::Null,
+                duration_ms: 1200,
+            }],
+        });
+
+        let actual_json: JsonOutput = report.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "command": ["supergraph", "fetch", "{graph_ref}"],
+                "targets": [{
+                    "graph_ref": "payments@prod",
+                    "profile": "payments",
+                    "success": true,
+                    "data": { "sdl": { "contents": "type Query { a: Int }" }, "success": true },
+                    "error": null,
+                    "duration_ms": 1200
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_graph_snapshot_json() {
+        let snapshot = RoverOutput::GraphSnapshot {
+            path: "snapshots/my-graph@prod-20240102T030405Z.tar.gz".into(),
+            subgraphs: vec!["products".to_string(), "reviews".to_string()],
+        };
+
+        let actual_json: JsonOutput = snapshot.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "path": "snapshots/my-graph@prod-20240102T030405Z.tar.gz",
+                "subgraphs": ["products", "reviews"],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_completions_json() {
+        let completions = RoverOutput::Completions("shop@prod\nshop@staging".to_string());
+
+        let actual_json: JsonOutput = completions.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "completions": "shop@prod\nshop@staging",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_format_file_size() {
+        assert_eq!(format_file_size(512), "512 B");
+        assert_eq!(format_file_size(45_300_000), "45.3 MB");
+        assert_eq!(format_file_size(1_200_000_000), "1.2 GB");
+    }
+
+    #[test]
+    fn test_prerelease_composition_result_json() {
+        let composition_result = RoverOutput::CompositionResult(CompositionOutput {
+            supergraph_sdl: "type Query { hello: String }".to_string(),
+            hints: vec![],
+            federation_version: Some("v2.7.0-beta.1".to_string()),
+        });
+
+        let actual_json: JsonOutput = composition_result.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "core_schema": "type Query { hello: String }",
+                "hints": [],
+                "federation_version": "v2.7.0-beta.1",
+                "prerelease": true,
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_whoami_with_graph_memberships_json() {
+        let whoami = RoverOutput::ConfigWhoAmIOutput {
+            api_key: "user:**************************************".to_string(),
+            graph_id: None,
+            graph_title: None,
+            key_type: "USER".to_string(),
+            origin: "--profile default".to_string(),
+            user_id: Some("gh.nobodydefinitelyhasthisusernamelol".to_string()),
+            graph_memberships: vec![GraphMembership {
+                organization: "Acme".to_string(),
+                graph_id: "acme-supergraph".to_string(),
+                graph_title: "Acme Supergraph".to_string(),
+                role: Some("GRAPH_ADMIN".to_string()),
+            }],
+        };
+
+        let actual_json: JsonOutput = whoami.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "key_type": "USER",
+                "graph_id": null,
+                "graph_title": null,
+                "user_id": "gh.nobodydefinitelyhasthisusernamelol",
+                "origin": "--profile default",
+                "api_key": "user:**************************************",
+                "graph_memberships": [{
+                    "organization": "Acme",
+                    "graph_id": "acme-supergraph",
+                    "graph_title": "Acme Supergraph",
+                    "role": "GRAPH_ADMIN"
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_profile_details_json() {
+        let profile_details = RoverOutput::ProfileDetails(vec![ProfileDetails {
+            name: "default".to_string(),
+            api_key: Some("user**************************LOLO".to_string()),
+            key_type: Some("personal".to_string()),
+            registry_url: "https://api.apollographql.com/graphql".to_string(),
+            registry_selected_by: "Rover's default registry endpoint".to_string(),
+            overridden_by: None,
+        }]);
+
+        let actual_json: JsonOutput = profile_details.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "profiles": [{
+                    "name": "default",
+                    "api_key": "user**************************LOLO",
+                    "key_type": "personal",
+                    "registry_url": "https://api.apollographql.com/graphql",
+                    "registry_selected_by": "Rover's default registry endpoint",
+                    "overridden_by": null
+                }],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_profile_setting_json() {
+        let profile_setting = RoverOutput::ProfileSetting {
+            key: "graph-ref".to_string(),
+            value: "my-graph@prod".to_string(),
+        };
+
+        let actual_json: JsonOutput = profile_setting.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "key": "graph-ref",
+                "value": "my-graph@prod",
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
+
+    #[test]
+    fn test_composition_result_hints_json() {
+        let composition_result = RoverOutput::CompositionResult(CompositionOutput {
+            supergraph_sdl: "type Query { hello: String }".to_string(),
+            hints: serde_json::from_value(json!([
+                { "message": "descriptions differ", "code": "INCONSISTENT_DESCRIPTION" }
+            ]))
+            .unwrap(),
+            federation_version: None,
+        });
+
+        let actual_json: JsonOutput = composition_result.into();
+        let hints = &json!(actual_json)["data"]["hints"];
+        assert_eq!(hints[0]["message"], json!("descriptions differ"));
+        assert_eq!(hints[0]["code"], json!("INCONSISTENT_DESCRIPTION"));
+        assert_eq!(hints[0]["level"], json!("warn"));
+    }
+
+    #[test]
+    fn test_graph_explain_json() {
+        let graph_explain = RoverOutput::GraphExplain(FieldExplanation {
+            graph_ref: "shop@prod".to_string(),
+            coordinate: "Product.price".to_string(),
+            defined_in: vec!["products".to_string()],
+            references: vec![FieldReference {
+                subgraph: "inventory".to_string(),
+                coordinate: "Product.shippingEstimate".to_string(),
+                directive: "@requires".to_string(),
+            }],
+            days: 7,
+            clients: vec![ClientFieldUsage {
+                client_name: Some("web".to_string()),
+                client_version: Some("1.2.0".to_string()),
+                estimated_execution_count: 100,
+            }],
+        });
+
+        let actual_json: JsonOutput = graph_explain.into();
+        let expected_json = json!(
+        {
+            "json_version": "1",
+            "data": {
+                "graph_ref": "shop@prod",
+                "coordinate": "Product.price",
+                "defined_in": ["products"],
+                "references": [
+                    {
+                        "subgraph": "inventory",
+                        "coordinate": "Product.shippingEstimate",
+                        "directive": "@requires"
+                    }
+                ],
+                "days": 7,
+                "clients": [
+                    {
+                        "client_name": "web",
+                        "client_version": "1.2.0",
+                        "estimated_execution_count": 100
+                    }
+                ],
+                "success": true
+            },
+            "error": null
+        });
+
+        assert_json_eq!(actual_json, expected_json);
+    }
 }
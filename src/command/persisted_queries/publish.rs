@@ -1,16 +1,21 @@
 use anyhow::{anyhow, Context};
 use clap::Parser;
+use rover_client::blocking::StudioClient;
+use rover_client::operations::graph::fetch::{self as graph_fetch, GraphFetchInput};
 use rover_client::operations::persisted_queries::{
     name::{self, PersistedQueryListNameInput},
     publish::RelayPersistedQueryManifest,
 };
-use rover_std::Style;
+use rover_client::shared::GraphRef;
+use rover_std::{Emoji, Style};
 use serde::Serialize;
 
+use crate::command::ops::coverage::unknown_fields;
 use crate::options::{OptionalGraphRefOpt, PersistedQueriesManifestFormat, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::utils::parsers::FileDescriptorType;
-use crate::{RoverOutput, RoverResult};
+use crate::utils::scope::{check_key_scope, RequiredScope};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
 use rover_client::operations::persisted_queries::publish::{
     self, ApolloPersistedQueryManifest, PersistedQueriesPublishInput,
@@ -41,6 +46,11 @@ pub struct Publish {
     #[arg(long, value_enum, default_value_t = PersistedQueriesManifestFormat::Apollo)]
     manifest_format: PersistedQueriesManifestFormat,
 
+    /// Check that every field the manifest's operations select exists in the schema published
+    /// to <GRAPH_REF>, without publishing them.
+    #[arg(long, requires = "graph_ref")]
+    dry_run: bool,
+
     #[clap(flatten)]
     profile: ProfileOpt,
 }
@@ -48,6 +58,11 @@ pub struct Publish {
 impl Publish {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            self.graph.graph_ref.as_ref(),
+            RequiredScope::RegisterOperations,
+        )?;
 
         let raw_manifest = self
             .manifest
@@ -90,6 +105,18 @@ impl Publish {
             (Some(_), Some(_), Some(_)) | (Some(_), Some(_), None) | (Some(_), None, Some(_)) => unreachable!("clap \"conflicts_with\" should make this impossible to reach")
         };
 
+        if let (true, Some(graph_ref)) = (self.dry_run, &self.graph.graph_ref) {
+            self.validate(graph_ref, &operation_manifest, &client)?;
+            eprintln!(
+                "{}{} operations are valid against {}. Nothing was published to list {}.",
+                Emoji::Success,
+                operation_manifest.operations.len(),
+                Style::Link.paint(graph_ref.to_string()),
+                Style::Link.paint(list_name)
+            );
+            return Ok(RoverOutput::EmptySuccess);
+        }
+
         eprintln!(
             "Publishing operations to list {} for {} using credentials from the {} profile.",
             Style::Link.paint(list_name),
@@ -107,4 +134,55 @@ impl Publish {
         )?;
         Ok(RoverOutput::PersistedQueriesPublishResponse(result))
     }
+
+    /// Fails with the operations in `operation_manifest` that select fields that aren't in the
+    /// schema published to `graph_ref`.
+    fn validate(
+        &self,
+        graph_ref: &GraphRef,
+        operation_manifest: &ApolloPersistedQueryManifest,
+        client: &StudioClient,
+    ) -> RoverResult<()> {
+        eprintln!(
+            "Validating {} operations against {} using credentials from the {} profile.",
+            operation_manifest.operations.len(),
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+        let sdl = graph_fetch::run(
+            GraphFetchInput {
+                graph_ref: graph_ref.clone(),
+            },
+            client,
+        )?
+        .sdl
+        .contents;
+        let operations = operation_manifest
+            .operations
+            .iter()
+            .map(|operation| (operation.name.clone(), operation.body.clone()))
+            .collect::<Vec<_>>();
+        let invalid = unknown_fields(&sdl, &operations)?;
+        if invalid.is_empty() {
+            return Ok(());
+        }
+        let mut err = RoverError::new(anyhow!(
+            "{} {} fields that {} doesn't have:\n{}",
+            invalid.len(),
+            match invalid.len() {
+                1 => "operation selects",
+                _ => "operations select",
+            },
+            graph_ref,
+            invalid
+                .iter()
+                .map(|(operation, fields)| format!("{}: {}", operation, fields.join(", ")))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "Publish the schema changes these operations depend on first, or regenerate the manifest from the client's current operations.".to_string(),
+        ));
+        Err(err)
+    }
 }
@@ -4,6 +4,7 @@ use serde::Serialize;
 use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::utils::parsers::FileDescriptorType;
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 use rover_client::operations::readme::publish::{self, ReadmePublishInput};
@@ -26,6 +27,11 @@ pub struct Publish {
 impl Publish {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::UpdateVariantReadme,
+        )?;
         let graph_ref = self.graph.graph_ref.to_string();
         eprintln!(
             "Publishing README for {} using credentials from the {} profile.",
@@ -0,0 +1,302 @@
+use std::fmt::Write;
+
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+use super::diff::{ChangeCode, SchemaChange, SchemaDiff, Severity, SeverityConfigOpt};
+use crate::utils::parsers::FileDescriptorType;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct DescribeChange {
+    /// The schema before the change, like the one on your main branch. You can pass `-` to use
+    /// stdin instead of a file.
+    #[arg(long = "base")]
+    #[serde(skip_serializing)]
+    base: FileDescriptorType,
+
+    /// The schema after the change. You can pass `-` to use stdin instead of a file.
+    #[arg(long = "proposed")]
+    #[serde(skip_serializing)]
+    proposed: FileDescriptorType,
+
+    /// How to write the summary. `--format` already picks between plain and JSON output, so
+    /// this is a separate option.
+    #[arg(long = "style", value_enum, default_value_t = DescriptionStyle::Markdown)]
+    style: DescriptionStyle,
+
+    #[clap(flatten)]
+    severity_config: SeverityConfigOpt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DescriptionStyle {
+    /// Markdown, for pasting into a pull request description
+    Markdown,
+    /// Plain text, for terminals and commit messages
+    Text,
+}
+
+impl DescribeChange {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let overrides = self.severity_config.load()?;
+        let base = self
+            .base
+            .read_file_descriptor("base SDL", &mut std::io::stdin())?;
+        let proposed = self
+            .proposed
+            .read_file_descriptor("proposed SDL", &mut std::io::stdin())?;
+        let summary = ChangeSummary::new(SchemaDiff::new(&base, &proposed, &overrides)?);
+        let description = summary.describe(self.style);
+        Ok(RoverOutput::SchemaChangeDescription {
+            summary,
+            description,
+        })
+    }
+}
+
+/// The changes between two schemas, grouped by what they mean for the clients of a graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChangeSummary {
+    /// Changes that can make operations that clients already send stop working
+    pub(crate) breaking_risks: Vec<SchemaChange>,
+    /// Types, fields, arguments, and values that clients can start using
+    pub(crate) new_capabilities: Vec<SchemaChange>,
+    pub(crate) deprecations: Vec<SchemaChange>,
+    /// Notable changes that are none of the above, like a new default value
+    pub(crate) other_changes: Vec<SchemaChange>,
+}
+
+impl ChangeSummary {
+    pub(crate) fn new(diff: SchemaDiff) -> Self {
+        let mut summary = Self::default();
+        for change in diff.changes {
+            let group = if change.severity == Severity::Breaking {
+                &mut summary.breaking_risks
+            } else if change.code == ChangeCode::FieldDeprecated {
+                &mut summary.deprecations
+            } else if is_addition(change.code) {
+                &mut summary.new_capabilities
+            } else {
+                &mut summary.other_changes
+            };
+            group.push(change);
+        }
+        summary
+    }
+
+    fn is_empty(&self) -> bool {
+        self.breaking_risks.is_empty()
+            && self.new_capabilities.is_empty()
+            && self.deprecations.is_empty()
+            && self.other_changes.is_empty()
+    }
+
+    /// One sentence that counts each group, like "This change adds 2 new capabilities and has
+    /// 1 breaking risk."
+    fn headline(&self) -> String {
+        if self.is_empty() {
+            return "This change doesn't change the schema.".to_string();
+        }
+        let mut parts = Vec::new();
+        if !self.new_capabilities.is_empty() {
+            parts.push(count(
+                "adds",
+                self.new_capabilities.len(),
+                "new capability",
+                "new capabilities",
+            ));
+        }
+        if !self.deprecations.is_empty() {
+            parts.push(count(
+                "deprecates",
+                self.deprecations.len(),
+                "field",
+                "fields",
+            ));
+        }
+        if !self.other_changes.is_empty() {
+            parts.push(count(
+                "makes",
+                self.other_changes.len(),
+                "other notable change",
+                "other notable changes",
+            ));
+        }
+        if !self.breaking_risks.is_empty() {
+            parts.push(count(
+                "has",
+                self.breaking_risks.len(),
+                "breaking risk",
+                "breaking risks",
+            ));
+        }
+        let parts = match parts.as_slice() {
+            [only] => only.clone(),
+            [first, second] => format!("{} and {}", first, second),
+            [rest @ .., last] => format!("{}, and {}", rest.join(", "), last),
+            [] => unreachable!("the summary has at least one change"),
+        };
+        format!("This change {}.", parts)
+    }
+
+    /// The groups that have changes, most important first, with a note on what each one means
+    fn sections(&self) -> Vec<(&'static str, &'static str, &[SchemaChange])> {
+        [
+            (
+                "Breaking risks",
+                "Operations that clients already send can stop working. Check that no client still depends on these before merging.",
+                self.breaking_risks.as_slice(),
+            ),
+            (
+                "New capabilities",
+                "Clients can start using these once the change is published.",
+                self.new_capabilities.as_slice(),
+            ),
+            (
+                "Deprecations",
+                "These keep working, but clients should stop using them.",
+                self.deprecations.as_slice(),
+            ),
+            (
+                "Other notable changes",
+                "Existing operations keep working, but their results can change.",
+                self.other_changes.as_slice(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, _, changes)| !changes.is_empty())
+        .collect()
+    }
+
+    pub(crate) fn describe(&self, style: DescriptionStyle) -> String {
+        match style {
+            DescriptionStyle::Markdown => self.to_markdown(),
+            DescriptionStyle::Text => self.to_text(),
+        }
+    }
+
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut markdown = format!("## Schema changes\n\n{}\n", self.headline());
+        for (heading, note, changes) in self.sections() {
+            let _ = write!(markdown, "\n### {}\n\n{}\n\n", heading, note);
+            for change in changes {
+                let _ = writeln!(markdown, "- {}", sentence_case(&change.description));
+            }
+        }
+        markdown
+    }
+
+    pub(crate) fn to_text(&self) -> String {
+        let mut text = self.headline();
+        for (heading, note, changes) in self.sections() {
+            let _ = write!(text, "\n\n{}: {}", heading, note);
+            for change in changes {
+                let _ = write!(
+                    text,
+                    "\n  - {}",
+                    sentence_case(&change.description.replace('`', ""))
+                );
+            }
+        }
+        text
+    }
+}
+
+/// Whether a kind of change adds something that clients can use
+fn is_addition(code: ChangeCode) -> bool {
+    use ChangeCode::*;
+    matches!(
+        code,
+        TypeAdded
+            | FieldAdded
+            | ArgAdded
+            | InputFieldAdded
+            | ValueAddedToEnum
+            | TypeAddedToUnion
+            | TypeAddedToInterface
+            | DirectiveAdded
+            | DirectiveLocationAdded
+    )
+}
+
+fn count(verb: &str, count: usize, singular: &str, plural: &str) -> String {
+    match count {
+        1 => format!("{} 1 {}", verb, singular),
+        count => format!("{} {} {}", verb, count, plural),
+    }
+}
+
+fn sentence_case(description: &str) -> String {
+    let mut chars = description.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChangeSummary;
+    use crate::command::schema::diff::{SchemaDiff, SeverityOverrides};
+
+    const BASE: &str = r#"type Query {
+  products: [Product]
+}
+
+type Product {
+  id: ID!
+  name: String
+  price: Float
+}
+"#;
+
+    const PROPOSED: &str = r#"type Query {
+  products: [Product]
+}
+
+type Product {
+  id: ID!
+  name: String @deprecated(reason: "Use title")
+  title: String
+  sku: String
+}
+"#;
+
+    fn summary() -> ChangeSummary {
+        ChangeSummary::new(SchemaDiff::new(BASE, PROPOSED, &SeverityOverrides::default()).unwrap())
+    }
+
+    #[test]
+    fn it_groups_changes_by_what_they_mean_for_clients() {
+        let summary = summary();
+        let coordinates = |changes: &[super::SchemaChange]| {
+            changes
+                .iter()
+                .map(|change| change.coordinate.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(coordinates(&summary.breaking_risks), vec!["Product.price"]);
+        assert_eq!(
+            coordinates(&summary.new_capabilities),
+            vec!["Product.sku", "Product.title"]
+        );
+        assert_eq!(coordinates(&summary.deprecations), vec!["Product.name"]);
+        assert!(summary.other_changes.is_empty());
+    }
+
+    #[test]
+    fn it_describes_a_change_in_markdown() {
+        let markdown = summary().to_markdown();
+        assert!(markdown.starts_with(
+            "## Schema changes\n\nThis change adds 2 new capabilities, deprecates 1 field, and has 1 breaking risk.\n"
+        ));
+        assert!(markdown.contains("### Breaking risks\n\n"));
+        assert!(markdown.contains("- `Product.price` was removed\n"));
+        assert_eq!(
+            ChangeSummary::default().to_text(),
+            "This change doesn't change the schema."
+        );
+    }
+}
@@ -0,0 +1,1055 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use apollo_parser::{
+    ast::{self, AstNode},
+    Parser as SdlParser,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Parser, ValueEnum};
+use rover_client::shared::GraphRef;
+use rover_std::{Fs, Style};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display as StrumDisplay;
+
+use super::normalize::{ty, value};
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::name;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// Where a project keeps its change severity overrides, relative to the working directory
+const DEFAULT_SEVERITY_CONFIG_PATH: &str = ".rover/change-severity.yaml";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Diff {
+    /// The schema to compare from. You can pass `-` to use stdin instead of a file.
+    #[arg(long = "old")]
+    #[serde(skip_serializing)]
+    old: FileDescriptorType,
+
+    /// The schema to compare to. You can pass `-` to use stdin instead of a file.
+    #[arg(long = "new")]
+    #[serde(skip_serializing)]
+    new: FileDescriptorType,
+
+    #[clap(flatten)]
+    severity_config: SeverityConfigOpt,
+
+    #[clap(flatten)]
+    fail_on: FailOnOpt,
+}
+
+impl Diff {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let overrides = self.severity_config.load()?;
+        let old = self
+            .old
+            .read_file_descriptor("old SDL", &mut std::io::stdin())?;
+        let new = self
+            .new
+            .read_file_descriptor("new SDL", &mut std::io::stdin())?;
+        let diff = SchemaDiff::new(&old, &new, &overrides)?;
+        Ok(RoverOutput::SchemaDiff(self.fail_on.check(diff)?))
+    }
+}
+
+/// One side of a diff: a schema file, stdin, or the schema published to a graph ref.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SchemaSource {
+    File(FileDescriptorType),
+    GraphRef(GraphRef),
+}
+
+impl SchemaSource {
+    /// Reads the schema from its file or stdin, or with `fetch` for a graph ref.
+    pub(crate) fn read(
+        &self,
+        file_description: &str,
+        fetch: impl FnOnce(&GraphRef) -> RoverResult<String>,
+    ) -> RoverResult<String> {
+        match self {
+            Self::File(file_descriptor) => {
+                file_descriptor.read_file_descriptor(file_description, &mut std::io::stdin())
+            }
+            Self::GraphRef(graph_ref) => fetch(graph_ref),
+        }
+    }
+}
+
+impl FromStr for SchemaSource {
+    type Err = std::io::Error;
+
+    /// Anything with an `@` that isn't a file, like `my-graph@main`, is a graph ref.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.contains('@') && !Utf8Path::new(input).exists() {
+            if let Ok(graph_ref) = GraphRef::from_str(input) {
+                return Ok(Self::GraphRef(graph_ref));
+            }
+        }
+        FileDescriptorType::from_str(input).map(Self::File)
+    }
+}
+
+impl Display for SchemaSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(file_descriptor) => write!(f, "{}", file_descriptor),
+            Self::GraphRef(graph_ref) => write!(f, "{}", graph_ref),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Parser)]
+pub(crate) struct FailOnOpt {
+    /// Exit with an error if there are any changes of this severity or a more severe one, like
+    /// `--fail-on breaking` in CI.
+    #[arg(long = "fail-on", value_enum)]
+    #[serde(skip_serializing)]
+    fail_on: Option<Severity>,
+}
+
+impl FailOnOpt {
+    /// Returns the diff, or an error that still reports it if it has changes that fail.
+    pub(crate) fn check(&self, diff: SchemaDiff) -> RoverResult<SchemaDiff> {
+        let Some(fail_on) = self.fail_on else {
+            return Ok(diff);
+        };
+        if diff.changes.iter().all(|change| change.severity > fail_on) {
+            return Ok(diff);
+        }
+        let mut err = RoverError::new(SchemaDiffFailures { diff, fail_on });
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "If these changes are expected, change their severity with `--severity-config`."
+                .to_string(),
+        ));
+        Err(err)
+    }
+}
+
+/// A diff with changes at or above the `--fail-on` severity.
+#[derive(Debug)]
+pub(crate) struct SchemaDiffFailures {
+    pub(crate) diff: SchemaDiff,
+    fail_on: Severity,
+}
+
+impl Display for SchemaDiffFailures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures = self
+            .diff
+            .changes
+            .iter()
+            .filter(|change| change.severity <= self.fail_on)
+            .count();
+        match self.fail_on {
+            Severity::Breaking => write!(f, "found {} breaking", failures)?,
+            severity => write!(f, "found {} {} or more severe", failures, severity)?,
+        }
+        match failures {
+            1 => write!(f, " change"),
+            _ => write!(f, " changes"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaDiffFailures {}
+
+#[derive(Debug, Clone, Default, Serialize, Parser)]
+pub(crate) struct SeverityConfigOpt {
+    /// A YAML file that changes the severity of specific kinds of changes, like
+    /// `ARG_DEFAULT_VALUE_CHANGE: breaking`. Defaults to `.rover/change-severity.yaml` if it exists.
+    #[arg(long = "severity-config")]
+    #[serde(skip_serializing)]
+    severity_config: Option<Utf8PathBuf>,
+}
+
+impl SeverityConfigOpt {
+    /// Reads the severity overrides from `--severity-config`, or from the project's default
+    /// config file if there is one.
+    pub(crate) fn load(&self) -> RoverResult<SeverityOverrides> {
+        match &self.severity_config {
+            Some(path) => SeverityOverrides::from_file(path),
+            None if Utf8Path::new(DEFAULT_SEVERITY_CONFIG_PATH).exists() => {
+                SeverityOverrides::from_file(DEFAULT_SEVERITY_CONFIG_PATH)
+            }
+            None => Ok(SeverityOverrides::default()),
+        }
+    }
+}
+
+/// How much a change can affect the clients of a graph.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    /// Operations that clients already send can stop working
+    Breaking,
+    /// Existing operations keep working, but clients might want to know about the change
+    Notable,
+    Safe,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Breaking => write!(f, "breaking"),
+            Severity::Notable => write!(f, "notable"),
+            Severity::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// The kinds of changes between two schemas.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, StrumDisplay,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum ChangeCode {
+    TypeAdded,
+    TypeRemoved,
+    TypeChangedKind,
+    FieldAdded,
+    FieldRemoved,
+    FieldChangedType,
+    FieldDeprecated,
+    ArgAdded,
+    RequiredArgAdded,
+    ArgRemoved,
+    ArgChangedType,
+    ArgDefaultValueChange,
+    InputFieldAdded,
+    RequiredInputFieldAdded,
+    InputFieldRemoved,
+    InputFieldChangedType,
+    InputFieldDefaultValueChange,
+    ValueAddedToEnum,
+    ValueRemovedFromEnum,
+    TypeAddedToUnion,
+    TypeRemovedFromUnion,
+    TypeAddedToInterface,
+    TypeRemovedFromInterface,
+    DirectiveAdded,
+    DirectiveRemoved,
+    DirectiveLocationAdded,
+    DirectiveLocationRemoved,
+}
+
+impl ChangeCode {
+    /// The severity of the change unless a project overrides it.
+    pub(crate) fn default_severity(self) -> Severity {
+        use ChangeCode::*;
+        match self {
+            TypeRemoved
+            | TypeChangedKind
+            | FieldRemoved
+            | FieldChangedType
+            | RequiredArgAdded
+            | ArgRemoved
+            | ArgChangedType
+            | RequiredInputFieldAdded
+            | InputFieldRemoved
+            | InputFieldChangedType
+            | ValueRemovedFromEnum
+            | TypeRemovedFromUnion
+            | TypeRemovedFromInterface
+            | DirectiveRemoved
+            | DirectiveLocationRemoved => Severity::Breaking,
+            FieldDeprecated | ArgDefaultValueChange | InputFieldDefaultValueChange => {
+                Severity::Notable
+            }
+            TypeAdded
+            | FieldAdded
+            | ArgAdded
+            | InputFieldAdded
+            | ValueAddedToEnum
+            | TypeAddedToUnion
+            | TypeAddedToInterface
+            | DirectiveAdded
+            | DirectiveLocationAdded => Severity::Safe,
+        }
+    }
+}
+
+/// The severities a project assigns to kinds of changes instead of their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SeverityOverrides(BTreeMap<ChangeCode, Severity>);
+
+impl SeverityOverrides {
+    pub(crate) fn from_file(path: impl AsRef<Utf8Path>) -> RoverResult<Self> {
+        let path = path.as_ref();
+        let contents = Fs::read_file(path)?;
+        Self::parse(&contents).map_err(|err| {
+            let mut err = RoverError::new(
+                anyhow!(err).context(format!("could not read the severity config at {}", path)),
+            );
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Map change codes to `breaking`, `notable`, or `safe`, like `VALUE_ADDED_TO_ENUM: notable`.".to_string(),
+            ));
+            err
+        })
+    }
+
+    fn parse(contents: &str) -> Result<Self, serde_yaml::Error> {
+        if contents.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(contents)
+    }
+
+    pub(crate) fn severity(&self, code: ChangeCode) -> Severity {
+        self.0
+            .get(&code)
+            .copied()
+            .unwrap_or_else(|| code.default_severity())
+    }
+}
+
+/// A single difference between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SchemaChange {
+    pub(crate) code: ChangeCode,
+    pub(crate) severity: Severity,
+    /// The schema coordinate that changed, like `Product.price` or `Query.products(first:)`
+    pub(crate) coordinate: String,
+    pub(crate) description: String,
+}
+
+/// The changes between two schemas, most severe first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaDiff {
+    pub(crate) changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub(crate) fn new(old: &str, new: &str, overrides: &SeverityOverrides) -> RoverResult<Self> {
+        let old = SchemaModel::from_sdl(old).context("could not parse the old schema")?;
+        let new = SchemaModel::from_sdl(new).context("could not parse the new schema")?;
+        let mut differ = Differ {
+            overrides,
+            changes: Vec::new(),
+        };
+        differ.schemas(&old, &new);
+        let mut changes = differ.changes;
+        changes.sort_by(|a, b| {
+            (a.severity, &a.coordinate, a.code).cmp(&(b.severity, &b.coordinate, b.code))
+        });
+        Ok(Self { changes })
+    }
+
+    /// The number of changes with `severity`.
+    pub(crate) fn count(&self, severity: Severity) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.severity == severity)
+            .count()
+    }
+
+    /// A count of each severity, like `1 breaking, 0 notable, and 2 safe changes.`
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "{} breaking, {} notable, and {} safe {}.",
+            self.count(Severity::Breaking),
+            self.count(Severity::Notable),
+            self.count(Severity::Safe),
+            match self.changes.len() {
+                1 => "change",
+                _ => "changes",
+            }
+        )
+    }
+
+    /// A count of each severity, followed by the changes grouped by severity.
+    pub(crate) fn get_output(&self) -> String {
+        let mut result = self.summary();
+        for (severity, heading) in [
+            (Severity::Breaking, "Breaking changes:"),
+            (Severity::Notable, "Notable changes:"),
+            (Severity::Safe, "Safe changes:"),
+        ] {
+            let changes: Vec<String> = self
+                .changes
+                .iter()
+                .filter(|change| change.severity == severity)
+                .map(|change| format!("{}: {}", change.code, change.description))
+                .collect();
+            if !changes.is_empty() {
+                result.push_str(&format!(
+                    "\n\n{}\n{}",
+                    Style::Heading.paint(heading),
+                    changes.join("\n")
+                ));
+            }
+        }
+        result
+    }
+}
+
+#[derive(Debug, Default)]
+struct SchemaModel {
+    types: BTreeMap<String, TypeModel>,
+    directives: BTreeMap<String, DirectiveModel>,
+}
+
+#[derive(Debug, Default)]
+struct DirectiveModel {
+    arguments: BTreeMap<String, InputValueModel>,
+    locations: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct TypeModel {
+    kind: &'static str,
+    fields: BTreeMap<String, FieldModel>,
+    input_fields: BTreeMap<String, InputValueModel>,
+    values: BTreeSet<String>,
+    members: BTreeSet<String>,
+    interfaces: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct FieldModel {
+    ty: String,
+    arguments: BTreeMap<String, InputValueModel>,
+    deprecated: bool,
+}
+
+#[derive(Debug, Default)]
+struct InputValueModel {
+    ty: String,
+    default: Option<String>,
+}
+
+impl InputValueModel {
+    fn new(input_value: ast::InputValueDefinition) -> (String, Self) {
+        (
+            name(input_value.name()),
+            Self {
+                ty: ty(input_value.ty()),
+                default: input_value
+                    .default_value()
+                    .map(|default| value(default.value())),
+            },
+        )
+    }
+
+    /// Whether callers have to start passing a value when this is added.
+    fn is_required(&self) -> bool {
+        self.ty.ends_with('!') && self.default.is_none()
+    }
+}
+
+impl SchemaModel {
+    /// Reads the types in `sdl`, merging extensions into the types they extend.
+    fn from_sdl(sdl: &str) -> anyhow::Result<Self> {
+        let tree = SdlParser::new(sdl).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(anyhow!("{}", errors.join(", ")));
+        }
+
+        let mut schema = SchemaModel::default();
+        for definition in tree.document().definitions() {
+            use ast::Definition::*;
+            match definition {
+                ObjectTypeDefinition(d) => {
+                    let model = schema.type_model(d.name(), "object");
+                    model.implements(d.implements_interfaces());
+                    model.fields(d.fields_definition());
+                }
+                ObjectTypeExtension(d) => {
+                    let model = schema.type_model(d.name(), "object");
+                    model.implements(d.implements_interfaces());
+                    model.fields(d.fields_definition());
+                }
+                InterfaceTypeDefinition(d) => {
+                    let model = schema.type_model(d.name(), "interface");
+                    model.implements(d.implements_interfaces());
+                    model.fields(d.fields_definition());
+                }
+                InterfaceTypeExtension(d) => {
+                    let model = schema.type_model(d.name(), "interface");
+                    model.implements(d.implements_interfaces());
+                    model.fields(d.fields_definition());
+                }
+                InputObjectTypeDefinition(d) => schema
+                    .type_model(d.name(), "input object")
+                    .input_fields(d.input_fields_definition()),
+                InputObjectTypeExtension(d) => schema
+                    .type_model(d.name(), "input object")
+                    .input_fields(d.input_fields_definition()),
+                EnumTypeDefinition(d) => schema
+                    .type_model(d.name(), "enum")
+                    .values(d.enum_values_definition()),
+                EnumTypeExtension(d) => schema
+                    .type_model(d.name(), "enum")
+                    .values(d.enum_values_definition()),
+                UnionTypeDefinition(d) => schema
+                    .type_model(d.name(), "union")
+                    .members(d.union_member_types()),
+                UnionTypeExtension(d) => schema
+                    .type_model(d.name(), "union")
+                    .members(d.union_member_types()),
+                ScalarTypeDefinition(d) => {
+                    schema.type_model(d.name(), "scalar");
+                }
+                ScalarTypeExtension(d) => {
+                    schema.type_model(d.name(), "scalar");
+                }
+                DirectiveDefinition(d) => {
+                    let directive = DirectiveModel {
+                        arguments: d
+                            .arguments_definition()
+                            .iter()
+                            .flat_map(|arguments| arguments.input_value_definitions())
+                            .map(InputValueModel::new)
+                            .collect(),
+                        locations: d
+                            .directive_locations()
+                            .iter()
+                            .flat_map(|locations| locations.directive_locations())
+                            .map(|location| location.syntax().text().to_string().trim().to_string())
+                            .collect(),
+                    };
+                    schema.directives.insert(name(d.name()), directive);
+                }
+                _ => {}
+            }
+        }
+        Ok(schema)
+    }
+
+    fn type_model(&mut self, type_name: Option<ast::Name>, kind: &'static str) -> &mut TypeModel {
+        let model = self.types.entry(name(type_name)).or_default();
+        model.kind = kind;
+        model
+    }
+}
+
+impl TypeModel {
+    fn implements(&mut self, interfaces: Option<ast::ImplementsInterfaces>) {
+        self.interfaces.extend(
+            interfaces
+                .iter()
+                .flat_map(|interfaces| interfaces.named_types())
+                .map(|interface| name(interface.name())),
+        );
+    }
+
+    fn fields(&mut self, fields: Option<ast::FieldsDefinition>) {
+        for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+            let deprecated = field
+                .directives()
+                .iter()
+                .flat_map(|directives| directives.directives())
+                .any(|directive| name(directive.name()) == "deprecated");
+            self.fields.insert(
+                name(field.name()),
+                FieldModel {
+                    ty: ty(field.ty()),
+                    arguments: field
+                        .arguments_definition()
+                        .iter()
+                        .flat_map(|arguments| arguments.input_value_definitions())
+                        .map(InputValueModel::new)
+                        .collect(),
+                    deprecated,
+                },
+            );
+        }
+    }
+
+    fn input_fields(&mut self, fields: Option<ast::InputFieldsDefinition>) {
+        self.input_fields.extend(
+            fields
+                .iter()
+                .flat_map(|fields| fields.input_value_definitions())
+                .map(InputValueModel::new),
+        );
+    }
+
+    fn values(&mut self, values: Option<ast::EnumValuesDefinition>) {
+        self.values.extend(
+            values
+                .iter()
+                .flat_map(|values| values.enum_value_definitions())
+                .map(|value| name(value.enum_value().and_then(|value| value.name()))),
+        );
+    }
+
+    fn members(&mut self, members: Option<ast::UnionMemberTypes>) {
+        self.members.extend(
+            members
+                .iter()
+                .flat_map(|members| members.named_types())
+                .map(|member| name(member.name())),
+        );
+    }
+}
+
+struct Differ<'a> {
+    overrides: &'a SeverityOverrides,
+    changes: Vec<SchemaChange>,
+}
+
+impl Differ<'_> {
+    fn change(&mut self, code: ChangeCode, coordinate: String, description: String) {
+        self.changes.push(SchemaChange {
+            code,
+            severity: self.overrides.severity(code),
+            coordinate,
+            description,
+        });
+    }
+
+    fn schemas(&mut self, old: &SchemaModel, new: &SchemaModel) {
+        for (type_name, old_type) in &old.types {
+            match new.types.get(type_name) {
+                None => self.change(
+                    ChangeCode::TypeRemoved,
+                    type_name.clone(),
+                    format!("`{}` was removed", type_name),
+                ),
+                Some(new_type) if new_type.kind != old_type.kind => self.change(
+                    ChangeCode::TypeChangedKind,
+                    type_name.clone(),
+                    format!(
+                        "`{}` changed from an {} type to an {} type",
+                        type_name, old_type.kind, new_type.kind
+                    ),
+                ),
+                Some(new_type) => self.types(type_name, old_type, new_type),
+            }
+        }
+        for type_name in new.types.keys() {
+            if !old.types.contains_key(type_name) {
+                self.change(
+                    ChangeCode::TypeAdded,
+                    type_name.clone(),
+                    format!("`{}` was added", type_name),
+                );
+            }
+        }
+
+        for (directive_name, old_directive) in &old.directives {
+            let coordinate = format!("@{}", directive_name);
+            match new.directives.get(directive_name) {
+                None => self.change(
+                    ChangeCode::DirectiveRemoved,
+                    coordinate.clone(),
+                    format!("directive `{}` was removed", coordinate),
+                ),
+                Some(new_directive) => self.directives(coordinate, old_directive, new_directive),
+            }
+        }
+        for directive_name in new.directives.keys() {
+            if !old.directives.contains_key(directive_name) {
+                let coordinate = format!("@{}", directive_name);
+                self.change(
+                    ChangeCode::DirectiveAdded,
+                    coordinate.clone(),
+                    format!("directive `{}` was added", coordinate),
+                );
+            }
+        }
+    }
+
+    fn directives(&mut self, coordinate: String, old: &DirectiveModel, new: &DirectiveModel) {
+        for location in old.locations.difference(&new.locations) {
+            self.change(
+                ChangeCode::DirectiveLocationRemoved,
+                coordinate.clone(),
+                format!("`{}` can no longer be used on {}", coordinate, location),
+            );
+        }
+        for location in new.locations.difference(&old.locations) {
+            self.change(
+                ChangeCode::DirectiveLocationAdded,
+                coordinate.clone(),
+                format!("`{}` can now be used on {}", coordinate, location),
+            );
+        }
+        self.arguments(&coordinate, &old.arguments, &new.arguments);
+    }
+
+    fn types(&mut self, type_name: &str, old: &TypeModel, new: &TypeModel) {
+        for (field_name, old_field) in &old.fields {
+            let coordinate = format!("{}.{}", type_name, field_name);
+            match new.fields.get(field_name) {
+                None => self.change(
+                    ChangeCode::FieldRemoved,
+                    coordinate.clone(),
+                    format!("`{}` was removed", coordinate),
+                ),
+                Some(new_field) => self.fields(coordinate, old_field, new_field),
+            }
+        }
+        for field_name in new.fields.keys() {
+            if !old.fields.contains_key(field_name) {
+                let coordinate = format!("{}.{}", type_name, field_name);
+                self.change(
+                    ChangeCode::FieldAdded,
+                    coordinate.clone(),
+                    format!("`{}` was added", coordinate),
+                );
+            }
+        }
+
+        for (field_name, old_field) in &old.input_fields {
+            let coordinate = format!("{}.{}", type_name, field_name);
+            match new.input_fields.get(field_name) {
+                None => self.change(
+                    ChangeCode::InputFieldRemoved,
+                    coordinate.clone(),
+                    format!("`{}` was removed", coordinate),
+                ),
+                Some(new_field) => self.input_values(
+                    coordinate,
+                    old_field,
+                    new_field,
+                    ChangeCode::InputFieldChangedType,
+                    ChangeCode::InputFieldDefaultValueChange,
+                ),
+            }
+        }
+        for (field_name, new_field) in &new.input_fields {
+            if !old.input_fields.contains_key(field_name) {
+                let coordinate = format!("{}.{}", type_name, field_name);
+                if new_field.is_required() {
+                    self.change(
+                        ChangeCode::RequiredInputFieldAdded,
+                        coordinate.clone(),
+                        format!("required input field `{}` was added", coordinate),
+                    );
+                } else {
+                    self.change(
+                        ChangeCode::InputFieldAdded,
+                        coordinate.clone(),
+                        format!("optional input field `{}` was added", coordinate),
+                    );
+                }
+            }
+        }
+
+        for value in old.values.difference(&new.values) {
+            let coordinate = format!("{}.{}", type_name, value);
+            self.change(
+                ChangeCode::ValueRemovedFromEnum,
+                coordinate.clone(),
+                format!("`{}` was removed from enum `{}`", value, type_name),
+            );
+        }
+        for value in new.values.difference(&old.values) {
+            let coordinate = format!("{}.{}", type_name, value);
+            self.change(
+                ChangeCode::ValueAddedToEnum,
+                coordinate.clone(),
+                format!("`{}` was added to enum `{}`", value, type_name),
+            );
+        }
+
+        for member in old.members.difference(&new.members) {
+            self.change(
+                ChangeCode::TypeRemovedFromUnion,
+                type_name.to_string(),
+                format!("`{}` was removed from union `{}`", member, type_name),
+            );
+        }
+        for member in new.members.difference(&old.members) {
+            self.change(
+                ChangeCode::TypeAddedToUnion,
+                type_name.to_string(),
+                format!("`{}` was added to union `{}`", member, type_name),
+            );
+        }
+
+        for interface in old.interfaces.difference(&new.interfaces) {
+            self.change(
+                ChangeCode::TypeRemovedFromInterface,
+                type_name.to_string(),
+                format!("`{}` no longer implements `{}`", type_name, interface),
+            );
+        }
+        for interface in new.interfaces.difference(&old.interfaces) {
+            self.change(
+                ChangeCode::TypeAddedToInterface,
+                type_name.to_string(),
+                format!("`{}` now implements `{}`", type_name, interface),
+            );
+        }
+    }
+
+    fn fields(&mut self, coordinate: String, old: &FieldModel, new: &FieldModel) {
+        if old.ty != new.ty {
+            self.change(
+                ChangeCode::FieldChangedType,
+                coordinate.clone(),
+                format!(
+                    "`{}` changed type from `{}` to `{}`",
+                    coordinate, old.ty, new.ty
+                ),
+            );
+        }
+        if new.deprecated && !old.deprecated {
+            self.change(
+                ChangeCode::FieldDeprecated,
+                coordinate.clone(),
+                format!("`{}` was deprecated", coordinate),
+            );
+        }
+        self.arguments(&coordinate, &old.arguments, &new.arguments);
+    }
+
+    /// Compares the arguments of a field or directive at `coordinate`.
+    fn arguments(
+        &mut self,
+        coordinate: &str,
+        old: &BTreeMap<String, InputValueModel>,
+        new: &BTreeMap<String, InputValueModel>,
+    ) {
+        for (argument_name, old_argument) in old {
+            let argument_coordinate = format!("{}({}:)", coordinate, argument_name);
+            match new.get(argument_name) {
+                None => self.change(
+                    ChangeCode::ArgRemoved,
+                    argument_coordinate.clone(),
+                    format!("argument `{}` was removed", argument_coordinate),
+                ),
+                Some(new_argument) => self.input_values(
+                    argument_coordinate,
+                    old_argument,
+                    new_argument,
+                    ChangeCode::ArgChangedType,
+                    ChangeCode::ArgDefaultValueChange,
+                ),
+            }
+        }
+        for (argument_name, new_argument) in new {
+            if !old.contains_key(argument_name) {
+                let argument_coordinate = format!("{}({}:)", coordinate, argument_name);
+                if new_argument.is_required() {
+                    self.change(
+                        ChangeCode::RequiredArgAdded,
+                        argument_coordinate.clone(),
+                        format!("required argument `{}` was added", argument_coordinate),
+                    );
+                } else {
+                    self.change(
+                        ChangeCode::ArgAdded,
+                        argument_coordinate.clone(),
+                        format!("optional argument `{}` was added", argument_coordinate),
+                    );
+                }
+            }
+        }
+    }
+
+    fn input_values(
+        &mut self,
+        coordinate: String,
+        old: &InputValueModel,
+        new: &InputValueModel,
+        changed_type: ChangeCode,
+        changed_default: ChangeCode,
+    ) {
+        if old.ty != new.ty {
+            self.change(
+                changed_type,
+                coordinate.clone(),
+                format!(
+                    "`{}` changed type from `{}` to `{}`",
+                    coordinate, old.ty, new.ty
+                ),
+            );
+        }
+        if old.default != new.default {
+            let describe = |default: &Option<String>| match default {
+                Some(default) => format!("`{}`", default),
+                None => "no default".to_string(),
+            };
+            self.change(
+                changed_default,
+                coordinate.clone(),
+                format!(
+                    "the default value of `{}` changed from {} to {}",
+                    coordinate,
+                    describe(&old.default),
+                    describe(&new.default)
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rover_client::shared::GraphRef;
+
+    use super::{ChangeCode, FailOnOpt, SchemaDiff, SchemaSource, Severity, SeverityOverrides};
+    use crate::utils::parsers::FileDescriptorType;
+
+    const OLD: &str = r#"
+type Query {
+  products(first: Int = 10): [Product]
+  reviews: [Review]
+}
+
+type Product {
+  id: ID!
+  name: String
+  price: Int
+}
+
+type Review {
+  body: String
+}
+
+enum Color {
+  RED
+  GREEN
+}
+"#;
+
+    const NEW: &str = r#"
+type Query {
+  products(first: Int = 20, region: String!): [Product]
+}
+
+type Product {
+  id: ID!
+  name: String @deprecated(reason: "use title")
+  title: String
+}
+
+enum Color {
+  RED
+  GREEN
+  BLUE
+}
+"#;
+
+    fn codes(diff: &SchemaDiff) -> Vec<(ChangeCode, Severity, &str)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.code, change.severity, change.coordinate.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn it_classifies_schema_changes() {
+        let diff = SchemaDiff::new(OLD, NEW, &SeverityOverrides::default()).unwrap();
+        assert_eq!(
+            codes(&diff),
+            vec![
+                (
+                    ChangeCode::FieldRemoved,
+                    Severity::Breaking,
+                    "Product.price"
+                ),
+                (
+                    ChangeCode::RequiredArgAdded,
+                    Severity::Breaking,
+                    "Query.products(region:)"
+                ),
+                (
+                    ChangeCode::FieldRemoved,
+                    Severity::Breaking,
+                    "Query.reviews"
+                ),
+                (ChangeCode::TypeRemoved, Severity::Breaking, "Review"),
+                (
+                    ChangeCode::FieldDeprecated,
+                    Severity::Notable,
+                    "Product.name"
+                ),
+                (
+                    ChangeCode::ArgDefaultValueChange,
+                    Severity::Notable,
+                    "Query.products(first:)"
+                ),
+                (ChangeCode::ValueAddedToEnum, Severity::Safe, "Color.BLUE"),
+                (ChangeCode::FieldAdded, Severity::Safe, "Product.title"),
+            ]
+        );
+        assert_eq!(diff.count(Severity::Breaking), 4);
+    }
+
+    #[test]
+    fn it_applies_severity_overrides() {
+        let overrides: SeverityOverrides = SeverityOverrides::parse(
+            "ARG_DEFAULT_VALUE_CHANGE: breaking\nVALUE_ADDED_TO_ENUM: notable\n",
+        )
+        .unwrap();
+        let diff = SchemaDiff::new(OLD, NEW, &overrides).unwrap();
+        assert_eq!(diff.count(Severity::Breaking), 5);
+        assert_eq!(diff.count(Severity::Notable), 2);
+        assert_eq!(diff.count(Severity::Safe), 1);
+
+        assert!(SeverityOverrides::parse("FIELD_RENAMED: safe").is_err());
+        assert!(SeverityOverrides::parse("FIELD_ADDED: harmless").is_err());
+    }
+
+    #[test]
+    fn it_classifies_directive_changes() {
+        let old = "directive @cacheControl(maxAge: Int) on FIELD_DEFINITION | OBJECT\ndirective @auth on FIELD_DEFINITION\ntype Query { a: String }";
+        let new = "directive @cacheControl(maxAge: Int, scope: String!) on FIELD_DEFINITION\ndirective @tag(name: String!) on FIELD_DEFINITION\ntype Query { a: String }";
+        let diff = SchemaDiff::new(old, new, &SeverityOverrides::default()).unwrap();
+        assert_eq!(
+            codes(&diff),
+            vec![
+                (ChangeCode::DirectiveRemoved, Severity::Breaking, "@auth"),
+                (
+                    ChangeCode::DirectiveLocationRemoved,
+                    Severity::Breaking,
+                    "@cacheControl"
+                ),
+                (
+                    ChangeCode::RequiredArgAdded,
+                    Severity::Breaking,
+                    "@cacheControl(scope:)"
+                ),
+                (ChangeCode::DirectiveAdded, Severity::Safe, "@tag"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_fails_on_changes_at_or_above_a_severity() {
+        let diff = SchemaDiff::new(OLD, NEW, &SeverityOverrides::default()).unwrap();
+        let fail_on = |fail_on| FailOnOpt { fail_on }.check(diff.clone());
+        assert!(fail_on(None).is_ok());
+        assert!(fail_on(Some(Severity::Breaking)).is_err());
+
+        let safe = SchemaDiff::new(OLD, OLD, &SeverityOverrides::default()).unwrap();
+        assert!(FailOnOpt {
+            fail_on: Some(Severity::Safe)
+        }
+        .check(safe)
+        .is_ok());
+    }
+
+    #[test]
+    fn it_reads_graph_refs_and_files() {
+        assert_eq!(
+            SchemaSource::from_str("my-graph@main").unwrap(),
+            SchemaSource::GraphRef(GraphRef::from_str("my-graph@main").unwrap())
+        );
+        assert_eq!(
+            SchemaSource::from_str("products.graphql").unwrap(),
+            SchemaSource::File(FileDescriptorType::File("products.graphql".into()))
+        );
+        assert_eq!(
+            SchemaSource::from_str("-").unwrap(),
+            SchemaSource::File(FileDescriptorType::Stdin)
+        );
+    }
+}
@@ -0,0 +1,897 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+use apollo_parser::{
+    ast::{self, AstNode},
+    Parser as SdlParser, SyntaxNode,
+};
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::{name, named_type};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Filter {
+    /// The schema file to filter. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's')]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// Keep only the types and fields with this `@tag`, and the types they use. Can be passed more than once.
+    #[arg(
+        long = "include-tag",
+        value_name = "TAG",
+        required_unless_present = "exclude_tags"
+    )]
+    include_tags: Vec<String>,
+
+    /// Remove the types, fields, arguments, enum values, and input fields with this `@tag`.
+    /// Can be passed more than once, and takes precedence over `--include-tag`.
+    #[arg(long = "exclude-tag", value_name = "TAG")]
+    exclude_tags: Vec<String>,
+
+    /// Also remove types that can't be reached from the schema's root types
+    #[arg(long)]
+    hide_unreachable_types: bool,
+}
+
+impl Filter {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let filtered = TagFilter {
+            include_tags: self.include_tags.iter().cloned().collect(),
+            exclude_tags: self.exclude_tags.iter().cloned().collect(),
+            hide_unreachable_types: self.hide_unreachable_types,
+        }
+        .apply(&sdl)?;
+        Ok(RoverOutput::SchemaFilterResponse {
+            sdl: filtered.sdl,
+            removed: filtered.removed.into_iter().collect(),
+        })
+    }
+}
+
+/// Removes the parts of a schema that a contract with the same tags would, without reformatting
+/// the rest of it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TagFilter {
+    pub(crate) include_tags: BTreeSet<String>,
+    pub(crate) exclude_tags: BTreeSet<String>,
+    pub(crate) hide_unreachable_types: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FilteredSchema {
+    pub(crate) sdl: String,
+    /// The coordinates of the types, fields, arguments, enum values, and input fields that were
+    /// removed, like `Product`, `Product.price`, or `Query.product(id:)`
+    pub(crate) removed: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Object,
+    Interface,
+    Union,
+    Enum,
+    Input,
+    Scalar,
+}
+
+/// A type definition or extension, with the parts of it that filtering can remove.
+struct Definition {
+    node: SyntaxNode,
+    type_name: String,
+    kind: Kind,
+    is_extension: bool,
+    tags: Vec<String>,
+    /// The block of fields, enum values, or input fields
+    members_block: Option<SyntaxNode>,
+    members: Vec<Member>,
+    /// Union members or implemented interfaces, by the node listing them
+    references: Option<(SyntaxNode, Vec<(SyntaxNode, String)>)>,
+}
+
+/// A field, argument, enum value, or input field.
+struct Member {
+    node: SyntaxNode,
+    coordinate: String,
+    tags: Vec<String>,
+    /// The named type it returns or accepts, which enum values don't have
+    ty: Option<String>,
+    /// Whether it's an argument or input field that has to be passed
+    required: bool,
+    arguments_block: Option<SyntaxNode>,
+    arguments: Vec<Member>,
+}
+
+impl TagFilter {
+    pub(crate) fn apply(&self, sdl: &str) -> RoverResult<FilteredSchema> {
+        let tree = SdlParser::new(sdl).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the schema: {}",
+                errors.join(", ")
+            )));
+        }
+
+        let mut root_types = BTreeMap::new();
+        let mut definitions = Vec::new();
+        for definition in tree.document().definitions() {
+            match definition {
+                ast::Definition::SchemaDefinition(d) => {
+                    root_types.extend(root_operations(d.root_operation_type_definitions()))
+                }
+                ast::Definition::SchemaExtension(d) => {
+                    root_types.extend(root_operations(d.root_operation_type_definitions()))
+                }
+                definition => definitions.extend(Definition::new(definition)),
+            }
+        }
+        for (operation, default) in [
+            ("query", "Query"),
+            ("mutation", "Mutation"),
+            ("subscription", "Subscription"),
+        ] {
+            root_types
+                .entry(operation)
+                .or_insert_with(|| default.to_string());
+        }
+
+        let removed = self.removed(&definitions, &root_types);
+        let query_type = &root_types["query"];
+        if removed.contains(query_type) && definitions.iter().any(|d| &d.type_name == query_type) {
+            let mut err = RoverError::new(anyhow!(
+                "Filtering removes every field of the {} type, which leaves an invalid schema.",
+                query_type
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Tag at least one query field with an included tag, and make sure it isn't excluded."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+
+        let mut edits = Vec::new();
+        for definition in &definitions {
+            definition.edits(sdl, &removed, &mut edits);
+        }
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+        let mut filtered = sdl.to_string();
+        for edit in edits {
+            filtered.replace_range(edit.start..edit.end, &edit.replacement);
+        }
+        let mut filtered = filtered.trim_end().to_string();
+        filtered.push('\n');
+        Ok(FilteredSchema {
+            sdl: filtered,
+            removed,
+        })
+    }
+
+    fn is_excluded(&self, tags: &[String]) -> bool {
+        tags.iter().any(|tag| self.exclude_tags.contains(tag))
+    }
+
+    fn is_included(&self, tags: &[String]) -> bool {
+        tags.iter().any(|tag| self.include_tags.contains(tag))
+    }
+
+    /// The coordinates of everything that filtering removes.
+    fn removed(
+        &self,
+        definitions: &[Definition],
+        root_types: &BTreeMap<&str, String>,
+    ) -> BTreeSet<String> {
+        let mut type_tags: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for definition in definitions {
+            type_tags
+                .entry(&definition.type_name)
+                .or_default()
+                .extend(definition.tags.iter().cloned());
+        }
+
+        let mut removed = BTreeSet::new();
+        for (type_name, tags) in &type_tags {
+            if self.is_excluded(tags) {
+                removed.insert(type_name.to_string());
+            }
+        }
+        for definition in definitions {
+            let type_included = self.is_included(&type_tags[definition.type_name.as_str()]);
+            for member in &definition.members {
+                let has_fields = matches!(definition.kind, Kind::Object | Kind::Interface);
+                if self.is_excluded(&member.tags)
+                    || (has_fields
+                        && !self.include_tags.is_empty()
+                        && !type_included
+                        && !self.is_included(&member.tags))
+                {
+                    removed.insert(member.coordinate.clone());
+                }
+                for argument in &member.arguments {
+                    if self.is_excluded(&argument.tags) {
+                        removed.insert(argument.coordinate.clone());
+                    }
+                }
+            }
+        }
+
+        // removing a type removes what uses it, which can leave more types empty
+        loop {
+            let before = removed.len();
+            let removed_type = |ty: &Option<String>, removed: &BTreeSet<String>| {
+                ty.as_ref().map_or(false, |ty| removed.contains(ty))
+            };
+            for definition in definitions {
+                if removed.contains(&definition.type_name) {
+                    continue;
+                }
+                for member in &definition.members {
+                    if removed.contains(&member.coordinate) {
+                        continue;
+                    }
+                    if removed_type(&member.ty, &removed) {
+                        if member.required {
+                            removed.insert(definition.type_name.clone());
+                        }
+                        removed.insert(member.coordinate.clone());
+                    }
+                    for argument in &member.arguments {
+                        if !removed.contains(&argument.coordinate)
+                            && removed_type(&argument.ty, &removed)
+                        {
+                            if argument.required {
+                                removed.insert(member.coordinate.clone());
+                            }
+                            removed.insert(argument.coordinate.clone());
+                        }
+                    }
+                }
+            }
+            // a type left without fields, values, or members is removed too, but the interfaces
+            // a type implements don't keep it in the schema
+            let mut contents: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+            for definition in definitions {
+                let type_contents = contents.entry(&definition.type_name).or_default();
+                type_contents.extend(definition.members.iter().map(|m| m.coordinate.as_str()));
+                if let (Kind::Union, Some((_, named_types))) =
+                    (definition.kind, &definition.references)
+                {
+                    type_contents.extend(named_types.iter().map(|(_, name)| name.as_str()));
+                }
+            }
+            for (type_name, type_contents) in contents {
+                if !type_contents.is_empty()
+                    && type_contents.iter().all(|item| removed.contains(*item))
+                {
+                    removed.insert(type_name.to_string());
+                }
+            }
+            if removed.len() == before {
+                break;
+            }
+        }
+
+        if self.hide_unreachable_types {
+            for type_name in self.unreachable_types(definitions, root_types, &removed) {
+                removed.insert(type_name);
+            }
+        }
+        removed
+    }
+
+    /// The types that can't be reached from a root type through the parts of the schema that
+    /// are kept.
+    fn unreachable_types(
+        &self,
+        definitions: &[Definition],
+        root_types: &BTreeMap<&str, String>,
+        removed: &BTreeSet<String>,
+    ) -> BTreeSet<String> {
+        let mut references: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for definition in definitions {
+            let type_name = definition.type_name.as_str();
+            for member in &definition.members {
+                if removed.contains(&member.coordinate) {
+                    continue;
+                }
+                let arguments = member
+                    .arguments
+                    .iter()
+                    .filter(|argument| !removed.contains(&argument.coordinate))
+                    .filter_map(|argument| argument.ty.as_deref());
+                references
+                    .entry(type_name)
+                    .or_default()
+                    .extend(member.ty.as_deref().into_iter().chain(arguments));
+            }
+            for (_, name) in definition.references.iter().flat_map(|(_, names)| names) {
+                match definition.kind {
+                    Kind::Union => references.entry(type_name).or_default().insert(name),
+                    // an interface reaches the types that implement it
+                    _ => references.entry(name).or_default().insert(type_name),
+                };
+            }
+        }
+
+        let mut reachable = BTreeSet::new();
+        let mut queue = root_types
+            .values()
+            .map(String::as_str)
+            .filter(|root_type| !removed.contains(*root_type))
+            .collect::<Vec<_>>();
+        while let Some(type_name) = queue.pop() {
+            if reachable.insert(type_name) {
+                queue.extend(references.get(type_name).into_iter().flatten());
+            }
+        }
+        definitions
+            .iter()
+            .map(|definition| definition.type_name.as_str())
+            .filter(|type_name| !reachable.contains(type_name) && !removed.contains(*type_name))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Definition {
+    fn new(definition: ast::Definition) -> Option<Self> {
+        use ast::Definition::*;
+        let definition = match definition {
+            ObjectTypeDefinition(d) => Self::with_fields(
+                d.syntax(),
+                name(d.name()),
+                Kind::Object,
+                false,
+                d.directives(),
+                d.implements_interfaces(),
+                d.fields_definition(),
+            ),
+            ObjectTypeExtension(d) => Self::with_fields(
+                d.syntax(),
+                name(d.name()),
+                Kind::Object,
+                true,
+                d.directives(),
+                d.implements_interfaces(),
+                d.fields_definition(),
+            ),
+            InterfaceTypeDefinition(d) => Self::with_fields(
+                d.syntax(),
+                name(d.name()),
+                Kind::Interface,
+                false,
+                d.directives(),
+                d.implements_interfaces(),
+                d.fields_definition(),
+            ),
+            InterfaceTypeExtension(d) => Self::with_fields(
+                d.syntax(),
+                name(d.name()),
+                Kind::Interface,
+                true,
+                d.directives(),
+                d.implements_interfaces(),
+                d.fields_definition(),
+            ),
+            UnionTypeDefinition(d) => Self::union(
+                d.syntax(),
+                name(d.name()),
+                false,
+                d.directives(),
+                d.union_member_types(),
+            ),
+            UnionTypeExtension(d) => Self::union(
+                d.syntax(),
+                name(d.name()),
+                true,
+                d.directives(),
+                d.union_member_types(),
+            ),
+            EnumTypeDefinition(d) => Self::enumeration(
+                d.syntax(),
+                name(d.name()),
+                false,
+                d.directives(),
+                d.enum_values_definition(),
+            ),
+            EnumTypeExtension(d) => Self::enumeration(
+                d.syntax(),
+                name(d.name()),
+                true,
+                d.directives(),
+                d.enum_values_definition(),
+            ),
+            InputObjectTypeDefinition(d) => Self::input(
+                d.syntax(),
+                name(d.name()),
+                false,
+                d.directives(),
+                d.input_fields_definition(),
+            ),
+            InputObjectTypeExtension(d) => Self::input(
+                d.syntax(),
+                name(d.name()),
+                true,
+                d.directives(),
+                d.input_fields_definition(),
+            ),
+            ScalarTypeDefinition(d) => Self::empty(
+                d.syntax(),
+                name(d.name()),
+                Kind::Scalar,
+                false,
+                d.directives(),
+            ),
+            ScalarTypeExtension(d) => Self::empty(
+                d.syntax(),
+                name(d.name()),
+                Kind::Scalar,
+                true,
+                d.directives(),
+            ),
+            _ => return None,
+        };
+        Some(definition)
+    }
+
+    fn empty(
+        node: &SyntaxNode,
+        type_name: String,
+        kind: Kind,
+        is_extension: bool,
+        directives: Option<ast::Directives>,
+    ) -> Self {
+        Self {
+            node: node.clone(),
+            type_name,
+            kind,
+            is_extension,
+            tags: tags(directives),
+            members_block: None,
+            members: Vec::new(),
+            references: None,
+        }
+    }
+
+    fn with_fields(
+        node: &SyntaxNode,
+        type_name: String,
+        kind: Kind,
+        is_extension: bool,
+        directives: Option<ast::Directives>,
+        interfaces: Option<ast::ImplementsInterfaces>,
+        fields: Option<ast::FieldsDefinition>,
+    ) -> Self {
+        let mut definition = Self::empty(node, type_name, kind, is_extension, directives);
+        definition.references = interfaces.map(|interfaces| {
+            let named_types = interfaces
+                .named_types()
+                .map(|ty| (ty.syntax().clone(), name(ty.name())))
+                .collect();
+            (interfaces.syntax().clone(), named_types)
+        });
+        if let Some(fields) = fields {
+            definition.members = fields
+                .field_definitions()
+                .map(|field| {
+                    let coordinate = format!("{}.{}", definition.type_name, name(field.name()));
+                    let arguments = field
+                        .arguments_definition()
+                        .iter()
+                        .flat_map(|arguments| arguments.input_value_definitions())
+                        .map(|argument| {
+                            let coordinate = format!("{}({}:)", coordinate, name(argument.name()));
+                            Member::input_value(coordinate, argument)
+                        })
+                        .collect();
+                    Member {
+                        node: field.syntax().clone(),
+                        tags: tags(field.directives()),
+                        ty: field.ty().and_then(named_type),
+                        required: false,
+                        arguments_block: field
+                            .arguments_definition()
+                            .map(|arguments| arguments.syntax().clone()),
+                        arguments,
+                        coordinate,
+                    }
+                })
+                .collect();
+            definition.members_block = Some(fields.syntax().clone());
+        }
+        definition
+    }
+
+    fn union(
+        node: &SyntaxNode,
+        type_name: String,
+        is_extension: bool,
+        directives: Option<ast::Directives>,
+        members: Option<ast::UnionMemberTypes>,
+    ) -> Self {
+        let mut definition = Self::empty(node, type_name, Kind::Union, is_extension, directives);
+        definition.references = members.map(|members| {
+            let named_types = members
+                .named_types()
+                .map(|ty| (ty.syntax().clone(), name(ty.name())))
+                .collect();
+            (members.syntax().clone(), named_types)
+        });
+        definition
+    }
+
+    fn enumeration(
+        node: &SyntaxNode,
+        type_name: String,
+        is_extension: bool,
+        directives: Option<ast::Directives>,
+        values: Option<ast::EnumValuesDefinition>,
+    ) -> Self {
+        let mut definition = Self::empty(node, type_name, Kind::Enum, is_extension, directives);
+        if let Some(values) = values {
+            definition.members = values
+                .enum_value_definitions()
+                .map(|value| Member {
+                    node: value.syntax().clone(),
+                    coordinate: format!(
+                        "{}.{}",
+                        definition.type_name,
+                        name(value.enum_value().and_then(|v| v.name()))
+                    ),
+                    tags: tags(value.directives()),
+                    ty: None,
+                    required: false,
+                    arguments_block: None,
+                    arguments: Vec::new(),
+                })
+                .collect();
+            definition.members_block = Some(values.syntax().clone());
+        }
+        definition
+    }
+
+    fn input(
+        node: &SyntaxNode,
+        type_name: String,
+        is_extension: bool,
+        directives: Option<ast::Directives>,
+        fields: Option<ast::InputFieldsDefinition>,
+    ) -> Self {
+        let mut definition = Self::empty(node, type_name, Kind::Input, is_extension, directives);
+        if let Some(fields) = fields {
+            definition.members = fields
+                .input_value_definitions()
+                .map(|field| {
+                    let coordinate = format!("{}.{}", definition.type_name, name(field.name()));
+                    Member::input_value(coordinate, field)
+                })
+                .collect();
+            definition.members_block = Some(fields.syntax().clone());
+        }
+        definition
+    }
+
+    /// Adds the edits that remove the removed parts of this definition to `edits`.
+    fn edits(&self, sdl: &str, removed: &BTreeSet<String>, edits: &mut Vec<Edit>) {
+        let is_removed = |member: &&Member| removed.contains(&member.coordinate);
+        let all_members_removed =
+            !self.members.is_empty() && self.members.iter().all(|m| is_removed(&m));
+        let references_removed = self.references.as_ref().map_or(true, |(_, names)| {
+            names.iter().all(|(_, name)| removed.contains(name))
+        });
+        // an extension that only adds removed members has nothing left to add
+        let has_contents = !self.members.is_empty() || self.references.is_some();
+        let contents_removed = self.members.iter().all(|m| is_removed(&m)) && references_removed;
+        if removed.contains(&self.type_name)
+            || (self.is_extension && has_contents && contents_removed)
+        {
+            edits.push(Edit::remove_definition(sdl, &self.node));
+            return;
+        }
+
+        if let Some((node, named_types)) = &self.references {
+            let kept = named_types
+                .iter()
+                .filter(|(_, name)| !removed.contains(name))
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>();
+            if kept.is_empty() {
+                edits.push(Edit::remove_block(sdl, node));
+            } else if kept.len() < named_types.len() {
+                let separator = if self.kind == Kind::Union {
+                    " | "
+                } else {
+                    " & "
+                };
+                let (start, _) = span(sdl, &named_types[0].0);
+                let (_, end) = span(sdl, &named_types[named_types.len() - 1].0);
+                edits.push(Edit {
+                    start,
+                    end,
+                    replacement: kept.join(separator),
+                });
+            }
+        }
+
+        if all_members_removed {
+            if let Some(members_block) = &self.members_block {
+                edits.push(Edit::remove_block(sdl, members_block));
+            }
+            return;
+        }
+        Edit::remove_items(sdl, &self.members, removed, edits);
+        for member in self.members.iter().filter(|m| !is_removed(m)) {
+            if !member.arguments.is_empty() && member.arguments.iter().all(|a| is_removed(&a)) {
+                if let Some(arguments_block) = &member.arguments_block {
+                    edits.push(Edit::remove_block(sdl, arguments_block));
+                }
+            } else {
+                Edit::remove_items(sdl, &member.arguments, removed, edits);
+            }
+        }
+    }
+}
+
+impl Member {
+    fn input_value(coordinate: String, value: ast::InputValueDefinition) -> Self {
+        Self {
+            node: value.syntax().clone(),
+            coordinate,
+            tags: tags(value.directives()),
+            ty: value.ty().and_then(named_type),
+            required: matches!(value.ty(), Some(ast::Type::NonNullType(_)))
+                && value.default_value().is_none(),
+            arguments_block: None,
+            arguments: Vec::new(),
+        }
+    }
+}
+
+/// Replaces the text between `start` and `end` with `replacement`.
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+impl Edit {
+    /// Removes a definition and the blank lines after it.
+    fn remove_definition(sdl: &str, node: &SyntaxNode) -> Self {
+        let (start, end) = span(sdl, node);
+        let rest = &sdl[end..];
+        Self {
+            start,
+            end: end + rest.len() - rest.trim_start().len(),
+            replacement: String::new(),
+        }
+    }
+
+    /// Removes a block, like a type's fields or a field's arguments, and the space before it.
+    fn remove_block(sdl: &str, node: &SyntaxNode) -> Self {
+        let (start, end) = span(sdl, node);
+        let before = &sdl[..start];
+        Self {
+            start: before.trim_end().len(),
+            end,
+            replacement: String::new(),
+        }
+    }
+
+    /// Removes the removed items of a block of fields, arguments, enum values, or input fields,
+    /// at least one of which is kept. Items on their own lines are removed with their lines, and
+    /// items that share a line are removed with the separator between them and the next kept
+    /// item, or the last one if none follow.
+    fn remove_items(
+        sdl: &str,
+        items: &[Member],
+        removed: &BTreeSet<String>,
+        edits: &mut Vec<Edit>,
+    ) {
+        let is_removed = |i: usize| removed.contains(&items[i].coordinate);
+        let spans = items
+            .iter()
+            .map(|item| span(sdl, &item.node))
+            .collect::<Vec<_>>();
+        let own_lines = spans.iter().all(|&(start, end)| {
+            let line_start = sdl[..start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = sdl[end..].find('\n').map_or(sdl.len(), |i| end + i);
+            sdl[line_start..start].trim().is_empty()
+                && sdl[end..line_end]
+                    .trim_matches(|c: char| c == ',' || c.is_whitespace())
+                    .is_empty()
+        });
+
+        let mut i = 0;
+        while i < items.len() {
+            if !is_removed(i) {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < items.len() && is_removed(i) {
+                i += 1;
+            }
+            let (start, end) = if own_lines {
+                let (start, _) = spans[run_start];
+                let (_, end) = spans[i - 1];
+                (
+                    sdl[..start].rfind('\n').map_or(0, |i| i + 1),
+                    sdl[end..].find('\n').map_or(sdl.len(), |i| end + i + 1),
+                )
+            } else if i < items.len() {
+                (spans[run_start].0, spans[i].0)
+            } else if run_start > 0 {
+                (spans[run_start - 1].1, spans[i - 1].1)
+            } else {
+                continue;
+            };
+            edits.push(Self {
+                start,
+                end,
+                replacement: String::new(),
+            });
+        }
+    }
+}
+
+/// The byte range of a node's text, without the whitespace and commas around it.
+fn span(sdl: &str, node: &SyntaxNode) -> (usize, usize) {
+    let range = node.text_range();
+    let (start, end) = (usize::from(range.start()), usize::from(range.end()));
+    let text = &sdl[start..end];
+    let is_trivia = |c: char| c.is_whitespace() || c == ',';
+    let leading = text.len() - text.trim_start_matches(is_trivia).len();
+    let trailing = text.len() - text.trim_end_matches(is_trivia).len();
+    (start + leading, end - trailing)
+}
+
+/// The names passed to the `@tag` directives in `directives`.
+fn tags(directives: Option<ast::Directives>) -> Vec<String> {
+    directives
+        .iter()
+        .flat_map(|directives| directives.directives())
+        .filter(|directive| name(directive.name()) == "tag")
+        .flat_map(|directive| {
+            directive
+                .arguments()
+                .into_iter()
+                .flat_map(|arguments| arguments.arguments())
+                .filter(|argument| name(argument.name()) == "name")
+                .filter_map(|argument| match argument.value() {
+                    Some(ast::Value::StringValue(tag)) => Some(String::from(tag)),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+fn root_operations(
+    definitions: impl Iterator<Item = ast::RootOperationTypeDefinition>,
+) -> Vec<(&'static str, String)> {
+    definitions
+        .filter_map(|definition| {
+            let operation_type = definition.operation_type()?;
+            let operation = if operation_type.mutation_token().is_some() {
+                "mutation"
+            } else if operation_type.subscription_token().is_some() {
+                "subscription"
+            } else {
+                "query"
+            };
+            Some((operation, name(definition.named_type()?.name())))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::TagFilter;
+
+    const SDL: &str = r#"type Query {
+  products: [Product] @tag(name: "public")
+  product(id: ID!, debug: Boolean @tag(name: "internal")): Product @tag(name: "public")
+  audit: AuditLog
+}
+
+type Product @tag(name: "public") {
+  id: ID!
+  name: String
+  cost: Float @tag(name: "internal")
+  supplier: Supplier
+}
+
+type Supplier @tag(name: "internal") {
+  id: ID!
+}
+
+type AuditLog {
+  entries: [String]
+}
+
+enum Status {
+  ACTIVE
+  HIDDEN @tag(name: "internal")
+}
+
+union SearchResult = Product | Supplier
+"#;
+
+    fn filter(include: &[&str], exclude: &[&str], hide_unreachable_types: bool) -> TagFilter {
+        TagFilter {
+            include_tags: include.iter().map(|tag| tag.to_string()).collect(),
+            exclude_tags: exclude.iter().map(|tag| tag.to_string()).collect(),
+            hide_unreachable_types,
+        }
+    }
+
+    #[test]
+    fn it_filters_by_included_and_excluded_tags() {
+        let filtered = filter(&["public"], &["internal"], false)
+            .apply(SDL)
+            .unwrap();
+        assert_eq!(
+            filtered.sdl,
+            r#"type Query {
+  products: [Product] @tag(name: "public")
+  product(id: ID!): Product @tag(name: "public")
+}
+
+type Product @tag(name: "public") {
+  id: ID!
+  name: String
+}
+
+enum Status {
+  ACTIVE
+}
+
+union SearchResult = Product
+"#
+        );
+        assert_eq!(
+            filtered.removed,
+            [
+                "AuditLog",
+                "AuditLog.entries",
+                "Product.cost",
+                "Product.supplier",
+                "Query.audit",
+                "Query.product(debug:)",
+                "Status.HIDDEN",
+                "Supplier",
+                "Supplier.id",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+        );
+    }
+
+    #[test]
+    fn it_hides_unreachable_types() {
+        let filtered = filter(&[], &["internal"], true).apply(SDL).unwrap();
+        assert!(filtered.removed.contains("Status"));
+        assert!(filtered.removed.contains("SearchResult"));
+        assert!(!filtered.removed.contains("AuditLog"));
+        assert!(filtered
+            .sdl
+            .ends_with("type AuditLog {\n  entries: [String]\n}\n"));
+    }
+
+    #[test]
+    fn it_fails_when_every_query_field_is_removed() {
+        assert!(filter(&["partner"], &[], false).apply(SDL).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use anyhow::anyhow;
+use apollo_parser::{ast, Parser as SdlParser};
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::{name, named_type};
+use crate::{RoverError, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Graph {
+    /// The schema or supergraph schema to render. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's')]
+    #[serde(skip_serializing)]
+    schema: FileDescriptorType,
+
+    /// The diagram language to render the graph in
+    #[arg(long = "diagram", value_enum, default_value_t = DiagramKind::Mermaid)]
+    diagram: DiagramKind,
+
+    /// Only include entities, which are types with a `@key`, along with their keys
+    #[arg(long = "entities-only")]
+    entities_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DiagramKind {
+    /// A Graphviz DOT digraph
+    Dot,
+    /// A Mermaid class diagram, which GitHub and many docs sites render in Markdown
+    Mermaid,
+}
+
+impl Graph {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let mut graph = TypeGraph::from_sdl(&sdl)?;
+        if self.entities_only {
+            graph = graph.entities_only();
+        }
+        let diagram = match self.diagram {
+            DiagramKind::Dot => graph.to_dot(),
+            DiagramKind::Mermaid => graph.to_mermaid(),
+        };
+        Ok(RoverOutput::SchemaGraph { diagram })
+    }
+}
+
+/// The composite types of a schema and the relationships between them. Scalars and enums are
+/// left out, since every field would otherwise point at them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct TypeGraph {
+    nodes: BTreeMap<String, Node>,
+    edges: BTreeSet<Edge>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Node {
+    kind: &'static str,
+    /// The `fields` of each `@key`, which make the type an entity
+    keys: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Edge {
+    from: String,
+    to: String,
+    kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum EdgeKind {
+    /// A field of `from` returns `to`
+    Field(String),
+    /// `from` implements the interface `to`
+    Implements,
+    /// `to` is a member of the union `from`
+    Member,
+}
+
+impl TypeGraph {
+    pub(crate) fn from_sdl(sdl: &str) -> RoverResult<Self> {
+        let tree = SdlParser::new(sdl).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the schema: {}",
+                errors.join(", ")
+            )));
+        }
+
+        let mut graph = TypeGraph::default();
+        let mut edges = Vec::new();
+        for definition in tree.document().definitions() {
+            use ast::Definition::*;
+            match definition {
+                ObjectTypeDefinition(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "object", d.directives());
+                    edges.extend(implements_edges(&type_name, d.implements_interfaces()));
+                    edges.extend(field_edges(&type_name, d.fields_definition()));
+                }
+                ObjectTypeExtension(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "object", d.directives());
+                    edges.extend(implements_edges(&type_name, d.implements_interfaces()));
+                    edges.extend(field_edges(&type_name, d.fields_definition()));
+                }
+                InterfaceTypeDefinition(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "interface", d.directives());
+                    edges.extend(implements_edges(&type_name, d.implements_interfaces()));
+                    edges.extend(field_edges(&type_name, d.fields_definition()));
+                }
+                InterfaceTypeExtension(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "interface", d.directives());
+                    edges.extend(implements_edges(&type_name, d.implements_interfaces()));
+                    edges.extend(field_edges(&type_name, d.fields_definition()));
+                }
+                UnionTypeDefinition(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "union", d.directives());
+                    edges.extend(member_edges(&type_name, d.union_member_types()));
+                }
+                UnionTypeExtension(d) => {
+                    let type_name = name(d.name());
+                    graph.node(&type_name, "union", d.directives());
+                    edges.extend(member_edges(&type_name, d.union_member_types()));
+                }
+                _ => {}
+            }
+        }
+        // edges to scalars, enums, and types that aren't defined aren't drawn
+        graph.edges = edges
+            .into_iter()
+            .filter(|edge| {
+                graph.nodes.contains_key(&edge.from) && graph.nodes.contains_key(&edge.to)
+            })
+            .collect();
+        Ok(graph)
+    }
+
+    fn node(&mut self, type_name: &str, kind: &'static str, directives: Option<ast::Directives>) {
+        // the supergraph's own bookkeeping types, and federation's `_Service`, aren't part of the graph
+        if type_name.starts_with('_') || type_name.contains("__") {
+            return;
+        }
+        let node = self.nodes.entry(type_name.to_string()).or_default();
+        node.kind = kind;
+        node.keys.extend(keys(directives));
+    }
+
+    /// Keeps only the entities, and the fields that connect them.
+    pub(crate) fn entities_only(self) -> Self {
+        let nodes: BTreeMap<String, Node> = self
+            .nodes
+            .into_iter()
+            .filter(|(_, node)| !node.keys.is_empty())
+            .collect();
+        let edges = self
+            .edges
+            .into_iter()
+            .filter(|edge| nodes.contains_key(&edge.from) && nodes.contains_key(&edge.to))
+            .collect();
+        Self { nodes, edges }
+    }
+
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut diagram = String::from("classDiagram\n");
+        for (type_name, node) in &self.nodes {
+            let annotation = match node.kind {
+                "interface" => Some("interface"),
+                "union" => Some("union"),
+                _ if !node.keys.is_empty() => Some("entity"),
+                _ => None,
+            };
+            let Some(annotation) = annotation else {
+                let _ = writeln!(diagram, "  class {}", type_name);
+                continue;
+            };
+            let _ = writeln!(diagram, "  class {} {{", type_name);
+            let _ = writeln!(diagram, "    <<{}>>", annotation);
+            for key in &node.keys {
+                let _ = writeln!(diagram, "    @key({})", key);
+            }
+            diagram.push_str("  }\n");
+        }
+        for edge in &self.edges {
+            let _ = match &edge.kind {
+                EdgeKind::Field(field) => {
+                    writeln!(diagram, "  {} --> {} : {}", edge.from, edge.to, field)
+                }
+                EdgeKind::Implements => writeln!(diagram, "  {} <|.. {}", edge.to, edge.from),
+                EdgeKind::Member => writeln!(diagram, "  {} <|-- {}", edge.from, edge.to),
+            };
+        }
+        diagram.trim_end().to_string()
+    }
+
+    pub(crate) fn to_dot(&self) -> String {
+        let mut diagram = String::from("digraph schema {\n  node [shape=record];\n");
+        for (type_name, node) in &self.nodes {
+            let mut label = match node.kind {
+                "object" if node.keys.is_empty() => type_name.clone(),
+                "object" => format!("{} (entity)", type_name),
+                kind => format!("{} ({})", type_name, kind),
+            };
+            for key in &node.keys {
+                let _ = write!(label, "|@key({})", key);
+            }
+            let _ = writeln!(
+                diagram,
+                "  \"{}\" [label=\"{{{}}}\"];",
+                type_name,
+                escape_dot(&label)
+            );
+        }
+        for edge in &self.edges {
+            let _ = match &edge.kind {
+                EdgeKind::Field(field) => writeln!(
+                    diagram,
+                    "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                    edge.from, edge.to, field
+                ),
+                EdgeKind::Implements => writeln!(
+                    diagram,
+                    "  \"{}\" -> \"{}\" [style=dashed, arrowhead=empty];",
+                    edge.from, edge.to
+                ),
+                EdgeKind::Member => writeln!(
+                    diagram,
+                    "  \"{}\" -> \"{}\" [style=dotted];",
+                    edge.from, edge.to
+                ),
+            };
+        }
+        diagram.push('}');
+        diagram
+    }
+}
+
+fn field_edges(type_name: &str, fields: Option<ast::FieldsDefinition>) -> Vec<Edge> {
+    fields
+        .iter()
+        .flat_map(|fields| fields.field_definitions())
+        .filter_map(|field| {
+            Some(Edge {
+                from: type_name.to_string(),
+                to: field.ty().and_then(named_type)?,
+                kind: EdgeKind::Field(name(field.name())),
+            })
+        })
+        .collect()
+}
+
+fn implements_edges(type_name: &str, interfaces: Option<ast::ImplementsInterfaces>) -> Vec<Edge> {
+    interfaces
+        .iter()
+        .flat_map(|interfaces| interfaces.named_types())
+        .map(|interface| Edge {
+            from: type_name.to_string(),
+            to: name(interface.name()),
+            kind: EdgeKind::Implements,
+        })
+        .collect()
+}
+
+fn member_edges(type_name: &str, members: Option<ast::UnionMemberTypes>) -> Vec<Edge> {
+    members
+        .iter()
+        .flat_map(|members| members.named_types())
+        .map(|member| Edge {
+            from: type_name.to_string(),
+            to: name(member.name()),
+            kind: EdgeKind::Member,
+        })
+        .collect()
+}
+
+/// The `fields` of each `@key` on a subgraph type, or each `key` of `@join__type` on a
+/// supergraph type.
+fn keys(directives: Option<ast::Directives>) -> Vec<String> {
+    directives
+        .iter()
+        .flat_map(|directives| directives.directives())
+        .filter_map(|directive| {
+            let argument_name = match name(directive.name()).as_str() {
+                // federation directives can be renamed with a namespace, like `@federation__key`
+                directive_name if directive_name == "key" || directive_name.ends_with("__key") => {
+                    "fields"
+                }
+                "join__type" => "key",
+                _ => return None,
+            };
+            directive
+                .arguments()
+                .iter()
+                .flat_map(|arguments| arguments.arguments())
+                .find(|argument| name(argument.name()) == argument_name)
+                .and_then(|argument| match argument.value() {
+                    Some(ast::Value::StringValue(fields)) => Some(String::from(fields)),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+fn escape_dot(label: &str) -> String {
+    label
+        .replace('"', "\\\"")
+        .replace('{', "\\{")
+        .replace('}', "\\}")
+        .replace('<', "\\<")
+        .replace('>', "\\>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeGraph;
+
+    const SDL: &str = r#"
+type Query {
+  topProducts(first: Int = 5): [Product!]!
+  search(text: String!): [SearchResult]
+}
+
+interface Node {
+  id: ID!
+}
+
+type Product implements Node @key(fields: "id") @key(fields: "sku") {
+  id: ID!
+  sku: String
+  reviews: [Review]
+}
+
+type Review implements Node @key(fields: "id") {
+  id: ID!
+  body: String
+  author: User
+}
+
+type User {
+  name: String
+}
+
+union SearchResult = Product | User
+"#;
+
+    #[test]
+    fn it_renders_mermaid() {
+        let graph = TypeGraph::from_sdl(SDL).unwrap();
+        assert_eq!(
+            graph.to_mermaid(),
+            r#"classDiagram
+  class Node {
+    <<interface>>
+  }
+  class Product {
+    <<entity>>
+    @key(id)
+    @key(sku)
+  }
+  class Query
+  class Review {
+    <<entity>>
+    @key(id)
+  }
+  class SearchResult {
+    <<union>>
+  }
+  class User
+  Node <|.. Product
+  Product --> Review : reviews
+  Query --> Product : topProducts
+  Query --> SearchResult : search
+  Node <|.. Review
+  Review --> User : author
+  SearchResult <|-- Product
+  SearchResult <|-- User"#
+        );
+    }
+
+    #[test]
+    fn it_renders_entities_as_dot() {
+        let graph = TypeGraph::from_sdl(SDL).unwrap().entities_only();
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph schema {
+  node [shape=record];
+  "Product" [label="{Product (entity)|@key(id)|@key(sku)}"];
+  "Review" [label="{Review (entity)|@key(id)}"];
+  "Product" -> "Review" [label="reviews"];
+}"#
+        );
+    }
+
+    #[test]
+    fn it_reads_keys_from_a_supergraph() {
+        let sdl = r#"
+enum join__Graph {
+  PRODUCTS @join__graph(name: "products", url: "http://localhost:4001")
+}
+
+type Product @join__type(graph: PRODUCTS, key: "upc") {
+  upc: String!
+}
+"#;
+        let graph = TypeGraph::from_sdl(sdl).unwrap().entities_only();
+        assert!(graph.to_mermaid().contains("@key(upc)"));
+    }
+}
@@ -0,0 +1,76 @@
+pub(crate) mod describe_change;
+pub(crate) mod diff;
+mod filter;
+mod graph;
+mod normalize;
+mod pull;
+mod push;
+pub(crate) mod stats;
+
+#[cfg(not(feature = "composition-js"))]
+mod no_watch;
+#[cfg(feature = "composition-js")]
+mod watch;
+
+#[cfg(not(feature = "composition-js"))]
+use no_watch::Watch;
+#[cfg(feature = "composition-js")]
+use watch::Watch;
+
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Schema {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Serialize, Parser)]
+pub enum Command {
+    /// Push a subgraph or supergraph schema to an OCI registry
+    Push(push::Push),
+
+    /// Pull a subgraph or supergraph schema from an OCI registry
+    Pull(pull::Pull),
+
+    /// Print a schema in a canonical form, with sorted types and fields and consistent formatting
+    Normalize(normalize::Normalize),
+
+    /// Summarize the types, fields, deprecations, directives, and entities in a schema
+    Stats(stats::Stats),
+
+    /// Compare two schemas and classify each change as breaking, notable, or safe
+    Diff(diff::Diff),
+
+    /// Summarize the changes between two schemas for a pull request description: new capabilities, deprecations, and breaking risks
+    DescribeChange(describe_change::DescribeChange),
+
+    /// Remove the types and fields of a schema by their `@tag`s, the way a contract would
+    Filter(filter::Filter),
+
+    /// Render the relationships between the types or entities of a schema as a DOT or Mermaid diagram
+    Graph(graph::Graph),
+
+    /// Watch the schema files in a supergraph config, printing LSP-style diagnostics as JSON on every save
+    Watch(Watch),
+}
+
+impl Schema {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        match &self.command {
+            Command::Push(command) => command.run(client_config),
+            Command::Pull(command) => command.run(client_config),
+            Command::Normalize(command) => command.run(),
+            Command::Stats(command) => command.run(client_config),
+            Command::Diff(command) => command.run(),
+            Command::DescribeChange(command) => command.run(),
+            Command::Filter(command) => command.run(),
+            Command::Graph(command) => command.run(),
+            Command::Watch(command) => command.run(client_config),
+        }
+    }
+}
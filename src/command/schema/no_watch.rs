@@ -0,0 +1,26 @@
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Watch {
+    /// The relative path to the supergraph configuration file whose subgraph schema files to watch
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    #[allow(unused)]
+    supergraph_yaml: Utf8PathBuf,
+}
+
+impl Watch {
+    pub fn run(&self, _client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let mut err = RoverError::new(anyhow!(
+            "This version of Rover does not support this command."
+        ));
+        err.set_suggestion(RoverErrorSuggestion::CheckGnuVersion);
+        Err(err)
+    }
+}
@@ -0,0 +1,683 @@
+use anyhow::anyhow;
+use apollo_parser::{
+    ast::{self, AstNode},
+    Parser as SdlParser, SyntaxNode,
+};
+use clap::Parser;
+use rover_std::{Emoji, Fs, Style};
+use serde::Serialize;
+
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::name;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Normalize {
+    /// The schema files to normalize. Reads from stdin if no files are passed, or if you pass `-`.
+    #[arg(value_name = "SCHEMA_PATH")]
+    #[serde(skip_serializing)]
+    files: Vec<FileDescriptorType>,
+
+    /// Remove the descriptions of types, fields, arguments, and directives
+    #[arg(long)]
+    strip_descriptions: bool,
+
+    /// Rewrite files in place instead of printing the normalized schema
+    #[arg(long, conflicts_with = "check")]
+    write: bool,
+
+    /// Fail if any file isn't already normalized, without changing it
+    #[arg(long)]
+    check: bool,
+}
+
+impl Normalize {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let files = if self.files.is_empty() {
+            vec![FileDescriptorType::Stdin]
+        } else {
+            self.files.clone()
+        };
+
+        if !self.write && !self.check {
+            if files.len() > 1 {
+                let mut err = RoverError::new(anyhow!(
+                    "Only one schema can be printed at a time, but {} were passed.",
+                    files.len()
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass `--write` to normalize several files in place, or `--check` to check them."
+                        .to_string(),
+                ));
+                return Err(err);
+            }
+            let sdl = files[0].read_file_descriptor("SDL", &mut std::io::stdin())?;
+            return Ok(RoverOutput::SchemaNormalizeResponse {
+                sdl: Some(normalize_sdl(&sdl, self.strip_descriptions)?),
+                changed_files: Vec::new(),
+            });
+        }
+
+        let mut changed_files = Vec::new();
+        for file in &files {
+            let path = match file {
+                FileDescriptorType::File(path) => path,
+                FileDescriptorType::Stdin => {
+                    return Err(RoverError::new(anyhow!(
+                        "`--write` and `--check` only work with files, not stdin."
+                    )))
+                }
+            };
+            let sdl = Fs::read_file(path)?;
+            let normalized = normalize_sdl(&sdl, self.strip_descriptions)
+                .map_err(|e| RoverError::new(anyhow!("{}: {}", path, e.message())))?;
+            if normalized != sdl {
+                if self.write {
+                    Fs::write_file(path, &normalized)?;
+                    eprintln!("{}normalized {}", Emoji::Sparkle, Style::Path.paint(path));
+                }
+                changed_files.push(path.to_string());
+            }
+        }
+
+        if self.check && !changed_files.is_empty() {
+            let mut err = RoverError::new(anyhow!(
+                "{} of {} schema files {} not normalized: {}",
+                changed_files.len(),
+                files.len(),
+                if changed_files.len() == 1 {
+                    "is"
+                } else {
+                    "are"
+                },
+                changed_files.join(", ")
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Run `rover schema normalize --write{} {}` to normalize them.",
+                if self.strip_descriptions {
+                    " --strip-descriptions"
+                } else {
+                    ""
+                },
+                changed_files.join(" ")
+            )));
+            return Err(err);
+        }
+
+        Ok(RoverOutput::SchemaNormalizeResponse {
+            sdl: None,
+            changed_files,
+        })
+    }
+}
+
+/// Prints `sdl` in a canonical form: the schema definition first, then directive definitions,
+/// then types, each sorted by name, with extensions following the type they extend.
+/// Fields, input fields, enum values, interfaces, and union members are sorted by name,
+/// while arguments and directives keep their order. Comments are dropped.
+pub(crate) fn normalize_sdl(sdl: &str, strip_descriptions: bool) -> RoverResult<String> {
+    let tree = SdlParser::new(sdl).parse();
+    let errors = tree
+        .errors()
+        .map(|e| format!("{} at index {}", e.message(), e.index()))
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(RoverError::new(anyhow!(
+            "Could not parse the schema: {}",
+            errors.join(", ")
+        )));
+    }
+
+    let printer = Printer { strip_descriptions };
+    let mut definitions = Vec::new();
+    for (index, definition) in tree.document().definitions().enumerate() {
+        definitions.push((
+            sort_key(&definition)?,
+            index,
+            printer.definition(&definition),
+        ));
+    }
+    definitions.sort();
+    let mut normalized = definitions
+        .into_iter()
+        .map(|(_, _, printed)| printed)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    normalized.push('\n');
+    Ok(normalized)
+}
+
+/// Orders definitions by section, then by name, with definitions before extensions.
+fn sort_key(definition: &ast::Definition) -> RoverResult<(u8, String, bool)> {
+    use ast::Definition::*;
+    Ok(match definition {
+        SchemaDefinition(_) => (0, String::new(), false),
+        SchemaExtension(_) => (0, String::new(), true),
+        DirectiveDefinition(d) => (1, name(d.name()), false),
+        ScalarTypeDefinition(d) => (2, name(d.name()), false),
+        ObjectTypeDefinition(d) => (2, name(d.name()), false),
+        InterfaceTypeDefinition(d) => (2, name(d.name()), false),
+        UnionTypeDefinition(d) => (2, name(d.name()), false),
+        EnumTypeDefinition(d) => (2, name(d.name()), false),
+        InputObjectTypeDefinition(d) => (2, name(d.name()), false),
+        ScalarTypeExtension(d) => (2, name(d.name()), true),
+        ObjectTypeExtension(d) => (2, name(d.name()), true),
+        InterfaceTypeExtension(d) => (2, name(d.name()), true),
+        UnionTypeExtension(d) => (2, name(d.name()), true),
+        EnumTypeExtension(d) => (2, name(d.name()), true),
+        InputObjectTypeExtension(d) => (2, name(d.name()), true),
+        OperationDefinition(_) | FragmentDefinition(_) => {
+            return Err(RoverError::new(anyhow!(
+                "Schemas can't contain operations or fragments."
+            )))
+        }
+    })
+}
+
+struct Printer {
+    strip_descriptions: bool,
+}
+
+impl Printer {
+    fn definition(&self, definition: &ast::Definition) -> String {
+        use ast::Definition::*;
+        match definition {
+            SchemaDefinition(d) => format!(
+                "{}schema{}{}",
+                self.description(d.description()),
+                directives(d.directives()),
+                block(root_operations(d.root_operation_type_definitions()))
+            ),
+            SchemaExtension(d) => format!(
+                "extend schema{}{}",
+                directives(d.directives()),
+                block(root_operations(d.root_operation_type_definitions()))
+            ),
+            DirectiveDefinition(d) => format!(
+                "{}directive @{}{}{} on {}",
+                self.description(d.description()),
+                name(d.name()),
+                self.arguments_definition(d.arguments_definition()),
+                if d.repeatable_token().is_some() {
+                    " repeatable"
+                } else {
+                    ""
+                },
+                d.directive_locations()
+                    .map(|locations| {
+                        locations
+                            .directive_locations()
+                            .map(|location| token_text(location.syntax()))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+                    .join(" | ")
+            ),
+            ScalarTypeDefinition(d) => format!(
+                "{}scalar {}{}",
+                self.description(d.description()),
+                name(d.name()),
+                directives(d.directives())
+            ),
+            ScalarTypeExtension(d) => format!(
+                "extend scalar {}{}",
+                name(d.name()),
+                directives(d.directives())
+            ),
+            ObjectTypeDefinition(d) => format!(
+                "{}type {}{}{}{}",
+                self.description(d.description()),
+                name(d.name()),
+                implements(d.implements_interfaces()),
+                directives(d.directives()),
+                block(self.fields(d.fields_definition()))
+            ),
+            ObjectTypeExtension(d) => format!(
+                "extend type {}{}{}{}",
+                name(d.name()),
+                implements(d.implements_interfaces()),
+                directives(d.directives()),
+                block(self.fields(d.fields_definition()))
+            ),
+            InterfaceTypeDefinition(d) => format!(
+                "{}interface {}{}{}{}",
+                self.description(d.description()),
+                name(d.name()),
+                implements(d.implements_interfaces()),
+                directives(d.directives()),
+                block(self.fields(d.fields_definition()))
+            ),
+            InterfaceTypeExtension(d) => format!(
+                "extend interface {}{}{}{}",
+                name(d.name()),
+                implements(d.implements_interfaces()),
+                directives(d.directives()),
+                block(self.fields(d.fields_definition()))
+            ),
+            UnionTypeDefinition(d) => format!(
+                "{}union {}{}{}",
+                self.description(d.description()),
+                name(d.name()),
+                directives(d.directives()),
+                union_members(d.union_member_types())
+            ),
+            UnionTypeExtension(d) => format!(
+                "extend union {}{}{}",
+                name(d.name()),
+                directives(d.directives()),
+                union_members(d.union_member_types())
+            ),
+            EnumTypeDefinition(d) => format!(
+                "{}enum {}{}{}",
+                self.description(d.description()),
+                name(d.name()),
+                directives(d.directives()),
+                block(self.enum_values(d.enum_values_definition()))
+            ),
+            EnumTypeExtension(d) => format!(
+                "extend enum {}{}{}",
+                name(d.name()),
+                directives(d.directives()),
+                block(self.enum_values(d.enum_values_definition()))
+            ),
+            InputObjectTypeDefinition(d) => format!(
+                "{}input {}{}{}",
+                self.description(d.description()),
+                name(d.name()),
+                directives(d.directives()),
+                block(self.input_fields(d.input_fields_definition()))
+            ),
+            InputObjectTypeExtension(d) => format!(
+                "extend input {}{}{}",
+                name(d.name()),
+                directives(d.directives()),
+                block(self.input_fields(d.input_fields_definition()))
+            ),
+            // rejected by `sort_key`
+            OperationDefinition(_) | FragmentDefinition(_) => String::new(),
+        }
+    }
+
+    /// Prints a description as a block string on the line(s) before what it describes.
+    fn description(&self, description: Option<ast::Description>) -> String {
+        let description = match description.and_then(|d| d.string_value()) {
+            Some(description) if !self.strip_descriptions => {
+                if token_text(description.syntax()).starts_with(r#"""""#) {
+                    block_string_value(&String::from(description))
+                } else {
+                    String::from(description)
+                }
+            }
+            _ => return String::new(),
+        };
+        let escaped = description.replace(r#"""""#, r#"\""""#);
+        if escaped.contains('\n') || escaped.ends_with('"') || escaped.ends_with('\\') {
+            format!("\"\"\"\n{}\n\"\"\"\n", escaped)
+        } else {
+            format!("\"\"\"{}\"\"\"\n", escaped)
+        }
+    }
+
+    fn fields(&self, fields: Option<ast::FieldsDefinition>) -> Vec<String> {
+        let mut fields = fields
+            .map(|fields| {
+                fields
+                    .field_definitions()
+                    .map(|field| {
+                        let printed = format!(
+                            "{}{}{}: {}{}",
+                            self.description(field.description()),
+                            name(field.name()),
+                            self.arguments_definition(field.arguments_definition()),
+                            ty(field.ty()),
+                            directives(field.directives())
+                        );
+                        (name(field.name()), printed)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        fields.sort();
+        fields.into_iter().map(|(_, printed)| printed).collect()
+    }
+
+    fn input_fields(&self, fields: Option<ast::InputFieldsDefinition>) -> Vec<String> {
+        let mut fields = fields
+            .map(|fields| {
+                fields
+                    .input_value_definitions()
+                    .map(|field| (name(field.name()), self.input_value(&field)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        fields.sort();
+        fields.into_iter().map(|(_, printed)| printed).collect()
+    }
+
+    fn enum_values(&self, values: Option<ast::EnumValuesDefinition>) -> Vec<String> {
+        let mut values = values
+            .map(|values| {
+                values
+                    .enum_value_definitions()
+                    .map(|value| {
+                        let value_name = name(value.enum_value().and_then(|v| v.name()));
+                        let printed = format!(
+                            "{}{}{}",
+                            self.description(value.description()),
+                            value_name,
+                            directives(value.directives())
+                        );
+                        (value_name, printed)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        values.sort();
+        values.into_iter().map(|(_, printed)| printed).collect()
+    }
+
+    /// Prints arguments on one line, unless any of them have descriptions.
+    fn arguments_definition(&self, arguments: Option<ast::ArgumentsDefinition>) -> String {
+        let arguments = arguments
+            .map(|arguments| arguments.input_value_definitions().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if arguments.is_empty() {
+            return String::new();
+        }
+        let printed = arguments
+            .iter()
+            .map(|argument| self.input_value(argument))
+            .collect::<Vec<_>>();
+        if printed.iter().any(|argument| argument.contains('\n')) {
+            format!("(\n{}\n)", indent(&printed.join("\n")))
+        } else {
+            format!("({})", printed.join(", "))
+        }
+    }
+
+    fn input_value(&self, input_value: &ast::InputValueDefinition) -> String {
+        format!(
+            "{}{}: {}{}{}",
+            self.description(input_value.description()),
+            name(input_value.name()),
+            ty(input_value.ty()),
+            input_value
+                .default_value()
+                .map(|default| format!(" = {}", value(default.value())))
+                .unwrap_or_default(),
+            directives(input_value.directives())
+        )
+    }
+}
+
+fn root_operations(operations: ast::AstChildren<ast::RootOperationTypeDefinition>) -> Vec<String> {
+    let mut operations = operations
+        .map(|operation| {
+            let operation_type = operation
+                .operation_type()
+                .map(|t| token_text(t.syntax()))
+                .unwrap_or_default();
+            let rank = match operation_type.as_str() {
+                "query" => 0,
+                "mutation" => 1,
+                _ => 2,
+            };
+            (
+                rank,
+                format!(
+                    "{}: {}",
+                    operation_type,
+                    name(operation.named_type().and_then(|t| t.name()))
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    operations.sort();
+    operations.into_iter().map(|(_, printed)| printed).collect()
+}
+
+fn implements(interfaces: Option<ast::ImplementsInterfaces>) -> String {
+    let mut interfaces = interfaces
+        .map(|interfaces| {
+            interfaces
+                .named_types()
+                .map(|t| name(t.name()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if interfaces.is_empty() {
+        return String::new();
+    }
+    interfaces.sort();
+    format!(" implements {}", interfaces.join(" & "))
+}
+
+fn union_members(members: Option<ast::UnionMemberTypes>) -> String {
+    let mut members = members
+        .map(|members| {
+            members
+                .named_types()
+                .map(|t| name(t.name()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if members.is_empty() {
+        return String::new();
+    }
+    members.sort();
+    format!(" = {}", members.join(" | "))
+}
+
+fn directives(directives: Option<ast::Directives>) -> String {
+    directives
+        .map(|directives| {
+            directives
+                .directives()
+                .map(|directive| {
+                    let arguments = directive
+                        .arguments()
+                        .map(|arguments| {
+                            arguments
+                                .arguments()
+                                .map(|argument| {
+                                    format!(
+                                        "{}: {}",
+                                        name(argument.name()),
+                                        value(argument.value())
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    if arguments.is_empty() {
+                        format!(" @{}", name(directive.name()))
+                    } else {
+                        format!(" @{}({})", name(directive.name()), arguments.join(", "))
+                    }
+                })
+                .collect::<String>()
+        })
+        .unwrap_or_default()
+}
+
+/// Prints a type reference, like `[String!]!`.
+pub(crate) fn ty(ty: Option<ast::Type>) -> String {
+    match ty {
+        Some(ast::Type::NamedType(t)) => name(t.name()),
+        Some(ast::Type::ListType(t)) => format!("[{}]", self::ty(t.ty())),
+        Some(ast::Type::NonNullType(t)) => match (t.named_type(), t.list_type()) {
+            (Some(t), _) => format!("{}!", name(t.name())),
+            (None, Some(t)) => format!("[{}]!", self::ty(t.ty())),
+            (None, None) => String::new(),
+        },
+        None => String::new(),
+    }
+}
+
+/// Prints a value in a canonical form, ignoring whitespace and commas.
+pub(crate) fn value(value: Option<ast::Value>) -> String {
+    match value {
+        Some(ast::Value::Variable(v)) => format!("${}", name(v.name())),
+        Some(ast::Value::StringValue(v)) => {
+            serde_json::to_string(&String::from(v)).unwrap_or_default()
+        }
+        Some(ast::Value::FloatValue(v)) => token_text(v.syntax()),
+        Some(ast::Value::IntValue(v)) => token_text(v.syntax()),
+        Some(ast::Value::BooleanValue(v)) => token_text(v.syntax()),
+        Some(ast::Value::NullValue(v)) => token_text(v.syntax()),
+        Some(ast::Value::EnumValue(v)) => name(v.name()),
+        Some(ast::Value::ListValue(v)) => format!(
+            "[{}]",
+            v.values()
+                .map(|v| self::value(Some(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(ast::Value::ObjectValue(v)) => format!(
+            "{{{}}}",
+            v.object_fields()
+                .map(|field| format!("{}: {}", name(field.name()), self::value(field.value())))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => String::new(),
+    }
+}
+
+/// The text of a node's first token, skipping whitespace, commas, and comments.
+fn token_text(node: &SyntaxNode) -> String {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .map(|token| token.text().to_string())
+        .find(|text| {
+            // apollo-parser doesn't export its token kinds, so these are told apart by their text
+            let text = text.trim();
+            !text.is_empty() && text != "," && !text.starts_with('#')
+        })
+        .unwrap_or_default()
+}
+
+/// Removes the common indentation and surrounding blank lines from a block string,
+/// as described in https://spec.graphql.org/October2021/#BlockStringValue().
+fn block_string_value(raw: &str) -> String {
+    let lines = raw.lines().collect::<Vec<_>>();
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.to_string()
+            } else {
+                line.get(common_indent..).unwrap_or_default().to_string()
+            }
+        })
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prints items in braces, one per line.
+fn block(items: Vec<String>) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!(" {{\n{}\n}}", indent(&items.join("\n")))
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_sdl;
+
+    const SCHEMA: &str = r#"
+# the products subgraph
+type Query { topProducts(first: Int = 5, after: String): [Product!]!   product(id: ID!): Product }
+
+"A product in the catalog"
+type Product @key(fields: "upc") {
+  upc: String!
+  """
+  The product's name,
+  as shown to customers
+  """
+  name: String
+  price(currency: Currency = USD): Int @deprecated(reason: "Use cost")
+}
+
+enum Currency { USD EUR }
+"#;
+
+    #[test]
+    fn it_sorts_and_formats_definitions() {
+        assert_eq!(
+            normalize_sdl(SCHEMA, false).unwrap(),
+            r#"enum Currency {
+  EUR
+  USD
+}
+
+"""A product in the catalog"""
+type Product @key(fields: "upc") {
+  """
+  The product's name,
+  as shown to customers
+  """
+  name: String
+  price(currency: Currency = USD): Int @deprecated(reason: "Use cost")
+  upc: String!
+}
+
+type Query {
+  product(id: ID!): Product
+  topProducts(first: Int = 5, after: String): [Product!]!
+}
+"#
+        );
+    }
+
+    #[test]
+    fn it_strips_descriptions() {
+        let normalized = normalize_sdl(SCHEMA, true).unwrap();
+        assert!(!normalized.contains("\"\"\""));
+        assert!(normalized.contains("type Product @key(fields: \"upc\") {\n  name: String\n"));
+    }
+
+    #[test]
+    fn it_is_idempotent() {
+        let normalized = normalize_sdl(SCHEMA, false).unwrap();
+        assert_eq!(normalize_sdl(&normalized, false).unwrap(), normalized);
+    }
+
+    #[test]
+    fn it_rejects_invalid_schemas() {
+        assert!(normalize_sdl("type Query {", false).is_err());
+        assert!(normalize_sdl("query { me }", false).is_err());
+    }
+}
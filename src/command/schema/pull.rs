@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+use clap::Parser;
+use rover_client::shared::{FetchResponse, Sdl, SdlType};
+use rover_std::Style;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::utils::oci::{
+    OciClient, OciReference, SUBGRAPH_SCHEMA_MEDIA_TYPE, SUPERGRAPH_SCHEMA_MEDIA_TYPE,
+};
+use crate::{RoverError, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Pull {
+    /// The OCI reference to pull the schema from, like `oci://ghcr.io/my-org/supergraph:1.2.3`
+    #[arg(value_name = "OCI_REF")]
+    #[serde(skip_serializing)]
+    reference: OciReference,
+}
+
+impl Pull {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        eprintln!(
+            "Pulling schema from {}",
+            Style::Link.paint(self.reference.to_string())
+        );
+        let client = OciClient::new(client_config.get_reqwest_client()?);
+        let artifact = client.pull(&self.reference)?;
+        let r#type = match artifact.media_type.as_str() {
+            SUBGRAPH_SCHEMA_MEDIA_TYPE => SdlType::Subgraph { routing_url: None },
+            SUPERGRAPH_SCHEMA_MEDIA_TYPE => SdlType::Supergraph,
+            media_type => {
+                return Err(RoverError::new(anyhow!(
+                    "{} is a '{}' artifact, not a schema pushed by Rover.",
+                    self.reference,
+                    media_type
+                )))
+            }
+        };
+
+        Ok(RoverOutput::FetchResponse(FetchResponse {
+            sdl: Sdl {
+                contents: artifact.content,
+                r#type,
+            },
+        }))
+    }
+}
@@ -0,0 +1,73 @@
+use clap::{Parser, ValueEnum};
+use rover_std::Style;
+use serde::Serialize;
+
+use crate::options::SchemaOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::oci::{
+    OciClient, OciReference, SUBGRAPH_SCHEMA_MEDIA_TYPE, SUPERGRAPH_SCHEMA_MEDIA_TYPE,
+};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Push {
+    /// The OCI reference to push the schema to, like `oci://ghcr.io/my-org/accounts-schema:1.2.3`
+    #[arg(value_name = "OCI_REF")]
+    #[serde(skip_serializing)]
+    reference: OciReference,
+
+    #[clap(flatten)]
+    #[serde(skip_serializing)]
+    schema: SchemaOpt,
+
+    /// The kind of schema being pushed
+    #[arg(long = "type", value_enum, default_value_t = SchemaArtifactType::Subgraph)]
+    artifact_type: SchemaArtifactType,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ValueEnum)]
+pub(crate) enum SchemaArtifactType {
+    Subgraph,
+    Supergraph,
+}
+
+impl SchemaArtifactType {
+    fn media_type(&self) -> &'static str {
+        match self {
+            Self::Subgraph => SUBGRAPH_SCHEMA_MEDIA_TYPE,
+            Self::Supergraph => SUPERGRAPH_SCHEMA_MEDIA_TYPE,
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Subgraph => "schema.graphql",
+            Self::Supergraph => "supergraph.graphql",
+        }
+    }
+}
+
+impl Push {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let schema = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+
+        eprintln!(
+            "Pushing schema to {}",
+            Style::Link.paint(self.reference.to_string())
+        );
+        let client = OciClient::new(client_config.get_reqwest_client()?);
+        let digest = client.push(
+            &self.reference,
+            self.artifact_type.media_type(),
+            self.artifact_type.file_name(),
+            &schema,
+        )?;
+
+        Ok(RoverOutput::SchemaPushResponse {
+            reference: self.reference.to_string(),
+            digest,
+        })
+    }
+}
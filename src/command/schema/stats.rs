@@ -0,0 +1,403 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+use apollo_parser::{ast, Parser as SdlParser};
+use clap::Parser;
+use rover_client::operations::graph::fetch::{self as graph_fetch, GraphFetchInput};
+use rover_client::operations::subgraph::fetch::{self as subgraph_fetch, SubgraphFetchInput};
+use rover_client::shared::GraphRef;
+use rover_std::Style;
+use serde::Serialize;
+
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::sdl::name;
+use crate::{RoverError, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Stats {
+    /// The schema file to summarize. You can pass `-` to use stdin instead of a file.
+    #[arg(long, short = 's', conflicts_with = "graph_ref")]
+    #[serde(skip_serializing)]
+    schema: Option<FileDescriptorType>,
+
+    /// The graph ref to fetch the schema from, like `my-graph@current`.
+    /// Summarizes the graph's API schema, unless `--name` is passed.
+    #[arg(value_name = "GRAPH_REF", required_unless_present = "schema")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<GraphRef>,
+
+    /// The subgraph of GRAPH_REF to summarize
+    #[arg(long = "name", short = 'n', requires = "graph_ref")]
+    #[serde(skip_serializing)]
+    subgraph_name: Option<String>,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+}
+
+impl Stats {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let sdl = match (&self.schema, &self.graph_ref) {
+            (Some(schema), _) => schema.read_file_descriptor("SDL", &mut std::io::stdin())?,
+            (None, Some(graph_ref)) => self.fetch(graph_ref, client_config)?,
+            (None, None) => unreachable!("clap requires either --schema or a graph ref"),
+        };
+        Ok(RoverOutput::SchemaStats(SchemaStats::from_sdl(&sdl)?))
+    }
+
+    fn fetch(
+        &self,
+        graph_ref: &GraphRef,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<String> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        eprintln!(
+            "Fetching SDL from {} using credentials from the {} profile.",
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+        let fetch_response = match &self.subgraph_name {
+            Some(subgraph_name) => subgraph_fetch::run(
+                SubgraphFetchInput {
+                    graph_ref: graph_ref.clone(),
+                    subgraph_name: subgraph_name.clone(),
+                },
+                &client,
+            )?,
+            None => graph_fetch::run(
+                GraphFetchInput {
+                    graph_ref: graph_ref.clone(),
+                },
+                &client,
+            )?,
+        };
+        Ok(fetch_response.sdl.contents)
+    }
+}
+
+/// A summary of the size and shape of a schema.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct SchemaStats {
+    pub(crate) types: TypeCounts,
+    /// Fields of object and interface types
+    pub(crate) fields: usize,
+    pub(crate) input_fields: usize,
+    pub(crate) enum_values: usize,
+    /// Arguments of object and interface fields
+    pub(crate) arguments: usize,
+    pub(crate) deprecated: DeprecationCounts,
+    /// How many times each directive is applied
+    pub(crate) directive_usages: BTreeMap<String, usize>,
+    /// The number of federation entities, which are types with a `@key`
+    pub(crate) entity_count: usize,
+    pub(crate) entities: Vec<String>,
+}
+
+/// The number of types of each kind, not counting built-in scalars.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub(crate) struct TypeCounts {
+    pub(crate) object: usize,
+    pub(crate) interface: usize,
+    pub(crate) union: usize,
+    pub(crate) r#enum: usize,
+    pub(crate) input: usize,
+    pub(crate) scalar: usize,
+    pub(crate) total: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub(crate) struct DeprecationCounts {
+    pub(crate) fields: usize,
+    pub(crate) input_fields: usize,
+    pub(crate) enum_values: usize,
+    pub(crate) arguments: usize,
+    pub(crate) total: usize,
+}
+
+impl SchemaStats {
+    /// Summarizes `sdl`, counting extensions toward the types they extend.
+    pub(crate) fn from_sdl(sdl: &str) -> RoverResult<Self> {
+        let tree = SdlParser::new(sdl).parse();
+        let errors = tree
+            .errors()
+            .map(|e| format!("{} at index {}", e.message(), e.index()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "Could not parse the schema: {}",
+                errors.join(", ")
+            )));
+        }
+
+        let mut counter = StatsCounter::default();
+        for definition in tree.document().definitions() {
+            counter.definition(definition);
+        }
+        Ok(counter.finish())
+    }
+}
+
+#[derive(Default)]
+struct StatsCounter {
+    stats: SchemaStats,
+    objects: BTreeSet<String>,
+    interfaces: BTreeSet<String>,
+    unions: BTreeSet<String>,
+    enums: BTreeSet<String>,
+    inputs: BTreeSet<String>,
+    scalars: BTreeSet<String>,
+    entities: BTreeSet<String>,
+}
+
+impl StatsCounter {
+    fn definition(&mut self, definition: ast::Definition) {
+        use ast::Definition::*;
+        match definition {
+            SchemaDefinition(d) => {
+                self.directives(d.directives());
+            }
+            SchemaExtension(d) => {
+                self.directives(d.directives());
+            }
+            ScalarTypeDefinition(d) => {
+                self.scalars.insert(name(d.name()));
+                self.directives(d.directives());
+            }
+            ScalarTypeExtension(d) => {
+                self.scalars.insert(name(d.name()));
+                self.directives(d.directives());
+            }
+            ObjectTypeDefinition(d) => {
+                self.objects.insert(name(d.name()));
+                self.entity(name(d.name()), d.directives());
+                self.fields(d.fields_definition());
+            }
+            ObjectTypeExtension(d) => {
+                self.objects.insert(name(d.name()));
+                self.entity(name(d.name()), d.directives());
+                self.fields(d.fields_definition());
+            }
+            InterfaceTypeDefinition(d) => {
+                self.interfaces.insert(name(d.name()));
+                self.entity(name(d.name()), d.directives());
+                self.fields(d.fields_definition());
+            }
+            InterfaceTypeExtension(d) => {
+                self.interfaces.insert(name(d.name()));
+                self.entity(name(d.name()), d.directives());
+                self.fields(d.fields_definition());
+            }
+            UnionTypeDefinition(d) => {
+                self.unions.insert(name(d.name()));
+                self.directives(d.directives());
+            }
+            UnionTypeExtension(d) => {
+                self.unions.insert(name(d.name()));
+                self.directives(d.directives());
+            }
+            EnumTypeDefinition(d) => {
+                self.enums.insert(name(d.name()));
+                self.directives(d.directives());
+                self.enum_values(d.enum_values_definition());
+            }
+            EnumTypeExtension(d) => {
+                self.enums.insert(name(d.name()));
+                self.directives(d.directives());
+                self.enum_values(d.enum_values_definition());
+            }
+            InputObjectTypeDefinition(d) => {
+                self.inputs.insert(name(d.name()));
+                self.directives(d.directives());
+                self.input_fields(d.input_fields_definition());
+            }
+            InputObjectTypeExtension(d) => {
+                self.inputs.insert(name(d.name()));
+                self.directives(d.directives());
+                self.input_fields(d.input_fields_definition());
+            }
+            DirectiveDefinition(_) | OperationDefinition(_) | FragmentDefinition(_) => {}
+        }
+    }
+
+    fn entity(&mut self, type_name: String, directives: Option<ast::Directives>) {
+        let applied = self.directives(directives);
+        // federation directives can be renamed with a namespace, like `@federation__key`
+        if applied
+            .iter()
+            .any(|directive| directive == "key" || directive.ends_with("__key"))
+        {
+            self.entities.insert(type_name);
+        }
+    }
+
+    fn fields(&mut self, fields: Option<ast::FieldsDefinition>) {
+        for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+            self.stats.fields += 1;
+            if is_deprecated(&self.directives(field.directives())) {
+                self.stats.deprecated.fields += 1;
+            }
+            for argument in field
+                .arguments_definition()
+                .iter()
+                .flat_map(|arguments| arguments.input_value_definitions())
+            {
+                self.stats.arguments += 1;
+                if is_deprecated(&self.directives(argument.directives())) {
+                    self.stats.deprecated.arguments += 1;
+                }
+            }
+        }
+    }
+
+    fn input_fields(&mut self, fields: Option<ast::InputFieldsDefinition>) {
+        for field in fields
+            .iter()
+            .flat_map(|fields| fields.input_value_definitions())
+        {
+            self.stats.input_fields += 1;
+            if is_deprecated(&self.directives(field.directives())) {
+                self.stats.deprecated.input_fields += 1;
+            }
+        }
+    }
+
+    fn enum_values(&mut self, values: Option<ast::EnumValuesDefinition>) {
+        for value in values
+            .iter()
+            .flat_map(|values| values.enum_value_definitions())
+        {
+            self.stats.enum_values += 1;
+            if is_deprecated(&self.directives(value.directives())) {
+                self.stats.deprecated.enum_values += 1;
+            }
+        }
+    }
+
+    /// Records the usage of each applied directive, returning their names.
+    fn directives(&mut self, directives: Option<ast::Directives>) -> Vec<String> {
+        let applied = directives
+            .iter()
+            .flat_map(|directives| directives.directives())
+            .map(|directive| name(directive.name()))
+            .collect::<Vec<_>>();
+        for directive in &applied {
+            *self
+                .stats
+                .directive_usages
+                .entry(directive.clone())
+                .or_default() += 1;
+        }
+        applied
+    }
+
+    fn finish(mut self) -> SchemaStats {
+        let types = &mut self.stats.types;
+        types.object = self.objects.len();
+        types.interface = self.interfaces.len();
+        types.union = self.unions.len();
+        types.r#enum = self.enums.len();
+        types.input = self.inputs.len();
+        types.scalar = self.scalars.len();
+        types.total = types.object
+            + types.interface
+            + types.union
+            + types.r#enum
+            + types.input
+            + types.scalar;
+
+        let deprecated = &mut self.stats.deprecated;
+        deprecated.total = deprecated.fields
+            + deprecated.input_fields
+            + deprecated.enum_values
+            + deprecated.arguments;
+
+        self.stats.entity_count = self.entities.len();
+        self.stats.entities = self.entities.into_iter().collect();
+        self.stats
+    }
+}
+
+fn is_deprecated(directives: &[String]) -> bool {
+    directives.iter().any(|directive| directive == "deprecated")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::SchemaStats;
+
+    #[test]
+    fn it_counts_types_fields_deprecations_and_entities() {
+        let stats = SchemaStats::from_sdl(
+            r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+            scalar DateTime
+
+            type Query {
+              products(first: Int, after: String @deprecated): [Product!]!
+              search(term: String!): [SearchResult!]!
+            }
+
+            type Product @key(fields: "upc") @key(fields: "sku") {
+              upc: String!
+              sku: String!
+              name: String @deprecated(reason: "Use title")
+              title: String
+              status: Status
+              createdAt: DateTime
+            }
+
+            extend type Product {
+              reviewCount: Int
+            }
+
+            type Review @federation__key(fields: "id") {
+              id: ID!
+            }
+
+            union SearchResult = Product | Review
+
+            enum Status { ACTIVE DISCONTINUED @deprecated }
+
+            input ProductFilter { status: Status, legacy: Boolean @deprecated }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.types.object, 3);
+        assert_eq!(stats.types.union, 1);
+        assert_eq!(stats.types.r#enum, 1);
+        assert_eq!(stats.types.input, 1);
+        assert_eq!(stats.types.scalar, 1);
+        assert_eq!(stats.types.total, 7);
+        assert_eq!(stats.fields, 10);
+        assert_eq!(stats.arguments, 3);
+        assert_eq!(stats.input_fields, 2);
+        assert_eq!(stats.enum_values, 2);
+        assert_eq!(stats.deprecated.fields, 1);
+        assert_eq!(stats.deprecated.arguments, 1);
+        assert_eq!(stats.deprecated.enum_values, 1);
+        assert_eq!(stats.deprecated.input_fields, 1);
+        assert_eq!(stats.deprecated.total, 4);
+        assert_eq!(
+            stats.directive_usages,
+            BTreeMap::from([
+                ("deprecated".to_string(), 4),
+                ("federation__key".to_string(), 1),
+                ("key".to_string(), 2),
+                ("link".to_string(), 1),
+            ])
+        );
+        assert_eq!(stats.entity_count, 2);
+        assert_eq!(stats.entities, vec!["Product", "Review"]);
+    }
+
+    #[test]
+    fn it_rejects_invalid_schemas() {
+        assert!(SchemaStats::from_sdl("type Query {").is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Watches the subgraph schema files in a supergraph config and reports their problems as
+//! LSP-style diagnostics, so editors can show Rover's own syntax, lint, and composition
+//! feedback inline on every save.
+//!
+//! After each run, one JSON object is printed to stdout per watched file, even when it has no
+//! diagnostics, so editors can clear problems that have been fixed.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use apollo_federation_types::build::{BuildErrors, BuildHint, SubgraphDefinition};
+use apollo_federation_types::config::SupergraphConfig;
+use apollo_parser::Parser as SdlParser;
+use calm_io::stdoutln;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use crossbeam_channel::unbounded;
+use rover_client::blocking::StudioClient;
+use rover_client::operations::subgraph::lint::{self, LintSubgraphInput};
+use rover_client::shared::{GraphRef, LintResponse};
+use rover_client::RoverClientError;
+use rover_std::Fs;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::command::supergraph::compose::Compose;
+use crate::command::supergraph::{expand_supergraph_yaml, resolve_supergraph_yaml_with_sources};
+use crate::options::PluginOpts;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::parsers::FileDescriptorType;
+use crate::utils::source_map::{without_locations, SubgraphSourceMap};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// How long a file must go without changing before it's checked again
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFORMATION: u8 = 3;
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Watch {
+    /// The relative path to the supergraph configuration file whose subgraph schema files to watch
+    #[arg(long = "config")]
+    #[serde(skip_serializing)]
+    supergraph_yaml: Utf8PathBuf,
+
+    /// Compose the subgraphs locally on every save, in addition to checking each schema's syntax
+    #[arg(long)]
+    check: bool,
+
+    /// Also lint each subgraph with the rules configured for this graph in GraphOS. Requires `--check`.
+    #[arg(long = "graph-ref", value_name = "GRAPH_REF", requires = "check")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<GraphRef>,
+
+    #[clap(flatten)]
+    opts: PluginOpts,
+}
+
+/// The diagnostics for one file, like the parameters of LSP's `textDocument/publishDiagnostics`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct FileDiagnostics {
+    file: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Diagnostic {
+    range: Range,
+    /// 1 for errors, 2 for warnings, and 3 for information, as in LSP
+    severity: u8,
+    source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+/// A zero-based line and character, as in LSP
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+impl Position {
+    fn at_byte_offset(source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        Self {
+            line: before.matches('\n').count(),
+            character: before[line_start..].chars().count(),
+        }
+    }
+}
+
+impl Watch {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let config_dir = self.supergraph_yaml.parent();
+        let supergraph_config = FileDescriptorType::File(self.supergraph_yaml.clone())
+            .read_file_descriptor("supergraph config", &mut io::stdin())
+            .and_then(|contents| {
+                expand_supergraph_yaml(&contents, config_dir, &[], &client_config, self.opts.locked)
+            })?;
+        let source_map = SubgraphSourceMap::from_supergraph_config(&supergraph_config, config_dir);
+        if source_map.iter().next().is_none() {
+            let mut err = RoverError::new(anyhow!(
+                "None of the subgraphs in {} read their schema from a file, so there's nothing to watch.",
+                &self.supergraph_yaml
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Point a subgraph's `schema.file` at the schema file you're editing.".to_string(),
+            ));
+            return Err(err);
+        }
+
+        // subgraphs that aren't read from files are resolved once, rather than on every save
+        let resolved_config = if self.check {
+            Some(
+                resolve_supergraph_yaml_with_sources(
+                    &FileDescriptorType::File(self.supergraph_yaml.clone()),
+                    &[],
+                    client_config.clone(),
+                    &self.opts.profile,
+                    self.opts.locked,
+                    None,
+                )?
+                .0,
+            )
+        } else {
+            None
+        };
+        let lint_client = match &self.graph_ref {
+            Some(_) => Some(client_config.get_authenticated_client(&self.opts.profile)?),
+            None => None,
+        };
+
+        let (tx, rx) = unbounded();
+        for (_, file) in source_map.iter() {
+            Fs::watch_file_with_delay(file, tx.clone(), WATCH_DEBOUNCE);
+        }
+        loop {
+            let diagnostics = self.check_files(
+                &client_config,
+                &source_map,
+                resolved_config.as_ref(),
+                lint_client.as_ref(),
+            );
+            for file_diagnostics in diagnostics {
+                stdoutln!("{}", serde_json::to_string(&file_diagnostics)?)?;
+            }
+            rx.recv().map_err(|e| anyhow!(e))?;
+            // saving several files at once only needs one more run
+            while rx.try_recv().is_ok() {}
+        }
+    }
+
+    fn check_files(
+        &self,
+        client_config: &StudioClientConfig,
+        source_map: &SubgraphSourceMap,
+        resolved_config: Option<&SupergraphConfig>,
+        lint_client: Option<&StudioClient>,
+    ) -> Vec<FileDiagnostics> {
+        let config_file = self.supergraph_yaml.to_string();
+        let mut diagnostics: BTreeMap<String, Vec<Diagnostic>> = BTreeMap::new();
+        diagnostics.insert(config_file.clone(), Vec::new());
+        let mut schemas = BTreeMap::new();
+        for (subgraph_name, file) in source_map.iter() {
+            let file_diagnostics = diagnostics.entry(file.to_string()).or_default();
+            match Fs::read_file(file) {
+                Ok(sdl) => {
+                    file_diagnostics.extend(syntax_diagnostics(&sdl));
+                    schemas.insert(subgraph_name.clone(), (file.clone(), sdl));
+                }
+                Err(e) => file_diagnostics.push(file_diagnostic(
+                    SEVERITY_ERROR,
+                    "rover",
+                    None,
+                    e.to_string(),
+                )),
+            }
+        }
+
+        // composition would only repeat the syntax errors
+        let has_errors = diagnostics
+            .values()
+            .flatten()
+            .any(|diagnostic| diagnostic.severity == SEVERITY_ERROR);
+        if let (Some(resolved_config), false) = (resolved_config, has_errors) {
+            for (file, diagnostic) in
+                self.compose(client_config, source_map, resolved_config, &schemas)
+            {
+                diagnostics
+                    .entry(file.unwrap_or_else(|| config_file.clone()))
+                    .or_default()
+                    .push(diagnostic);
+            }
+        }
+
+        if let (Some(graph_ref), Some(client)) = (&self.graph_ref, lint_client) {
+            for (subgraph_name, (file, sdl)) in &schemas {
+                let lint_diagnostics = match lint::run(
+                    LintSubgraphInput {
+                        graph_ref: graph_ref.clone(),
+                        file_name: file.to_string(),
+                        proposed_schema: sdl.clone(),
+                        subgraph_name: subgraph_name.clone(),
+                        ignore_existing: false,
+                    },
+                    client,
+                ) {
+                    Ok(lint_response) | Err(RoverClientError::LintFailures { lint_response }) => {
+                        lint_diagnostics(&lint_response)
+                    }
+                    Err(e) => vec![file_diagnostic(
+                        SEVERITY_WARNING,
+                        "rover lint",
+                        None,
+                        format!("could not lint this schema: {}", e),
+                    )],
+                };
+                diagnostics
+                    .entry(file.to_string())
+                    .or_default()
+                    .extend(lint_diagnostics);
+            }
+        }
+
+        diagnostics
+            .into_iter()
+            .map(|(file, diagnostics)| FileDiagnostics { file, diagnostics })
+            .collect()
+    }
+
+    /// Composes the latest version of each schema file with the subgraphs that were resolved
+    /// up front, returning each diagnostic with the file it belongs in, if it's known.
+    fn compose(
+        &self,
+        client_config: &StudioClientConfig,
+        source_map: &SubgraphSourceMap,
+        resolved_config: &SupergraphConfig,
+        schemas: &BTreeMap<String, (Utf8PathBuf, String)>,
+    ) -> Vec<(Option<String>, Diagnostic)> {
+        let definitions = match resolved_config.get_subgraph_definitions() {
+            Ok(definitions) => definitions,
+            Err(e) => return vec![(None, config_diagnostic(e.to_string()))],
+        };
+        let definitions: Vec<SubgraphDefinition> = definitions
+            .into_iter()
+            .map(|definition| match schemas.get(&definition.name) {
+                Some((_, sdl)) => SubgraphDefinition::new(definition.name, definition.url, sdl),
+                None => definition,
+            })
+            .collect();
+        let mut supergraph_config: SupergraphConfig = definitions.into();
+        if let Some(federation_version) = resolved_config.get_federation_version() {
+            supergraph_config.set_federation_version(federation_version);
+        }
+
+        match Compose::new(self.opts.clone()).exec(
+            None,
+            client_config.clone(),
+            &mut supergraph_config,
+            source_map,
+        ) {
+            Ok(output) => composition_diagnostics(None, &output.hints),
+            Err(e) => match e.build_errors() {
+                Some(build_errors) => composition_diagnostics(Some(build_errors), &[]),
+                None => vec![(None, config_diagnostic(e.message()))],
+            },
+        }
+    }
+}
+
+fn file_diagnostic(
+    severity: u8,
+    source: &'static str,
+    code: Option<String>,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: Range::default(),
+        severity,
+        source,
+        code,
+        message,
+    }
+}
+
+fn config_diagnostic(message: String) -> Diagnostic {
+    file_diagnostic(SEVERITY_ERROR, "rover composition", None, message)
+}
+
+fn syntax_diagnostics(sdl: &str) -> Vec<Diagnostic> {
+    SdlParser::new(sdl)
+        .parse()
+        .errors()
+        .map(|error| Diagnostic {
+            range: Range {
+                start: Position::at_byte_offset(sdl, error.index()),
+                end: Position::at_byte_offset(sdl, error.index() + error.data().len()),
+            },
+            severity: SEVERITY_ERROR,
+            source: "rover syntax",
+            code: None,
+            message: error.message().to_string(),
+        })
+        .collect()
+}
+
+fn lint_diagnostics(lint_response: &LintResponse) -> Vec<Diagnostic> {
+    let sdl = &lint_response.proposed_schema;
+    lint_response
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            let severity = match diagnostic.level.as_str() {
+                "ERROR" => SEVERITY_ERROR,
+                "WARNING" => SEVERITY_WARNING,
+                _ => return None,
+            };
+            Some(Diagnostic {
+                range: Range {
+                    start: Position::at_byte_offset(sdl, diagnostic.start_byte_offset),
+                    end: Position::at_byte_offset(sdl, diagnostic.end_byte_offset),
+                },
+                severity,
+                source: "rover lint",
+                code: Some(diagnostic.coordinate.clone()),
+                message: diagnostic.message.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Turns composition errors and hints into diagnostics on each file they point at. Their nodes'
+/// `source` must already be filled in by a [`SubgraphSourceMap`].
+fn composition_diagnostics(
+    build_errors: Option<&BuildErrors>,
+    hints: &[BuildHint],
+) -> Vec<(Option<String>, Diagnostic)> {
+    let errors = build_errors
+        .and_then(|build_errors| serde_json::to_value(build_errors).ok())
+        .and_then(|value| value["build_errors"].as_array().cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|error| (SEVERITY_ERROR, error));
+    let hints = hints
+        .iter()
+        .filter_map(|hint| serde_json::to_value(hint).ok())
+        .map(|hint| (SEVERITY_INFORMATION, hint));
+
+    let mut diagnostics = Vec::new();
+    for (severity, item) in errors.chain(hints) {
+        // the source map lists locations after the message, which the ranges already cover
+        let message = without_locations(item["message"].as_str().unwrap_or_default()).to_string();
+        let diagnostic = Diagnostic {
+            range: Range::default(),
+            severity,
+            source: "rover composition",
+            code: item["code"].as_str().map(str::to_string),
+            message,
+        };
+        let located: Vec<_> = item["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|node| {
+                let file = node["source"].as_str()?.to_string();
+                let start = node_position(&node["start"])?;
+                let end = node_position(&node["end"]).unwrap_or(start);
+                let mut diagnostic = diagnostic.clone();
+                diagnostic.range = Range { start, end };
+                Some((Some(file), diagnostic))
+            })
+            .collect();
+        if located.is_empty() {
+            diagnostics.push((None, diagnostic));
+        } else {
+            diagnostics.extend(located);
+        }
+    }
+    diagnostics
+}
+
+/// Composition reports one-based lines and columns
+fn node_position(location: &Value) -> Option<Position> {
+    Some(Position {
+        line: (location["line"].as_u64()? as usize).saturating_sub(1),
+        character: (location["column"].as_u64()? as usize).saturating_sub(1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::build::{BuildError, BuildErrors};
+    use serde_json::json;
+
+    use super::{composition_diagnostics, syntax_diagnostics, Position, SEVERITY_ERROR};
+
+    #[test]
+    fn it_reports_syntax_errors_with_ranges() {
+        let diagnostics = syntax_diagnostics("type Query {\n  a: Int\n  b:\n}\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, SEVERITY_ERROR);
+        assert_eq!(diagnostics[0].range.start.line, 3);
+
+        assert!(syntax_diagnostics("type Query { a: Int }").is_empty());
+    }
+
+    #[test]
+    fn it_places_composition_errors_in_their_files() {
+        let errors: BuildErrors = vec![serde_json::from_value::<BuildError>(json!({
+            "message": "[products] Field \"Product.id\" is defined differently\n  --> products.graphql:3:5 (subgraph 'products')",
+            "code": "FIELD_TYPE_MISMATCH",
+            "type": "composition",
+            "nodes": [
+                {
+                    "subgraph": "products",
+                    "source": "products.graphql",
+                    "start": { "start": 0, "end": 0, "line": 3, "column": 5 },
+                    "end": { "start": 0, "end": 0, "line": 3, "column": 12 }
+                },
+                {
+                    "subgraph": "reviews",
+                    "source": null,
+                    "start": { "start": 0, "end": 0, "line": 1, "column": 1 },
+                    "end": null
+                }
+            ],
+            "omittedNodesCount": 0
+        }))
+        .unwrap()]
+        .into();
+
+        let diagnostics = composition_diagnostics(Some(&errors), &[]);
+        assert_eq!(diagnostics.len(), 1);
+        let (file, diagnostic) = &diagnostics[0];
+        assert_eq!(file.as_deref(), Some("products.graphql"));
+        assert_eq!(
+            diagnostic.message,
+            "[products] Field \"Product.id\" is defined differently"
+        );
+        assert_eq!(diagnostic.code.as_deref(), Some("FIELD_TYPE_MISMATCH"));
+        assert_eq!(
+            diagnostic.range.start,
+            Position {
+                line: 2,
+                character: 4
+            }
+        );
+        assert_eq!(
+            diagnostic.range.end,
+            Position {
+                line: 2,
+                character: 11
+            }
+        );
+    }
+}
@@ -6,8 +6,18 @@ use serde::Serialize;
 use rover_client::operations::subgraph::check_workflow::{self, CheckWorkflowInput};
 use rover_client::shared::{CheckConfig, GitContext};
 
-use crate::options::{CheckConfigOpts, GraphRefOpt, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::command::subgraph::local_composition::compose_with_published_subgraphs;
+use crate::options::{
+    CheckConfigOpts, GraphRefOpt, LicenseAccepter, PluginChannel, PluginOpts, ProfileOpt,
+    SchemaOpt, SubgraphOpt,
+};
+use crate::utils::check_annotations;
+use crate::utils::check_history::CheckHistory;
+use crate::utils::check_report;
 use crate::utils::client::StudioClientConfig;
+use crate::utils::events::emit_check_completed;
+use crate::utils::scope::{check_key_scope, RequiredScope};
+use crate::utils::source_map::SubgraphSourceMap;
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
@@ -27,6 +37,14 @@ pub struct Check {
 
     #[clap(flatten)]
     config: CheckConfigOpts,
+
+    /// Compose the proposed schema with the other subgraphs published to the graph before
+    /// running the remote check, and fail without running it if composition fails.
+    #[arg(long = "local-composition")]
+    local_composition: bool,
+
+    #[clap(flatten)]
+    elv2_license_accepter: LicenseAccepter,
 }
 
 impl Check {
@@ -37,6 +55,11 @@ impl Check {
         checks_timeout_seconds: u64,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::CheckSchemas,
+        )?;
 
         let proposed_schema = self
             .schema
@@ -48,12 +71,30 @@ impl Check {
             Style::Link.paint(self.graph.graph_ref.to_string())
         );
 
+        if self.local_composition {
+            compose_with_published_subgraphs(
+                &client,
+                client_config.clone(),
+                PluginOpts {
+                    profile: self.profile.clone(),
+                    elv2_license_accepter: self.elv2_license_accepter,
+                    skip_update: false,
+                    locked: false,
+                    channel: PluginChannel::Stable,
+                },
+                &self.graph.graph_ref,
+                &self.subgraph.subgraph_name,
+                &proposed_schema,
+                self.schema.file_path(),
+            )?;
+        }
+
         let workflow_res = check::run(
             SubgraphCheckAsyncInput {
                 graph_ref: self.graph.graph_ref.clone(),
                 subgraph: self.subgraph.subgraph_name.clone(),
                 git_context,
-                proposed_schema,
+                proposed_schema: proposed_schema.clone(),
                 config: CheckConfig {
                     query_count_threshold: self.config.query_count_threshold,
                     query_count_threshold_percentage: self.config.query_percentage_threshold,
@@ -65,6 +106,13 @@ impl Check {
         if self.config.background {
             Ok(RoverOutput::AsyncCheckResponse(workflow_res))
         } else {
+            if self.config.watch_until_complete {
+                eprintln!(
+                    "Waiting for check {} to complete. Follow its progress at {}",
+                    workflow_res.workflow_id,
+                    Style::Link.paint(&workflow_res.target_url)
+                );
+            }
             let check_res = check_workflow::run(
                 CheckWorkflowInput {
                     graph_ref: self.graph.graph_ref.clone(),
@@ -73,9 +121,42 @@ impl Check {
                 },
                 self.subgraph.subgraph_name.clone(),
                 &client,
-            )?;
+            )
+            .map_err(|e| {
+                SubgraphSourceMap::for_subgraph(
+                    &self.subgraph.subgraph_name,
+                    self.schema.file_path(),
+                )
+                .annotate_client_error(e)
+            });
+            CheckHistory::new(
+                &client_config.config.home,
+                &self.graph.graph_ref,
+                Some(&self.subgraph.subgraph_name),
+            )
+            .record(&check_res);
+            emit_check_completed(
+                &self.graph.graph_ref,
+                Some(&self.subgraph.subgraph_name),
+                &check_res,
+            );
 
-            Ok(RoverOutput::CheckWorkflowResponse(check_res))
+            if let Some(format) = self.config.annotations {
+                check_annotations::write(
+                    format,
+                    self.config.annotations_file.as_deref(),
+                    &check_res,
+                    &proposed_schema,
+                    self.schema.file_path().as_deref(),
+                );
+            }
+            if self.config.watch_until_complete {
+                check_report::print(&check_res);
+                return Ok(RoverOutput::CheckWorkflowResponse(check_report::finish(
+                    check_res,
+                )?));
+            }
+            Ok(RoverOutput::CheckWorkflowResponse(check_res?))
         }
     }
 }
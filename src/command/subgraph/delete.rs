@@ -3,6 +3,7 @@ use serde::Serialize;
 
 use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverOutput, RoverResult};
 
 use rover_client::operations::subgraph::delete::{self, SubgraphDeleteInput};
@@ -29,6 +30,11 @@ pub struct Delete {
 impl Delete {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::PushSchemas,
+        )?;
         eprintln!(
             "Checking for build errors resulting from deleting subgraph {} from {} using credentials from the {} profile.",
             Style::Link.paint(&self.subgraph.subgraph_name),
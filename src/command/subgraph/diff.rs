@@ -0,0 +1,84 @@
+use anyhow::anyhow;
+use clap::Parser;
+use serde::Serialize;
+
+use rover_client::operations::subgraph::fetch::{self, SubgraphFetchInput};
+use rover_std::Style;
+
+use crate::command::schema::diff::{FailOnOpt, SchemaDiff, SchemaSource, SeverityConfigOpt};
+use crate::options::ProfileOpt;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Diff {
+    /// The schema to compare from: a graph ref like `my-graph@main`, a file, or `-` for stdin
+    #[arg(long = "old")]
+    #[serde(skip_serializing)]
+    old: SchemaSource,
+
+    /// The schema to compare to: a graph ref like `my-graph@staging`, a file, or `-` for stdin
+    #[arg(long = "new")]
+    #[serde(skip_serializing)]
+    new: SchemaSource,
+
+    /// The name of the subgraph to fetch from the graph refs. Only needed when `--old` or
+    /// `--new` is a graph ref.
+    #[arg(long = "name")]
+    #[serde(skip_serializing)]
+    subgraph_name: Option<String>,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    #[clap(flatten)]
+    severity_config: SeverityConfigOpt,
+
+    #[clap(flatten)]
+    fail_on: FailOnOpt,
+}
+
+impl Diff {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let overrides = self.severity_config.load()?;
+        let old = self.read(&self.old, "old SDL", &client_config)?;
+        let new = self.read(&self.new, "new SDL", &client_config)?;
+        let diff = SchemaDiff::new(&old, &new, &overrides)?;
+        Ok(RoverOutput::SchemaDiff(self.fail_on.check(diff)?))
+    }
+
+    fn read(
+        &self,
+        source: &SchemaSource,
+        file_description: &str,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<String> {
+        source.read(file_description, |graph_ref| {
+            let Some(subgraph_name) = &self.subgraph_name else {
+                let mut err = RoverError::new(anyhow!(
+                    "a subgraph name is needed to compare the schema published to {}",
+                    graph_ref
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass the subgraph's name with `--name`.".to_string(),
+                ));
+                return Err(err);
+            };
+            let client = client_config.get_authenticated_client(&self.profile)?;
+            eprintln!(
+                "Fetching SDL from {} (subgraph: {}) using credentials from the {} profile.",
+                Style::Link.paint(graph_ref.to_string()),
+                Style::Link.paint(subgraph_name),
+                Style::Command.paint(&self.profile.profile_name)
+            );
+            let fetch_response = fetch::run(
+                SubgraphFetchInput {
+                    graph_ref: graph_ref.clone(),
+                    subgraph_name: subgraph_name.clone(),
+                },
+                &client,
+            )?;
+            Ok(fetch_response.sdl.contents)
+        })
+    }
+}
@@ -1,44 +1,398 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::Serialize;
+use serde_json::json;
 
+use rover_client::blocking::StudioClient;
 use rover_client::operations::subgraph::fetch::{self, SubgraphFetchInput};
-use rover_std::Style;
+use rover_client::operations::subgraph::list::{self, SubgraphListInput};
+use rover_std::{Emoji, Fs, Style};
 
-use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
+use crate::options::{GraphRefOpt, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
-use crate::{RoverOutput, RoverResult};
+use crate::utils::table::{self, row};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Fetch {
     #[clap(flatten)]
     graph: GraphRefOpt,
 
-    #[clap(flatten)]
-    subgraph: SubgraphOpt,
+    /// The name of the subgraph.
+    #[arg(long = "name", required_unless_present = "all", conflicts_with = "all")]
+    #[serde(skip_serializing)]
+    subgraph_name: Option<String>,
 
     #[clap(flatten)]
     profile: ProfileOpt,
+
+    /// Fetch every subgraph published to the graph, writing each schema to `--output-dir`
+    #[arg(long = "all", requires = "output_dir")]
+    all: bool,
+
+    /// The directory to write each subgraph's schema to when fetching `--all`, as
+    /// `<name>.graphql`
+    #[arg(long = "output-dir", requires = "all")]
+    #[serde(skip_serializing)]
+    output_dir: Option<Utf8PathBuf>,
+
+    /// How many subgraphs to fetch at once with `--all`
+    #[arg(
+        long = "concurrency",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    concurrency: u64,
+
+    /// Skip the subgraphs whose schema is already in `--output-dir`, so a fetch that partly
+    /// failed can pick up where it left off
+    #[arg(long = "resume", requires = "all")]
+    resume: bool,
+    /// Also write a `supergraph.yaml` to `--output-dir` that composes the fetched schemas with
+    /// their routing URLs, for `rover supergraph compose` and `rover dev`
+    #[arg(long = "write-supergraph-config", requires = "all")]
+    write_supergraph_config: bool,
 }
 
 impl Fetch {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
+        let Some(subgraph_name) = &self.subgraph_name else {
+            return self.fetch_all(&client);
+        };
         eprintln!(
             "Fetching SDL from {} (subgraph: {}) using credentials from the {} profile.",
             Style::Link.paint(graph_ref),
-            Style::Link.paint(&self.subgraph.subgraph_name),
+            Style::Link.paint(subgraph_name),
             Style::Command.paint(&self.profile.profile_name)
         );
 
         let fetch_response = fetch::run(
             SubgraphFetchInput {
                 graph_ref: self.graph.graph_ref.clone(),
-                subgraph_name: self.subgraph.subgraph_name.clone(),
+                subgraph_name: subgraph_name.clone(),
             },
             &client,
         )?;
 
         Ok(RoverOutput::FetchResponse(fetch_response))
     }
+
+    fn fetch_all(&self, client: &StudioClient) -> RoverResult<RoverOutput> {
+        let output_dir = self
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| Utf8PathBuf::from("."));
+        Fs::create_dir_all(&output_dir)?;
+        let routing_urls = list::run(
+            SubgraphListInput {
+                graph_ref: self.graph.graph_ref.clone(),
+            },
+            client,
+        )?
+        .subgraphs
+        .into_iter()
+        .map(|subgraph| (subgraph.name, subgraph.url))
+        .collect::<BTreeMap<_, _>>();
+        // sorted, so the report lists subgraphs in the same order on every run
+        let names = routing_urls.keys().cloned().collect::<Vec<_>>();
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.concurrency as usize)
+            .build()
+            .context("could not start the threads to fetch subgraphs on")?;
+        eprintln!(
+            "{}fetching {} subgraphs from {} into {}, {} at a time",
+            Emoji::Action,
+            names.len(),
+            Style::Link.paint(self.graph.graph_ref.to_string()),
+            Style::Path.paint(output_dir.as_str()),
+            self.concurrency
+        );
+        let subgraphs = pool.install(|| {
+            names
+                .par_iter()
+                .map(|name| self.fetch_one(client, name, &output_dir))
+                .collect::<Vec<_>>()
+        });
+        let mut report = SubgraphFetchReport {
+            graph_ref: self.graph.graph_ref.to_string(),
+            subgraphs,
+            supergraph_config: None,
+        };
+
+        if report.failures() == 0 {
+            if self.write_supergraph_config {
+                let path = output_dir.join("supergraph.yaml");
+                let subgraphs = report
+                    .subgraphs
+                    .iter()
+                    .map(|subgraph| {
+                        let routing_url = routing_urls.get(&subgraph.name).cloned().flatten();
+                        let sdl =
+                            Fs::read_file(output_dir.join(format!("{}.graphql", subgraph.name)))?;
+                        Ok((subgraph.name.clone(), routing_url, sdl))
+                    })
+                    .collect::<RoverResult<Vec<_>>>()?;
+                Fs::write_file(&path, supergraph_config_yaml(&subgraphs)?)?;
+                eprintln!(
+                    "{}wrote a supergraph config for the fetched subgraphs to {}",
+                    Emoji::Success,
+                    Style::Path.paint(path.as_str())
+                );
+                report.supergraph_config = Some(path);
+            }
+            Ok(RoverOutput::SubgraphFetchReport(report))
+        } else {
+            let mut err = RoverError::new(SubgraphFetchFailures { report });
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Run the same command with `--resume` to fetch only the subgraphs that failed."
+                    .to_string(),
+            ));
+            Err(err)
+        }
+    }
+
+    fn fetch_one(
+        &self,
+        client: &StudioClient,
+        name: &str,
+        output_dir: &Utf8Path,
+    ) -> SubgraphFetchResult {
+        let path = output_dir.join(format!("{}.graphql", name));
+        if self.resume && path.exists() {
+            return SubgraphFetchResult {
+                name: name.to_string(),
+                status: SubgraphFetchStatus::Skipped,
+                path: Some(path),
+                error: None,
+            };
+        }
+        let fetched = fetch::run(
+            SubgraphFetchInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                subgraph_name: name.to_string(),
+            },
+            client,
+        )
+        .map_err(|e| e.to_string())
+        // written atomically, so `--resume` never mistakes a half-written schema for a fetched one
+        .and_then(|response| {
+            Fs::write_file_atomic(&path, response.sdl.contents).map_err(|e| e.to_string())
+        });
+        match fetched {
+            Ok(()) => {
+                eprintln!("{}{} fetched", Emoji::Success, name);
+                SubgraphFetchResult {
+                    name: name.to_string(),
+                    status: SubgraphFetchStatus::Fetched,
+                    path: Some(path),
+                    error: None,
+                }
+            }
+            Err(error) => {
+                eprintln!(
+                    "{} {} failed: {}",
+                    Style::ErrorPrefix.paint("ERROR:"),
+                    name,
+                    error
+                );
+                SubgraphFetchResult {
+                    name: name.to_string(),
+                    status: SubgraphFetchStatus::Failed,
+                    path: None,
+                    error: Some(error),
+                }
+            }
+        }
+    }
+}
+
+/// A supergraph config that composes each `(name, routing URL, SDL)` from the `<name>.graphql`
+/// file next to it. It uses Federation 2 if any of the schemas link to the federation 2 spec.
+pub(crate) fn supergraph_config_yaml(
+    subgraphs: &[(String, Option<String>, String)],
+) -> RoverResult<String> {
+    let federation_version = if subgraphs
+        .iter()
+        .any(|(_, _, sdl)| sdl.contains("specs.apollo.dev/federation/v2"))
+    {
+        "=2"
+    } else {
+        "=1"
+    };
+    let mut config = serde_yaml::Mapping::new();
+    for (name, routing_url, _) in subgraphs {
+        let mut subgraph = serde_yaml::Mapping::new();
+        if let Some(routing_url) = routing_url {
+            subgraph.insert("routing_url".into(), routing_url.as_str().into());
+        }
+        subgraph.insert(
+            "schema".into(),
+            serde_yaml::to_value(json!({ "file": format!("./{}.graphql", name) }))?,
+        );
+        config.insert(name.as_str().into(), subgraph.into());
+    }
+    let config = json!({
+        "federation_version": federation_version,
+        "subgraphs": config,
+    });
+    Ok(serde_yaml::to_string(&config)?)
+}
+
+/// How fetching each subgraph of a graph went, in order of subgraph name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubgraphFetchReport {
+    pub(crate) graph_ref: String,
+    pub(crate) subgraphs: Vec<SubgraphFetchResult>,
+    /// Where the supergraph config was written, with `--write-supergraph-config`
+    pub(crate) supergraph_config: Option<Utf8PathBuf>,
+}
+
+impl SubgraphFetchReport {
+    pub(crate) fn failures(&self) -> usize {
+        self.subgraphs
+            .iter()
+            .filter(|subgraph| subgraph.status == SubgraphFetchStatus::Failed)
+            .count()
+    }
+
+    pub(crate) fn get_table(&self) -> String {
+        let mut table = table::get_table();
+
+        // bc => sets top row to be bold and center
+        table.add_row(row![bc => "Name", "Result", "Schema"]);
+        for subgraph in &self.subgraphs {
+            let result = match &subgraph.error {
+                Some(error) => format!("failed: {}", error),
+                None => subgraph.status.to_string(),
+            };
+            let path = subgraph
+                .path
+                .as_ref()
+                .map(|path| path.to_string())
+                .unwrap_or_default();
+            table.add_row(row![subgraph.name, result, path]);
+        }
+        table.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SubgraphFetchResult {
+    pub(crate) name: String,
+    pub(crate) status: SubgraphFetchStatus,
+    /// Where the schema was written, unless fetching it failed
+    pub(crate) path: Option<Utf8PathBuf>,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SubgraphFetchStatus {
+    Fetched,
+    /// The schema was already in the output directory, and `--resume` was passed
+    Skipped,
+    Failed,
+}
+
+impl Display for SubgraphFetchStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Fetched => "fetched",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// The error for `rover subgraph fetch --all` when some of the subgraphs couldn't be fetched.
+/// It carries the report, so the subgraphs that were fetched are still listed.
+#[derive(Debug)]
+pub(crate) struct SubgraphFetchFailures {
+    pub(crate) report: SubgraphFetchReport,
+}
+
+impl Display for SubgraphFetchFailures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "could not fetch {} of {} subgraphs",
+            self.report.failures(),
+            self.report.subgraphs.len()
+        )
+    }
+}
+
+impl std::error::Error for SubgraphFetchFailures {}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+
+    use super::{
+        supergraph_config_yaml, SubgraphFetchReport, SubgraphFetchResult, SubgraphFetchStatus,
+    };
+
+    #[test]
+    fn it_reports_which_subgraphs_failed() {
+        let report = SubgraphFetchReport {
+            graph_ref: "my-graph@current".to_string(),
+            subgraphs: vec![
+                SubgraphFetchResult {
+                    name: "products".to_string(),
+                    status: SubgraphFetchStatus::Fetched,
+                    path: Some(Utf8PathBuf::from("subgraphs/products.graphql")),
+                    error: None,
+                },
+                SubgraphFetchResult {
+                    name: "reviews".to_string(),
+                    status: SubgraphFetchStatus::Failed,
+                    path: None,
+                    error: Some("The registry did not respond".to_string()),
+                },
+            ],
+            supergraph_config: None,
+        };
+        assert_eq!(report.failures(), 1);
+        let table = report.get_table();
+        assert!(table.contains("subgraphs/products.graphql"));
+        assert!(table.contains("failed: The registry did not respond"));
+    }
+
+    #[test]
+    fn it_writes_a_supergraph_config_for_the_fetched_schemas() {
+        let yaml = supergraph_config_yaml(&[
+            (
+                "products".to_string(),
+                Some("https://products.example.com/graphql".to_string()),
+                r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.3")"#
+                    .to_string(),
+            ),
+            (
+                "reviews".to_string(),
+                None,
+                "type Query { a: Int }".to_string(),
+            ),
+        ])
+        .unwrap();
+        let config: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(config["federation_version"].as_str(), Some("=2"));
+        assert_eq!(
+            config["subgraphs"]["products"]["routing_url"].as_str(),
+            Some("https://products.example.com/graphql")
+        );
+        assert_eq!(
+            config["subgraphs"]["reviews"]["schema"]["file"].as_str(),
+            Some("./reviews.graphql")
+        );
+        assert!(config["subgraphs"]["reviews"]["routing_url"].is_null());
+    }
 }
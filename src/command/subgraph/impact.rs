@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use clap::Parser;
+use rover_client::operations::subgraph::fetch_all::{self, SubgraphFetchAllInput};
+use rover_std::{Emoji, Style};
+use serde::Serialize;
+
+use crate::command::schema::diff::{ChangeCode, SchemaChange, SchemaDiff, SeverityOverrides};
+use crate::command::subgraph::local_lint::FederationUsage;
+use crate::options::{GraphRefOpt, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Impact {
+    #[clap(flatten)]
+    graph: GraphRefOpt,
+
+    #[clap(flatten)]
+    subgraph: SubgraphOpt,
+
+    #[clap(flatten)]
+    profile: ProfileOpt,
+
+    #[clap(flatten)]
+    #[serde(skip_serializing)]
+    schema: SchemaOpt,
+}
+
+impl Impact {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let client = client_config.get_authenticated_client(&self.profile)?;
+        let proposed_schema = self
+            .schema
+            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let graph_ref = &self.graph.graph_ref;
+        let subgraph_name = &self.subgraph.subgraph_name;
+        eprintln!(
+            "{}Fetching the subgraphs in {} using credentials from the {} profile.",
+            Emoji::Hourglass,
+            Style::Link.paint(graph_ref.to_string()),
+            Style::Command.paint(&self.profile.profile_name)
+        );
+
+        let published = fetch_all::run(
+            SubgraphFetchAllInput {
+                graph_ref: graph_ref.clone(),
+            },
+            &client,
+        )?;
+        let mut published_schema = String::new();
+        let mut other_subgraphs = Vec::new();
+        for subgraph in published.subgraphs {
+            if &subgraph.name == subgraph_name {
+                published_schema = subgraph.sdl;
+            } else {
+                other_subgraphs.push((subgraph.name, subgraph.sdl));
+            }
+        }
+
+        Ok(RoverOutput::SubgraphImpact(SubgraphImpact::analyze(
+            subgraph_name,
+            &published_schema,
+            &proposed_schema,
+            &other_subgraphs,
+        )?))
+    }
+}
+
+/// Which parts of the other subgraphs in a graph depend on the changes to one of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SubgraphImpact {
+    pub(crate) subgraph: String,
+    /// The changes between the published schema and the proposed one
+    pub(crate) changes: Vec<SchemaChange>,
+    pub(crate) dependents: Vec<Dependent>,
+}
+
+/// A type or field in another subgraph that depends on a changed type or field.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct Dependent {
+    pub(crate) subgraph: String,
+    /// The type or field that depends on the change, like `Product.shippingEstimate`
+    pub(crate) coordinate: String,
+    /// How it depends on the change: through a `@key`, `@requires`, `@provides`, or `@external`,
+    /// as the same entity, or as a field both subgraphs resolve
+    pub(crate) relationship: String,
+    /// The changed type or field it depends on
+    pub(crate) changed: String,
+}
+
+impl SubgraphImpact {
+    /// Compares `subgraph`'s published and proposed schemas, and finds what depends on the
+    /// changes in `other_subgraphs`, a list of names and schemas.
+    pub(crate) fn analyze(
+        subgraph: &str,
+        published_schema: &str,
+        proposed_schema: &str,
+        other_subgraphs: &[(String, String)],
+    ) -> RoverResult<Self> {
+        let changes = SchemaDiff::new(
+            published_schema,
+            proposed_schema,
+            &SeverityOverrides::default(),
+        )?
+        .changes;
+        // a removed type takes its fields with it
+        let changed = |coordinate: &str| {
+            changes
+                .iter()
+                .find(|change| change.coordinate == coordinate)
+                .or_else(|| {
+                    let (type_name, _) = coordinate.split_once('.')?;
+                    changes.iter().find(|change| {
+                        change.coordinate == type_name
+                            && matches!(
+                                change.code,
+                                ChangeCode::TypeRemoved | ChangeCode::TypeChangedKind
+                            )
+                    })
+                })
+                .map(|change| change.coordinate.clone())
+        };
+
+        let mut dependents = BTreeSet::new();
+        for (other_subgraph, sdl) in other_subgraphs {
+            let usage = FederationUsage::from_sdl(sdl).with_context(|| {
+                format!(
+                    "could not parse the schema of the '{}' subgraph",
+                    other_subgraph
+                )
+            })?;
+            let mut depends = |coordinate: &str, relationship: String, changed: Option<String>| {
+                if let Some(changed) = changed {
+                    dependents.insert(Dependent {
+                        subgraph: other_subgraph.clone(),
+                        coordinate: coordinate.to_string(),
+                        relationship,
+                        changed,
+                    });
+                }
+            };
+            for field_set in &usage.field_sets {
+                for selected in &field_set.selected {
+                    depends(
+                        &field_set.coordinate,
+                        format!("@{}", field_set.directive),
+                        changed(selected),
+                    );
+                }
+            }
+            for field in &usage.external_fields {
+                depends(field, "@external".to_string(), changed(field));
+            }
+            for field in &usage.fields {
+                depends(field, "shared field".to_string(), changed(field));
+            }
+            for entity in &usage.entities {
+                let entity_changed = changed(entity).filter(|changed| changed == entity);
+                depends(entity, "entity".to_string(), entity_changed);
+            }
+        }
+
+        Ok(Self {
+            subgraph: subgraph.to_string(),
+            changes,
+            dependents: dependents.into_iter().collect(),
+        })
+    }
+
+    /// The other subgraphs that depend on the changes
+    pub(crate) fn dependent_subgraphs(&self) -> BTreeSet<&str> {
+        self.dependents
+            .iter()
+            .map(|dependent| dependent.subgraph.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dependent, SubgraphImpact};
+
+    const PUBLISHED: &str = r#"type Query {
+  product(id: ID!): Product
+}
+
+type Product @key(fields: "id") {
+  id: ID!
+  weight: Float
+  sku: String
+  name: String
+}
+"#;
+
+    const INVENTORY: &str = r#"type Product @key(fields: "id") {
+  id: ID!
+  weight: Float @external
+  shippingEstimate: Float @requires(fields: "weight")
+}
+"#;
+
+    const REVIEWS: &str = r#"type Review {
+  product: Product @provides(fields: "name")
+}
+
+type Product @key(fields: "id") {
+  id: ID!
+  name: String @external
+}
+"#;
+
+    #[test]
+    fn it_finds_what_depends_on_a_change() {
+        let proposed = PUBLISHED.replace("  weight: Float\n", "  weight: Int\n");
+        let impact = SubgraphImpact::analyze(
+            "products",
+            PUBLISHED,
+            &proposed,
+            &[
+                ("inventory".to_string(), INVENTORY.to_string()),
+                ("reviews".to_string(), REVIEWS.to_string()),
+            ],
+        )
+        .unwrap();
+        let dependent = |coordinate: &str, relationship: &str| Dependent {
+            subgraph: "inventory".to_string(),
+            coordinate: coordinate.to_string(),
+            relationship: relationship.to_string(),
+            changed: "Product.weight".to_string(),
+        };
+        assert_eq!(
+            impact.dependents,
+            vec![
+                dependent("Product.shippingEstimate", "@requires"),
+                dependent("Product.weight", "@external"),
+            ]
+        );
+        assert_eq!(
+            impact.dependent_subgraphs().into_iter().collect::<Vec<_>>(),
+            vec!["inventory"]
+        );
+    }
+
+    #[test]
+    fn it_finds_entities_that_depend_on_a_removed_type() {
+        let proposed = "type Query {\n  version: String\n}\n";
+        let impact = SubgraphImpact::analyze(
+            "products",
+            PUBLISHED,
+            proposed,
+            &[("reviews".to_string(), REVIEWS.to_string())],
+        )
+        .unwrap();
+        let relationships = impact
+            .dependents
+            .iter()
+            .map(|dependent| {
+                (
+                    dependent.coordinate.as_str(),
+                    dependent.relationship.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relationships,
+            vec![
+                ("Product", "@key"),
+                ("Product", "entity"),
+                ("Product.id", "shared field"),
+                ("Product.name", "@external"),
+                ("Review.product", "@provides"),
+            ]
+        );
+    }
+}
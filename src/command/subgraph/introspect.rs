@@ -18,17 +18,28 @@ pub struct Introspect {
 }
 
 impl Introspect {
-    pub fn run(&self, client: Client, output_opts: &OutputOpts) -> RoverResult<RoverOutput> {
+    pub fn run(
+        &self,
+        client: Client,
+        retries: Option<u32>,
+        output_opts: &OutputOpts,
+    ) -> RoverResult<RoverOutput> {
         if self.opts.watch {
             self.exec_and_watch(&client, output_opts)
         } else {
-            let sdl = self.exec(&client, true)?;
+            let sdl = self.exec(&client, retries)?;
             Ok(RoverOutput::Introspection(sdl))
         }
     }
 
-    pub fn exec(&self, client: &Client, should_retry: bool) -> RoverResult<String> {
+    /// Introspects the endpoint, retrying failed requests `retries` times, or `None` for Rover's
+    /// default retries.
+    pub fn exec(&self, client: &Client, retries: Option<u32>) -> RoverResult<String> {
         let client = GraphQLClient::new(self.opts.endpoint.as_ref(), client.clone());
+        let client = match retries {
+            Some(retries) => client.with_max_retries(retries),
+            None => client,
+        };
 
         // add the flag headers to a hashmap to pass along to rover-client
         let mut headers = HashMap::new();
@@ -38,11 +49,11 @@ impl Introspect {
             }
         };
 
-        Ok(introspect::run(SubgraphIntrospectInput { headers }, &client, should_retry)?.result)
+        Ok(introspect::run(SubgraphIntrospectInput { headers }, &client, true)?.result)
     }
 
     pub fn exec_and_watch(&self, client: &Client, output_opts: &OutputOpts) -> ! {
         self.opts
-            .exec_and_watch(|| self.exec(client, false), output_opts)
+            .exec_and_watch(|| self.exec(client, Some(0)), output_opts)
     }
 }
@@ -1,19 +1,27 @@
 use clap::Parser;
 use rover_client::operations::subgraph::lint::{self, LintSubgraphInput};
+use rover_client::shared::GraphRef;
+use rover_client::RoverClientError;
 use serde::Serialize;
 
-use crate::options::{GraphRefOpt, LintOpts, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::command::subgraph::local_lint::lint_subgraph_schema;
+use crate::options::{LintOpts, ProfileOpt, SchemaOpt};
 
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Lint {
-    #[clap(flatten)]
-    graph: GraphRefOpt,
+    /// <NAME>@<VARIANT> of graph in Apollo Studio.
+    /// @<VARIANT> may be left off, defaulting to @current
+    #[arg(value_name = "GRAPH_REF", required_unless_present = "offline")]
+    #[serde(skip_serializing)]
+    graph_ref: Option<GraphRef>,
 
-    #[clap(flatten)]
-    subgraph: SubgraphOpt,
+    /// The name of the subgraph.
+    #[arg(long = "name", required_unless_present = "offline")]
+    #[serde(skip_serializing)]
+    subgraph_name: Option<String>,
 
     #[clap(flatten)]
     profile: ProfileOpt,
@@ -24,22 +32,50 @@ pub struct Lint {
 
     #[clap(flatten)]
     lint: LintOpts,
+
+    /// Lint the schema on this machine instead of with the graph's rules in GraphOS.
+    ///
+    /// Offline linting reports federation problems that composition would reject: unknown directives,
+    /// and `@key`, `@requires`, and `@provides` selections of fields that don't exist.
+    #[arg(
+        long,
+        conflicts_with_all = ["graph_ref", "subgraph_name", "ignore_existing_lint_violations"]
+    )]
+    offline: bool,
 }
 
 impl Lint {
     pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
-        let client = client_config.get_authenticated_client(&self.profile)?;
-
         let file_with_metadata = self
             .schema
             .read_file_descriptor_with_metadata("SDL", &mut std::io::stdin())?;
 
+        if self.offline {
+            let lint_response =
+                lint_subgraph_schema(file_with_metadata.file_path, file_with_metadata.schema);
+            if lint_response
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.level == "ERROR")
+            {
+                return Err(RoverClientError::LintFailures { lint_response }.into());
+            }
+            return Ok(RoverOutput::LintResponse(lint_response));
+        }
+
+        let client = client_config.get_authenticated_client(&self.profile)?;
         let lint_result: rover_client::shared::LintResponse = lint::run(
             LintSubgraphInput {
-                graph_ref: self.graph.graph_ref.clone(),
+                graph_ref: self
+                    .graph_ref
+                    .clone()
+                    .expect("a graph ref is required without --offline"),
                 file_name: file_with_metadata.file_path,
                 proposed_schema: file_with_metadata.schema,
-                subgraph_name: self.subgraph.subgraph_name.clone(),
+                subgraph_name: self
+                    .subgraph_name
+                    .clone()
+                    .expect("a subgraph name is required without --offline"),
                 ignore_existing: self.lint.ignore_existing_lint_violations,
             },
             &client,
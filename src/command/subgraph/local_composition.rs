@@ -0,0 +1,132 @@
+//! Composes a proposed subgraph schema with the other subgraphs published to a graph,
+//! so `rover subgraph check --local-composition` can fail fast on composition errors
+//! before waiting on the checks pipeline.
+
+#[cfg(feature = "composition-js")]
+pub(crate) use composition::compose_with_published_subgraphs;
+
+#[cfg(not(feature = "composition-js"))]
+pub(crate) use no_composition::compose_with_published_subgraphs;
+
+#[cfg(feature = "composition-js")]
+mod composition {
+    use apollo_federation_types::{
+        build::SubgraphDefinition,
+        config::{FederationVersion, SupergraphConfig},
+    };
+    use camino::Utf8PathBuf;
+    use rover_client::blocking::StudioClient;
+    use rover_client::operations::subgraph::fetch_all::{self, SubgraphFetchAllInput};
+    use rover_client::shared::GraphRef;
+    use rover_std::{Emoji, Style};
+
+    use crate::command::supergraph::{compose::Compose, fed_two_subgraph_names};
+    use crate::options::PluginOpts;
+    use crate::utils::client::StudioClientConfig;
+    use crate::utils::source_map::SubgraphSourceMap;
+    use crate::RoverResult;
+
+    /// Used when the graph doesn't have a routing URL for the subgraph, since composition requires one.
+    const PLACEHOLDER_ROUTING_URL: &str = "http://localhost:4000";
+
+    /// Fetches every other subgraph published to `graph_ref` once, and composes them with
+    /// `proposed_schema` in place of `subgraph_name`'s published schema, using the federation
+    /// version the graph was last composed with.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn compose_with_published_subgraphs(
+        client: &StudioClient,
+        client_config: StudioClientConfig,
+        plugin_opts: PluginOpts,
+        graph_ref: &GraphRef,
+        subgraph_name: &str,
+        proposed_schema: &str,
+        schema_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<()> {
+        eprintln!(
+            "{}composing the proposed schema with the other subgraphs in {} locally",
+            Emoji::Compose,
+            Style::Link.paint(graph_ref.to_string())
+        );
+        let published = fetch_all::run(
+            SubgraphFetchAllInput {
+                graph_ref: graph_ref.clone(),
+            },
+            client,
+        )?;
+
+        let proposed_routing_url = published
+            .subgraphs
+            .iter()
+            .find(|subgraph| subgraph.name == subgraph_name)
+            .and_then(|subgraph| subgraph.routing_url.clone())
+            .unwrap_or_else(|| PLACEHOLDER_ROUTING_URL.to_string());
+        let mut subgraph_definitions = published
+            .subgraphs
+            .into_iter()
+            .filter(|subgraph| subgraph.name != subgraph_name)
+            .map(|subgraph| {
+                SubgraphDefinition::new(
+                    subgraph.name,
+                    subgraph
+                        .routing_url
+                        .unwrap_or_else(|| PLACEHOLDER_ROUTING_URL.to_string()),
+                    subgraph.sdl,
+                )
+            })
+            .collect::<Vec<_>>();
+        subgraph_definitions.push(SubgraphDefinition::new(
+            subgraph_name,
+            proposed_routing_url,
+            proposed_schema,
+        ));
+
+        let mut supergraph_config: SupergraphConfig = subgraph_definitions.into();
+        // compose with the version the graph was last composed with, and guess from the
+        // schemas only when it hasn't been composed yet
+        let federation_version = match published.federation_version {
+            Some(federation_version) => federation_version,
+            None if fed_two_subgraph_names(&supergraph_config)?.is_empty() => {
+                FederationVersion::LatestFedOne
+            }
+            None => FederationVersion::LatestFedTwo,
+        };
+        supergraph_config.set_federation_version(federation_version);
+        let source_map = SubgraphSourceMap::for_subgraph(subgraph_name, schema_path);
+
+        Compose::new(plugin_opts).exec(None, client_config, &mut supergraph_config, &source_map)?;
+        eprintln!(
+            "{}local composition succeeded, running the remote check",
+            Emoji::Success
+        );
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "composition-js"))]
+mod no_composition {
+    use anyhow::anyhow;
+    use camino::Utf8PathBuf;
+    use rover_client::blocking::StudioClient;
+    use rover_client::shared::GraphRef;
+
+    use crate::options::PluginOpts;
+    use crate::utils::client::StudioClientConfig;
+    use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn compose_with_published_subgraphs(
+        _client: &StudioClient,
+        _client_config: StudioClientConfig,
+        _plugin_opts: PluginOpts,
+        _graph_ref: &GraphRef,
+        _subgraph_name: &str,
+        _proposed_schema: &str,
+        _schema_path: Option<Utf8PathBuf>,
+    ) -> RoverResult<()> {
+        let mut err = RoverError::new(anyhow!(
+            "This version of Rover does not support `--local-composition`."
+        ));
+        err.set_suggestion(RoverErrorSuggestion::CheckGnuVersion);
+        Err(err)
+    }
+}
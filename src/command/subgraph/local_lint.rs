@@ -0,0 +1,712 @@
+//! Lints a subgraph schema without GraphOS, so `rover subgraph lint --offline` can catch the
+//! federation mistakes composition would reject, like unknown directives, or `@key`, `@requires`,
+//! and `@provides` selections of fields that don't exist.
+//!
+//! The same analysis tells `rover subgraph impact` which types and fields a subgraph shares with
+//! the others.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use anyhow::anyhow;
+use apollo_parser::{
+    ast::{self, AstNode},
+    Parser as SdlParser, SyntaxNode,
+};
+use rover_client::shared::{Diagnostic, LintResponse};
+
+use crate::utils::sdl::{name, named_type};
+
+const ERROR: &str = "ERROR";
+
+/// Directives every GraphQL schema can use without defining them
+const BUILT_IN_DIRECTIVES: &[&str] = &[
+    "defer",
+    "deprecated",
+    "include",
+    "oneOf",
+    "skip",
+    "specifiedBy",
+    "stream",
+];
+
+/// Directives from the federation spec, which Federation 1 subgraphs use without importing them
+const FEDERATION_DIRECTIVES: &[&str] = &[
+    "authenticated",
+    "composeDirective",
+    "context",
+    "cost",
+    "extends",
+    "external",
+    "fromContext",
+    "inaccessible",
+    "interfaceObject",
+    "key",
+    "listSize",
+    "override",
+    "policy",
+    "provides",
+    "requires",
+    "requiresScopes",
+    "shareable",
+    "tag",
+];
+
+/// Lints `proposed_schema` locally, reporting its problems the way GraphOS linting does.
+pub(crate) fn lint_subgraph_schema(file_name: String, proposed_schema: String) -> LintResponse {
+    LintResponse {
+        diagnostics: lint(&proposed_schema),
+        file_name,
+        proposed_schema,
+    }
+}
+
+fn lint(sdl: &str) -> Vec<Diagnostic> {
+    let tree = SdlParser::new(sdl).parse();
+    let syntax_errors = tree
+        .errors()
+        .map(|error| {
+            let start = error.index();
+            diagnostic(sdl, start..start + error.data().len(), "", error.message())
+        })
+        .collect::<Vec<_>>();
+    // the other checks would only report what the syntax errors left out
+    if !syntax_errors.is_empty() {
+        return syntax_errors;
+    }
+
+    let document = tree.document();
+    let schema = Schema::new(&document);
+    let mut diagnostics = Vec::new();
+    for directive in document
+        .syntax()
+        .descendants()
+        .filter_map(ast::Directive::cast)
+    {
+        let directive_name = name(directive.name());
+        if !schema.is_known_directive(&directive_name) {
+            diagnostics.push(diagnostic(
+                sdl,
+                text_range(directive.syntax()),
+                &format!("@{}", directive_name),
+                &format!(
+                    "Unknown directive `@{0}`. Define it with `directive @{0}`, or import it with `@link`.",
+                    directive_name
+                ),
+            ));
+        }
+    }
+
+    for (type_name, directives, fields) in fields_definitions(&document) {
+        let mut check = |directive: ast::Directive,
+                         directive_name: &str,
+                         coordinate: &str,
+                         ty: &str,
+                         require_external: bool| {
+            if let Err((range, problem)) = schema.check_field_set(&directive, ty, require_external)
+            {
+                diagnostics.push(diagnostic(
+                    sdl,
+                    range,
+                    coordinate,
+                    &format!(
+                        "`@{}` on `{}` is invalid: {}.",
+                        directive_name, coordinate, problem
+                    ),
+                ));
+            }
+        };
+        for key in schema.links.federation_directives(&directives, "key") {
+            check(key, "key", &type_name, &type_name, false);
+        }
+        for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+            let coordinate = format!("{}.{}", type_name, name(field.name()));
+            let directives = field.directives();
+            // a field can only require fields of its own type that another subgraph resolves
+            for requires in schema.links.federation_directives(&directives, "requires") {
+                check(requires, "requires", &coordinate, &type_name, true);
+            }
+            if let Some(return_type) = field.ty().and_then(named_type) {
+                for provides in schema.links.federation_directives(&directives, "provides") {
+                    check(provides, "provides", &coordinate, &return_type, false);
+                }
+            }
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.start_byte_offset);
+    diagnostics
+}
+
+/// How a subgraph depends on the types and fields it shares with other subgraphs.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct FederationUsage {
+    /// Every `@key`, `@requires`, and `@provides` in the subgraph
+    pub(crate) field_sets: Vec<FieldSetUsage>,
+    /// The fields it marks `@external`, like `Product.weight`
+    pub(crate) external_fields: BTreeSet<String>,
+    /// The fields it defines and resolves itself
+    pub(crate) fields: BTreeSet<String>,
+    /// The types it defines a `@key` for
+    pub(crate) entities: BTreeSet<String>,
+}
+
+/// A `@key`, `@requires`, or `@provides`, and the fields it selects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldSetUsage {
+    pub(crate) directive: &'static str,
+    /// The type or field it's on, like `Product` or `Product.shippingEstimate`
+    pub(crate) coordinate: String,
+    /// The fields it selects, including nested ones, like `Product.sku` and `Sku.code`
+    pub(crate) selected: Vec<String>,
+}
+
+impl FederationUsage {
+    /// Finds the federation relationships in `sdl`. Invalid selections are left out, since
+    /// linting reports them.
+    pub(crate) fn from_sdl(sdl: &str) -> anyhow::Result<Self> {
+        let tree = SdlParser::new(sdl).parse();
+        if let Some(error) = tree.errors().next() {
+            return Err(anyhow!("{} at index {}", error.message(), error.index()));
+        }
+        let document = tree.document();
+        let schema = Schema::new(&document);
+        let mut usage = Self::default();
+        for (type_name, type_fields) in &schema.types {
+            for (field_name, field) in &type_fields.fields {
+                let coordinate = format!("{}.{}", type_name, field_name);
+                if type_fields.external || field.external {
+                    usage.external_fields.insert(coordinate);
+                } else {
+                    usage.fields.insert(coordinate);
+                }
+            }
+        }
+        for (type_name, directives, fields) in fields_definitions(&document) {
+            let mut add = |directive: &'static str,
+                           coordinate: &str,
+                           ty: &str,
+                           found: Vec<ast::Directive>| {
+                for field_set in found {
+                    if let Ok((_, selection_set)) = parse_field_set(&field_set) {
+                        let mut selected = Vec::new();
+                        schema.selected_fields(ty, selection_set, &mut selected);
+                        usage.field_sets.push(FieldSetUsage {
+                            directive,
+                            coordinate: coordinate.to_string(),
+                            selected,
+                        });
+                    }
+                }
+            };
+            let keys = schema.links.federation_directives(&directives, "key");
+            if !keys.is_empty() {
+                usage.entities.insert(type_name.clone());
+            }
+            add("key", &type_name, &type_name, keys);
+            for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+                let coordinate = format!("{}.{}", type_name, name(field.name()));
+                let directives = field.directives();
+                add(
+                    "requires",
+                    &coordinate,
+                    &type_name,
+                    schema.links.federation_directives(&directives, "requires"),
+                );
+                if let Some(return_type) = field.ty().and_then(named_type) {
+                    add(
+                        "provides",
+                        &coordinate,
+                        &return_type,
+                        schema.links.federation_directives(&directives, "provides"),
+                    );
+                }
+            }
+        }
+        Ok(usage)
+    }
+}
+
+/// What the checks need to know about the whole schema.
+#[derive(Debug, Default)]
+struct Schema {
+    links: Links,
+    defined_directives: BTreeSet<String>,
+    /// The fields of every object and interface, including the fields of their extensions
+    types: BTreeMap<String, TypeFields>,
+}
+
+#[derive(Debug, Default)]
+struct TypeFields {
+    /// Whether the type is `@external`, which makes all of its fields external
+    external: bool,
+    fields: BTreeMap<String, Field>,
+}
+
+#[derive(Debug)]
+struct Field {
+    /// The named type it returns
+    ty: Option<String>,
+    external: bool,
+}
+
+impl Schema {
+    fn new(document: &ast::Document) -> Self {
+        let mut schema = Self::default();
+        for definition in document.definitions() {
+            match definition {
+                ast::Definition::SchemaDefinition(d) => schema.links.add(d.directives()),
+                ast::Definition::SchemaExtension(d) => schema.links.add(d.directives()),
+                ast::Definition::DirectiveDefinition(d) => {
+                    schema.defined_directives.insert(name(d.name()));
+                }
+                _ => {}
+            }
+        }
+        for (type_name, directives, fields) in fields_definitions(document) {
+            let external = !schema
+                .links
+                .federation_directives(&directives, "external")
+                .is_empty();
+            let type_fields = schema.types.entry(type_name).or_default();
+            type_fields.external |= external;
+            for field in fields.iter().flat_map(|fields| fields.field_definitions()) {
+                let external = !schema
+                    .links
+                    .federation_directives(&field.directives(), "external")
+                    .is_empty();
+                type_fields.fields.insert(
+                    name(field.name()),
+                    Field {
+                        ty: field.ty().and_then(named_type),
+                        external,
+                    },
+                );
+            }
+        }
+        schema
+    }
+
+    fn is_known_directive(&self, directive_name: &str) -> bool {
+        BUILT_IN_DIRECTIVES.contains(&directive_name)
+            || directive_name == "link"
+            || self.defined_directives.contains(directive_name)
+            || self.links.resolve(directive_name).is_some()
+            || (!self.links.federation && FEDERATION_DIRECTIVES.contains(&directive_name))
+    }
+
+    /// Checks the `fields` argument of a `@key`, `@requires`, or `@provides` against `type_name`,
+    /// returning where the problem is and what it is.
+    fn check_field_set(
+        &self,
+        directive: &ast::Directive,
+        type_name: &str,
+        require_external: bool,
+    ) -> Result<(), (Range<usize>, String)> {
+        let (range, selection_set) = parse_field_set(directive)?;
+        let mut problems = Vec::new();
+        self.check_selection_set(
+            type_name,
+            selection_set,
+            "",
+            require_external,
+            &mut problems,
+        );
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err((range, problems.join(", and ")))
+        }
+    }
+
+    /// The coordinates of the fields `selection_set` selects from `type_name`, and from the
+    /// types of the fields it selects.
+    fn selected_fields(
+        &self,
+        type_name: &str,
+        selection_set: ast::SelectionSet,
+        selected: &mut Vec<String>,
+    ) {
+        for selection in selection_set.selections() {
+            match selection {
+                ast::Selection::Field(field) => {
+                    let field_name = name(field.name());
+                    if field_name == "__typename" {
+                        continue;
+                    }
+                    let field_type = self
+                        .types
+                        .get(type_name)
+                        .and_then(|type_fields| type_fields.fields.get(&field_name))
+                        .and_then(|field| field.ty.clone());
+                    selected.push(format!("{}.{}", type_name, field_name));
+                    if let (Some(selection_set), Some(ty)) = (field.selection_set(), field_type) {
+                        self.selected_fields(&ty, selection_set, selected);
+                    }
+                }
+                ast::Selection::InlineFragment(fragment) => {
+                    let fragment_type = fragment
+                        .type_condition()
+                        .and_then(|condition| condition.named_type())
+                        .map(|ty| name(ty.name()))
+                        .unwrap_or_else(|| type_name.to_string());
+                    if let Some(selection_set) = fragment.selection_set() {
+                        self.selected_fields(&fragment_type, selection_set, selected);
+                    }
+                }
+                ast::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn check_selection_set(
+        &self,
+        type_name: &str,
+        selection_set: ast::SelectionSet,
+        path: &str,
+        require_external: bool,
+        problems: &mut Vec<String>,
+    ) {
+        // scalars and types from other subgraphs can't be checked here
+        let Some(type_fields) = self.types.get(type_name) else {
+            return;
+        };
+        for selection in selection_set.selections() {
+            match selection {
+                ast::Selection::Field(field) => {
+                    let field_name = name(field.name());
+                    if field_name == "__typename" {
+                        continue;
+                    }
+                    let field_path = match path {
+                        "" => field_name.clone(),
+                        path => format!("{}.{}", path, field_name),
+                    };
+                    match type_fields.fields.get(&field_name) {
+                        None => problems
+                            .push(format!("`{}` isn't a field of `{}`", field_path, type_name)),
+                        Some(selected) => {
+                            if require_external && !type_fields.external && !selected.external {
+                                problems.push(format!("`{}` isn't marked `@external`", field_path));
+                            }
+                            if let (Some(selection_set), Some(ty)) =
+                                (field.selection_set(), &selected.ty)
+                            {
+                                self.check_selection_set(
+                                    ty,
+                                    selection_set,
+                                    &field_path,
+                                    false,
+                                    problems,
+                                );
+                            }
+                        }
+                    }
+                }
+                ast::Selection::InlineFragment(fragment) => {
+                    let fragment_type = fragment
+                        .type_condition()
+                        .and_then(|condition| condition.named_type())
+                        .map(|ty| name(ty.name()))
+                        .unwrap_or_else(|| type_name.to_string());
+                    if let Some(selection_set) = fragment.selection_set() {
+                        self.check_selection_set(
+                            &fragment_type,
+                            selection_set,
+                            path,
+                            require_external,
+                            problems,
+                        );
+                    }
+                }
+                ast::Selection::FragmentSpread(_) => {
+                    problems.push("field sets can't use named fragments".to_string())
+                }
+            }
+        }
+    }
+}
+
+/// The specs a schema links to with `@link`, and the directives it imports from them.
+#[derive(Debug, Default)]
+struct Links {
+    /// Whether the federation spec is linked, which means its directives have to be imported
+    /// or namespaced, like `@federation__key`
+    federation: bool,
+    /// The spec and directive that each imported directive name refers to
+    imports: BTreeMap<String, (String, String)>,
+    /// The spec that each namespace, like `federation` in `@federation__key`, refers to
+    namespaces: BTreeMap<String, String>,
+}
+
+impl Links {
+    fn add(&mut self, directives: Option<ast::Directives>) {
+        for link in directives
+            .iter()
+            .flat_map(|directives| directives.directives())
+            .filter(|directive| name(directive.name()) == "link")
+        {
+            let arguments = link
+                .arguments()
+                .into_iter()
+                .flat_map(|arguments| arguments.arguments())
+                .map(|argument| (name(argument.name()), argument.value()))
+                .collect::<BTreeMap<_, _>>();
+            let Some(Some(ast::Value::StringValue(url))) = arguments.get("url") else {
+                continue;
+            };
+            let Some(spec) = spec_name(&String::from(url.clone())) else {
+                continue;
+            };
+            let namespace = match arguments.get("as") {
+                Some(Some(ast::Value::StringValue(namespace))) => String::from(namespace.clone()),
+                _ => spec.clone(),
+            };
+            if let Some(Some(ast::Value::ListValue(imports))) = arguments.get("import") {
+                for import in imports.values() {
+                    let (imported, local) = match import {
+                        ast::Value::StringValue(imported) => {
+                            let imported = String::from(imported);
+                            (imported.clone(), imported)
+                        }
+                        ast::Value::ObjectValue(import) => {
+                            let field = |field_name: &str| {
+                                import
+                                    .object_fields()
+                                    .find(|field| name(field.name()) == field_name)
+                                    .and_then(|field| match field.value() {
+                                        Some(ast::Value::StringValue(value)) => {
+                                            Some(String::from(value))
+                                        }
+                                        _ => None,
+                                    })
+                            };
+                            let Some(imported) = field("name") else {
+                                continue;
+                            };
+                            let local = field("as").unwrap_or_else(|| imported.clone());
+                            (imported, local)
+                        }
+                        _ => continue,
+                    };
+                    // types can be imported too, but only directives start with `@`
+                    if let (Some(imported), Some(local)) =
+                        (imported.strip_prefix('@'), local.strip_prefix('@'))
+                    {
+                        self.imports
+                            .insert(local.to_string(), (spec.clone(), imported.to_string()));
+                    }
+                }
+            }
+            self.federation |= spec == "federation";
+            self.namespaces.insert(namespace, spec);
+        }
+    }
+
+    /// The spec and the spec's name for a directive that comes from a linked spec.
+    fn resolve(&self, directive_name: &str) -> Option<(String, String)> {
+        if let Some(imported) = self.imports.get(directive_name) {
+            return Some(imported.clone());
+        }
+        let (namespace, spec_directive) = directive_name.split_once("__")?;
+        let spec = self.namespaces.get(namespace)?;
+        Some((spec.clone(), spec_directive.to_string()))
+    }
+
+    /// The directives in `directives` that are the federation directive `federation_directive`,
+    /// under whatever name the schema uses for it.
+    fn federation_directives(
+        &self,
+        directives: &Option<ast::Directives>,
+        federation_directive: &str,
+    ) -> Vec<ast::Directive> {
+        directives
+            .iter()
+            .flat_map(|directives| directives.directives())
+            .filter(|directive| {
+                let directive_name = name(directive.name());
+                match self.resolve(&directive_name) {
+                    Some((spec, spec_directive)) => {
+                        spec == "federation" && spec_directive == federation_directive
+                    }
+                    None => !self.federation && directive_name == federation_directive,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The `fields` argument of a `@key`, `@requires`, or `@provides`, and where it is. Returns
+/// where the problem is and what it is if it isn't a valid selection.
+fn parse_field_set(
+    directive: &ast::Directive,
+) -> Result<(Range<usize>, ast::SelectionSet), (Range<usize>, String)> {
+    let fields = directive
+        .arguments()
+        .into_iter()
+        .flat_map(|arguments| arguments.arguments())
+        .find(|argument| name(argument.name()) == "fields")
+        .and_then(|argument| argument.value());
+    let (range, fields) = match fields {
+        Some(ast::Value::StringValue(fields)) => {
+            (text_range(fields.syntax()), String::from(fields))
+        }
+        _ => {
+            return Err((
+                text_range(directive.syntax()),
+                "it needs a `fields` string with the fields it selects".to_string(),
+            ))
+        }
+    };
+    // there's no parser entry point for a bare selection set, so parse it as a query
+    let tree = SdlParser::new(&format!("{{ {} }}", fields)).parse();
+    let invalid = |message: &str| {
+        (
+            range.clone(),
+            format!("`{}` isn't a valid selection: {}", fields.trim(), message),
+        )
+    };
+    if let Some(error) = tree.errors().next() {
+        return Err(invalid(error.message()));
+    }
+    tree.document()
+        .definitions()
+        .find_map(|definition| match definition {
+            ast::Definition::OperationDefinition(operation) => operation.selection_set(),
+            _ => None,
+        })
+        .map(|selection_set| (range.clone(), selection_set))
+        .ok_or_else(|| invalid("it doesn't select any fields"))
+}
+
+/// The name of the spec a `@link` URL points to, like `federation` for
+/// `https://specs.apollo.dev/federation/v2.3`.
+fn spec_name(url: &str) -> Option<String> {
+    let mut segments = url.trim_end_matches('/').rsplit('/');
+    let last = segments.next()?;
+    let spec = match last.strip_prefix('v') {
+        Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => segments.next()?,
+        _ => last,
+    };
+    Some(spec.to_string())
+}
+
+/// Every object and interface definition and extension, with its directives and fields.
+fn fields_definitions(
+    document: &ast::Document,
+) -> Vec<(
+    String,
+    Option<ast::Directives>,
+    Option<ast::FieldsDefinition>,
+)> {
+    document
+        .definitions()
+        .filter_map(|definition| match definition {
+            ast::Definition::ObjectTypeDefinition(d) => {
+                Some((name(d.name()), d.directives(), d.fields_definition()))
+            }
+            ast::Definition::ObjectTypeExtension(d) => {
+                Some((name(d.name()), d.directives(), d.fields_definition()))
+            }
+            ast::Definition::InterfaceTypeDefinition(d) => {
+                Some((name(d.name()), d.directives(), d.fields_definition()))
+            }
+            ast::Definition::InterfaceTypeExtension(d) => {
+                Some((name(d.name()), d.directives(), d.fields_definition()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn diagnostic(sdl: &str, range: Range<usize>, coordinate: &str, message: &str) -> Diagnostic {
+    let start = range.start.min(sdl.len());
+    Diagnostic {
+        level: ERROR.to_string(),
+        message: message.to_string(),
+        coordinate: coordinate.to_string(),
+        start_line: sdl[..start].matches('\n').count() as i64 + 1,
+        start_byte_offset: start,
+        end_byte_offset: range.end.clamp(start, sdl.len()),
+    }
+}
+
+fn text_range(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    usize::from(range.start())..usize::from(range.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lint;
+
+    fn messages(sdl: &str) -> Vec<String> {
+        lint(sdl)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn it_accepts_a_valid_federation_2_subgraph() {
+        let sdl = r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external", { name: "@requires", as: "@needs" }])
+
+type Query {
+  product(id: ID!): Product
+}
+
+type Product @key(fields: "id sku { code }") {
+  id: ID!
+  sku: Sku!
+  weight: Float @external
+  shippingEstimate: Float @needs(fields: "weight")
+  inStock: Boolean @federation__shareable
+}
+
+type Sku {
+  code: String!
+}
+"#;
+        assert_eq!(messages(sdl), Vec::<String>::new());
+    }
+
+    #[test]
+    fn it_reports_federation_problems() {
+        let sdl = r#"extend schema
+  @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@requires"])
+
+type Query {
+  product(id: ID!): Product @cached
+}
+
+type Product @key(fields: "id upc") @shareable {
+  id: ID!
+  weight: Float
+  shippingEstimate: Float @requires(fields: "weight")
+}
+"#;
+        assert_eq!(
+            messages(sdl),
+            vec![
+                "Unknown directive `@cached`. Define it with `directive @cached`, or import it with `@link`.",
+                "`@key` on `Product` is invalid: `upc` isn't a field of `Product`.",
+                "Unknown directive `@shareable`. Define it with `directive @shareable`, or import it with `@link`.",
+                "`@requires` on `Product.shippingEstimate` is invalid: `weight` isn't marked `@external`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_accepts_federation_1_directives_without_imports() {
+        let sdl = r#"type Review {
+  product: Product @provides(fields: "name")
+}
+
+extend type Product @key(fields: "upc") {
+  upc: String! @external
+  name: String @external
+}
+"#;
+        assert_eq!(messages(sdl), Vec::<String>::new());
+    }
+}
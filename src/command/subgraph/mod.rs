@@ -1,9 +1,13 @@
 mod check;
 mod delete;
-mod fetch;
+mod diff;
+pub(crate) mod fetch;
+pub(crate) mod impact;
 mod introspect;
 mod lint;
 mod list;
+mod local_composition;
+pub(crate) mod local_lint;
 mod publish;
 
 pub use introspect::Introspect;
@@ -11,7 +15,7 @@ pub use introspect::Introspect;
 use clap::Parser;
 use serde::Serialize;
 
-use crate::options::OutputOpts;
+use crate::options::{OutputOpts, ProfileOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -32,9 +36,16 @@ pub enum Command {
     /// Delete a subgraph from the Apollo registry and trigger composition in the graph router
     Delete(delete::Delete),
 
+    /// Compare two schemas of a subgraph, from graph refs or files, and list the changes by severity
+    Diff(diff::Diff),
+
     /// Fetch a subgraph schema from the Apollo graph registry
     Fetch(fetch::Fetch),
 
+    /// Report which types and fields in other subgraphs depend on the changes in a proposed subgraph schema,
+    /// through `@key`, `@requires`, `@provides`, and `@external`
+    Impact(impact::Impact),
+
     /// Introspect a running subgraph endpoint to retrieve its schema definition (SDL)
     Introspect(introspect::Introspect),
 
@@ -62,9 +73,17 @@ impl Subgraph {
             }
             Command::Delete(command) => command.run(client_config),
             Command::Introspect(command) => {
-                command.run(client_config.get_reqwest_client()?, output_opts)
+                // introspection doesn't take a `--profile`, so it uses the default profile's settings
+                let client = client_config.get_endpoint_reqwest_client(&ProfileOpt::default())?;
+                command.run(
+                    client,
+                    client_config.get_builder().get_retries(),
+                    output_opts,
+                )
             }
+            Command::Diff(command) => command.run(client_config),
             Command::Fetch(command) => command.run(client_config),
+            Command::Impact(command) => command.run(client_config),
             Command::Lint(command) => command.run(client_config),
             Command::List(command) => command.run(client_config),
             Command::Publish(command) => command.run(client_config, git_context),
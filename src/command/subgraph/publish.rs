@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{self, IsTerminal};
 
 use anyhow::anyhow;
@@ -6,13 +7,25 @@ use reqwest::Url;
 use rover_client::operations::subgraph::routing_url::{self, SubgraphRoutingUrlInput};
 use serde::Serialize;
 
-use crate::options::{GraphRefOpt, ProfileOpt, SchemaOpt, SubgraphOpt};
+use crate::command::schema::diff::{SchemaDiff, SeverityOverrides};
+use crate::options::{GraphRefOpt, ProfileOpt, SubgraphOpt};
 use crate::utils::client::StudioClientConfig;
+use crate::utils::events::{self, Event};
+use crate::utils::parsers::{parse_header, FileDescriptorType};
+use crate::utils::protected_variant::{confirm_protected_publish, ProtectedVariants};
+use crate::utils::scope::{check_key_scope, RequiredScope};
 use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
-use rover_client::operations::subgraph::publish::{self, SubgraphPublishInput};
+use rover_client::blocking::StudioClient;
+use rover_client::operations::subgraph::fetch::{self, SubgraphFetchInput};
+use rover_client::operations::subgraph::introspect::{self, SubgraphIntrospectInput};
+use rover_client::operations::subgraph::launch::{self, SubgraphLaunchInput};
+use rover_client::operations::subgraph::publish::{
+    self, SubgraphPublishInput, SubgraphPublishResponse,
+};
 use rover_client::shared::GitContext;
-use rover_std::Style;
+use rover_client::RoverClientError;
+use rover_std::{Emoji, Style};
 
 #[derive(Debug, Serialize, Parser)]
 pub struct Publish {
@@ -25,16 +38,41 @@ pub struct Publish {
     #[clap(flatten)]
     profile: ProfileOpt,
 
-    #[clap(flatten)]
+    /// The schema file to publish. You can pass `-` to use stdin instead of a file.
+    #[arg(
+        long,
+        short = 's',
+        required_unless_present = "schema_from_url",
+        conflicts_with = "schema_from_url"
+    )]
+    #[serde(skip_serializing)]
+    schema: Option<FileDescriptorType>,
+
+    /// The endpoint of a running subgraph to introspect, publishing the
+    /// resulting schema instead of a schema file
+    #[arg(long)]
+    #[serde(skip_serializing)]
+    schema_from_url: Option<Url>,
+
+    /// Headers to pass to the `--schema-from-url` endpoint. Values must be key:value pairs.
+    /// If a value has a space in it, use quotes around the pair,
+    /// ex. -H "Auth:some key"
+    #[arg(
+        value_name = "KEY:VALUE",
+        long = "header",
+        short = 'H',
+        value_parser = parse_header,
+        requires = "schema_from_url"
+    )]
     #[serde(skip_serializing)]
-    schema: SchemaOpt,
+    headers: Option<Vec<(String, String)>>,
 
     /// Indicate whether to convert a non-federated graph into a subgraph
     #[arg(short, long)]
     convert: bool,
 
     /// Url of a running subgraph that a supergraph can route operations to
-    /// (often a deployed subgraph). May be left empty ("") or a placeholder url
+    /// (often a deployed subgraph). May be set to `none` (with `--skip-url-check`)
     /// if not running a gateway or router in managed federation mode
     #[arg(long)]
     #[serde(skip_serializing)]
@@ -48,10 +86,36 @@ pub struct Publish {
     allow_invalid_routing_url: bool,
 
     /// This is shorthand for `--routing-url "" --allow-invalid-routing-url`.
-    #[arg(long)]
+    #[arg(long, conflicts_with_all = ["routing_url", "skip_url_check"])]
     no_url: bool,
+
+    /// Acknowledge that this subgraph is intentionally published without a
+    /// routable URL. Required when passing `--routing-url none`, and skips
+    /// all routing url validation.
+    #[arg(long)]
+    skip_url_check: bool,
+
+    /// Skip the confirmation before publishing to a variant listed in `APOLLO_PROTECTED_VARIANTS`
+    /// or the profile's `protected-variants` default. Required to publish to one in a non-TTY
+    /// environment.
+    #[arg(long, short = 'y')]
+    yes: bool,
+
+    /// Wait for the launch this publish starts to finish, and fail if it does.
+    /// A launch finishes once the new supergraph has been delivered to Uplink,
+    /// where routers fetch it from.
+    #[arg(long)]
+    wait_for_launch: bool,
+
+    /// How long to wait for the launch to finish, in seconds
+    #[arg(long, requires = "wait_for_launch", default_value_t = 300)]
+    launch_timeout: u64,
 }
 
+/// The value accepted by `--routing-url` to mark a subgraph as intentionally
+/// not routable, e.g. for internal-only graphs or pre-provisioned environments
+const PLACEHOLDER_ROUTING_URL: &str = "none";
+
 impl Publish {
     pub fn run(
         &self,
@@ -59,11 +123,17 @@ impl Publish {
         git_context: GitContext,
     ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
+        check_key_scope(
+            &client,
+            Some(&self.graph.graph_ref),
+            RequiredScope::PushSchemas,
+        )?;
 
         let url = Self::determine_routing_url(
             self.no_url,
             &self.routing_url,
             self.allow_invalid_routing_url,
+            self.skip_url_check,
             || {
                 Ok(routing_url::run(
                     SubgraphRoutingUrlInput {
@@ -85,12 +155,43 @@ impl Publish {
             Style::Command.paint(&self.profile.profile_name)
         );
 
-        let schema = self
-            .schema
-            .read_file_descriptor("SDL", &mut std::io::stdin())?;
+        let schema = self.read_schema(&client_config)?;
 
         tracing::debug!("Publishing \n{}", &schema);
 
+        if !self.yes {
+            let protected_variants =
+                ProtectedVariants::new(&client_config, &self.profile.profile_name);
+            if let Some(protected_by) = protected_variants.matching_pattern(&self.graph.graph_ref) {
+                let current_schema = match fetch::run(
+                    SubgraphFetchInput {
+                        graph_ref: self.graph.graph_ref.clone(),
+                        subgraph_name: self.subgraph.subgraph_name.clone(),
+                    },
+                    &client,
+                ) {
+                    Ok(fetch_response) => fetch_response.sdl.contents,
+                    // a subgraph that hasn't been published yet has no current schema to compare to
+                    Err(
+                        RoverClientError::NoSubgraphInGraph { .. }
+                        | RoverClientError::GraphNotFound { .. },
+                    ) => String::new(),
+                    Err(RoverClientError::ExpectedFederatedGraph { .. }) if self.convert => {
+                        String::new()
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                confirm_protected_publish(
+                    &self.graph.graph_ref,
+                    protected_by,
+                    &SchemaDiff::new(&current_schema, &schema, &SeverityOverrides::default())?,
+                    &mut io::stdin(),
+                    &mut io::stderr(),
+                    io::stderr().is_terminal() && io::stdin().is_terminal(),
+                )?;
+            }
+        }
+
         let publish_response = publish::run(
             SubgraphPublishInput {
                 graph_ref: self.graph.graph_ref.clone(),
@@ -102,6 +203,16 @@ impl Publish {
             },
             &client,
         )?;
+        events::emit(Event::SubgraphRegistered {
+            graph_ref: self.graph.graph_ref.to_string(),
+            subgraph: self.subgraph.subgraph_name.clone(),
+            created: publish_response.subgraph_was_created,
+            supergraph_updated: publish_response.supergraph_was_updated,
+        });
+
+        if self.wait_for_launch {
+            self.wait_for_launch(&publish_response, &client)?;
+        }
 
         Ok(RoverOutput::SubgraphPublishResponse {
             graph_ref: self.graph.graph_ref.clone(),
@@ -110,10 +221,78 @@ impl Publish {
         })
     }
 
+    /// Reads the schema to publish from `--schema`, or introspects it from
+    /// the subgraph running at `--schema-from-url`.
+    fn read_schema(&self, client_config: &StudioClientConfig) -> RoverResult<String> {
+        match (&self.schema, &self.schema_from_url) {
+            (Some(schema), _) => schema.read_file_descriptor("SDL", &mut std::io::stdin()),
+            (None, Some(endpoint)) => {
+                eprintln!(
+                    "Introspecting the subgraph schema from {}",
+                    Style::Link.paint(endpoint.as_str())
+                );
+                let client = client_config
+                    .get_builder()
+                    .build_graphql_client(endpoint.as_str())?;
+                let headers: HashMap<String, String> =
+                    self.headers.iter().flatten().cloned().collect();
+                Ok(introspect::run(SubgraphIntrospectInput { headers }, &client, true)?.result)
+            }
+            (None, None) => Err(RoverError::new(anyhow!(
+                "You must pass either --schema or --schema-from-url."
+            ))),
+        }
+    }
+
+    fn wait_for_launch(
+        &self,
+        publish_response: &SubgraphPublishResponse,
+        client: &StudioClient,
+    ) -> RoverResult<()> {
+        if !publish_response.build_errors.is_empty() {
+            return Err(RoverClientError::LaunchFailed {
+                graph_ref: self.graph.graph_ref.clone(),
+                launch_url: publish_response.launch_url.clone(),
+                errors: vec![publish_response.build_errors.to_string()],
+            }
+            .into());
+        }
+        let launch_id = match &publish_response.launch_id {
+            Some(launch_id) => launch_id.clone(),
+            None => {
+                eprintln!("This publish didn't start a launch, so there's nothing to wait for.");
+                return Ok(());
+            }
+        };
+        eprintln!(
+            "{}Waiting for launch {} to deliver the new supergraph for {} to Uplink...",
+            Emoji::Hourglass,
+            &launch_id,
+            Style::Link.paint(self.graph.graph_ref.to_string())
+        );
+        let launch_response = launch::run(
+            SubgraphLaunchInput {
+                graph_ref: self.graph.graph_ref.clone(),
+                launch_id,
+                launch_url: publish_response.launch_url.clone(),
+                timeout_seconds: self.launch_timeout,
+            },
+            client,
+        )?;
+        eprintln!(
+            "{}Launch {} finished, and routers can now fetch the new supergraph.",
+            Emoji::Success,
+            launch_response.launch_id
+        );
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn determine_routing_url<F>(
         no_url: bool,
         routing_url: &Option<String>,
         allow_invalid_routing_url: bool,
+        skip_url_check: bool,
 
         // For testing purposes, we pass in a closure for fetching the
         // routing url from GraphOS
@@ -128,10 +307,40 @@ impl Publish {
     where
         F: Fn() -> RoverResult<String>,
     {
-        if no_url && routing_url.is_some() {
+        // a placeholder routing url is only accepted alongside an explicit
+        // acknowledgment, so it can't be published by accident
+        let is_placeholder = routing_url
+            .as_ref()
+            .map(|url| Self::is_placeholder_routing_url(url))
+            .unwrap_or(false);
+        if is_placeholder && !skip_url_check {
             return Err(RoverError::new(anyhow!(
-                "You cannot use --no-url and --routing-url at the same time."
-            )));
+                "You cannot use `--routing-url {}` without acknowledging that the subgraph will not be routable.",
+                PLACEHOLDER_ROUTING_URL
+            ))
+            .with_suggestion(RoverErrorSuggestion::AcknowledgePlaceholderRoutingUrl));
+        }
+
+        // GraphOS already reads an empty routing url as a subgraph without one, so that's what
+        // the placeholder publishes, rather than a `none` that would look like a hostname
+        if is_placeholder {
+            return Ok(Some("".to_string()));
+        }
+
+        // if --skip-url-check is set, we send whatever was provided (which may
+        // be nothing at all, leaving the existing routing url untouched).
+        // an empty string is easy to pass by accident (like an unset variable),
+        // so we point folks at the placeholder.
+        if skip_url_check {
+            if let Some(url) = routing_url {
+                if url.trim().is_empty() {
+                    return Err(RoverError::new(anyhow!(
+                        "You cannot publish an empty routing URL with --skip-url-check."
+                    ))
+                    .with_suggestion(RoverErrorSuggestion::AcknowledgePlaceholderRoutingUrl));
+                }
+            }
+            return Ok(routing_url.clone());
         }
 
         // if --allow-invalid-routing-url is not provided, we need to inspect
@@ -165,6 +374,14 @@ impl Publish {
         }
     }
 
+    /// Whether a `--routing-url` value is the placeholder for a subgraph
+    /// that is intentionally not routable
+    fn is_placeholder_routing_url(routing_url: &str) -> bool {
+        routing_url
+            .trim()
+            .eq_ignore_ascii_case(PLACEHOLDER_ROUTING_URL)
+    }
+
     fn handle_maybe_invalid_routing_url(
         maybe_invalid_routing_url: &Option<String>,
         // For testing purposes, we pass in stub `Write`er and `Read`ers to
@@ -258,8 +475,46 @@ impl Publish {
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use crate::command::subgraph::publish::Publish;
 
+    #[test]
+    fn test_schema_from_url_args() {
+        let publish = Publish::try_parse_from([
+            "publish",
+            "my-graph@current",
+            "--name",
+            "accounts",
+            "--schema-from-url",
+            "http://accounts.internal/graphql",
+            "--header",
+            "Authorization: Bearer token",
+        ])
+        .unwrap();
+        assert!(publish.schema.is_none());
+        assert_eq!(
+            publish.schema_from_url.unwrap().as_str(),
+            "http://accounts.internal/graphql"
+        );
+        assert_eq!(publish.headers.unwrap().len(), 1);
+
+        assert!(Publish::try_parse_from([
+            "publish",
+            "my-graph@current",
+            "--name",
+            "accounts",
+            "--schema",
+            "schema.graphql",
+            "--schema-from-url",
+            "http://accounts.internal/graphql",
+        ])
+        .is_err());
+        assert!(
+            Publish::try_parse_from(["publish", "my-graph@current", "--name", "accounts"]).is_err()
+        );
+    }
+
     #[test]
     fn test_no_url() {
         let mut input: &[u8] = &[];
@@ -268,6 +523,7 @@ mod tests {
             true,
             &None,
             false,
+            false,
             || Ok("".to_string()),
             &mut output,
             &mut input,
@@ -285,6 +541,7 @@ mod tests {
             false,
             &Some("https://provided".to_string()),
             false,
+            false,
             || Ok("".to_string()),
             &mut output,
             &mut input,
@@ -295,23 +552,31 @@ mod tests {
     }
 
     #[test]
-    fn test_no_url_and_routing_url_provided() {
-        let mut input: &[u8] = &[];
-        let mut output: Vec<u8> = Vec::new();
-        let result = Publish::determine_routing_url(
-            true,
-            &Some("https://provided".to_string()),
-            false,
-            || Ok("".to_string()),
-            &mut output,
-            &mut input,
-            true,
-        )
-        .unwrap_err();
-        assert_eq!(
-            result.message(),
-            "You cannot use --no-url and --routing-url at the same time."
-        );
+    fn test_no_url_conflicts_with_routing_url_and_skip_url_check() {
+        assert!(Publish::try_parse_from([
+            "publish",
+            "my-graph@current",
+            "--name",
+            "accounts",
+            "--schema",
+            "accounts.graphql",
+            "--no-url",
+            "--routing-url",
+            "https://provided",
+        ])
+        .is_err());
+        // --no-url publishes an empty routing url, which is exactly what --skip-url-check refuses
+        assert!(Publish::try_parse_from([
+            "publish",
+            "my-graph@current",
+            "--name",
+            "accounts",
+            "--schema",
+            "accounts.graphql",
+            "--no-url",
+            "--skip-url-check",
+        ])
+        .is_err());
     }
 
     #[test]
@@ -322,6 +587,7 @@ mod tests {
             false,
             &None,
             false,
+            false,
             || Ok("https://fromstudio".to_string()),
             &mut output,
             &mut input,
@@ -341,6 +607,7 @@ mod tests {
             false,
             &Some("invalid".to_string()),
             false,
+            false,
             || Ok("".to_string()),
             &mut output,
             &mut input,
@@ -361,6 +628,7 @@ mod tests {
             true,
             &None,
             false,
+            false,
             || Ok("invalid".to_string()),
             &mut output,
             &mut input,
@@ -372,6 +640,86 @@ mod tests {
         assert!(std::str::from_utf8(&output).unwrap().is_empty());
     }
 
+    #[test]
+    fn test_placeholder_routing_url_without_acknowledgment() {
+        let mut input: &[u8] = &[];
+        let mut output: Vec<u8> = Vec::new();
+        let result = Publish::determine_routing_url(
+            false,
+            &Some("none".to_string()),
+            false,
+            false,
+            || Ok("".to_string()),
+            &mut output,
+            &mut input,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            result.message(),
+            "You cannot use `--routing-url none` without acknowledging that the subgraph will not be routable."
+        );
+    }
+
+    #[test]
+    fn test_placeholder_routing_url_with_skip_url_check() {
+        let mut input: &[u8] = &[];
+        let mut output: Vec<u8> = Vec::new();
+        let result = Publish::determine_routing_url(
+            false,
+            &Some("NONE".to_string()),
+            false,
+            true,
+            || panic!("the existing routing url should not be fetched"),
+            &mut output,
+            &mut input,
+            false,
+        )
+        .unwrap();
+        assert_eq!(result, Some("".to_string()));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_empty_routing_url_with_skip_url_check() {
+        let mut input: &[u8] = &[];
+        let mut output: Vec<u8> = Vec::new();
+        let result = Publish::determine_routing_url(
+            false,
+            &Some("".to_string()),
+            false,
+            true,
+            || Ok("".to_string()),
+            &mut output,
+            &mut input,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            result.message(),
+            "You cannot publish an empty routing URL with --skip-url-check."
+        );
+    }
+
+    #[test]
+    fn test_skip_url_check_does_not_validate() {
+        let mut input: &[u8] = &[];
+        let mut output: Vec<u8> = Vec::new();
+        let result = Publish::determine_routing_url(
+            false,
+            &Some("http://localhost:4001".to_string()),
+            false,
+            true,
+            || Ok("".to_string()),
+            &mut output,
+            &mut input,
+            false,
+        )
+        .unwrap();
+        assert_eq!(result, Some("http://localhost:4001".to_string()));
+        assert!(output.is_empty());
+    }
+
     #[test]
     fn test_confirm_invalid_url_publish() {
         let mut input = "y".as_bytes();
@@ -0,0 +1,129 @@
+use apollo_federation_types::{
+    build::BuildHint,
+    config::{FederationVersion, SupergraphConfig},
+};
+use camino::Utf8Path;
+use rover_std::Fs;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::RoverResult;
+
+/// A machine-readable summary of a `rover supergraph compose` run, written with `--build-report`.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct BuildReport {
+    pub(crate) success: bool,
+    pub(crate) federation_version: Option<FederationVersion>,
+    pub(crate) plugin_version: Option<String>,
+    pub(crate) subgraphs: Vec<SubgraphReport>,
+    pub(crate) timings: PhaseTimings,
+    pub(crate) hints: HintCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SubgraphReport {
+    pub(crate) name: String,
+    pub(crate) routing_url: String,
+    pub(crate) sdl_sha256: String,
+}
+
+/// How long each phase of composition took, in milliseconds.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct PhaseTimings {
+    pub(crate) resolve_ms: u64,
+    pub(crate) install_ms: u64,
+    pub(crate) compose_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct HintCounts {
+    pub(crate) total: usize,
+    pub(crate) by_code: BTreeMap<String, usize>,
+}
+
+impl BuildReport {
+    /// Starts a report from a resolved supergraph config, hashing each subgraph's SDL.
+    pub(crate) fn new(
+        supergraph_config: &SupergraphConfig,
+        resolve_time: Duration,
+    ) -> RoverResult<Self> {
+        let subgraphs = supergraph_config
+            .get_subgraph_definitions()?
+            .into_iter()
+            .map(|subgraph| SubgraphReport {
+                sdl_sha256: format!("{:x}", Sha256::digest(subgraph.sdl.as_bytes())),
+                name: subgraph.name,
+                routing_url: subgraph.url,
+            })
+            .collect();
+        Ok(Self {
+            federation_version: supergraph_config.get_federation_version(),
+            subgraphs,
+            timings: PhaseTimings {
+                resolve_ms: resolve_time.as_millis() as u64,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn record_hints(&mut self, hints: &[BuildHint]) {
+        self.hints.total = hints.len();
+        for hint in hints {
+            let code = hint.code.clone().unwrap_or_else(|| "UNKNOWN".to_string());
+            *self.hints.by_code.entry(code).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn write(&self, path: &Utf8Path) -> RoverResult<()> {
+        Fs::write_file(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::{build::BuildHint, config::SupergraphConfig};
+    use serde_json::json;
+
+    use std::time::Duration;
+
+    use super::BuildReport;
+
+    #[test]
+    fn it_reports_subgraph_hashes_and_hint_counts() {
+        let supergraph_config: SupergraphConfig = serde_yaml::from_str(
+            r#"
+federation_version: =2.3.2
+subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      sdl: "type Query { films: [String] }"
+"#,
+        )
+        .unwrap();
+        let mut report = BuildReport::new(&supergraph_config, Duration::from_millis(12)).unwrap();
+        let hints: Vec<BuildHint> = serde_json::from_value(json!([
+            { "message": "a", "code": "INCONSISTENT_FIELD" },
+            { "message": "b", "code": "INCONSISTENT_FIELD" },
+        ]))
+        .unwrap();
+        report.record_hints(&hints);
+
+        let report = serde_json::to_value(&report).unwrap();
+        assert_eq!(report["federation_version"], json!("=2.3.2"));
+        assert_eq!(report["subgraphs"][0]["name"], json!("films"));
+        assert_eq!(
+            report["subgraphs"][0]["sdl_sha256"].as_str().unwrap().len(),
+            64
+        );
+        assert_eq!(report["timings"]["resolve_ms"], json!(12));
+        assert_eq!(
+            report["hints"],
+            json!({ "total": 2, "by_code": { "INCONSISTENT_FIELD": 2 } })
+        );
+    }
+}
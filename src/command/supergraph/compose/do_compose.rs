@@ -1,29 +1,50 @@
-use crate::command::supergraph::resolve_supergraph_yaml;
-use crate::utils::{client::StudioClientConfig, parsers::FileDescriptorType};
+use crate::command::supergraph::{expand_supergraph_yaml, resolve_supergraph_yaml_with_sources};
+use crate::utils::{
+    client::StudioClientConfig,
+    events::{self, Event},
+    parsers::FileDescriptorType,
+    source_map::SubgraphSourceMap,
+};
 use crate::{
     command::{
-        install::{Install, Plugin},
-        supergraph::compose::CompositionOutput,
+        install::{resolve_federation_version, Install, Plugin},
+        supergraph::compose::{
+            build_report::BuildReport, format_hints, is_prerelease, CompositionOutput,
+        },
     },
-    options::PluginOpts,
+    options::{OutputOpts, PluginChannel, PluginOpts, RoverPrinter},
     RoverError, RoverErrorSuggestion, RoverOutput, RoverResult,
 };
 
 use anyhow::{anyhow, Context};
-use apollo_federation_types::config::SupergraphConfig;
+use apollo_federation_types::config::{SchemaSource, SupergraphConfig};
 use apollo_federation_types::{
     build::BuildResult,
     config::{FederationVersion, PluginVersion},
 };
+use crossbeam_channel::unbounded;
 use rover_client::RoverClientError;
-use rover_std::{Emoji, Style};
+use rover_std::{
+    perf::{self, Phase},
+    Emoji, Fs, Style,
+};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
 use serde::Serialize;
 use tempdir::TempDir;
 
-use std::{fs::File, io::Write, process::Command, str};
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{self, Write},
+    process::Command,
+    str,
+    time::{Duration, Instant},
+};
+
+/// How long a watched file must go without changing before the supergraph is composed again
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
 
 #[derive(Debug, Clone, Serialize, Parser)]
 pub struct Compose {
@@ -32,6 +53,50 @@ pub struct Compose {
     #[arg(long = "config")]
     supergraph_yaml: FileDescriptorType,
 
+    /// The path to an overlay file that patches the supergraph configuration for a specific
+    /// environment, such as its routing URLs, introspection headers, or schema sources.
+    /// Can be passed multiple times, in which case overlays are applied in order.
+    #[serde(skip_serializing)]
+    #[arg(long = "overlay")]
+    overlays: Vec<Utf8PathBuf>,
+
+    /// Write a JSON report of the build to this path, including a hash of each subgraph's SDL,
+    /// the composition plugin version, how long each phase took, and the number of hints by code.
+    /// The report is written whether or not composition succeeds.
+    #[serde(skip_serializing)]
+    #[arg(long = "build-report")]
+    build_report: Option<Utf8PathBuf>,
+
+    /// Keep running, and compose again whenever the supergraph config or a subgraph's schema
+    /// file changes. The supergraph schema is written again whenever it changes, so pass
+    /// `--output` to keep a file up to date for a router running with `--hot-reload`.
+    #[arg(long = "watch", conflicts_with = "build_report")]
+    watch: bool,
+
+    /// While watching, how often to introspect subgraphs with a `subgraph_url` again, in seconds
+    #[arg(
+        long = "poll-interval",
+        value_name = "SECONDS",
+        requires = "watch",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    poll_interval: u64,
+
+    /// How many subgraphs to resolve at once. Introspecting subgraphs and fetching them from
+    /// GraphOS mostly waits on the network, so configs with many of them resolve faster with a
+    /// higher number. Defaults to the number of CPUs.
+    #[arg(
+        long = "concurrency",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    concurrency: Option<u64>,
+
+    /// Fail when composition succeeds with hints, so CI can keep subgraphs free of them.
+    /// The hints are still printed, and counted in the `--build-report`.
+    #[arg(long = "hints-as-errors")]
+    hints_as_errors: bool,
+
     #[clap(flatten)]
     opts: PluginOpts,
 }
@@ -40,6 +105,12 @@ impl Compose {
     pub fn new(compose_opts: PluginOpts) -> Self {
         Self {
             supergraph_yaml: FileDescriptorType::File("RAM".into()),
+            overlays: Vec::new(),
+            build_report: None,
+            watch: false,
+            poll_interval: 10,
+            concurrency: None,
+            hints_as_errors: false,
             opts: compose_opts,
         }
     }
@@ -60,7 +131,10 @@ impl Compose {
         // and create our plugin that we may need to install from it
         let install_command = Install {
             force: false,
-            plugin: Some(plugin),
+            plugin: Some(plugin.into()),
+            from: None,
+            list: false,
+            locked: self.opts.locked,
             elv2_license_accepter: self.opts.elv2_license_accepter,
         };
 
@@ -77,18 +151,189 @@ impl Compose {
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
+        output_opts: &OutputOpts,
     ) -> RoverResult<RoverOutput> {
+        if self.watch {
+            return self.exec_and_watch(override_install_path, client_config, output_opts);
+        }
+        let started = Instant::now();
+        let (mut supergraph_config, source_map) = self.resolve(&client_config)?;
+        let report_path = match &self.build_report {
+            Some(report_path) => report_path,
+            None => {
+                return self.compose(
+                    override_install_path,
+                    client_config,
+                    &mut supergraph_config,
+                    &source_map,
+                )
+            }
+        };
+
+        let mut report = BuildReport::new(&supergraph_config, started.elapsed())?;
+        let result = self.exec_with_report(
+            override_install_path,
+            client_config,
+            &mut supergraph_config,
+            &source_map,
+            &mut report,
+        );
+        if let Ok(output) = &result {
+            report.success = true;
+            report.record_hints(&output.hints);
+        }
+        report.write(report_path)?;
+        eprintln!(
+            "{}wrote build report to {}",
+            Emoji::Memo,
+            Style::Path.paint(report_path.as_str())
+        );
+        Ok(RoverOutput::CompositionResult(result?))
+    }
+
+    /// Resolves the SDL of every subgraph in the supergraph config.
+    fn resolve(
+        &self,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<(SupergraphConfig, SubgraphSourceMap)> {
         eprintln!(
             "{}resolving SDL for subgraphs defined in {}",
             Emoji::Hourglass,
             Style::Path.paint(self.supergraph_yaml.to_string())
         );
-        let mut supergraph_config = resolve_supergraph_yaml(
+        let (mut supergraph_config, source_map) = resolve_supergraph_yaml_with_sources(
             &self.supergraph_yaml,
+            &self.overlays,
             client_config.clone(),
             &self.opts.profile,
+            self.opts.locked,
+            self.concurrency.map(|concurrency| concurrency as usize),
         )?;
-        self.compose(override_install_path, client_config, &mut supergraph_config)
+        // the `next` channel takes precedence over the `federation_version` in the config
+        if self.opts.channel == PluginChannel::Next {
+            supergraph_config.set_federation_version(resolve_federation_version(
+                "next",
+                client_config,
+                self.opts.locked,
+            )?);
+        }
+        Ok((supergraph_config, source_map))
+    }
+
+    /// Composes the supergraph whenever the supergraph config, an overlay, or a subgraph's schema
+    /// file changes, and every `--poll-interval` seconds if any subgraph is introspected. The
+    /// supergraph schema is printed when it changes, and errors are printed when they change,
+    /// without exiting.
+    fn exec_and_watch(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        output_opts: &OutputOpts,
+    ) -> RoverResult<RoverOutput> {
+        let config_path = match &self.supergraph_yaml {
+            FileDescriptorType::File(config_path) => config_path.clone(),
+            FileDescriptorType::Stdin => {
+                let mut err = RoverError::new(anyhow!(
+                    "`--watch` can't be used with a supergraph config read from stdin"
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass the path to the supergraph config file to `--config`.".to_string(),
+                ));
+                return Err(err);
+            }
+        };
+
+        let (tx, rx) = unbounded();
+        let mut watched_files = BTreeSet::new();
+        for file in std::iter::once(&config_path).chain(&self.overlays) {
+            watched_files.insert(file.clone());
+            Fs::watch_file_with_delay(file, tx.clone(), WATCH_DEBOUNCE);
+        }
+        let mut last_sdl: Option<String> = None;
+        let mut last_error: Option<String> = None;
+        loop {
+            // subgraphs can be added to the config while it's watched
+            let introspects = match self.watched_sources(&config_path, &client_config) {
+                Ok((files, introspects)) => {
+                    for file in files {
+                        if watched_files.insert(file.clone()) {
+                            Fs::watch_file_with_delay(&file, tx.clone(), WATCH_DEBOUNCE);
+                        }
+                    }
+                    introspects
+                }
+                // resolving the config reports the same error
+                Err(_) => false,
+            };
+
+            let result =
+                self.resolve(&client_config)
+                    .and_then(|(mut supergraph_config, source_map)| {
+                        self.exec(
+                            override_install_path.clone(),
+                            client_config.clone(),
+                            &mut supergraph_config,
+                            &source_map,
+                        )
+                    });
+            match result {
+                Ok(output) => {
+                    last_error = None;
+                    if last_sdl.as_ref() != Some(&output.supergraph_sdl) {
+                        last_sdl = Some(output.supergraph_sdl.clone());
+                        let output = RoverOutput::CompositionResult(output);
+                        let _ = output.write_or_print(output_opts).map_err(|e| e.print());
+                    } else {
+                        eprintln!("{}the supergraph schema didn't change", Emoji::Note);
+                    }
+                }
+                Err(error) => {
+                    // keep the last supergraph schema in place until the errors are fixed
+                    let message = error.to_string();
+                    if last_error.as_ref() != Some(&message) {
+                        let _ = error.write_or_print(output_opts).map_err(|e| e.print());
+                        last_error = Some(message);
+                    }
+                }
+            }
+
+            if introspects {
+                let _ = rx.recv_timeout(Duration::from_secs(self.poll_interval));
+            } else {
+                rx.recv().map_err(|e| anyhow!(e))?;
+            }
+            // saving several files at once only needs one more composition
+            while rx.try_recv().is_ok() {}
+        }
+    }
+
+    /// The subgraph schema files in the supergraph config, and whether any subgraph is
+    /// introspected from a `subgraph_url`.
+    fn watched_sources(
+        &self,
+        config_path: &Utf8Path,
+        client_config: &StudioClientConfig,
+    ) -> RoverResult<(Vec<Utf8PathBuf>, bool)> {
+        let config_dir = config_path.parent();
+        let supergraph_config = FileDescriptorType::File(config_path.to_path_buf())
+            .read_file_descriptor("supergraph config", &mut io::stdin())
+            .and_then(|contents| {
+                expand_supergraph_yaml(
+                    &contents,
+                    config_dir,
+                    &self.overlays,
+                    client_config,
+                    self.opts.locked,
+                )
+            })?;
+        let files = SubgraphSourceMap::from_supergraph_config(&supergraph_config, config_dir)
+            .iter()
+            .map(|(_, file)| file.clone())
+            .collect();
+        let introspects = supergraph_config.into_iter().any(|(_, subgraph)| {
+            matches!(subgraph.schema, SchemaSource::SubgraphIntrospection { .. })
+        });
+        Ok((files, introspects))
     }
 
     pub fn compose(
@@ -96,25 +341,52 @@ impl Compose {
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         supergraph_config: &mut SupergraphConfig,
+        source_map: &SubgraphSourceMap,
     ) -> RoverResult<RoverOutput> {
-        let output = self.exec(override_install_path, client_config, supergraph_config)?;
+        let output = self.exec(
+            override_install_path,
+            client_config,
+            supergraph_config,
+            source_map,
+        )?;
         Ok(RoverOutput::CompositionResult(output))
     }
 
+    /// Composes `supergraph_config`, pointing any errors and hints at the files in `source_map`.
     pub fn exec(
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
         supergraph_config: &mut SupergraphConfig,
+        source_map: &SubgraphSourceMap,
+    ) -> RoverResult<CompositionOutput> {
+        self.exec_with_report(
+            override_install_path,
+            client_config,
+            supergraph_config,
+            source_map,
+            &mut BuildReport::default(),
+        )
+    }
+
+    fn exec_with_report(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+        supergraph_config: &mut SupergraphConfig,
+        source_map: &SubgraphSourceMap,
+        report: &mut BuildReport,
     ) -> RoverResult<CompositionOutput> {
         // first, grab the _actual_ federation version from the config we just resolved
         // (this will always be `Some` as long as we have created with `resolve_supergraph_yaml` so it is safe to unwrap)
         let federation_version = supergraph_config.get_federation_version().unwrap();
+        let started = Instant::now();
         let exe = self.maybe_install_supergraph(
             override_install_path,
             client_config,
             federation_version.clone(),
         )?;
+        report.timings.install_ms = started.elapsed().as_millis() as u64;
 
         // _then_, overwrite the federation_version with _only_ the major version
         // before sending it to the supergraph plugin.
@@ -140,30 +412,67 @@ impl Compose {
 
         let federation_version =
             exe.as_str().split("supergraph-").collect::<Vec<&str>>()[1].to_string();
+        let prerelease_label = if is_prerelease(&federation_version) {
+            " (pre-release)"
+        } else {
+            ""
+        };
         eprintln!(
-            "{}composing supergraph with Federation {}",
+            "{}composing supergraph with Federation {}{}",
             Emoji::Compose,
-            &federation_version
+            &federation_version,
+            prerelease_label
         );
+        report.plugin_version = Some(federation_version.clone());
+        events::emit(Event::CompositionStarted {
+            federation_version: federation_version.clone(),
+            subgraphs: num_subgraphs,
+        });
 
-        let output = Command::new(&exe)
-            .args(["compose", yaml_path.as_ref()])
-            .output()
-            .context("Failed to execute command")?;
+        let started = Instant::now();
+        let output = perf::time(Phase::Composition, || {
+            Command::new(&exe)
+                .args(["compose", yaml_path.as_ref()])
+                .output()
+        })
+        .context("Failed to execute command")?;
+        report.timings.compose_ms = started.elapsed().as_millis() as u64;
         let stdout = str::from_utf8(&output.stdout)
             .with_context(|| format!("Could not parse output of `{} compose`", &exe))?;
 
         match serde_json::from_str::<BuildResult>(stdout) {
             Ok(build_result) => match build_result {
-                Ok(build_output) => Ok(CompositionOutput {
-                    hints: build_output.hints,
-                    supergraph_sdl: build_output.supergraph_sdl,
-                    federation_version: Some(federation_version),
-                }),
-                Err(build_errors) => Err(RoverError::from(RoverClientError::BuildErrors {
-                    source: build_errors,
-                    num_subgraphs,
-                })),
+                Ok(build_output) => {
+                    events::emit(Event::CompositionSucceeded {
+                        federation_version: federation_version.clone(),
+                        hints: build_output.hints.len(),
+                    });
+                    let hints = source_map.annotate_hints(build_output.hints);
+                    if self.hints_as_errors && !hints.is_empty() {
+                        report.record_hints(&hints);
+                        eprint!("{}", format_hints(&hints));
+                        let mut err = RoverError::new(anyhow!(
+                            "Composition succeeded with {} hint(s), which `--hints-as-errors` treats as errors.",
+                            hints.len()
+                        ));
+                        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                            "Resolve the hints above, or compose without `--hints-as-errors` to only print them.".to_string(),
+                        ));
+                        return Err(err);
+                    }
+                    Ok(CompositionOutput {
+                        hints,
+                        supergraph_sdl: build_output.supergraph_sdl,
+                        federation_version: Some(federation_version),
+                    })
+                }
+                Err(build_errors) => {
+                    events::emit_composition_failed(federation_version, &build_errors);
+                    Err(RoverError::from(RoverClientError::BuildErrors {
+                        source: source_map.annotate_errors(build_errors),
+                        num_subgraphs,
+                    }))
+                }
             },
             Err(bad_json) => Err(anyhow!("{}", bad_json))
                 .with_context(|| anyhow!("{} compose output: {}", &exe, stdout))
@@ -180,6 +489,7 @@ impl Compose {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::supergraph::resolve_config::resolve_supergraph_yaml;
     use crate::options::ProfileOpt;
     use crate::utils::client::ClientBuilder;
     use assert_fs::TempDir;
@@ -216,14 +526,49 @@ mod tests {
         fs::write(&config_path, raw_good_yaml).unwrap();
         assert!(resolve_supergraph_yaml(
             &FileDescriptorType::File(config_path),
+            &[],
             get_studio_config(),
             &ProfileOpt {
                 profile_name: "profile".to_string()
-            }
+            },
+            false,
         )
         .is_err())
     }
 
+    #[test]
+    fn it_reports_every_subgraph_that_fails_to_resolve() {
+        let raw_yaml = r#"subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: ./films-do-not-exist.graphql
+  people:
+    routing_url: https://people.example.com
+    schema:
+      file: ./people-do-not-exist.graphql
+  planets:
+    routing_url: https://planets.example.com
+    schema:
+      sdl: "type Query { planets: [String] }""#;
+        let tmp_home = TempDir::new().unwrap();
+        let mut config_path = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        config_path.push("config.yaml");
+        fs::write(&config_path, raw_yaml).unwrap();
+        let err = resolve_supergraph_yaml_with_sources(
+            &FileDescriptorType::File(config_path),
+            &[],
+            get_studio_config(),
+            &ProfileOpt {
+                profile_name: "profile".to_string(),
+            },
+            false,
+            Some(2),
+        )
+        .unwrap_err();
+        assert!(err.message().contains("2 build errors"));
+    }
+
     #[test]
     fn it_can_get_subgraph_definitions_from_fs() {
         let raw_good_yaml = r#"subgraphs:
@@ -246,10 +591,12 @@ mod tests {
         fs::write(people_path, "there is also something here").unwrap();
         assert!(resolve_supergraph_yaml(
             &FileDescriptorType::File(config_path),
+            &[],
             get_studio_config(),
             &ProfileOpt {
                 profile_name: "profile".to_string()
-            }
+            },
+            false,
         )
         .is_ok())
     }
@@ -279,10 +626,12 @@ mod tests {
         fs::write(people_path, "there is also something here").unwrap();
         let subgraph_definitions = resolve_supergraph_yaml(
             &FileDescriptorType::File(config_path),
+            &[],
             get_studio_config(),
             &ProfileOpt {
                 profile_name: "profile".to_string(),
             },
+            false,
         )
         .unwrap()
         .get_subgraph_definitions()
@@ -0,0 +1,128 @@
+//! Composition hints, with the severity Federation gives each hint code.
+
+use std::fmt::{self, Write};
+
+use apollo_federation_types::build::BuildHint;
+use rover_std::Style;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Hint codes that usually point at a mistake in a subgraph schema
+const WARN_CODES: &[&str] = &[
+    "INCONSISTENT_DEFAULT_VALUE_PRESENCE",
+    "INCONSISTENT_INPUT_OBJECT_FIELD",
+    "INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM",
+    "INCONSISTENT_EXECUTABLE_DIRECTIVE_PRESENCE",
+    "NO_EXECUTABLE_DIRECTIVE_LOCATIONS_INTERSECTION",
+    "INCONSISTENT_EXECUTABLE_DIRECTIVE_REPEATABLE",
+    "INCONSISTENT_EXECUTABLE_DIRECTIVE_LOCATIONS",
+    "INCONSISTENT_DESCRIPTION",
+    "INCONSISTENT_ARGUMENT_PRESENCE",
+    "FROM_SUBGRAPH_DOES_NOT_EXIST",
+    "INCONSISTENT_NON_REPEATABLE_DIRECTIVE_ARGUMENTS",
+    "DIRECTIVE_COMPOSITION_WARN",
+    "INCONSISTENT_RUNTIME_TYPES_FOR_SHAREABLE_RETURN",
+    "CONTEXTUAL_ARGUMENT_NOT_CONTEXTUAL_IN_ALL_SUBGRAPHS",
+];
+
+/// Hint codes that only describe how types were merged
+const DEBUG_CODES: &[&str] = &[
+    "INCONSISTENT_OBJECT_VALUE_TYPE_FIELD",
+    "INCONSISTENT_INTERFACE_VALUE_TYPE_FIELD",
+    "INCONSISTENT_UNION_MEMBER",
+    "INCONSISTENT_ENUM_VALUE_FOR_OUTPUT_ENUM",
+    "INCONSISTENT_TYPE_SYSTEM_DIRECTIVE_REPEATABLE",
+    "INCONSISTENT_TYPE_SYSTEM_DIRECTIVE_LOCATIONS",
+    "UNUSED_ENUM_TYPE",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HintLevel {
+    Debug,
+    Info,
+    Warn,
+}
+
+impl HintLevel {
+    /// The level of `hint`. Hints with codes this version of Rover doesn't know are `Info`.
+    pub(crate) fn of(hint: &BuildHint) -> Self {
+        match hint.code.as_deref() {
+            Some(code) if WARN_CODES.contains(&code) => HintLevel::Warn,
+            Some(code) if DEBUG_CODES.contains(&code) => HintLevel::Debug,
+            _ => HintLevel::Info,
+        }
+    }
+}
+
+impl fmt::Display for HintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HintLevel::Debug => write!(f, "DEBUG"),
+            HintLevel::Info => write!(f, "INFO"),
+            HintLevel::Warn => write!(f, "WARN"),
+        }
+    }
+}
+
+/// A line for each hint, prefixed with its level and code.
+pub(crate) fn format_hints(hints: &[BuildHint]) -> String {
+    hints.iter().fold(String::new(), |mut output, hint| {
+        let level = HintLevel::of(hint);
+        let prefix = format!("HINT [{}]", level);
+        let prefix = match level {
+            HintLevel::Warn => Style::WarningPrefix.paint(prefix),
+            _ => Style::HintPrefix.paint(prefix),
+        };
+        let _ = match &hint.code {
+            Some(code) => writeln!(output, "{} {}: {}", prefix, code, hint.message),
+            None => writeln!(output, "{} {}", prefix, hint.message),
+        };
+        output
+    })
+}
+
+/// `hints` as JSON, each with its `level`.
+pub(crate) fn hints_json(hints: &[BuildHint]) -> Vec<Value> {
+    hints
+        .iter()
+        .map(|hint| {
+            let mut value = json!(hint);
+            value["level"] = json!(HintLevel::of(hint));
+            value
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::build::BuildHint;
+    use serde_json::json;
+
+    use super::{hints_json, HintLevel};
+
+    #[test]
+    fn it_levels_hints_by_code() {
+        let hints: Vec<BuildHint> = serde_json::from_value(json!([
+            { "message": "a", "code": "INCONSISTENT_DESCRIPTION" },
+            { "message": "b", "code": "UNUSED_ENUM_TYPE" },
+            { "message": "c", "code": "OVERRIDE_MIGRATION_IN_PROGRESS" },
+            { "message": "d" },
+        ]))
+        .unwrap();
+        let levels: Vec<_> = hints.iter().map(HintLevel::of).collect();
+        assert_eq!(
+            levels,
+            [
+                HintLevel::Warn,
+                HintLevel::Debug,
+                HintLevel::Info,
+                HintLevel::Info
+            ]
+        );
+        let json = hints_json(&hints);
+        assert_eq!(json[0]["level"], json!("warn"));
+        assert_eq!(json[0]["code"], json!("INCONSISTENT_DESCRIPTION"));
+        assert_eq!(json[1]["level"], json!("debug"));
+    }
+}
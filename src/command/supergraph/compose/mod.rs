@@ -4,13 +4,20 @@ mod no_compose;
 #[cfg(not(feature = "composition-js"))]
 pub(crate) use no_compose::Compose;
 
+#[cfg(feature = "composition-js")]
+mod build_report;
 #[cfg(feature = "composition-js")]
 mod do_compose;
 
 #[cfg(feature = "composition-js")]
 pub(crate) use do_compose::Compose;
 
+mod hints;
+
+pub(crate) use hints::{format_hints, hints_json};
+
 use apollo_federation_types::build::BuildHint;
+use semver::Version;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompositionOutput {
@@ -18,3 +25,20 @@ pub struct CompositionOutput {
     pub hints: Vec<BuildHint>,
     pub federation_version: Option<String>,
 }
+
+impl CompositionOutput {
+    /// Whether the supergraph was composed by a pre-release build of the `supergraph` plugin.
+    pub fn is_prerelease(&self) -> bool {
+        self.federation_version
+            .as_deref()
+            .map(is_prerelease)
+            .unwrap_or(false)
+    }
+}
+
+/// Whether a plugin version like `v2.7.0-beta.1` is a pre-release.
+pub(crate) fn is_prerelease(version: &str) -> bool {
+    Version::parse(version.strip_prefix('v').unwrap_or(version))
+        .map(|version| !version.pre.is_empty())
+        .unwrap_or(false)
+}
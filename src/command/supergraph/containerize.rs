@@ -0,0 +1,173 @@
+use std::process::Command;
+
+use anyhow::{anyhow, Context};
+use apollo_federation_types::config::RouterVersion;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::{Emoji, Fs, Style};
+use serde::Serialize;
+
+use crate::command::install::{Plugin, PluginSpec};
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// The router image that generated images are built on
+const ROUTER_IMAGE: &str = "ghcr.io/apollographql/router";
+
+/// Where the router image looks for its supergraph schema and config
+const IMAGE_SCHEMA_PATH: &str = "/dist/schema/supergraph.graphql";
+const IMAGE_CONFIG_PATH: &str = "/dist/config/router.yaml";
+
+/// The router config used when `--router-config` isn't passed, which serves the graph and the
+/// health check on every interface so they're reachable from outside the container
+const DEFAULT_ROUTER_CONFIG: &str = "supergraph:
+  listen: 0.0.0.0:4000
+health_check:
+  listen: 0.0.0.0:8088
+";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Containerize {
+    /// The path to the supergraph schema to serve, like the output of `rover supergraph compose`
+    #[arg(long = "supergraph")]
+    #[serde(skip_serializing)]
+    supergraph_path: Utf8PathBuf,
+
+    /// The path to a router configuration file to include. By default, the router listens on
+    /// port 4000 and serves its health check on port 8088.
+    #[arg(long = "router-config")]
+    #[serde(skip_serializing)]
+    router_config_path: Option<Utf8PathBuf>,
+
+    /// The version of the router to build the image on: an exact version like `v1.33.0`, or a
+    /// range like `~1.33`, which resolves to the newest release that matches it
+    #[arg(long = "router-version", default_value = "1")]
+    router_version: String,
+
+    /// The directory to write the Dockerfile and the files it copies into the image to
+    #[arg(long = "output-dir", default_value = "router-image")]
+    #[serde(skip_serializing)]
+    output_dir: Utf8PathBuf,
+
+    /// Build the image with Docker and tag it, like `my-graph:latest`. If Docker isn't installed,
+    /// Rover prints the command to build it with instead.
+    #[arg(long = "tag")]
+    #[serde(skip_serializing)]
+    tag: Option<String>,
+}
+
+impl Containerize {
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let router_version = self.resolve_router_version(&client_config)?;
+
+        Fs::create_dir_all(&self.output_dir)?;
+        Fs::copy(
+            &self.supergraph_path,
+            self.output_dir.join("supergraph.graphql"),
+        )?;
+        let router_config_path = self.output_dir.join("router.yaml");
+        match &self.router_config_path {
+            Some(path) => Fs::copy(path, &router_config_path)?,
+            None => Fs::write_file(&router_config_path, DEFAULT_ROUTER_CONFIG)?,
+        }
+        Fs::write_file(
+            self.output_dir.join("Dockerfile"),
+            dockerfile(&router_version),
+        )?;
+        eprintln!(
+            "{}wrote a Dockerfile for router {} to {}",
+            Emoji::Success,
+            router_version,
+            Style::Path.paint(&self.output_dir)
+        );
+
+        if let Some(tag) = &self.tag {
+            self.build_image(tag)?;
+        }
+        Ok(RoverOutput::EmptySuccess)
+    }
+
+    /// Resolves `--router-version` to the tag of a published router image, like `v1.33.0`.
+    fn resolve_router_version(&self, client_config: &StudioClientConfig) -> RoverResult<String> {
+        match PluginSpec::router(&self.router_version)?.resolve(client_config, false)? {
+            Plugin::Router(RouterVersion::Exact(version)) => Ok(format!("v{}", version)),
+            _ => {
+                let mut err = RoverError::new(anyhow!(
+                    "'{}' is not a router version that can be used as an image tag",
+                    self.router_version
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Pass an exact version like `--router-version v1.33.0`, or a range like `--router-version ~1.33`.".to_string(),
+                ));
+                Err(err)
+            }
+        }
+    }
+
+    fn build_image(&self, tag: &str) -> RoverResult<()> {
+        let build_command = format!("docker build -t {} {}", tag, self.output_dir);
+        if which::which("docker").is_err() {
+            eprintln!(
+                "{} Docker is not installed, so the image wasn't built. Build it on a machine with Docker with `{}`",
+                Style::WarningPrefix.paint("WARN:"),
+                Style::Command.paint(&build_command)
+            );
+            return Ok(());
+        }
+        eprintln!(
+            "{}building {}...",
+            Emoji::Hourglass,
+            Style::Command.paint(tag)
+        );
+        let status = Command::new("docker")
+            .args(["build", "-t", tag, self.output_dir.as_str()])
+            .status()
+            .context("could not run `docker build`")?;
+        if !status.success() {
+            return Err(RoverError::new(anyhow!(
+                "`{}` failed ({})",
+                build_command,
+                status
+            )));
+        }
+        eprintln!(
+            "{}built {}. Run it with `{}`",
+            Emoji::Success,
+            Style::Command.paint(tag),
+            Style::Command.paint(format!("docker run -p 4000:4000 {}", tag))
+        );
+        Ok(())
+    }
+}
+
+/// A Dockerfile that adds the supergraph schema and router config in its build context to the
+/// router image for `router_version`.
+fn dockerfile(router_version: &str) -> String {
+    format!(
+        "FROM {ROUTER_IMAGE}:{router_version}
+
+COPY supergraph.graphql {IMAGE_SCHEMA_PATH}
+COPY router.yaml {IMAGE_CONFIG_PATH}
+
+ENV APOLLO_ROUTER_SUPERGRAPH_PATH={IMAGE_SCHEMA_PATH}
+ENV APOLLO_ROUTER_CONFIG_PATH={IMAGE_CONFIG_PATH}
+
+EXPOSE 4000 8088
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dockerfile;
+
+    #[test]
+    fn it_builds_on_the_router_image() {
+        let dockerfile = dockerfile("v1.33.0");
+        assert!(dockerfile.starts_with("FROM ghcr.io/apollographql/router:v1.33.0\n"));
+        assert!(dockerfile.contains("COPY supergraph.graphql /dist/schema/supergraph.graphql\n"));
+        assert!(dockerfile
+            .contains("ENV APOLLO_ROUTER_SUPERGRAPH_PATH=/dist/schema/supergraph.graphql\n"));
+        assert!(dockerfile.contains("ENV APOLLO_ROUTER_CONFIG_PATH=/dist/config/router.yaml\n"));
+    }
+}
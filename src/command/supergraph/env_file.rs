@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context};
+use camino::Utf8Path;
+use rover_std::Fs;
+use serde_yaml::{Mapping, Value};
+
+use crate::utils::expansion::{expand, expand_with_vars};
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const ENV_FILE_KEY: &str = "env_file";
+const SUBGRAPHS_KEY: &str = "subgraphs";
+
+/// Expands variables in a `supergraph.yaml` like [`expand`], except that `${env.NAME}` in a
+/// subgraph's entry is read from the dotenv file at the subgraph's `env_file` (resolved relative
+/// to `config_dir`) when the process doesn't have `NAME` set. This way the config can use the same
+/// `.env` file that the subgraph server is started with.
+pub(crate) fn expand_with_env_files(
+    value: Value,
+    config_dir: Option<&Utf8Path>,
+) -> RoverResult<Value> {
+    let root = match value {
+        Value::Mapping(root) => root,
+        _ => return expand(value),
+    };
+    let config_dir = config_dir.unwrap_or_else(|| Utf8Path::new(""));
+    root.into_iter()
+        .map(|(key, value)| match value {
+            Value::Mapping(subgraphs) if key.as_str() == Some(SUBGRAPHS_KEY) => subgraphs
+                .into_iter()
+                .map(|(name, subgraph)| {
+                    expand_subgraph(&name, subgraph, config_dir).map(|subgraph| (name, subgraph))
+                })
+                .collect::<RoverResult<Mapping>>()
+                .map(|subgraphs| (key, Value::Mapping(subgraphs))),
+            value => expand(value).map(|value| (key, value)),
+        })
+        .collect::<RoverResult<Mapping>>()
+        .map(Value::Mapping)
+}
+
+fn expand_subgraph(name: &Value, subgraph: Value, config_dir: &Utf8Path) -> RoverResult<Value> {
+    let mut subgraph = match subgraph {
+        Value::Mapping(subgraph) => subgraph,
+        // leave it up to deserialization to report what's wrong
+        _ => return expand(subgraph),
+    };
+    let name = name.as_str().unwrap_or_default();
+    let vars = match subgraph.remove(ENV_FILE_KEY) {
+        None | Some(Value::Null) => HashMap::new(),
+        Some(Value::String(path)) => {
+            let path = config_dir.join(path);
+            let contents = Fs::read_file(&path)
+                .with_context(|| format!("could not read the `env_file` of subgraph '{}'", name))?;
+            parse_env_file(&contents, &path)?
+        }
+        Some(_) => {
+            let mut err = RoverError::new(anyhow!(
+                "the `env_file` of subgraph '{}' must be a path",
+                name
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Set `env_file: .env` to read variables from the `.env` file next to the supergraph config.".to_string(),
+            ));
+            return Err(err);
+        }
+    };
+    expand_with_vars(Value::Mapping(subgraph), &vars)
+}
+
+/// Parses `NAME=value` lines, skipping blank lines and `#` comments. Values can be wrapped in
+/// single or double quotes, and lines can start with `export`.
+fn parse_env_file(contents: &str, path: &Utf8Path) -> RoverResult<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=').ok_or_else(|| {
+            let mut err = RoverError::new(anyhow!(
+                "line {} of '{}' isn't a variable assignment",
+                index + 1,
+                path
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Write each variable on its own line, like `PORT=4001`.".to_string(),
+            ));
+            err
+        })?;
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|quote| {
+                value
+                    .strip_prefix(*quote)
+                    .and_then(|value| value.strip_suffix(*quote))
+            })
+            .unwrap_or_else(|| {
+                value
+                    .split_once(" #")
+                    .map_or(value, |(value, _)| value.trim_end())
+            });
+        vars.insert(key.trim().to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use assert_fs::TempDir;
+    use camino::{Utf8Path, Utf8PathBuf};
+    use serde_yaml::Value;
+
+    use super::{expand_with_env_files, parse_env_file};
+
+    #[test]
+    fn it_parses_env_files() {
+        let vars = parse_env_file(
+            "# accounts\nPORT=4001\nexport TOKEN=\"secret value\"\nNAME='accounts' \nDEBUG=true # verbose\n\n",
+            Utf8Path::new(".env"),
+        )
+        .unwrap();
+        assert_eq!(
+            vars,
+            HashMap::from([
+                ("PORT".to_string(), "4001".to_string()),
+                ("TOKEN".to_string(), "secret value".to_string()),
+                ("NAME".to_string(), "accounts".to_string()),
+                ("DEBUG".to_string(), "true".to_string()),
+            ])
+        );
+
+        assert!(parse_env_file("PORT 4001", Utf8Path::new(".env")).is_err());
+    }
+
+    #[test]
+    fn it_expands_subgraphs_with_their_env_file() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        fs::write(
+            tmp_dir.join(".env.accounts"),
+            "ENV_FILE_TEST_ACCOUNTS_PORT=4001\n",
+        )
+        .unwrap();
+        let yaml = r#"federation_version: =2.4.7
+subgraphs:
+  accounts:
+    routing_url: http://localhost:${env.ENV_FILE_TEST_ACCOUNTS_PORT}
+    env_file: .env.accounts
+    schema:
+      file: ./accounts.graphql
+  products:
+    routing_url: http://localhost:${env.ENV_FILE_TEST_ACCOUNTS_PORT:-4002}
+    schema:
+      file: ./products.graphql"#;
+        let expanded =
+            expand_with_env_files(serde_yaml::from_str(yaml).unwrap(), Some(&tmp_dir)).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            r#"federation_version: =2.4.7
+subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      file: ./accounts.graphql
+  products:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./products.graphql"#,
+        )
+        .unwrap();
+        assert_eq!(expanded, expected);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::anyhow;
+use apollo_federation_types::config::FederationVersion;
+use serde_yaml::Value;
+
+use std::str::FromStr;
+
+use crate::command::install::resolve_federation_version;
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const FEDERATION_VERSION_KEY: &str = "federation_version";
+
+/// Pins the `federation_version` of a `supergraph.yaml` to an exact version
+/// when it's specified as a semver range, e.g.
+///
+/// ```yaml
+/// federation_version: ~2.3
+/// ```
+///
+/// When `locked` is true, a missing or floating `federation_version` is an error.
+pub(crate) fn resolve_federation_version_range(
+    mut value: Value,
+    client_config: &StudioClientConfig,
+    locked: bool,
+) -> RoverResult<Value> {
+    let spec = match value.get(FEDERATION_VERSION_KEY) {
+        Some(Value::String(spec)) => spec.clone(),
+        Some(Value::Number(major)) => major.to_string(),
+        Some(_) => return Ok(value),
+        None if locked => {
+            let mut err = RoverError::new(anyhow!(
+                "`--locked` requires the supergraph config to specify a `federation_version`."
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Pin an exact version, e.g. `federation_version: =2.3.2`, or remove the `--locked` flag.".to_string(),
+            ));
+            return Err(err);
+        }
+        None => return Ok(value),
+    };
+
+    // channels and exact versions are understood as-is, so only ranges need to be resolved
+    let is_range = FederationVersion::from_str(&spec).is_err();
+    if !is_range && !locked {
+        return Ok(value);
+    }
+    let federation_version = resolve_federation_version(&spec, client_config, locked)?;
+    if let FederationVersion::ExactFedOne(version) | FederationVersion::ExactFedTwo(version) =
+        federation_version
+    {
+        if is_range {
+            value[FEDERATION_VERSION_KEY] = Value::String(format!("={version}"));
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::Config;
+    use serde_yaml::Value;
+
+    use super::resolve_federation_version_range;
+    use crate::utils::client::{ClientBuilder, StudioClientConfig};
+
+    fn get_studio_config() -> StudioClientConfig {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_path = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        StudioClientConfig::new(
+            None,
+            Config::new(Some(&tmp_path), None).unwrap(),
+            false,
+            ClientBuilder::default(),
+        )
+    }
+
+    fn resolve(yaml: &str, locked: bool) -> Option<Value> {
+        let value = serde_yaml::from_str(yaml).unwrap();
+        resolve_federation_version_range(value, &get_studio_config(), locked).ok()
+    }
+
+    #[test]
+    fn it_leaves_channels_and_exact_versions_alone() {
+        for yaml in [
+            "federation_version: 2",
+            "federation_version: latest-2",
+            "federation_version: =2.3.2",
+            "subgraphs: {}",
+        ] {
+            let value: Value = serde_yaml::from_str(yaml).unwrap();
+            assert_eq!(resolve(yaml, false), Some(value));
+        }
+    }
+
+    #[test]
+    fn it_only_accepts_exact_versions_when_locked() {
+        assert!(resolve("federation_version: =2.3.2", true).is_some());
+        assert!(resolve("federation_version: 2", true).is_none());
+        assert!(resolve("federation_version: latest-2", true).is_none());
+        assert!(resolve("federation_version: ~2.3", true).is_none());
+        assert!(resolve("subgraphs: {}", true).is_none());
+    }
+}
@@ -1,15 +1,20 @@
+use crate::options::{OutputOpts, RoverPrinter};
 use crate::utils::client::StudioClientConfig;
 use crate::{
     options::{GraphRefOpt, ProfileOpt},
     RoverOutput, RoverResult,
 };
 
+use rover_client::blocking::StudioClient;
 use rover_client::operations::supergraph::fetch::{self, SupergraphFetchInput};
-use rover_std::Style;
+use rover_client::shared::FetchResponse;
+use rover_std::{Emoji, Style};
 
 use clap::Parser;
 use serde::Serialize;
 
+use std::time::Duration;
+
 #[derive(Debug, Serialize, Parser)]
 pub struct Fetch {
     #[clap(flatten)]
@@ -17,10 +22,29 @@ pub struct Fetch {
 
     #[clap(flatten)]
     profile: ProfileOpt,
+
+    /// Keep checking for the supergraph that routers fetch for the variant, and print it again
+    /// whenever a launch delivers a new one. Use with `--output` to keep a file up to date.
+    #[arg(long)]
+    watch: bool,
+
+    /// How often to check for a new supergraph with `--watch`, in seconds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        requires = "watch",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    poll_interval: u64,
 }
 
 impl Fetch {
-    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+    pub fn run(
+        &self,
+        client_config: StudioClientConfig,
+        output_opts: &OutputOpts,
+    ) -> RoverResult<RoverOutput> {
         let client = client_config.get_authenticated_client(&self.profile)?;
         let graph_ref = self.graph.graph_ref.to_string();
         eprintln!(
@@ -29,13 +53,60 @@ impl Fetch {
             Style::Command.paint(&self.profile.profile_name)
         );
 
-        let fetch_response = fetch::run(
+        if self.watch {
+            self.exec_and_watch(&client, output_opts)
+        } else {
+            Ok(RoverOutput::FetchResponse(self.exec(&client)?))
+        }
+    }
+
+    fn exec(&self, client: &StudioClient) -> RoverResult<FetchResponse> {
+        Ok(fetch::run(
             SupergraphFetchInput {
                 graph_ref: self.graph.graph_ref.clone(),
             },
-            &client,
-        )?;
+            client,
+        )?)
+    }
 
-        Ok(RoverOutput::FetchResponse(fetch_response))
+    /// Prints the supergraph every time a different one is fetched, and errors only when
+    /// they change, so a self-hosted router's schema file is rewritten once per launch.
+    fn exec_and_watch(&self, client: &StudioClient, output_opts: &OutputOpts) -> ! {
+        eprintln!(
+            "{}Checking for a new supergraph every {} seconds. Press Ctrl+C to stop.",
+            Emoji::Hourglass,
+            self.poll_interval
+        );
+        let mut last_sdl: Option<String> = None;
+        let mut last_error: Option<String> = None;
+        loop {
+            match self.exec(client) {
+                Ok(fetch_response) => {
+                    last_error = None;
+                    let sdl = fetch_response.sdl.contents.clone();
+                    if last_sdl.as_ref() != Some(&sdl) {
+                        if last_sdl.is_some() {
+                            eprintln!(
+                                "{}A launch delivered a new supergraph for {}.",
+                                Emoji::Success,
+                                Style::Link.paint(self.graph.graph_ref.to_string())
+                            );
+                        }
+                        let output = RoverOutput::FetchResponse(fetch_response);
+                        let _ = output.write_or_print(output_opts).map_err(|e| e.print());
+                        last_sdl = Some(sdl);
+                    }
+                }
+                Err(error) => {
+                    // keep the last supergraph in place, and try again on the next poll
+                    let message = error.to_string();
+                    if last_error.as_ref() != Some(&message) {
+                        let _ = error.write_or_print(output_opts).map_err(|e| e.print());
+                        last_error = Some(message);
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_secs(self.poll_interval))
+        }
     }
 }
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_std::{Fs, Style};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const GIT_KEY: &str = "git";
+
+/// A subgraph schema that lives in a git repository, e.g.
+///
+/// ```yaml
+/// schema:
+///   git:
+///     url: https://github.com/my-org/accounts.git
+///     rev: main
+///     path: schema.graphql
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GitSchemaSource {
+    url: String,
+    rev: Option<String>,
+    path: Utf8PathBuf,
+}
+
+impl GitSchemaSource {
+    fn rev(&self) -> &str {
+        self.rev.as_deref().unwrap_or("HEAD")
+    }
+}
+
+/// Replaces every `schema.git` source in a `supergraph.yaml` with the SDL it points to.
+///
+/// Each repository revision is shallow-fetched into its own directory in `cache_dir`,
+/// which is reused on later runs. If a fetch fails (e.g. while offline), the last
+/// checkout of that revision is used instead.
+pub(crate) fn resolve_git_sources(mut value: Value, cache_dir: &Utf8Path) -> RoverResult<Value> {
+    let subgraphs = match value.get_mut("subgraphs").and_then(Value::as_mapping_mut) {
+        Some(subgraphs) => subgraphs,
+        None => return Ok(value),
+    };
+
+    let mut checkouts: HashMap<(String, String), Utf8PathBuf> = HashMap::new();
+    for (name, subgraph) in subgraphs.iter_mut() {
+        let schema = match subgraph.get_mut("schema") {
+            Some(schema) if schema.get(GIT_KEY).is_some() => schema,
+            _ => continue,
+        };
+        let subgraph_name = name.as_str().unwrap_or_default();
+        let source: GitSchemaSource =
+            serde_yaml::from_value(schema[GIT_KEY].clone()).map_err(|e| {
+                let mut err = RoverError::new(anyhow!(
+                    "The `git` schema source for the '{}' subgraph is invalid: {}",
+                    subgraph_name,
+                    e
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "A `git` schema source must have a `url` and a `path`, and may have a `rev`."
+                        .to_string(),
+                ));
+                err
+            })?;
+
+        let key = (source.url.clone(), source.rev().to_string());
+        let checkout = match checkouts.get(&key) {
+            Some(checkout) => checkout.clone(),
+            None => {
+                let checkout = fetch_revision(&source, cache_dir)?;
+                checkouts.insert(key, checkout.clone());
+                checkout
+            }
+        };
+
+        let sdl = Fs::read_file(checkout.join(&source.path)).map_err(|e| {
+            let mut err = RoverError::new(e);
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Make sure '{}' exists in {} at revision '{}'.",
+                source.path,
+                source.url,
+                source.rev()
+            )));
+            err
+        })?;
+        let mut sdl_source = Mapping::new();
+        sdl_source.insert(Value::String("sdl".to_string()), Value::String(sdl));
+        *schema = Value::Mapping(sdl_source);
+    }
+
+    Ok(value)
+}
+
+fn fetch_revision(source: &GitSchemaSource, cache_dir: &Utf8Path) -> RoverResult<Utf8PathBuf> {
+    let cache_key = Sha256::digest(format!("{}#{}", source.url, source.rev()).as_bytes());
+    let checkout = cache_dir.join(format!("{:x}", cache_key));
+    let has_cached_checkout = checkout.join(".git").exists()
+        && run_git(&checkout, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok();
+    if !checkout.join(".git").exists() {
+        Fs::create_dir_all(&checkout)?;
+        run_git(&checkout, &["init", "--quiet"])?;
+    }
+
+    tracing::info!("fetching {} at {}", source.url, source.rev());
+    // `--` keeps a url or rev that starts with `-` from being read as an option like `--upload-pack`
+    let fetched = run_git(
+        &checkout,
+        &[
+            "fetch",
+            "--quiet",
+            "--depth",
+            "1",
+            "--",
+            &source.url,
+            source.rev(),
+        ],
+    )
+    .and_then(|_| run_git(&checkout, &["checkout", "--quiet", "--force", "FETCH_HEAD"]));
+
+    match fetched {
+        Ok(_) => Ok(checkout),
+        Err(e) if has_cached_checkout => {
+            eprintln!(
+                "{} Could not fetch {} at revision '{}', using a previously cached copy instead: {}",
+                Style::WarningPrefix.paint("WARN:"),
+                Style::Link.paint(&source.url),
+                source.rev(),
+                e.message()
+            );
+            Ok(checkout)
+        }
+        Err(mut e) => {
+            e.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Make sure `git` is installed, that you have access to {}, and that '{}' is a valid branch, tag, or commit.",
+                source.url,
+                source.rev()
+            )));
+            Err(e)
+        }
+    }
+}
+
+fn run_git(dir: &Utf8Path, args: &[&str]) -> RoverResult<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| anyhow!("Could not run `git`: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(RoverError::new(anyhow!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use serde_yaml::Value;
+    use std::fs;
+
+    use super::{resolve_git_sources, run_git};
+
+    #[test]
+    fn it_replaces_git_sources_with_sdl() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let repo = tmp_dir.join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("schema.graphql"), "type Query { me: String }").unwrap();
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["add", "schema.graphql"],
+            vec![
+                "-c",
+                "user.name=rover",
+                "-c",
+                "user.email=rover@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+        ] {
+            run_git(&repo, &args).unwrap();
+        }
+
+        let yaml = format!(
+            r#"subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      git:
+        url: file://{}
+        path: schema.graphql"#,
+            repo
+        );
+        let resolved =
+            resolve_git_sources(serde_yaml::from_str(&yaml).unwrap(), &tmp_dir.join("cache"))
+                .unwrap();
+        let expected: Value = serde_yaml::from_str("sdl: 'type Query { me: String }'").unwrap();
+        assert_eq!(resolved["subgraphs"]["accounts"]["schema"], expected);
+    }
+
+    #[test]
+    fn it_errs_on_invalid_git_sources() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let yaml = r#"subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      git:
+        url: https://github.com/my-org/accounts.git"#;
+        let err = resolve_git_sources(serde_yaml::from_str(yaml).unwrap(), &tmp_dir).unwrap_err();
+        assert!(err
+            .message()
+            .starts_with("The `git` schema source for the 'accounts' subgraph is invalid"));
+    }
+
+    #[test]
+    fn it_does_not_read_git_sources_as_options() {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let marker = tmp_dir.join("uploaded");
+        let yaml = format!(
+            r#"subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      git:
+        url: "--upload-pack=touch {}"
+        path: schema.graphql"#,
+            marker
+        );
+        assert!(
+            resolve_git_sources(serde_yaml::from_str(&yaml).unwrap(), &tmp_dir.join("cache"))
+                .is_err()
+        );
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_std::Fs;
+use serde_yaml::{Mapping, Value};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const INCLUDE_KEY: &str = "include";
+const SUBGRAPHS_KEY: &str = "subgraphs";
+
+/// Merges any files listed under a top-level `include` key into a
+/// `supergraph.yaml`, so subgraph entries can be split across per-team files.
+///
+/// Included files are resolved relative to the file that includes them, may
+/// themselves include other files, and local schema `file` paths inside of them
+/// are rewritten to be relative to `config_dir`.
+pub(crate) fn merge_includes(value: Value, config_dir: Option<&Utf8Path>) -> RoverResult<Value> {
+    let config_dir = config_dir.unwrap_or_else(|| Utf8Path::new(""));
+    let mut stack = Vec::new();
+    merge_includes_from(value, config_dir, "the supergraph config", &mut stack)
+}
+
+fn merge_includes_from(
+    value: Value,
+    config_dir: &Utf8Path,
+    origin: &str,
+    stack: &mut Vec<Utf8PathBuf>,
+) -> RoverResult<Value> {
+    let mut root = match value {
+        Value::Mapping(root) => root,
+        // leave it up to deserialization to report what's wrong
+        _ => return Ok(value),
+    };
+
+    let include_paths = match root.remove(INCLUDE_KEY) {
+        None | Some(Value::Null) => return Ok(Value::Mapping(root)),
+        Some(Value::String(path)) => vec![path],
+        Some(Value::Sequence(paths)) => paths
+            .into_iter()
+            .map(|path| match path {
+                Value::String(path) => Ok(path),
+                _ => Err(invalid_include(origin)),
+            })
+            .collect::<RoverResult<Vec<_>>>()?,
+        Some(_) => return Err(invalid_include(origin)),
+    };
+
+    // keep track of where each subgraph was defined so conflicts can point at both files
+    let mut subgraph_origins: HashMap<String, String> = HashMap::new();
+    if let Some(Value::Mapping(subgraphs)) = root.get(SUBGRAPHS_KEY) {
+        for name in subgraphs.keys().filter_map(Value::as_str) {
+            subgraph_origins.insert(name.to_string(), origin.to_string());
+        }
+    }
+
+    for include_path in include_paths {
+        let include_path = Utf8PathBuf::from(include_path);
+        let disk_path = config_dir.join(&include_path);
+        let canonical_path = disk_path
+            .canonicalize_utf8()
+            .unwrap_or_else(|_| disk_path.clone());
+        if stack.contains(&canonical_path) {
+            let mut err = RoverError::new(anyhow!(
+                "'{}' is included by itself. Supergraph config includes cannot be circular.",
+                disk_path
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Remove the `include` entry for '{}' from {}.",
+                include_path, origin
+            )));
+            return Err(err);
+        }
+
+        let contents = Fs::read_file(&disk_path).map_err(|e| {
+            let mut err = RoverError::new(e);
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Make sure '{}', included by {}, exists and is readable.",
+                include_path, origin
+            )));
+            err
+        })?;
+        let included: Value = serde_yaml::from_str(&contents)?;
+
+        let include_dir = disk_path.parent().unwrap_or_else(|| Utf8Path::new(""));
+        let include_origin = format!("'{}'", disk_path);
+        stack.push(canonical_path);
+        let included = merge_includes_from(included, include_dir, &include_origin, stack)?;
+        stack.pop();
+
+        let mut included = match included {
+            Value::Mapping(included) => included,
+            Value::Null => continue,
+            _ => {
+                return Err(RoverError::new(anyhow!(
+                    "{} must be a YAML mapping to be included in a supergraph config.",
+                    include_origin
+                )))
+            }
+        };
+
+        if let Some(subgraphs) = included.remove(SUBGRAPHS_KEY) {
+            let subgraphs = match subgraphs {
+                Value::Mapping(subgraphs) => subgraphs,
+                Value::Null => Mapping::new(),
+                _ => {
+                    return Err(RoverError::new(anyhow!(
+                        "The `subgraphs` entry in {} must be a YAML mapping.",
+                        include_origin
+                    )))
+                }
+            };
+            let relative_dir = include_path.parent().unwrap_or_else(|| Utf8Path::new(""));
+            let root_subgraphs = root
+                .entry(Value::String(SUBGRAPHS_KEY.to_string()))
+                .or_insert_with(|| Value::Mapping(Mapping::new()));
+            if root_subgraphs.is_null() {
+                *root_subgraphs = Value::Mapping(Mapping::new());
+            }
+            let root_subgraphs = root_subgraphs.as_mapping_mut().ok_or_else(|| {
+                RoverError::new(anyhow!(
+                    "The `subgraphs` entry in {} must be a YAML mapping.",
+                    origin
+                ))
+            })?;
+
+            for (name, mut subgraph) in subgraphs {
+                let subgraph_name = name.as_str().unwrap_or_default().to_string();
+                if let Some(previous_origin) = subgraph_origins.get(&subgraph_name) {
+                    let mut err = RoverError::new(anyhow!(
+                        "The '{}' subgraph is defined in both {} and {}.",
+                        subgraph_name,
+                        previous_origin,
+                        include_origin
+                    ));
+                    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                        "Subgraph names must be unique across all included supergraph config files. Rename or remove one of the definitions.".to_string(),
+                    ));
+                    return Err(err);
+                }
+                rebase_schema_file(&mut subgraph, relative_dir);
+                subgraph_origins.insert(subgraph_name, include_origin.clone());
+                root_subgraphs.insert(name, subgraph);
+            }
+        }
+
+        // any other top-level keys (like `federation_version`) must agree with the including file
+        for (key, included_value) in included {
+            match root.get(&key) {
+                Some(root_value) if root_value != &included_value => {
+                    let mut err = RoverError::new(anyhow!(
+                        "{} sets `{}` to a different value than {}.",
+                        include_origin,
+                        key.as_str().unwrap_or_default(),
+                        origin
+                    ));
+                    err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                        "Remove `{}` from {}, or make sure it matches.",
+                        key.as_str().unwrap_or_default(),
+                        include_origin
+                    )));
+                    return Err(err);
+                }
+                Some(_) => {}
+                None => {
+                    root.insert(key, included_value);
+                }
+            }
+        }
+    }
+
+    Ok(Value::Mapping(root))
+}
+
+/// Rewrites a relative `schema.file` so it still points at the same file once
+/// the subgraph is merged into the including config
+pub(super) fn rebase_schema_file(subgraph: &mut Value, relative_dir: &Utf8Path) {
+    if relative_dir.as_str().is_empty() {
+        return;
+    }
+    if let Some(Value::String(file)) = subgraph
+        .get_mut("schema")
+        .and_then(|schema| schema.get_mut("file"))
+    {
+        if Utf8Path::new(file.as_str()).is_relative() {
+            *file = relative_dir.join(file.as_str()).to_string();
+        }
+    }
+}
+
+fn invalid_include(origin: &str) -> RoverError {
+    let mut err = RoverError::new(anyhow!(
+        "The `include` entry in {} must be a path or a list of paths.",
+        origin
+    ));
+    err.set_suggestion(RoverErrorSuggestion::ValidComposeFile);
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use serde_yaml::Value;
+    use std::fs;
+
+    use super::merge_includes;
+
+    fn setup(files: &[(&str, &str)]) -> (TempDir, Utf8PathBuf) {
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_dir = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        for (path, contents) in files {
+            let path = tmp_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        (tmp_home, tmp_dir)
+    }
+
+    #[test]
+    fn it_merges_included_subgraphs() {
+        let (_tmp_home, tmp_dir) = setup(&[(
+            "teams/films.yaml",
+            r#"subgraphs:
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: ./films.graphql"#,
+        )]);
+        let yaml = r#"federation_version: =2.4.7
+include:
+  - teams/films.yaml
+subgraphs:
+  people:
+    routing_url: https://people.example.com
+    schema:
+      file: ./people.graphql"#;
+        let merged = merge_includes(serde_yaml::from_str(yaml).unwrap(), Some(&tmp_dir)).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            r#"federation_version: =2.4.7
+subgraphs:
+  people:
+    routing_url: https://people.example.com
+    schema:
+      file: ./people.graphql
+  films:
+    routing_url: https://films.example.com
+    schema:
+      file: teams/./films.graphql"#,
+        )
+        .unwrap();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn it_errs_on_conflicting_subgraph_names() {
+        let (_tmp_home, tmp_dir) = setup(&[
+            (
+                "a.yaml",
+                "subgraphs:\n  films:\n    schema:\n      subgraph_url: http://localhost:4001",
+            ),
+            (
+                "b.yaml",
+                "subgraphs:\n  films:\n    schema:\n      subgraph_url: http://localhost:4002",
+            ),
+        ]);
+        let yaml = "include: [a.yaml, b.yaml]";
+        let err = merge_includes(serde_yaml::from_str(yaml).unwrap(), Some(&tmp_dir)).unwrap_err();
+        assert!(err
+            .message()
+            .starts_with("The 'films' subgraph is defined in both"));
+    }
+
+    #[test]
+    fn it_errs_on_circular_includes() {
+        let (_tmp_home, tmp_dir) =
+            setup(&[("a.yaml", "include: b.yaml"), ("b.yaml", "include: a.yaml")]);
+        let yaml = "include: a.yaml";
+        let err = merge_includes(serde_yaml::from_str(yaml).unwrap(), Some(&tmp_dir)).unwrap_err();
+        assert!(err
+            .message()
+            .contains("Supergraph config includes cannot be circular"));
+    }
+
+    #[test]
+    fn it_errs_on_conflicting_federation_versions() {
+        let (_tmp_home, tmp_dir) = setup(&[("a.yaml", "federation_version: =2.3.2")]);
+        let yaml = "federation_version: =2.4.7\ninclude: a.yaml";
+        assert!(merge_includes(serde_yaml::from_str(yaml).unwrap(), Some(&tmp_dir)).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde_yaml::Value;
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const TIMEOUT_KEY: &str = "introspection_timeout";
+
+/// How long to wait for each subgraph's introspection request, from the
+/// `introspection_timeout` (in seconds) next to a `subgraph_url`, e.g.
+///
+/// ```yaml
+/// schema:
+///   subgraph_url: http://localhost:4001
+///   introspection_headers:
+///     Authorization: Bearer ${env.ACCOUNTS_TOKEN}
+///   introspection_timeout: 30
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct IntrospectionTimeouts {
+    timeouts: BTreeMap<String, Duration>,
+}
+
+impl IntrospectionTimeouts {
+    /// Removes every `introspection_timeout` from a `supergraph.yaml`, since the schema
+    /// source types don't have one, and returns them by subgraph name.
+    pub(crate) fn extract(value: &mut Value) -> RoverResult<Self> {
+        let mut timeouts = BTreeMap::new();
+        let Some(subgraphs) = value.get_mut("subgraphs").and_then(Value::as_mapping_mut) else {
+            return Ok(Self { timeouts });
+        };
+        for (name, subgraph) in subgraphs.iter_mut() {
+            let Some(schema) = subgraph.get_mut("schema").and_then(Value::as_mapping_mut) else {
+                continue;
+            };
+            let Some(timeout) = schema.remove(TIMEOUT_KEY) else {
+                continue;
+            };
+            let subgraph_name = name.as_str().unwrap_or_default().to_string();
+            // variable expansion leaves numbers as strings
+            let seconds = match &timeout {
+                Value::Number(seconds) => seconds.as_u64(),
+                Value::String(seconds) => seconds.trim().parse().ok(),
+                _ => None,
+            };
+            let seconds = seconds.filter(|seconds| *seconds > 0).ok_or_else(|| {
+                let mut err = RoverError::new(anyhow!(
+                    "The `{}` for the '{}' subgraph must be a positive number of seconds.",
+                    TIMEOUT_KEY,
+                    subgraph_name
+                ));
+                err.set_suggestion(RoverErrorSuggestion::ValidComposeFile);
+                err
+            })?;
+            if schema.get("subgraph_url").is_none() {
+                let mut err = RoverError::new(anyhow!(
+                    "The '{}' subgraph has an `{}`, but its schema isn't introspected from a `subgraph_url`.",
+                    subgraph_name,
+                    TIMEOUT_KEY
+                ));
+                err.set_suggestion(RoverErrorSuggestion::ValidComposeFile);
+                return Err(err);
+            }
+            timeouts.insert(subgraph_name, Duration::from_secs(seconds));
+        }
+        Ok(Self { timeouts })
+    }
+
+    /// The timeout for introspecting `subgraph_name`, if its config sets one
+    pub(crate) fn get(&self, subgraph_name: &str) -> Option<Duration> {
+        self.timeouts.get(subgraph_name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::IntrospectionTimeouts;
+
+    #[test]
+    fn it_extracts_introspection_timeouts() {
+        let mut value = serde_yaml::from_str(
+            r#"
+subgraphs:
+  accounts:
+    routing_url: http://localhost:4001
+    schema:
+      subgraph_url: http://localhost:4001
+      introspection_timeout: 30
+  products:
+    routing_url: http://localhost:4002
+    schema:
+      subgraph_url: http://localhost:4002
+      introspection_timeout: "15"
+  reviews:
+    routing_url: http://localhost:4003
+    schema:
+      file: ./reviews.graphql
+"#,
+        )
+        .unwrap();
+        let timeouts = IntrospectionTimeouts::extract(&mut value).unwrap();
+        assert_eq!(timeouts.get("accounts"), Some(Duration::from_secs(30)));
+        assert_eq!(timeouts.get("products"), Some(Duration::from_secs(15)));
+        assert_eq!(timeouts.get("reviews"), None);
+        assert!(value["subgraphs"]["accounts"]["schema"]
+            .get("introspection_timeout")
+            .is_none());
+    }
+
+    #[test]
+    fn it_rejects_timeouts_without_a_subgraph_url() {
+        let mut value = serde_yaml::from_str(
+            r#"
+subgraphs:
+  reviews:
+    routing_url: http://localhost:4003
+    schema:
+      file: ./reviews.graphql
+      introspection_timeout: 30
+"#,
+        )
+        .unwrap();
+        assert!(IntrospectionTimeouts::extract(&mut value).is_err());
+    }
+}
@@ -1,15 +1,38 @@
 pub(crate) mod compose;
+mod containerize;
 mod fetch;
+mod query_plan;
+mod sign;
+mod signature;
+mod verify;
 
+#[cfg(feature = "composition-js")]
+mod env_file;
+#[cfg(feature = "composition-js")]
+mod federation_version;
+#[cfg(feature = "composition-js")]
+mod git_source;
+#[cfg(feature = "composition-js")]
+mod include;
+#[cfg(feature = "composition-js")]
+mod introspection_timeout;
+#[cfg(feature = "composition-js")]
+mod oci_source;
+#[cfg(feature = "composition-js")]
+mod overlay;
 #[cfg(feature = "composition-js")]
 mod resolve_config;
 #[cfg(feature = "composition-js")]
-pub(crate) use resolve_config::{expand_supergraph_yaml, resolve_supergraph_yaml};
+pub(crate) use resolve_config::{
+    expand_supergraph_yaml, expand_supergraph_yaml_with_timeouts, fed_two_subgraph_names,
+    resolve_supergraph_yaml_with_sources,
+};
 
 use camino::Utf8PathBuf;
 use clap::Parser;
 use serde::Serialize;
 
+use crate::options::OutputOpts;
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
@@ -26,6 +49,18 @@ pub enum Command {
 
     /// Fetch supergraph SDL from the graph registry
     Fetch(fetch::Fetch),
+
+    /// Print the query plan the router would use to run an operation against a supergraph schema
+    QueryPlan(query_plan::QueryPlan),
+
+    /// Write a Dockerfile for an image that serves a supergraph schema with the router, and optionally build it
+    Containerize(containerize::Containerize),
+
+    /// Write a detached signature for a supergraph schema, so deploys can check where it came from
+    Sign(sign::Sign),
+
+    /// Check a supergraph schema against its detached signature before deploying it
+    Verify(verify::Verify),
 }
 
 impl Supergraph {
@@ -33,10 +68,17 @@ impl Supergraph {
         &self,
         override_install_path: Option<Utf8PathBuf>,
         client_config: StudioClientConfig,
+        output_opts: &OutputOpts,
     ) -> RoverResult<RoverOutput> {
         match &self.command {
-            Command::Fetch(command) => command.run(client_config),
-            Command::Compose(command) => command.run(override_install_path, client_config),
+            Command::Fetch(command) => command.run(client_config, output_opts),
+            Command::Compose(command) => {
+                command.run(override_install_path, client_config, output_opts)
+            }
+            Command::QueryPlan(command) => command.run(override_install_path, client_config),
+            Command::Containerize(command) => command.run(client_config),
+            Command::Sign(command) => command.run(),
+            Command::Verify(command) => command.run(),
         }
     }
 }
@@ -0,0 +1,77 @@
+use anyhow::anyhow;
+use serde_yaml::{Mapping, Value};
+
+use crate::utils::client::StudioClientConfig;
+use crate::utils::oci::{OciClient, OciReference, SUBGRAPH_SCHEMA_MEDIA_TYPE};
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const OCI_KEY: &str = "oci";
+
+/// Replaces every `schema.oci` source in a `supergraph.yaml` with the SDL pulled
+/// from the referenced OCI artifact, e.g.
+///
+/// ```yaml
+/// schema:
+///   oci: oci://ghcr.io/my-org/accounts-schema:1.2.3
+/// ```
+pub(crate) fn resolve_oci_sources(
+    mut value: Value,
+    client_config: &StudioClientConfig,
+) -> RoverResult<Value> {
+    let subgraphs = match value.get_mut("subgraphs").and_then(Value::as_mapping_mut) {
+        Some(subgraphs) => subgraphs,
+        None => return Ok(value),
+    };
+
+    let mut oci_client: Option<OciClient> = None;
+    for (name, subgraph) in subgraphs.iter_mut() {
+        let schema = match subgraph.get_mut("schema") {
+            Some(schema) if schema.get(OCI_KEY).is_some() => schema,
+            _ => continue,
+        };
+        let subgraph_name = name.as_str().unwrap_or_default();
+        let reference: OciReference = schema[OCI_KEY]
+            .as_str()
+            .ok_or_else(|| {
+                RoverError::new(anyhow!(
+                    "The `oci` schema source for the '{}' subgraph must be a string.",
+                    subgraph_name
+                ))
+            })?
+            .parse()?;
+
+        let oci_client = if let Some(oci_client) = oci_client.as_ref() {
+            oci_client
+        } else {
+            oci_client = Some(OciClient::new(client_config.get_reqwest_client()?));
+            oci_client.as_ref().unwrap()
+        };
+        tracing::info!(
+            "pulling the '{}' subgraph from {}",
+            subgraph_name,
+            reference
+        );
+        let artifact = oci_client.pull(&reference)?;
+        if artifact.media_type != SUBGRAPH_SCHEMA_MEDIA_TYPE {
+            let mut err = RoverError::new(anyhow!(
+                "{} is a '{}' artifact, not a subgraph schema.",
+                reference,
+                artifact.media_type
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Push subgraph schemas with `rover schema push {} --schema <SCHEMA_FILE>`.",
+                reference
+            )));
+            return Err(err);
+        }
+
+        let mut sdl_source = Mapping::new();
+        sdl_source.insert(
+            Value::String("sdl".to_string()),
+            Value::String(artifact.content),
+        );
+        *schema = Value::Mapping(sdl_source);
+    }
+
+    Ok(value)
+}
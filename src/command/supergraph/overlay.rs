@@ -0,0 +1,281 @@
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_std::Fs;
+use serde_yaml::{Mapping, Value};
+
+use crate::command::supergraph::include::rebase_schema_file;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+const SUBGRAPHS_KEY: &str = "subgraphs";
+const SCHEMA_KEY: &str = "schema";
+
+/// The keys that determine which kind of schema source a subgraph uses
+const SCHEMA_SOURCE_KEYS: [&str; 6] = ["file", "subgraph_url", "graphref", "sdl", "git", "oci"];
+
+/// Applies environment overlays, in order, on top of a `supergraph.yaml`.
+///
+/// An overlay uses the same format as `supergraph.yaml`, but only needs to
+/// contain the values that differ for an environment. Mappings are merged key
+/// by key, so an overlay can change a single `routing_url` or introspection
+/// header. A `schema` that switches to a different kind of source replaces the
+/// original source entirely.
+pub(crate) fn apply_overlays(
+    value: Value,
+    overlays: &[Utf8PathBuf],
+    config_dir: Option<&Utf8Path>,
+) -> RoverResult<Value> {
+    overlays.iter().try_fold(value, |value, overlay_path| {
+        let contents = Fs::read_file(overlay_path).map_err(|e| {
+            let mut err = RoverError::new(e);
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Make sure the overlay '{}' exists and is readable.",
+                overlay_path
+            )));
+            err
+        })?;
+        let overlay: Value = serde_yaml::from_str(&contents)?;
+        let relative_dir = relative_overlay_dir(overlay_path, config_dir);
+        apply_overlay(value, overlay, overlay_path, &relative_dir)
+    })
+}
+
+fn apply_overlay(
+    value: Value,
+    overlay: Value,
+    overlay_path: &Utf8Path,
+    relative_dir: &Utf8Path,
+) -> RoverResult<Value> {
+    let mut overlay = match overlay {
+        Value::Mapping(overlay) => overlay,
+        Value::Null => return Ok(value),
+        _ => return Err(invalid_overlay(overlay_path)),
+    };
+    let mut root = match value {
+        Value::Mapping(root) => root,
+        _ => return Ok(value),
+    };
+
+    if let Some(overlay_subgraphs) = overlay.remove(SUBGRAPHS_KEY) {
+        let overlay_subgraphs = match overlay_subgraphs {
+            Value::Mapping(overlay_subgraphs) => overlay_subgraphs,
+            Value::Null => Mapping::new(),
+            _ => return Err(invalid_overlay(overlay_path)),
+        };
+        let subgraphs = root.get_mut(SUBGRAPHS_KEY).and_then(Value::as_mapping_mut);
+        let mut empty = Mapping::new();
+        let subgraphs = subgraphs.unwrap_or(&mut empty);
+
+        for (name, mut overlay_subgraph) in overlay_subgraphs {
+            rebase_schema_file(&mut overlay_subgraph, relative_dir);
+            let subgraph = subgraphs.get_mut(&name).ok_or_else(|| {
+                let mut err = RoverError::new(anyhow!(
+                    "The overlay '{}' patches the '{}' subgraph, which is not defined in the supergraph config.",
+                    overlay_path,
+                    name.as_str().unwrap_or_default()
+                ));
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                    "Overlays can only change subgraphs that already exist. Add the subgraph to your supergraph config, or remove it from the overlay.".to_string(),
+                ));
+                err
+            })?;
+            merge_subgraph(subgraph, overlay_subgraph);
+        }
+    }
+
+    // everything else (like `federation_version`) is merged as-is
+    for (key, overlay_value) in overlay {
+        match root.get_mut(&key) {
+            Some(value) => merge_value(value, overlay_value),
+            None => {
+                root.insert(key, overlay_value);
+            }
+        }
+    }
+
+    Ok(Value::Mapping(root))
+}
+
+fn merge_subgraph(subgraph: &mut Value, overlay_subgraph: Value) {
+    match (subgraph, overlay_subgraph) {
+        (Value::Mapping(subgraph), Value::Mapping(overlay_subgraph)) => {
+            for (key, overlay_value) in overlay_subgraph {
+                match subgraph.get_mut(&key) {
+                    Some(schema) if key.as_str() == Some(SCHEMA_KEY) => {
+                        if switches_schema_source(schema, &overlay_value) {
+                            *schema = overlay_value;
+                        } else {
+                            merge_value(schema, overlay_value);
+                        }
+                    }
+                    Some(value) => merge_value(value, overlay_value),
+                    None => {
+                        subgraph.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (subgraph, overlay_subgraph) => *subgraph = overlay_subgraph,
+    }
+}
+
+/// Whether the overlay's `schema` uses a kind of source that the original doesn't
+fn switches_schema_source(schema: &Value, overlay_schema: &Value) -> bool {
+    SCHEMA_SOURCE_KEYS
+        .iter()
+        .any(|key| overlay_schema.get(key).is_some() && schema.get(key).is_none())
+}
+
+fn merge_value(value: &mut Value, overlay_value: Value) {
+    match (value, overlay_value) {
+        (Value::Mapping(mapping), Value::Mapping(overlay_mapping)) => {
+            for (key, overlay_value) in overlay_mapping {
+                match mapping.get_mut(&key) {
+                    Some(value) => merge_value(value, overlay_value),
+                    None => {
+                        mapping.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (value, overlay_value) => *value = overlay_value,
+    }
+}
+
+/// The overlay's directory, relative to the supergraph config's directory when possible,
+/// so schema `file` paths in the overlay resolve the same way as ones in the config
+fn relative_overlay_dir(overlay_path: &Utf8Path, config_dir: Option<&Utf8Path>) -> Utf8PathBuf {
+    let overlay_dir = overlay_path.parent().unwrap_or_else(|| Utf8Path::new(""));
+    match config_dir {
+        Some(config_dir) if !config_dir.as_str().is_empty() => {
+            match overlay_dir.strip_prefix(config_dir) {
+                Ok(relative_dir) => relative_dir.to_path_buf(),
+                Err(_) => overlay_dir
+                    .canonicalize_utf8()
+                    .unwrap_or_else(|_| overlay_dir.to_path_buf()),
+            }
+        }
+        _ => overlay_dir.to_path_buf(),
+    }
+}
+
+fn invalid_overlay(overlay_path: &Utf8Path) -> RoverError {
+    let mut err = RoverError::new(anyhow!(
+        "The overlay '{}' is not a valid supergraph config overlay.",
+        overlay_path
+    ));
+    err.set_suggestion(RoverErrorSuggestion::ValidComposeFile);
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use serde_yaml::Value;
+
+    use super::apply_overlay;
+
+    const BASE: &str = r#"federation_version: =2.4.7
+subgraphs:
+  products:
+    routing_url: http://localhost:4001
+    schema:
+      subgraph_url: http://localhost:4001
+      introspection_headers:
+        Router-Authorization: local
+        X-Client: rover
+  users:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./users.graphql"#;
+
+    fn overlay(yaml: &str) -> Result<Value, String> {
+        apply_overlay(
+            serde_yaml::from_str(BASE).unwrap(),
+            serde_yaml::from_str(yaml).unwrap(),
+            Utf8Path::new("overlays/staging.yaml"),
+            Utf8Path::new(""),
+        )
+        .map_err(|e| e.message())
+    }
+
+    #[test]
+    fn it_patches_routing_urls_and_headers() {
+        let patched = overlay(
+            r#"subgraphs:
+  products:
+    routing_url: https://products.staging.example.com
+    schema:
+      introspection_headers:
+        Router-Authorization: staging"#,
+        )
+        .unwrap();
+        let expected: Value = serde_yaml::from_str(
+            r#"federation_version: =2.4.7
+subgraphs:
+  products:
+    routing_url: https://products.staging.example.com
+    schema:
+      subgraph_url: http://localhost:4001
+      introspection_headers:
+        Router-Authorization: staging
+        X-Client: rover
+  users:
+    routing_url: http://localhost:4002
+    schema:
+      file: ./users.graphql"#,
+        )
+        .unwrap();
+        assert_eq!(patched, expected);
+    }
+
+    #[test]
+    fn it_replaces_schema_sources_of_a_different_kind() {
+        let patched = overlay(
+            r#"subgraphs:
+  users:
+    schema:
+      graphref: mygraph@staging
+      subgraph: users"#,
+        )
+        .unwrap();
+        let users_schema = &patched["subgraphs"]["users"]["schema"];
+        let expected: Value =
+            serde_yaml::from_str("graphref: mygraph@staging\nsubgraph: users").unwrap();
+        assert_eq!(users_schema, &expected);
+    }
+
+    #[test]
+    fn it_replaces_git_schema_sources() {
+        let base = r#"subgraphs:
+  inventory:
+    routing_url: http://localhost:4003
+    schema:
+      git:
+        url: https://github.com/my-org/inventory.git
+        rev: main
+        path: schema.graphql"#;
+        let patched = apply_overlay(
+            serde_yaml::from_str(base).unwrap(),
+            serde_yaml::from_str(
+                "subgraphs:\n  inventory:\n    schema:\n      file: ./inventory.graphql",
+            )
+            .unwrap(),
+            Utf8Path::new("overlays/local.yaml"),
+            Utf8Path::new(""),
+        )
+        .unwrap();
+        let inventory_schema = &patched["subgraphs"]["inventory"]["schema"];
+        let expected: Value = serde_yaml::from_str("file: ./inventory.graphql").unwrap();
+        assert_eq!(inventory_schema, &expected);
+    }
+
+    #[test]
+    fn it_errs_on_unknown_subgraphs() {
+        let err =
+            overlay("subgraphs:\n  reviews:\n    routing_url: http://localhost:4003").unwrap_err();
+        assert_eq!(
+            err,
+            "The overlay 'overlays/staging.yaml' patches the 'reviews' subgraph, which is not defined in the supergraph config."
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::net::{SocketAddr, TcpListener};
+
+use anyhow::{anyhow, Context};
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::Fs;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tempdir::TempDir;
+
+use crate::command::dev::router::RouterRunner;
+use crate::options::PluginOpts;
+use crate::utils::{client::StudioClientConfig, parsers::FileDescriptorType};
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+/// Asks the router to plan an operation without sending any requests to subgraphs
+const EXPOSE_QUERY_PLAN_HEADER: &str = "Apollo-Expose-Query-Plan";
+
+#[derive(Debug, Serialize, Parser)]
+pub struct QueryPlan {
+    /// The path to the supergraph schema to plan the operation against, like the output of
+    /// `rover supergraph compose`.
+    #[serde(skip_serializing)]
+    #[arg(long = "supergraph")]
+    supergraph_path: Utf8PathBuf,
+
+    /// The path to a file containing the operation to plan. You can pass `-` to use stdin instead of a file.
+    #[serde(skip_serializing)]
+    #[arg(long = "operation")]
+    operation: FileDescriptorType,
+
+    /// The name of the operation to plan, if the file contains more than one.
+    #[serde(skip_serializing)]
+    #[arg(long = "operation-name")]
+    operation_name: Option<String>,
+
+    #[clap(flatten)]
+    opts: PluginOpts,
+}
+
+impl QueryPlan {
+    pub fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        self.opts.prompt_for_license_accept(&client_config)?;
+        let operation = self
+            .operation
+            .read_file_descriptor("operation", &mut std::io::stdin())?;
+
+        // the router plans operations with the same query planner it uses to serve them, so start
+        // one on a free port with the supergraph, and have it plan the operation without running it
+        let tmp_dir = TempDir::new("query-plan")?;
+        tracing::debug!("temp dir created at {}", tmp_dir.path().display());
+        let tmp_path = Utf8PathBuf::try_from(tmp_dir.path().to_path_buf())?;
+        let supergraph_schema_path = tmp_path.join("supergraph.graphql");
+        Fs::copy(&self.supergraph_path, &supergraph_schema_path)?;
+        let router_socket_addr = free_socket_addr()?;
+        let router_config_path = tmp_path.join("router.yaml");
+        Fs::write_file(
+            &router_config_path,
+            format!(
+                "supergraph:\n  listen: {}\nhealth_check:\n  enabled: false\nplugins:\n  experimental.expose_query_plan: true\n",
+                router_socket_addr
+            ),
+        )?;
+
+        let mut router_runner = RouterRunner::new(
+            supergraph_schema_path,
+            router_config_path,
+            self.opts.clone(),
+            router_socket_addr,
+            "/".to_string(),
+            override_install_path,
+            client_config.clone(),
+        );
+        router_runner.set_announce_startup(false);
+        router_runner.spawn()?;
+
+        let response: Value = client_config
+            .get_reqwest_client()?
+            .post(format!("http://{}/", router_socket_addr))
+            .header(EXPOSE_QUERY_PLAN_HEADER, "dry-run")
+            .json(&json!({
+                "query": operation,
+                "operationName": self.operation_name,
+            }))
+            .send()
+            .and_then(|response| response.json())
+            .context("could not get a query plan from the router")?;
+        router_runner.kill()?;
+
+        query_plan_from_response(response)
+    }
+}
+
+/// Finds a port that nothing is listening on yet.
+fn free_socket_addr() -> RoverResult<SocketAddr> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?)
+}
+
+/// Reads the query plan the router puts in its response's extensions, or the errors that kept it
+/// from planning the operation.
+fn query_plan_from_response(response: Value) -> RoverResult<RoverOutput> {
+    let query_plan = &response["extensions"]["apolloQueryPlan"];
+    match query_plan["text"].as_str() {
+        Some(text) => Ok(RoverOutput::QueryPlan {
+            text: text.trim_end().to_string(),
+            plan: query_plan["object"]["node"].clone(),
+        }),
+        None => {
+            let messages: Vec<&str> = response["errors"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|error| error["message"].as_str())
+                .collect();
+            let mut err = if messages.is_empty() {
+                RoverError::new(anyhow!("the router did not return a query plan"))
+            } else {
+                RoverError::new(anyhow!(
+                    "the operation could not be planned:\n{}",
+                    messages.join("\n")
+                ))
+            };
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Make sure the operation is valid against the supergraph schema, and pass `--operation-name` if the file contains more than one operation.".to_string(),
+            ));
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::query_plan_from_response;
+    use crate::RoverOutput;
+
+    #[test]
+    fn it_reads_the_query_plan_from_the_response() {
+        let response = json!({
+            "data": null,
+            "extensions": {
+                "apolloQueryPlan": {
+                    "object": {
+                        "kind": "QueryPlan",
+                        "node": { "kind": "Fetch", "serviceName": "products" }
+                    },
+                    "text": "QueryPlan {\n  Fetch(service: \"products\") {\n    {\n      topProducts {\n        name\n      }\n    }\n  },\n}\n"
+                }
+            }
+        });
+        match query_plan_from_response(response).unwrap() {
+            RoverOutput::QueryPlan { text, plan } => {
+                assert!(text.starts_with("QueryPlan {"));
+                assert!(text.ends_with('}'));
+                assert_eq!(plan, json!({ "kind": "Fetch", "serviceName": "products" }));
+            }
+            output => panic!("unexpected output: {:?}", output),
+        }
+
+        let response = json!({
+            "errors": [{ "message": "Cannot query field \"topProduct\" on type \"Query\"." }]
+        });
+        let err = query_plan_from_response(response).unwrap_err();
+        assert!(err.to_string().contains("topProduct"));
+    }
+}
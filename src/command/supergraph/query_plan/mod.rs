@@ -0,0 +1,11 @@
+#[cfg(not(feature = "composition-js"))]
+mod no_query_plan;
+
+#[cfg(not(feature = "composition-js"))]
+pub(crate) use no_query_plan::QueryPlan;
+
+#[cfg(feature = "composition-js")]
+mod do_query_plan;
+
+#[cfg(feature = "composition-js")]
+pub(crate) use do_query_plan::QueryPlan;
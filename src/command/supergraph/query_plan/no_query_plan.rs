@@ -0,0 +1,36 @@
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::utils::client::StudioClientConfig;
+use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct QueryPlan {
+    /// The path to the supergraph schema to plan the operation against.
+    #[clap(long = "supergraph")]
+    #[serde(skip_serializing)]
+    #[allow(unused)]
+    supergraph_path: Option<Utf8PathBuf>,
+
+    /// The path to a file containing the operation to plan.
+    #[clap(long = "operation")]
+    #[serde(skip_serializing)]
+    #[allow(unused)]
+    operation_path: Option<Utf8PathBuf>,
+}
+
+impl QueryPlan {
+    pub fn run(
+        &self,
+        _override_install_path: Option<Utf8PathBuf>,
+        _client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        let mut err = RoverError::new(anyhow!(
+            "This version of Rover does not support this command."
+        ));
+        err.set_suggestion(RoverErrorSuggestion::CheckGnuVersion);
+        Err(err)
+    }
+}
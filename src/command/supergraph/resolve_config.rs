@@ -1,70 +1,168 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use apollo_federation_types::{
     build::{BuildError, BuildErrors, SubgraphDefinition},
     config::{FederationVersion, SchemaSource, SubgraphConfig, SupergraphConfig},
 };
 use apollo_parser::{ast, Parser};
+use camino::{Utf8Path, Utf8PathBuf};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rover_std::{Fs, Style};
 
-use std::str::FromStr;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    str::FromStr,
+};
 
 use rover_client::operations::subgraph::fetch::{self, SubgraphFetchInput};
 use rover_client::operations::subgraph::introspect::{self, SubgraphIntrospectInput};
 use rover_client::shared::GraphRef;
-use rover_client::{blocking::GraphQLClient, RoverClientError};
+use rover_client::RoverClientError;
 
+use crate::command::install::resolve_federation_version;
+use crate::command::supergraph::{
+    env_file::expand_with_env_files, federation_version::resolve_federation_version_range,
+    git_source::resolve_git_sources, include::merge_includes,
+    introspection_timeout::IntrospectionTimeouts, oci_source::resolve_oci_sources,
+    overlay::apply_overlays,
+};
 use crate::{
     options::ProfileOpt,
-    utils::{client::StudioClientConfig, expansion::expand, parsers::FileDescriptorType},
+    utils::{
+        client::StudioClientConfig, parsers::FileDescriptorType, source_map::SubgraphSourceMap,
+    },
 };
 use crate::{RoverError, RoverErrorSuggestion, RoverResult};
 
-pub(crate) fn expand_supergraph_yaml(content: &str) -> RoverResult<SupergraphConfig> {
-    serde_yaml::from_str(content)
-        .map_err(RoverError::from)
-        .and_then(expand)
-        .and_then(|v| serde_yaml::from_value(v).map_err(RoverError::from))
+/// Parses a `supergraph.yaml`, merging in any `include`d files (resolved
+/// relative to `config_dir`), applying environment overlays, expanding variables,
+/// fetching any `git` and `oci` schema sources, and pinning a `federation_version`
+/// range to an exact version (which is required when `locked` is true).
+pub(crate) fn expand_supergraph_yaml(
+    content: &str,
+    config_dir: Option<&Utf8Path>,
+    overlays: &[Utf8PathBuf],
+    client_config: &StudioClientConfig,
+    locked: bool,
+) -> RoverResult<SupergraphConfig> {
+    expand_supergraph_yaml_with_timeouts(content, config_dir, overlays, client_config, locked)
+        .map(|(supergraph_config, _)| supergraph_config)
 }
 
-#[cfg(test)]
-mod test_expand_supergraph_yaml {
-    use apollo_federation_types::config::FederationVersion;
-
-    #[test]
-    fn test_supergraph_yaml_int_version() {
-        let yaml = r#"
-federation_version: 1
-subgraphs: 
-"#;
-        let config = super::expand_supergraph_yaml(yaml).unwrap();
-        assert_eq!(
-            config.get_federation_version(),
-            Some(FederationVersion::LatestFedOne)
-        );
-    }
+/// Like [`expand_supergraph_yaml`], but also returns the `introspection_timeout` of each
+/// subgraph that's introspected from a `subgraph_url`.
+pub(crate) fn expand_supergraph_yaml_with_timeouts(
+    content: &str,
+    config_dir: Option<&Utf8Path>,
+    overlays: &[Utf8PathBuf],
+    client_config: &StudioClientConfig,
+    locked: bool,
+) -> RoverResult<(SupergraphConfig, IntrospectionTimeouts)> {
+    let mut value = serde_yaml::from_str(content)
+        .map_err(RoverError::from)
+        .and_then(|v| merge_includes(v, config_dir))
+        .and_then(|v| apply_overlays(v, overlays, config_dir))
+        .and_then(|v| expand_with_env_files(v, config_dir))
+        .and_then(|v| resolve_git_sources(v, &client_config.config.home.join("git")))
+        .and_then(|v| resolve_oci_sources(v, client_config))
+        .and_then(|v| resolve_federation_version_range(v, client_config, locked))?;
+    let introspection_timeouts = IntrospectionTimeouts::extract(&mut value)?;
+    let supergraph_config = serde_yaml::from_value(value).map_err(RoverError::from)?;
+    Ok((supergraph_config, introspection_timeouts))
 }
 
+#[cfg(test)]
 pub(crate) fn resolve_supergraph_yaml(
     unresolved_supergraph_yaml: &FileDescriptorType,
+    overlays: &[Utf8PathBuf],
     client_config: StudioClientConfig,
     profile_opt: &ProfileOpt,
+    locked: bool,
 ) -> RoverResult<SupergraphConfig> {
+    resolve_supergraph_yaml_with_sources(
+        unresolved_supergraph_yaml,
+        overlays,
+        client_config,
+        profile_opt,
+        locked,
+        None,
+    )
+    .map(|(supergraph_config, _)| supergraph_config)
+}
+
+/// Resolves every subgraph in a supergraph config, and returns the files that each subgraph's
+/// schema was read from, so composition errors can point at them. Subgraphs are resolved
+/// `concurrency` at a time, or one per CPU if it's `None`.
+pub(crate) fn resolve_supergraph_yaml_with_sources(
+    unresolved_supergraph_yaml: &FileDescriptorType,
+    overlays: &[Utf8PathBuf],
+    client_config: StudioClientConfig,
+    profile_opt: &ProfileOpt,
+    locked: bool,
+    concurrency: Option<usize>,
+) -> RoverResult<(SupergraphConfig, SubgraphSourceMap)> {
     let err_no_routing_url = || {
         let err = anyhow!("No routing_url found for schema file.");
         let mut err = RoverError::new(err);
         err.set_suggestion(RoverErrorSuggestion::ValidComposeRoutingUrl);
         err
     };
-    let supergraph_config = unresolved_supergraph_yaml
+    let config_dir = match unresolved_supergraph_yaml {
+        FileDescriptorType::File(config_path) => config_path.parent(),
+        FileDescriptorType::Stdin => None,
+    };
+    let (supergraph_config, introspection_timeouts) = unresolved_supergraph_yaml
         .read_file_descriptor("supergraph config", &mut std::io::stdin())
-        .and_then(|contents| expand_supergraph_yaml(&contents))?;
-    let maybe_specified_federation_version = supergraph_config.get_federation_version();
+        .and_then(|contents| {
+            expand_supergraph_yaml_with_timeouts(
+                &contents,
+                config_dir,
+                overlays,
+                &client_config,
+                locked,
+            )
+        })?;
+    let source_map = SubgraphSourceMap::from_supergraph_config(&supergraph_config, config_dir);
+    // the profile's `federation-version` default applies when the config doesn't set one
+    let maybe_specified_federation_version = match supergraph_config.get_federation_version() {
+        Some(federation_version) => Some(federation_version),
+        None => client_config
+            .get_profile_settings(&profile_opt.profile_name)
+            .federation_version
+            .map(|spec| resolve_federation_version(&spec, &client_config, locked))
+            .transpose()?,
+    };
     let supergraph_config = supergraph_config
         .into_iter()
         .collect::<Vec<(String, SubgraphConfig)>>();
 
-    let subgraph_definition_results: Vec<(String, RoverResult<SubgraphDefinition>)> =
+    // subgraphs share clients, so requests to the same host reuse their connections
+    let studio_client = if supergraph_config
+        .iter()
+        .any(|(_, subgraph)| matches!(subgraph.schema, SchemaSource::Subgraph { .. }))
+    {
+        Some(client_config.get_authenticated_client(profile_opt)?)
+    } else {
+        None
+    };
+    let mut endpoint_builder = None;
+    let mut endpoint_clients = BTreeMap::new();
+    for (subgraph_name, subgraph_data) in &supergraph_config {
+        if let SchemaSource::SubgraphIntrospection { .. } = subgraph_data.schema {
+            let endpoint_builder = *endpoint_builder
+                .get_or_insert_with(|| client_config.get_endpoint_builder(profile_opt));
+            // a client's timeout applies to all of its requests, so each timeout needs its own
+            let timeout = introspection_timeouts.get(subgraph_name);
+            if let Entry::Vacant(entry) = endpoint_clients.entry(timeout) {
+                let client = match timeout {
+                    Some(timeout) => endpoint_builder.with_timeout(timeout).build()?,
+                    None => endpoint_builder.build()?,
+                };
+                entry.insert(client);
+            }
+        }
+    }
+
+    let resolve_subgraphs = || -> Vec<(String, RoverResult<SubgraphDefinition>)> {
         supergraph_config
             .into_par_iter()
             .map(|(subgraph_name, subgraph_data)| {
@@ -101,74 +199,76 @@ pub(crate) fn resolve_supergraph_yaml(
                         subgraph_url,
                         introspection_headers,
                     } => {
-                        client_config
-                            .get_reqwest_client()
-                            .map_err(RoverError::from)
-                            .and_then(|reqwest_client| {
-                                let client =
-                                    GraphQLClient::new(subgraph_url.as_ref(), reqwest_client);
-
-                                // given a federated introspection URL, use subgraph introspect to
-                                // obtain SDL and add it to subgraph_definition.
-                                introspect::run(
-                                    SubgraphIntrospectInput {
-                                        headers: introspection_headers.clone().unwrap_or_default(),
-                                    },
-                                    &client,
-                                    false,
-                                )
-                                .map(|introspection_response| {
-                                    let schema = introspection_response.result;
+                        let endpoint_builder = endpoint_builder
+                            .expect("a client is made whenever a subgraph is introspected");
+                        // introspection is only retried when the number of retries is configured
+                        let should_retry = endpoint_builder.get_retries().is_some();
+                        let client = endpoint_builder.graphql_client_with(
+                            subgraph_url.as_ref(),
+                            endpoint_clients[&introspection_timeouts.get(&subgraph_name)].clone(),
+                        );
+                        // given a federated introspection URL, use subgraph introspect to
+                        // obtain SDL and add it to subgraph_definition.
+                        introspect::run(
+                            SubgraphIntrospectInput {
+                                headers: introspection_headers.clone().unwrap_or_default(),
+                            },
+                            &client,
+                            should_retry,
+                        )
+                        .map(|introspection_response| {
+                            let schema = introspection_response.result;
 
-                                    // We don't require a routing_url in config for this variant of a schema,
-                                    // if one isn't provided, just use the URL they passed for introspection.
-                                    let url = &subgraph_data
-                                        .routing_url
-                                        .clone()
-                                        .unwrap_or_else(|| subgraph_url.to_string());
-                                    SubgraphDefinition::new(subgraph_name, url, schema)
-                                })
-                                .map_err(RoverError::from)
-                            })
+                            // We don't require a routing_url in config for this variant of a schema,
+                            // if one isn't provided, just use the URL they passed for introspection.
+                            let url = &subgraph_data
+                                .routing_url
+                                .clone()
+                                .unwrap_or_else(|| subgraph_url.to_string());
+                            SubgraphDefinition::new(subgraph_name, url, schema)
+                        })
+                        .map_err(RoverError::from)
                     }
                     SchemaSource::Subgraph {
                         graphref: graph_ref,
                         subgraph,
                     } => {
-                        client_config
-                            .get_authenticated_client(profile_opt)
+                        let authenticated_client = studio_client
+                            .as_ref()
+                            .expect("a client is made whenever a subgraph is fetched from GraphOS");
+                        GraphRef::from_str(graph_ref)
                             .map_err(RoverError::from)
-                            .and_then(|authenticated_client| {
+                            .and_then(|graph_ref| {
                                 // given a graph_ref and subgraph, run subgraph fetch to
                                 // obtain SDL and add it to subgraph_definition.
                                 fetch::run(
                                     SubgraphFetchInput {
-                                        graph_ref: GraphRef::from_str(graph_ref)?,
+                                        graph_ref,
                                         subgraph_name: subgraph.clone(),
                                     },
-                                    &authenticated_client,
+                                    authenticated_client,
                                 )
                                 .map_err(RoverError::from)
-                                .and_then(|result| {
-                                    // We don't require a routing_url in config for this variant of a schema,
-                                    // if one isn't provided, just use the routing URL from the graph registry (if it exists).
-                                    if let rover_client::shared::SdlType::Subgraph {
-                                        routing_url: Some(graph_registry_routing_url),
-                                    } = result.sdl.r#type
-                                    {
-                                        let url = subgraph_data
-                                            .routing_url
-                                            .clone()
-                                            .unwrap_or(graph_registry_routing_url);
-                                        Ok(SubgraphDefinition::new(
-                                            subgraph_name,
-                                            url,
-                                            &result.sdl.contents,
-                                        ))
-                                    } else {
-                                        Err(err_no_routing_url())
-                                    }
-                                })
+                            })
+                            .and_then(|result| {
+                                // We don't require a routing_url in config for this variant of a schema,
+                                // if one isn't provided, just use the routing URL from the graph registry (if it exists).
+                                if let rover_client::shared::SdlType::Subgraph {
+                                    routing_url: Some(graph_registry_routing_url),
+                                } = result.sdl.r#type
+                                {
+                                    let url = subgraph_data
+                                        .routing_url
+                                        .clone()
+                                        .unwrap_or(graph_registry_routing_url);
+                                    Ok(SubgraphDefinition::new(
+                                        subgraph_name,
+                                        url,
+                                        &result.sdl.contents,
+                                    ))
+                                } else {
+                                    Err(err_no_routing_url())
+                                }
                             })
                     }
                     SchemaSource::Sdl { sdl } => subgraph_data
@@ -180,7 +280,16 @@ pub(crate) fn resolve_supergraph_yaml(
 
                 (cloned_subgraph_name, result)
             })
-            .collect();
+            .collect()
+    };
+    let subgraph_definition_results = match concurrency {
+        Some(concurrency) => rayon::ThreadPoolBuilder::new()
+            .num_threads(concurrency)
+            .build()
+            .context("could not start the threads to resolve subgraphs on")?
+            .install(resolve_subgraphs),
+        None => resolve_subgraphs(),
+    };
 
     let mut subgraph_definitions = Vec::new();
     let mut subgraph_definition_errors = Vec::new();
@@ -222,29 +331,7 @@ pub(crate) fn resolve_supergraph_yaml(
 
     let mut resolved_supergraph_config: SupergraphConfig = subgraph_definitions.into();
 
-    let mut fed_two_subgraph_names = Vec::new();
-    for subgraph_definition in resolved_supergraph_config.get_subgraph_definitions()? {
-        let parser = Parser::new(&subgraph_definition.sdl);
-        let parsed_ast = parser.parse();
-        let doc = parsed_ast.document();
-        for definition in doc.definitions() {
-            let maybe_directives = match definition {
-                ast::Definition::SchemaExtension(ext) => ext.directives(),
-                ast::Definition::SchemaDefinition(def) => def.directives(),
-                _ => None,
-            }
-            .map(|d| d.directives());
-            if let Some(directives) = maybe_directives {
-                for directive in directives {
-                    if let Some(directive_name) = directive.name() {
-                        if "link" == directive_name.text() {
-                            fed_two_subgraph_names.push(subgraph_definition.name.clone());
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let fed_two_subgraph_names = fed_two_subgraph_names(&resolved_supergraph_config)?;
 
     let print_inexact_warning = || {
         eprintln!("{} An exact {} was not specified in '{}'. Future versions of {} will fail without specifying an exact federation version. See {} for more information.", Style::WarningPrefix.paint("WARN:"), Style::Command.paint("federation_version"), &unresolved_supergraph_yaml, Style::Command.paint("`rover supergraph compose`"), Style::Link.paint("https://www.apollographql.com/docs/rover/commands/supergraphs#setting-a-composition-version"))
@@ -284,5 +371,66 @@ pub(crate) fn resolve_supergraph_yaml(
         resolved_supergraph_config.set_federation_version(FederationVersion::LatestFedTwo)
     }
 
-    Ok(resolved_supergraph_config)
+    Ok((resolved_supergraph_config, source_map))
+}
+
+/// Returns the names of subgraphs with `@link` directives, which are only valid in Federation 2.
+pub(crate) fn fed_two_subgraph_names(
+    supergraph_config: &SupergraphConfig,
+) -> RoverResult<Vec<String>> {
+    let mut fed_two_subgraph_names = Vec::new();
+    for subgraph_definition in supergraph_config.get_subgraph_definitions()? {
+        let parser = Parser::new(&subgraph_definition.sdl);
+        let parsed_ast = parser.parse();
+        let doc = parsed_ast.document();
+        for definition in doc.definitions() {
+            let maybe_directives = match definition {
+                ast::Definition::SchemaExtension(ext) => ext.directives(),
+                ast::Definition::SchemaDefinition(def) => def.directives(),
+                _ => None,
+            }
+            .map(|d| d.directives());
+            if let Some(directives) = maybe_directives {
+                for directive in directives {
+                    if let Some(directive_name) = directive.name() {
+                        if "link" == directive_name.text() {
+                            fed_two_subgraph_names.push(subgraph_definition.name.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(fed_two_subgraph_names)
+}
+
+#[cfg(test)]
+mod test_expand_supergraph_yaml {
+    use apollo_federation_types::config::FederationVersion;
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::Config;
+
+    use crate::utils::client::{ClientBuilder, StudioClientConfig};
+
+    #[test]
+    fn test_supergraph_yaml_int_version() {
+        let yaml = r#"
+federation_version: 1
+subgraphs: 
+"#;
+        let tmp_home = TempDir::new().unwrap();
+        let tmp_path = Utf8PathBuf::try_from(tmp_home.path().to_path_buf()).unwrap();
+        let client_config = StudioClientConfig::new(
+            None,
+            Config::new(Some(&tmp_path), None).unwrap(),
+            false,
+            ClientBuilder::default(),
+        );
+        let config = super::expand_supergraph_yaml(yaml, None, &[], &client_config, false).unwrap();
+        assert_eq!(
+            config.get_federation_version(),
+            Some(FederationVersion::LatestFedOne)
+        );
+    }
 }
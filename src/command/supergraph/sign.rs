@@ -0,0 +1,61 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::{Emoji, Fs, Style};
+use serde::Serialize;
+
+use super::signature::{read_key, SupergraphSignature};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Sign {
+    /// The path to the supergraph schema to sign, like the output of `rover supergraph compose`
+    #[arg(long = "schema")]
+    #[serde(skip_serializing)]
+    schema_path: Utf8PathBuf,
+
+    /// A file containing the secret key to sign the schema with
+    #[arg(
+        long = "key-file",
+        required_unless_present = "sign_command",
+        conflicts_with = "sign_command"
+    )]
+    #[serde(skip_serializing)]
+    key_file: Option<Utf8PathBuf>,
+
+    /// A command that signs the schema's digest, like a call to a KMS CLI. It gets the digest on
+    /// stdin and prints the signature.
+    #[arg(long = "sign-command")]
+    #[serde(skip_serializing)]
+    sign_command: Option<String>,
+
+    /// Where to write the signature. Defaults to the schema's path with `.sig` added.
+    #[arg(long = "signature")]
+    #[serde(skip_serializing)]
+    signature_path: Option<Utf8PathBuf>,
+}
+
+impl Sign {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = Fs::read_file(&self.schema_path)?;
+        let signature = match (&self.key_file, &self.sign_command) {
+            (Some(key_file), _) => SupergraphSignature::sign_with_key(&sdl, &read_key(key_file)?),
+            (None, Some(sign_command)) => {
+                SupergraphSignature::sign_with_command(&sdl, sign_command)?
+            }
+            (None, None) => unreachable!("clap requires --key-file or --sign-command"),
+        };
+        let signature_path = self
+            .signature_path
+            .clone()
+            .unwrap_or_else(|| SupergraphSignature::default_path(&self.schema_path));
+        signature.write(&signature_path)?;
+        eprintln!(
+            "{}signed {} ({}), and wrote the signature to {}",
+            Emoji::Success,
+            Style::Path.paint(&self.schema_path),
+            signature.digest,
+            Style::Path.paint(&signature_path)
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
@@ -0,0 +1,271 @@
+//! Detached signatures for supergraph schemas, so a router's deploy can check that the schema it's
+//! about to load is the one CI composed. A signature is a small JSON file next to the schema,
+//! signed either with a shared secret key from a file or by an external command, like a KMS CLI.
+
+use std::fmt::Write as _;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context};
+use camino::{Utf8Path, Utf8PathBuf};
+use hmac::{Hmac, Mac};
+use rover_std::Fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// The extension added to a schema's path to find its signature
+const SIGNATURE_EXTENSION: &str = "sig";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How a supergraph schema was signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SignatureAlgorithm {
+    /// An HMAC-SHA256 of the schema with a secret key from a file
+    HmacSha256,
+    /// Whatever an external signing command, like a KMS CLI, printed for the schema's digest
+    Command,
+}
+
+/// The contents of a supergraph schema's signature file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SupergraphSignature {
+    pub(crate) algorithm: SignatureAlgorithm,
+    /// The SHA-256 digest of the signed schema, like `sha256:9f86d0...`
+    pub(crate) digest: String,
+    pub(crate) signature: String,
+}
+
+impl SupergraphSignature {
+    /// Signs `sdl` with the secret key in `key`.
+    pub(crate) fn sign_with_key(sdl: &str, key: &str) -> Self {
+        Self {
+            algorithm: SignatureAlgorithm::HmacSha256,
+            digest: digest(sdl),
+            signature: hex(&hmac_sha256(key.as_bytes(), sdl.as_bytes())
+                .finalize()
+                .into_bytes()),
+        }
+    }
+
+    /// Signs `sdl` by running `command` with the schema's digest on stdin, and using what it
+    /// prints as the signature.
+    pub(crate) fn sign_with_command(sdl: &str, command: &str) -> RoverResult<Self> {
+        let digest = digest(sdl);
+        let mut child = shell_command(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("could not run the signing command `{}`", command))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(digest.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        let signature = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || signature.is_empty() {
+            return Err(RoverError::new(anyhow!(
+                "the signing command `{}` did not print a signature ({})",
+                command,
+                output.status
+            )));
+        }
+        Ok(Self {
+            algorithm: SignatureAlgorithm::Command,
+            digest,
+            signature,
+        })
+    }
+
+    /// Checks that this signature is for `sdl`, and that it was made with the secret key in `key`.
+    pub(crate) fn verify_with_key(&self, sdl: &str, key: &str) -> RoverResult<()> {
+        self.verify_digest(sdl)?;
+        if self.algorithm != SignatureAlgorithm::HmacSha256 {
+            let mut err = RoverError::new(anyhow!(
+                "the supergraph schema was signed by a command, so it can't be verified with a key file"
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Pass the command that verifies its signatures with `--verify-command` instead."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+        // `verify_slice` compares in constant time, so timing doesn't leak how much of it matched
+        let mac = hmac_sha256(key.as_bytes(), sdl.as_bytes());
+        match unhex(&self.signature) {
+            Some(signature) if mac.verify_slice(&signature).is_ok() => Ok(()),
+            _ => Err(invalid_signature()),
+        }
+    }
+
+    /// Checks that this signature is for `sdl`, and runs `command` to check the signature itself.
+    /// The command gets the digest and signature in the `ROVER_SUPERGRAPH_DIGEST` and
+    /// `ROVER_SUPERGRAPH_SIGNATURE` environment variables, and exits successfully if it's valid.
+    pub(crate) fn verify_with_command(&self, sdl: &str, command: &str) -> RoverResult<()> {
+        self.verify_digest(sdl)?;
+        let status = shell_command(command)
+            .env("ROVER_SUPERGRAPH_DIGEST", &self.digest)
+            .env("ROVER_SUPERGRAPH_SIGNATURE", &self.signature)
+            .status()
+            .with_context(|| format!("could not run the verification command `{}`", command))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(invalid_signature())
+        }
+    }
+
+    fn verify_digest(&self, sdl: &str) -> RoverResult<()> {
+        if self.digest == digest(sdl) {
+            Ok(())
+        } else {
+            let mut err = RoverError::new(anyhow!(
+                "the supergraph schema has changed since it was signed"
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Make sure the schema and its signature come from the same build.".to_string(),
+            ));
+            Err(err)
+        }
+    }
+
+    pub(crate) fn read(path: &Utf8Path) -> RoverResult<Self> {
+        let contents = Fs::read_file(path)?;
+        Ok(serde_json::from_str(&contents)
+            .with_context(|| format!("could not read the signature at {}", path))?)
+    }
+
+    pub(crate) fn write(&self, path: &Utf8Path) -> RoverResult<()> {
+        Fs::write_file(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Where the signature of the schema at `schema_path` goes unless another path is given.
+    pub(crate) fn default_path(schema_path: &Utf8Path) -> Utf8PathBuf {
+        match schema_path.extension() {
+            Some(extension) => {
+                schema_path.with_extension(format!("{}.{}", extension, SIGNATURE_EXTENSION))
+            }
+            None => schema_path.with_extension(SIGNATURE_EXTENSION),
+        }
+    }
+}
+
+/// Reads a secret signing key, ignoring the trailing newline most editors add.
+pub(crate) fn read_key(path: &Utf8Path) -> RoverResult<String> {
+    let key = Fs::read_file(path)?.trim_end().to_string();
+    if key.is_empty() {
+        return Err(RoverError::new(anyhow!(
+            "the key file at {} is empty",
+            path
+        )));
+    }
+    Ok(key)
+}
+
+fn invalid_signature() -> RoverError {
+    let mut err = RoverError::new(anyhow!("the supergraph schema's signature is not valid"));
+    err.set_suggestion(RoverErrorSuggestion::Adhoc(
+        "Don't deploy this schema. Sign it again with the right key if it came from a trusted build."
+            .to_string(),
+    ));
+    err
+}
+
+fn digest(sdl: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(sdl.as_bytes()))
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+/// Decodes a hex string, or returns `None` if it isn't valid hex.
+fn unhex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use hmac::Mac;
+
+    use super::{hex, hmac_sha256, SignatureAlgorithm, SupergraphSignature};
+
+    const SDL: &str = "schema @link(url: \"https://specs.apollo.dev/link/v1.0\") { query: Query }";
+
+    #[test]
+    fn it_computes_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")
+                .finalize()
+                .into_bytes()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn it_signs_and_verifies_with_a_key() {
+        let signature = SupergraphSignature::sign_with_key(SDL, "secret");
+        assert_eq!(signature.algorithm, SignatureAlgorithm::HmacSha256);
+        assert!(signature.digest.starts_with("sha256:"));
+        assert!(signature.verify_with_key(SDL, "secret").is_ok());
+
+        let err = signature
+            .verify_with_key(SDL, "not the secret")
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "the supergraph schema's signature is not valid"
+        );
+        let err = signature
+            .verify_with_key(&format!("{}\n# tampered", SDL), "secret")
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "the supergraph schema has changed since it was signed"
+        );
+    }
+
+    #[test]
+    fn it_puts_signatures_next_to_the_schema() {
+        assert_eq!(
+            SupergraphSignature::default_path(Utf8Path::new("dist/supergraph.graphql")),
+            "dist/supergraph.graphql.sig"
+        );
+        assert_eq!(
+            SupergraphSignature::default_path(Utf8Path::new("supergraph")),
+            "supergraph.sig"
+        );
+    }
+}
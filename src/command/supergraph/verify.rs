@@ -0,0 +1,58 @@
+use camino::Utf8PathBuf;
+use clap::Parser;
+use rover_std::{Emoji, Fs, Style};
+use serde::Serialize;
+
+use super::signature::{read_key, SupergraphSignature};
+use crate::{RoverOutput, RoverResult};
+
+#[derive(Debug, Serialize, Parser)]
+pub struct Verify {
+    /// The path to the supergraph schema to verify
+    #[arg(long = "schema")]
+    #[serde(skip_serializing)]
+    schema_path: Utf8PathBuf,
+
+    /// A file containing the secret key the schema was signed with
+    #[arg(
+        long = "key-file",
+        required_unless_present = "verify_command",
+        conflicts_with = "verify_command"
+    )]
+    #[serde(skip_serializing)]
+    key_file: Option<Utf8PathBuf>,
+
+    /// A command that checks a signature made with `--sign-command`. It gets the schema's digest
+    /// and signature in the `ROVER_SUPERGRAPH_DIGEST` and `ROVER_SUPERGRAPH_SIGNATURE`
+    /// environment variables, and exits successfully if the signature is valid.
+    #[arg(long = "verify-command")]
+    #[serde(skip_serializing)]
+    verify_command: Option<String>,
+
+    /// The path to the signature. Defaults to the schema's path with `.sig` added.
+    #[arg(long = "signature")]
+    #[serde(skip_serializing)]
+    signature_path: Option<Utf8PathBuf>,
+}
+
+impl Verify {
+    pub fn run(&self) -> RoverResult<RoverOutput> {
+        let sdl = Fs::read_file(&self.schema_path)?;
+        let signature_path = self
+            .signature_path
+            .clone()
+            .unwrap_or_else(|| SupergraphSignature::default_path(&self.schema_path));
+        let signature = SupergraphSignature::read(&signature_path)?;
+        match (&self.key_file, &self.verify_command) {
+            (Some(key_file), _) => signature.verify_with_key(&sdl, &read_key(key_file)?)?,
+            (None, Some(verify_command)) => signature.verify_with_command(&sdl, verify_command)?,
+            (None, None) => unreachable!("clap requires --key-file or --verify-command"),
+        }
+        eprintln!(
+            "{}the signature of {} is valid",
+            Emoji::Success,
+            Style::Path.paint(&self.schema_path)
+        );
+        Ok(RoverOutput::EmptySuccess)
+    }
+}
@@ -0,0 +1,195 @@
+use camino::Utf8Path;
+use rover_std::Fs;
+
+use crate::options::ProjectLanguage;
+use crate::RoverResult;
+
+use super::custom_scalars::Url;
+use super::queries::list_templates_for_language::{Language, ListTemplatesForLanguageTemplates};
+
+/// Where connector templates are documented, listed in place of a template repository
+const DOCS_URL: &str =
+    "https://www.apollographql.com/docs/rover/commands/template#connector-templates";
+
+/// The subgraph name connector templates use unless `--name` says otherwise
+pub(crate) const DEFAULT_SUBGRAPH_NAME: &str = "products";
+
+/// The port connector templates listen on unless `--port` says otherwise
+pub(crate) const DEFAULT_PORT: u16 = 4001;
+
+/// A template that ships with Rover, rather than being downloaded, for a subgraph that wraps an
+/// existing REST or gRPC backend. In its files, `{{name}}` is replaced with the project's
+/// directory name, `{{subgraph_name}}` with the subgraph's name, and `{{port}}` with the port
+/// it listens on.
+pub(crate) struct ConnectorTemplate {
+    pub(crate) id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    language: Language,
+    files: &'static [(&'static str, &'static str)],
+}
+
+const CONNECTOR_TEMPLATES: &[ConnectorTemplate] = &[
+    ConnectorTemplate {
+        id: "subgraph-typescript-rest-connector",
+        name: "REST API connector (Apollo Server)",
+        description: "A subgraph that wraps an existing REST API with a RESTDataSource",
+        language: Language::TYPESCRIPT,
+        files: &[
+            ("README.md", include_str!("./connectors/rest/README.md")),
+            (
+                "package.json",
+                include_str!("./connectors/rest/package.json"),
+            ),
+            (
+                "tsconfig.json",
+                include_str!("./connectors/rest/tsconfig.json"),
+            ),
+            (
+                "supergraph.yaml",
+                include_str!("./connectors/rest/supergraph.yaml"),
+            ),
+            (
+                "schema.graphql",
+                include_str!("./connectors/rest/schema.graphql"),
+            ),
+            (
+                "src/index.ts",
+                include_str!("./connectors/rest/src/index.ts"),
+            ),
+            (
+                "src/resolvers.ts",
+                include_str!("./connectors/rest/src/resolvers.ts"),
+            ),
+            (
+                "src/datasources/products-api.ts",
+                include_str!("./connectors/rest/src/datasources/products-api.ts"),
+            ),
+        ],
+    },
+    ConnectorTemplate {
+        id: "subgraph-typescript-grpc-connector",
+        name: "gRPC service connector (Apollo Server)",
+        description: "A subgraph that wraps an existing gRPC service, batching entity lookups",
+        language: Language::TYPESCRIPT,
+        files: &[
+            ("README.md", include_str!("./connectors/grpc/README.md")),
+            (
+                "package.json",
+                include_str!("./connectors/grpc/package.json"),
+            ),
+            (
+                "tsconfig.json",
+                include_str!("./connectors/grpc/tsconfig.json"),
+            ),
+            (
+                "supergraph.yaml",
+                include_str!("./connectors/grpc/supergraph.yaml"),
+            ),
+            (
+                "schema.graphql",
+                include_str!("./connectors/grpc/schema.graphql"),
+            ),
+            (
+                "proto/products.proto",
+                include_str!("./connectors/grpc/proto/products.proto"),
+            ),
+            (
+                "src/index.ts",
+                include_str!("./connectors/grpc/src/index.ts"),
+            ),
+            (
+                "src/resolvers.ts",
+                include_str!("./connectors/grpc/src/resolvers.ts"),
+            ),
+            (
+                "src/datasources/products-client.ts",
+                include_str!("./connectors/grpc/src/datasources/products-client.ts"),
+            ),
+        ],
+    },
+];
+
+/// The connector template with this ID, if there is one
+pub(crate) fn get_connector_template(template_id: &str) -> Option<&'static ConnectorTemplate> {
+    CONNECTOR_TEMPLATES
+        .iter()
+        .find(|template| template.id == template_id)
+}
+
+/// The connector templates for `language`, or for every language, as `rover template list`
+/// lists them
+pub(crate) fn list_connector_templates(
+    language: Option<ProjectLanguage>,
+) -> Vec<ListTemplatesForLanguageTemplates> {
+    let language: Option<Language> = language.map(Into::into);
+    CONNECTOR_TEMPLATES
+        .iter()
+        .filter(|template| language.as_ref().map_or(true, |l| l == &template.language))
+        .map(|template| ListTemplatesForLanguageTemplates {
+            id: template.id.to_string(),
+            name: template.name.to_string(),
+            description: template.description.to_string(),
+            repo_url: Url::parse(DOCS_URL).expect("the connector templates' docs URL is valid"),
+            language: template.language.clone(),
+        })
+        .collect()
+}
+
+impl ConnectorTemplate {
+    /// Writes the template's files to `path`, which must already exist.
+    pub(crate) fn write(&self, path: &Utf8Path, subgraph_name: &str, port: u16) -> RoverResult<()> {
+        let name = path.file_name().unwrap_or(self.id);
+        for (file, contents) in self.files {
+            let file_path = path.join(file);
+            if let Some(parent) = file_path.parent() {
+                Fs::create_dir_all(parent)?;
+            }
+            let contents = contents
+                .replace("{{name}}", name)
+                .replace("{{subgraph_name}}", subgraph_name)
+                .replace("{{port}}", &port.to_string());
+            Fs::write_file(&file_path, contents)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use rover_std::Fs;
+
+    use super::{get_connector_template, list_connector_templates};
+    use crate::options::ProjectLanguage;
+
+    #[test]
+    fn it_lists_connector_templates_by_language() {
+        assert_eq!(list_connector_templates(None).len(), 2);
+        assert_eq!(
+            list_connector_templates(Some(ProjectLanguage::Typescript)).len(),
+            2
+        );
+        assert!(list_connector_templates(Some(ProjectLanguage::Go)).is_empty());
+    }
+
+    #[test]
+    fn it_writes_a_connector_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(temp_dir.path().join("products-subgraph")).unwrap();
+        Fs::create_dir_all(&path).unwrap();
+        get_connector_template("subgraph-typescript-grpc-connector")
+            .unwrap()
+            .write(&path, "catalog", 4005)
+            .unwrap();
+        let package_json = Fs::read_file(path.join("package.json")).unwrap();
+        assert!(package_json.contains(r#""name": "products-subgraph""#));
+        let supergraph_config = Fs::read_file(path.join("supergraph.yaml")).unwrap();
+        assert!(supergraph_config.contains("  catalog:\n    routing_url: http://localhost:4005\n"));
+        let index = Fs::read_file(path.join("src/index.ts")).unwrap();
+        assert!(index.contains("process.env.PORT ?? 4005"));
+        assert!(path.join("proto/products.proto").exists());
+        assert!(path.join("src/datasources/products-client.ts").exists());
+    }
+}
@@ -0,0 +1,48 @@
+import { resolve } from "path";
+import DataLoader from "dataloader";
+import * as grpc from "@grpc/grpc-js";
+import * as protoLoader from "@grpc/proto-loader";
+
+/** The shape of a product in the gRPC service's responses. */
+export interface ProductMessage {
+  id: string;
+  name: string;
+  price: number;
+}
+
+const packageDefinition = protoLoader.loadSync(
+  resolve(__dirname, "..", "..", "proto", "products.proto"),
+  { keepCase: true, defaults: true },
+);
+const proto = grpc.loadPackageDefinition(packageDefinition) as any;
+
+// one connection is shared by every request
+const service = new proto.products.ProductService(
+  process.env.PRODUCTS_GRPC_ADDRESS ?? "localhost:50051",
+  grpc.credentials.createInsecure(),
+);
+
+function batchGetProducts(ids: readonly string[]): Promise<ProductMessage[]> {
+  return new Promise((resolve, reject) => {
+    service.BatchGetProducts({ ids }, (error: grpc.ServiceError | null, response: any) =>
+      error ? reject(error) : resolve(response.products),
+    );
+  });
+}
+
+/**
+ * Fetches products from the gRPC service at `PRODUCTS_GRPC_ADDRESS`. A new instance is created
+ * for each request, so every product looked up while resolving one operation, including entity
+ * references from other subgraphs, is fetched with a single `BatchGetProducts` call.
+ */
+export class ProductsClient {
+  private loader = new DataLoader<string, ProductMessage | null>(async (ids) => {
+    const products = await batchGetProducts(ids);
+    const byId = new Map(products.map((product) => [product.id, product]));
+    return ids.map((id) => byId.get(id) ?? null);
+  });
+
+  getProduct(id: string): Promise<ProductMessage | null> {
+    return this.loader.load(id);
+  }
+}
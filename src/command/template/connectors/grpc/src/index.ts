@@ -0,0 +1,28 @@
+import { readFileSync } from "fs";
+import { resolve } from "path";
+import { ApolloServer } from "@apollo/server";
+import { startStandaloneServer } from "@apollo/server/standalone";
+import { buildSubgraphSchema } from "@apollo/subgraph";
+import gql from "graphql-tag";
+
+import { ProductsClient } from "./datasources/products-client";
+import { Context, resolvers } from "./resolvers";
+
+const typeDefs = gql(readFileSync(resolve(__dirname, "..", "schema.graphql"), "utf-8"));
+
+async function main() {
+  const server = new ApolloServer<Context>({
+    schema: buildSubgraphSchema({ typeDefs, resolvers }),
+  });
+  const { url } = await startStandaloneServer(server, {
+    listen: { port: Number(process.env.PORT ?? {{port}}) },
+    context: async () => ({
+      dataSources: {
+        productsClient: new ProductsClient(),
+      },
+    }),
+  });
+  console.log(`🚀 Subgraph ready at ${url}`);
+}
+
+main();
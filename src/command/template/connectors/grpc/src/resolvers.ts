@@ -0,0 +1,19 @@
+import { ProductsClient } from "./datasources/products-client";
+
+export interface Context {
+  dataSources: {
+    productsClient: ProductsClient;
+  };
+}
+
+export const resolvers = {
+  Query: {
+    product: (_: unknown, { id }: { id: string }, { dataSources }: Context) =>
+      dataSources.productsClient.getProduct(id),
+  },
+  Product: {
+    // the router calls this with the `@key` fields when another subgraph references a product
+    __resolveReference: ({ id }: { id: string }, { dataSources }: Context) =>
+      dataSources.productsClient.getProduct(id),
+  },
+};
@@ -0,0 +1,31 @@
+import { RESTDataSource } from "@apollo/datasource-rest";
+
+/** The shape of a product in the REST API's responses. */
+export interface ProductResponse {
+  id: string;
+  name: string;
+  price?: number;
+}
+
+/**
+ * Fetches products from the REST API at `PRODUCTS_API_URL`. A new instance is created for each
+ * request, so identical GETs made while resolving one operation are only sent once.
+ */
+export class ProductsAPI extends RESTDataSource {
+  override baseURL = process.env.PRODUCTS_API_URL ?? "http://localhost:8080/";
+
+  async getProduct(id: string): Promise<ProductResponse | null> {
+    try {
+      return await this.get<ProductResponse>(`products/${encodeURIComponent(id)}`);
+    } catch (error: any) {
+      if (error?.extensions?.response?.status === 404) {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async getProducts(): Promise<ProductResponse[]> {
+    return this.get<ProductResponse[]>("products");
+  }
+}
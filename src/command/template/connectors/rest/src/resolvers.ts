@@ -0,0 +1,21 @@
+import { ProductsAPI } from "./datasources/products-api";
+
+export interface Context {
+  dataSources: {
+    productsAPI: ProductsAPI;
+  };
+}
+
+export const resolvers = {
+  Query: {
+    product: (_: unknown, { id }: { id: string }, { dataSources }: Context) =>
+      dataSources.productsAPI.getProduct(id),
+    products: (_: unknown, __: unknown, { dataSources }: Context) =>
+      dataSources.productsAPI.getProducts(),
+  },
+  Product: {
+    // the router calls this with the `@key` fields when another subgraph references a product
+    __resolveReference: ({ id }: { id: string }, { dataSources }: Context) =>
+      dataSources.productsAPI.getProduct(id),
+  },
+};
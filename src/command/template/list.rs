@@ -2,8 +2,10 @@ use clap::Parser;
 use serde::Serialize;
 
 use crate::options::TemplateOpt;
+use crate::utils::client::StudioClientConfig;
 use crate::{RoverOutput, RoverResult};
 
+use super::connectors::list_connector_templates;
 use super::templates::list_templates;
 
 #[derive(Clone, Debug, Parser, Serialize)]
@@ -13,8 +15,12 @@ pub struct List {
 }
 
 impl List {
-    pub fn run(&self) -> RoverResult<RoverOutput> {
-        let templates = list_templates(self.options.language.clone())?;
+    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+        let mut templates = list_templates(
+            &client_config.get_reqwest_client()?,
+            self.options.language.clone(),
+        )?;
+        templates.extend(list_connector_templates(self.options.language.clone()));
         Ok(RoverOutput::TemplateList(templates))
     }
 }
@@ -1,3 +1,4 @@
+mod connectors;
 pub(crate) mod custom_scalars;
 mod list;
 pub(crate) mod queries;
@@ -7,6 +8,7 @@ mod r#use;
 pub use list::List;
 pub use r#use::Use;
 
+use camino::Utf8PathBuf;
 use clap::Parser;
 use serde::Serialize;
 
@@ -29,10 +31,14 @@ enum Command {
 }
 
 impl Template {
-    pub(crate) fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+    pub(crate) fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
         match &self.command {
-            Command::Use(use_template) => use_template.run(client_config),
-            Command::List(list) => list.run(),
+            Command::Use(use_template) => use_template.run(override_install_path, client_config),
+            Command::List(list) => list.run(client_config),
         }
     }
 }
@@ -18,10 +18,13 @@ use super::queries::{
     list_templates_for_language::ListTemplatesForLanguageTemplates, *,
 };
 
-fn request<Body: Serialize, Data: DeserializeOwned>(body: &Body) -> RoverResult<Data> {
+fn request<Body: Serialize, Data: DeserializeOwned>(
+    client: &Client,
+    body: &Body,
+) -> RoverResult<Data> {
     let uri = env::var("APOLLO_TEMPLATES_API")
         .unwrap_or_else(|_| "https://rover.apollo.dev/templates".to_string());
-    let resp = Client::new()
+    let resp = client
         .post(uri)
         .json(body)
         .send()
@@ -35,34 +38,39 @@ fn request<Body: Serialize, Data: DeserializeOwned>(body: &Body) -> RoverResult<
 }
 
 /// Get a template by ID
-pub fn get_template(template_id: &str) -> RoverResult<Option<GetTemplateByIdTemplate>> {
+pub fn get_template(
+    client: &Client,
+    template_id: &str,
+) -> RoverResult<Option<GetTemplateByIdTemplate>> {
     use super::queries::get_template_by_id::*;
     let query = GetTemplateById::build_query(Variables {
         id: template_id.to_string(),
     });
-    let resp: ResponseData = request(&query)?;
+    let resp: ResponseData = request(client, &query)?;
     Ok(resp.template)
 }
 
 pub fn get_templates_for_language(
+    client: &Client,
     language: ProjectLanguage,
 ) -> RoverResult<Vec<GetTemplatesForLanguageTemplates>> {
     use super::queries::get_templates_for_language::*;
     let query = GetTemplatesForLanguage::build_query(Variables {
         language: Some(language.into()),
     });
-    let resp: ResponseData = request(&query)?;
+    let resp: ResponseData = request(client, &query)?;
     error_if_empty(resp.templates)
 }
 
 pub fn list_templates(
+    client: &Client,
     language: Option<ProjectLanguage>,
 ) -> RoverResult<Vec<ListTemplatesForLanguageTemplates>> {
     use super::queries::list_templates_for_language::*;
     let query = ListTemplatesForLanguage::build_query(Variables {
         language: language.map(Into::into),
     });
-    let resp: ResponseData = request(&query)?;
+    let resp: ResponseData = request(client, &query)?;
     error_if_empty(resp.templates)
 }
 
@@ -4,16 +4,22 @@ use std::{
 };
 
 use anyhow::{anyhow, Context};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::{error::ErrorKind as ClapErrorKind, CommandFactory, Parser};
 use dialoguer::Input;
+#[cfg(feature = "composition-js")]
+use rover_std::Emoji;
+use rover_std::Style;
 use serde::Serialize;
 
 use crate::cli::Rover;
+#[cfg(feature = "composition-js")]
+use crate::command::dev::sessions::Attach;
 use crate::options::{extract_tarball, TemplateOpt};
 use crate::utils::client::StudioClientConfig;
 use crate::{RoverError, RoverErrorSuggestion, RoverOutput, RoverResult};
 
+use super::connectors::{get_connector_template, DEFAULT_PORT, DEFAULT_SUBGRAPH_NAME};
 use super::templates::{get_template, get_templates_for_language, selection_prompt};
 
 #[derive(Clone, Debug, Parser, Serialize)]
@@ -26,6 +32,23 @@ pub struct Use {
     #[arg(short = 't', long = "template")]
     pub template: Option<String>,
 
+    /// The name of the subgraph, for the template's `supergraph.yaml`. Only connector templates
+    /// can fill this in. Defaults to `products`.
+    #[arg(long = "name")]
+    pub subgraph_name: Option<String>,
+
+    /// The port the subgraph listens on. Only connector templates can fill this in.
+    /// Defaults to 4001.
+    #[arg(long = "port")]
+    pub port: Option<u16>,
+
+    /// Add the new subgraph to the running `rover dev` session with this router port, or full
+    /// address, like `rover dev attach` does. Only connector templates can be attached, since
+    /// their schema is in a known place.
+    #[cfg(feature = "composition-js")]
+    #[arg(long = "attach", value_name = "SESSION")]
+    pub attach: Option<String>,
+
     /// The relative or absolute path to create the template directory.
     ///
     /// If omitted, the template will be extracted to a child directory
@@ -34,11 +57,61 @@ pub struct Use {
 }
 
 impl Use {
-    pub fn run(&self, client_config: StudioClientConfig) -> RoverResult<RoverOutput> {
+    pub fn run(
+        &self,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<RoverOutput> {
+        // connector templates ship with Rover, so they're written rather than downloaded
+        if let Some(template) = self.template.as_deref().and_then(get_connector_template) {
+            let path = self.get_or_prompt_path()?;
+            let subgraph_name = self
+                .subgraph_name
+                .as_deref()
+                .unwrap_or(DEFAULT_SUBGRAPH_NAME);
+            let port = self.port.unwrap_or(DEFAULT_PORT);
+            template.write(&path, subgraph_name, port)?;
+            #[cfg(feature = "composition-js")]
+            if let Some(session) = &self.attach {
+                self.attach(
+                    session,
+                    &path,
+                    subgraph_name,
+                    port,
+                    override_install_path,
+                    client_config,
+                )?;
+            }
+            #[cfg(not(feature = "composition-js"))]
+            let _ = override_install_path;
+            return Ok(RoverOutput::TemplateUseSuccess {
+                template_id: template.id.to_string(),
+                path,
+            });
+        }
+
+        #[cfg(feature = "composition-js")]
+        if self.attach.is_some() {
+            let mut err = RoverError::new(anyhow!(
+                "Only connector templates can be attached to a `rover dev` session."
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Run `rover dev attach` with the subgraph's name, URL, and schema once it's running."
+                    .to_string(),
+            ));
+            return Err(err);
+        }
+        if self.subgraph_name.is_some() || self.port.is_some() {
+            eprintln!(
+                "{} `--name` and `--port` are only filled in for connector templates. Set them in the downloaded template's files instead.",
+                Style::WarningPrefix.paint("WARN:")
+            );
+        }
+
         // find the template to extract
         let (template_id, download_url) = if let Some(template_id) = &self.template {
             // if they specify an ID, get it
-            let result = get_template(template_id)?;
+            let result = get_template(&client_config.get_reqwest_client()?, template_id)?;
             if let Some(result) = result {
                 (template_id.clone(), result.download_url)
             } else {
@@ -51,7 +124,8 @@ impl Use {
         } else {
             // otherwise, ask them what language they want to use
             let project_language = self.options.get_or_prompt_language()?;
-            let templates = get_templates_for_language(project_language)?;
+            let templates =
+                get_templates_for_language(&client_config.get_reqwest_client()?, project_language)?;
             let template = selection_prompt(templates)?;
             (template.id, template.download_url)
         };
@@ -65,6 +139,42 @@ impl Use {
         Ok(RoverOutput::TemplateUseSuccess { template_id, path })
     }
 
+    /// Adds the written subgraph to a running `rover dev` session, and keeps watching its schema
+    /// until this process stops, like `rover dev attach`.
+    #[cfg(feature = "composition-js")]
+    fn attach(
+        &self,
+        session: &str,
+        path: &Utf8Path,
+        subgraph_name: &str,
+        port: u16,
+        override_install_path: Option<Utf8PathBuf>,
+        client_config: StudioClientConfig,
+    ) -> RoverResult<()> {
+        let schema = path.join("schema.graphql");
+        let url = format!("http://localhost:{}", port);
+        eprintln!(
+            "{}attaching the {} subgraph to the session on {}. Start it with {} in another terminal.",
+            Emoji::Action,
+            Style::Link.paint(subgraph_name),
+            Style::Link.paint(session),
+            Style::Command.paint(format!("cd {} && npm install && npm start", path))
+        );
+        let attach = Attach::try_parse_from([
+            "attach",
+            session,
+            "--name",
+            subgraph_name,
+            "--url",
+            &url,
+            "--schema",
+            schema.as_str(),
+        ])
+        .map_err(|e| anyhow!("could not attach the subgraph: {}", e))?;
+        attach.run(override_install_path, client_config)?;
+        Ok(())
+    }
+
     pub(crate) fn get_or_prompt_path(&self) -> RoverResult<Utf8PathBuf> {
         let path: Utf8PathBuf = if let Some(path) = &self.path {
             Ok::<Utf8PathBuf, RoverError>(path.clone())
@@ -254,4 +254,108 @@ impl RoverErrorCode {
             "Explanation not available".to_string()
         }
     }
+
+    /// Finds the error codes whose explanations mention every word in `query`, best matches
+    /// first. Words match partially, like `satisfiab`, and tolerate a single typo.
+    pub fn search(query: &str) -> Vec<ErrorCodeMatch> {
+        let words = tokenize(query);
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(usize, ErrorCodeMatch)> = RoverErrorCode::explanations()
+            .into_iter()
+            .filter_map(|(code, explanation)| {
+                let summary = summarize(&explanation);
+                let summary_tokens = tokenize(&summary);
+                let tokens = tokenize(&explanation);
+                let mut score = 0;
+                for word in &words {
+                    // words in the summary count for more than words further down
+                    score += if summary_tokens.iter().any(|token| token.contains(word)) {
+                        3
+                    } else if tokens.iter().any(|token| token.contains(word)) {
+                        2
+                    } else if tokens.iter().any(|token| is_typo_of(word, token)) {
+                        1
+                    } else {
+                        return None;
+                    };
+                }
+                Some((score, ErrorCodeMatch { code, summary }))
+            })
+            .collect();
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score.cmp(a_score).then_with(|| a.code.cmp(&b.code))
+        });
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+}
+
+/// An error code whose explanation matched a search
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ErrorCodeMatch {
+    pub code: RoverErrorCode,
+    /// The first paragraph of the code's explanation
+    pub summary: String,
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+fn summarize(explanation: &str) -> String {
+    explanation
+        .trim()
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `word` is `token` with one character added, removed, or changed.
+/// Short words are too likely to collide with unrelated ones, so they must match exactly.
+fn is_typo_of(word: &str, token: &str) -> bool {
+    let (word, token): (Vec<char>, Vec<char>) = (word.chars().collect(), token.chars().collect());
+    if word.len() < 5 || word.len().abs_diff(token.len()) > 1 {
+        return false;
+    }
+    let prefix = word.iter().zip(&token).take_while(|(a, b)| a == b).count();
+    let suffix = word[prefix..]
+        .iter()
+        .rev()
+        .zip(token[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    word.len().max(token.len()) - prefix - suffix <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_typo_of, RoverErrorCode};
+
+    #[test]
+    fn it_finds_codes_by_keyword() {
+        let matches = RoverErrorCode::search("satisfiab");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].code, RoverErrorCode::E027);
+
+        let matches = RoverErrorCode::search("COMPOSE");
+        assert!(matches.iter().any(|m| m.code == RoverErrorCode::E029));
+
+        assert!(RoverErrorCode::search("no explanation mentions this").is_empty());
+    }
+
+    #[test]
+    fn it_tolerates_a_typo() {
+        assert!(is_typo_of("subgrph", "subgraph"));
+        assert!(is_typo_of("compse", "compose"));
+        assert!(is_typo_of("composr", "compose"));
+        assert!(!is_typo_of("grph", "graph"));
+        assert!(!is_typo_of("subgrahp", "subgraph"));
+    }
 }
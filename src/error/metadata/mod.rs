@@ -1,7 +1,7 @@
 mod code;
 mod suggestion;
 
-pub use code::RoverErrorCode;
+pub use code::{ErrorCodeMatch, RoverErrorCode};
 pub use suggestion::RoverErrorSuggestion;
 
 use houston::HoustonProblem;
@@ -222,6 +222,13 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                     Some(RoverErrorCode::E010),
                 ),
                 RoverClientError::GraphQl { .. } => (None, None),
+                RoverClientError::TransientGraphQl { .. } => (
+                    Some(RoverErrorSuggestion::Adhoc(
+                        "This error is likely temporary. Wait a few moments and try again."
+                            .to_string(),
+                    )),
+                    None,
+                ),
                 RoverClientError::IntrospectionError { .. } => (None, Some(RoverErrorCode::E011)),
                 RoverClientError::ClientError { .. } => (None, Some(RoverErrorCode::E012)),
                 RoverClientError::InvalidKey => {
@@ -274,6 +281,16 @@ impl From<&mut anyhow::Error> for RoverErrorMetadata {
                     Some(RoverErrorSuggestion::IncreaseChecksTimeout { url: url.clone() }),
                     None,
                 ),
+                RoverClientError::LaunchFailed { launch_url, .. } => (
+                    launch_url
+                        .clone()
+                        .map(|url| RoverErrorSuggestion::ViewFailedLaunch { url }),
+                    None,
+                ),
+                RoverClientError::LaunchTimeoutError { url } => (
+                    Some(RoverErrorSuggestion::IncreaseLaunchTimeout { url: url.clone() }),
+                    None,
+                ),
                 RoverClientError::UnknownCheckWorkflowStatus => {
                     (Some(RoverErrorSuggestion::SubmitIssue), None)
                 }
@@ -62,6 +62,12 @@ pub enum RoverErrorSuggestion {
     IncreaseChecksTimeout {
         url: Option<String>,
     },
+    IncreaseLaunchTimeout {
+        url: Option<String>,
+    },
+    ViewFailedLaunch {
+        url: String,
+    },
     FixChecksInput {
         graph_ref: GraphRef,
     },
@@ -84,6 +90,7 @@ pub enum RoverErrorSuggestion {
         graph_ref: String,
     },
     AllowInvalidRoutingUrlOrSpecifyValidUrl,
+    AcknowledgePlaceholderRoutingUrl,
     ContactApolloAccountManager,
     TryAgainLater,
 }
@@ -235,8 +242,10 @@ FixOperationsInSchema { graph_ref } => format!("The changes in the schema you pr
 FixDownstreamCheckFailure { target_url } => format!("The changes in the schema you proposed cause checks to fail for blocking downstream variants. See {} to view the failure reasons for these downstream checks.", Style::Link.paint(target_url)),
 FixOtherCheckTaskFailure { target_url } => format!("See {} to view the failure reason for the check.", Style::Link.paint(target_url)),
 FixLintFailure => "The schema you submitted contains lint violations. Please address the violations and resubmit the schema.".to_string(),
-IncreaseClientTimeout => "You can try increasing the timeout value by passing a higher value to the --client-timeout option.".to_string(),
+IncreaseClientTimeout => "You can try increasing the timeout value by passing a higher value to the --client-timeout option, or by saving one with `rover config client --timeout`.".to_string(),
 IncreaseChecksTimeout {url} => format!("You can try increasing the timeout value by setting APOLLO_CHECKS_TIMEOUT_SECONDS to a higher value in your env. The default value is 300 seconds. You can also view the live check progress by visiting {}.", Style::Link.paint(url.clone().unwrap_or_else(|| "https://studio.apollographql.com".to_string()))),
+IncreaseLaunchTimeout {url} => format!("The launch may still finish. You can wait longer by passing a higher `--launch-timeout`, or follow the launch by visiting {}.", Style::Link.paint(url.clone().unwrap_or_else(|| "https://studio.apollographql.com".to_string()))),
+ViewFailedLaunch {url} => format!("See why the launch failed by visiting {}.", Style::Link.paint(url)),
 FixChecksInput { graph_ref } => format!("Graph {} has no published schema or is not a composition variant. Please publish a schema or use a different variant.", Style::Link.paint(graph_ref.to_string())),
 UpgradePlan => "Rover has likely reached rate limits while running graph or subgraph checks. Please try again later or contact your graph admin about upgrading your billing plan.".to_string(),
             ProvideRoutingUrl { subgraph_name, graph_ref } => {
@@ -256,6 +265,7 @@ UpgradePlan => "Rover has likely reached rate limits while running graph or subg
                 format!("Try publishing the subgraph with a routing URL like so `rover subgraph publish {graph_ref} --name {subgraph_name} --routing-url <url>`")
             },
             AllowInvalidRoutingUrlOrSpecifyValidUrl => format!("Try publishing the subgraph with a valid routing URL. If you are sure you want to publish an invalid routing URL, re-run this command with the {} option.", Style::Command.paint("`--allow-invalid-routing-url`")),
+            AcknowledgePlaceholderRoutingUrl => format!("If this subgraph is intentionally not routable, re-run this command with {}.", Style::Command.paint("`--routing-url none --skip-url-check`")),
             ContactApolloAccountManager => {"Discuss your requirements with your Apollo point of contact.".to_string()}
             TryAgainLater => {"Please try again later.".to_string()}
         };
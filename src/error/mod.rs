@@ -1,6 +1,6 @@
 mod metadata;
 
-pub use metadata::{RoverErrorCode, RoverErrorMetadata, RoverErrorSuggestion};
+pub use metadata::{ErrorCodeMatch, RoverErrorCode, RoverErrorMetadata, RoverErrorSuggestion};
 
 pub type RoverResult<T> = std::result::Result<T, RoverError>;
 
@@ -17,6 +17,9 @@ use std::fmt::{self, Debug, Display};
 
 use apollo_federation_types::build::BuildErrors;
 
+use crate::command::fleet::run::FleetFailures;
+use crate::command::schema::diff::SchemaDiffFailures;
+use crate::command::subgraph::fetch::SubgraphFetchFailures;
 use crate::options::JsonVersion;
 
 /// A specialized `Error` type for Rover that wraps `anyhow`
@@ -29,6 +32,9 @@ pub struct RoverError {
 
     #[serde(flatten)]
     metadata: RoverErrorMetadata,
+
+    #[serde(skip)]
+    exit_code: Option<i32>,
 }
 
 fn serialize_anyhow<S>(error: &anyhow::Error, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -63,7 +69,11 @@ impl RoverError {
         let mut error = error.into();
         let metadata = RoverErrorMetadata::from(error.borrow_mut());
 
-        Self { error, metadata }
+        Self {
+            error,
+            metadata,
+            exit_code: None,
+        }
     }
 
     pub fn set_suggestion(&mut self, suggestion: RoverErrorSuggestion) {
@@ -87,7 +97,39 @@ impl RoverError {
         self.metadata.code.clone()
     }
 
+    /// Makes Rover exit with `exit_code` instead of 1, for commands whose callers need to tell
+    /// kinds of failure apart.
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code.unwrap_or(1)
+    }
+
+    /// The composition errors that caused this error, if there were any
+    pub(crate) fn build_errors(&self) -> Option<&BuildErrors> {
+        self.error
+            .downcast_ref::<RoverClientError>()?
+            .source()?
+            .downcast_ref::<BuildErrors>()
+    }
+
     pub fn print(&self) -> RoverResult<()> {
+        if let Some(FleetFailures { report }) = self.error.downcast_ref::<FleetFailures>() {
+            stdoutln!("{}", report.get_table())?;
+        }
+        if let Some(SubgraphFetchFailures { report }) =
+            self.error.downcast_ref::<SubgraphFetchFailures>()
+        {
+            stdoutln!("{}", report.get_table())?;
+        }
+        if let Some(SchemaDiffFailures { diff, .. }) =
+            self.error.downcast_ref::<SchemaDiffFailures>()
+        {
+            stdoutln!("{}", diff.get_output())?;
+        }
         match self.error.downcast_ref::<RoverClientError>() {
             Some(RoverClientError::CheckWorkflowFailure {
                 graph_ref: _,
@@ -104,6 +146,19 @@ impl RoverError {
     }
 
     pub(crate) fn get_internal_data_json(&self) -> Value {
+        if let Some(FleetFailures { report }) = self.error.downcast_ref::<FleetFailures>() {
+            return json!(report);
+        }
+        if let Some(SubgraphFetchFailures { report }) =
+            self.error.downcast_ref::<SubgraphFetchFailures>()
+        {
+            return json!(report);
+        }
+        if let Some(SchemaDiffFailures { diff, .. }) =
+            self.error.downcast_ref::<SchemaDiffFailures>()
+        {
+            return json!(diff);
+        }
         return match self.error.downcast_ref::<RoverClientError>() {
             Some(RoverClientError::CheckWorkflowFailure {
                 graph_ref: _,
@@ -1,5 +1,6 @@
 use anyhow::anyhow;
-use clap::Parser;
+use camino::Utf8PathBuf;
+use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 use rover_client::shared::ValidationPeriod;
@@ -26,6 +27,33 @@ pub struct CheckConfigOpts {
     /// If the check should be run asynchronously and exit without waiting for check results
     #[arg(long)]
     pub background: bool,
+
+    /// Wait for the check to complete, print a table of each task's result, and exit with a
+    /// code for how it ended: 0 if every check passed, 2 if a check failed, 3 if the checks
+    /// didn't finish in time, and 1 if they couldn't be run
+    #[arg(long, conflicts_with = "background")]
+    pub watch_until_complete: bool,
+
+    /// Also report the check's failures as CI annotations that point at lines of the `--schema`
+    /// file: `github` prints GitHub Actions workflow commands, and `gitlab-codequality` writes a
+    /// GitLab Code Quality report
+    #[arg(long, value_enum, conflicts_with = "background")]
+    pub annotations: Option<AnnotationFormat>,
+
+    /// Where to write the annotations. GitHub Actions annotations are printed to stdout, and
+    /// GitLab Code Quality reports are written to `gl-code-quality-report.json`, by default.
+    #[arg(long, requires = "annotations")]
+    #[serde(skip_serializing)]
+    pub annotations_file: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnnotationFormat {
+    /// GitHub Actions `::error` and `::warning` workflow commands
+    Github,
+    /// A GitLab Code Quality report
+    GitlabCodequality,
 }
 
 fn parse_query_count_threshold(threshold: &str) -> Result<i64, io::Error> {
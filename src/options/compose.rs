@@ -1,7 +1,7 @@
 use super::ProfileOpt;
 use crate::options::LicenseAccepter;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use serde::Serialize;
 
 #[cfg(feature = "composition-js")]
@@ -20,6 +20,24 @@ pub struct PluginOpts {
     /// Passing this flag will attempt to use the latest compatible version of a plugin already installed on this machine.
     #[arg(long = "skip-update")]
     pub skip_update: bool,
+
+    /// Refuse to use a plugin version that isn't pinned exactly.
+    ///
+    /// Passing this flag will fail instead of resolving version ranges like `~2.3` or release channels like `latest-2`.
+    #[arg(long = "locked")]
+    pub locked: bool,
+
+    /// The release channel to use plugins from.
+    ///
+    /// Passing `next` opts in to the newest pre-release builds, so you can try upcoming federation and router features before they're released.
+    #[arg(long = "channel", value_enum, default_value_t = PluginChannel::Stable)]
+    pub channel: PluginChannel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+pub enum PluginChannel {
+    Stable,
+    Next,
 }
 
 #[cfg(feature = "composition-js")]
@@ -0,0 +1,45 @@
+use std::fs::OpenOptions;
+
+use anyhow::Context;
+use camino::Utf8PathBuf;
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+use crate::utils::events;
+use crate::RoverResult;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, ValueEnum)]
+pub enum EventFormat {
+    /// One JSON object per line
+    Ndjson,
+}
+
+#[derive(Debug, Parser, Serialize)]
+pub struct EventOpts {
+    /// Emit structured progress events, like plugin downloads and composition results, for tools
+    /// that wrap Rover. Requires `--events-file`
+    #[arg(long = "events", global = true, requires = "events_file")]
+    events: Option<EventFormat>,
+
+    /// The file or pipe to write progress events to, for example `/dev/fd/3`. Events are kept
+    /// apart from Rover's messages on stderr so tools don't have to separate them
+    #[arg(long = "events-file", global = true, requires = "events")]
+    events_file: Option<Utf8PathBuf>,
+}
+
+impl EventOpts {
+    /// Starts writing events to their destination, if they were requested.
+    pub fn enable(&self) -> RoverResult<()> {
+        let path = match (&self.events, &self.events_file) {
+            (Some(_), Some(path)) => path,
+            _ => return Ok(()),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("could not open {} to write events to", path))?;
+        events::enable(Box::new(file));
+        Ok(())
+    }
+}
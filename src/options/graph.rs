@@ -5,8 +5,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize, Parser)]
 pub struct GraphRefOpt {
     /// <NAME>@<VARIANT> of graph in Apollo Studio.
-    /// @<VARIANT> may be left off, defaulting to @current
-    #[arg(value_name = "GRAPH_REF")]
+    /// @<VARIANT> may be left off, defaulting to @current.
+    /// Defaults to the graph ref set with `rover config set graph-ref`
+    #[arg(value_name = "GRAPH_REF", env = "APOLLO_GRAPH_REF")]
     #[serde(skip_serializing)]
     pub graph_ref: GraphRef,
 }
@@ -1,5 +1,6 @@
 mod check;
 mod compose;
+mod events;
 mod graph;
 mod introspect;
 mod license;
@@ -13,6 +14,7 @@ mod template;
 
 pub(crate) use check::*;
 pub(crate) use compose::*;
+pub(crate) use events::*;
 pub(crate) use graph::*;
 pub(crate) use introspect::*;
 pub(crate) use license::*;
@@ -72,7 +72,7 @@ impl RoverPrinter for RoverOutput {
                         self.descriptor().unwrap_or("The output")
                     ));
                     let path_text = Style::Path.paint(&path);
-                    Fs::write_file(&path, result)?;
+                    Fs::write_file_atomic(&path, result)?;
                     stderrln!("{} {}", success_heading, path_text)?;
                 }
                 RoverOutputDestination::Stdout => {
@@ -103,7 +103,7 @@ impl RoverPrinter for RoverError {
                     RoverOutputDestination::File(file) => {
                         let success_heading = Style::Heading
                             .paint(format!("{}Error JSON was printed to", Emoji::Memo,));
-                        Fs::write_file(&file, json.to_string())?;
+                        Fs::write_file_atomic(&file, json.to_string())?;
                         stderrln!("{} {}", success_heading, file)?;
                     }
                     RoverOutputDestination::Stdout => json.print()?,
@@ -118,11 +118,13 @@ impl RoverPrinter for RoverError {
 
 #[derive(Debug, Parser, Serialize)]
 pub struct OutputOpts {
-    /// Specify Rover's format type
-    #[arg(long = "format", global = true)]
+    /// Specify Rover's format type.
+    /// Defaults to the format set with `rover config set format`, or plain
+    #[arg(long = "format", global = true, env = "APOLLO_ROVER_FORMAT")]
     format_kind: Option<RoverOutputFormatKind>,
 
-    /// Specify a file to write Rover's output to
+    /// Specify a file to write Rover's output to.
+    /// The file is replaced atomically, and missing parent directories are created.
     #[arg(long = "output", short = 'o', global = true)]
     output_file: Option<OutputOpt>,
 }
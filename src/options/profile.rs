@@ -10,6 +10,14 @@ pub struct ProfileOpt {
     pub profile_name: String,
 }
 
+impl Default for ProfileOpt {
+    fn default() -> Self {
+        Self {
+            profile_name: "default".to_string(),
+        }
+    }
+}
+
 impl Display for ProfileOpt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.profile_name)
@@ -1,3 +1,4 @@
+use camino::Utf8PathBuf;
 use clap::Parser;
 
 use crate::{utils::parsers::FileDescriptorType, RoverResult};
@@ -41,4 +42,12 @@ impl SchemaOpt {
             Err(e) => Err(e),
         }
     }
+
+    /// The path of the schema file, unless the schema is read from stdin.
+    pub(crate) fn file_path(&self) -> Option<Utf8PathBuf> {
+        match &self.schema {
+            FileDescriptorType::Stdin => None,
+            FileDescriptorType::File(file_path) => Some(file_path.clone()),
+        }
+    }
 }
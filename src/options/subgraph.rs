@@ -21,6 +21,7 @@ use rover_std::{Emoji, Fs, Style};
 
 #[cfg(feature = "composition-js")]
 use crate::cli::Rover;
+use crate::utils::parsers::parse_header;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
 pub struct SubgraphOpt {
@@ -65,10 +66,37 @@ pub struct OptionalSubgraphOpts {
     )]
     #[serde(skip_serializing)]
     pub subgraph_polling_interval: u64,
+
+    /// A header to send when introspecting the running subgraph, like an auth token or a `Host` override.
+    /// Values must be key:value pairs, and the flag can be passed more than once.
+    /// If a value has a space in it, use quotes around the pair, ex. --introspection-header "Authorization:Bearer token"
+    #[arg(
+        long = "introspection-header",
+        value_name = "KEY:VALUE",
+        value_parser = parse_header,
+        conflicts_with = "subgraph_schema_path"
+    )]
+    #[serde(skip_serializing)]
+    pub subgraph_introspection_headers: Option<Vec<(String, String)>>,
+
+    /// A URL that responds successfully once the running subgraph is ready to serve its schema (e.g., http://localhost:4000/health).
+    ///
+    /// `rover dev` waits for it to pass before adding the subgraph to the supergraph, for up to `--startup-timeout` seconds.
+    /// Without it, `rover dev` waits for the subgraph to respond to introspection instead.
+    #[arg(long = "health-check", value_name = "URL")]
+    #[serde(skip_serializing)]
+    pub subgraph_health_check: Option<url::Url>,
 }
 
 #[cfg(feature = "composition-js")]
 impl OptionalSubgraphOpts {
+    /// The subgraph's name and schema file, if both were passed as arguments.
+    pub fn schema_file(&self) -> Option<(String, Utf8PathBuf)> {
+        self.subgraph_name
+            .clone()
+            .zip(self.subgraph_schema_path.clone())
+    }
+
     pub fn prompt_for_name(&self) -> Result<String> {
         if let Some(name) = &self.subgraph_name {
             Ok(name.to_string())
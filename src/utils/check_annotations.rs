@@ -0,0 +1,334 @@
+//! Writes the findings of a check as annotations that CI systems show inline on a pull request's
+//! diff: GitHub Actions workflow commands, or a GitLab Code Quality report.
+//!
+//! GraphOS reports where lint violations are, but only names the types and fields that an
+//! operation check's changes are about, so those are found in the proposed schema by name.
+
+use std::collections::BTreeMap;
+
+use apollo_parser::{
+    ast::{self, AstNode},
+    Parser as SdlParser, SyntaxNode,
+};
+use calm_io::stdout;
+use camino::Utf8Path;
+use rover_client::shared::CheckWorkflowResponse;
+use rover_client::RoverClientError;
+use rover_std::{Fs, Style};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::options::AnnotationFormat;
+use crate::utils::check_history::{as_array, as_string};
+use crate::RoverResult;
+
+/// Where GitLab looks for a Code Quality report, unless the job's `artifacts` say otherwise
+pub(crate) const GITLAB_CODEQUALITY_FILE: &str = "gl-code-quality-report.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AnnotationLevel {
+    Error,
+    Warning,
+}
+
+/// A finding of a check, and the line of the proposed schema it's about, if it could be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckAnnotation {
+    pub(crate) level: AnnotationLevel,
+    /// The check task and the code of the finding, like "Operation check: FIELD_REMOVED"
+    pub(crate) title: String,
+    pub(crate) message: String,
+    pub(crate) line: Option<usize>,
+}
+
+/// Writes the annotations for a check in `format`, to `path` or, for GitHub, to stdout.
+/// `schema_path` is the file the proposed schema was read from, if it wasn't stdin. Annotations
+/// that can't be written are only warned about, so the check's results are still reported.
+pub(crate) fn write(
+    format: AnnotationFormat,
+    path: Option<&Utf8Path>,
+    result: &Result<CheckWorkflowResponse, RoverClientError>,
+    proposed_schema: &str,
+    schema_path: Option<&Utf8Path>,
+) {
+    let annotations = annotations(result, proposed_schema);
+    if let Err(e) = write_annotations(format, path, &annotations, schema_path) {
+        eprintln!(
+            "{} could not write the check's annotations: {}",
+            Style::WarningPrefix.paint("WARN:"),
+            e.message()
+        );
+    }
+}
+
+fn write_annotations(
+    format: AnnotationFormat,
+    path: Option<&Utf8Path>,
+    annotations: &[CheckAnnotation],
+    schema_path: Option<&Utf8Path>,
+) -> RoverResult<()> {
+    match format {
+        AnnotationFormat::Github => {
+            let commands = github_commands(annotations, schema_path);
+            match path {
+                Some(path) => Fs::write_file(path, commands)?,
+                None => stdout!("{}", commands)?,
+            }
+        }
+        AnnotationFormat::GitlabCodequality => {
+            let report = gitlab_codequality(annotations, schema_path);
+            let report = serde_json::to_string_pretty(&report)?;
+            Fs::write_file(
+                path.unwrap_or(Utf8Path::new(GITLAB_CODEQUALITY_FILE)),
+                report,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The failed changes, lint violations, and composition errors of a check.
+pub(crate) fn annotations(
+    result: &Result<CheckWorkflowResponse, RoverClientError>,
+    proposed_schema: &str,
+) -> Vec<CheckAnnotation> {
+    let check_response = match result {
+        Ok(check_response) => check_response,
+        Err(RoverClientError::CheckWorkflowFailure { check_response, .. }) => check_response,
+        Err(RoverClientError::SubgraphBuildErrors { source, .. }) => {
+            let build_errors = serde_json::to_value(source).unwrap_or_default();
+            return as_array(&build_errors["build_errors"])
+                .iter()
+                .map(|build_error| CheckAnnotation {
+                    level: AnnotationLevel::Error,
+                    title: format!("Composition: {}", as_string(&build_error["code"])),
+                    message: as_string(&build_error["message"]),
+                    line: as_array(&build_error["nodes"])
+                        .first()
+                        .and_then(|node| node["start"]["line"].as_u64())
+                        .map(|line| line as usize),
+                })
+                .collect();
+        }
+        Err(_) => return Vec::new(),
+    };
+
+    // some of the responses' fields are private, but they're all serialized
+    let response = serde_json::to_value(check_response).unwrap_or_default();
+    let lines = coordinate_lines(proposed_schema);
+    let mut annotations = Vec::new();
+    for change in as_array(&response["maybe_operations_response"]["changes"]) {
+        if as_string(&change["severity"]) != "FAIL" {
+            continue;
+        }
+        let description = as_string(&change["description"]);
+        annotations.push(CheckAnnotation {
+            level: AnnotationLevel::Error,
+            title: format!("Operation check: {}", as_string(&change["code"])),
+            line: change_line(&description, &lines),
+            message: description,
+        });
+    }
+    for diagnostic in as_array(&response["maybe_lint_response"]["diagnostics"]) {
+        let level = match as_string(&diagnostic["level"]).as_str() {
+            "ERROR" => AnnotationLevel::Error,
+            "WARNING" => AnnotationLevel::Warning,
+            _ => continue,
+        };
+        annotations.push(CheckAnnotation {
+            level,
+            title: format!("Linter: {}", as_string(&diagnostic["coordinate"])),
+            message: as_string(&diagnostic["message"]),
+            line: diagnostic["start_line"]
+                .as_u64()
+                .filter(|line| *line > 0)
+                .map(|line| line as usize),
+        });
+    }
+    annotations
+}
+
+/// GitHub Actions workflow commands, like `::error file=schema.graphql,line=3::...`, one per
+/// line.
+pub(crate) fn github_commands(
+    annotations: &[CheckAnnotation],
+    schema_path: Option<&Utf8Path>,
+) -> String {
+    let mut commands = String::new();
+    for annotation in annotations {
+        let command = match annotation.level {
+            AnnotationLevel::Error => "error",
+            AnnotationLevel::Warning => "warning",
+        };
+        let mut properties = Vec::new();
+        if let Some(schema_path) = schema_path {
+            properties.push(format!("file={}", escape_property(schema_path.as_str())));
+            if let Some(line) = annotation.line {
+                properties.push(format!("line={}", line));
+            }
+        }
+        properties.push(format!("title={}", escape_property(&annotation.title)));
+        commands.push_str(&format!(
+            "::{} {}::{}\n",
+            command,
+            properties.join(","),
+            escape_data(&annotation.message)
+        ));
+    }
+    commands
+}
+
+/// A GitLab Code Quality report. Each finding's fingerprint is a hash of what it says and
+/// where, so GitLab can tell which findings a merge request introduced or fixed.
+pub(crate) fn gitlab_codequality(
+    annotations: &[CheckAnnotation],
+    schema_path: Option<&Utf8Path>,
+) -> Value {
+    let path = schema_path.map_or("-", |path| path.as_str());
+    annotations
+        .iter()
+        .map(|annotation| {
+            let severity = match annotation.level {
+                AnnotationLevel::Error => "major",
+                AnnotationLevel::Warning => "minor",
+            };
+            let fingerprint = Sha256::digest(
+                format!("{}\n{}\n{}", path, annotation.title, annotation.message).as_bytes(),
+            );
+            json!({
+                "description": format!("{}: {}", annotation.title, annotation.message),
+                "check_name": annotation.title,
+                "fingerprint": format!("{:x}", fingerprint),
+                "severity": severity,
+                "location": {
+                    "path": path,
+                    "lines": { "begin": annotation.line.unwrap_or(1) }
+                }
+            })
+        })
+        .collect()
+}
+
+/// The line of each type, field, and enum value defined in `sdl`, by schema coordinate, like
+/// `Product` or `Product.upc`.
+fn coordinate_lines(sdl: &str) -> BTreeMap<String, usize> {
+    let document = SdlParser::new(sdl).parse().document();
+    let line = |node: &SyntaxNode| {
+        let start = usize::from(node.text_range().start()).min(sdl.len());
+        sdl[..start].matches('\n').count() + 1
+    };
+    let mut lines = BTreeMap::new();
+    for definition in document.definitions() {
+        let definition = definition.syntax();
+        let Some(type_name) = definition.children().find_map(ast::Name::cast) else {
+            continue;
+        };
+        lines
+            .entry(type_name.text().to_string())
+            .or_insert_with(|| line(type_name.syntax()));
+        let members = definition.descendants().filter(|node| {
+            ast::FieldDefinition::can_cast(node.kind())
+                || ast::EnumValueDefinition::can_cast(node.kind())
+                || (ast::InputValueDefinition::can_cast(node.kind())
+                    && node.parent().map_or(false, |parent| {
+                        ast::InputFieldsDefinition::can_cast(parent.kind())
+                    }))
+        });
+        for member in members {
+            if let Some(member_name) = member.descendants().find_map(ast::Name::cast) {
+                lines
+                    .entry(format!("{}.{}", type_name.text(), member_name.text()))
+                    .or_insert_with(|| line(member_name.syntax()));
+            }
+        }
+    }
+    lines
+}
+
+/// The line of the type or field a change's description names in backticks, like
+/// "type `Product`: field `upc` removed". Removed fields aren't in the proposed schema, so
+/// those fall back to their type.
+fn change_line(description: &str, lines: &BTreeMap<String, usize>) -> Option<usize> {
+    let names = description
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .collect::<Vec<_>>();
+    (1..=names.len().min(2))
+        .rev()
+        .find_map(|depth| lines.get(&names[..depth].join(".")).copied())
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+
+    use super::{
+        change_line, coordinate_lines, github_commands, gitlab_codequality, AnnotationLevel,
+        CheckAnnotation,
+    };
+
+    const SDL: &str = r#"type Query {
+  products: [Product]
+}
+
+"A product"
+type Product {
+  id: ID!
+  name: String
+}
+
+enum Color {
+  RED
+}
+"#;
+
+    #[test]
+    fn it_finds_the_lines_of_changes() {
+        let lines = coordinate_lines(SDL);
+        assert_eq!(lines.get("Product"), Some(&6));
+        assert_eq!(lines.get("Product.name"), Some(&8));
+        assert_eq!(lines.get("Color.RED"), Some(&12));
+        assert_eq!(
+            change_line("type `Product`: field `name` changed type", &lines),
+            Some(8)
+        );
+        // removed fields point at their type
+        assert_eq!(
+            change_line("type `Product`: field `upc` removed", &lines),
+            Some(6)
+        );
+        assert_eq!(change_line("type `Review` removed", &lines), None);
+    }
+
+    #[test]
+    fn it_writes_github_and_gitlab_annotations() {
+        let annotations = vec![CheckAnnotation {
+            level: AnnotationLevel::Error,
+            title: "Operation check: FIELD_REMOVED".to_string(),
+            message: "type `Product`: field `upc` removed".to_string(),
+            line: Some(6),
+        }];
+        let schema_path = Some(Utf8Path::new("products.graphql"));
+        assert_eq!(
+            github_commands(&annotations, schema_path),
+            "::error file=products.graphql,line=6,title=Operation check%3A FIELD_REMOVED::type `Product`: field `upc` removed\n"
+        );
+        let report = gitlab_codequality(&annotations, schema_path);
+        assert_eq!(report[0]["location"]["path"], "products.graphql");
+        assert_eq!(report[0]["location"]["lines"]["begin"], 6);
+        assert_eq!(report[0]["severity"], "major");
+    }
+}
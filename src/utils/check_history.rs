@@ -0,0 +1,277 @@
+//! Remembers the findings of the last check of each graph ref and subgraph, so that the next
+//! check can point out which findings are new and which were resolved since then.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+use apollo_federation_types::build::BuildErrors;
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_client::shared::{CheckWorkflowResponse, GraphRef};
+use rover_client::RoverClientError;
+use rover_std::{Emoji, Fs, Style};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::source_map::without_locations;
+
+/// Something a check reported, like a breaking change, a lint violation, or a composition error.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct CheckFinding {
+    pub(crate) task: String,
+    pub(crate) severity: String,
+    pub(crate) code: String,
+    pub(crate) message: String,
+}
+
+impl Display for CheckFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.task, self.severity)?;
+        if !self.code.is_empty() {
+            write!(f, " {}", self.code)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckRecord {
+    checked_at: String,
+    findings: BTreeSet<CheckFinding>,
+}
+
+/// How the findings of a check compare to the last check of the same graph ref and subgraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckDelta {
+    pub(crate) previous_checked_at: String,
+    pub(crate) new: Vec<CheckFinding>,
+    pub(crate) resolved: Vec<CheckFinding>,
+    pub(crate) unchanged: usize,
+}
+
+impl CheckDelta {
+    fn print(&self) {
+        eprintln!(
+            "{}compared to the last check on {}: {} new, {} resolved, {} unchanged",
+            Emoji::Memo,
+            self.previous_checked_at,
+            self.new.len(),
+            self.resolved.len(),
+            self.unchanged
+        );
+        for finding in &self.new {
+            eprintln!("  {} {}", Style::Failure.paint("+ new:"), finding);
+        }
+        for finding in &self.resolved {
+            eprintln!("  {} {}", Style::Success.paint("- resolved:"), finding);
+        }
+    }
+}
+
+/// The findings of the last check of a graph ref (and subgraph), stored in Rover's home directory.
+#[derive(Debug, Clone)]
+pub(crate) struct CheckHistory {
+    path: Utf8PathBuf,
+}
+
+impl CheckHistory {
+    pub(crate) fn new(home: &Utf8Path, graph_ref: &GraphRef, subgraph: Option<&str>) -> Self {
+        let file_name = match subgraph {
+            Some(subgraph) => format!("{}.json", sanitize(subgraph)),
+            // subgraph names can't start with an underscore, so this can't collide with one
+            None => "_graph.json".to_string(),
+        };
+        Self {
+            path: home
+                .join("checks")
+                .join(sanitize(&graph_ref.to_string()))
+                .join(file_name),
+        }
+    }
+
+    /// Compares the findings of a check to the last one, prints what changed, and
+    /// remembers them for next time. Checks that didn't complete aren't recorded.
+    pub(crate) fn record(&self, result: &Result<CheckWorkflowResponse, RoverClientError>) {
+        let findings = match result {
+            Ok(check_response) => response_findings(check_response),
+            Err(RoverClientError::CheckWorkflowFailure { check_response, .. }) => {
+                response_findings(check_response)
+            }
+            Err(RoverClientError::SubgraphBuildErrors { source, .. }) => {
+                composition_findings(source)
+            }
+            Err(_) => return,
+        };
+        if let Some(delta) = self.compare(findings) {
+            delta.print();
+        }
+    }
+
+    fn compare(&self, findings: BTreeSet<CheckFinding>) -> Option<CheckDelta> {
+        let previous = Fs::read_file(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CheckRecord>(&contents).ok());
+        let record = CheckRecord {
+            checked_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            findings,
+        };
+        let written = serde_json::to_string_pretty(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| {
+                Fs::create_dir_all(self.path.parent().unwrap_or(&self.path))?;
+                Fs::write_file(&self.path, contents)?;
+                Ok(())
+            });
+        if let Err(e) = written {
+            tracing::debug!("could not save the check result to {}: {}", self.path, e);
+        }
+
+        let previous = previous?;
+        Some(CheckDelta {
+            previous_checked_at: previous.checked_at,
+            new: record
+                .findings
+                .difference(&previous.findings)
+                .cloned()
+                .collect(),
+            resolved: previous
+                .findings
+                .difference(&record.findings)
+                .cloned()
+                .collect(),
+            unchanged: record.findings.intersection(&previous.findings).count(),
+        })
+    }
+}
+
+/// Replaces characters that aren't safe in file names, like the `@` in graph refs.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn response_findings(check_response: &CheckWorkflowResponse) -> BTreeSet<CheckFinding> {
+    // some of the responses' fields are private, but they're all serialized
+    let check_response = serde_json::to_value(check_response).unwrap_or_default();
+    let mut findings = BTreeSet::new();
+    for change in as_array(&check_response["maybe_operations_response"]["changes"]) {
+        findings.insert(CheckFinding {
+            task: "operations".to_string(),
+            severity: as_string(&change["severity"]),
+            code: as_string(&change["code"]),
+            message: as_string(&change["description"]),
+        });
+    }
+    for diagnostic in as_array(&check_response["maybe_lint_response"]["diagnostics"]) {
+        findings.insert(CheckFinding {
+            task: "lint".to_string(),
+            severity: as_string(&diagnostic["level"]),
+            code: as_string(&diagnostic["coordinate"]),
+            message: as_string(&diagnostic["message"]),
+        });
+    }
+    findings
+}
+
+fn composition_findings(build_errors: &BuildErrors) -> BTreeSet<CheckFinding> {
+    build_errors
+        .iter()
+        .map(|build_error| CheckFinding {
+            task: "composition".to_string(),
+            severity: "ERROR".to_string(),
+            code: build_error.get_code().unwrap_or_default(),
+            // locations shift whenever lines are added above them, which would make the
+            // same error look new
+            message: without_locations(&build_error.get_message().unwrap_or_default()).to_string(),
+        })
+        .collect()
+}
+
+pub(crate) fn as_array(value: &Value) -> Vec<Value> {
+    value.as_array().cloned().unwrap_or_default()
+}
+
+pub(crate) fn as_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    use apollo_federation_types::build::{BuildError, BuildErrors};
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use rover_client::shared::GraphRef;
+
+    use super::{composition_findings, CheckFinding, CheckHistory};
+
+    fn finding(message: &str) -> CheckFinding {
+        CheckFinding {
+            task: "lint".to_string(),
+            severity: "WARNING".to_string(),
+            code: "Query.products".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_compares_findings_to_the_last_check() {
+        let home = TempDir::new().unwrap();
+        let home = Utf8PathBuf::try_from(home.path().to_path_buf()).unwrap();
+        let graph_ref = GraphRef::from_str("my-graph@current").unwrap();
+        let history = CheckHistory::new(&home, &graph_ref, Some("products"));
+        assert!(history
+            .path
+            .ends_with("checks/my-graph_current/products.json"));
+
+        let first = BTreeSet::from([finding("fixed"), finding("still there")]);
+        assert_eq!(history.compare(first), None);
+
+        let second = BTreeSet::from([finding("still there"), finding("introduced")]);
+        let delta = history.compare(second).unwrap();
+        assert_eq!(delta.new, vec![finding("introduced")]);
+        assert_eq!(delta.resolved, vec![finding("fixed")]);
+        assert_eq!(delta.unchanged, 1);
+
+        // other subgraphs have their own history
+        let other = CheckHistory::new(&home, &graph_ref, Some("reviews"));
+        assert_eq!(other.compare(BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn it_ignores_locations_in_composition_errors() {
+        let build_errors = |message: &str| {
+            BuildErrors::from(vec![BuildError::composition_error(
+                Some("FIELD_TYPE_MISMATCH".to_string()),
+                Some(message.to_string()),
+                None,
+                None,
+            )])
+        };
+        let before = composition_findings(&build_errors(
+            "Field \"Product.id\" is defined differently\n  --> products.graphql:3:5 (subgraph 'products')",
+        ));
+        let after = composition_findings(&build_errors(
+            "Field \"Product.id\" is defined differently\n  --> products.graphql:9:5 (subgraph 'products')",
+        ));
+        assert_eq!(before, after);
+        let finding = before.into_iter().next().unwrap();
+        assert_eq!(finding.code, "FIELD_TYPE_MISMATCH");
+        assert_eq!(
+            finding.message,
+            "Field \"Product.id\" is defined differently"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! The report that `--watch-until-complete` prints when a check workflow finishes, and the exit
+//! codes that let CI tell failed checks apart from checks that couldn't run.
+
+use rover_client::shared::CheckWorkflowResponse;
+use rover_client::RoverClientError;
+use rover_std::{Emoji, Style};
+use serde_json::Value;
+
+use crate::utils::check_history::{as_array, as_string};
+use crate::utils::table::{self, row};
+use crate::{RoverError, RoverResult};
+
+/// How a check workflow ended, from CI's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckOutcome {
+    /// Every check passed
+    Passed,
+    /// The checks ran, and at least one of them failed, including composition
+    ChecksFailed,
+    /// The checks didn't finish before `--checks-timeout-seconds`
+    TimedOut,
+    /// Rover couldn't run the checks or read their results, like when the API can't be reached
+    Error,
+}
+
+impl CheckOutcome {
+    pub(crate) fn new(result: &Result<CheckWorkflowResponse, RoverClientError>) -> Self {
+        match result {
+            Ok(_) => Self::Passed,
+            Err(RoverClientError::CheckWorkflowFailure { .. })
+            | Err(RoverClientError::SubgraphBuildErrors { .. }) => Self::ChecksFailed,
+            Err(RoverClientError::ChecksTimeoutError { .. }) => Self::TimedOut,
+            Err(_) => Self::Error,
+        }
+    }
+
+    pub(crate) fn exit_code(self) -> i32 {
+        match self {
+            Self::Passed => 0,
+            Self::Error => 1,
+            Self::ChecksFailed => 2,
+            Self::TimedOut => 3,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Passed => "all checks passed",
+            Self::ChecksFailed => "checks failed",
+            Self::TimedOut => "checks did not finish in time",
+            Self::Error => "checks could not be run",
+        }
+    }
+}
+
+/// Prints a table of each check task's result to stderr, so it shows up in CI logs next to
+/// the full results, whatever the `--format`.
+pub(crate) fn print(result: &Result<CheckWorkflowResponse, RoverClientError>) {
+    let outcome = CheckOutcome::new(result);
+    let table = match result {
+        Ok(check_response) => Some(task_table(check_response)),
+        Err(RoverClientError::CheckWorkflowFailure { check_response, .. }) => {
+            Some(task_table(check_response))
+        }
+        Err(RoverClientError::SubgraphBuildErrors { source, .. }) => {
+            let build_errors = serde_json::to_value(source).unwrap_or_default();
+            Some(composition_table(&build_errors))
+        }
+        Err(_) => None,
+    };
+    if let Some(table) = table {
+        eprintln!("{}", table);
+    }
+    let summary = format!("{} (exit code {})", outcome.describe(), outcome.exit_code());
+    match outcome {
+        CheckOutcome::Passed => eprintln!("{}{}", Emoji::Success, summary),
+        _ => eprintln!("{} {}", Style::ErrorPrefix.paint("ERROR:"), summary),
+    }
+}
+
+/// Returns the check response, or an error that exits with the code for how the checks ended.
+pub(crate) fn finish(
+    result: Result<CheckWorkflowResponse, RoverClientError>,
+) -> RoverResult<CheckWorkflowResponse> {
+    let exit_code = CheckOutcome::new(&result).exit_code();
+    result.map_err(|e| RoverError::new(e).with_exit_code(exit_code))
+}
+
+/// One row per check task, with a count of what it found.
+fn task_table(check_response: &CheckWorkflowResponse) -> String {
+    // some of the responses' fields are private, but they're all serialized
+    let response = serde_json::to_value(check_response).unwrap_or_default();
+    let mut table = table::get_table();
+
+    // bc => sets top row to be bold and center
+    table.add_row(row![bc => "Check", "Status", "Result"]);
+    if let Some(core_schema_modified) = check_response.maybe_core_schema_modified {
+        let result = if core_schema_modified {
+            "core schema modified"
+        } else {
+            "no changes to the composed schema"
+        };
+        table.add_row(row!["Composition", "PASSED", result]);
+    }
+    let operations = &response["maybe_operations_response"];
+    if !operations.is_null() {
+        table.add_row(row![
+            "Operations",
+            as_string(&operations["task_status"]),
+            format!(
+                "{}, {} breaking, {} checked",
+                count(as_array(&operations["changes"]).len(), "change"),
+                as_string(&operations["failure_count"]),
+                count(
+                    operations["operation_check_count"].as_u64().unwrap_or(0) as usize,
+                    "operation"
+                )
+            )
+        ]);
+    }
+    let lint = &response["maybe_lint_response"];
+    if !lint.is_null() {
+        table.add_row(row![
+            "Linter",
+            as_string(&lint["task_status"]),
+            format!(
+                "{}, {}",
+                count(lint["errors_count"].as_u64().unwrap_or(0) as usize, "error"),
+                count(
+                    lint["warnings_count"].as_u64().unwrap_or(0) as usize,
+                    "warning"
+                )
+            )
+        ]);
+    }
+    let proposals = &response["maybe_proposals_response"];
+    if !proposals.is_null() {
+        table.add_row(row![
+            "Proposals",
+            as_string(&proposals["task_status"]),
+            format!(
+                "{} coverage",
+                as_string(&proposals["proposal_coverage"]).to_lowercase()
+            )
+        ]);
+    }
+    let downstream = &response["maybe_downstream_response"];
+    if !downstream.is_null() {
+        table.add_row(row![
+            "Downstream",
+            as_string(&downstream["task_status"]),
+            count(
+                as_array(&downstream["blocking_variants"]).len(),
+                "blocking variant"
+            )
+        ]);
+    }
+    table.to_string()
+}
+
+/// The task table for a check that composition errors stopped, followed by the errors.
+fn composition_table(build_errors: &Value) -> String {
+    let build_errors = as_array(&build_errors["build_errors"]);
+    let mut tasks = table::get_table();
+    tasks.add_row(row![bc => "Check", "Status", "Result"]);
+    tasks.add_row(row![
+        "Composition",
+        "FAILED",
+        count(build_errors.len(), "error")
+    ]);
+
+    let mut errors = table::get_table();
+    errors.add_row(row![bc => "Code", "Message"]);
+    for build_error in &build_errors {
+        errors.add_row(row![
+            as_string(&build_error["code"]),
+            as_string(&build_error["message"])
+        ]);
+    }
+    format!("{}\n{}", tasks, errors)
+}
+
+fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rover_client::shared::{CheckWorkflowResponse, GraphRef};
+    use rover_client::RoverClientError;
+    use serde_json::json;
+
+    use super::{composition_table, CheckOutcome};
+
+    #[test]
+    fn it_maps_each_outcome_to_an_exit_code() {
+        let check_response = CheckWorkflowResponse {
+            default_target_url: "https://studio.apollographql.com".to_string(),
+            maybe_core_schema_modified: Some(false),
+            maybe_operations_response: None,
+            maybe_lint_response: None,
+            maybe_proposals_response: None,
+            maybe_downstream_response: None,
+        };
+        let failure = RoverClientError::CheckWorkflowFailure {
+            graph_ref: GraphRef::from_str("my-graph@current").unwrap(),
+            check_response: Box::new(check_response.clone()),
+        };
+        let timeout = RoverClientError::ChecksTimeoutError { url: None };
+        let outcomes = [
+            CheckOutcome::new(&Ok(check_response)),
+            CheckOutcome::new(&Err(failure)),
+            CheckOutcome::new(&Err(timeout)),
+            CheckOutcome::new(&Err(RoverClientError::InvalidKey)),
+        ];
+        assert_eq!(outcomes.map(CheckOutcome::exit_code), [0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn it_lists_composition_errors() {
+        let table = composition_table(&json!({
+            "build_errors": [
+                { "code": "FIELD_TYPE_MISMATCH", "message": "Type of field \"Product.id\" is incompatible" }
+            ]
+        }));
+        assert!(table.contains("1 error"));
+        assert!(table.contains("FIELD_TYPE_MISMATCH"));
+    }
+}
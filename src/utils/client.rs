@@ -1,23 +1,55 @@
 use core::fmt;
-use std::{io, str::FromStr, time::Duration};
+use std::{
+    io,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    time::Duration,
+};
 
-use crate::{options::ProfileOpt, PKG_NAME, PKG_VERSION};
-use anyhow::Result;
+use crate::utils::{connectivity::check_connectivity, env::RoverEnvKey};
+use crate::{
+    options::ProfileOpt, RoverError, RoverErrorSuggestion, RoverResult, PKG_NAME, PKG_VERSION,
+};
+use anyhow::{anyhow, Result};
+use camino::Utf8Path;
+use clap::ValueEnum;
 
 use houston as config;
 use reqwest::blocking::Client;
-use rover_client::blocking::StudioClient;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::redirect::Policy;
+use reqwest::Certificate;
+use rover_client::blocking::{GraphQLClient, RateLimiter, StudioClient};
+use rover_std::perf::{self, Phase};
+use rover_std::{Fs, Style};
 
 use serde::Serialize;
 
+/// Whether the warning about a profile that accepts invalid certificates was printed
+static WARNED_ABOUT_PROFILE_CERTS: AtomicBool = AtomicBool::new(false);
+
+/// The certificate authorities from `--ca-file`, which every client trusts along with the system's
+static CA_CERTIFICATES: OnceLock<Vec<Certificate>> = OnceLock::new();
+
 /// the Apollo graph registry's production API endpoint
 const STUDIO_PROD_API_ENDPOINT: &str = "https://api.apollographql.com/graphql";
 
+/// The header that carries a profile's organization
+const ORGANIZATION_HEADER: &str = "apollo-rover-organization";
+
+/// The prefix of the headers that carry a profile's request metadata
+pub(crate) const REQUEST_METADATA_HEADER_PREFIX: &str = "apollo-rover-metadata-";
+
 #[derive(Debug, Clone, Copy)]
 pub struct ClientBuilder {
     accept_invalid_certs: bool,
     accept_invalid_hostnames: bool,
     timeout: Option<std::time::Duration>,
+    retries: Option<u32>,
+    follow_redirects: bool,
 }
 
 impl Default for ClientBuilder {
@@ -32,6 +64,8 @@ impl ClientBuilder {
             accept_invalid_certs: false,
             accept_invalid_hostnames: false,
             timeout: None,
+            retries: None,
+            follow_redirects: true,
         }
     }
 
@@ -56,18 +90,115 @@ impl ClientBuilder {
         }
     }
 
+    /// Uses `timeout` instead of Rover's default timeout, unless a timeout was already set.
+    pub fn with_default_timeout(self, timeout: std::time::Duration) -> Self {
+        Self {
+            timeout: self.timeout.or(Some(timeout)),
+            ..self
+        }
+    }
+
+    /// Retries failed GraphQL requests this many times, with exponential backoff between them,
+    /// rather than for a fixed amount of time.
+    pub fn with_retries(self, retries: u32) -> Self {
+        Self {
+            retries: Some(retries),
+            ..self
+        }
+    }
+
+    pub fn follow_redirects(self, value: bool) -> Self {
+        Self {
+            follow_redirects: value,
+            ..self
+        }
+    }
+
     pub(crate) fn build(self) -> Result<Client> {
-        let client = Client::builder()
+        let redirect_policy = if self.follow_redirects {
+            Policy::default()
+        } else {
+            Policy::none()
+        };
+        let mut client = Client::builder();
+        for certificate in CA_CERTIFICATES.get().into_iter().flatten() {
+            client = client.add_root_certificate(certificate.clone());
+        }
+        let client = client
             .gzip(true)
             .brotli(true)
             .danger_accept_invalid_certs(self.accept_invalid_certs)
             .danger_accept_invalid_hostnames(self.accept_invalid_hostnames)
-            .timeout(self.timeout)
+            .timeout(
+                self.timeout
+                    .unwrap_or_else(|| ClientTimeout::default().get_duration()),
+            )
+            .redirect(redirect_policy)
             .user_agent(format!("{}/{}", PKG_NAME, PKG_VERSION))
             .build()?;
 
         Ok(client)
     }
+
+    /// Builds a client for GraphQL requests to `endpoint`, like introspection, that retries
+    /// failed requests as many times as this builder was configured to.
+    pub(crate) fn build_graphql_client(self, endpoint: &str) -> Result<GraphQLClient> {
+        Ok(self.graphql_client_with(endpoint, self.build()?))
+    }
+
+    /// Like [`Self::build_graphql_client`], but sends requests with `client`, so that clients
+    /// for several endpoints share its connections.
+    pub(crate) fn graphql_client_with(self, endpoint: &str, client: Client) -> GraphQLClient {
+        let client = GraphQLClient::new(endpoint, client);
+        match self.retries {
+            Some(retries) => client.with_max_retries(retries),
+            None => client,
+        }
+    }
+
+    /// How many times failed requests are retried, or `None` for Rover's default retries
+    pub(crate) fn get_retries(&self) -> Option<u32> {
+        self.retries
+    }
+}
+
+/// Makes every client built after this trust the certificate authorities in the PEM file at
+/// `path`, like the one a TLS-intercepting proxy signs with, along with the system's.
+pub(crate) fn trust_ca_file(path: &Utf8Path) -> RoverResult<()> {
+    let invalid_ca_file = |reason: String| {
+        let mut err = RoverError::new(anyhow!(
+            "Could not read certificate authorities from '{}': {}",
+            path,
+            reason
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "Pass a file with one or more PEM-encoded certificates, each starting with `-----BEGIN CERTIFICATE-----`.".to_string(),
+        ));
+        err
+    };
+    let pem = Fs::read_file(path)?;
+    let certificates =
+        Certificate::from_pem_bundle(pem.as_bytes()).map_err(|e| invalid_ca_file(e.to_string()))?;
+    if certificates.is_empty() {
+        return Err(invalid_ca_file("it has no certificates".to_string()));
+    }
+    tracing::debug!(
+        "trusting {} certificate authorities from {}",
+        certificates.len(),
+        path
+    );
+    let _ = CA_CERTIFICATES.set(certificates);
+    Ok(())
+}
+
+/// Which requests `--insecure-accept-invalid-certs` stops verifying certificates for
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum InvalidCertScope {
+    /// Every request, including requests to the Apollo registry
+    All,
+    /// Only introspection and local development endpoints
+    Endpoints,
 }
 
 #[derive(Debug, Copy, Clone, Serialize)]
@@ -113,9 +244,14 @@ pub struct StudioClientConfig {
     pub(crate) config: config::Config,
     client_builder: ClientBuilder,
     uri: String,
+    uri_selected_by: String,
+    uri_overridden: bool,
+    connectivity_checked: Arc<AtomicBool>,
     version: String,
     is_sudo: bool,
     client: Option<Client>,
+    accept_invalid_endpoint_certs: bool,
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl StudioClientConfig {
@@ -131,13 +267,43 @@ impl StudioClientConfig {
             PKG_VERSION.to_string()
         };
 
+        let uri_overridden = override_endpoint.is_some();
+        let uri_selected_by = if uri_overridden {
+            format!("the `{}` environment variable", RoverEnvKey::RegistryUrl)
+        } else {
+            "Rover's default registry endpoint".to_string()
+        };
+
         StudioClientConfig {
             uri: override_endpoint.unwrap_or_else(|| STUDIO_PROD_API_ENDPOINT.to_string()),
+            uri_selected_by,
+            uri_overridden,
+            connectivity_checked: Arc::new(AtomicBool::new(false)),
             config,
             version,
             client_builder,
             is_sudo,
             client: None,
+            accept_invalid_endpoint_certs: false,
+            rate_limiter: None,
+        }
+    }
+
+    /// Starts no more than `requests_per_second` requests to the registry each second, across
+    /// every client made from this config.
+    pub(crate) fn max_requests_per_second(self, requests_per_second: Option<u32>) -> Self {
+        Self {
+            rate_limiter: requests_per_second.map(RateLimiter::new),
+            ..self
+        }
+    }
+
+    /// Accepts invalid certificates from introspection and local development endpoints, whatever
+    /// the profile's settings are.
+    pub(crate) fn accept_invalid_endpoint_certs(self, value: bool) -> Self {
+        Self {
+            accept_invalid_endpoint_certs: value,
+            ..self
         }
     }
 
@@ -150,19 +316,173 @@ impl StudioClientConfig {
         }
     }
 
-    #[cfg(feature = "composition-js")]
+    /// Returns the registry endpoint that requests with a profile are sent to, and the
+    /// configuration that selected it: `APOLLO_REGISTRY_URL`, the profile's `registry-url`
+    /// default, or Rover's default endpoint.
+    pub(crate) fn get_registry_endpoint(&self, profile_name: &str) -> (String, String) {
+        if !self.uri_overridden {
+            if let Some(registry_url) = self.get_profile_settings(profile_name).registry_url {
+                return (
+                    registry_url,
+                    format!(
+                        "the `registry-url` default of the '{}' profile",
+                        profile_name
+                    ),
+                );
+            }
+        }
+        (self.uri.clone(), self.uri_selected_by.clone())
+    }
+
+    /// Returns a profile's settings, or the defaults if they can't be read.
+    pub(crate) fn get_profile_settings(&self, profile_name: &str) -> config::ProfileSettings {
+        config::Profile::get_settings(profile_name, &self.config).unwrap_or_else(|e| {
+            tracing::debug!("could not read the profile's settings: {}", e);
+            config::ProfileSettings::default()
+        })
+    }
+
     pub(crate) fn get_builder(&self) -> ClientBuilder {
         self.client_builder
     }
 
-    pub fn get_authenticated_client(&self, profile_opt: &ProfileOpt) -> Result<StudioClient> {
-        let credential = config::Profile::get_credential(&profile_opt.profile_name, &self.config)?;
-        Ok(StudioClient::new(
+    /// Returns a builder for clients that talk to introspection and local development endpoints
+    /// rather than the registry. These accept invalid certificates if
+    /// `--insecure-accept-invalid-certs` was passed, or if the profile's settings allow it.
+    pub(crate) fn get_endpoint_builder(&self, profile_opt: &ProfileOpt) -> ClientBuilder {
+        let profile_allows_it = !self.accept_invalid_endpoint_certs
+            && self
+                .get_profile_settings(&profile_opt.profile_name)
+                .insecure_accept_invalid_certs;
+        if profile_allows_it && !WARNED_ABOUT_PROFILE_CERTS.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "{} The '{}' profile accepts invalid TLS certificates from introspection and local development endpoints. Requests to them can be intercepted.",
+                Style::WarningPrefix.paint("WARN:"),
+                profile_opt.profile_name
+            );
+        }
+        if self.accept_invalid_endpoint_certs || profile_allows_it {
+            self.client_builder.accept_invalid_certs(true)
+        } else {
+            self.client_builder
+        }
+    }
+
+    /// Builds a client for introspection and local development endpoints, see [`Self::get_endpoint_builder`].
+    pub(crate) fn get_endpoint_reqwest_client(&self, profile_opt: &ProfileOpt) -> Result<Client> {
+        self.get_endpoint_builder(profile_opt).build()
+    }
+
+    pub fn get_authenticated_client(&self, profile_opt: &ProfileOpt) -> RoverResult<StudioClient> {
+        let credential = perf::time(Phase::Credentials, || {
+            config::Profile::get_credential(&profile_opt.profile_name, &self.config)
+        })?;
+        let (uri, uri_selected_by) = self.get_registry_endpoint(&profile_opt.profile_name);
+        // fail fast when the registry is unreachable, rather than after every request retries
+        if !self.connectivity_checked.load(Ordering::Relaxed) {
+            check_connectivity(&uri, &uri_selected_by)?;
+            self.connectivity_checked.store(true, Ordering::Relaxed);
+        }
+        let settings = self.get_profile_settings(&profile_opt.profile_name);
+        let client = StudioClient::new(
             credential,
-            &self.uri,
+            &uri,
             &self.version,
             self.is_sudo,
             self.get_reqwest_client()?,
-        ))
+        )
+        .with_request_headers(request_metadata_headers(&settings)?);
+        let client = match self.client_builder.retries {
+            Some(retries) => client.with_max_retries(retries),
+            None => client,
+        };
+        Ok(match &self.rate_limiter {
+            Some(rate_limiter) => client.with_rate_limiter(rate_limiter.clone()),
+            None => client,
+        })
+    }
+}
+
+/// The headers that attribute requests to the organization and CI metadata in a profile's
+/// settings: a header for each, and a `User-Agent` that lists them after Rover's version.
+/// Metadata that's read from an environment variable that isn't set is left out.
+fn request_metadata_headers(settings: &config::ProfileSettings) -> RoverResult<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    let mut user_agent_details: Vec<String> = Vec::new();
+    if let Some(organization) = &settings.organization {
+        headers.insert(ORGANIZATION_HEADER, HeaderValue::from_str(organization)?);
+        user_agent_details.push(organization.clone());
+    }
+    for (key, value) in &settings.request_metadata {
+        let value = match value.strip_prefix('$') {
+            Some(variable) => match std::env::var(variable) {
+                Ok(value) => value,
+                Err(_) => continue,
+            },
+            None => value.clone(),
+        };
+        headers.insert(
+            HeaderName::from_str(&format!("{}{}", REQUEST_METADATA_HEADER_PREFIX, key))?,
+            HeaderValue::from_str(&value)?,
+        );
+        user_agent_details.push(format!("{}={}", key, value));
+    }
+    if !user_agent_details.is_empty() {
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(&format!(
+                "{}/{} ({})",
+                PKG_NAME,
+                PKG_VERSION,
+                user_agent_details.join("; ")
+            ))?,
+        );
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use camino::Utf8PathBuf;
+    use houston::ProfileSettings;
+
+    use super::{request_metadata_headers, trust_ca_file};
+    use crate::{PKG_NAME, PKG_VERSION};
+
+    #[test]
+    fn it_rejects_ca_files_without_certificates() {
+        let tmp_home = TempDir::new().unwrap();
+        let path = Utf8PathBuf::try_from(tmp_home.path().join("ca.pem")).unwrap();
+        std::fs::write(&path, "not a certificate").unwrap();
+        assert!(trust_ca_file(&path).is_err());
+        assert!(trust_ca_file(&path.with_file_name("missing.pem")).is_err());
+    }
+
+    #[test]
+    fn it_builds_request_metadata_headers() {
+        assert!(request_metadata_headers(&ProfileSettings::default())
+            .unwrap()
+            .is_empty());
+
+        let settings = ProfileSettings {
+            organization: Some("acme".to_string()),
+            request_metadata: [
+                ("repo", "acme/graphs"),
+                ("build-id", "$ROVER_TEST_UNSET_BUILD_ID"),
+            ]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+            ..Default::default()
+        };
+        let headers = request_metadata_headers(&settings).unwrap();
+        assert_eq!(headers["apollo-rover-organization"], "acme");
+        assert_eq!(headers["apollo-rover-metadata-repo"], "acme/graphs");
+        assert!(!headers.contains_key("apollo-rover-metadata-build-id"));
+        assert_eq!(
+            headers["user-agent"],
+            format!("{}/{} (acme; repo=acme/graphs)", PKG_NAME, PKG_VERSION).as_str()
+        );
     }
 }
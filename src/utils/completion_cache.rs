@@ -0,0 +1,201 @@
+//! Remembers the graph refs and subgraph names that recent commands used, so the scripts from
+//! `rover completions` can suggest them without calling GraphOS on every key press.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use camino::Utf8PathBuf;
+use houston::Config;
+use rover_std::Fs;
+use serde::{Deserialize, Serialize};
+
+use crate::RoverOutput;
+
+/// The cache's file in Rover's config home
+const CACHE_FILE: &str = "completions.json";
+
+/// How many graph refs to remember. Older ones are forgotten first.
+const MAX_GRAPH_REFS: usize = 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct CompletionCache {
+    /// The most recently used first
+    graph_refs: Vec<String>,
+    /// The subgraph names seen for each graph ref
+    subgraphs: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CompletionCache {
+    fn path(config: &Config) -> Utf8PathBuf {
+        config.home.join(CACHE_FILE)
+    }
+
+    /// The cache in `config`'s home, or an empty one if it can't be read.
+    pub(crate) fn load(config: &Config) -> Self {
+        Fs::read_file(Self::path(config))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Remembers the graph refs and subgraph names in a command's output. Completions are only a
+    /// convenience, so a cache that can't be written is only logged.
+    pub(crate) fn record_output(config: &Config, output: &RoverOutput) {
+        let mut cache = Self::load(config);
+        if !cache.record(output) {
+            return;
+        }
+        let written = serde_json::to_string(&cache)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                Fs::write_file(Self::path(config), contents).map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            tracing::debug!("could not write the completion cache: {}", e);
+        }
+    }
+
+    /// Remembers what `output` used, and returns whether anything was new.
+    fn record(&mut self, output: &RoverOutput) -> bool {
+        let before = self.clone();
+        match output {
+            RoverOutput::SubgraphList(list) => {
+                // a listing is every subgraph there is, so deleted ones are forgotten
+                let graph_ref = list.graph_ref.to_string();
+                self.use_graph_ref(&graph_ref);
+                self.subgraphs.insert(
+                    graph_ref,
+                    list.subgraphs
+                        .iter()
+                        .map(|subgraph| subgraph.name.clone())
+                        .collect(),
+                );
+            }
+            RoverOutput::SubgraphFetchReport(report) => {
+                self.use_graph_ref(&report.graph_ref);
+                self.subgraphs
+                    .entry(report.graph_ref.clone())
+                    .or_default()
+                    .extend(report.subgraphs.iter().map(|result| result.name.clone()));
+            }
+            RoverOutput::SubgraphPublishResponse {
+                graph_ref,
+                subgraph,
+                ..
+            } => {
+                let graph_ref = graph_ref.to_string();
+                self.use_graph_ref(&graph_ref);
+                self.subgraphs
+                    .entry(graph_ref)
+                    .or_default()
+                    .insert(subgraph.clone());
+            }
+            RoverOutput::SubgraphDeleteResponse {
+                graph_ref,
+                subgraph,
+                dry_run: false,
+                ..
+            } => {
+                let graph_ref = graph_ref.to_string();
+                self.use_graph_ref(&graph_ref);
+                if let Some(subgraphs) = self.subgraphs.get_mut(&graph_ref) {
+                    subgraphs.remove(subgraph);
+                }
+            }
+            RoverOutput::GraphPublishResponse { graph_ref, .. }
+            | RoverOutput::ReadmeFetchResponse { graph_ref, .. }
+            | RoverOutput::ReadmePublishResponse { graph_ref, .. } => {
+                self.use_graph_ref(&graph_ref.to_string());
+            }
+            _ => {}
+        }
+        *self != before
+    }
+
+    fn use_graph_ref(&mut self, graph_ref: &str) {
+        self.graph_refs.retain(|used| used != graph_ref);
+        self.graph_refs.insert(0, graph_ref.to_string());
+        for forgotten in self
+            .graph_refs
+            .split_off(self.graph_refs.len().min(MAX_GRAPH_REFS))
+        {
+            self.subgraphs.remove(&forgotten);
+        }
+    }
+
+    /// The remembered graph refs, the most recently used first.
+    pub(crate) fn graph_refs(&self) -> &[String] {
+        &self.graph_refs
+    }
+
+    /// The subgraph names seen for `graph_ref`, or for every graph ref if it's `None` or hasn't
+    /// been seen.
+    pub(crate) fn subgraph_names(&self, graph_ref: Option<&str>) -> Vec<String> {
+        if let Some(subgraphs) = graph_ref.and_then(|graph_ref| self.subgraphs.get(graph_ref)) {
+            return subgraphs.iter().cloned().collect();
+        }
+        self.subgraphs
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rover_client::operations::subgraph::list::{
+        SubgraphInfo, SubgraphListResponse, SubgraphUpdatedAt,
+    };
+    use rover_client::shared::GraphRef;
+
+    use super::{CompletionCache, MAX_GRAPH_REFS};
+    use crate::RoverOutput;
+
+    fn subgraph_list(graph_ref: &str, names: &[&str]) -> RoverOutput {
+        RoverOutput::SubgraphList(SubgraphListResponse {
+            subgraphs: names
+                .iter()
+                .map(|name| SubgraphInfo {
+                    name: name.to_string(),
+                    url: None,
+                    updated_at: SubgraphUpdatedAt {
+                        local: None,
+                        utc: None,
+                    },
+                })
+                .collect(),
+            root_url: "https://studio.apollographql.com/".to_string(),
+            graph_ref: GraphRef::from_str(graph_ref).unwrap(),
+        })
+    }
+
+    #[test]
+    fn it_remembers_graph_refs_and_subgraphs() {
+        let mut cache = CompletionCache::default();
+        assert!(cache.record(&subgraph_list("shop@prod", &["products", "reviews"])));
+        assert!(cache.record(&subgraph_list("shop@staging", &["products", "inventory"])));
+        assert!(!cache.record(&subgraph_list("shop@staging", &["products", "inventory"])));
+        assert!(cache.record(&subgraph_list("shop@prod", &["products"])));
+
+        assert_eq!(cache.graph_refs(), ["shop@prod", "shop@staging"]);
+        assert_eq!(cache.subgraph_names(Some("shop@prod")), ["products"]);
+        assert_eq!(cache.subgraph_names(None), ["inventory", "products"]);
+        assert!(!cache.record(&RoverOutput::EmptySuccess));
+    }
+
+    #[test]
+    fn it_forgets_the_least_recently_used_graph_refs() {
+        let mut cache = CompletionCache::default();
+        for variant in 0..=MAX_GRAPH_REFS {
+            cache.record(&subgraph_list(&format!("shop@v{}", variant), &["products"]));
+        }
+        assert_eq!(cache.graph_refs().len(), MAX_GRAPH_REFS);
+        assert_eq!(cache.graph_refs()[0], format!("shop@v{}", MAX_GRAPH_REFS));
+        assert!(!cache.subgraphs.contains_key("shop@v0"));
+    }
+}
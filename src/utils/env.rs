@@ -64,7 +64,7 @@ impl RoverEnv {
     }
 
     fn get_debug_value(key: RoverEnvKey, value: &str) -> String {
-        let value = if let RoverEnvKey::Key = key {
+        let value = if let RoverEnvKey::Key | RoverEnvKey::OciPassword = key {
             houston::mask_key(value)
         } else {
             value.to_string()
@@ -93,6 +93,7 @@ impl RoverEnv {
 /// For example, `RoverEnvKey::ConfigHome.to_string()` becomes `APOLLO_CONFIG_HOME`
 #[derive(Debug, Copy, Clone, EnumIter)]
 pub enum RoverEnvKey {
+    CaFile,
     ConfigHome,
     FireFlower,
     Home,
@@ -106,6 +107,12 @@ pub enum RoverEnvKey {
     VcsAuthor,
     NodeModulesBin,
     ChecksTimeoutSeconds,
+    OciUsername,
+    OciPassword,
+    SkipConnectivityCheck,
+    ProtectedVariants,
+    DevSocketDir,
+    DevIpcTransport,
 }
 
 impl fmt::Display for RoverEnvKey {
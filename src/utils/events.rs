@@ -0,0 +1,165 @@
+//! Structured lifecycle events for `--events ndjson`, so IDE extensions and wrappers can follow
+//! a command's progress without parsing the human-readable messages on stderr.
+
+use std::io::Write;
+use std::sync::Mutex;
+
+use apollo_federation_types::build::BuildErrors;
+use rover_client::shared::{CheckWorkflowResponse, GraphRef};
+use rover_client::RoverClientError;
+use serde::Serialize;
+use serde_json::json;
+
+/// Where events are written, if they've been enabled
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// A lifecycle event, written as a single JSON object per line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub(crate) enum Event {
+    PluginDownloadStarted {
+        plugin: String,
+    },
+    PluginDownloadCompleted {
+        plugin: String,
+    },
+    CompositionStarted {
+        federation_version: String,
+        subgraphs: usize,
+    },
+    CompositionSucceeded {
+        federation_version: String,
+        hints: usize,
+    },
+    CompositionFailed {
+        federation_version: String,
+        errors: usize,
+        messages: Vec<String>,
+    },
+    SubgraphRegistered {
+        graph_ref: String,
+        subgraph: String,
+        created: bool,
+        supergraph_updated: bool,
+    },
+    CheckCompleted {
+        graph_ref: String,
+        subgraph: Option<String>,
+        passed: bool,
+        target_url: Option<String>,
+    },
+    SubgraphAdded {
+        subgraph: String,
+        url: String,
+    },
+    SubgraphUpdated {
+        subgraph: String,
+        url: String,
+    },
+    SubgraphRemoved {
+        subgraph: String,
+    },
+    RouterReady {
+        url: String,
+    },
+    SessionEnded,
+}
+
+impl Event {
+    fn to_line(&self) -> String {
+        let mut line = json!({ "timestamp": chrono::Utc::now().to_rfc3339() });
+        if let (Some(line), Ok(serde_json::Value::Object(event))) =
+            (line.as_object_mut(), serde_json::to_value(self))
+        {
+            line.extend(event);
+        }
+        line.to_string()
+    }
+}
+
+/// Emits a `check-completed` event for a check that ran to completion, whether it passed or not.
+pub(crate) fn emit_check_completed(
+    graph_ref: &GraphRef,
+    subgraph: Option<&str>,
+    result: &Result<CheckWorkflowResponse, RoverClientError>,
+) {
+    let (passed, check_response) = match result {
+        Ok(check_response) => (true, Some(check_response)),
+        Err(RoverClientError::CheckWorkflowFailure { check_response, .. }) => {
+            (false, Some(check_response.as_ref()))
+        }
+        Err(RoverClientError::SubgraphBuildErrors { .. }) => (false, None),
+        Err(_) => return,
+    };
+    emit(Event::CheckCompleted {
+        graph_ref: graph_ref.to_string(),
+        subgraph: subgraph.map(str::to_string),
+        passed,
+        target_url: check_response.map(|response| response.default_target_url.clone()),
+    });
+}
+
+/// Emits a `composition-failed` event with the message of each composition error.
+pub(crate) fn emit_composition_failed(federation_version: String, build_errors: &BuildErrors) {
+    let messages = build_errors
+        .iter()
+        .filter_map(|error| error.get_message())
+        .collect();
+    emit(Event::CompositionFailed {
+        federation_version,
+        errors: build_errors.len(),
+        messages,
+    });
+}
+
+/// Starts writing every emitted event to `sink`.
+pub(crate) fn enable(sink: Box<dyn Write + Send>) {
+    if let Ok(mut current) = SINK.lock() {
+        *current = Some(sink);
+    }
+}
+
+/// Writes `event` if events are enabled. Failing to write an event never fails the command.
+pub(crate) fn emit(event: Event) {
+    if let Ok(mut sink) = SINK.lock() {
+        if let Some(sink) = sink.as_mut() {
+            let written = writeln!(sink, "{}", event.to_line()).and_then(|_| sink.flush());
+            if let Err(e) = written {
+                tracing::debug!("could not write a progress event: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::Event;
+
+    #[test]
+    fn it_writes_events_as_single_lines() {
+        let line = Event::CheckCompleted {
+            graph_ref: "my-graph@current".to_string(),
+            subgraph: Some("products".to_string()),
+            passed: false,
+            target_url: None,
+        }
+        .to_line();
+        assert!(!line.contains('\n'));
+
+        let event: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(event["event"], "check-completed");
+        assert_eq!(event["graph_ref"], "my-graph@current");
+        assert_eq!(event["subgraph"], "products");
+        assert_eq!(event["passed"], false);
+        assert!(event["timestamp"].is_string());
+    }
+
+    #[test]
+    fn it_writes_events_without_fields() {
+        let event: Value = serde_json::from_str(&Event::SessionEnded.to_line()).unwrap();
+        assert_eq!(event["event"], "session-ended");
+        assert!(event["timestamp"].is_string());
+    }
+}
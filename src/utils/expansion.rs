@@ -4,6 +4,7 @@
 //! `supergraph compose`)
 use anyhow::{anyhow, bail, Context, Error};
 use serde_yaml::{Mapping, Sequence, Value};
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 
@@ -16,17 +17,23 @@ use crate::RoverResult;
 /// [variable expansion](https://www.apollographql.com/docs/router/configuration/overview/#variable-expansion)
 /// for a YAML mapping (e.g., an entire `supergraph.yaml` or `router.yaml`).
 pub(crate) fn expand(value: Value) -> RoverResult<Value> {
+    expand_with_vars(value, &HashMap::new())
+}
+
+/// Like [`expand`], but `${env.NAME}` falls back to `vars` when the process doesn't have an
+/// environment variable called `NAME`, like the variables from a subgraph's `env_file`.
+pub(crate) fn expand_with_vars(value: Value, vars: &HashMap<String, String>) -> RoverResult<Value> {
     match value {
-        Value::String(s) => expand_str(&s).map(Value::String),
+        Value::String(s) => expand_str_with_vars(&s, vars).map(Value::String),
         Value::Null | Value::Bool(_) | Value::Number(_) | Value::Tagged(_) => Ok(value),
         Value::Sequence(inner) => inner
             .into_iter()
-            .map(expand)
+            .map(|value| expand_with_vars(value, vars))
             .collect::<RoverResult<Sequence>>()
             .map(Value::Sequence),
         Value::Mapping(inner) => inner
             .into_iter()
-            .map(|(key, value)| expand(value).map(|value| (key, value)))
+            .map(|(key, value)| expand_with_vars(value, vars).map(|value| (key, value)))
             .collect::<RoverResult<Mapping>>()
             .map(Value::Mapping),
     }
@@ -90,20 +97,29 @@ subgraphs:
 /// Implements router-config-style
 /// [variable expansion](https://www.apollographql.com/docs/router/configuration/overview/#variable-expansion)
 /// for a single value.
+#[cfg(test)]
 fn expand_str(value: &str) -> RoverResult<String> {
-    env_with_context(value, context)
+    expand_str_with_vars(value, &HashMap::new())
+}
+
+/// Expands a single value, falling back to `vars` for env vars the process doesn't have.
+fn expand_str_with_vars(value: &str, vars: &HashMap<String, String>) -> RoverResult<String> {
+    env_with_context(value, |key| context(key, vars))
         .map_err(|e| anyhow!(e).context("While expanding variables").into())
         .map(|cow| cow.into_owned())
 }
 
-fn context(key: &str) -> Result<Option<String>, Error> {
+fn context(key: &str, vars: &HashMap<String, String>) -> Result<Option<String>, Error> {
     if let Some(env_var_key) = key.strip_prefix("env.") {
-        env::var(env_var_key).map(Some).with_context(|| {
-            format!(
-                "While reading env var {} for variable expansion",
-                env_var_key
-            )
-        })
+        match (env::var(env_var_key), vars.get(env_var_key)) {
+            (Err(_), Some(value)) => Ok(Some(value.clone())),
+            (result, _) => result.map(Some).with_context(|| {
+                format!(
+                    "While reading env var {} for variable expansion",
+                    env_var_key
+                )
+            }),
+        }
     } else if let Some(file_name) = key.strip_prefix("file.") {
         if !Path::new(file_name).exists() {
             Ok(None)
@@ -190,6 +206,26 @@ mod test_expand_str {
         assert_eq!(expand_str(value).unwrap(), value);
     }
 
+    #[test]
+    fn env_var_from_vars() {
+        let vars = HashMap::from([
+            (ENV_VAR_KEY_1.to_string(), "ignored".to_string()),
+            (
+                "RESOLVE_HEADER_VALUE_TEST_VAR_FROM_FILE".to_string(),
+                "from-file".to_string(),
+            ),
+        ]);
+        env::set_var(ENV_VAR_KEY_1, ENV_VAR_VALUE_1);
+        let value = format!(
+            "${{env.{}}}-${{env.RESOLVE_HEADER_VALUE_TEST_VAR_FROM_FILE}}",
+            ENV_VAR_KEY_1
+        );
+        assert_eq!(
+            expand_str_with_vars(&value, &vars).unwrap(),
+            format!("{}-from-file", ENV_VAR_VALUE_1)
+        );
+    }
+
     #[test]
     fn content_from_file() {
         let temp = NamedTempFile::new("variable.txt").unwrap();
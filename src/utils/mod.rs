@@ -1,7 +1,19 @@
+pub(crate) mod check_annotations;
+pub(crate) mod check_history;
+pub(crate) mod check_report;
 pub mod client;
+pub(crate) mod completion_cache;
+pub(crate) mod connectivity;
 pub mod env;
+pub(crate) mod events;
+pub(crate) mod oci;
 pub mod parsers;
 pub mod pkg;
+pub(crate) mod profile_defaults;
+pub(crate) mod protected_variant;
+pub(crate) mod scope;
+pub(crate) mod sdl;
+pub(crate) mod source_map;
 pub mod stringify;
 pub mod table;
 pub mod telemetry;
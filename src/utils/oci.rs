@@ -0,0 +1,440 @@
+//! A minimal client for the [OCI distribution spec](https://github.com/opencontainers/distribution-spec),
+//! which Rover uses to push and pull schemas as single-layer OCI artifacts.
+
+use std::cell::RefCell;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, LOCATION, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::utils::env::RoverEnvKey;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// The media type of a subgraph schema layer
+pub(crate) const SUBGRAPH_SCHEMA_MEDIA_TYPE: &str =
+    "application/vnd.apollographql.subgraph.schema.v1+graphql";
+
+/// The media type of a composed supergraph schema layer
+pub(crate) const SUPERGRAPH_SCHEMA_MEDIA_TYPE: &str =
+    "application/vnd.apollographql.supergraph.schema.v1+graphql";
+
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const EMPTY_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.empty.v1+json";
+const EMPTY_CONFIG: &[u8] = b"{}";
+const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+
+/// A reference to an artifact in an OCI registry, like `oci://ghcr.io/org/accounts-schema:1.2.3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OciReference {
+    pub(crate) registry: String,
+    pub(crate) repository: String,
+    /// either a tag or a `sha256:` digest
+    pub(crate) reference: String,
+}
+
+impl OciReference {
+    fn base_url(&self) -> String {
+        // local registries rarely have TLS set up
+        let host = self.registry.split(':').next().unwrap_or_default();
+        let scheme = if ["localhost", "127.0.0.1"].contains(&host) {
+            "http"
+        } else {
+            "https"
+        };
+        format!("{}://{}/v2/{}", scheme, self.registry, self.repository)
+    }
+
+    fn is_digest(&self) -> bool {
+        self.reference.starts_with("sha256:")
+    }
+}
+
+impl FromStr for OciReference {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            anyhow!(
+                "'{}' is not a valid OCI reference. OCI references look like `oci://<registry>/<repository>:<tag>` or `oci://<registry>/<repository>@sha256:<digest>`.",
+                input
+            )
+        };
+        let rest = input.strip_prefix("oci://").ok_or_else(invalid)?;
+        let (registry, rest) = rest.split_once('/').ok_or_else(invalid)?;
+        let (repository, reference) = if let Some((repository, digest)) = rest.split_once('@') {
+            (repository, digest)
+        } else {
+            match rest.rsplit_once(':') {
+                Some((repository, tag)) if !tag.contains('/') => (repository, tag),
+                _ => (rest, "latest"),
+            }
+        };
+        if registry.is_empty() || repository.is_empty() || reference.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for OciReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.is_digest() { '@' } else { ':' };
+        write!(
+            f,
+            "oci://{}/{}{}{}",
+            self.registry, self.repository, separator, self.reference
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    annotations: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    schema_version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact_type: Option<String>,
+    config: Descriptor,
+    layers: Vec<Descriptor>,
+}
+
+/// A pulled artifact
+#[derive(Debug, Clone)]
+pub(crate) struct OciArtifact {
+    pub(crate) media_type: String,
+    pub(crate) content: String,
+}
+
+pub(crate) struct OciClient {
+    client: Client,
+    credentials: Option<(String, String)>,
+    token: RefCell<Option<String>>,
+}
+
+impl OciClient {
+    /// Creates a client that authenticates with `$APOLLO_OCI_USERNAME` and `$APOLLO_OCI_PASSWORD`
+    /// if they are set, and anonymously otherwise.
+    pub(crate) fn new(client: Client) -> Self {
+        let credentials = match (
+            env::var(RoverEnvKey::OciUsername.to_string()),
+            env::var(RoverEnvKey::OciPassword.to_string()),
+        ) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            _ => None,
+        };
+        Self {
+            client,
+            credentials,
+            token: RefCell::new(None),
+        }
+    }
+
+    /// Pulls a single-layer artifact, verifying the layer's digest
+    pub(crate) fn pull(&self, reference: &OciReference) -> RoverResult<OciArtifact> {
+        let manifest_url = format!("{}/manifests/{}", reference.base_url(), reference.reference);
+        let manifest: Manifest = self
+            .send(reference, |client| {
+                client
+                    .get(&manifest_url)
+                    .header(ACCEPT, MANIFEST_MEDIA_TYPE)
+            })?
+            .json()
+            .with_context(|| format!("Could not parse the OCI manifest for {}", reference))?;
+
+        let layer = match manifest.layers.as_slice() {
+            [layer] => layer.clone(),
+            layers => {
+                return Err(RoverError::new(anyhow!(
+                    "{} has {} layers, but schema artifacts must have exactly one.",
+                    reference,
+                    layers.len()
+                )))
+            }
+        };
+
+        let blob_url = format!("{}/blobs/{}", reference.base_url(), layer.digest);
+        let content = self
+            .send(reference, |client| client.get(&blob_url))?
+            .bytes()
+            .with_context(|| format!("Could not download the schema layer for {}", reference))?;
+        if sha256_digest(&content) != layer.digest {
+            return Err(RoverError::new(anyhow!(
+                "The schema layer for {} does not match its digest '{}'.",
+                reference,
+                layer.digest
+            )));
+        }
+
+        Ok(OciArtifact {
+            media_type: layer.media_type,
+            content: String::from_utf8(content.to_vec()).with_context(|| {
+                format!("The schema layer for {} is not valid UTF-8", reference)
+            })?,
+        })
+    }
+
+    /// Pushes `content` as a single-layer artifact, returning the digest of the pushed manifest
+    pub(crate) fn push(
+        &self,
+        reference: &OciReference,
+        media_type: &str,
+        title: &str,
+        content: &str,
+    ) -> RoverResult<String> {
+        let config = self.push_blob(reference, EMPTY_CONFIG)?;
+        let layer = self.push_blob(reference, content.as_bytes())?;
+
+        let mut annotations = serde_json::Map::new();
+        annotations.insert(TITLE_ANNOTATION.to_string(), title.into());
+        let manifest = Manifest {
+            schema_version: 2,
+            media_type: Some(MANIFEST_MEDIA_TYPE.to_string()),
+            artifact_type: Some(media_type.to_string()),
+            config: Descriptor {
+                media_type: EMPTY_CONFIG_MEDIA_TYPE.to_string(),
+                digest: config,
+                size: EMPTY_CONFIG.len(),
+                annotations: None,
+            },
+            layers: vec![Descriptor {
+                media_type: media_type.to_string(),
+                digest: layer,
+                size: content.len(),
+                annotations: Some(annotations),
+            }],
+        };
+        let manifest = serde_json::to_vec(&manifest)?;
+        let manifest_url = format!("{}/manifests/{}", reference.base_url(), reference.reference);
+        self.send(reference, |client| {
+            client
+                .put(&manifest_url)
+                .header(CONTENT_TYPE, MANIFEST_MEDIA_TYPE)
+                .body(manifest.clone())
+        })?;
+
+        Ok(sha256_digest(&manifest))
+    }
+
+    fn push_blob(&self, reference: &OciReference, content: &[u8]) -> RoverResult<String> {
+        let digest = sha256_digest(content);
+        let upload_url = format!("{}/blobs/uploads/", reference.base_url());
+        let response = self.send(reference, |client| client.post(&upload_url))?;
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The registry did not return an upload location for {}",
+                    reference
+                )
+            })?;
+        // the location may be relative to the registry
+        let location = if location.starts_with('/') {
+            let base_url = reference.base_url();
+            let origin = base_url
+                .split("/v2/")
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            format!("{}{}", origin, location)
+        } else {
+            location.to_string()
+        };
+        let separator = if location.contains('?') { '&' } else { '?' };
+        let blob_url = format!("{}{}digest={}", location, separator, digest);
+        self.send(reference, |client| {
+            client
+                .put(&blob_url)
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(content.to_vec())
+        })?;
+        Ok(digest)
+    }
+
+    /// Sends a request, negotiating a bearer token with the registry if it asks for one
+    fn send<F>(&self, reference: &OciReference, build: F) -> RoverResult<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let response = self.authorize(build(&self.client)).send()?;
+        let response = if response.status() == StatusCode::UNAUTHORIZED {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|challenge| challenge.to_str().ok())
+                .map(str::to_string);
+            match challenge {
+                Some(challenge) if challenge.starts_with("Bearer ") => {
+                    let token = self.fetch_token(&challenge)?;
+                    self.token.replace(Some(token));
+                    self.authorize(build(&self.client)).send()?
+                }
+                _ => response,
+            }
+        } else {
+            response
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body = response.text().unwrap_or_default();
+            let mut err = RoverError::new(anyhow!(
+                "The OCI registry responded with {} for {}: {}",
+                status,
+                reference,
+                body.trim()
+            ));
+            if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+                err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                    "Set {} and {} to credentials that have access to {}.",
+                    RoverEnvKey::OciUsername,
+                    RoverEnvKey::OciPassword,
+                    reference.repository
+                )));
+            }
+            Err(err)
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        if let Some(token) = self.token.borrow().as_ref() {
+            request.bearer_auth(token)
+        } else if let Some((username, password)) = &self.credentials {
+            request.basic_auth(username, Some(password))
+        } else {
+            request
+        }
+    }
+
+    fn fetch_token(&self, challenge: &str) -> RoverResult<String> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+
+        let params = parse_challenge(challenge);
+        let realm = params
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| anyhow!("The OCI registry did not provide an authentication realm"))?;
+        let query: Vec<(String, String)> = params
+            .into_iter()
+            .filter(|(key, _)| key == "service" || key == "scope")
+            .collect();
+        let mut request = self.client.get(realm).query(&query);
+        if let Some((username, password)) = &self.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response: TokenResponse = request
+            .send()?
+            .error_for_status()?
+            .json()
+            .context("Could not parse the OCI registry's token response")?;
+        response
+            .token
+            .or(response.access_token)
+            .ok_or_else(|| anyhow!("The OCI registry did not return a token").into())
+    }
+}
+
+fn sha256_digest(content: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(content))
+}
+
+/// Parses the parameters of a `WWW-Authenticate: Bearer k1="v1",k2="v2"` challenge
+fn parse_challenge(challenge: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = challenge.trim_start_matches("Bearer ").trim();
+    while let Some((key, value)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_string();
+        let (value, remainder) = if let Some(quoted) = value.strip_prefix('"') {
+            quoted.split_once('"').unwrap_or((quoted, ""))
+        } else {
+            value.split_once(',').unwrap_or((value, ""))
+        };
+        params.push((key, value.to_string()));
+        rest = remainder;
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_challenge, OciReference};
+
+    #[test]
+    fn it_parses_tagged_references() {
+        let reference: OciReference = "oci://ghcr.io/org/accounts-schema:1.2.3".parse().unwrap();
+        assert_eq!(reference.registry, "ghcr.io");
+        assert_eq!(reference.repository, "org/accounts-schema");
+        assert_eq!(reference.reference, "1.2.3");
+        assert_eq!(
+            reference.to_string(),
+            "oci://ghcr.io/org/accounts-schema:1.2.3"
+        );
+    }
+
+    #[test]
+    fn it_parses_digest_and_untagged_references() {
+        let reference: OciReference = "oci://localhost:5000/accounts@sha256:abc".parse().unwrap();
+        assert_eq!(reference.registry, "localhost:5000");
+        assert_eq!(reference.repository, "accounts");
+        assert_eq!(reference.reference, "sha256:abc");
+        assert_eq!(reference.base_url(), "http://localhost:5000/v2/accounts");
+
+        let reference: OciReference = "oci://ghcr.io/org/accounts".parse().unwrap();
+        assert_eq!(reference.reference, "latest");
+    }
+
+    #[test]
+    fn it_rejects_invalid_references() {
+        assert!("ghcr.io/org/accounts:1.2.3"
+            .parse::<OciReference>()
+            .is_err());
+        assert!("oci://ghcr.io".parse::<OciReference>().is_err());
+    }
+
+    #[test]
+    fn it_parses_bearer_challenges() {
+        let params = parse_challenge(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/accounts:pull,push""#,
+        );
+        assert_eq!(
+            params,
+            vec![
+                ("realm".to_string(), "https://ghcr.io/token".to_string()),
+                ("service".to_string(), "ghcr.io".to_string()),
+                (
+                    "scope".to_string(),
+                    "repository:org/accounts:pull,push".to_string()
+                ),
+            ]
+        );
+    }
+}
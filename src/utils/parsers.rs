@@ -109,9 +109,41 @@ pub fn parse_header(header: &str) -> std::result::Result<(String, String), io::E
     }
 }
 
+/// Parses a KEY=VALUE environment variable from a string and returns a tuple of key and value.
+/// The value may be empty, but the key may not.
+pub fn parse_env_var(env_var: &str) -> std::result::Result<(String, String), io::Error> {
+    match env_var.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => {
+            let msg = format!("Could not parse \"KEY=VALUE\" pair for provided environment variable: \"{}\". Environment variables must be provided in KEY=VALUE pairs, with quotes around the pair if there are any spaces in the value.", env_var);
+            Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+/// Parses the schema coordinate of a field, like `Product.price`, and returns a tuple of the
+/// type name and the field name. Both must be valid GraphQL names.
+pub fn parse_field_coordinate(
+    coordinate: &str,
+) -> std::result::Result<(String, String), io::Error> {
+    let is_name = |name: &str| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    match coordinate.trim().split_once('.') {
+        Some((type_name, field_name)) if is_name(type_name) && is_name(field_name) => {
+            Ok((type_name.to_string(), field_name.to_string()))
+        }
+        _ => {
+            let msg = format!("Could not parse a field coordinate from \"{}\". Fields must be provided as TypeName.fieldName, like \"Product.price\".", coordinate);
+            Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::FileDescriptorType;
+    use super::{parse_env_var, parse_field_coordinate, FileDescriptorType};
     use assert_fs::prelude::*;
     use camino::Utf8PathBuf;
     use std::convert::TryFrom;
@@ -192,4 +224,38 @@ mod tests {
         let schema_result = fd.read_file_descriptor("SDL", &mut input.as_bytes());
         assert!(schema_result.is_err())
     }
+
+    #[test]
+    fn it_parses_env_vars() {
+        assert_eq!(
+            parse_env_var("DATABASE_URL=postgres://localhost?sslmode=disable").unwrap(),
+            (
+                "DATABASE_URL".to_string(),
+                "postgres://localhost?sslmode=disable".to_string()
+            )
+        );
+        assert_eq!(
+            parse_env_var("EMPTY=").unwrap(),
+            ("EMPTY".to_string(), "".to_string())
+        );
+        assert!(parse_env_var("=value").is_err());
+        assert!(parse_env_var("NO_VALUE").is_err());
+    }
+
+    #[test]
+    fn it_parses_field_coordinates() {
+        assert_eq!(
+            parse_field_coordinate("Product.price").unwrap(),
+            ("Product".to_string(), "price".to_string())
+        );
+        assert_eq!(
+            parse_field_coordinate("_Service.sdl").unwrap(),
+            ("_Service".to_string(), "sdl".to_string())
+        );
+        assert!(parse_field_coordinate("Product").is_err());
+        assert!(parse_field_coordinate("Product.").is_err());
+        assert!(parse_field_coordinate("Product.price.amount").is_err());
+        assert!(parse_field_coordinate("Query.product(id:)").is_err());
+        assert!(parse_field_coordinate("1Product.price").is_err());
+    }
 }
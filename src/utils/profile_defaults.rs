@@ -0,0 +1,151 @@
+//! Fills in the graph ref and output format that a profile stores with `rover config set`.
+//! A value passed on the command line wins, then one set in an environment variable, then the
+//! profile's.
+
+use std::ffi::OsString;
+
+use camino::Utf8PathBuf;
+use clap::Command;
+use houston::{Config, Profile, ProfileSettings};
+
+use crate::utils::env::{RoverEnv, RoverEnvKey};
+
+/// The id of the positional `GRAPH_REF` that most commands take
+const GRAPH_REF_ARG: &str = "graph_ref";
+
+/// The id of the global `--format` option
+const FORMAT_ARG: &str = "format_kind";
+
+/// The profile that `args` run a command with. Profiles are read before the command line is
+/// parsed, so `--profile` is found by name.
+pub(crate) fn profile_name(args: &[OsString]) -> String {
+    let mut args = args.iter().filter_map(|arg| arg.to_str());
+    let mut name = "default";
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--profile" {
+            name = args.next().unwrap_or(name);
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            name = value;
+        }
+    }
+    name.to_string()
+}
+
+/// The settings of the profile that `args` run a command with, or the defaults if they can't be
+/// read.
+pub(crate) fn load(args: &[OsString]) -> ProfileSettings {
+    let override_home = RoverEnv::new()
+        .ok()
+        .and_then(|env| env.get(RoverEnvKey::ConfigHome))
+        .map(Utf8PathBuf::from);
+    Config::new(override_home.as_ref(), None)
+        .and_then(|config| Profile::get_settings(&profile_name(args), &config))
+        .unwrap_or_else(|e| {
+            tracing::debug!("could not read the profile's settings: {}", e);
+            ProfileSettings::default()
+        })
+}
+
+/// Makes the profile's graph ref the default `GRAPH_REF` of every command that requires one,
+/// and its output format the default of `--format`. Clap reads `APOLLO_GRAPH_REF` and
+/// `APOLLO_ROVER_FORMAT` before defaults, so those still take precedence.
+pub(crate) fn apply(mut command: Command, settings: &ProfileSettings) -> Command {
+    if let Some(format) = &settings.format {
+        if command
+            .get_arguments()
+            .any(|arg| arg.get_id() == FORMAT_ARG)
+        {
+            command = command.mut_arg(FORMAT_ARG, |arg| arg.default_value(format.clone()));
+        }
+    }
+    match &settings.graph_ref {
+        Some(graph_ref) => with_graph_ref(command, graph_ref),
+        None => command,
+    }
+}
+
+fn with_graph_ref(mut command: Command, graph_ref: &str) -> Command {
+    // only a command's one positional is given a default, so it can't shift any others
+    let positionals = command.get_positionals().collect::<Vec<_>>();
+    let takes_graph_ref = match positionals.as_slice() {
+        [positional] => positional.get_id() == GRAPH_REF_ARG && positional.is_required_set(),
+        _ => false,
+    };
+    if takes_graph_ref {
+        command = command.mut_arg(GRAPH_REF_ARG, |arg| {
+            arg.required(false).default_value(graph_ref.to_string())
+        });
+    }
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    for subcommand in subcommands {
+        command = command.mut_subcommand(subcommand, |subcommand| {
+            with_graph_ref(subcommand, graph_ref)
+        });
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use clap::CommandFactory;
+    use houston::ProfileSettings;
+    use rover_client::shared::GraphRef;
+
+    use super::{apply, profile_name};
+    use crate::cli::{Rover, RoverOutputFormatKind};
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn it_finds_the_profile_name() {
+        assert_eq!(
+            profile_name(&args(&["rover", "subgraph", "list"])),
+            "default"
+        );
+        assert_eq!(
+            profile_name(&args(&["rover", "subgraph", "list", "--profile", "work"])),
+            "work"
+        );
+        assert_eq!(
+            profile_name(&args(&["rover", "graph", "fetch", "--profile=work"])),
+            "work"
+        );
+    }
+
+    #[test]
+    fn it_applies_profile_defaults() {
+        let settings = ProfileSettings {
+            graph_ref: Some("my-graph@prod".to_string()),
+            format: Some("json".to_string()),
+            ..Default::default()
+        };
+        let command = apply(Rover::command(), &settings);
+        let graph_ref = |command_line: &[&str]| {
+            let matches = command
+                .clone()
+                .try_get_matches_from(args(command_line))
+                .unwrap();
+            let (_, subgraph) = matches.subcommand().unwrap();
+            let (_, list) = subgraph.subcommand().unwrap();
+            assert_eq!(
+                list.get_one::<RoverOutputFormatKind>("format_kind"),
+                Some(&RoverOutputFormatKind::Json)
+            );
+            list.get_one::<GraphRef>("graph_ref").unwrap().to_string()
+        };
+        assert_eq!(graph_ref(&["rover", "subgraph", "list"]), "my-graph@prod");
+        assert_eq!(
+            graph_ref(&["rover", "subgraph", "list", "other-graph@dev"]),
+            "other-graph@dev"
+        );
+    }
+}
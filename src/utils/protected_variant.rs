@@ -0,0 +1,283 @@
+//! Guards publishes to variants marked as protected with `APOLLO_PROTECTED_VARIANTS` or a
+//! profile's `protected-variants` default, so a schema meant for a development variant isn't
+//! pushed to production by accident.
+
+use std::env;
+use std::fmt::{self, Display};
+use std::io;
+
+use anyhow::anyhow;
+use rover_client::shared::GraphRef;
+use rover_std::Style;
+
+use crate::command::schema::diff::SchemaDiff;
+use crate::utils::client::StudioClientConfig;
+use crate::utils::env::RoverEnvKey;
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// How many changes to list before summarizing the rest.
+const MAX_LISTED_CHANGES: usize = 10;
+
+/// A pattern that marks graph refs as protected, and where it was configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProtectedPattern {
+    pub(crate) pattern: String,
+    pub(crate) configured_in: String,
+}
+
+impl Display for ProtectedPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` in {}", self.pattern, self.configured_in)
+    }
+}
+
+/// The graph refs that require confirmation before publishing.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct ProtectedVariants {
+    patterns: Vec<ProtectedPattern>,
+}
+
+impl ProtectedVariants {
+    /// Reads the comma-separated patterns in `APOLLO_PROTECTED_VARIANTS` and in the
+    /// `protected-variants` default of the profile.
+    pub(crate) fn new(client_config: &StudioClientConfig, profile_name: &str) -> Self {
+        let mut protected = Self::default();
+        protected.add(
+            &env::var(RoverEnvKey::ProtectedVariants.to_string()).unwrap_or_default(),
+            &format!("`{}`", RoverEnvKey::ProtectedVariants),
+        );
+        if let Some(patterns) = client_config
+            .get_profile_settings(profile_name)
+            .protected_variants
+        {
+            protected.add(
+                &patterns,
+                &format!(
+                    "the `protected-variants` default of the '{}' profile",
+                    profile_name
+                ),
+            );
+        }
+        protected
+    }
+
+    fn add(&mut self, patterns: &str, configured_in: &str) {
+        self.patterns
+            .extend(
+                Self::parse_patterns(patterns)
+                    .into_iter()
+                    .map(|pattern| ProtectedPattern {
+                        pattern,
+                        configured_in: configured_in.to_string(),
+                    }),
+            );
+    }
+
+    /// Splits a comma-separated list of patterns, ignoring empty ones.
+    pub(crate) fn parse_patterns(patterns: &str) -> Vec<String> {
+        patterns
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns the first pattern that matches `graph_ref`. Patterns with an `@` match the whole
+    /// graph ref, like `my-graph@prod` or `*@prod`, and patterns without one match only the
+    /// variant, like `prod*`.
+    pub(crate) fn matching_pattern(&self, graph_ref: &GraphRef) -> Option<&ProtectedPattern> {
+        self.patterns.iter().find(|protected| {
+            if protected.pattern.contains('@') {
+                glob_match(&protected.pattern, &graph_ref.to_string())
+            } else {
+                glob_match(&protected.pattern, &graph_ref.variant)
+            }
+        })
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => match text.strip_prefix(prefix) {
+            Some(text) => (0..=text.len())
+                .filter(|i| text.is_char_boundary(*i))
+                .any(|i| glob_match(rest, &text[i..])),
+            None => false,
+        },
+    }
+}
+
+/// Shows what a publish to a protected variant changes and asks to continue. Outside of a
+/// terminal there's no one to ask, so the publish is refused unless `--yes` was passed.
+pub(crate) fn confirm_protected_publish(
+    graph_ref: &GraphRef,
+    protected_by: &ProtectedPattern,
+    diff: &SchemaDiff,
+    // For testing purposes, we pass in stub `Write`er and `Read`ers to
+    // simulate input and verify output.
+    reader: &mut impl io::Read,
+    writer: &mut impl io::Write,
+    // Simulate a CI environment (non-TTY) for testing
+    is_atty: bool,
+) -> RoverResult<()> {
+    let reason = format!(
+        "{} is a protected variant (it matches {}).",
+        Style::Link.paint(graph_ref.to_string()),
+        protected_by
+    );
+    if !is_atty {
+        let mut err = RoverError::new(anyhow!(
+            "{} Refusing to publish without confirmation.",
+            reason
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+            "If you meant to publish to {}, pass `--yes` to skip the confirmation.",
+            graph_ref
+        )));
+        return Err(err);
+    }
+
+    writeln!(writer, "{} {}", Style::WarningPrefix.paint("WARN:"), reason)?;
+    writeln!(writer, "Compared to the schema published there now:")?;
+    write_changes(writer, diff)?;
+    write!(writer, "Would you still like to publish? [y/N] ")?;
+    let mut response = [0];
+    reader.read_exact(&mut response)?;
+    if std::str::from_utf8(&response)
+        .unwrap_or_default()
+        .to_lowercase()
+        == *"y"
+    {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "You cancelled a publish to the protected variant {}.",
+            graph_ref
+        )
+        .into())
+    }
+}
+
+/// Lists the most severe changes in `diff`, and how many more there are.
+fn write_changes(writer: &mut impl io::Write, diff: &SchemaDiff) -> io::Result<()> {
+    writeln!(writer, "  {}", diff.summary())?;
+    for change in diff.changes.iter().take(MAX_LISTED_CHANGES) {
+        writeln!(
+            writer,
+            "  {} {}: {}",
+            change.severity, change.code, change.description
+        )?;
+    }
+    if diff.changes.len() > MAX_LISTED_CHANGES {
+        writeln!(
+            writer,
+            "  ...and {} more",
+            diff.changes.len() - MAX_LISTED_CHANGES
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rover_client::shared::GraphRef;
+
+    use super::{confirm_protected_publish, ProtectedPattern, ProtectedVariants};
+    use crate::command::schema::diff::{SchemaDiff, SeverityOverrides};
+
+    #[test]
+    fn it_matches_graph_refs_and_variants() {
+        let mut protected = ProtectedVariants::default();
+        protected.add(" *@prod, my-graph@staging ,", "`APOLLO_PROTECTED_VARIANTS`");
+        protected.add(
+            "release-*",
+            "the `protected-variants` default of the 'default' profile",
+        );
+        let matching = |graph_ref: &str| {
+            protected
+                .matching_pattern(&GraphRef::from_str(graph_ref).unwrap())
+                .map(|protected_by| protected_by.to_string())
+        };
+        assert_eq!(
+            matching("other-graph@prod"),
+            Some("`*@prod` in `APOLLO_PROTECTED_VARIANTS`".to_string())
+        );
+        assert_eq!(
+            matching("my-graph@staging"),
+            Some("`my-graph@staging` in `APOLLO_PROTECTED_VARIANTS`".to_string())
+        );
+        assert_eq!(matching("other-graph@staging"), None);
+        assert_eq!(
+            matching("my-graph@release-2"),
+            Some(
+                "`release-*` in the `protected-variants` default of the 'default' profile"
+                    .to_string()
+            )
+        );
+        assert_eq!(matching("my-graph@production"), None);
+        assert_eq!(
+            ProtectedVariants::default()
+                .matching_pattern(&GraphRef::from_str("my-graph@prod").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn it_requires_confirmation_for_protected_variants() {
+        let graph_ref = GraphRef::from_str("my-graph@prod").unwrap();
+        let protected_by = ProtectedPattern {
+            pattern: "*@prod".to_string(),
+            configured_in: "`APOLLO_PROTECTED_VARIANTS`".to_string(),
+        };
+        let diff = SchemaDiff::new(
+            "type Query { hello: String }",
+            "type Query { hello: Int }",
+            &SeverityOverrides::default(),
+        )
+        .unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        confirm_protected_publish(
+            &graph_ref,
+            &protected_by,
+            &diff,
+            &mut "y".as_bytes(),
+            &mut output,
+            true,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("is a protected variant"));
+        assert!(output.contains("1 breaking, 0 notable, and 0 safe change."));
+        assert!(output.contains("breaking FIELD_CHANGED_TYPE"));
+
+        let mut output: Vec<u8> = Vec::new();
+        assert!(confirm_protected_publish(
+            &graph_ref,
+            &protected_by,
+            &diff,
+            &mut "n".as_bytes(),
+            &mut output,
+            true
+        )
+        .is_err());
+
+        let mut output: Vec<u8> = Vec::new();
+        let err = confirm_protected_publish(
+            &graph_ref,
+            &protected_by,
+            &diff,
+            &mut "".as_bytes(),
+            &mut output,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.suggestions()[0].to_string().contains("--yes"));
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::{self, Display};
+
+use anyhow::anyhow;
+use rover_client::blocking::StudioClient;
+use rover_client::operations::graph::permissions::{self, GraphPermissionsInput};
+use rover_client::shared::GraphRef;
+
+use crate::{RoverError, RoverErrorSuggestion, RoverResult};
+
+/// A permission that a mutating command needs from the API key it runs with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum RequiredScope {
+    CheckSchemas,
+    PushSchemas,
+    ManageVariants,
+    RegisterOperations,
+    UpdateVariantReadme,
+    CreateGraphs,
+}
+
+impl Display for RequiredScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            RequiredScope::CheckSchemas => "run schema checks",
+            RequiredScope::PushSchemas => "publish schemas",
+            RequiredScope::ManageVariants => "manage variants",
+            RequiredScope::RegisterOperations => "publish persisted queries",
+            RequiredScope::UpdateVariantReadme => "update READMEs",
+            RequiredScope::CreateGraphs => "create graphs",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+/// The kind of API key, as encoded in its prefix.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum KeyType<'a> {
+    /// A personal API key, e.g. `user:gh.octocat:...`
+    User,
+    /// A graph API key, e.g. `service:my-graph:...`, which only has access to one graph
+    Graph {
+        graph_id: &'a str,
+    },
+    Unknown,
+}
+
+impl<'a> KeyType<'a> {
+    pub(crate) fn from_api_key(api_key: &'a str) -> Self {
+        let mut parts = api_key.splitn(3, ':');
+        match (parts.next(), parts.next()) {
+            (Some("user"), Some(_)) => KeyType::User,
+            (Some("service"), Some(graph_id)) => KeyType::Graph { graph_id },
+            _ => KeyType::Unknown,
+        }
+    }
+}
+
+impl Display for KeyType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyType::User => write!(f, "personal"),
+            KeyType::Graph { .. } => write!(f, "graph"),
+            KeyType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Checks that the client's API key can perform `scope` on `graph_ref` before a
+/// command sends anything to the registry, so a key without the right access
+/// fails with a message naming what's missing instead of a generic permission error.
+///
+/// Only a definite "no" from the registry is an error. If the permissions can't be
+/// fetched, the command goes ahead and the registry has the final say.
+pub(crate) fn check_key_scope(
+    client: &StudioClient,
+    graph_ref: Option<&GraphRef>,
+    scope: RequiredScope,
+) -> RoverResult<()> {
+    check_key_type(&client.credential.api_key, graph_ref, scope)?;
+    let graph_ref = match graph_ref {
+        // a graph that's about to be created has no permissions to look up
+        Some(graph_ref) if scope != RequiredScope::CreateGraphs => graph_ref,
+        _ => return Ok(()),
+    };
+    let permissions = match permissions::run(
+        GraphPermissionsInput {
+            graph_ref: graph_ref.clone(),
+        },
+        client,
+    ) {
+        Ok(permissions) => permissions,
+        Err(e) => {
+            tracing::debug!("skipping the API key scope check: {}", e);
+            return Ok(());
+        }
+    };
+    let (allowed, permission) = match scope {
+        RequiredScope::CheckSchemas => (permissions.can_check_schemas, "canCheckSchemas"),
+        RequiredScope::PushSchemas => (permissions.can_push_schemas, "canPushSchemas"),
+        RequiredScope::ManageVariants => (permissions.can_manage_variants, "canManageVariants"),
+        RequiredScope::RegisterOperations => {
+            (permissions.can_register_operations, "canRegisterOperations")
+        }
+        RequiredScope::UpdateVariantReadme => (
+            permissions.can_update_variant_readme,
+            "canUpdateVariantReadme",
+        ),
+        RequiredScope::CreateGraphs => return Ok(()),
+    };
+    if allowed == Some(false) {
+        let mut err = RoverError::new(anyhow!(
+            "The API key you're using doesn't have permission to {} on {}. This requires the `{}` permission.",
+            scope,
+            graph_ref,
+            permission
+        ));
+        err.set_suggestion(RoverErrorSuggestion::Adhoc(
+            "Ask a graph admin to grant you a role that includes this permission, or use an API key that has it.".to_string(),
+        ));
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Checks what can be learned from the API key itself, without a request to the registry.
+fn check_key_type(
+    api_key: &str,
+    graph_ref: Option<&GraphRef>,
+    scope: RequiredScope,
+) -> RoverResult<()> {
+    match (KeyType::from_api_key(api_key), graph_ref) {
+        (KeyType::Graph { graph_id }, _) if scope == RequiredScope::CreateGraphs => {
+            let mut err = RoverError::new(anyhow!(
+                "Only a personal API key can {}, but you're using a graph API key for {}.",
+                scope,
+                graph_id
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(
+                "Create a personal API key in Apollo Studio and run `rover config auth` to use it."
+                    .to_string(),
+            ));
+            Err(err)
+        }
+        (KeyType::Graph { graph_id }, Some(graph_ref)) if graph_id != graph_ref.name => {
+            let mut err = RoverError::new(anyhow!(
+                "The API key you're using is a graph API key for {}, so it can't {} on {}.",
+                graph_id,
+                scope,
+                graph_ref
+            ));
+            err.set_suggestion(RoverErrorSuggestion::Adhoc(format!(
+                "Use a graph API key for {} or a personal API key instead.",
+                graph_ref.name
+            )));
+            Err(err)
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rover_client::shared::GraphRef;
+
+    use super::{check_key_type, KeyType, RequiredScope};
+
+    fn graph_ref(name: &str) -> GraphRef {
+        GraphRef {
+            name: name.to_string(),
+            variant: "current".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_reads_the_key_type_from_its_prefix() {
+        assert_eq!(KeyType::from_api_key("user:gh.octocat:abc"), KeyType::User);
+        assert_eq!(
+            KeyType::from_api_key("service:my-graph:abc"),
+            KeyType::Graph {
+                graph_id: "my-graph"
+            }
+        );
+        assert_eq!(KeyType::from_api_key("abc"), KeyType::Unknown);
+    }
+
+    #[test]
+    fn it_rejects_graph_keys_for_other_graphs() {
+        let key = "service:my-graph:abc";
+        assert!(check_key_type(
+            key,
+            Some(&graph_ref("my-graph")),
+            RequiredScope::PushSchemas
+        )
+        .is_ok());
+        let err = check_key_type(
+            key,
+            Some(&graph_ref("other-graph")),
+            RequiredScope::PushSchemas,
+        )
+        .unwrap_err();
+        assert!(err.message().contains("my-graph"));
+        assert!(err.message().contains("other-graph@current"));
+    }
+
+    #[test]
+    fn it_requires_a_personal_key_to_create_graphs() {
+        let new_graph = graph_ref("new-graph");
+        assert!(check_key_type(
+            "user:gh.octocat:abc",
+            Some(&new_graph),
+            RequiredScope::CreateGraphs
+        )
+        .is_ok());
+        assert!(check_key_type(
+            "service:new-graph:abc",
+            Some(&new_graph),
+            RequiredScope::CreateGraphs
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,21 @@
+//! Helpers for reading GraphQL documents parsed with `apollo-parser`.
+
+use apollo_parser::ast;
+
+/// The text of a name, or an empty string if it's missing from a document that didn't parse.
+pub(crate) fn name(name: Option<ast::Name>) -> String {
+    name.map(|name| name.text().to_string()).unwrap_or_default()
+}
+
+/// The named type at the bottom of a list or non-null type.
+pub(crate) fn named_type(ty: ast::Type) -> Option<String> {
+    match ty {
+        ast::Type::NamedType(t) => Some(name(t.name())),
+        ast::Type::ListType(t) => t.ty().and_then(named_type),
+        ast::Type::NonNullType(t) => match (t.named_type(), t.list_type()) {
+            (Some(t), _) => Some(name(t.name())),
+            (None, Some(t)) => t.ty().and_then(named_type),
+            (None, None) => None,
+        },
+    }
+}
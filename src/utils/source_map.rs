@@ -0,0 +1,277 @@
+//! Points composition errors and hints at the subgraph schema files they came from.
+//!
+//! Composition reports the subgraph, line, and column of the definitions related to each error
+//! and hint in its `nodes`, but it only ever sees SDL, so it can't say which file that SDL was
+//! read from. A [`SubgraphSourceMap`] fills in each node's `source` with the subgraph's file,
+//! and adds the locations to the message so they show up in human-readable output too.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use apollo_federation_types::build::{BuildError, BuildErrorNode, BuildErrors, BuildHint};
+use apollo_federation_types::config::{SchemaSource, SupergraphConfig};
+use camino::{Utf8Path, Utf8PathBuf};
+use rover_client::RoverClientError;
+use serde_json::json;
+
+/// What comes before each location listed after a message.
+const LOCATION_PREFIX: &str = "\n  --> ";
+
+/// A message without the locations a [`SubgraphSourceMap`] listed after it.
+pub(crate) fn without_locations(message: &str) -> &str {
+    message.split(LOCATION_PREFIX).next().unwrap_or(message)
+}
+
+/// The files that each subgraph's schema was read from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SubgraphSourceMap {
+    files: BTreeMap<String, Utf8PathBuf>,
+}
+
+impl SubgraphSourceMap {
+    /// Collects the schema files in a supergraph config, resolving relative paths against `config_dir`.
+    pub(crate) fn from_supergraph_config(
+        supergraph_config: &SupergraphConfig,
+        config_dir: Option<&Utf8Path>,
+    ) -> Self {
+        let mut source_map = Self::default();
+        for (subgraph_name, subgraph_config) in supergraph_config.clone().into_iter() {
+            if let SchemaSource::File { file } = subgraph_config.schema {
+                let file = match config_dir {
+                    Some(config_dir) => config_dir.join(file.strip_prefix(".").unwrap_or(&file)),
+                    None => file,
+                };
+                source_map.insert(subgraph_name, file);
+            }
+        }
+        source_map
+    }
+
+    /// Maps a single subgraph to the file its schema was read from, if it was read from one.
+    pub(crate) fn for_subgraph(subgraph_name: &str, file: Option<Utf8PathBuf>) -> Self {
+        let mut source_map = Self::default();
+        if let Some(file) = file {
+            source_map.insert(subgraph_name.to_string(), file);
+        }
+        source_map
+    }
+
+    pub(crate) fn insert(&mut self, subgraph_name: String, file: Utf8PathBuf) {
+        self.files.insert(subgraph_name, file);
+    }
+
+    /// Each subgraph's name and the file its schema is read from
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &Utf8PathBuf)> {
+        self.files.iter()
+    }
+
+    /// Adds source locations to composition errors, unless they're errors in the supergraph config itself.
+    pub(crate) fn annotate_errors(&self, build_errors: BuildErrors) -> BuildErrors {
+        if build_errors.is_config {
+            return build_errors;
+        }
+        build_errors
+            .iter()
+            .map(|error| {
+                let (locations, nodes) = self.annotate_nodes(error.get_nodes());
+                BuildError::composition_error(
+                    error.get_code(),
+                    error.get_message().map(|message| message + &locations),
+                    nodes,
+                    error.get_omitted_nodes_count(),
+                )
+            })
+            .collect()
+    }
+
+    /// Adds source locations to composition hints.
+    pub(crate) fn annotate_hints(&self, hints: Vec<BuildHint>) -> Vec<BuildHint> {
+        hints
+            .into_iter()
+            .map(|mut hint| {
+                let (locations, nodes) = self.annotate_nodes(hint.nodes.take());
+                hint.message.push_str(&locations);
+                hint.nodes = nodes;
+                hint
+            })
+            .collect()
+    }
+
+    /// Adds source locations to the composition errors in `error`, if it has any.
+    pub(crate) fn annotate_client_error(&self, error: RoverClientError) -> RoverClientError {
+        match error {
+            RoverClientError::BuildErrors {
+                source,
+                num_subgraphs,
+            } => RoverClientError::BuildErrors {
+                source: self.annotate_errors(source),
+                num_subgraphs,
+            },
+            RoverClientError::SubgraphBuildErrors {
+                subgraph,
+                graph_ref,
+                source,
+            } => RoverClientError::SubgraphBuildErrors {
+                subgraph,
+                graph_ref,
+                source: self.annotate_errors(source),
+            },
+            error => error,
+        }
+    }
+
+    /// Fills in the file for each of an error's or hint's `nodes`, and lists their locations
+    /// to add after its message.
+    fn annotate_nodes(
+        &self,
+        nodes: Option<Vec<BuildErrorNode>>,
+    ) -> (String, Option<Vec<BuildErrorNode>>) {
+        let mut locations = String::new();
+        let nodes = nodes.map(|nodes| {
+            nodes
+                .into_iter()
+                .map(|node| {
+                    let subgraph = node.get_subgraph();
+                    let file = subgraph.as_ref().and_then(|name| self.files.get(name));
+                    let start = node.get_start();
+                    let location = SourceLocation {
+                        subgraph,
+                        file: file.map(|file| file.to_string()),
+                        line: start.as_ref().and_then(|start| start.get_line()),
+                        column: start.as_ref().and_then(|start| start.get_column()),
+                    };
+                    if location.line.is_some() || location.subgraph.is_some() {
+                        locations.push_str(&format!("{}{}", LOCATION_PREFIX, location));
+                    }
+                    match file {
+                        Some(file) => with_source(node, file),
+                        None => node,
+                    }
+                })
+                .collect()
+        });
+        (locations, nodes)
+    }
+}
+
+/// `node` with its `source` set to `file`. `BuildErrorNode` has no constructor, so the node is
+/// deserialized from its parts.
+fn with_source(node: BuildErrorNode, file: &Utf8Path) -> BuildErrorNode {
+    serde_json::from_value(json!({
+        "subgraph": node.get_subgraph(),
+        "source": file,
+        "start": node.get_start(),
+        "end": node.get_end(),
+    }))
+    .unwrap_or(node)
+}
+
+/// Where a definition related to a composition error or hint lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceLocation {
+    subgraph: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => write!(f, "{}:{}:{}", file, line, column)?,
+            (Some(file), Some(line), None) => write!(f, "{}:{}", file, line)?,
+            (Some(file), None, _) => write!(f, "{}", file)?,
+            (None, Some(line), Some(column)) => write!(f, "line {}, column {}", line, column)?,
+            (None, Some(line), None) => write!(f, "line {}", line)?,
+            (None, None, _) => {}
+        }
+        if let Some(subgraph) = &self.subgraph {
+            if self.file.is_some() || self.line.is_some() {
+                write!(f, " ")?;
+            }
+            write!(f, "(subgraph '{}')", subgraph)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_federation_types::build::{BuildError, BuildErrors, BuildHint};
+    use camino::Utf8PathBuf;
+    use serde_json::json;
+
+    use super::SubgraphSourceMap;
+
+    fn source_map() -> SubgraphSourceMap {
+        let supergraph_config = serde_yaml::from_str(
+            r#"
+subgraphs:
+  products:
+    routing_url: http://localhost:4001
+    schema:
+      file: ./products.graphql
+  reviews:
+    routing_url: http://localhost:4002
+    schema:
+      subgraph_url: http://localhost:4002
+"#,
+        )
+        .unwrap();
+        SubgraphSourceMap::from_supergraph_config(
+            &supergraph_config,
+            Some(&Utf8PathBuf::from("graphs")),
+        )
+    }
+
+    fn node(subgraph: &str, line: u64, column: u64) -> serde_json::Value {
+        json!({
+            "subgraph": subgraph,
+            "source": null,
+            "start": { "start": 0, "end": 0, "line": line, "column": column },
+            "end": null
+        })
+    }
+
+    #[test]
+    fn it_adds_files_and_locations_to_errors() {
+        let errors: BuildErrors = vec![serde_json::from_value::<BuildError>(json!({
+            "message": "[products] Field \"Product.id\" is defined differently",
+            "code": "FIELD_TYPE_MISMATCH",
+            "type": "composition",
+            "nodes": [node("products", 3, 5), node("reviews", 12, 3)],
+            "omittedNodesCount": 0
+        }))
+        .unwrap()]
+        .into();
+
+        let annotated = serde_json::to_value(source_map().annotate_errors(errors)).unwrap();
+        let error = &annotated["build_errors"][0];
+        assert_eq!(
+            error["message"],
+            json!("[products] Field \"Product.id\" is defined differently\n  --> graphs/products.graphql:3:5 (subgraph 'products')\n  --> line 12, column 3 (subgraph 'reviews')")
+        );
+        assert_eq!(
+            error["nodes"][0]["source"],
+            json!("graphs/products.graphql")
+        );
+        assert_eq!(error["nodes"][1]["source"], json!(null));
+        assert_eq!(error["code"], json!("FIELD_TYPE_MISMATCH"));
+    }
+
+    #[test]
+    fn it_adds_locations_to_hints() {
+        let hints: Vec<BuildHint> = serde_json::from_value(json!([
+            { "message": "a hint", "code": "INCONSISTENT_FIELD", "nodes": [node("products", 7, 1)] },
+            { "message": "a hint without nodes", "code": "INCONSISTENT_FIELD" }
+        ]))
+        .unwrap();
+
+        let hints = source_map().annotate_hints(hints);
+        assert_eq!(
+            hints[0].message,
+            "a hint\n  --> graphs/products.graphql:7:1 (subgraph 'products')"
+        );
+        assert_eq!(hints[1].message, "a hint without nodes");
+    }
+}
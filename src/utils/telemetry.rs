@@ -143,9 +143,11 @@ mod tests {
         let actual_serialized_command = rover
             .serialize_command()
             .expect("could not serialize command");
+        let mut expected_arguments = HashMap::new();
+        expected_arguments.insert("verbose".to_string(), json!(false));
         let expected_serialized_command = Command {
             name: "config list".to_string(),
-            arguments: HashMap::new(),
+            arguments: expected_arguments,
         };
         assert_eq!(actual_serialized_command, expected_serialized_command);
     }